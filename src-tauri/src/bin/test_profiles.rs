@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 use quizdd::database::DatabaseService;
 use quizdd::services::{ProfileManager, SecurityService};
 use quizdd::models::CreateProfileRequest;
@@ -34,7 +35,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("✅ Database service initialized");
 
     // Create profile manager
-    let security_service = SecurityService::new()?;
+    let security_service = Arc::new(SecurityService::new()?);
     let profile_manager = ProfileManager::new(db_service.manager(), security_service);
     println!("✅ Profile manager created");
 
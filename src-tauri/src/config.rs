@@ -0,0 +1,300 @@
+use crate::errors::{AppError, AppResult};
+use crate::services::UpdateConfig;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+/// Name of the configuration file in the app data directory
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Environment variable pointing at an alternative configuration file
+pub const CONFIG_PATH_ENV: &str = "QUIZDD_CONFIG";
+
+/// Marker file next to the executable that switches on portable mode
+pub const PORTABLE_MARKER_FILE: &str = "quizdd.portable";
+
+/// Command-line flag that switches on portable mode
+pub const PORTABLE_FLAG: &str = "--portable";
+
+/// Directory next to the executable holding all data in portable mode
+pub const PORTABLE_DATA_DIR: &str = "QuizDD Data";
+
+const ENV_PREFIX: &str = "QUIZDD_";
+const FEATURE_ENV_PREFIX: &str = "QUIZDD_FEATURE_";
+
+/// Application configuration loaded from `config.toml` at startup.
+/// Every value can be overridden with a `QUIZDD_*` environment variable.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Store the database, content and backups here instead of the OS app data directory
+    pub data_directory: Option<PathBuf>,
+    /// One of `error`, `warn`, `info`, `debug`, `trace` or `off`
+    pub log_level: String,
+    pub network: NetworkConfig,
+    pub updates: UpdateSettings,
+    /// Default values for feature flags, keyed by flag name
+    pub features: HashMap<String, bool>,
+}
+
+/// Network settings shared by all outgoing HTTP clients
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub proxy: Option<String>,
+}
+
+/// Update repository settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UpdateSettings {
+    pub repository_urls: Vec<String>,
+    pub auto_check: bool,
+    pub check_interval_hours: u32,
+    pub backup_retention_days: u32,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            data_directory: None,
+            log_level: "info".to_string(),
+            network: NetworkConfig::default(),
+            updates: UpdateSettings::default(),
+            features: HashMap::new(),
+        }
+    }
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            repository_urls: vec![
+                "https://updates.educationalquizapp.com".to_string(),
+                "https://content.educationalquizapp.com".to_string(),
+            ],
+            auto_check: false,
+            check_interval_hours: 24,
+            backup_retention_days: 7,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Load configuration from `path` (defaults if the file does not exist),
+    /// then apply overrides from the process environment
+    pub fn load(path: &Path) -> AppResult<Self> {
+        let mut config = Self::from_file(path)?;
+        config.apply_env_overrides(std::env::vars())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Resolve the configuration file path, honouring `QUIZDD_CONFIG`
+    pub fn resolve_path(default_directory: &Path) -> PathBuf {
+        std::env::var_os(CONFIG_PATH_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| default_directory.join(CONFIG_FILE_NAME))
+    }
+
+    /// Read configuration from a TOML file without environment overrides
+    pub fn from_file(path: &Path) -> AppResult<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        Self::from_toml(&contents)
+            .map_err(|e| AppError::Configuration(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Parse configuration from TOML text
+    pub fn from_toml(contents: &str) -> AppResult<Self> {
+        toml::from_str(contents)
+            .map_err(|e| AppError::Configuration(format!("Invalid configuration: {}", e)))
+    }
+
+    /// Apply `QUIZDD_*` overrides from the given environment variables
+    pub fn apply_env_overrides<I>(&mut self, vars: I) -> AppResult<()>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (key, value) in vars {
+            if !key.starts_with(ENV_PREFIX) {
+                continue;
+            }
+
+            if let Some(flag) = key.strip_prefix(FEATURE_ENV_PREFIX) {
+                self.features.insert(flag.to_lowercase(), parse_bool(&key, &value)?);
+                continue;
+            }
+
+            match key.as_str() {
+                "QUIZDD_DATA_DIR" => self.data_directory = Some(PathBuf::from(value)),
+                "QUIZDD_LOG_LEVEL" => self.log_level = value,
+                "QUIZDD_PROXY" => self.network.proxy = Some(value).filter(|v| !v.is_empty()),
+                "QUIZDD_UPDATE_URLS" => {
+                    self.updates.repository_urls = value.split(',')
+                        .map(|url| url.trim().to_string())
+                        .filter(|url| !url.is_empty())
+                        .collect();
+                },
+                "QUIZDD_UPDATE_AUTO_CHECK" => self.updates.auto_check = parse_bool(&key, &value)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The configured log level as a `log` filter
+    pub fn log_level_filter(&self) -> AppResult<log::LevelFilter> {
+        self.log_level.parse()
+            .map_err(|_| AppError::Configuration(format!("Invalid log level: {}", self.log_level)))
+    }
+
+    /// Update service configuration derived from this config
+    pub fn update_config(&self) -> UpdateConfig {
+        UpdateConfig {
+            repository_urls: self.updates.repository_urls.clone(),
+            auto_check: self.updates.auto_check,
+            check_interval_hours: self.updates.check_interval_hours,
+            backup_retention_days: self.updates.backup_retention_days,
+            proxy: self.network.proxy.clone(),
+        }
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        self.log_level_filter()?;
+
+        if let Some(ref proxy) = self.network.proxy {
+            url::Url::parse(proxy)
+                .map_err(|e| AppError::Configuration(format!("Invalid proxy URL: {}", e)))?;
+        }
+
+        for repository_url in &self.updates.repository_urls {
+            url::Url::parse(repository_url)
+                .map_err(|e| AppError::Configuration(format!("Invalid repository URL {}: {}", repository_url, e)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Data directory for a portable install, or `None` for a normal install.
+/// Portable mode is on when `--portable` is passed or a marker file sits next to the executable,
+/// so the database, content and settings can live on a USB stick instead of a locked-down AppData.
+pub fn portable_data_dir<I>(executable: &Path, args: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = String>,
+{
+    let executable_dir = executable.parent()?;
+    let flag_passed = args.into_iter().any(|arg| arg == PORTABLE_FLAG);
+
+    if flag_passed || executable_dir.join(PORTABLE_MARKER_FILE).is_file() {
+        Some(executable_dir.join(PORTABLE_DATA_DIR))
+    } else {
+        None
+    }
+}
+
+/// Apply the configured proxy (if any) to an HTTP client builder
+pub fn apply_proxy(builder: reqwest::ClientBuilder, proxy: Option<&str>) -> AppResult<reqwest::ClientBuilder> {
+    match proxy {
+        Some(proxy_url) => {
+            let proxy = reqwest::Proxy::all(proxy_url)
+                .map_err(|e| AppError::Configuration(format!("Invalid proxy URL: {}", e)))?;
+            Ok(builder.proxy(proxy))
+        },
+        None => Ok(builder),
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> AppResult<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(AppError::Configuration(format!("{} must be true or false, got '{}'", key, value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_missing_file_uses_defaults() {
+        let temp_dir = tempdir().unwrap();
+        let config = AppConfig::from_file(&temp_dir.path().join(CONFIG_FILE_NAME)).unwrap();
+
+        assert_eq!(config, AppConfig::default());
+        assert_eq!(config.update_config().repository_urls.len(), 2);
+    }
+
+    #[test]
+    fn test_toml_file_and_env_overrides() {
+        let mut config = AppConfig::from_toml(r#"
+            log_level = "debug"
+
+            [network]
+            proxy = "http://proxy.school.local:3128"
+
+            [updates]
+            repository_urls = ["https://mirror.example.org/quizdd"]
+
+            [features]
+            sync = true
+        "#).unwrap();
+
+        assert_eq!(config.log_level_filter().unwrap(), log::LevelFilter::Debug);
+        assert_eq!(config.updates.check_interval_hours, 24);
+        assert_eq!(config.features.get("sync"), Some(&true));
+
+        config.apply_env_overrides(vec![
+            ("QUIZDD_LOG_LEVEL".to_string(), "warn".to_string()),
+            ("QUIZDD_DATA_DIR".to_string(), "/srv/quizdd".to_string()),
+            ("QUIZDD_FEATURE_ADAPTIVE_ENGINE".to_string(), "on".to_string()),
+            ("QUIZDD_UPDATE_URLS".to_string(), "https://a.example.org, https://b.example.org".to_string()),
+            ("PATH".to_string(), "/usr/bin".to_string()),
+        ]).unwrap();
+
+        assert_eq!(config.log_level, "warn");
+        assert_eq!(config.data_directory, Some(PathBuf::from("/srv/quizdd")));
+        assert_eq!(config.features.get("adaptive_engine"), Some(&true));
+        assert_eq!(config.updates.repository_urls, vec!["https://a.example.org", "https://b.example.org"]);
+        assert_eq!(config.update_config().proxy.as_deref(), Some("http://proxy.school.local:3128"));
+    }
+
+    #[test]
+    fn test_portable_mode_detection() {
+        let temp_dir = tempdir().unwrap();
+        let executable = temp_dir.path().join("quizdd.exe");
+
+        assert_eq!(portable_data_dir(&executable, Vec::new()), None);
+        assert_eq!(
+            portable_data_dir(&executable, vec![PORTABLE_FLAG.to_string()]),
+            Some(temp_dir.path().join(PORTABLE_DATA_DIR))
+        );
+
+        fs::write(temp_dir.path().join(PORTABLE_MARKER_FILE), "").unwrap();
+        assert_eq!(
+            portable_data_dir(&executable, Vec::new()),
+            Some(temp_dir.path().join(PORTABLE_DATA_DIR))
+        );
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        assert!(matches!(AppConfig::from_toml("log_level = 5"), Err(AppError::Configuration(_))));
+
+        let mut config = AppConfig::default();
+        assert!(config.apply_env_overrides(vec![
+            ("QUIZDD_FEATURE_SYNC".to_string(), "maybe".to_string()),
+        ]).is_err());
+
+        config.log_level = "loud".to_string();
+        assert!(config.validate().is_err());
+    }
+}
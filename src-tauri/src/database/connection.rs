@@ -1,6 +1,7 @@
 use rusqlite::{Connection, Result as SqlResult, OpenFlags};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -204,7 +205,7 @@ impl ConnectionPool {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct PoolStats {
     pub active_connections: usize,
     pub max_connections: usize,
@@ -214,12 +215,13 @@ pub struct PoolStats {
 
 pub struct DatabaseManager {
     pool: ConnectionPool,
+    compaction_scheduled: AtomicBool,
 }
 
 impl DatabaseManager {
     pub fn new<P: AsRef<Path>>(database_path: P) -> DatabaseResult<Self> {
         let pool = ConnectionPool::new(database_path, 10)?; // Default 10 connections
-        Ok(Self { pool })
+        Ok(Self { pool, compaction_scheduled: AtomicBool::new(false) })
     }
 
     pub fn with_pool_config<P: AsRef<Path>>(
@@ -230,7 +232,7 @@ impl DatabaseManager {
     ) -> DatabaseResult<Self> {
         let pool = ConnectionPool::new(database_path, max_connections)?
             .with_timeouts(max_lifetime, max_idle_time, Duration::from_secs(30));
-        Ok(Self { pool })
+        Ok(Self { pool, compaction_scheduled: AtomicBool::new(false) })
     }
 
     pub fn execute<F, R>(&self, f: F) -> DatabaseResult<R>
@@ -262,6 +264,43 @@ impl DatabaseManager {
         self.pool.pool_stats()
     }
 
+    pub fn checkpoint(&self) -> DatabaseResult<()> {
+        self.execute(|conn| conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);"))
+    }
+
+    /// Ask for the space freed by a large deletion to be handed back to the filesystem
+    /// the next time `run_scheduled_compaction` is called
+    pub fn schedule_compaction(&self) {
+        self.compaction_scheduled.store(true, Ordering::SeqCst);
+    }
+
+    /// Run an incremental vacuum if one was scheduled, returning the number of pages freed.
+    /// A failed vacuum stays scheduled so it is retried next time.
+    pub fn run_scheduled_compaction(&self) -> DatabaseResult<Option<u32>> {
+        if !self.compaction_scheduled.swap(false, Ordering::SeqCst) {
+            return Ok(None);
+        }
+
+        let result = self.execute(|conn| {
+            let free_pages: u32 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+            // Each step of the pragma frees one page, so it has to be run to completion
+            let mut stmt = conn.prepare("PRAGMA incremental_vacuum")?;
+            let mut steps = stmt.query([])?;
+            while steps.next()?.is_some() {}
+            let remaining: u32 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+            Ok(free_pages.saturating_sub(remaining))
+        }).and_then(|freed| {
+            // The file only shrinks once the write-ahead log is checkpointed
+            self.checkpoint()?;
+            Ok(freed)
+        });
+
+        if result.is_err() {
+            self.schedule_compaction();
+        }
+        result.map(Some)
+    }
+
     pub fn close(&self) -> DatabaseResult<()> {
         self.pool.close_all()
     }
@@ -1,6 +1,26 @@
 use rusqlite::{Connection, Result as SqlResult};
 use std::collections::HashMap;
 
+/// Question types allowed by the questions table before short answers were added
+const ORIGINAL_QUESTION_TYPES: &str = "'multiple_choice', 'drag_drop', 'hotspot', 'fill_blank', 'story_quiz'";
+
+/// Keeps the previous state of a question whenever its content changes. App writes name
+/// their origin in `content_write_origin` for the length of the transaction; a change made
+/// without one (edited outside the app, or by code that bypasses the content manager) is
+/// raised as a content change alert.
+const QUESTION_VERSIONS_TRIGGER_SQL: &str = "CREATE TRIGGER IF NOT EXISTS questions_keep_versions
+    AFTER UPDATE OF content, correct_answer, difficulty_level, tags ON questions
+    WHEN OLD.content IS NOT NEW.content OR OLD.correct_answer IS NOT NEW.correct_answer
+        OR OLD.difficulty_level IS NOT NEW.difficulty_level OR OLD.tags IS NOT NEW.tags
+    BEGIN
+        INSERT INTO question_versions (question_id, content, correct_answer, difficulty_level, tags, origin, replaced_at)
+        VALUES (OLD.id, OLD.content, OLD.correct_answer, OLD.difficulty_level, OLD.tags,
+                (SELECT origin FROM content_write_origin LIMIT 1), strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+        INSERT INTO content_change_alerts (question_id, version_id, detected_at)
+        SELECT OLD.id, last_insert_rowid(), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+        WHERE NOT EXISTS (SELECT 1 FROM content_write_origin);
+    END;";
+
 pub struct Migration {
     pub version: u32,
     pub description: String,
@@ -39,6 +59,1241 @@ impl MigrationManager {
                 ('flags_capitals', 'Flags & Capitals', 'icons/flags.svg', '#00BCD4', 'World flags, capital cities, and country knowledge');".to_string(),
             down_sql: Some("DELETE FROM subjects WHERE name IN ('times_tables', 'flags_capitals');".to_string()),
         });
+
+        // Migration 3: Feature flags with per-profile overrides
+        self.add_migration(Migration {
+            version: 3,
+            description: "Add feature flag tables".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS feature_flags (
+                    flag_key TEXT PRIMARY KEY,
+                    enabled BOOLEAN NOT NULL,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE IF NOT EXISTS profile_feature_flags (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    flag_key TEXT NOT NULL,
+                    enabled BOOLEAN NOT NULL,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (profile_id, flag_key)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS profile_feature_flags;
+                DROP TABLE IF EXISTS feature_flags;".to_string()),
+        });
+
+        // Migration 4: Opt-in anonymized telemetry
+        self.add_migration(Migration {
+            version: 4,
+            description: "Add telemetry tables".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS telemetry_settings (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    opted_in BOOLEAN NOT NULL DEFAULT 0,
+                    installation_id TEXT NOT NULL,
+                    endpoint TEXT,
+                    session_in_progress BOOLEAN NOT NULL DEFAULT 0,
+                    period_started_at DATETIME,
+                    last_submitted_at DATETIME
+                );
+                INSERT OR IGNORE INTO telemetry_settings (id, opted_in, installation_id)
+                    VALUES (1, 0, lower(hex(randomblob(16))));
+                CREATE TABLE IF NOT EXISTS telemetry_counters (
+                    category TEXT NOT NULL CHECK (category IN ('feature', 'content_pack', 'session')),
+                    name TEXT NOT NULL,
+                    count INTEGER NOT NULL DEFAULT 0,
+                    PRIMARY KEY (category, name)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS telemetry_counters;
+                DROP TABLE IF EXISTS telemetry_settings;".to_string()),
+        });
+
+        // Migration 5: Desktop notifications and reminders
+        self.add_migration(Migration {
+            version: 5,
+            description: "Add notification tables".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS notification_preferences (
+                    profile_id INTEGER PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+                    reminders_enabled BOOLEAN NOT NULL DEFAULT 1,
+                    reminder_time TEXT NOT NULL DEFAULT '16:00',
+                    quiet_hours_start TEXT,
+                    quiet_hours_end TEXT,
+                    daily_question_goal INTEGER NOT NULL DEFAULT 20,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE IF NOT EXISTS notification_templates (
+                    kind TEXT PRIMARY KEY,
+                    title TEXT NOT NULL,
+                    body TEXT NOT NULL,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE IF NOT EXISTS notification_log (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER REFERENCES profiles(id) ON DELETE CASCADE,
+                    kind TEXT NOT NULL,
+                    sent_on TEXT NOT NULL,
+                    sent_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE INDEX IF NOT EXISTS idx_notification_log_sent_on ON notification_log(sent_on, kind);
+                CREATE TABLE IF NOT EXISTS daily_activity (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    activity_date TEXT NOT NULL,
+                    questions_answered INTEGER NOT NULL DEFAULT 0,
+                    PRIMARY KEY (profile_id, activity_date)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS daily_activity;
+                DROP TABLE IF EXISTS notification_log;
+                DROP TABLE IF EXISTS notification_templates;
+                DROP TABLE IF EXISTS notification_preferences;".to_string()),
+        });
+
+        // Migration 6: Homework assignments
+        self.add_migration(Migration {
+            version: 6,
+            description: "Add assignments table".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS assignments (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    title TEXT NOT NULL,
+                    source_type TEXT NOT NULL CHECK (source_type IN ('mix', 'blueprint')),
+                    mix_id INTEGER REFERENCES custom_mixes(id) ON DELETE CASCADE,
+                    blueprint TEXT, -- JSON mix config for one-off assignments
+                    due_at DATETIME NOT NULL,
+                    minimum_score INTEGER NOT NULL CHECK (minimum_score BETWEEN 0 AND 100),
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    completed_at DATETIME,
+                    best_score INTEGER,
+                    attempts INTEGER NOT NULL DEFAULT 0
+                );
+                CREATE INDEX IF NOT EXISTS idx_assignments_profile ON assignments(profile_id, due_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS assignments;".to_string()),
+        });
+
+        // Migration 7: Classroom mode classes and membership
+        self.add_migration(Migration {
+            version: 7,
+            description: "Add classes for classroom mode".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS classes (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                    description TEXT,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE IF NOT EXISTS class_members (
+                    class_id INTEGER NOT NULL REFERENCES classes(id) ON DELETE CASCADE,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    joined_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (class_id, profile_id)
+                );
+                CREATE INDEX IF NOT EXISTS idx_class_members_profile ON class_members(profile_id);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS class_members;
+                DROP TABLE IF EXISTS classes;".to_string()),
+        });
+
+        // Migration 8: Emailed weekly reports
+        self.add_migration(Migration {
+            version: 8,
+            description: "Add SMTP settings and email report log".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS email_settings (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    enabled BOOLEAN NOT NULL DEFAULT 0,
+                    host TEXT NOT NULL,
+                    port INTEGER NOT NULL,
+                    security TEXT NOT NULL CHECK (security IN ('none', 'starttls', 'tls')),
+                    username TEXT,
+                    password TEXT,
+                    from_address TEXT NOT NULL,
+                    to_address TEXT NOT NULL,
+                    send_weekday INTEGER NOT NULL CHECK (send_weekday BETWEEN 0 AND 6),
+                    send_hour INTEGER NOT NULL CHECK (send_hour BETWEEN 0 AND 23),
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE IF NOT EXISTS email_report_log (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    report_date TEXT NOT NULL,
+                    attempted_at DATETIME NOT NULL,
+                    succeeded BOOLEAN NOT NULL,
+                    error TEXT
+                );
+                CREATE INDEX IF NOT EXISTS idx_email_report_log_date ON email_report_log(report_date);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS email_report_log;
+                DROP TABLE IF EXISTS email_settings;".to_string()),
+        });
+
+        // Migration 9: Question of the day
+        self.add_migration(Migration {
+            version: 9,
+            description: "Add question of the day picks and completions".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS question_of_the_day (
+                    qotd_date TEXT NOT NULL,
+                    key_stage TEXT NOT NULL CHECK (key_stage IN ('KS1', 'KS2')),
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    PRIMARY KEY (qotd_date, key_stage)
+                );
+                CREATE TABLE IF NOT EXISTS question_of_the_day_completions (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    qotd_date TEXT NOT NULL,
+                    key_stage TEXT NOT NULL,
+                    question_id INTEGER NOT NULL,
+                    correct BOOLEAN NOT NULL,
+                    completed_at DATETIME NOT NULL,
+                    PRIMARY KEY (profile_id, qotd_date, key_stage)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS question_of_the_day_completions;
+                DROP TABLE IF EXISTS question_of_the_day;".to_string()),
+        });
+
+        // Migration 10: Reading passages shared by question groups
+        self.add_migration(Migration {
+            version: 10,
+            description: "Add reading passages and passage questions".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS passages (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    subject_id INTEGER NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+                    key_stage TEXT NOT NULL CHECK (key_stage IN ('KS1', 'KS2')),
+                    title TEXT NOT NULL,
+                    text TEXT NOT NULL,
+                    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE IF NOT EXISTS passage_questions (
+                    question_id INTEGER PRIMARY KEY REFERENCES questions(id) ON DELETE CASCADE,
+                    passage_id INTEGER NOT NULL REFERENCES passages(id) ON DELETE CASCADE,
+                    position INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_passages_subject ON passages(subject_id, key_stage);
+                CREATE INDEX IF NOT EXISTS idx_passage_questions_passage ON passage_questions(passage_id, position);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS passage_questions;
+                DROP TABLE IF EXISTS passages;".to_string()),
+        });
+
+        // Migration 11: EYFS and KS3 key stages. SQLite cannot alter a CHECK constraint,
+        // so the tables that restrict key_stage are rebuilt.
+        self.add_migration(Migration {
+            version: 11,
+            description: "Allow EYFS and KS3 key stages".to_string(),
+            up_sql: Self::rebuild_key_stage_tables_sql("'EYFS', 'KS1', 'KS2', 'KS3'"),
+            down_sql: Some(format!(
+                "DELETE FROM assets WHERE question_id IN (SELECT id FROM questions WHERE key_stage NOT IN ('KS1', 'KS2'));
+                DELETE FROM question_attempts WHERE question_id IN (SELECT id FROM questions WHERE key_stage NOT IN ('KS1', 'KS2'));
+                DELETE FROM passage_questions WHERE question_id IN (SELECT id FROM questions WHERE key_stage NOT IN ('KS1', 'KS2'))
+                    OR passage_id IN (SELECT id FROM passages WHERE key_stage NOT IN ('KS1', 'KS2'));
+                DELETE FROM question_of_the_day WHERE key_stage NOT IN ('KS1', 'KS2')
+                    OR question_id IN (SELECT id FROM questions WHERE key_stage NOT IN ('KS1', 'KS2'));
+                DELETE FROM questions WHERE key_stage NOT IN ('KS1', 'KS2');
+                DELETE FROM passages WHERE key_stage NOT IN ('KS1', 'KS2');
+                DELETE FROM progress WHERE key_stage NOT IN ('KS1', 'KS2');
+                {}",
+                Self::rebuild_key_stage_tables_sql("'KS1', 'KS2'")
+            )),
+        });
+
+        // Migration 12: Per-profile accessibility (dyslexia-friendly) settings
+        self.add_migration(Migration {
+            version: 12,
+            description: "Add accessibility settings".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS accessibility_settings (
+                    profile_id INTEGER PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+                    syllable_hints BOOLEAN NOT NULL DEFAULT 0,
+                    simplified_vocabulary BOOLEAN NOT NULL DEFAULT 0,
+                    extended_timers BOOLEAN NOT NULL DEFAULT 0,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS accessibility_settings;".to_string()),
+        });
+
+        // Migration 13: Drawn answers kept for parent review
+        self.add_migration(Migration {
+            version: 13,
+            description: "Add answer drawings".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS answer_drawings (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    session_id INTEGER,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    drawing TEXT NOT NULL,
+                    recognized_text TEXT,
+                    is_correct BOOLEAN NOT NULL,
+                    submitted_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_answer_drawings_profile ON answer_drawings(profile_id, submitted_at);
+                CREATE INDEX IF NOT EXISTS idx_answer_drawings_session ON answer_drawings(session_id);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS answer_drawings;".to_string()),
+        });
+
+        // Migration 14: Per-question working-out kept with the answer
+        self.add_migration(Migration {
+            version: 14,
+            description: "Add scratchpad entries".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS scratchpad_entries (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    session_id INTEGER NOT NULL,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    text TEXT,
+                    drawing TEXT,
+                    answer TEXT,
+                    is_correct BOOLEAN,
+                    updated_at DATETIME NOT NULL,
+                    answered_at DATETIME
+                );
+                CREATE INDEX IF NOT EXISTS idx_scratchpad_entries_session ON scratchpad_entries(profile_id, session_id, question_id);
+                CREATE INDEX IF NOT EXISTS idx_scratchpad_entries_answered ON scratchpad_entries(profile_id, answered_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS scratchpad_entries;".to_string()),
+        });
+
+        // Migration 15: Wellbeing breaks after long stretches of quizzing
+        self.add_migration(Migration {
+            version: 15,
+            description: "Add wellbeing breaks".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS wellbeing_settings (
+                    profile_id INTEGER PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+                    enabled BOOLEAN NOT NULL DEFAULT 1,
+                    max_continuous_minutes INTEGER NOT NULL,
+                    break_minutes INTEGER NOT NULL,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                CREATE TABLE IF NOT EXISTS wellbeing_activity (
+                    profile_id INTEGER PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+                    stretch_started_at DATETIME NOT NULL,
+                    last_activity_at DATETIME NOT NULL
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS wellbeing_activity;
+                DROP TABLE IF EXISTS wellbeing_settings;".to_string()),
+        });
+
+        // Migration 16: Destructive actions waiting for a parent's approval
+        self.add_migration(Migration {
+            version: 16,
+            description: "Add approval requests".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS approval_requests (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    action TEXT NOT NULL,
+                    summary TEXT NOT NULL,
+                    requested_by INTEGER REFERENCES profiles(id) ON DELETE SET NULL,
+                    reason TEXT,
+                    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+                    requested_at DATETIME NOT NULL,
+                    decided_at DATETIME
+                );
+                CREATE INDEX IF NOT EXISTS idx_approval_requests_status ON approval_requests(status, requested_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS approval_requests;".to_string()),
+        });
+
+        // Migration 17: Boss question attempts
+        self.add_migration(Migration {
+            version: 17,
+            description: "Add boss question attempts".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS boss_question_attempts (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    difficulty_level INTEGER NOT NULL,
+                    is_correct BOOLEAN NOT NULL,
+                    attempted_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_boss_question_attempts_profile ON boss_question_attempts(profile_id, attempted_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS boss_question_attempts;".to_string()),
+        });
+
+        // Migration 18: Open-ended short-answer questions; the question_type CHECK is rebuilt
+        self.add_migration(Migration {
+            version: 18,
+            description: "Add short answer question type".to_string(),
+            up_sql: Self::rebuild_questions_table_sql(
+                "'EYFS', 'KS1', 'KS2', 'KS3'",
+                &format!("{}, 'short_answer'", ORIGINAL_QUESTION_TYPES),
+            ),
+            down_sql: Some(format!(
+                "DELETE FROM assets WHERE question_id IN (SELECT id FROM questions WHERE question_type = 'short_answer');
+                DELETE FROM question_attempts WHERE question_id IN (SELECT id FROM questions WHERE question_type = 'short_answer');
+                DELETE FROM passage_questions WHERE question_id IN (SELECT id FROM questions WHERE question_type = 'short_answer');
+                DELETE FROM question_of_the_day WHERE question_id IN (SELECT id FROM questions WHERE question_type = 'short_answer');
+                DELETE FROM answer_drawings WHERE question_id IN (SELECT id FROM questions WHERE question_type = 'short_answer');
+                DELETE FROM scratchpad_entries WHERE question_id IN (SELECT id FROM questions WHERE question_type = 'short_answer');
+                DELETE FROM boss_question_attempts WHERE question_id IN (SELECT id FROM questions WHERE question_type = 'short_answer');
+                DELETE FROM questions WHERE question_type = 'short_answer';
+                {}",
+                Self::rebuild_questions_table_sql("'EYFS', 'KS1', 'KS2', 'KS3'", ORIGINAL_QUESTION_TYPES)
+            )),
+        });
+
+        // Migration 19: Question failures the quiz engine recovered from mid-session
+        self.add_migration(Migration {
+            version: 19,
+            description: "Add quiz incidents".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS quiz_incidents (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id INTEGER NOT NULL,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    question_id INTEGER,
+                    stage TEXT NOT NULL,
+                    error TEXT NOT NULL,
+                    replacement_question_id INTEGER,
+                    occurred_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_quiz_incidents_occurred ON quiz_incidents(occurred_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS quiz_incidents;".to_string()),
+        });
+
+        // Migration 20: Per-subject quick play presets
+        self.add_migration(Migration {
+            version: 20,
+            description: "Add quiz presets".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS quiz_presets (
+                    subject_id INTEGER PRIMARY KEY REFERENCES subjects(id) ON DELETE CASCADE,
+                    question_count INTEGER NOT NULL,
+                    time_limit_seconds INTEGER,
+                    allowed_question_types TEXT NOT NULL DEFAULT '[]',
+                    updated_at DATETIME NOT NULL
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS quiz_presets;".to_string()),
+        });
+
+        // Migration 21: Audit log of progress resets, with what each one cleared
+        self.add_migration(Migration {
+            version: 21,
+            description: "Add progress reset log".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS progress_resets (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    scope TEXT NOT NULL,
+                    snapshot TEXT NOT NULL,
+                    reset_at DATETIME NOT NULL,
+                    restored_at DATETIME
+                );
+                CREATE INDEX IF NOT EXISTS idx_progress_resets_profile ON progress_resets(profile_id, reset_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS progress_resets;".to_string()),
+        });
+
+        // Migration 22: Per-subject key stage and difficulty overrides
+        self.add_migration(Migration {
+            version: 22,
+            description: "Add difficulty curves".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS difficulty_curves (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    subject_id INTEGER NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+                    key_stage TEXT NOT NULL,
+                    min_difficulty INTEGER,
+                    max_difficulty INTEGER,
+                    updated_at DATETIME NOT NULL,
+                    PRIMARY KEY (profile_id, subject_id)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS difficulty_curves;".to_string()),
+        });
+
+        // Migration 23: Where each question came from
+        self.add_migration(Migration {
+            version: 23,
+            description: "Add question sources".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS question_sources (
+                    question_id INTEGER PRIMARY KEY REFERENCES questions(id) ON DELETE CASCADE,
+                    kind TEXT NOT NULL,
+                    reference TEXT,
+                    details TEXT NOT NULL,
+                    recorded_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_question_sources_kind ON question_sources(kind, reference);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS question_sources;".to_string()),
+        });
+
+        // Migration 24: Questions kept only for the history that refers to them
+        self.add_migration(Migration {
+            version: 24,
+            description: "Add retired questions".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS retired_questions (
+                    question_id INTEGER PRIMARY KEY REFERENCES questions(id) ON DELETE CASCADE,
+                    reason TEXT NOT NULL,
+                    retired_at DATETIME NOT NULL
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS retired_questions;".to_string()),
+        });
+
+        // Migration 25: Weekly spelling lists from school
+        self.add_migration(Migration {
+            version: 25,
+            description: "Add spelling lists".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS spelling_lists (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    title TEXT NOT NULL,
+                    key_stage TEXT NOT NULL,
+                    starts_on TEXT NOT NULL,
+                    test_date TEXT NOT NULL,
+                    created_at DATETIME NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS spelling_words (
+                    list_id INTEGER NOT NULL REFERENCES spelling_lists(id) ON DELETE CASCADE,
+                    position INTEGER NOT NULL,
+                    word TEXT NOT NULL,
+                    sentence TEXT,
+                    spelling_question_id INTEGER REFERENCES questions(id) ON DELETE SET NULL,
+                    fill_blank_question_id INTEGER REFERENCES questions(id) ON DELETE SET NULL,
+                    PRIMARY KEY (list_id, position)
+                );
+                CREATE TABLE IF NOT EXISTS spelling_attempts (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    list_id INTEGER NOT NULL REFERENCES spelling_lists(id) ON DELETE CASCADE,
+                    word TEXT NOT NULL,
+                    answer TEXT NOT NULL,
+                    correct BOOLEAN NOT NULL,
+                    attempted_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_spelling_lists_profile ON spelling_lists(profile_id, test_date);
+                CREATE INDEX IF NOT EXISTS idx_spelling_attempts_word ON spelling_attempts(list_id, word);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS spelling_attempts;
+                DROP TABLE IF EXISTS spelling_words;
+                DROP TABLE IF EXISTS spelling_lists;".to_string()),
+        });
+
+        // Migration 26: Which questions each profile has answered, for curriculum coverage
+        self.add_migration(Migration {
+            version: 26,
+            description: "Add answered questions".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS answered_questions (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    times_answered INTEGER NOT NULL,
+                    times_correct INTEGER NOT NULL,
+                    last_answered_at DATETIME NOT NULL,
+                    PRIMARY KEY (profile_id, question_id)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS answered_questions;".to_string()),
+        });
+
+        // Migration 27: Timeline of each quiz session, for replays
+        self.add_migration(Migration {
+            version: 27,
+            description: "Add session events".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS session_events (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id INTEGER NOT NULL,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    kind TEXT NOT NULL,
+                    question_index INTEGER,
+                    question_id INTEGER REFERENCES questions(id) ON DELETE SET NULL,
+                    details TEXT NOT NULL,
+                    occurred_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_session_events_session ON session_events(session_id, id);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS session_events;".to_string()),
+        });
+
+        // Migration 28: Disk usage cap and cleanup preferences
+        self.add_migration(Migration {
+            version: 28,
+            description: "Add storage settings".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS storage_settings (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    cap_bytes INTEGER CHECK (cap_bytes IS NULL OR cap_bytes > 0),
+                    backups_to_keep INTEGER NOT NULL DEFAULT 3 CHECK (backups_to_keep >= 0),
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                INSERT OR IGNORE INTO storage_settings (id) VALUES (1);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS storage_settings;".to_string()),
+        });
+
+        // Migration 29: How long detailed history is kept, and what pruning condensed
+        self.add_migration(Migration {
+            version: 29,
+            description: "Add history retention".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS retention_settings (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    detail_retention_months INTEGER DEFAULT 12 CHECK (detail_retention_months IS NULL OR detail_retention_months > 0),
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+                INSERT OR IGNORE INTO retention_settings (id) VALUES (1);
+                CREATE TABLE IF NOT EXISTS condensed_sessions (
+                    session_id INTEGER PRIMARY KEY,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    started_at DATETIME NOT NULL,
+                    completed_at DATETIME,
+                    questions_answered INTEGER NOT NULL,
+                    correct_answers INTEGER NOT NULL,
+                    hints_used INTEGER NOT NULL,
+                    condensed_at DATETIME NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS retention_runs (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    ran_at DATETIME NOT NULL,
+                    cutoff DATETIME NOT NULL,
+                    summary TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_condensed_sessions_profile ON condensed_sessions(profile_id, started_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS retention_runs;
+                DROP TABLE IF EXISTS condensed_sessions;
+                DROP TABLE IF EXISTS retention_settings;".to_string()),
+        });
+
+        // Migration 30: Questions reported as wrong or confusing
+        self.add_migration(Migration {
+            version: 30,
+            description: "Add question flags".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS question_flags (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    profile_id INTEGER REFERENCES profiles(id) ON DELETE SET NULL,
+                    reason TEXT,
+                    flagged_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_question_flags_question ON question_flags(question_id);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS question_flags;".to_string()),
+        });
+
+        // Migration 31: Tokens letting integrations read a profile's data
+        self.add_migration(Migration {
+            version: 31,
+            description: "Add API tokens".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS api_tokens (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    name TEXT NOT NULL,
+                    token_hash TEXT NOT NULL UNIQUE,
+                    token_prefix TEXT NOT NULL,
+                    scopes TEXT NOT NULL,
+                    created_at DATETIME NOT NULL,
+                    last_used_at DATETIME,
+                    revoked_at DATETIME
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS api_tokens;".to_string()),
+        });
+
+        // Migration 32: First-run onboarding and the parent's PIN. Databases that already
+        // have profiles were set up before onboarding existed, so they skip it.
+        self.add_migration(Migration {
+            version: 32,
+            description: "Add onboarding state and parent credential".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS onboarding_state (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    step TEXT NOT NULL,
+                    first_profile_id INTEGER REFERENCES profiles(id) ON DELETE SET NULL,
+                    subjects TEXT,
+                    completed_at DATETIME,
+                    updated_at DATETIME
+                );
+                INSERT OR IGNORE INTO onboarding_state (id, step, completed_at)
+                    SELECT 1,
+                           CASE WHEN EXISTS (SELECT 1 FROM profiles) THEN 'complete' ELSE 'parent_credential' END,
+                           CASE WHEN EXISTS (SELECT 1 FROM profiles) THEN strftime('%Y-%m-%dT%H:%M:%SZ', 'now') END;
+                CREATE TABLE IF NOT EXISTS parent_credential (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    salt TEXT NOT NULL,
+                    hash TEXT NOT NULL,
+                    iterations INTEGER NOT NULL,
+                    created_at DATETIME NOT NULL
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS parent_credential;
+                DROP TABLE IF EXISTS onboarding_state;".to_string()),
+        });
+
+        // Migration 33: Families sharing one computer. Everything so far, including the
+        // parent's PIN, moves into the first household.
+        self.add_migration(Migration {
+            version: 33,
+            description: "Add households".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS households (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    credential_salt TEXT,
+                    credential_hash TEXT,
+                    credential_iterations INTEGER,
+                    created_at DATETIME NOT NULL
+                );
+                INSERT INTO households (id, name, credential_salt, credential_hash, credential_iterations, created_at)
+                    SELECT 1, 'Family', c.salt, c.hash, c.iterations, strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+                    FROM (SELECT 1) LEFT JOIN parent_credential c ON c.id = 1;
+                DROP TABLE parent_credential;
+                CREATE TABLE IF NOT EXISTS active_household (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    household_id INTEGER NOT NULL REFERENCES households(id)
+                );
+                INSERT INTO active_household (id, household_id) VALUES (1, 1);
+                CREATE TABLE IF NOT EXISTS profile_households (
+                    profile_id INTEGER PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+                    household_id INTEGER NOT NULL REFERENCES households(id) ON DELETE CASCADE
+                );
+                INSERT INTO profile_households (profile_id, household_id) SELECT id, 1 FROM profiles;
+                CREATE TABLE IF NOT EXISTS question_households (
+                    question_id INTEGER PRIMARY KEY REFERENCES questions(id) ON DELETE CASCADE,
+                    household_id INTEGER NOT NULL REFERENCES households(id) ON DELETE CASCADE
+                );
+                INSERT INTO question_households (question_id, household_id)
+                    SELECT question_id, 1 FROM question_sources WHERE kind IN ('parent', 'import');
+                CREATE INDEX IF NOT EXISTS idx_profile_households_household ON profile_households(household_id);
+                CREATE INDEX IF NOT EXISTS idx_question_households_household ON question_households(household_id);".to_string(),
+            down_sql: Some("CREATE TABLE IF NOT EXISTS parent_credential (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    salt TEXT NOT NULL,
+                    hash TEXT NOT NULL,
+                    iterations INTEGER NOT NULL,
+                    created_at DATETIME NOT NULL
+                );
+                INSERT INTO parent_credential (id, salt, hash, iterations, created_at)
+                    SELECT 1, credential_salt, credential_hash, credential_iterations, created_at
+                    FROM households WHERE id = 1 AND credential_hash IS NOT NULL;
+                DROP TABLE IF EXISTS question_households;
+                DROP TABLE IF EXISTS profile_households;
+                DROP TABLE IF EXISTS active_household;
+                DROP TABLE IF EXISTS households;".to_string()),
+        });
+
+        // Migration 34: Year group and key stage of students imported from a class roster
+        self.add_migration(Migration {
+            version: 34,
+            description: "Add profile school details".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS profile_school_details (
+                    profile_id INTEGER PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+                    year_group INTEGER CHECK (year_group BETWEEN 0 AND 9),
+                    key_stage TEXT
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS profile_school_details;".to_string()),
+        });
+
+        // Migration 35: Keep whether a session was an assessment, and how often it was left,
+        // once its timeline is condensed
+        self.add_migration(Migration {
+            version: 35,
+            description: "Add session mode to condensed sessions".to_string(),
+            up_sql: "ALTER TABLE condensed_sessions ADD COLUMN mode TEXT NOT NULL DEFAULT 'standard';
+                ALTER TABLE condensed_sessions ADD COLUMN times_left INTEGER NOT NULL DEFAULT 0;".to_string(),
+            down_sql: Some("ALTER TABLE condensed_sessions DROP COLUMN times_left;
+                ALTER TABLE condensed_sessions DROP COLUMN mode;".to_string()),
+        });
+
+        // Migration 36: Mock test papers, so the same paper can be sat on screen and printed
+        self.add_migration(Migration {
+            version: 36,
+            description: "Add mock papers".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS mock_papers (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    blueprint_id TEXT NOT NULL,
+                    shortfalls TEXT NOT NULL,
+                    created_at DATETIME NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS mock_paper_questions (
+                    paper_id INTEGER NOT NULL REFERENCES mock_papers(id) ON DELETE CASCADE,
+                    position INTEGER NOT NULL,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    topic TEXT NOT NULL,
+                    PRIMARY KEY (paper_id, position)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS mock_paper_questions;
+                DROP TABLE IF EXISTS mock_papers;".to_string()),
+        });
+
+        // Migration 37: Reading age estimates, kept so parents can see the trend
+        self.add_migration(Migration {
+            version: 37,
+            description: "Add reading age estimates".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS reading_age_estimates (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    reading_age REAL NOT NULL,
+                    lower_bound REAL NOT NULL,
+                    upper_bound REAL NOT NULL,
+                    answers_considered INTEGER NOT NULL,
+                    estimated_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_reading_age_estimates_profile ON reading_age_estimates(profile_id, estimated_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS reading_age_estimates;".to_string()),
+        });
+
+        // Migration 38: Spaced-repetition review schedule for each profile's questions
+        self.add_migration(Migration {
+            version: 38,
+            description: "Add question reviews".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS question_reviews (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    ease_factor REAL NOT NULL,
+                    interval_days INTEGER NOT NULL,
+                    repetitions INTEGER NOT NULL,
+                    due_at DATETIME NOT NULL,
+                    last_reviewed_at DATETIME NOT NULL,
+                    PRIMARY KEY (profile_id, question_id)
+                );
+                CREATE INDEX IF NOT EXISTS idx_question_reviews_due ON question_reviews(profile_id, due_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS question_reviews;".to_string()),
+        });
+
+        // Migration 39: Multiplication Tables Check practice runs
+        self.add_migration(Migration {
+            version: 39,
+            description: "Add multiplication checks".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS multiplication_checks (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    started_at DATETIME NOT NULL,
+                    completed_at DATETIME,
+                    score INTEGER
+                );
+                CREATE TABLE IF NOT EXISTS multiplication_check_questions (
+                    check_id INTEGER NOT NULL REFERENCES multiplication_checks(id) ON DELETE CASCADE,
+                    position INTEGER NOT NULL,
+                    multiplicand INTEGER NOT NULL,
+                    multiplier INTEGER NOT NULL,
+                    answer TEXT,
+                    correct BOOLEAN,
+                    shown_at DATETIME,
+                    answered_at DATETIME,
+                    PRIMARY KEY (check_id, position)
+                );
+                CREATE INDEX IF NOT EXISTS idx_multiplication_checks_profile ON multiplication_checks(profile_id, completed_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS multiplication_check_questions;
+                DROP TABLE IF EXISTS multiplication_checks;".to_string()),
+        });
+
+        // Migration 40: Ordering questions; the question_type CHECK is rebuilt
+        self.add_migration(Migration {
+            version: 40,
+            description: "Add ordering question type".to_string(),
+            up_sql: Self::rebuild_questions_table_sql(
+                "'EYFS', 'KS1', 'KS2', 'KS3'",
+                &format!("{}, 'short_answer', 'ordering'", ORIGINAL_QUESTION_TYPES),
+            ),
+            down_sql: Some(format!(
+                "{}
+                {}",
+                Self::delete_questions_sql("question_type = 'ordering'"),
+                Self::rebuild_questions_table_sql("'EYFS', 'KS1', 'KS2', 'KS3'", &format!("{}, 'short_answer'", ORIGINAL_QUESTION_TYPES))
+            )),
+        });
+
+        // Migration 41: Phonics screening check practice, marked word by word
+        self.add_migration(Migration {
+            version: 41,
+            description: "Add phonics screenings".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS phonics_screenings (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    started_at DATETIME NOT NULL,
+                    completed_at DATETIME,
+                    score INTEGER
+                );
+                CREATE TABLE IF NOT EXISTS phonics_screening_words (
+                    screening_id INTEGER NOT NULL REFERENCES phonics_screenings(id) ON DELETE CASCADE,
+                    position INTEGER NOT NULL,
+                    word TEXT NOT NULL,
+                    graphemes TEXT NOT NULL,
+                    section INTEGER NOT NULL CHECK (section IN (1, 2)),
+                    pseudo BOOLEAN NOT NULL,
+                    read_correctly BOOLEAN,
+                    misread_graphemes TEXT,
+                    marked_at DATETIME,
+                    PRIMARY KEY (screening_id, position)
+                );
+                CREATE INDEX IF NOT EXISTS idx_phonics_screenings_profile ON phonics_screenings(profile_id, completed_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS phonics_screening_words;
+                DROP TABLE IF EXISTS phonics_screenings;".to_string()),
+        });
+
+        // Migration 42: Explore mode, where browsing is recorded as exposure rather than performance
+        self.add_migration(Migration {
+            version: 42,
+            description: "Add explore exposures and practice-later list".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS explore_exposures (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    view_count INTEGER NOT NULL DEFAULT 1,
+                    answer_revealed BOOLEAN NOT NULL DEFAULT 0,
+                    first_viewed_at DATETIME NOT NULL,
+                    last_viewed_at DATETIME NOT NULL,
+                    PRIMARY KEY (profile_id, question_id)
+                );
+                CREATE TABLE IF NOT EXISTS practice_later (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    marked_at DATETIME NOT NULL,
+                    PRIMARY KEY (profile_id, question_id)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS practice_later;
+                DROP TABLE IF EXISTS explore_exposures;".to_string()),
+        });
+
+        // Migration 43: Question explanations, moved out of content.additional_data
+        self.add_migration(Migration {
+            version: 43,
+            description: "Add question explanations".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS question_explanations (
+                    question_id INTEGER PRIMARY KEY REFERENCES questions(id) ON DELETE CASCADE,
+                    explanation TEXT NOT NULL
+                );
+                INSERT OR IGNORE INTO question_explanations (question_id, explanation)
+                    SELECT id, trim(json_extract(content, '$.additional_data.explanation')) FROM questions
+                    WHERE json_valid(content)
+                      AND json_type(content, '$.additional_data.explanation') = 'text'
+                      AND trim(json_extract(content, '$.additional_data.explanation')) != '';
+                UPDATE questions SET content = json_remove(content, '$.additional_data.explanation')
+                    WHERE json_valid(content) AND json_type(content, '$.additional_data.explanation') IS NOT NULL;".to_string(),
+            down_sql: Some("UPDATE questions SET content = json_set(
+                        content,
+                        '$.additional_data',
+                        json_set(
+                            CASE WHEN json_type(content, '$.additional_data') = 'object'
+                                THEN json_extract(content, '$.additional_data') ELSE '{}' END,
+                            '$.explanation',
+                            (SELECT explanation FROM question_explanations WHERE question_id = questions.id)
+                        )
+                    )
+                    WHERE id IN (SELECT question_id FROM question_explanations);
+                DROP TABLE IF EXISTS question_explanations;".to_string()),
+        });
+
+        // Migration 44: Version history of question content, and alerts for changes made
+        // without the app saying where they came from
+        self.add_migration(Migration {
+            version: 44,
+            description: "Add question versions and content change alerts".to_string(),
+            up_sql: format!(
+                "CREATE TABLE IF NOT EXISTS question_versions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    content TEXT NOT NULL,
+                    correct_answer TEXT NOT NULL,
+                    difficulty_level INTEGER,
+                    tags TEXT,
+                    origin TEXT,
+                    replaced_at DATETIME NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_question_versions_question ON question_versions(question_id, id);
+                CREATE TABLE IF NOT EXISTS content_write_origin (
+                    origin TEXT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS content_change_alerts (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    version_id INTEGER NOT NULL REFERENCES question_versions(id) ON DELETE CASCADE,
+                    detected_at DATETIME NOT NULL,
+                    announced_at DATETIME,
+                    reverted_at DATETIME,
+                    dismissed_at DATETIME
+                );
+                {}",
+                QUESTION_VERSIONS_TRIGGER_SQL
+            ),
+            down_sql: Some("DROP TRIGGER IF EXISTS questions_keep_versions;
+                DROP TABLE IF EXISTS content_change_alerts;
+                DROP TABLE IF EXISTS content_write_origin;
+                DROP TABLE IF EXISTS question_versions;".to_string()),
+        });
+
+        // Migration 45: Each household's locale, for money questions and report formatting
+        self.add_migration(Migration {
+            version: 45,
+            description: "Add locale settings".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS locale_settings (
+                    household_id INTEGER PRIMARY KEY REFERENCES households(id) ON DELETE CASCADE,
+                    locale TEXT NOT NULL,
+                    updated_at DATETIME NOT NULL
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS locale_settings;".to_string()),
+        });
+
+        // Migration 46: Curriculum profiles. Each curriculum's name and expected difficulties for
+        // every key stage, and which curriculum each household follows.
+        self.add_migration(Migration {
+            version: 46,
+            description: "Add curriculum profiles".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS curriculum_levels (
+                    curriculum TEXT NOT NULL,
+                    key_stage TEXT NOT NULL,
+                    label TEXT NOT NULL,
+                    min_difficulty INTEGER NOT NULL CHECK (min_difficulty BETWEEN 1 AND 5),
+                    max_difficulty INTEGER NOT NULL CHECK (max_difficulty BETWEEN 1 AND 5),
+                    PRIMARY KEY (curriculum, key_stage)
+                );
+
+                INSERT OR IGNORE INTO curriculum_levels (curriculum, key_stage, label, min_difficulty, max_difficulty) VALUES
+                    ('england', 'EYFS', 'EYFS', 1, 5),
+                    ('england', 'KS1', 'KS1', 1, 5),
+                    ('england', 'KS2', 'KS2', 1, 5),
+                    ('england', 'KS3', 'KS3', 1, 5),
+                    ('scotland', 'EYFS', 'Early Level', 1, 5),
+                    ('scotland', 'KS1', 'First Level', 1, 5),
+                    ('scotland', 'KS2', 'Second Level', 2, 5),
+                    ('scotland', 'KS3', 'Third Level', 1, 5),
+                    ('united_states', 'EYFS', 'Pre-K', 1, 5),
+                    ('united_states', 'KS1', 'Kindergarten-Grade 1', 1, 5),
+                    ('united_states', 'KS2', 'Grades 2-5', 1, 5),
+                    ('united_states', 'KS3', 'Grades 6-8', 1, 5);
+
+                CREATE TABLE IF NOT EXISTS curriculum_settings (
+                    household_id INTEGER PRIMARY KEY REFERENCES households(id) ON DELETE CASCADE,
+                    curriculum TEXT NOT NULL,
+                    updated_at DATETIME NOT NULL
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS curriculum_settings;
+                DROP TABLE IF EXISTS curriculum_levels;".to_string()),
+        });
+
+        // Migration 47: Questions each profile got wrong, until they've been answered right twice
+        self.add_migration(Migration {
+            version: 47,
+            description: "Add mistakes bank".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS mistakes (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                    times_wrong INTEGER NOT NULL,
+                    correct_since INTEGER NOT NULL DEFAULT 0,
+                    first_wrong_at DATETIME NOT NULL,
+                    last_wrong_at DATETIME NOT NULL,
+                    PRIMARY KEY (profile_id, question_id)
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS mistakes;".to_string()),
+        });
+
+        // Migration 48: Achievement rules, so badges are data rather than code. The first four
+        // keep the ids of the achievements profiles may already have earned.
+        self.add_migration(Migration {
+            version: 48,
+            description: "Add achievement rules".to_string(),
+            up_sql: r#"CREATE TABLE IF NOT EXISTS achievement_rules (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    description TEXT NOT NULL,
+                    icon TEXT NOT NULL,
+                    category TEXT NOT NULL CHECK (category IN ('accuracy', 'streak', 'completion', 'time', 'subject_mastery')),
+                    condition TEXT NOT NULL,
+                    sort_order INTEGER NOT NULL
+                );
+
+                INSERT OR IGNORE INTO achievement_rules (id, name, description, icon, category, condition, sort_order) VALUES
+                    ('first_steps', 'First Steps', 'Answered your first question!', '👶', 'completion', '{"kind": "questions_answered", "count": 1}', 1),
+                    ('perfect_score', 'Perfect Score', 'Got every question right in a quiz of at least 5 questions!', '💯', 'accuracy', '{"kind": "perfect_quiz", "min_questions": 5}', 2),
+                    ('quick_learner', 'Quick Learner', 'Answered 10 questions!', '⚡', 'completion', '{"kind": "questions_answered", "count": 10}', 3),
+                    ('subject_explorer', 'Subject Explorer', 'Tried questions from 3 different subjects!', '🗺️', 'subject_mastery', '{"kind": "subjects_tried", "count": 3}', 4),
+                    ('week_streak', 'Week Streak', 'Practised 7 days in a row!', '🔥', 'streak', '{"kind": "practice_streak", "days": 7}', 5),
+                    ('century', 'Century', 'Answered 100 questions!', '🏅', 'completion', '{"kind": "questions_answered", "count": 100}', 6),
+                    ('maths_100', 'Maths Marathon', 'Answered 100 maths questions!', '🧮', 'subject_mastery', '{"kind": "subject_questions", "subject": "mathematics", "count": 100}', 7);"#.to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS achievement_rules;".to_string()),
+        });
+
+        // Migration 49: Completed quizzes for daily streaks, and the freeze tokens parents grant
+        // to cover missed days. Days already practised are carried over at midday UTC, which
+        // falls on the same date in almost every time zone.
+        self.add_migration(Migration {
+            version: 49,
+            description: "Add daily streaks".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS quiz_completions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    completed_at DATETIME NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_quiz_completions_profile ON quiz_completions(profile_id, completed_at);
+
+                CREATE TABLE IF NOT EXISTS streak_freezes (
+                    profile_id INTEGER PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+                    available INTEGER NOT NULL DEFAULT 0
+                );
+
+                CREATE TABLE IF NOT EXISTS streak_freeze_days (
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    frozen_day TEXT NOT NULL,
+                    PRIMARY KEY (profile_id, frozen_day)
+                );
+
+                INSERT INTO quiz_completions (profile_id, completed_at)
+                    SELECT profile_id, activity_date || 'T12:00:00+00:00' FROM daily_activity
+                    WHERE questions_answered > 0 AND profile_id IN (SELECT id FROM profiles);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS streak_freeze_days;
+                DROP TABLE IF EXISTS streak_freezes;
+                DROP TABLE IF EXISTS quiz_completions;".to_string()),
+        });
+
+        // Migration 50: Experience points, with each profile's total and level kept alongside
+        // the gains that made them up
+        self.add_migration(Migration {
+            version: 50,
+            description: "Add experience points".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS profile_xp (
+                    profile_id INTEGER PRIMARY KEY REFERENCES profiles(id) ON DELETE CASCADE,
+                    total_xp INTEGER NOT NULL DEFAULT 0,
+                    level INTEGER NOT NULL DEFAULT 1,
+                    updated_at DATETIME NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS xp_gains (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    xp INTEGER NOT NULL,
+                    subject TEXT NOT NULL,
+                    level_before INTEGER NOT NULL,
+                    level_after INTEGER NOT NULL,
+                    earned_at DATETIME NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_xp_gains_profile ON xp_gains(profile_id, earned_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS xp_gains;
+                DROP TABLE IF EXISTS profile_xp;".to_string()),
+        });
+
+        // Migration 51: Hashes of question asset files as first seen, and the results of each
+        // integrity check against them
+        self.add_migration(Migration {
+            version: 51,
+            description: "Add asset integrity checks".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS asset_hashes (
+                    file_path TEXT PRIMARY KEY,
+                    content_hash TEXT NOT NULL,
+                    recorded_at DATETIME NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS asset_integrity_runs (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    ran_at DATETIME NOT NULL,
+                    summary TEXT NOT NULL
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS asset_integrity_runs;
+                DROP TABLE IF EXISTS asset_hashes;".to_string()),
+        });
+
+        // Migration 52: Encrypted backups to storage the parent controls. The destination is
+        // JSON without its secret key, which is kept in its own column with the backup key
+        // so both can be wiped from the copies uploaded.
+        self.add_migration(Migration {
+            version: 52,
+            description: "Add cloud backups".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS cloud_backup_settings (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    enabled BOOLEAN NOT NULL DEFAULT 0,
+                    destination TEXT NOT NULL,
+                    secret_access_key TEXT,
+                    interval_hours INTEGER NOT NULL CHECK (interval_hours > 0),
+                    backups_to_keep INTEGER NOT NULL CHECK (backups_to_keep > 0),
+                    encryption_key TEXT,
+                    key_salt TEXT,
+                    key_rounds INTEGER,
+                    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                );
+
+                CREATE TABLE IF NOT EXISTS cloud_backup_log (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    attempted_at DATETIME NOT NULL,
+                    succeeded BOOLEAN NOT NULL,
+                    backup_name TEXT,
+                    size_bytes INTEGER,
+                    error TEXT
+                );".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS cloud_backup_log;
+                DROP TABLE IF EXISTS cloud_backup_settings;".to_string()),
+        });
+
+        // Migration 53: Head-to-head results, a row for each player so every profile has its
+        // own record. The opponent is cleared if their profile is deleted.
+        self.add_migration(Migration {
+            version: 53,
+            description: "Add head-to-head results".to_string(),
+            up_sql: "CREATE TABLE IF NOT EXISTS head_to_head_results (
+                    session_id INTEGER NOT NULL,
+                    profile_id INTEGER NOT NULL REFERENCES profiles(id) ON DELETE CASCADE,
+                    opponent_id INTEGER REFERENCES profiles(id) ON DELETE SET NULL,
+                    subject TEXT NOT NULL,
+                    correct_answers INTEGER NOT NULL,
+                    opponent_correct_answers INTEGER NOT NULL,
+                    total_questions INTEGER NOT NULL,
+                    final_score INTEGER NOT NULL,
+                    outcome TEXT NOT NULL CHECK (outcome IN ('won', 'lost', 'drew')),
+                    played_at DATETIME NOT NULL,
+                    PRIMARY KEY (session_id, profile_id)
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_head_to_head_results_profile ON head_to_head_results(profile_id, played_at);".to_string(),
+            down_sql: Some("DROP TABLE IF EXISTS head_to_head_results;".to_string()),
+        });
+
+        // Migration 54: Wrong parent PINs in a row for each household, and when the PIN may
+        // next be tried, so it can't be guessed by trying every PIN in turn
+        self.add_migration(Migration {
+            version: 54,
+            description: "Add parent PIN lockout".to_string(),
+            up_sql: "ALTER TABLE households ADD COLUMN failed_credential_attempts INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE households ADD COLUMN credential_locked_until DATETIME;".to_string(),
+            down_sql: Some("ALTER TABLE households DROP COLUMN credential_locked_until;
+                ALTER TABLE households DROP COLUMN failed_credential_attempts;".to_string()),
+        });
+    }
+
+    /// SQL rebuilding every table with a key_stage CHECK constraint to allow `key_stages`.
+    /// Must run with foreign keys off, which `apply_migration` and `rollback_migration` ensure.
+    fn rebuild_key_stage_tables_sql(key_stages: &str) -> String {
+        format!(
+            "{questions}
+
+            CREATE TABLE question_of_the_day_rebuild (
+                qotd_date TEXT NOT NULL,
+                key_stage TEXT NOT NULL CHECK (key_stage IN ({key_stages})),
+                question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+                PRIMARY KEY (qotd_date, key_stage)
+            );
+            INSERT INTO question_of_the_day_rebuild SELECT qotd_date, key_stage, question_id FROM question_of_the_day;
+            DROP TABLE question_of_the_day;
+            ALTER TABLE question_of_the_day_rebuild RENAME TO question_of_the_day;
+
+            CREATE TABLE passages_rebuild (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                subject_id INTEGER NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+                key_stage TEXT NOT NULL CHECK (key_stage IN ({key_stages})),
+                title TEXT NOT NULL,
+                text TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO passages_rebuild SELECT id, subject_id, key_stage, title, text, created_at FROM passages;
+            DROP TABLE passages;
+            ALTER TABLE passages_rebuild RENAME TO passages;
+            CREATE INDEX IF NOT EXISTS idx_passages_subject ON passages(subject_id, key_stage);",
+            questions = Self::rebuild_questions_table_sql(key_stages, ORIGINAL_QUESTION_TYPES),
+            key_stages = key_stages
+        )
+    }
+
+    /// SQL deleting the questions matching `condition` and everything that refers to them.
+    /// Rollbacks run with foreign keys off, so nothing cascades by itself.
+    fn delete_questions_sql(condition: &str) -> String {
+        let matching = format!("SELECT id FROM questions WHERE {}", condition);
+        let mut sql = String::new();
+        for table in [
+            "assets", "question_attempts", "passage_questions", "question_of_the_day", "answer_drawings",
+            "scratchpad_entries", "boss_question_attempts", "question_sources", "retired_questions",
+            "answered_questions", "question_flags", "question_households", "mock_paper_questions",
+            "question_reviews",
+        ] {
+            sql.push_str(&format!("DELETE FROM {} WHERE question_id IN ({});\n", table, matching));
+        }
+        sql.push_str(&format!(
+            "UPDATE session_events SET question_id = NULL WHERE question_id IN ({matching});
+            UPDATE spelling_words SET spelling_question_id = NULL WHERE spelling_question_id IN ({matching});
+            UPDATE spelling_words SET fill_blank_question_id = NULL WHERE fill_blank_question_id IN ({matching});
+            DELETE FROM questions WHERE {condition};",
+            matching = matching,
+            condition = condition
+        ));
+        sql
+    }
+
+    /// SQL rebuilding the questions table with the given key_stage and question_type CHECK constraints.
+    /// Dropping the old table drops its triggers, so a rebuild after migration 44 must also run
+    /// `QUESTION_VERSIONS_TRIGGER_SQL`.
+    fn rebuild_questions_table_sql(key_stages: &str, question_types: &str) -> String {
+        format!(
+            "CREATE TABLE questions_rebuild (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                subject_id INTEGER NOT NULL REFERENCES subjects(id) ON DELETE CASCADE,
+                key_stage TEXT NOT NULL CHECK (key_stage IN ({key_stages})),
+                question_type TEXT NOT NULL CHECK (question_type IN ({question_types})),
+                content TEXT NOT NULL,
+                correct_answer TEXT NOT NULL,
+                difficulty_level INTEGER DEFAULT 1 CHECK (difficulty_level BETWEEN 1 AND 5),
+                tags TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO questions_rebuild SELECT id, subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at FROM questions;
+            DROP TABLE questions;
+            ALTER TABLE questions_rebuild RENAME TO questions;
+            CREATE INDEX IF NOT EXISTS idx_questions_subject_stage ON questions(subject_id, key_stage);
+            CREATE INDEX IF NOT EXISTS idx_questions_difficulty ON questions(difficulty_level);
+            CREATE INDEX IF NOT EXISTS idx_questions_composite_fast ON questions(subject_id, key_stage, difficulty_level);
+            CREATE INDEX IF NOT EXISTS idx_questions_type_difficulty ON questions(question_type, difficulty_level);
+            CREATE INDEX IF NOT EXISTS idx_questions_created_at ON questions(created_at);",
+            key_stages = key_stages,
+            question_types = question_types
+        )
     }
 
     fn add_migration(&mut self, migration: Migration) {
@@ -119,40 +1374,76 @@ impl MigrationManager {
     }
 
     fn apply_migration(&self, conn: &Connection, migration: &Migration) -> SqlResult<()> {
-        let tx = conn.unchecked_transaction()?;
+        Self::without_foreign_keys(conn, || {
+            let tx = conn.unchecked_transaction()?;
 
-        // Execute the migration SQL
-        tx.execute_batch(&migration.up_sql)?;
+            // Execute the migration SQL
+            tx.execute_batch(&migration.up_sql)?;
+            Self::check_foreign_keys(&tx)?;
 
-        // Record the migration
-        tx.execute(
-            "INSERT INTO schema_migrations (version, description) VALUES (?1, ?2)",
-            [&migration.version.to_string(), &migration.description],
-        )?;
+            // Record the migration
+            tx.execute(
+                "INSERT INTO schema_migrations (version, description) VALUES (?1, ?2)",
+                [&migration.version.to_string(), &migration.description],
+            )?;
 
-        tx.commit()?;
-        Ok(())
+            tx.commit()
+        })
     }
 
     fn rollback_migration(&self, conn: &Connection, migration: &Migration) -> SqlResult<()> {
         if let Some(down_sql) = &migration.down_sql {
-            let tx = conn.unchecked_transaction()?;
+            Self::without_foreign_keys(conn, || {
+                let tx = conn.unchecked_transaction()?;
 
-            // Execute the rollback SQL
-            tx.execute_batch(down_sql)?;
+                // Execute the rollback SQL
+                tx.execute_batch(down_sql)?;
+                Self::check_foreign_keys(&tx)?;
 
-            // Remove the migration record
-            tx.execute(
-                "DELETE FROM schema_migrations WHERE version = ?1",
-                [&migration.version.to_string()],
-            )?;
+                // Remove the migration record
+                tx.execute(
+                    "DELETE FROM schema_migrations WHERE version = ?1",
+                    [&migration.version.to_string()],
+                )?;
 
-            tx.commit()?;
+                tx.commit()
+            })?;
         }
         Ok(())
     }
 
-    fn get_latest_version(&self) -> u32 {
+    /// Run `f` with foreign key enforcement off, so a table rebuild's DROP TABLE does not
+    /// cascade into child tables. The pragma only takes effect outside a transaction.
+    fn without_foreign_keys<F>(conn: &Connection, f: F) -> SqlResult<()>
+    where
+        F: FnOnce() -> SqlResult<()>,
+    {
+        let enabled: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0))?;
+        if enabled {
+            conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+        }
+
+        let result = f();
+
+        if enabled {
+            conn.execute_batch("PRAGMA foreign_keys = ON")?;
+        }
+        result
+    }
+
+    /// Fail the migration if it left rows pointing at missing parents
+    fn check_foreign_keys(conn: &Connection) -> SqlResult<()> {
+        let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+        if stmt.query([])?.next()?.is_some() {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+                Some("Migration left foreign key violations".to_string()),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn get_latest_version(&self) -> u32 {
         self.migrations.keys().max().copied().unwrap_or(0)
     }
 
@@ -241,4 +1532,38 @@ mod tests {
             assert_eq!(count, 1, "Table {} should exist", table);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_key_stage_migration_keeps_child_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+        let manager = MigrationManager::new();
+
+        manager.migrate_to_version(&conn, 10).unwrap();
+        conn.execute_batch(
+            "INSERT INTO questions (id, subject_id, key_stage, question_type, content, correct_answer)
+                 VALUES (1, (SELECT id FROM subjects WHERE name = 'mathematics'), 'KS2', 'multiple_choice', '{}', '\"1\"');
+             INSERT INTO assets (question_id, asset_type, file_path) VALUES (1, 'image', 'images/one.png');"
+        ).unwrap();
+        assert!(conn.execute(
+            "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer) VALUES (1, 'KS3', 'multiple_choice', '{}', '\"1\"')",
+            [],
+        ).is_err());
+
+        manager.migrate_to_latest(&conn).unwrap();
+        let assets: i32 = conn.query_row("SELECT COUNT(*) FROM assets WHERE question_id = 1", [], |row| row.get(0)).unwrap();
+        assert_eq!(assets, 1);
+        conn.execute(
+            "INSERT INTO questions (id, subject_id, key_stage, question_type, content, correct_answer) VALUES (2, 1, 'KS3', 'multiple_choice', '{}', '\"1\"')",
+            [],
+        ).unwrap();
+        let foreign_keys: bool = conn.query_row("PRAGMA foreign_keys", [], |row| row.get(0)).unwrap();
+        assert!(foreign_keys);
+
+        manager.migrate_to_version(&conn, 10).unwrap();
+        let key_stages: Vec<String> = conn.prepare("SELECT key_stage FROM questions").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(key_stages, vec!["KS2".to_string()]);
+    }
+}
@@ -1,12 +1,17 @@
 pub mod connection;
 pub mod migrations;
+pub mod references;
 
 pub use connection::{DatabaseManager, DatabaseError, DatabaseResult, PoolStats};
 pub use migrations::{Migration, MigrationManager};
+pub use references::row_exists;
 
 use std::path::Path;
 use std::sync::Arc;
 
+/// `PRAGMA auto_vacuum` value for incremental mode
+const AUTO_VACUUM_INCREMENTAL: u32 = 2;
+
 /// Main database service that combines connection management and migrations
 pub struct DatabaseService {
     manager: Arc<DatabaseManager>,
@@ -28,6 +33,13 @@ impl DatabaseService {
     /// Initialize the database by running all pending migrations
     pub fn initialize(&self) -> DatabaseResult<()> {
         self.manager.execute(|conn| {
+            // Incremental vacuuming has to be switched on before it can be used. Databases
+            // created before it was enabled need one full vacuum to change over.
+            let auto_vacuum: u32 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+            if auto_vacuum != AUTO_VACUUM_INCREMENTAL {
+                conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+            }
+
             self.migration_manager.migrate_to_latest(conn)
                 .map_err(|e| rusqlite::Error::SqliteFailure(
                     rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
@@ -55,6 +67,11 @@ impl DatabaseService {
         self.manager.get_pool_stats()
     }
 
+    /// Flush pending write-ahead log entries into the database file
+    pub fn checkpoint(&self) -> DatabaseResult<()> {
+        self.manager.checkpoint()
+    }
+
     /// Close all database connections
     pub fn close(&self) -> DatabaseResult<()> {
         self.manager.close()
@@ -99,4 +116,38 @@ mod tests {
         let version = service.get_version().unwrap();
         assert!(version > 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_scheduled_compaction_shrinks_existing_database() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        // A database created before incremental vacuuming was enabled
+        let conn = rusqlite::Connection::open(&db_path).unwrap();
+        conn.execute_batch("CREATE TABLE history (id INTEGER PRIMARY KEY, data BLOB NOT NULL)").unwrap();
+        drop(conn);
+
+        let service = DatabaseService::new(&db_path).unwrap();
+        service.initialize().unwrap();
+        let manager = service.manager();
+        let page_count = || manager.execute(|conn| conn.query_row("PRAGMA page_count", [], |row| row.get::<_, u32>(0))).unwrap();
+
+        manager.execute(|conn| {
+            for _ in 0..200 {
+                conn.execute("INSERT INTO history (data) VALUES (zeroblob(4096))", [])?;
+            }
+            Ok(())
+        }).unwrap();
+        let before = page_count();
+
+        manager.execute(|conn| conn.execute("DELETE FROM history", [])).unwrap();
+        assert_eq!(manager.run_scheduled_compaction().unwrap(), None);
+        assert_eq!(page_count(), before);
+
+        manager.schedule_compaction();
+        let freed = manager.run_scheduled_compaction().unwrap().unwrap();
+        assert!(freed >= 200);
+        assert!(page_count() < before - 200);
+        assert_eq!(manager.run_scheduled_compaction().unwrap(), None);
+    }
+}
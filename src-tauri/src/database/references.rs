@@ -0,0 +1,67 @@
+use crate::database::{DatabaseManager, DatabaseResult};
+use crate::errors::AppResult;
+use crate::models::EntityId;
+use rusqlite::Connection;
+
+/// Whether the row `id` refers to exists. Takes a connection so it can be checked inside
+/// the same transaction that goes on to reference the row.
+pub fn row_exists<I: EntityId>(conn: &Connection, id: I) -> rusqlite::Result<bool> {
+    conn.query_row(
+        &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?1)", I::TABLE),
+        [id],
+        |row| row.get(0),
+    )
+}
+
+impl DatabaseManager {
+    /// Whether the row `id` refers to exists
+    pub fn exists<I: EntityId>(&self, id: I) -> DatabaseResult<bool> {
+        self.execute(|conn| row_exists(conn, id))
+    }
+
+    /// Fail with the id's not-found error unless its row exists
+    pub fn require<I: EntityId>(&self, id: I) -> AppResult<()> {
+        if self.exists(id)? {
+            Ok(())
+        } else {
+            Err(id.not_found())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::DatabaseService;
+    use crate::errors::AppError;
+    use crate::models::{MixId, ProfileId};
+    use chrono::Utc;
+    use rusqlite::params;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_require_reports_missing_rows_by_type() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let profile_id = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+
+        assert!(db_manager.exists(profile_id).unwrap());
+        db_manager.require(profile_id).unwrap();
+        // The same number names no row in another table
+        assert!(!db_manager.exists(MixId(profile_id.0)).unwrap());
+
+        assert!(matches!(db_manager.require(ProfileId(999)), Err(AppError::ProfileNotFound { id: ProfileId(999) })));
+        match db_manager.require(MixId(999)) {
+            Err(AppError::NotFound(message)) => assert_eq!(message, "Custom mix with id 999 not found"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
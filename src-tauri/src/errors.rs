@@ -1,4 +1,5 @@
 use thiserror::Error;
+use crate::models::ProfileId;
 
 /// Main application error type that encompasses all possible errors
 #[derive(Debug, Error)]
@@ -13,7 +14,7 @@ pub enum AppError {
     ContentVerification(String),
     
     #[error("Profile not found: {id}")]
-    ProfileNotFound { id: u32 },
+    ProfileNotFound { id: ProfileId },
     
     #[error("Invalid question format: {0}")]
     InvalidQuestion(String),
@@ -50,6 +51,25 @@ pub enum AppError {
     
     #[error("Internal error: {0}")]
     Internal(String),
+    
+    #[error("Telemetry submission failed: {0}")]
+    Telemetry(String),
+    
+    #[error("Configuration error: {0}")]
+    Configuration(String),
+    
+    #[error("Email delivery failed: {0}")]
+    Email(String),
+    
+    #[error("Backup failed: {0}")]
+    Backup(String),
+    
+    /// A quiz question failed and was swapped out; the message is meant for the child
+    #[error("{0}")]
+    QuestionReplaced(String),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
 }
 
 /// Result type alias for application operations
@@ -82,6 +102,12 @@ impl AppError {
             AppError::PermissionDenied(_) => false, // Permission errors need intervention
             AppError::Internal(_) => false, // Internal errors usually indicate bugs
             AppError::DatabaseConnection(_) => false, // Database connection errors need intervention
+            AppError::Telemetry(_) => true, // Submission can be retried with the next batch
+            AppError::Configuration(_) => false, // The config file or environment needs fixing
+            AppError::Email(_) => true, // The mail server may be temporarily unreachable
+            AppError::Backup(_) => true, // The backup storage may be temporarily unreachable
+            AppError::QuestionReplaced(_) => true, // The session carries on with another question
+            AppError::Cancelled(_) => true, // The user stopped it and can start it again
         }
     }
     
@@ -104,6 +130,12 @@ impl AppError {
             AppError::PermissionDenied(_) => "auth",
             AppError::Internal(_) => "internal",
             AppError::DatabaseConnection(_) => "database_connection",
+            AppError::Telemetry(_) => "network",
+            AppError::Configuration(_) => "configuration",
+            AppError::Email(_) => "network",
+            AppError::Backup(_) => "backup",
+            AppError::QuestionReplaced(_) => "business_logic",
+            AppError::Cancelled(_) => "cancelled",
         }
     }
 }
@@ -117,7 +149,7 @@ mod tests {
         let recoverable_error = AppError::UpdateFailed("Network timeout".to_string());
         assert!(recoverable_error.is_recoverable());
         
-        let non_recoverable_error = AppError::ProfileNotFound { id: 1 };
+        let non_recoverable_error = AppError::ProfileNotFound { id: ProfileId(1) };
         assert!(!non_recoverable_error.is_recoverable());
     }
 
@@ -132,7 +164,7 @@ mod tests {
 
     #[test]
     fn test_error_conversion_to_string() {
-        let error = AppError::ProfileNotFound { id: 42 };
+        let error = AppError::ProfileNotFound { id: ProfileId(42) };
         let error_string: String = error.into();
         assert!(error_string.contains("Profile not found: 42"));
     }
@@ -2,11 +2,13 @@ pub mod database;
 pub mod models;
 pub mod services;
 pub mod errors;
+pub mod config;
 
 pub use database::{DatabaseService, DatabaseManager, DatabaseError, DatabaseResult};
 pub use models::*;
 pub use services::*;
 pub use errors::{AppError, AppResult};
+pub use config::AppConfig;
 
 // Re-export commonly used types
 pub use rusqlite;
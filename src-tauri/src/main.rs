@@ -5,22 +5,67 @@ use quizdd::{
     DatabaseService, 
     services::{
         QuizEngine, ProfileManager, ContentManager, ContentSeeder, SecurityService, CustomMixManager,
-        UpdateService, UpdateInfo, UpdateConfig,
+        UpdateService, UpdateInfo, FeatureFlagService, FeatureFlag,
+        TelemetryService, TelemetrySettings, TelemetryPayload, CrashReporter, CrashReport, FileLogger,
+        ShutdownCoordinator, LazyService, SeedingProgress, ContentReset,
         ProfileUpdateRequest, QuizResult, QuizConfig, QuizSession, Score, 
-        ContentPack, ContentStatistics, AnswerResult, ParentalChallenge, QuizProgress
+        ContentPack, ContentStatistics, QuestionCacheStats, AnswerResult, HintResult, PassAndPlaySummary, CoopPlayer, CoopSummary, HeadToHeadSummary, ParentalChallenge, QuizProgress,
+        Permission, WindowSessionRegistry, WindowSession, MAIN_WINDOW_LABEL, PARENT_DASHBOARD_LABEL,
+        NotificationService, NotificationKind, NotificationPreferences, NotificationTemplate, PendingNotification,
+        AssignmentManager, ClassroomManager, CalendarExporter,
+        EmailReportService, SmtpSettings, EmailDeliveryStatus, WeeklyDigest, CertificateGenerator,
+        QuestionOfTheDayService, DailyQuestion, DailyQuestionCompletion, PassageManager,
+        AccessibilityService, AccessibilitySettings,
+        VoiceInputService, SpokenAudio, Transcription, VoiceThresholds, VoiceInterpretation,
+        DrawingAnswerService, DrawingSubmission, ScratchpadService, ScratchpadWork, ScratchpadEntry,
+        WellbeingService, BreakSettings, BreakStatus,
+        ApprovalQueue, ApprovalRequest, ApprovalStatus, PendingAction,
+        BossQuestionTracker, BossQuestionStats, PhonicsService, QuizIncident, SLOW_DOWN_MESSAGE,
+        QuizPresetService, QuizPreset, ResultCardGenerator, ResetScope, ProgressReset, HeadToHeadResult,
+        DifficultyCurveService, DifficultyCurve, MarkdownImporter, MarkdownImportReport,
+        ContentInbox, InboxReport, INBOX_SETTLE_TIME, QuestionSourceSummary, QuestionRemoval,
+        RetiredQuestion, RETIRED_QUESTION_RETENTION_DAYS, AssetPipeline, AssetIntegrityChecker, AssetIntegrityReport,
+        AudioPromptService, SpellingListService, SpellingList, CreateSpellingListRequest,
+        PracticeDay, SpellingAttemptResult, SpellingReadiness, CurriculumCoverageService, CoverageReport,
+        SessionReplay, StorageManager, StorageLocations, StorageUsage, StorageSettings, CleanupReport, DatabaseBackup,
+        CloudBackupService, CloudBackupSettings, CloudBackupStatus, CloudBackup,
+        RestoreService, RestoreSelection, RestorePreview, BackupSource, apply_pending_restore, RESTORE_DIRECTORY,
+        HistoryRetentionService, RetentionSettings, RetentionReport, SelectionAudit,
+        ArithmeticProblem, Distractor, generate_distractors,
+        QuestionQualityService, QuestionQuality, PhrasingStats,
+        ApiTokenService, ApiToken, ApiTokenScope, CreatedApiToken,
+        DiagnosticsService, AppDiagnostics, UpdateStatus,
+        SupportBundleExporter, SupportBundleSummary,
+        OnboardingService, OnboardingState,
+        HouseholdManager, Household,
+        MockPaperGenerator, MockPaper, PaperBlueprint,
+        ReadingAgeService, ReadingAgeEstimate,
+        ReviewScheduler, QuestionReview,
+        MultiplicationCheckService, MultiplicationCheck, CheckQuestion, CheckAnswerReceipt, CheckResult, CheckReadiness,
+        PhonicsScreeningService, PhonicsScreening, ScreeningResult, GraphemeAccuracy,
+        ExploreService, ExploreTopic, ExploreItem, RevealedAnswer, PracticeLaterItem,
+        ContentChangeMonitor, ContentChangeAlert, QuestionVersion, FormattingService, CurriculumService,
+        MistakesBank, MistakeItem, MISTAKES_QUIZ_QUESTIONS, AchievementManager, Badge
     }
 };
 use std::sync::{Arc, Mutex};
-use tauri::{State, Manager};
+use tauri::{State, Manager, CustomMenuItem, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 // Import models and types
 use quizdd::models::{
-    Question, Answer, Profile, CreateProfileRequest, Progress, Subject,
-    KeyStage, CustomMix, CreateMixRequest, UpdateMixRequest, MixConfig
+    Question, QuestionSource, Asset, AssetType, AudioPrompt, Answer, Profile, CreateProfileRequest, Progress, HouseholdStatistics, Subject,
+    KeyStage, CustomMix, CreateMixRequest, UpdateMixRequest, MixConfig,
+    Assignment, CreateAssignmentRequest, AssignmentSummary,
+    ClassGroup, CreateClassRequest, RosterImportResult, ClassReport,
+    ProfileCsvColumns, ProfileImportPreview, ProfileSchoolDetails,
+    Passage, CreatePassageRequest, PassageGroup, PassageBlock, Locale, Curriculum, CurriculumLevel, Achievement, DailyStreak, LevelInfo,
+    ProfileId, QuestionId, SessionId, MixId
 };
-use quizdd::errors::AppResult;
+use quizdd::errors::{AppError, AppResult};
+use quizdd::config::{AppConfig, portable_data_dir};
+use quizdd::services::feature_flags::flags;
 
 // Application state that will be managed by Tauri
 pub struct AppState {
@@ -28,10 +73,66 @@ pub struct AppState {
     pub quiz_engine: Arc<Mutex<QuizEngine>>,
     pub profile_manager: Arc<ProfileManager>,
     pub content_manager: Arc<ContentManager>,
-    pub content_seeder: Arc<ContentSeeder>,
+    pub content_seeder: LazyService<ContentSeeder>,
     pub security_service: Arc<SecurityService>,
     pub custom_mix_manager: Arc<CustomMixManager>,
-    pub update_service: Arc<UpdateService>,
+    pub update_service: LazyService<UpdateService>,
+    pub feature_flags: Arc<FeatureFlagService>,
+    pub telemetry: Arc<TelemetryService>,
+    pub crash_reporter: Arc<CrashReporter>,
+    pub shutdown: Arc<ShutdownCoordinator>,
+    pub seeding_progress: Arc<Mutex<Option<SeedingProgress>>>,
+    pub window_sessions: Arc<WindowSessionRegistry>,
+    pub notifications: Arc<NotificationService>,
+    pub assignment_manager: Arc<AssignmentManager>,
+    pub classroom_manager: Arc<ClassroomManager>,
+    pub calendar_exporter: Arc<CalendarExporter>,
+    pub email_reports: Arc<EmailReportService>,
+    pub certificates: Arc<CertificateGenerator>,
+    pub mock_papers: Arc<MockPaperGenerator>,
+    pub reading_age: Arc<ReadingAgeService>,
+    pub review_scheduler: Arc<ReviewScheduler>,
+    pub multiplication_checks: Arc<MultiplicationCheckService>,
+    pub result_cards: Arc<ResultCardGenerator>,
+    pub question_of_the_day: Arc<QuestionOfTheDayService>,
+    pub passage_manager: Arc<PassageManager>,
+    pub accessibility: Arc<AccessibilityService>,
+    pub voice_input: Arc<VoiceInputService>,
+    pub drawing_answers: Arc<DrawingAnswerService>,
+    pub scratchpads: Arc<ScratchpadService>,
+    pub wellbeing: Arc<WellbeingService>,
+    pub approval_queue: Arc<ApprovalQueue>,
+    pub boss_questions: Arc<BossQuestionTracker>,
+    pub phonics: Arc<PhonicsService>,
+    pub phonics_screenings: Arc<PhonicsScreeningService>,
+    pub explore: Arc<ExploreService>,
+    pub mistakes: Arc<MistakesBank>,
+    pub achievements: Arc<AchievementManager>,
+    pub content_changes: Arc<ContentChangeMonitor>,
+    pub quiz_presets: Arc<QuizPresetService>,
+    pub difficulty_curves: Arc<DifficultyCurveService>,
+    pub markdown_importer: Arc<MarkdownImporter>,
+    pub content_inbox: Arc<ContentInbox>,
+    pub asset_pipeline: Arc<AssetPipeline>,
+    pub asset_integrity: Arc<AssetIntegrityChecker>,
+    pub audio_prompts: Arc<AudioPromptService>,
+    pub spelling_lists: Arc<SpellingListService>,
+    pub curriculum_coverage: Arc<CurriculumCoverageService>,
+    pub storage: Arc<StorageManager>,
+    pub cloud_backup: Arc<CloudBackupService>,
+    pub restore: Arc<RestoreService>,
+    pub history_retention: Arc<HistoryRetentionService>,
+    pub formatting: Arc<FormattingService>,
+    pub curriculum: Arc<CurriculumService>,
+    pub question_quality: Arc<QuestionQualityService>,
+    pub api_tokens: Arc<ApiTokenService>,
+    pub diagnostics: Arc<DiagnosticsService>,
+    pub support_bundle: Arc<SupportBundleExporter>,
+    pub onboarding: Arc<OnboardingService>,
+    pub households: Arc<HouseholdManager>,
+    /// Where the frontend's app settings are saved, inside the data directory so portable
+    /// installs keep them on the drive
+    pub settings_path: std::path::PathBuf,
 }
 
 impl AppState {
@@ -39,55 +140,262 @@ impl AppState {
         database_service: DatabaseService,
         content_directory: std::path::PathBuf,
         app_data_dir: std::path::PathBuf,
+        crash_directory: std::path::PathBuf,
+        config: &AppConfig,
     ) -> AppResult<Self> {
         println!("🏗️ AppState::new - Getting database manager...");
         let db_manager = database_service.manager();
         
         println!("🔒 AppState::new - Creating security service...");
-        let security_service = Arc::new(SecurityService::new()?);
+        // Shared by every service so key material and session tokens stay consistent
+        let session_key = SecurityService::load_or_create_session_key(&app_data_dir.join("session.key"))?;
+        let security_service = Arc::new(SecurityService::with_session_key(session_key)?);
         
         println!("👤 AppState::new - Creating profile manager...");
         let profile_manager = Arc::new(ProfileManager::new(
             db_manager.clone(),
-            SecurityService::new()?,
+            security_service.clone(),
         ));
         
+        println!("🧭 AppState::new - Creating onboarding service...");
+        let onboarding = Arc::new(OnboardingService::new(db_manager.clone(), profile_manager.clone()));
+        
+        println!("🏠 AppState::new - Creating household manager...");
+        let households = Arc::new(HouseholdManager::new(db_manager.clone()));
+        security_service.set_household(households.active_household_id()?);
+        
         println!("📚 AppState::new - Creating content manager...");
+        let inbox_directory = content_directory.join("inbox");
+        let asset_directory = content_directory.join("assets");
+        let pack_archive_directory = content_directory.join("packs");
         let content_manager = Arc::new(ContentManager::new(
             db_manager.clone(),
-            SecurityService::new()?,
-            content_directory,
+            security_service.clone(),
+            content_directory.clone(),
         ));
         
+        println!("🚩 AppState::new - Creating feature flag service...");
+        let feature_flags = Arc::new(
+            FeatureFlagService::new(db_manager.clone()).with_defaults(&config.features)
+        );
+
         println!("🎯 AppState::new - Creating quiz engine...");
         let quiz_engine = Arc::new(Mutex::new(QuizEngine::new(
             db_manager.clone(),
             content_manager.clone(),
-        )));
+        ).with_feature_flags(feature_flags.clone())));
 
         println!("🎨 AppState::new - Creating custom mix manager...");
         let custom_mix_manager = Arc::new(CustomMixManager::new(db_manager.clone()));
         
-        println!("🔄 AppState::new - Creating update service...");
-        // Create update service with default configuration
-        let update_config = UpdateConfig {
-            repository_urls: vec![
-                "https://updates.educationalquizapp.com".to_string(),
-                "https://content.educationalquizapp.com".to_string(),
-            ],
-            auto_check: false,
-            check_interval_hours: 24,
-            backup_retention_days: 7,
+        let calendar_directory = app_data_dir.join("calendars");
+        let restore_directory = app_data_dir.join(RESTORE_DIRECTORY);
+        let settings_path = app_data_dir.join("settings.json");
+        let certificate_directory = app_data_dir.join("certificates");
+        let mock_paper_directory = app_data_dir.join("mock_papers");
+        let result_card_directory = app_data_dir.join("result_cards");
+        let phonics_audio_directory = app_data_dir.join("phonics_audio");
+        let storage_locations = StorageLocations {
+            asset_directory: asset_directory.clone(),
+            backup_directory: app_data_dir.join("backups"),
+            log_directory: crash_directory.clone(),
         };
+
+        // Heavyweight services are created on first use to keep them off the startup path
+        let update_security_service = security_service.clone();
+        let update_config = config.update_config();
+        let update_service = LazyService::new("update service", move || {
+            UpdateService::new(
+                update_security_service.clone(),
+                update_config.clone(),
+                app_data_dir.clone(),
+            )
+        });
         
-        let update_service = Arc::new(UpdateService::new(
-            SecurityService::new()?,
-            update_config,
-            app_data_dir,
+        let seeder_db_manager = db_manager.clone();
+        let seeder_content_manager = content_manager.clone();
+        let content_seeder = LazyService::new("content seeder", move || {
+            Ok(ContentSeeder::new(seeder_db_manager.clone()).with_content_manager(seeder_content_manager.clone()))
+        });
+
+        println!("📊 AppState::new - Creating telemetry service...");
+        let telemetry = Arc::new(TelemetryService::new(db_manager.clone(), config.network.proxy.as_deref())?);
+
+        println!("💥 AppState::new - Creating crash reporter...");
+        let crash_reporter = Arc::new(CrashReporter::new(crash_directory.clone(), config.network.proxy.as_deref())?);
+
+        println!("🪟 AppState::new - Creating window session registry...");
+        let window_sessions = Arc::new(WindowSessionRegistry::new(security_service.clone()));
+
+        println!("📝 AppState::new - Creating assignment manager...");
+        let assignment_manager = Arc::new(AssignmentManager::new(db_manager.clone()));
+
+        println!("🏫 AppState::new - Creating classroom manager...");
+        let classroom_manager = Arc::new(ClassroomManager::new(
+            db_manager.clone(),
+            profile_manager.clone(),
+            assignment_manager.clone(),
+        ));
+
+        println!("🔔 AppState::new - Creating notification service...");
+        let notifications = Arc::new(NotificationService::new(db_manager.clone()));
+
+        println!("📅 AppState::new - Creating calendar exporter...");
+        let calendar_exporter = Arc::new(CalendarExporter::new(
+            profile_manager.clone(),
+            assignment_manager.clone(),
+            notifications.clone(),
+            calendar_directory,
+        ));
+
+        println!("📖 AppState::new - Creating reading age service...");
+        let reading_age = Arc::new(ReadingAgeService::new(db_manager.clone()));
+
+        println!("🔁 AppState::new - Creating review scheduler...");
+        let review_scheduler = Arc::new(ReviewScheduler::new(db_manager.clone()));
+
+        println!("✖️ AppState::new - Creating multiplication check service...");
+        let multiplication_checks = Arc::new(MultiplicationCheckService::new(db_manager.clone()));
+
+        println!("🌍 AppState::new - Creating formatting service...");
+        let formatting = Arc::new(FormattingService::new(db_manager.clone()));
+
+        println!("🏫 AppState::new - Creating curriculum service...");
+        let curriculum = Arc::new(CurriculumService::new(db_manager.clone()));
+
+        println!("📧 AppState::new - Creating email report service...");
+        let email_reports = Arc::new(EmailReportService::new(
+            db_manager.clone(),
+            profile_manager.clone(),
+            assignment_manager.clone(),
+            reading_age.clone(),
+        ));
+
+        println!("🏅 AppState::new - Creating certificate generator...");
+        let certificates = Arc::new(CertificateGenerator::new(profile_manager.clone(), formatting.clone(), certificate_directory));
+
+        println!("📝 AppState::new - Creating mock paper generator...");
+        let mock_papers = Arc::new(MockPaperGenerator::new(db_manager.clone(), content_manager.clone(), mock_paper_directory));
+
+        println!("🖼️ AppState::new - Creating result card generator...");
+        let result_cards = Arc::new(ResultCardGenerator::new(content_manager.clone(), result_card_directory));
+
+        println!("🌞 AppState::new - Creating question of the day service...");
+        let question_of_the_day = Arc::new(QuestionOfTheDayService::new(db_manager.clone(), content_manager.clone()));
+
+        println!("📖 AppState::new - Creating passage manager...");
+        let passage_manager = Arc::new(PassageManager::new(db_manager.clone(), content_manager.clone()));
+
+        println!("🔤 AppState::new - Creating accessibility service...");
+        let accessibility = Arc::new(AccessibilityService::new(db_manager.clone(), content_manager.clone()));
+
+        println!("🎙️ AppState::new - Creating voice input service...");
+        let voice_input = Arc::new(VoiceInputService::new(content_manager.clone()));
+
+        println!("🔊 AppState::new - Creating phonics service...");
+        let phonics = Arc::new(PhonicsService::new(content_manager.clone(), phonics_audio_directory));
+
+        println!("👽 AppState::new - Creating phonics screening service...");
+        let phonics_screenings = Arc::new(PhonicsScreeningService::new(db_manager.clone(), phonics.clone())?);
+
+        println!("🧭 AppState::new - Creating explore service...");
+        let explore = Arc::new(ExploreService::new(db_manager.clone(), content_manager.clone()));
+
+        println!("❌ AppState::new - Creating mistakes bank...");
+        let mistakes = Arc::new(MistakesBank::new(db_manager.clone(), content_manager.clone()));
+
+        println!("🏆 AppState::new - Creating achievement manager...");
+        let achievements = Arc::new(AchievementManager::new(db_manager.clone()));
+
+        println!("🕵️ AppState::new - Creating content change monitor...");
+        let content_changes = Arc::new(ContentChangeMonitor::new(db_manager.clone(), content_manager.clone()));
+
+        println!("✏️ AppState::new - Creating drawing answer service...");
+        let drawing_answers = Arc::new(DrawingAnswerService::new(db_manager.clone()));
+
+        println!("📝 AppState::new - Creating scratchpad service...");
+        let scratchpads = Arc::new(ScratchpadService::new(db_manager.clone()));
+
+        println!("🧘 AppState::new - Creating wellbeing service...");
+        let wellbeing = Arc::new(WellbeingService::new(db_manager.clone()));
+
+        println!("👑 AppState::new - Creating boss question tracker...");
+        let boss_questions = Arc::new(BossQuestionTracker::new(db_manager.clone()));
+
+        println!("⚡ AppState::new - Creating quiz preset service...");
+        let quiz_presets = Arc::new(QuizPresetService::new(db_manager.clone()));
+
+        println!("📈 AppState::new - Creating difficulty curve service...");
+        let difficulty_curves = Arc::new(DifficultyCurveService::new(db_manager.clone()));
+
+        println!("📝 AppState::new - Creating Markdown importer...");
+        let markdown_importer = Arc::new(MarkdownImporter::new(content_manager.clone()));
+
+        println!("📥 AppState::new - Creating content inbox...");
+        let content_inbox = Arc::new(ContentInbox::new(content_manager.clone(), markdown_importer.clone(), inbox_directory));
+
+        println!("🖼️ AppState::new - Creating asset pipeline...");
+        let asset_pipeline = Arc::new(AssetPipeline::new(asset_directory));
+
+        println!("🎤 AppState::new - Creating audio prompt service...");
+        let audio_prompts = Arc::new(AudioPromptService::new(content_manager.clone(), asset_pipeline.clone()));
+
+        println!("🧾 AppState::new - Creating asset integrity checker...");
+        let asset_integrity = Arc::new(AssetIntegrityChecker::new(
+            db_manager.clone(),
+            content_manager.clone(),
+            content_directory,
+            pack_archive_directory,
+        ));
+
+        println!("🔤 AppState::new - Creating spelling list service...");
+        let spelling_lists = Arc::new(SpellingListService::new(db_manager.clone(), content_manager.clone()));
+
+        println!("🧭 AppState::new - Creating curriculum coverage service...");
+        let curriculum_coverage = Arc::new(CurriculumCoverageService::new(db_manager.clone(), custom_mix_manager.clone()));
+
+        println!("💽 AppState::new - Creating storage manager...");
+        let storage = Arc::new(StorageManager::new(db_manager.clone(), storage_locations));
+
+        println!("☁️ AppState::new - Creating cloud backup service...");
+        let cloud_backup = Arc::new(CloudBackupService::new(
+            db_manager.clone(),
+            restore_directory.clone(),
+            config.network.proxy.as_deref(),
         )?);
-        
-        println!("🌱 AppState::new - Creating content seeder...");
-        let content_seeder = Arc::new(ContentSeeder::new(db_manager.clone()));
+
+        println!("♻️ AppState::new - Creating restore service...");
+        let restore = Arc::new(RestoreService::new(db_manager.clone(), restore_directory));
+
+        println!("🗄️ AppState::new - Creating history retention service...");
+        let history_retention = Arc::new(HistoryRetentionService::new(db_manager.clone()));
+
+        println!("🩺 AppState::new - Creating question quality service...");
+        let question_quality = Arc::new(QuestionQualityService::new(db_manager.clone()));
+
+        println!("🔑 AppState::new - Creating API token service...");
+        let api_tokens = Arc::new(ApiTokenService::new(db_manager.clone()));
+
+        println!("🩻 AppState::new - Creating diagnostics service...");
+        let diagnostics = Arc::new(DiagnosticsService::new(
+            db_manager.clone(),
+            content_manager.clone(),
+            storage.clone(),
+            crash_reporter.clone(),
+        ));
+
+        println!("🧳 AppState::new - Creating support bundle exporter...");
+        let support_bundle = Arc::new(SupportBundleExporter::new(db_manager.clone(), crash_directory, config.clone()));
+
+        println!("🗳️ AppState::new - Creating approval queue...");
+        let approval_queue = Arc::new(ApprovalQueue::new(
+            db_manager.clone(),
+            custom_mix_manager.clone(),
+            profile_manager.clone(),
+        ));
+
+        println!("🛑 AppState::new - Creating shutdown coordinator...");
+        let shutdown = Arc::new(ShutdownCoordinator::new());
 
         println!("✅ AppState::new - All services created, assembling state...");
         Ok(Self {
@@ -99,8 +407,98 @@ impl AppState {
             security_service,
             custom_mix_manager,
             update_service,
+            feature_flags,
+            telemetry,
+            crash_reporter,
+            shutdown,
+            seeding_progress: Arc::new(Mutex::new(None)),
+            window_sessions,
+            notifications,
+            assignment_manager,
+            classroom_manager,
+            calendar_exporter,
+            email_reports,
+            certificates,
+            mock_papers,
+            reading_age,
+            review_scheduler,
+            multiplication_checks,
+            result_cards,
+            question_of_the_day,
+            passage_manager,
+            accessibility,
+            voice_input,
+            drawing_answers,
+            scratchpads,
+            wellbeing,
+            approval_queue,
+            boss_questions,
+            phonics,
+            phonics_screenings,
+            explore,
+            mistakes,
+            achievements,
+            content_changes,
+            quiz_presets,
+            difficulty_curves,
+            markdown_importer,
+            content_inbox,
+            asset_pipeline,
+            asset_integrity,
+            audio_prompts,
+            spelling_lists,
+            curriculum_coverage,
+            storage,
+            cloud_backup,
+            restore,
+            history_retention,
+            formatting,
+            curriculum,
+            question_quality,
+            api_tokens,
+            diagnostics,
+            support_bundle,
+            onboarding,
+            households,
+            settings_path,
         })
     }
+
+    /// Register the steps run on window close or OS shutdown, in order:
+    /// flush in-flight quiz sessions, record the clean exit, then flush and close the database.
+    pub fn register_shutdown_hooks(&self, session_snapshot_path: std::path::PathBuf) {
+        let quiz_engine = self.quiz_engine.clone();
+        self.shutdown.register_hook("flush_quiz_sessions", move || {
+            let quiz_engine = quiz_engine.lock()
+                .map_err(|e| AppError::Internal(format!("Lock error: {}", e)))?;
+            let saved = quiz_engine.save_sessions_snapshot(&session_snapshot_path)?;
+            println!("💾 Saved {} in-flight quiz session(s)", saved);
+            Ok(())
+        });
+
+        let telemetry = self.telemetry.clone();
+        self.shutdown.register_hook("record_clean_exit", move || {
+            telemetry.record_session_end();
+            Ok(())
+        });
+
+        let database = self.database.clone();
+        self.shutdown.register_hook("close_database", move || {
+            database.checkpoint()?;
+            database.close()?;
+            Ok(())
+        });
+    }
+}
+
+/// Reject the command unless `session_token` grants at least `permission`.
+/// Must be the first statement of every command that is not open to the child UI.
+macro_rules! require_permission {
+    ($state:expr, $permission:expr, $session_token:expr) => {
+        $state.security_service
+            .authorize($permission, &$session_token)
+            .map_err(|e| e.to_string())?
+    };
 }
 
 // ============================================================================
@@ -113,6 +511,9 @@ pub struct GetQuestionsRequest {
     pub key_stage: KeyStage,
     pub count: usize,
     pub difficulty_range: Option<(u8, u8)>,
+    /// Profile the questions are for, so their difficulty curve applies
+    #[serde(default)]
+    pub profile_id: Option<ProfileId>,
 }
 
 #[tauri::command]
@@ -120,62 +521,491 @@ async fn get_questions(
     state: State<'_, AppState>,
     request: GetQuestionsRequest,
 ) -> Result<Vec<Question>, String> {
+    let (key_stage, difficulty_range) = match request.profile_id {
+        Some(profile_id) => match state.difficulty_curves.get_curve(profile_id, &request.subject)
+            .map_err(|e| e.to_string())?
+        {
+            Some(curve) => (curve.key_stage, curve.difficulty_range.or(request.difficulty_range)),
+            None => (request.key_stage, request.difficulty_range),
+        },
+        None => (request.key_stage, request.difficulty_range),
+    };
+
     let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
     
     quiz_engine.get_questions(
         &request.subject,
-        request.key_stage,
+        key_stage,
         request.count,
-        request.difficulty_range,
+        difficulty_range,
     ).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn validate_answer(
     state: State<'_, AppState>,
-    question_id: u32,
+    question_id: QuestionId,
     submitted_answer: Answer,
 ) -> Result<AnswerResult, String> {
+    let submitted_answer = state.drawing_answers.prepare_answer(submitted_answer)
+        .map_err(|e| e.to_string())?;
     let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
     
     quiz_engine.validate_answer(question_id, submitted_answer)
         .map_err(|e| e.to_string())
 }
 
+/// Safely evaluate an arithmetic expression, for generated questions and their distractors
+#[tauri::command]
+async fn evaluate_expression(
+    expression: String,
+) -> Result<f64, String> {
+    quizdd::services::expression::evaluate(&expression)
+        .map_err(|e| e.to_string())
+}
+
+/// Suggest plausible wrong options for an arithmetic question while it's being written
+#[tauri::command]
+async fn suggest_distractors(
+    question_text: String,
+    difficulty: u8,
+    count: Option<usize>,
+) -> Result<Vec<Distractor>, String> {
+    let problem = ArithmeticProblem::parse(&question_text)
+        .ok_or_else(|| "No whole-number sum found in the question".to_string())?;
+    generate_distractors(&problem, difficulty, count.unwrap_or(3).min(10))
+        .ok_or_else(|| "The sum doesn't have a whole-number answer".to_string())
+}
+
 #[tauri::command]
 async fn start_quiz_session(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-    profile_id: u32,
+    profile_id: ProfileId,
     config: QuizConfig,
 ) -> Result<QuizSession, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("quiz_session");
+    state.telemetry.record_content_pack_usage(&config.subject);
+
+    let mut config = config;
+    state.difficulty_curves.adjust_config(profile_id, &mut config)
+        .map_err(|e| e.to_string())?;
+
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    accessibility.adjust_config(&mut config);
+
     let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
     
-    quiz_engine.start_quiz_session(profile_id, config)
+    let mut session = quiz_engine.start_quiz_session(profile_id, config)
+        .map_err(|e| e.to_string())?;
+    accessibility.transform_session(&mut session);
+    drop(quiz_engine);
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(session)
+}
+
+/// Start a session of only the questions due for spaced-repetition review
+#[tauri::command]
+async fn start_review_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    question_count: Option<usize>,
+) -> Result<QuizSession, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("review_session");
+
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut session = quiz_engine.start_review_session(profile_id, question_count.unwrap_or(20))
+        .map_err(|e| e.to_string())?;
+    accessibility.transform_session(&mut session);
+    drop(quiz_engine);
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(session)
+}
+
+/// Questions due for review, most overdue first
+#[tauri::command]
+async fn get_due_review_questions(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    limit: Option<usize>,
+) -> Result<Vec<Question>, String> {
+    let reviews = state.review_scheduler.get_due_reviews(profile_id, limit.unwrap_or(20))
+        .map_err(|e| e.to_string())?;
+    reviews.iter()
+        .map(|review| state.content_manager.get_question_by_id(review.question_id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// Record how well a question was recalled (0-5) outside a quiz, e.g. a flashcard
+#[tauri::command]
+async fn record_review_result(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    question_id: QuestionId,
+    quality: u8,
+) -> Result<QuestionReview, String> {
+    state.review_scheduler.record_review_result(profile_id, question_id, quality)
+        .map_err(|e| e.to_string())
+}
+
+/// Start a Multiplication Tables Check practice run
+#[tauri::command]
+async fn start_multiplication_check(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<MultiplicationCheck, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("multiplication_check");
+
+    let check = state.multiplication_checks.start_check(profile_id)
+        .map_err(|e| e.to_string())?;
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(check)
+}
+
+/// Show the next question of a check and start its six seconds. None once it's over.
+#[tauri::command]
+async fn next_multiplication_check_question(
+    state: State<'_, AppState>,
+    check_id: u32,
+) -> Result<Option<CheckQuestion>, String> {
+    state.multiplication_checks.next_question(check_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn submit_multiplication_check_answer(
+    state: State<'_, AppState>,
+    check_id: u32,
+    answer: String,
+) -> Result<CheckAnswerReceipt, String> {
+    state.multiplication_checks.submit_answer(check_id, &answer)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_multiplication_check_result(
+    state: State<'_, AppState>,
+    check_id: u32,
+) -> Result<CheckResult, String> {
+    state.multiplication_checks.get_result(check_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_multiplication_check_readiness(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<CheckReadiness, String> {
+    state.multiplication_checks.get_readiness(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Start a timed mock test. Unlike a quiz it can't be paused, its questions are fixed at
+/// the start, and it isn't adjusted to the child's recent results.
+#[tauri::command]
+async fn start_assessment_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    config: QuizConfig,
+) -> Result<QuizSession, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("assessment_session");
+    state.telemetry.record_content_pack_usage(&config.subject);
+
+    let mut config = config;
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    accessibility.adjust_config(&mut config);
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut session = quiz_engine.start_assessment_session(profile_id, config)
+        .map_err(|e| e.to_string())?;
+    accessibility.transform_session(&mut session);
+    drop(quiz_engine);
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(session)
+}
+
+/// Start a pass-and-play quiz for siblings taking turns on one device. `question_count` in
+/// the config is per player.
+#[tauri::command]
+async fn start_pass_and_play_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    players: Vec<ProfileId>,
+    config: QuizConfig,
+) -> Result<QuizSession, String> {
+    for &player in &players {
+        state.wellbeing.check_can_start(player, chrono::Utc::now())
+            .map_err(|e| e.to_string())?;
+    }
+
+    state.telemetry.record_feature_usage("pass_and_play_session");
+    state.telemetry.record_content_pack_usage(&config.subject);
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    // Each player's accessibility settings are applied to their own questions as they're shown
+    let session = quiz_engine.start_pass_and_play_session(players, config)
+        .map_err(|e| e.to_string())?;
+    drop(quiz_engine);
+
+    for &player in &session.players {
+        record_wellbeing_activity(&app, &state.wellbeing, player);
+    }
+    Ok(session)
+}
+
+/// Each player's score from a pass-and-play quiz and how they did together
+#[tauri::command]
+async fn get_pass_and_play_summary(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<PassAndPlaySummary, String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    quiz_engine.get_pass_and_play_summary(session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Start a head-to-head quiz where two players on one device answer the same questions in
+/// turn. `question_count` in the config is how many they both answer.
+#[tauri::command]
+async fn start_head_to_head_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    players: [ProfileId; 2],
+    config: QuizConfig,
+) -> Result<QuizSession, String> {
+    for &player in &players {
+        state.wellbeing.check_can_start(player, chrono::Utc::now())
+            .map_err(|e| e.to_string())?;
+    }
+
+    state.telemetry.record_feature_usage("head_to_head_session");
+    state.telemetry.record_content_pack_usage(&config.subject);
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    // As in pass-and-play, each player's accessibility settings are applied as their turn comes
+    let session = quiz_engine.start_head_to_head_session(players, config)
+        .map_err(|e| e.to_string())?;
+    drop(quiz_engine);
+
+    for &player in &session.players {
+        record_wellbeing_activity(&app, &state.wellbeing, player);
+    }
+    Ok(session)
+}
+
+/// The result of a finished head-to-head quiz. The first call saves it in both players'
+/// records and counts each player's answers towards their own progress.
+#[tauri::command]
+async fn finish_head_to_head_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<HeadToHeadSummary, String> {
+    let (summary, results) = {
+        let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let summary = quiz_engine.get_head_to_head_summary(session_id).map_err(|e| e.to_string())?;
+        let results = summary.players.iter()
+            .map(|player| quiz_engine.get_player_quiz_result(session_id, player.profile_id).map(|result| (player.profile_id, result)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        (summary, results)
+    };
+
+    let subject = results.first().map(|(_, result)| result.subject.clone()).unwrap_or_default();
+    let newly_recorded = state.profile_manager.record_head_to_head(&summary, &subject)
+        .map_err(|e| e.to_string())?;
+    if newly_recorded {
+        for (profile_id, result) in results {
+            record_quiz_progress(&app, &state, profile_id, result)?;
+        }
+    }
+    Ok(summary)
+}
+
+/// A profile's head-to-head results, newest first
+#[tauri::command]
+async fn get_head_to_head_results(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    limit: Option<u32>,
+) -> Result<Vec<HeadToHeadResult>, String> {
+    state.profile_manager.get_head_to_head_results(profile_id, limit.unwrap_or(20))
+        .map_err(|e| e.to_string())
+}
+
+/// Start a co-op quiz where family members take turns, each at their own key stage, towards
+/// one shared target. `question_count` in the config is per player.
+#[tauri::command]
+async fn start_coop_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    players: Vec<CoopPlayer>,
+    config: QuizConfig,
+    target: Option<u32>,
+) -> Result<QuizSession, String> {
+    for player in &players {
+        state.wellbeing.check_can_start(player.profile_id, chrono::Utc::now())
+            .map_err(|e| e.to_string())?;
+    }
+
+    state.telemetry.record_feature_usage("coop_session");
+    state.telemetry.record_content_pack_usage(&config.subject);
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    // As in pass-and-play, each player's accessibility settings are applied as their questions are shown
+    let session = quiz_engine.start_coop_session(players, config, target)
+        .map_err(|e| e.to_string())?;
+    drop(quiz_engine);
+
+    for &player in &session.players {
+        record_wellbeing_activity(&app, &state.wellbeing, player);
+    }
+    Ok(session)
+}
+
+/// A co-op team's shared score against its target, and what each player added to it
+#[tauri::command]
+async fn get_coop_summary(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<CoopSummary, String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    quiz_engine.get_coop_summary(session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Tell the backend the quiz window lost (`left`) or regained focus, so an assessment's
+/// integrity notes record the child leaving it
+#[tauri::command]
+async fn record_session_focus(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+    left: bool,
+) -> Result<(), String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    quiz_engine.record_focus_change(session_id, left)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn submit_answer(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-    session_id: u32,
+    session_id: SessionId,
     answer: Answer,
     time_taken_seconds: u32,
 ) -> Result<AnswerResult, String> {
+    let answer = state.drawing_answers.prepare_answer(answer)
+        .map_err(|e| e.to_string())?;
+    let submitted = answer.clone();
+
     let mut quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
     
-    quiz_engine.submit_answer(session_id, answer, time_taken_seconds)
-        .map_err(|e| e.to_string())
+    let result = quiz_engine.submit_answer(session_id, answer, time_taken_seconds)
+        .map_err(|e| e.to_string())?;
+    
+    notify_quiz_activity(&app, &quiz_engine, session_id, &result);
+    record_answer(&app, &state, &quiz_engine, session_id, &submitted, &result)?;
+    
+    Ok(result)
+}
+
+/// Add a submitted answer to the answering profile's history, however it was given
+fn record_answer(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    quiz_engine: &QuizEngine,
+    session_id: SessionId,
+    submitted: &Answer,
+    result: &AnswerResult,
+) -> Result<(), String> {
+    // Sandbox sessions are a parent trying questions out, so leave every profile's history alone
+    let profile_id = match quiz_engine.get_recording_profile_id(session_id)
+        .map_err(|e| e.to_string())?
+    {
+        Some(profile_id) => profile_id,
+        None => return Ok(()),
+    };
+    // In pass-and-play, the answer belongs to whoever's turn it was
+    let profile_id = result.answered_by.unwrap_or(profile_id);
+    record_wellbeing_activity(app, &state.wellbeing, profile_id);
+
+    // A wrong go at a practice question isn't a failure: the child just tries again
+    if result.try_again {
+        return Ok(());
+    }
+
+    // Keep drawn answers so a parent can look at the handwriting later
+    if let Answer::Drawing(drawing) = submitted {
+        if let Err(e) = state.drawing_answers.record(profile_id, Some(session_id), result.question_id, drawing, result.is_correct) {
+            eprintln!("Warning: Failed to save drawn answer: {}", e);
+        }
+    }
+    if let Err(e) = state.scratchpads.attach_answer(profile_id, session_id, result.question_id, submitted, result.is_correct) {
+        eprintln!("Warning: Failed to save working-out: {}", e);
+    }
+    record_boss_attempt(state, profile_id, result);
+    if let Err(e) = state.curriculum_coverage.record_answer(profile_id, result.question_id, result.is_correct) {
+        eprintln!("Warning: Failed to record answered question: {}", e);
+    }
+    if let Err(e) = state.mistakes.record_answer(profile_id, result.question_id, result.is_correct) {
+        eprintln!("Warning: Failed to update mistakes bank: {}", e);
+    }
+    Ok(())
 }
 
 #[tauri::command]
 async fn get_current_question(
     state: State<'_, AppState>,
-    session_id: u32,
+    session_id: SessionId,
 ) -> Result<Option<Question>, String> {
     let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
     
-    quiz_engine.get_current_question(session_id)
-        .map_err(|e| e.to_string())
+    let question = quiz_engine.get_current_question(session_id)
+        .map_err(|e| e.to_string())?;
+    let profile_id = quiz_engine.get_current_player(session_id)
+        .map_err(|e| e.to_string())?;
+    drop(quiz_engine);
+
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    Ok(question.map(|mut question| {
+        accessibility.transform_question(&mut question);
+        question
+    }))
 }
 
 #[tauri::command]
@@ -189,10 +1019,28 @@ async fn calculate_score(
         .map_err(|e| e.to_string())
 }
 
+/// Save a shareable results card image for a quiz and return its path. The card shows the
+/// score, subject and a badge but nothing about the child.
+#[tauri::command]
+async fn generate_result_card(
+    state: State<'_, AppState>,
+    quiz_session: QuizSession,
+) -> Result<String, String> {
+    let score = {
+        let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+        quiz_engine.calculate_score(&quiz_session)
+            .map_err(|e| e.to_string())?
+    };
+
+    state.result_cards.generate(&quiz_session.config.subject, quiz_session.config.key_stage, &score)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn pause_quiz(
     state: State<'_, AppState>,
-    session_id: u32,
+    session_id: SessionId,
 ) -> Result<(), String> {
     let mut quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
     
@@ -201,455 +1049,4087 @@ async fn pause_quiz(
 }
 
 #[tauri::command]
-async fn resume_quiz(
+async fn resume_quiz(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<(), String> {
+    let mut quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    
+    quiz_engine.resume_quiz(session_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_resumable_sessions(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<QuizSession>, String> {
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    
+    let mut sessions = quiz_engine.get_active_sessions(Some(profile_id));
+    for session in sessions.iter_mut() {
+        accessibility.transform_session(session);
+    }
+    Ok(sessions)
+}
+
+/// Keep track of boss question results for the profile's boss stats
+fn record_boss_attempt(state: &AppState, profile_id: ProfileId, result: &AnswerResult) {
+    if result.is_boss_question {
+        if let Err(e) = state.boss_questions.record(profile_id, result.question_id, result.is_correct) {
+            eprintln!("Warning: Failed to record boss question: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_boss_question_stats(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<BossQuestionStats, String> {
+    state.boss_questions.get_stats(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Questions the quiz engine had to swap out or skip mid-session, newest first
+#[tauri::command]
+async fn get_quiz_incidents(
+    state: State<'_, AppState>,
+    session_token: String,
+    limit: Option<u32>,
+) -> Result<Vec<QuizIncident>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.get_incidents(limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// QUIZ PRESET COMMANDS
+// ============================================================================
+
+/// Quick play presets for every subject
+#[tauri::command]
+async fn get_quiz_presets(
+    state: State<'_, AppState>,
+) -> Result<Vec<QuizPreset>, String> {
+    state.quiz_presets.list_presets()
+        .map_err(|e| e.to_string())
+}
+
+/// Change a subject's quick play question count, time limit or allowed question types
+#[tauri::command]
+async fn set_quiz_preset(
+    state: State<'_, AppState>,
+    session_token: String,
+    preset: QuizPreset,
+) -> Result<QuizPreset, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.quiz_presets.set_preset(&preset)
+        .map_err(|e| e.to_string())
+}
+
+/// Put a subject's quick play preset back to the built-in defaults
+#[tauri::command]
+async fn reset_quiz_preset(
+    state: State<'_, AppState>,
+    session_token: String,
+    subject: String,
+) -> Result<QuizPreset, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.quiz_presets.reset_preset(&subject)
+        .map_err(|e| e.to_string())
+}
+
+/// Start a quiz from a subject tile using the subject's preset
+#[tauri::command]
+async fn quick_play(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    subject: String,
+    key_stage: KeyStage,
+) -> Result<QuizSession, String> {
+    let config = state.quiz_presets.quick_play_config(&subject, key_stage)
+        .map_err(|e| e.to_string())?;
+    start_quiz_session(app, state, profile_id, config).await
+}
+
+// ============================================================================
+// DIFFICULTY CURVE COMMANDS
+// ============================================================================
+
+/// A profile's per-subject key stage and difficulty overrides
+#[tauri::command]
+async fn get_difficulty_curves(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<DifficultyCurve>, String> {
+    state.difficulty_curves.get_curves(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Play one subject at a different key stage or difficulty from the rest
+#[tauri::command]
+async fn set_difficulty_curve(
+    state: State<'_, AppState>,
+    session_token: String,
+    curve: DifficultyCurve,
+) -> Result<DifficultyCurve, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.difficulty_curves.set_curve(&curve)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn clear_difficulty_curve(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    subject: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.difficulty_curves.clear_curve(profile_id, &subject)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// VOICE INPUT COMMANDS
+// ============================================================================
+
+/// A spoken answer and, once accepted or confirmed, its result
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceAnswerOutcome {
+    pub interpretation: VoiceInterpretation,
+    /// Set when the answer was submitted; `None` means confirm or retry first
+    pub result: Option<AnswerResult>,
+}
+
+/// Match transcriptions from the frontend's own recogniser against a question, without submitting
+#[tauri::command]
+async fn interpret_voice_answer(
+    state: State<'_, AppState>,
+    question_id: QuestionId,
+    transcriptions: Vec<Transcription>,
+) -> Result<VoiceInterpretation, String> {
+    state.voice_input.interpret(question_id, &transcriptions)
+        .map_err(|e| e.to_string())
+}
+
+/// Transcribe recorded audio (base64) with the installed speech-to-text provider
+#[tauri::command]
+async fn transcribe_voice_answer(
+    state: State<'_, AppState>,
+    question_id: QuestionId,
+    mime_type: String,
+    audio_base64: String,
+) -> Result<VoiceInterpretation, String> {
+    use base64::Engine;
+
+    let data = base64::engine::general_purpose::STANDARD.decode(audio_base64.as_bytes())
+        .map_err(|e| format!("Invalid audio data: {}", e))?;
+    let voice_input = state.voice_input.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        voice_input.transcribe_answer(question_id, &SpokenAudio { mime_type, data })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Answer the session's current question by voice. Confident answers are submitted straight
+/// away; probable ones only once the child has confirmed them (`confirmed`).
+#[tauri::command]
+async fn submit_voice_answer(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_id: SessionId,
+    transcriptions: Vec<Transcription>,
+    confirmed: bool,
+    time_taken_seconds: u32,
+) -> Result<VoiceAnswerOutcome, String> {
+    let mut quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let question_id = quiz_engine.get_current_question(session_id)
+        .map_err(|e| e.to_string())?
+        .and_then(|question| question.id)
+        .ok_or_else(|| "Quiz has no current question".to_string())?;
+
+    let interpretation = state.voice_input.interpret(question_id, &transcriptions)
+        .map_err(|e| e.to_string())?;
+
+    let result = match interpretation.answer_to_submit(confirmed) {
+        Some(answer) => {
+            let result = quiz_engine.submit_answer(session_id, answer.clone(), time_taken_seconds)
+                .map_err(|e| e.to_string())?;
+            notify_quiz_activity(&app, &quiz_engine, session_id, &result);
+            record_answer(&app, &state, &quiz_engine, session_id, answer, &result)?;
+            Some(result)
+        }
+        None => None,
+    };
+
+    Ok(VoiceAnswerOutcome { interpretation, result })
+}
+
+/// Name of the installed speech-to-text provider, if any
+#[tauri::command]
+async fn get_voice_provider(
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    Ok(state.voice_input.provider_name())
+}
+
+#[tauri::command]
+async fn get_voice_thresholds(
+    state: State<'_, AppState>,
+) -> Result<VoiceThresholds, String> {
+    Ok(state.voice_input.get_thresholds())
+}
+
+#[tauri::command]
+async fn set_voice_thresholds(
+    state: State<'_, AppState>,
+    session_token: String,
+    thresholds: VoiceThresholds,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.voice_input.set_thresholds(thresholds)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// PHONICS COMMANDS
+// ============================================================================
+
+/// Generate the KS1 "which word begins with..." phonics questions and their audio.
+/// Returns the ids of the questions added; ones already installed are skipped.
+#[tauri::command]
+async fn generate_phonics_questions(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Vec<QuestionId>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let phonics = state.phonics.clone();
+    tauri::async_runtime::spawn_blocking(move || phonics.install_questions())
+        .await
+        .map_err(|e| format!("Phonics task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Name of the installed speech synthesiser used for phonics audio, if any
+#[tauri::command]
+async fn get_phonics_synthesizer(
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    Ok(state.phonics.synthesizer_name())
+}
+
+/// Start a phonics screening check practice: 40 real and pseudo-words to read aloud
+#[tauri::command]
+async fn start_phonics_screening(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<PhonicsScreening, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("phonics_screening");
+
+    let screenings = state.phonics_screenings.clone();
+    let screening = tauri::async_runtime::spawn_blocking(move || screenings.start_screening(profile_id))
+        .await
+        .map_err(|e| format!("Phonics task failed: {}", e))?
+        .map_err(|e| e.to_string())?;
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(screening)
+}
+
+#[tauri::command]
+async fn get_phonics_screening(
+    state: State<'_, AppState>,
+    screening_id: u32,
+) -> Result<PhonicsScreening, String> {
+    state.phonics_screenings.get_screening(screening_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Mark a word as the child reads it. Returns the result once the last word is marked.
+#[tauri::command]
+async fn mark_phonics_screening_word(
+    state: State<'_, AppState>,
+    screening_id: u32,
+    position: usize,
+    read_correctly: bool,
+    misread_graphemes: Vec<String>,
+) -> Result<Option<ScreeningResult>, String> {
+    state.phonics_screenings.mark_word(screening_id, position, read_correctly, misread_graphemes)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_phonics_screening_result(
+    state: State<'_, AppState>,
+    screening_id: u32,
+) -> Result<ScreeningResult, String> {
+    state.phonics_screenings.get_result(screening_id)
+        .map_err(|e| e.to_string())
+}
+
+/// How accurately each grapheme has been read across all of a profile's screenings
+#[tauri::command]
+async fn get_phonics_grapheme_accuracy(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<GraphemeAccuracy>, String> {
+    state.phonics_screenings.get_grapheme_accuracy(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// EXPLORE COMMANDS
+// ============================================================================
+
+/// Topics a child can explore in a subject
+#[tauri::command]
+async fn get_explore_topics(
+    state: State<'_, AppState>,
+    subject: String,
+    key_stage: KeyStage,
+) -> Result<Vec<ExploreTopic>, String> {
+    state.explore.get_topics(&subject, key_stage)
+        .map_err(|e| e.to_string())
+}
+
+/// Questions on a topic, or in the whole subject when no topic is given, with no timer or score
+#[tauri::command]
+async fn browse_explore_topic(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    subject: String,
+    key_stage: KeyStage,
+    topic: Option<String>,
+) -> Result<Vec<ExploreItem>, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("explore");
+
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    let mut items = state.explore.browse(profile_id, &subject, key_stage, topic.as_deref())
+        .map_err(|e| e.to_string())?;
+    for item in &mut items {
+        accessibility.transform_question(&mut item.question);
+    }
+    Ok(items)
+}
+
+#[tauri::command]
+async fn view_explore_question(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    question_id: QuestionId,
+) -> Result<Question, String> {
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    let mut question = state.explore.view_question(profile_id, question_id)
+        .map_err(|e| e.to_string())?;
+    accessibility.transform_question(&mut question);
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(question)
+}
+
+#[tauri::command]
+async fn reveal_explore_answer(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    question_id: QuestionId,
+) -> Result<RevealedAnswer, String> {
+    state.explore.reveal_answer(profile_id, question_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_practice_later(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    question_id: QuestionId,
+    marked: bool,
+) -> Result<(), String> {
+    state.explore.set_practice_later(profile_id, question_id, marked)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_practice_later(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<PracticeLaterItem>, String> {
+    state.explore.get_practice_later(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Quiz the child on the questions they set aside while exploring
+#[tauri::command]
+async fn start_practice_later_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<QuizSession, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("practice_later_session");
+
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    let questions = state.explore.get_practice_later(profile_id)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|item| item.question)
+        .collect();
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut session = quiz_engine.start_practice_later_session(profile_id, questions)
+        .map_err(|e| e.to_string())?;
+    accessibility.transform_session(&mut session);
+    drop(quiz_engine);
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(session)
+}
+
+/// Questions the child got wrong and hasn't yet put right, optionally in one subject
+#[tauri::command]
+async fn get_mistakes(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    subject: Option<String>,
+) -> Result<Vec<MistakeItem>, String> {
+    state.mistakes.get_mistakes(profile_id, subject.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Quiz the child on their past mistakes, most often wrong first. Each one leaves the bank
+/// once it's been answered right twice in a row.
+#[tauri::command]
+async fn start_mistakes_quiz(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    subject: Option<String>,
+) -> Result<QuizSession, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("mistakes_quiz");
+
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    let questions = state.mistakes.get_mistakes(profile_id, subject.as_deref())
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .take(MISTAKES_QUIZ_QUESTIONS)
+        .map(|item| item.question)
+        .collect();
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut session = quiz_engine.start_mistakes_session(profile_id, questions)
+        .map_err(|e| e.to_string())?;
+    accessibility.transform_session(&mut session);
+    drop(quiz_engine);
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(session)
+}
+
+// ============================================================================
+// DRAWN ANSWER COMMANDS
+// ============================================================================
+
+/// Drawn answers from one quiz session, for parent review
+#[tauri::command]
+async fn get_session_drawings(
+    state: State<'_, AppState>,
+    session_token: String,
+    session_id: SessionId,
+) -> Result<Vec<DrawingSubmission>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.drawing_answers.get_session_drawings(session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// A profile's most recent drawn answers, for parent review
+#[tauri::command]
+async fn get_profile_drawings(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    limit: Option<u32>,
+) -> Result<Vec<DrawingSubmission>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.drawing_answers.get_profile_drawings(profile_id, limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+/// Name of the installed drawing recogniser, if any
+#[tauri::command]
+async fn get_drawing_recognizer(
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    Ok(state.drawing_answers.recognizer_name())
+}
+
+// ============================================================================
+// SCRATCHPAD COMMANDS
+// ============================================================================
+
+/// Save the child's working-out for the current question of a quiz session
+#[tauri::command]
+async fn save_scratchpad(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+    work: ScratchpadWork,
+) -> Result<(), String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let question_id = quiz_engine.get_current_question(session_id)
+        .map_err(|e| e.to_string())?
+        .and_then(|question| question.id)
+        .ok_or_else(|| "Quiz has no current question".to_string())?;
+    let profile_id = match quiz_engine.get_recording_profile_id(session_id)
+        .map_err(|e| e.to_string())?
+    {
+        Some(profile_id) => profile_id,
+        None => return Ok(()),
+    };
+    drop(quiz_engine);
+
+    state.scratchpads.save_work(profile_id, session_id, question_id, work)
+        .map_err(|e| e.to_string())
+}
+
+/// Working-out saved so far for the current question, e.g. after resuming a quiz
+#[tauri::command]
+async fn get_scratchpad(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<Option<ScratchpadWork>, String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let question_id = match quiz_engine.get_current_question(session_id)
+        .map_err(|e| e.to_string())?
+        .and_then(|question| question.id)
+    {
+        Some(question_id) => question_id,
+        None => return Ok(None),
+    };
+    let profile_id = match quiz_engine.get_recording_profile_id(session_id)
+        .map_err(|e| e.to_string())?
+    {
+        Some(profile_id) => profile_id,
+        None => return Ok(None),
+    };
+    drop(quiz_engine);
+
+    state.scratchpads.get_work(profile_id, session_id, question_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Working-out and answers from one of a child's quiz sessions, for parent review
+#[tauri::command]
+async fn get_session_scratchpads(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    session_id: SessionId,
+) -> Result<Vec<ScratchpadEntry>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.scratchpads.get_session_entries(profile_id, session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// A child's most recent answers that came with working-out, for parent review
+#[tauri::command]
+async fn get_profile_scratchpads(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    limit: Option<u32>,
+) -> Result<Vec<ScratchpadEntry>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.scratchpads.get_profile_entries(profile_id, limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// QUESTION OF THE DAY COMMANDS
+// ============================================================================
+
+/// Result of answering the question of the day
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyQuestionAnswer {
+    pub result: AnswerResult,
+    /// The first answer given today, which is the one that counts
+    pub completion: DailyQuestionCompletion,
+}
+
+#[tauri::command]
+async fn get_question_of_the_day(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    key_stage: KeyStage,
+) -> Result<Option<DailyQuestion>, String> {
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+
+    let daily = state.question_of_the_day.get_for_profile(profile_id, key_stage, chrono::Local::now().date_naive())
+        .map_err(|e| e.to_string())?;
+    Ok(daily.map(|mut daily| {
+        accessibility.transform_question(&mut daily.question);
+        daily
+    }))
+}
+
+#[tauri::command]
+async fn answer_question_of_the_day(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    key_stage: KeyStage,
+    question_id: QuestionId,
+    answer: Answer,
+) -> Result<DailyQuestionAnswer, String> {
+    let today = chrono::Local::now().date_naive();
+    let result = {
+        let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+        quiz_engine.validate_answer(question_id, answer).map_err(|e| e.to_string())?
+    };
+
+    let completion = state.question_of_the_day
+        .record_completion(profile_id, key_stage, today, question_id, result.is_correct)
+        .map_err(|e| e.to_string())?;
+
+    // The daily question counts towards the day's practice goal
+    state.notifications.record_practice(profile_id, 1, today)
+        .map_err(|e| e.to_string())?;
+
+    Ok(DailyQuestionAnswer { result, completion })
+}
+
+// ============================================================================
+// PROFILE MANAGEMENT COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn create_profile(
+    state: State<'_, AppState>,
+    request: CreateProfileRequest,
+) -> Result<Profile, String> {
+    println!("🔍 create_profile command called with name: {}", request.name);
+    match state.profile_manager.create_profile(request) {
+        Ok(profile) => {
+            println!("✅ Successfully created profile: {} (ID: {:?})", profile.name, profile.id);
+            Ok(profile)
+        }
+        Err(e) => {
+            println!("❌ Failed to create profile in Rust backend: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_profile_by_id(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Profile, String> {
+    state.profile_manager.get_profile_by_id(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_all_profiles(
+    state: State<'_, AppState>,
+) -> Result<Vec<Profile>, String> {
+    println!("🔍 get_all_profiles command called");
+    match state.profile_manager.get_all_profiles() {
+        Ok(profiles) => {
+            println!("✅ Successfully retrieved {} profiles from Rust backend", profiles.len());
+            for (i, profile) in profiles.iter().enumerate() {
+                println!("  Profile {}: {} (ID: {:?})", i + 1, profile.name, profile.id);
+            }
+            Ok(profiles)
+        }
+        Err(e) => {
+            println!("❌ Failed to retrieve profiles in Rust backend: {}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+async fn update_profile(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    updates: ProfileUpdateRequest,
+) -> Result<Profile, String> {
+    state.profile_manager.update_profile(profile_id, updates)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_profile(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.profile_manager.delete_profile(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_progress(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Progress, String> {
+    state.profile_manager.get_progress(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Days in a row the child has completed a quiz, and any freezes saved up
+#[tauri::command]
+async fn get_streak(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<DailyStreak, String> {
+    state.profile_manager.get_streak(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// The child's level, the XP still needed for the next one, and what they earned lately
+#[tauri::command]
+async fn get_level_info(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<LevelInfo, String> {
+    state.profile_manager.get_level_info(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Give a child freeze tokens so a missed day doesn't end their streak
+#[tauri::command]
+async fn grant_streak_freezes(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    count: u32,
+) -> Result<u32, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.profile_manager.grant_streak_freezes(profile_id, count)
+        .map_err(|e| e.to_string())
+}
+
+/// Totals across the whole family, for the family stats screen
+#[tauri::command]
+async fn get_household_statistics(
+    state: State<'_, AppState>,
+) -> Result<HouseholdStatistics, String> {
+    state.profile_manager.get_household_statistics()
+        .map_err(|e| e.to_string())
+}
+
+/// Reset one subject, one tag or all of a profile's progress. What's cleared is saved
+/// first so the reset can be undone.
+#[tauri::command]
+async fn reset_progress(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    scope: ResetScope,
+) -> Result<ProgressReset, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.profile_manager.reset_progress(profile_id, scope)
+        .map_err(|e| e.to_string())
+}
+
+/// A profile's progress resets, newest first
+#[tauri::command]
+async fn get_progress_resets(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+) -> Result<Vec<ProgressReset>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.profile_manager.get_progress_resets(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Undo a progress reset from its snapshot
+#[tauri::command]
+async fn restore_progress_reset(
+    state: State<'_, AppState>,
+    session_token: String,
+    reset_id: u32,
+) -> Result<ProgressReset, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.profile_manager.restore_progress_reset(reset_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn update_progress(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    quiz_result: QuizResult,
+) -> Result<(), String> {
+    record_quiz_progress(&app, &state, profile_id, quiz_result)
+}
+
+/// Count a quiz towards a profile's progress, XP and achievements
+fn record_quiz_progress(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    profile_id: ProfileId,
+    mut quiz_result: QuizResult,
+) -> Result<(), String> {
+    let questions_answered = quiz_result.questions_answered;
+
+    // Sandbox sessions never count towards a profile
+    if let Some(session_id) = quiz_result.session_id {
+        let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if quiz_engine.get_recording_profile_id(session_id).map_err(|e| e.to_string())?.is_none() {
+            return Ok(());
+        }
+    }
+
+    // Practice only counts the questions got right first time, so it never records a
+    // failure. Whether a quiz was practice comes from the session, not the client.
+    quiz_result.practice = false;
+    if let Some(session_id) = quiz_result.session_id {
+        let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if let Some(first_time) = quiz_engine.get_practice_credit(session_id, profile_id).map_err(|e| e.to_string())? {
+            quiz_result.questions_answered = first_time;
+            quiz_result.correct_answers = first_time;
+            quiz_result.practice = true;
+        }
+    }
+
+    // Rushed sessions still count as practice, but not towards progress and mastery
+    let timing_flagged = match quiz_result.session_id {
+        Some(session_id) => {
+            let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+            quiz_engine.is_timing_flagged(session_id).unwrap_or(false)
+        }
+        None => false,
+    };
+    let session_id = quiz_result.session_id;
+    let subject = quiz_result.subject.clone();
+    let new_achievements = if timing_flagged {
+        Vec::new()
+    } else {
+        state.profile_manager.update_progress(profile_id, quiz_result)
+            .map_err(|e| e.to_string())?
+    };
+    
+    // XP is worked out from the session itself rather than trusted from the client
+    if let Some(session_id) = session_id.filter(|_| !timing_flagged) {
+        let xp = {
+            let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+            quiz_engine.get_xp_earned(session_id, profile_id).map_err(|e| e.to_string())?
+        };
+        if xp > 0 {
+            let gain = state.profile_manager.award_xp(profile_id, xp, &subject)
+                .map_err(|e| e.to_string())?;
+            if gain.level_after > gain.level_before {
+                let event = LevelUpEvent { profile_id, level: gain.level_after };
+                if let Err(e) = app.emit_all("level-up", &event) {
+                    eprintln!("Warning: Failed to announce level up: {}", e);
+                }
+            }
+        }
+    }
+    
+    // Every milestone earns a printable certificate
+    for path in state.certificates.generate_for_new_achievements(profile_id, &new_achievements) {
+        let event = CertificateCreatedEvent {
+            profile_id,
+            path: path.to_string_lossy().to_string(),
+        };
+        if let Err(e) = app.emit_all("certificate-created", &event) {
+            eprintln!("Warning: Failed to announce certificate: {}", e);
+        }
+    }
+    
+    // Daily activity drives reminders, goals and streak warnings
+    state.notifications.record_practice(profile_id, questions_answered, chrono::Local::now().date_naive())
+        .map_err(|e| e.to_string())
+}
+
+/// Sent to the frontend when XP from a quiz takes a profile up a level
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelUpEvent {
+    pub profile_id: ProfileId,
+    pub level: u32,
+}
+
+/// Sent to the frontend when a certificate is generated for a new achievement
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateCreatedEvent {
+    pub profile_id: ProfileId,
+    pub path: String,
+}
+
+/// Every badge, with when the child earned it, or none if they're still working towards it
+#[tauri::command]
+async fn get_achievements(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<Badge>, String> {
+    state.achievements.get_achievements(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Award any achievements earned by a finished quiz, returning just the new ones.
+/// `update_progress` already does this after saving progress.
+#[tauri::command]
+async fn check_achievements_after_quiz(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    quiz_result: QuizResult,
+) -> Result<Vec<Achievement>, String> {
+    let new_achievements = state.achievements.check_after_quiz(profile_id, &quiz_result)
+        .map_err(|e| e.to_string())?;
+
+    for path in state.certificates.generate_for_new_achievements(profile_id, &new_achievements) {
+        let event = CertificateCreatedEvent {
+            profile_id,
+            path: path.to_string_lossy().to_string(),
+        };
+        if let Err(e) = app.emit_all("certificate-created", &event) {
+            eprintln!("Warning: Failed to announce certificate: {}", e);
+        }
+    }
+    Ok(new_achievements)
+}
+
+#[tauri::command]
+async fn generate_certificate(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    achievement_id: String,
+) -> Result<String, String> {
+    state.certificates.generate_certificate(profile_id, &achievement_id)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// The SATs-style papers that can be generated
+#[tauri::command]
+async fn get_paper_blueprints(
+    state: State<'_, AppState>,
+) -> Result<Vec<PaperBlueprint>, String> {
+    Ok(state.mock_papers.get_blueprints().to_vec())
+}
+
+/// Assemble a mock paper from the question bank. The same paper can then be sat on
+/// screen and printed.
+#[tauri::command]
+async fn generate_mock_paper(
+    state: State<'_, AppState>,
+    session_token: String,
+    blueprint_id: String,
+) -> Result<MockPaper, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("mock_paper");
+
+    state.mock_papers.generate(&blueprint_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Save a mock paper with its mark scheme as a PDF and return its path
+#[tauri::command]
+async fn export_mock_paper_pdf(
+    state: State<'_, AppState>,
+    session_token: String,
+    paper_id: u32,
+) -> Result<String, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let paper = state.mock_papers.get_paper(paper_id)
+        .map_err(|e| e.to_string())?;
+    state.mock_papers.write_pdf(&paper)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Sit a mock paper on screen as a timed assessment
+#[tauri::command]
+async fn start_mock_paper_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    paper_id: u32,
+) -> Result<QuizSession, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    let paper = state.mock_papers.get_paper(paper_id)
+        .map_err(|e| e.to_string())?;
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    let time_limit_seconds = (paper.time_limit_seconds as f32 * accessibility.timer_multiplier()).round() as u32;
+    let questions = paper.questions.into_iter().map(|paper_question| paper_question.question).collect();
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut session = quiz_engine.start_paper_session(profile_id, &paper.title, time_limit_seconds, questions)
+        .map_err(|e| e.to_string())?;
+    accessibility.transform_session(&mut session);
+    drop(quiz_engine);
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(session)
+}
+
+/// Reading age estimates for a profile, oldest first, after bringing them up to date
+#[tauri::command]
+async fn get_reading_age_history(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+) -> Result<Vec<ReadingAgeEstimate>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.reading_age.record_estimate(profile_id)
+        .map_err(|e| e.to_string())?;
+    state.reading_age.get_history(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// CONTENT MANAGEMENT COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn get_subjects(
+    state: State<'_, AppState>,
+) -> Result<Vec<Subject>, String> {
+    state.content_manager.get_subjects()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_questions_by_subject(
+    state: State<'_, AppState>,
+    subject_name: String,
+    key_stage: Option<KeyStage>,
+    difficulty_range: Option<(u8, u8)>,
+    limit: Option<usize>,
+) -> Result<Vec<Question>, String> {
+    state.content_manager.get_questions_by_subject(
+        &subject_name,
+        key_stage,
+        difficulty_range,
+        limit,
+    ).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_question_by_id(
+    state: State<'_, AppState>,
+    question_id: QuestionId,
+) -> Result<Question, String> {
+    state.content_manager.get_question_by_id(question_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn add_question(
+    state: State<'_, AppState>,
+    session_token: String,
+    question: Question,
+) -> Result<QuestionId, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    // Questions added here are always the parent's own, whatever the client claims
+    let author = match question.source.clone() {
+        Some(QuestionSource::Parent { author }) => author,
+        _ => None,
+    };
+    state.content_manager.add_question(question.with_source(QuestionSource::Parent { author }))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn update_question(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+    question: Question,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_manager.update_question(question_id, question)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_question(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_manager.delete_question(question_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Questions deleted but kept for history until they are purged
+#[tauri::command]
+async fn get_retired_questions(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Vec<RetiredQuestion>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_manager.get_retired_questions()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn restore_question(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_manager.restore_question(question_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Permanently remove questions retired longer ago than the retention period, along with
+/// the history recorded against them
+#[tauri::command]
+async fn purge_retired_questions(
+    state: State<'_, AppState>,
+    session_token: String,
+    retention_days: Option<u32>,
+) -> Result<Vec<QuestionId>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let retention_days = retention_days.map_or(RETIRED_QUESTION_RETENTION_DAYS, i64::from);
+    state.content_manager.purge_retired_questions(chrono::Duration::days(retention_days))
+        .map_err(|e| e.to_string())
+}
+
+/// Changes to question content made outside a parent's edits or a pack install, newest first
+#[tauri::command]
+async fn get_content_change_alerts(
+    state: State<'_, AppState>,
+    session_token: String,
+    include_resolved: Option<bool>,
+) -> Result<Vec<ContentChangeAlert>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_changes.get_alerts(include_resolved.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// Earlier versions of a question, newest first
+#[tauri::command]
+async fn get_question_versions(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+) -> Result<Vec<QuestionVersion>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_changes.get_versions(question_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Put a question back as it was before a suspicious change
+#[tauri::command]
+async fn revert_content_change(
+    state: State<'_, AppState>,
+    session_token: String,
+    alert_id: u32,
+) -> Result<ContentChangeAlert, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_changes.revert(alert_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Keep a flagged change, marking the alert as seen
+#[tauri::command]
+async fn dismiss_content_change(
+    state: State<'_, AppState>,
+    session_token: String,
+    alert_id: u32,
+) -> Result<ContentChangeAlert, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_changes.dismiss(alert_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Report a question as wrong or confusing, from the quiz screen
+#[tauri::command]
+async fn flag_question(
+    state: State<'_, AppState>,
+    question_id: QuestionId,
+    profile_id: Option<ProfileId>,
+    reason: Option<String>,
+) -> Result<(), String> {
+    state.question_quality.flag_question(question_id, profile_id, reason.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Questions scoring worst on accuracy, flags, duplication and missing explanations
+#[tauri::command]
+async fn get_questions_needing_attention(
+    state: State<'_, AppState>,
+    session_token: String,
+    subject: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<QuestionQuality>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.question_quality.get_needs_attention(subject.as_deref(), limit.unwrap_or(50))
+        .map_err(|e| e.to_string())
+}
+
+/// How children get on with each phrasing of a question
+#[tauri::command]
+async fn get_question_phrasing_stats(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+) -> Result<Vec<PhrasingStats>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.question_quality.get_phrasing_stats(question_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_content_statistics(
+    state: State<'_, AppState>,
+) -> Result<ContentStatistics, String> {
+    state.content_manager.get_content_statistics()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn load_content_pack(
+    state: State<'_, AppState>,
+    session_token: String,
+    pack_path: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("load_content_pack");
+
+    let path = std::path::Path::new(&pack_path);
+    state.content_manager.load_content_pack(path)
+        .map_err(|e| e.to_string())
+}
+
+/// Where the questions in the bank came from, with a count for each source
+#[tauri::command]
+async fn get_question_sources(
+    state: State<'_, AppState>,
+) -> Result<Vec<QuestionSourceSummary>, String> {
+    state.content_manager.get_question_sources()
+        .map_err(|e| e.to_string())
+}
+
+/// Remove every question from one source, e.g. everything a content pack installed
+#[tauri::command]
+async fn delete_questions_by_source(
+    state: State<'_, AppState>,
+    session_token: String,
+    kind: String,
+    reference: Option<String>,
+) -> Result<QuestionRemoval, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_manager.delete_questions_by_source(&kind, reference.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Uninstall a content pack by name. Questions children have already answered are retired
+/// rather than deleted so their history stays intact.
+#[tauri::command]
+async fn uninstall_content_pack(
+    state: State<'_, AppState>,
+    session_token: String,
+    pack_id: String,
+) -> Result<QuestionRemoval, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("uninstall_content_pack");
+
+    state.content_manager.uninstall_content_pack(&pack_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Save questions as a content pack, with each question's source included
+#[tauri::command]
+async fn export_content_pack(
+    state: State<'_, AppState>,
+    session_token: String,
+    pack_path: String,
+    name: String,
+    subject: Option<String>,
+) -> Result<usize, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.content_manager.export_content_pack(&name, subject.as_deref(), std::path::Path::new(&pack_path))
+        .map_err(|e| e.to_string())
+}
+
+/// Import questions written in the Markdown question format. Questions with mistakes are
+/// listed in the report rather than failing the whole file.
+#[tauri::command]
+async fn import_markdown_questions(
+    state: State<'_, AppState>,
+    session_token: String,
+    file_path: String,
+) -> Result<MarkdownImportReport, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("import_markdown_questions");
+
+    state.markdown_importer.import_file(std::path::Path::new(&file_path))
+        .map_err(|e| e.to_string())
+}
+
+/// Copy a picture or sound into the content directory, optimising it, ready to attach to
+/// a question
+#[tauri::command]
+async fn import_asset(
+    state: State<'_, AppState>,
+    session_token: String,
+    file_path: String,
+    asset_type: AssetType,
+    alt_text: Option<String>,
+) -> Result<Asset, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let asset_pipeline = state.asset_pipeline.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        asset_pipeline.import_asset(std::path::Path::new(&file_path), asset_type, alt_text)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Save a clip the parent recorded in the app (base64) as the question's spoken prompt,
+/// e.g. the word for a spelling test in their own voice
+#[tauri::command]
+async fn record_question_audio(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+    mime_type: String,
+    audio_base64: String,
+    label: Option<String>,
+) -> Result<AudioPrompt, String> {
+    use base64::Engine;
+
+    require_permission!(state, Permission::Parent, session_token);
+
+    let data = base64::engine::general_purpose::STANDARD.decode(audio_base64.as_bytes())
+        .map_err(|e| format!("Invalid audio data: {}", e))?;
+    let audio_prompts = state.audio_prompts.clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        audio_prompts.record_prompt(question_id, &SpokenAudio { mime_type, data }, label)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Use an existing audio file as the question's spoken prompt
+#[tauri::command]
+async fn attach_question_audio_file(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+    file_path: String,
+    label: Option<String>,
+) -> Result<AudioPrompt, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let audio_prompts = state.audio_prompts.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        audio_prompts.attach_prompt_file(question_id, std::path::Path::new(&file_path), label)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn remove_question_audio(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.audio_prompts.remove_prompt(question_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Enter the school's weekly spelling list; practice starts today
+#[tauri::command]
+async fn create_spelling_list(
+    state: State<'_, AppState>,
+    session_token: String,
+    request: CreateSpellingListRequest,
+) -> Result<SpellingList, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("spelling_lists");
+    state.spelling_lists.create_list(request, chrono::Local::now().date_naive())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_spelling_lists(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<SpellingList>, String> {
+    state.spelling_lists.get_lists(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_spelling_list(
+    state: State<'_, AppState>,
+    session_token: String,
+    list_id: u32,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.spelling_lists.delete_list(list_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_spelling_practice_schedule(
+    state: State<'_, AppState>,
+    list_id: u32,
+) -> Result<Vec<PracticeDay>, String> {
+    state.spelling_lists.practice_schedule(list_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Today's spelling practice questions
+#[tauri::command]
+async fn get_spelling_practice(
+    state: State<'_, AppState>,
+    list_id: u32,
+) -> Result<Vec<Question>, String> {
+    state.spelling_lists.get_practice_questions(list_id, chrono::Local::now().date_naive())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn submit_spelling_attempt(
+    state: State<'_, AppState>,
+    list_id: u32,
+    word: String,
+    answer: String,
+) -> Result<SpellingAttemptResult, String> {
+    state.spelling_lists.submit_attempt(list_id, &word, &answer)
+        .map_err(|e| e.to_string())
+}
+
+/// Curriculum objectives the profile has and hasn't practised
+#[tauri::command]
+async fn get_curriculum_coverage(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    key_stage: Option<KeyStage>,
+) -> Result<CoverageReport, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.curriculum_coverage.get_coverage_report(profile_id, key_stage)
+        .map_err(|e| e.to_string())
+}
+
+/// Save a custom mix aimed at the profile's biggest curriculum gaps
+#[tauri::command]
+async fn create_gap_mix(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    key_stage: KeyStage,
+    question_count: Option<u32>,
+) -> Result<CustomMix, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("gap_mix");
+    state.curriculum_coverage.create_gap_mix(profile_id, key_stage, question_count.unwrap_or(20))
+        .map_err(|e| e.to_string())
+}
+
+/// How ready each word is before the test
+#[tauri::command]
+async fn get_spelling_readiness(
+    state: State<'_, AppState>,
+    session_token: String,
+    list_id: u32,
+) -> Result<SpellingReadiness, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.spelling_lists.get_readiness(list_id, chrono::Local::now().date_naive())
+        .map_err(|e| e.to_string())
+}
+
+/// Folder that content packs, Markdown question files and question CSVs can be dropped into
+#[tauri::command]
+async fn get_content_inbox_path(
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    Ok(state.content_inbox.inbox_dir().to_string_lossy().to_string())
+}
+
+/// Import whatever is in the content inbox now rather than waiting for the next scan
+#[tauri::command]
+async fn process_content_inbox(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Vec<InboxReport>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let content_inbox = state.content_inbox.clone();
+    let reports = tauri::async_runtime::spawn_blocking(move || content_inbox.process_pending(std::time::Duration::ZERO))
+        .await
+        .map_err(|e| format!("Inbox task failed: {}", e))?
+        .map_err(|e| e.to_string())?;
+    announce_inbox_reports(&app, &reports);
+    Ok(reports)
+}
+
+/// Tell every window what happened to files dropped in the content inbox
+fn announce_inbox_reports(app: &tauri::AppHandle, reports: &[InboxReport]) {
+    for report in reports {
+        if let Err(e) = app.emit_all("content-inbox-processed", report) {
+            eprintln!("Warning: Failed to announce content inbox import: {}", e);
+        }
+    }
+}
+
+/// Import files dropped into the content inbox, checking every few seconds until shutdown
+fn run_content_inbox_watcher(app_handle: tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let shutdown_signal = state.shutdown.subscribe();
+
+    while !*shutdown_signal.borrow() {
+        match state.content_inbox.process_pending(INBOX_SETTLE_TIME) {
+            Ok(reports) => announce_inbox_reports(&app_handle, &reports),
+            Err(e) => eprintln!("Warning: Failed to check the content inbox: {}", e),
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(5));
+    }
+}
+
+/// Prune history past the retention period once a day, hand space freed by big deletions
+/// back to the filesystem and tell the parent about suspicious content changes, checking
+/// every minute until shutdown
+fn run_database_maintenance(app_handle: tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let shutdown_signal = state.shutdown.subscribe();
+
+    while !*shutdown_signal.borrow() {
+        match state.content_changes.take_unannounced() {
+            Ok(alerts) => {
+                for alert in alerts {
+                    eprintln!("Warning: Question {} was changed outside the app", alert.question_id);
+                    if let Err(e) = app_handle.emit_all("suspicious-content-change", &alert) {
+                        eprintln!("Warning: Failed to announce content change: {}", e);
+                    }
+                }
+            }
+            Err(e) => eprintln!("Warning: Failed to check for content changes: {}", e),
+        }
+
+        match state.history_retention.prune_if_due(chrono::Utc::now()) {
+            Ok(Some(report)) if report.total_removed() > 0 => println!(
+                "🗄️ Pruned {} history record(s) past the retention period, condensing {} session(s)",
+                report.total_removed(),
+                report.sessions_condensed
+            ),
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: Failed to prune quiz history: {}", e),
+        }
+
+        // Runs on the first pass after startup, then daily
+        match state.asset_integrity.check_if_due(chrono::Utc::now()) {
+            Ok(Some(report)) => {
+                if !report.repaired.is_empty() {
+                    println!("🧾 Restored {} question asset file(s) from pack archives", report.repaired.len());
+                }
+                if !report.broken.is_empty() {
+                    eprintln!(
+                        "Warning: {} question asset file(s) are missing or damaged, affecting {} question(s)",
+                        report.broken.len(),
+                        report.broken_questions().len()
+                    );
+                    if let Err(e) = app_handle.emit_all("broken-question-assets", &report) {
+                        eprintln!("Warning: Failed to announce broken question assets: {}", e);
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("Warning: Failed to check question assets: {}", e),
+        }
+
+        // Failures are recorded by the service and shown in the backup status
+        if let Err(e) = tauri::async_runtime::block_on(state.cloud_backup.backup_if_due(chrono::Utc::now())) {
+            eprintln!("Warning: Failed to back up to cloud storage: {}", e);
+        }
+
+        match state.database.manager().run_scheduled_compaction() {
+            Ok(Some(pages)) => println!("🗜️ Compacted the database, freeing {} page(s)", pages),
+            Ok(None) => {}
+            Err(e) => eprintln!("Warning: Failed to compact the database: {}", e),
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(60));
+    }
+}
+
+#[tauri::command]
+async fn get_question_cache_stats(
+    state: State<'_, AppState>,
+) -> Result<QuestionCacheStats, String> {
+    Ok(state.content_manager.get_question_cache_stats())
+}
+
+#[tauri::command]
+async fn verify_content_signature(
+    state: State<'_, AppState>,
+    pack: ContentPack,
+) -> Result<bool, String> {
+    state.content_manager.verify_content_signature(&pack)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// READING PASSAGE COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn create_passage(
+    state: State<'_, AppState>,
+    session_token: String,
+    request: CreatePassageRequest,
+) -> Result<Passage, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.passage_manager.create_passage(request)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_passages(
+    state: State<'_, AppState>,
+    subject_id: Option<u32>,
+    key_stage: Option<KeyStage>,
+) -> Result<Vec<Passage>, String> {
+    state.passage_manager.get_passages(subject_id, key_stage)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_passage_group(
+    state: State<'_, AppState>,
+    passage_id: u32,
+) -> Result<PassageGroup, String> {
+    state.passage_manager.get_passage_group(passage_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_passage_questions(
+    state: State<'_, AppState>,
+    session_token: String,
+    passage_id: u32,
+    question_ids: Vec<QuestionId>,
+) -> Result<Passage, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.passage_manager.set_passage_questions(passage_id, &question_ids)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_passage(
+    state: State<'_, AppState>,
+    session_token: String,
+    passage_id: u32,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.passage_manager.delete_passage(passage_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Reveal the next hint for the current question; each one taken costs some of its points
+#[tauri::command]
+async fn request_hint(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<HintResult, String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.request_hint(session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Note that the child opened a hint for the current question, for the session replay
+#[tauri::command]
+async fn record_hint_used(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<(), String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.record_hint_used(session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Simulate picking questions for a quiz config many times and report how the picks were
+/// spread across tags and difficulties, to catch selection bias
+#[tauri::command]
+async fn audit_question_selection(
+    state: State<'_, AppState>,
+    session_token: String,
+    config: QuizConfig,
+    runs: u32,
+) -> Result<SelectionAudit, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let quiz_engine = state.quiz_engine.lock()
+        .map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.audit_selection(&config, runs)
+        .map_err(|e| e.to_string())
+}
+
+/// Timeline of a finished or running quiz, for the parent to play back
+#[tauri::command]
+async fn get_session_replay(
+    state: State<'_, AppState>,
+    session_token: String,
+    session_id: SessionId,
+) -> Result<SessionReplay, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.get_session_replay(session_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Let a parent play a quiz as a child would, without it counting for any profile
+#[tauri::command]
+async fn start_sandbox_session(
+    state: State<'_, AppState>,
+    session_token: String,
+    config: QuizConfig,
+) -> Result<QuizSession, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("sandbox_session");
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.start_sandbox_session(config)
+        .map_err(|e| e.to_string())
+}
+
+/// Let a parent try a single question in a sandbox session
+#[tauri::command]
+async fn preview_question(
+    state: State<'_, AppState>,
+    session_token: String,
+    question_id: QuestionId,
+) -> Result<QuizSession, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let question = state.content_manager.get_question_by_id(question_id)
+        .map_err(|e| e.to_string())?;
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.start_sandbox_preview("Question preview", vec![question])
+        .map_err(|e| e.to_string())
+}
+
+/// Show an unsaved question exactly as a quiz would, so authors can check it before saving
+#[tauri::command]
+async fn render_question_preview(
+    state: State<'_, AppState>,
+    session_token: String,
+    question: Question,
+) -> Result<Question, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.render_question_preview(question)
+        .map_err(|e| e.to_string())
+}
+
+/// Let a parent try a custom mix in a sandbox session
+#[tauri::command]
+async fn preview_custom_mix(
+    state: State<'_, AppState>,
+    session_token: String,
+    mix_id: MixId,
+) -> Result<QuizSession, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let mix = state.custom_mix_manager.get_custom_mix_by_id(mix_id)
+        .map_err(|e| e.to_string())?;
+    let questions = state.custom_mix_manager.pick_question_ids(&mix.config)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|question_id| state.content_manager.get_question_by_id(question_id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.start_sandbox_preview(&mix.name, questions)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn start_passage_quiz_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    config: QuizConfig,
+    block_count: usize,
+    questions_per_block: usize,
+) -> Result<QuizSession, String> {
+    state.wellbeing.check_can_start(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("passage_quiz_session");
+    state.telemetry.record_content_pack_usage(&config.subject);
+
+    let mut config = config;
+    state.difficulty_curves.adjust_config(profile_id, &mut config)
+        .map_err(|e| e.to_string())?;
+
+    let groups = state.passage_manager
+        .get_quiz_blocks(&config.subject, config.key_stage, block_count, questions_per_block)
+        .map_err(|e| e.to_string())?;
+
+    let accessibility = state.accessibility.get_settings(profile_id)
+        .map_err(|e| e.to_string())?;
+    accessibility.adjust_config(&mut config);
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let mut session = quiz_engine.start_passage_session(profile_id, config, groups)
+        .map_err(|e| e.to_string())?;
+    accessibility.transform_session(&mut session);
+    drop(quiz_engine);
+
+    record_wellbeing_activity(&app, &state.wellbeing, profile_id);
+    Ok(session)
+}
+
+#[tauri::command]
+async fn get_current_passage(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<Option<PassageBlock>, String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    quiz_engine.get_current_passage(session_id)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// CONTENT SEEDING COMMANDS
+// ============================================================================
+
+/// Seed all content on a background task, publishing `seeding-progress` events
+#[tauri::command]
+async fn seed_all_content(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let result = state.content_seeder.get()
+            .and_then(|content_seeder| content_seeder.seed_all_content_with_progress(|progress| {
+                publish_seeding_progress(&app, &state, progress);
+            }));
+        if let Err(e) = &result {
+            publish_seeding_failure(&app, &state, e);
+        }
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Stop the seeding run in progress, removing the questions it has inserted so far
+#[tauri::command]
+async fn cancel_seeding(
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.content_seeder.get()
+        .map(|content_seeder| content_seeder.cancel_seeding())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn is_content_seeded(
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state.content_seeder.get()
+        .and_then(|content_seeder| content_seeder.is_content_seeded())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn seed_if_empty(
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state.content_seeder.get()
+        .and_then(|content_seeder| content_seeder.seed_if_empty())
+        .map_err(|e| e.to_string())
+}
+
+/// Replace built-in content with a fresh seed, backing up the database first and keeping
+/// questions parents wrote or imported
+#[tauri::command]
+async fn reset_and_reseed_database(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<ContentReset, String> {
+    require_permission!(state, Permission::Admin, session_token);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        state.content_seeder.get()
+            .and_then(|content_seeder| content_seeder.reset_content(&state.storage))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_seeding_progress(
+    state: State<'_, AppState>,
+) -> Result<Option<SeedingProgress>, String> {
+    state.seeding_progress.lock()
+        .map(|progress| progress.clone())
+        .map_err(|e| format!("Lock error: {}", e))
+}
+
+// ============================================================================
+// ONBOARDING COMMANDS
+// ============================================================================
+
+/// Where first-run onboarding has got to, so the frontend can resume it
+#[tauri::command]
+async fn get_onboarding_state(
+    state: State<'_, AppState>,
+) -> Result<OnboardingState, String> {
+    state.onboarding.get_state()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_parent_credential(
+    state: State<'_, AppState>,
+    credential: String,
+) -> Result<OnboardingState, String> {
+    state.onboarding.set_parent_credential(&credential)
+        .map_err(|e| e.to_string())
+}
+
+/// Exchange the parent PIN for a parental session token
+#[tauri::command]
+async fn verify_parent_credential(
+    state: State<'_, AppState>,
+    credential: String,
+) -> Result<String, String> {
+    if !state.households.verify_credential(&credential, chrono::Utc::now()).map_err(|e| e.to_string())? {
+        return Err("Incorrect PIN".to_string());
+    }
+    state.security_service.generate_parental_session_token()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn create_first_profile(
+    state: State<'_, AppState>,
+    request: CreateProfileRequest,
+) -> Result<Profile, String> {
+    state.onboarding.create_first_profile(request)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn choose_onboarding_subjects(
+    state: State<'_, AppState>,
+    subjects: Vec<String>,
+) -> Result<OnboardingState, String> {
+    state.onboarding.choose_subjects(&subjects)
+        .map_err(|e| e.to_string())
+}
+
+/// Install content for the chosen subjects, publishing `seeding-progress` events. Safe to
+/// call again if an earlier attempt was interrupted.
+#[tauri::command]
+async fn seed_onboarding_content(
+    app: tauri::AppHandle,
+) -> Result<OnboardingState, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let result = state.content_seeder.get()
+            .and_then(|content_seeder| state.onboarding.seed_content(&content_seeder, |progress| {
+                publish_seeding_progress(&app, &state, progress);
+            }));
+        if let Err(e) = &result {
+            publish_seeding_failure(&app, &state, e);
+        }
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_seeder_statistics(
+    state: State<'_, AppState>,
+) -> Result<ContentStatistics, String> {
+    state.content_manager.get_content_statistics()
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// HOUSEHOLD COMMANDS
+// ============================================================================
+
+/// Households on this computer, for choosing one at start-up
+#[tauri::command]
+async fn get_households(
+    state: State<'_, AppState>,
+) -> Result<Vec<Household>, String> {
+    state.households.get_households()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_active_household(
+    state: State<'_, AppState>,
+) -> Result<Household, String> {
+    state.households.get_active_household()
+        .map_err(|e| e.to_string())
+}
+
+/// Switch household. Parent sessions from the previous household stop working.
+#[tauri::command]
+async fn select_household(
+    state: State<'_, AppState>,
+    household_id: u32,
+) -> Result<Household, String> {
+    let household = state.households.select_household(household_id)
+        .map_err(|e| e.to_string())?;
+    state.security_service.set_household(household.id);
+    // Cached questions may include another household's own questions
+    state.content_manager.invalidate_question_cache();
+    Ok(household)
+}
+
+/// Add a household with its own parent PIN
+#[tauri::command]
+async fn create_household(
+    state: State<'_, AppState>,
+    session_token: String,
+    name: String,
+    credential: String,
+) -> Result<Household, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.households.create_household(&name, &credential)
+        .map_err(|e| e.to_string())
+}
+
+/// Change the active household's parent PIN
+#[tauri::command]
+async fn change_parent_credential(
+    state: State<'_, AppState>,
+    session_token: String,
+    credential: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.households.set_credential(&credential)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// CUSTOM MIX COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn create_custom_mix(
+    state: State<'_, AppState>,
+    session_token: String,
+    request: CreateMixRequest,
+) -> Result<CustomMix, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("custom_mix");
+
+    state.custom_mix_manager.create_custom_mix(request)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_custom_mix_by_id(
+    state: State<'_, AppState>,
+    mix_id: MixId,
+) -> Result<CustomMix, String> {
+    state.custom_mix_manager.get_custom_mix_by_id(mix_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_all_custom_mixes(
+    state: State<'_, AppState>,
+) -> Result<Vec<CustomMix>, String> {
+    state.custom_mix_manager.get_all_custom_mixes()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_custom_mixes_by_profile(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<CustomMix>, String> {
+    state.custom_mix_manager.get_custom_mixes_by_profile(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn update_custom_mix(
+    state: State<'_, AppState>,
+    session_token: String,
+    mix_id: MixId,
+    updates: UpdateMixRequest,
+) -> Result<CustomMix, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.custom_mix_manager.update_custom_mix(mix_id, updates)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_custom_mix(
+    state: State<'_, AppState>,
+    session_token: String,
+    mix_id: MixId,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.custom_mix_manager.delete_custom_mix(mix_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_available_question_count(
+    state: State<'_, AppState>,
+    config: MixConfig,
+) -> Result<u32, String> {
+    state.custom_mix_manager.get_available_question_count(&config)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn validate_mix_feasibility(
+    state: State<'_, AppState>,
+    config: MixConfig,
+) -> Result<(), String> {
+    state.custom_mix_manager.validate_mix_feasibility(&config)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// APPROVAL QUEUE COMMANDS
+// ============================================================================
+
+/// Queue an action for a parent to approve and let an open parent dashboard know
+fn queue_for_approval(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    action: PendingAction,
+    requested_by: ProfileId,
+    reason: Option<String>,
+) -> Result<ApprovalRequest, String> {
+    let request = state.approval_queue.request(action, Some(requested_by), reason)
+        .map_err(|e| e.to_string())?;
+    if let Err(e) = app.emit_to(PARENT_DASHBOARD_LABEL, "approval-requested", &request) {
+        eprintln!("Warning: Failed to notify parent dashboard: {}", e);
+    }
+    Ok(request)
+}
+
+/// Ask a parent to delete a custom mix; used from child-reachable screens instead of
+/// `delete_custom_mix`
+#[tauri::command]
+async fn request_mix_deletion(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    mix_id: MixId,
+    reason: Option<String>,
+) -> Result<ApprovalRequest, String> {
+    queue_for_approval(&app, &state, PendingAction::DeleteMix { mix_id }, profile_id, reason)
+}
+
+/// Ask a parent to reset a profile's progress, all of it unless a scope is given
+#[tauri::command]
+async fn request_progress_reset(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+    scope: Option<ResetScope>,
+    reason: Option<String>,
+) -> Result<ApprovalRequest, String> {
+    let action = PendingAction::ResetProgress { profile_id, scope: scope.unwrap_or_default() };
+    queue_for_approval(&app, &state, action, profile_id, reason)
+}
+
+#[tauri::command]
+async fn get_approval_requests(
+    state: State<'_, AppState>,
+    session_token: String,
+    status: Option<ApprovalStatus>,
+) -> Result<Vec<ApprovalRequest>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.approval_queue.list_requests(status)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn approve_request(
+    state: State<'_, AppState>,
+    session_token: String,
+    request_id: u32,
+) -> Result<ApprovalRequest, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.approval_queue.approve(request_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn reject_request(
+    state: State<'_, AppState>,
+    session_token: String,
+    request_id: u32,
+) -> Result<ApprovalRequest, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.approval_queue.reject(request_id)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// SECURITY COMMANDS
+// ============================================================================
+
+/// Answer a parental challenge, getting a parental session token if the answer is right.
+/// Households with a parent PIN must use `verify_parent_credential` instead.
+#[tauri::command]
+async fn validate_parental_access(
+    state: State<'_, AppState>,
+    challenge: String,
+    input: String,
+) -> Result<Option<String>, String> {
+    if state.households.get_active_household().map_err(|e| e.to_string())?.has_credential {
+        return Err("Enter the parent PIN to open parent controls".to_string());
+    }
+    state.security_service.redeem_parental_challenge(&challenge, &input)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn generate_parental_challenge(
+    state: State<'_, AppState>,
+) -> Result<ParentalChallenge, String> {
+    state.security_service.generate_parental_challenge()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn validate_parental_feature_access(
+    state: State<'_, AppState>,
+    feature: String,
+    session_token: String,
+) -> Result<bool, String> {
+    state.security_service.validate_parental_feature_access(&feature, &session_token)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn generate_admin_session_token(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<String, String> {
+    state.security_service.generate_admin_session_token(&session_token)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_session_permission(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Permission, String> {
+    state.security_service.session_permission(&session_token)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_quiz_progress(
+    state: State<'_, AppState>,
+    session_id: SessionId,
+) -> Result<QuizProgress, String> {
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    
+    quiz_engine.get_quiz_progress(session_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn verify_update_signature(
+    state: State<'_, AppState>,
+    update_data: Vec<u8>,
+    signature: Vec<u8>,
+) -> Result<bool, String> {
+    state.security_service.verify_update_signature(&update_data, &signature)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn encrypt_sensitive_data(
+    state: State<'_, AppState>,
+    session_token: String,
+    data: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    require_permission!(state, Permission::Admin, session_token);
+
+    state.security_service.encrypt_sensitive_data(&data)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn decrypt_sensitive_data(
+    state: State<'_, AppState>,
+    session_token: String,
+    encrypted_data: Vec<u8>,
+) -> Result<Vec<u8>, String> {
+    require_permission!(state, Permission::Admin, session_token);
+
+    state.security_service.decrypt_sensitive_data(&encrypted_data)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn verify_content_package(
+    state: State<'_, AppState>,
+    package_data: Vec<u8>,
+    expected_hash: String,
+) -> Result<bool, String> {
+    state.security_service.verify_content_package(&package_data, &expected_hash)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// UPDATE SERVICE COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn check_for_updates(
+    state: State<'_, AppState>,
+) -> Result<Vec<UpdateInfo>, String> {
+    state.telemetry.record_feature_usage("check_for_updates");
+
+    let update_service = state.update_service.get().map_err(|e| e.to_string())?;
+    update_service.check_for_updates().await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn download_and_install_update(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_token: String,
+    update_info: UpdateInfo,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let update_service = state.update_service.get().map_err(|e| e.to_string())?;
+    update_service.download_and_install_update(&update_info).await
+        .map_err(|e| e.to_string())?;
+
+    // The update left a new backup behind, which may push usage over the cap
+    if let Err(e) = state.storage.enforce_cap() {
+        eprintln!("Warning: Failed to prune storage after update: {}", e);
+    }
+
+    match state.notifications.update_installed(&update_info.version, chrono::Local::now().date_naive()) {
+        Ok(notification) => show_notification(&app, &notification),
+        Err(e) => eprintln!("Warning: Failed to prepare update notification: {}", e),
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn rollback_to_backup(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Admin, session_token);
+
+    let update_service = state.update_service.get().map_err(|e| e.to_string())?;
+    update_service.rollback_to_backup().await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_current_version(
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let update_service = state.update_service.get().map_err(|e| e.to_string())?;
+    update_service.get_current_version().await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_backups(
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let update_service = state.update_service.get().map_err(|e| e.to_string())?;
+    update_service.list_backups().await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// FEATURE FLAG COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn get_feature_flags(
+    state: State<'_, AppState>,
+    profile_id: Option<ProfileId>,
+) -> Result<Vec<FeatureFlag>, String> {
+    state.feature_flags.get_flags(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn is_feature_enabled(
+    state: State<'_, AppState>,
+    flag: String,
+    profile_id: Option<ProfileId>,
+) -> Result<bool, String> {
+    state.feature_flags.is_enabled(&flag, profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_feature_flag(
+    state: State<'_, AppState>,
+    session_token: String,
+    flag: String,
+    enabled: Option<bool>,
+) -> Result<FeatureFlag, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    match enabled {
+        Some(enabled) => state.feature_flags.set_flag(&flag, enabled),
+        None => state.feature_flags.reset_flag(&flag),
+    }.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_profile_feature_flag(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    flag: String,
+    enabled: Option<bool>,
+) -> Result<FeatureFlag, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    match enabled {
+        Some(enabled) => state.feature_flags.set_profile_override(profile_id, &flag, enabled),
+        None => state.feature_flags.clear_profile_override(profile_id, &flag),
+    }.map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// TELEMETRY COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn get_telemetry_settings(
+    state: State<'_, AppState>,
+) -> Result<TelemetrySettings, String> {
+    state.telemetry.get_settings()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn preview_telemetry_payload(
+    state: State<'_, AppState>,
+) -> Result<TelemetryPayload, String> {
+    state.telemetry.build_payload()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_telemetry_opt_in(
+    state: State<'_, AppState>,
+    session_token: String,
+    opted_in: bool,
+) -> Result<TelemetrySettings, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.set_opt_in(opted_in)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_telemetry_endpoint(
+    state: State<'_, AppState>,
+    session_token: String,
+    endpoint: Option<String>,
+) -> Result<TelemetrySettings, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.set_endpoint(endpoint)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn submit_telemetry(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<TelemetryPayload, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.submit().await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// DIAGNOSTICS COMMANDS
+// ============================================================================
+
+/// Database health, pending migrations, backups, content, updates and recent errors in
+/// one report, for the diagnostics screen and support exports
+#[tauri::command]
+async fn get_app_diagnostics(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<AppDiagnostics, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.diagnostics.collect(update_status(&state).await)
+        .map_err(|e| e.to_string())
+}
+
+/// Zip up logs, diagnostics, redacted settings and the schema version for a bug report.
+/// Child personal data is left out.
+#[tauri::command]
+async fn export_support_bundle(
+    state: State<'_, AppState>,
+    session_token: String,
+    file_path: String,
+) -> Result<SupportBundleSummary, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let diagnostics = state.diagnostics.collect(update_status(&state).await)
+        .map_err(|e| e.to_string())?;
+    let mut settings = std::collections::BTreeMap::new();
+    if let Ok(app_settings) = read_app_settings(&state.settings_path) {
+        settings.insert("app".to_string(), serde_json::to_value(app_settings).map_err(|e| e.to_string())?);
+    }
+    if let Ok(Some(email_settings)) = state.email_reports.get_settings() {
+        settings.insert("email".to_string(), serde_json::to_value(email_settings).map_err(|e| e.to_string())?);
+    }
+
+    state.support_bundle.export(std::path::Path::new(&file_path), &diagnostics, settings)
+        .map_err(|e| e.to_string())
+}
+
+/// Content update status without contacting the update servers
+async fn update_status(state: &AppState) -> UpdateStatus {
+    match state.update_service.get() {
+        Ok(update_service) => UpdateStatus {
+            content_version: update_service.get_current_version().await.ok(),
+            auto_check: update_service.config().auto_check,
+            repositories: update_service.config().repository_urls.len(),
+            error: None,
+        },
+        Err(e) => UpdateStatus {
+            content_version: None,
+            auto_check: false,
+            repositories: 0,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+// ============================================================================
+// CRASH REPORT COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn get_crash_reports(
+    state: State<'_, AppState>,
+    session_token: String,
+    include_acknowledged: Option<bool>,
+) -> Result<Vec<CrashReport>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    if include_acknowledged.unwrap_or(false) {
+        state.crash_reporter.list_reports()
+    } else {
+        state.crash_reporter.get_unacknowledged_reports()
+    }.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn has_pending_crash_reports(
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    state.crash_reporter.get_unacknowledged_reports()
+        .map(|reports| !reports.is_empty())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn acknowledge_crash_report(
+    state: State<'_, AppState>,
+    session_token: String,
+    report_id: String,
+) -> Result<CrashReport, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.crash_reporter.acknowledge_report(&report_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_crash_report(
+    state: State<'_, AppState>,
+    session_token: String,
+    report_id: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.crash_reporter.delete_report(&report_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Disk space used by the database, assets, backups and logs
+#[tauri::command]
+async fn get_storage_usage(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<StorageUsage, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.storage.get_storage_usage()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_storage_settings(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<StorageSettings, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.storage.get_settings()
+        .map_err(|e| e.to_string())
+}
+
+/// Save the storage cap, pruning straight away if usage is already over it
+#[tauri::command]
+async fn update_storage_settings(
+    state: State<'_, AppState>,
+    session_token: String,
+    settings: StorageSettings,
+) -> Result<StorageSettings, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let settings = state.storage.update_settings(&settings)
+        .map_err(|e| e.to_string())?;
+    state.storage.enforce_cap()
+        .map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+/// Prune old backups, purge unused assets and compress reviewed crash reports
+#[tauri::command]
+async fn cleanup_storage(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<CleanupReport, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.telemetry.record_feature_usage("storage_cleanup");
+    state.storage.cleanup()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_cloud_backup_settings(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Option<CloudBackupSettings>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.cloud_backup.get_settings()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_cloud_backup_settings(
+    state: State<'_, AppState>,
+    session_token: String,
+    settings: CloudBackupSettings,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.cloud_backup.save_settings(&settings)
+        .map_err(|e| e.to_string())
+}
+
+/// Set the passphrase backups are encrypted with. It isn't stored, so parents must keep it
+/// safe: without it backups can't be restored.
+#[tauri::command]
+async fn set_cloud_backup_passphrase(
+    state: State<'_, AppState>,
+    session_token: String,
+    passphrase: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let cloud_backup = state.cloud_backup.clone();
+    tauri::async_runtime::spawn_blocking(move || cloud_backup.set_passphrase(&passphrase))
+        .await
+        .map_err(|e| format!("Passphrase task failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_cloud_backup_status(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<CloudBackupStatus, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.cloud_backup.get_status()
+        .map_err(|e| e.to_string())
+}
+
+/// Back up now rather than waiting for the schedule
+#[tauri::command]
+async fn back_up_to_cloud_now(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<CloudBackup, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.cloud_backup.backup_now(chrono::Utc::now()).await
+        .map_err(|e| e.to_string())
+}
+
+/// Backups at the destination, newest first: the first step of restoring
+#[tauri::command]
+async fn list_cloud_backups(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Vec<CloudBackup>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.cloud_backup.list_backups().await
+        .map_err(|e| e.to_string())
+}
+
+/// Download and decrypt a backup and show what restoring it would change. Nothing changes
+/// until it's confirmed.
+#[tauri::command]
+async fn prepare_cloud_restore(
+    state: State<'_, AppState>,
+    session_token: String,
+    name: String,
+    passphrase: String,
+) -> Result<RestorePreview, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let (backup, database) = state.cloud_backup.download_backup(&name, &passphrase).await
+        .map_err(|e| e.to_string())?;
+    state.restore.prepare(&database, BackupSource::Cloud, &backup.name, backup.taken_at)
+        .map_err(|e| e.to_string())
+}
+
+/// Database backups taken on this machine, newest first
+#[tauri::command]
+async fn list_local_backups(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Vec<DatabaseBackup>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.storage.get_database_backups()
+        .map_err(|e| e.to_string())
+}
+
+/// Show what restoring a local backup would change. Nothing changes until it's confirmed.
+#[tauri::command]
+async fn prepare_local_restore(
+    state: State<'_, AppState>,
+    session_token: String,
+    name: String,
+) -> Result<RestorePreview, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let (backup, database) = state.storage.read_database_backup(&name)
+        .map_err(|e| e.to_string())?;
+    state.restore.prepare(&database, BackupSource::Local, &backup.name, backup.taken_at)
+        .map_err(|e| e.to_string())
+}
+
+/// Restore the prepared backup, in full or just the chosen parts. The current database is
+/// backed up locally first, then the app restarts to apply the restore before anything
+/// opens the database.
+#[tauri::command]
+async fn confirm_restore(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_token: String,
+    selection: RestoreSelection,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.storage.backup_database().map_err(|e| e.to_string())?;
+    state.restore.confirm(&selection).map_err(|e| e.to_string())?;
+
+    run_shutdown(&state.shutdown);
+    app.restart();
+    Ok(())
+}
+
+#[tauri::command]
+async fn cancel_restore(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.restore.cancel()
+        .map_err(|e| e.to_string())
+}
+
+/// How the family writes money, numbers and dates, for the frontend to format with too
+#[tauri::command]
+async fn get_locale(
+    state: State<'_, AppState>,
+) -> Result<Locale, String> {
+    state.formatting.get_locale()
+        .map_err(|e| e.to_string())
+}
+
+/// Choose the family's locale. Money questions are asked in its currency from the next session.
+#[tauri::command]
+async fn set_locale(
+    state: State<'_, AppState>,
+    session_token: String,
+    locale: Locale,
+) -> Result<Locale, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.formatting.set_locale(locale)
+        .map_err(|e| e.to_string())
+}
+
+/// The curriculum the family follows
+#[tauri::command]
+async fn get_curriculum(
+    state: State<'_, AppState>,
+) -> Result<Curriculum, String> {
+    state.curriculum.get_curriculum()
+        .map_err(|e| e.to_string())
+}
+
+/// Choose the family's curriculum. Key stages are shown under its names and questions are
+/// drawn at the difficulties it expects.
+#[tauri::command]
+async fn set_curriculum(
+    state: State<'_, AppState>,
+    session_token: String,
+    curriculum: Curriculum,
+) -> Result<Curriculum, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.curriculum.set_curriculum(curriculum)
+        .map_err(|e| e.to_string())
+}
+
+/// Every key stage as a curriculum knows it, or as the family's curriculum does
+#[tauri::command]
+async fn get_curriculum_levels(
+    state: State<'_, AppState>,
+    curriculum: Option<Curriculum>,
+) -> Result<Vec<CurriculumLevel>, String> {
+    let curriculum = match curriculum {
+        Some(curriculum) => curriculum,
+        None => state.curriculum.get_curriculum().map_err(|e| e.to_string())?,
+    };
+    state.curriculum.get_levels(curriculum)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn update_curriculum_level(
+    state: State<'_, AppState>,
+    session_token: String,
+    level: CurriculumLevel,
+) -> Result<CurriculumLevel, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.curriculum.update_level(level)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_retention_settings(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<RetentionSettings, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.history_retention.get_settings()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn update_retention_settings(
+    state: State<'_, AppState>,
+    session_token: String,
+    settings: RetentionSettings,
+) -> Result<RetentionSettings, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.history_retention.update_settings(&settings)
+        .map_err(|e| e.to_string())
+}
+
+/// Prune history past the retention period now rather than waiting for the daily run
+#[tauri::command]
+async fn prune_history(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Option<RetentionReport>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.history_retention.prune(chrono::Utc::now())
+        .map_err(|e| e.to_string())
+}
+
+/// What recent pruning runs condensed and removed, newest first
+#[tauri::command]
+async fn get_retention_reports(
+    state: State<'_, AppState>,
+    session_token: String,
+    limit: Option<u32>,
+) -> Result<Vec<RetentionReport>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.history_retention.get_reports(limit.unwrap_or(10))
+        .map_err(|e| e.to_string())
+}
+
+/// Check every question image and audio file now, restoring what can be restored from
+/// pack archives, rather than waiting for the daily check
+#[tauri::command]
+async fn check_asset_integrity(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<AssetIntegrityReport, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let asset_integrity = state.asset_integrity.clone();
+    tauri::async_runtime::spawn_blocking(move || asset_integrity.check(chrono::Utc::now()))
+        .await
+        .map_err(|e| format!("Asset check failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// What recent asset checks found and repaired, newest first
+#[tauri::command]
+async fn get_asset_integrity_reports(
+    state: State<'_, AppState>,
+    session_token: String,
+    limit: Option<u32>,
+) -> Result<Vec<AssetIntegrityReport>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.asset_integrity.get_reports(limit.unwrap_or(10))
+        .map_err(|e| e.to_string())
+}
+
+/// Retire the questions whose assets are still broken after another repair attempt
+#[tauri::command]
+async fn retire_broken_asset_questions(
+    state: State<'_, AppState>,
+    session_token: String,
+) -> Result<Vec<QuestionId>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let asset_integrity = state.asset_integrity.clone();
+    tauri::async_runtime::spawn_blocking(move || asset_integrity.retire_broken_questions(chrono::Utc::now()))
+        .await
+        .map_err(|e| format!("Asset check failed: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+/// Issue a token for the local API and command line tools. The secret is only returned
+/// here, so the UI must show it straight away.
+#[tauri::command]
+async fn create_api_token(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: ProfileId,
+    name: String,
+    scopes: Vec<ApiTokenScope>,
+) -> Result<CreatedApiToken, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.api_tokens.create_token(profile_id, &name, &scopes)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_api_tokens(
+    state: State<'_, AppState>,
+    session_token: String,
+    profile_id: Option<ProfileId>,
+) -> Result<Vec<ApiToken>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.api_tokens.list_tokens(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn revoke_api_token(
+    state: State<'_, AppState>,
+    session_token: String,
+    token_id: u32,
+) -> Result<ApiToken, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.api_tokens.revoke_token(token_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Submitting a report is an explicit parental action and is the consent for that report
+#[tauri::command]
+async fn submit_crash_report(
+    state: State<'_, AppState>,
+    session_token: String,
+    report_id: String,
+) -> Result<CrashReport, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let endpoint = state.telemetry.get_settings()
+        .map_err(|e| e.to_string())?
+        .endpoint
+        .ok_or_else(|| "No telemetry endpoint configured".to_string())?;
+    let endpoint = format!("{}/crash-reports", endpoint.trim_end_matches('/'));
+
+    state.crash_reporter.submit_report(&report_id, &endpoint).await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// ASSIGNMENT COMMANDS
+// ============================================================================
+
+#[tauri::command]
+async fn create_assignment(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_token: String,
+    request: CreateAssignmentRequest,
+) -> Result<Assignment, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    let assignment = state.assignment_manager.create_assignment(request)
+        .map_err(|e| e.to_string())?;
+    refresh_tray_badge(&app, &state);
+    refresh_calendar_feeds(&state);
+    Ok(assignment)
+}
+
+#[tauri::command]
+async fn delete_assignment(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    session_token: String,
+    assignment_id: u32,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.assignment_manager.delete_assignment(assignment_id)
+        .map_err(|e| e.to_string())?;
+    refresh_tray_badge(&app, &state);
+    refresh_calendar_feeds(&state);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_assignments(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<Assignment>, String> {
+    state.assignment_manager.get_assignments_for_profile(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_pending_assignments(
+    state: State<'_, AppState>,
+    profile_id: ProfileId,
+) -> Result<Vec<Assignment>, String> {
+    state.assignment_manager.get_pending_assignments(profile_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_assignment_quiz_config(
+    state: State<'_, AppState>,
+    assignment_id: u32,
+) -> Result<MixConfig, String> {
+    state.assignment_manager.get_quiz_config(assignment_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Start the quiz for an assignment, drawn from the assignment's mix or blueprint
+#[tauri::command]
+async fn start_assignment_session(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    assignment_id: u32,
+) -> Result<QuizSession, String> {
+    let assignment = state.assignment_manager.get_assignment(assignment_id)
+        .map_err(|e| e.to_string())?;
+    state.wellbeing.check_can_start(assignment.profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+
+    state.telemetry.record_feature_usage("assignment_session");
+
+    let config = state.assignment_manager.get_quiz_config(assignment_id)
+        .map_err(|e| e.to_string())?;
+    let accessibility = state.accessibility.get_settings(assignment.profile_id)
+        .map_err(|e| e.to_string())?;
+    let time_limit_seconds = config.time_limit
+        .map(|seconds| (seconds as f32 * accessibility.timer_multiplier()).round() as u32);
+    let questions = state.custom_mix_manager.pick_question_ids(&config)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|question_id| state.content_manager.get_question_by_id(question_id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+    let mut session = quiz_engine.start_assignment_session(
+        assignment.profile_id,
+        assignment_id,
+        &assignment.title,
+        time_limit_seconds,
+        questions,
+    ).map_err(|e| e.to_string())?;
+    accessibility.transform_session(&mut session);
+    drop(quiz_engine);
+
+    record_wellbeing_activity(&app, &state.wellbeing, assignment.profile_id);
+    Ok(session)
+}
+
+/// Count a finished assignment quiz as an attempt, scored from the session's answers
+#[tauri::command]
+async fn record_assignment_attempt(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-    session_id: u32,
-) -> Result<(), String> {
-    let mut quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
-    
-    quiz_engine.resume_quiz(session_id)
-        .map_err(|e| e.to_string())
+    assignment_id: u32,
+    session_id: SessionId,
+) -> Result<Assignment, String> {
+    let (session, score) = {
+        let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let session = quiz_engine.get_completed_session(session_id)
+            .map_err(|e| e.to_string())?;
+        let score = quiz_engine.calculate_score(&session)
+            .map_err(|e| e.to_string())?;
+        (session, score)
+    };
+    let assignment = state.assignment_manager.record_attempt(assignment_id, &session, score.accuracy_percentage)
+        .map_err(|e| e.to_string())?;
+
+    refresh_tray_badge(&app, &state);
+    refresh_calendar_feeds(&state);
+    if let Err(e) = app.emit_to(PARENT_DASHBOARD_LABEL, "assignment-updated", &assignment) {
+        eprintln!("Warning: Failed to notify parent dashboard: {}", e);
+    }
+    Ok(assignment)
 }
 
 // ============================================================================
-// PROFILE MANAGEMENT COMMANDS
+// CALENDAR EXPORT COMMANDS
 // ============================================================================
 
+/// Write a profile's practice calendar to a file chosen by the user
 #[tauri::command]
-async fn create_profile(
+async fn export_calendar(
     state: State<'_, AppState>,
-    request: CreateProfileRequest,
-) -> Result<Profile, String> {
-    println!("🔍 create_profile command called with name: {}", request.name);
-    match state.profile_manager.create_profile(request) {
-        Ok(profile) => {
-            println!("✅ Successfully created profile: {} (ID: {:?})", profile.name, profile.id);
-            Ok(profile)
-        }
-        Err(e) => {
-            println!("❌ Failed to create profile in Rust backend: {}", e);
-            Err(e.to_string())
-        }
-    }
+    session_token: String,
+    profile_id: ProfileId,
+    file_path: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.calendar_exporter.write_calendar(profile_id, std::path::Path::new(&file_path))
+        .map_err(|e| e.to_string())
 }
 
+/// Path of a live calendar file that stays current as assignments and reminders change,
+/// so calendar apps can subscribe to it instead of importing a one-off copy
 #[tauri::command]
-async fn get_profile_by_id(
+async fn get_calendar_feed_path(
     state: State<'_, AppState>,
-    profile_id: u32,
-) -> Result<Profile, String> {
-    state.profile_manager.get_profile_by_id(profile_id)
+    session_token: String,
+    profile_id: ProfileId,
+) -> Result<String, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.calendar_exporter.refresh_feed(profile_id)
+        .map(|path| path.to_string_lossy().to_string())
         .map_err(|e| e.to_string())
 }
 
+/// Rewrite the live calendar feeds after a schedule change
+fn refresh_calendar_feeds(state: &AppState) {
+    if let Err(e) = state.calendar_exporter.refresh_existing_feeds() {
+        eprintln!("Warning: Failed to refresh calendar feeds: {}", e);
+    }
+}
+
+// ============================================================================
+// EMAIL REPORT COMMANDS
+// ============================================================================
+
 #[tauri::command]
-async fn get_all_profiles(
+async fn get_email_settings(
     state: State<'_, AppState>,
-) -> Result<Vec<Profile>, String> {
-    println!("🔍 get_all_profiles command called");
-    match state.profile_manager.get_all_profiles() {
-        Ok(profiles) => {
-            println!("✅ Successfully retrieved {} profiles from Rust backend", profiles.len());
-            for (i, profile) in profiles.iter().enumerate() {
-                println!("  Profile {}: {} (ID: {:?})", i + 1, profile.name, profile.id);
-            }
-            Ok(profiles)
-        }
-        Err(e) => {
-            println!("❌ Failed to retrieve profiles in Rust backend: {}", e);
-            Err(e.to_string())
-        }
-    }
+    session_token: String,
+) -> Result<Option<SmtpSettings>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.email_reports.get_settings()
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn update_profile(
+async fn save_email_settings(
     state: State<'_, AppState>,
-    profile_id: u32,
-    updates: ProfileUpdateRequest,
-) -> Result<Profile, String> {
-    state.profile_manager.update_profile(profile_id, updates)
+    session_token: String,
+    settings: SmtpSettings,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.email_reports.save_settings(&settings)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn delete_profile(
+async fn send_test_email(
     state: State<'_, AppState>,
-    profile_id: u32,
+    session_token: String,
 ) -> Result<(), String> {
-    state.profile_manager.delete_profile(profile_id)
+    require_permission!(state, Permission::Parent, session_token);
+
+    // Talking to the mail server blocks, so keep it off the async runtime
+    let email_reports = state.email_reports.clone();
+    tauri::async_runtime::spawn_blocking(move || email_reports.send_test_email())
+        .await
+        .map_err(|e| e.to_string())?
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_progress(
+async fn preview_weekly_report(
     state: State<'_, AppState>,
-    profile_id: u32,
-) -> Result<Progress, String> {
-    state.profile_manager.get_progress(profile_id)
+    session_token: String,
+) -> Result<Vec<WeeklyDigest>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.email_reports.build_weekly_digests(chrono::Local::now().date_naive())
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn update_progress(
+async fn get_email_report_status(
     state: State<'_, AppState>,
-    profile_id: u32,
-    quiz_result: QuizResult,
-) -> Result<(), String> {
-    state.profile_manager.update_progress(profile_id, quiz_result)
+) -> Result<EmailDeliveryStatus, String> {
+    state.email_reports.get_delivery_status()
         .map_err(|e| e.to_string())
 }
 
 // ============================================================================
-// CONTENT MANAGEMENT COMMANDS
+// CLASSROOM COMMANDS
 // ============================================================================
 
+/// Classroom commands are only available once a parent or teacher turns on classroom mode
+fn require_classroom_mode(state: &AppState) -> Result<(), String> {
+    let enabled = state.feature_flags.is_enabled(flags::CLASSROOM_MODE, None)
+        .map_err(|e| e.to_string())?;
+    if enabled {
+        Ok(())
+    } else {
+        Err("Classroom mode is not enabled".to_string())
+    }
+}
+
 #[tauri::command]
-async fn get_subjects(
+async fn create_class(
     state: State<'_, AppState>,
-) -> Result<Vec<Subject>, String> {
-    state.content_manager.get_subjects()
+    session_token: String,
+    request: CreateClassRequest,
+) -> Result<ClassGroup, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.create_class(request)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_questions_by_subject(
+async fn get_classes(
     state: State<'_, AppState>,
-    subject_name: String,
-    key_stage: Option<KeyStage>,
-    difficulty_range: Option<(u8, u8)>,
-    limit: Option<usize>,
-) -> Result<Vec<Question>, String> {
-    state.content_manager.get_questions_by_subject(
-        &subject_name,
-        key_stage,
-        difficulty_range,
-        limit,
-    ).map_err(|e| e.to_string())
+    session_token: String,
+) -> Result<Vec<ClassGroup>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.get_classes()
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_question_by_id(
+async fn delete_class(
     state: State<'_, AppState>,
-    question_id: u32,
-) -> Result<Question, String> {
-    state.content_manager.get_question_by_id(question_id)
+    session_token: String,
+    class_id: u32,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.delete_class(class_id)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn add_question(
+async fn add_class_members(
     state: State<'_, AppState>,
-    question: Question,
-) -> Result<u32, String> {
-    state.content_manager.add_question(question)
+    session_token: String,
+    class_id: u32,
+    profile_ids: Vec<ProfileId>,
+) -> Result<ClassGroup, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.add_members(class_id, &profile_ids)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn update_question(
+async fn remove_class_member(
     state: State<'_, AppState>,
-    question_id: u32,
-    question: Question,
-) -> Result<(), String> {
-    state.content_manager.update_question(question_id, question)
+    session_token: String,
+    class_id: u32,
+    profile_id: ProfileId,
+) -> Result<ClassGroup, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.remove_member(class_id, profile_id)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn delete_question(
+async fn import_class_roster(
     state: State<'_, AppState>,
-    question_id: u32,
-) -> Result<(), String> {
-    state.content_manager.delete_question(question_id)
+    session_token: String,
+    class_id: u32,
+    csv_content: String,
+) -> Result<RosterImportResult, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.import_roster_csv(class_id, &csv_content)
         .map_err(|e| e.to_string())
 }
 
+/// Check a CSV of students before importing it: which rows would become profiles and
+/// which are duplicates or invalid
 #[tauri::command]
-async fn get_content_statistics(
+async fn preview_profiles_csv(
     state: State<'_, AppState>,
-) -> Result<ContentStatistics, String> {
-    state.content_manager.get_content_statistics()
+    session_token: String,
+    file_path: String,
+    columns: Option<ProfileCsvColumns>,
+) -> Result<ProfileImportPreview, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.preview_profiles_csv(std::path::Path::new(&file_path), columns)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn load_content_pack(
+async fn import_profiles_csv(
     state: State<'_, AppState>,
-    pack_path: String,
-) -> Result<(), String> {
-    let path = std::path::Path::new(&pack_path);
-    state.content_manager.load_content_pack(path)
+    session_token: String,
+    file_path: String,
+    columns: Option<ProfileCsvColumns>,
+) -> Result<RosterImportResult, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.import_profiles_csv(std::path::Path::new(&file_path), columns)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn verify_content_signature(
+async fn get_profile_school_details(
     state: State<'_, AppState>,
-    pack: ContentPack,
-) -> Result<bool, String> {
-    state.content_manager.verify_content_signature(&pack)
+    profile_id: ProfileId,
+) -> Result<Option<ProfileSchoolDetails>, String> {
+    state.classroom_manager.get_school_details(profile_id)
         .map_err(|e| e.to_string())
 }
 
-// ============================================================================
-// CONTENT SEEDING COMMANDS
-// ============================================================================
-
 #[tauri::command]
-async fn seed_all_content(
+async fn assign_to_class(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    state.content_seeder.seed_all_content()
-        .map_err(|e| e.to_string())
+    session_token: String,
+    class_id: u32,
+    request: CreateAssignmentRequest,
+) -> Result<Vec<Assignment>, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    let assignments = state.classroom_manager.assign_to_class(class_id, request)
+        .map_err(|e| e.to_string())?;
+    refresh_tray_badge(&app, &state);
+    refresh_calendar_feeds(&state);
+    Ok(assignments)
 }
 
 #[tauri::command]
-async fn is_content_seeded(
+async fn get_class_report(
     state: State<'_, AppState>,
-) -> Result<bool, String> {
-    state.content_seeder.is_content_seeded()
+    session_token: String,
+    class_id: u32,
+) -> Result<ClassReport, String> {
+    require_permission!(state, Permission::Parent, session_token);
+    require_classroom_mode(&state)?;
+
+    state.classroom_manager.get_class_report(class_id)
         .map_err(|e| e.to_string())
 }
 
+// ============================================================================
+// ACCESSIBILITY COMMANDS
+// ============================================================================
+
 #[tauri::command]
-async fn seed_if_empty(
+async fn get_accessibility_settings(
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    state.content_seeder.seed_if_empty()
+    profile_id: ProfileId,
+) -> Result<AccessibilitySettings, String> {
+    state.accessibility.get_settings(profile_id)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn reset_and_reseed_database(
+async fn set_accessibility_settings(
     state: State<'_, AppState>,
+    session_token: String,
+    settings: AccessibilitySettings,
 ) -> Result<(), String> {
-    // Clear all questions by deleting them from the database
-    state.database.manager().execute(|conn| {
-        conn.execute("DELETE FROM questions", [])?;
-        Ok(())
-    }).map_err(|e| e.to_string())?;
-    
-    // Reseed with correct format
-    state.content_seeder.seed_all_content()
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.accessibility.set_settings(&settings)
         .map_err(|e| e.to_string())
 }
 
+/// Set the simplified-vocabulary wording of a question; `None` removes it
 #[tauri::command]
-async fn get_seeder_statistics(
+async fn set_question_simplified_text(
     state: State<'_, AppState>,
-) -> Result<ContentStatistics, String> {
-    state.content_manager.get_content_statistics()
+    session_token: String,
+    question_id: QuestionId,
+    simplified_text: Option<String>,
+) -> Result<Question, String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.accessibility.set_simplified_text(question_id, simplified_text)
         .map_err(|e| e.to_string())
 }
 
 // ============================================================================
-// CUSTOM MIX COMMANDS
+// WELLBEING COMMANDS
 // ============================================================================
 
-#[tauri::command]
-async fn create_custom_mix(
-    state: State<'_, AppState>,
-    request: CreateMixRequest,
-) -> Result<CustomMix, String> {
-    state.custom_mix_manager.create_custom_mix(request)
-        .map_err(|e| e.to_string())
+/// Count quiz activity towards the profile's break, telling the UI once a break is due so it
+/// can show the break screen when the current quiz ends
+fn record_wellbeing_activity(app: &tauri::AppHandle, wellbeing: &WellbeingService, profile_id: ProfileId) {
+    match wellbeing.record_activity(profile_id, chrono::Utc::now()) {
+        Ok(status) if status.on_break => {
+            if let Err(e) = app.emit_all("wellbeing-break-due", &status) {
+                eprintln!("Warning: Failed to announce break: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: Failed to record quiz activity: {}", e),
+    }
 }
 
 #[tauri::command]
-async fn get_custom_mix_by_id(
+async fn get_break_status(
     state: State<'_, AppState>,
-    mix_id: u32,
-) -> Result<CustomMix, String> {
-    state.custom_mix_manager.get_custom_mix_by_id(mix_id)
+    profile_id: ProfileId,
+) -> Result<BreakStatus, String> {
+    state.wellbeing.get_status(profile_id, chrono::Utc::now())
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_all_custom_mixes(
+async fn get_break_settings(
     state: State<'_, AppState>,
-) -> Result<Vec<CustomMix>, String> {
-    state.custom_mix_manager.get_all_custom_mixes()
+    profile_id: ProfileId,
+) -> Result<BreakSettings, String> {
+    state.wellbeing.get_settings(profile_id)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_custom_mixes_by_profile(
+async fn set_break_settings(
     state: State<'_, AppState>,
-    profile_id: u32,
-) -> Result<Vec<CustomMix>, String> {
-    state.custom_mix_manager.get_custom_mixes_by_profile(profile_id)
-        .map_err(|e| e.to_string())
-}
+    session_token: String,
+    settings: BreakSettings,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
 
-#[tauri::command]
-async fn update_custom_mix(
-    state: State<'_, AppState>,
-    mix_id: u32,
-    updates: UpdateMixRequest,
-) -> Result<CustomMix, String> {
-    state.custom_mix_manager.update_custom_mix(mix_id, updates)
+    state.wellbeing.set_settings(&settings)
         .map_err(|e| e.to_string())
 }
 
+/// Let a parent end a profile's break early
 #[tauri::command]
-async fn delete_custom_mix(
+async fn end_break(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-    mix_id: u32,
+    session_token: String,
+    profile_id: ProfileId,
 ) -> Result<(), String> {
-    state.custom_mix_manager.delete_custom_mix(mix_id)
-        .map_err(|e| e.to_string())
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.wellbeing.end_break(profile_id)
+        .map_err(|e| e.to_string())?;
+
+    let status = state.wellbeing.get_status(profile_id, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+    if let Err(e) = app.emit_all("wellbeing-break-ended", &status) {
+        eprintln!("Warning: Failed to announce end of break: {}", e);
+    }
+    Ok(())
 }
 
+// ============================================================================
+// NOTIFICATION COMMANDS
+// ============================================================================
+
 #[tauri::command]
-async fn get_available_question_count(
+async fn get_notification_preferences(
     state: State<'_, AppState>,
-    config: MixConfig,
-) -> Result<u32, String> {
-    state.custom_mix_manager.get_available_question_count(&config)
+    profile_id: ProfileId,
+) -> Result<NotificationPreferences, String> {
+    state.notifications.get_preferences(profile_id)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn validate_mix_feasibility(
+async fn set_notification_preferences(
     state: State<'_, AppState>,
-    config: MixConfig,
+    session_token: String,
+    preferences: NotificationPreferences,
 ) -> Result<(), String> {
-    state.custom_mix_manager.validate_mix_feasibility(&config)
-        .map_err(|e| e.to_string())
-}
+    require_permission!(state, Permission::Parent, session_token);
 
-// ============================================================================
-// SECURITY COMMANDS
-// ============================================================================
+    state.notifications.set_preferences(&preferences)
+        .map_err(|e| e.to_string())?;
+    refresh_calendar_feeds(&state);
+    Ok(())
+}
 
 #[tauri::command]
-async fn validate_parental_access(
+async fn get_notification_templates(
     state: State<'_, AppState>,
-    challenge: String,
-    input: String,
-) -> Result<bool, String> {
-    state.security_service.validate_parental_access(&challenge, &input)
+) -> Result<Vec<NotificationTemplate>, String> {
+    state.notifications.get_templates()
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn generate_parental_challenge(
+async fn set_notification_template(
     state: State<'_, AppState>,
-) -> Result<ParentalChallenge, String> {
-    state.security_service.generate_parental_challenge()
+    session_token: String,
+    kind: NotificationKind,
+    title: String,
+    body: String,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.notifications.set_template(kind, &title, &body)
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn validate_parental_feature_access(
+async fn reset_notification_template(
     state: State<'_, AppState>,
-    feature: String,
     session_token: String,
-) -> Result<bool, String> {
-    state.security_service.validate_parental_feature_access(&feature, &session_token)
+    kind: NotificationKind,
+) -> Result<(), String> {
+    require_permission!(state, Permission::Parent, session_token);
+
+    state.notifications.reset_template(kind)
         .map_err(|e| e.to_string())
 }
 
+/// Show a notification through the OS notification centre
+fn show_notification(app_handle: &tauri::AppHandle, notification: &PendingNotification) {
+    let identifier = app_handle.config().tauri.bundle.identifier.clone();
+    if let Err(e) = tauri::api::notification::Notification::new(identifier)
+        .title(&notification.title)
+        .body(&notification.body)
+        .show()
+    {
+        eprintln!("Warning: Failed to show notification: {}", e);
+    }
+}
+
+/// Check for due reminders once a minute until shutdown
+fn run_notification_scheduler(app_handle: tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let shutdown_signal = state.shutdown.subscribe();
+
+    while !*shutdown_signal.borrow() {
+        match state.notifications.take_due_notifications(chrono::Local::now().naive_local()) {
+            Ok(due) => {
+                for notification in &due {
+                    show_notification(&app_handle, notification);
+                }
+            }
+            Err(e) => eprintln!("Warning: Failed to check notifications: {}", e),
+        }
+
+        // Assignments drop off the badge once they pass their due date
+        refresh_tray_badge(&app_handle, &state);
+
+        // Failures are recorded by the service and shown in diagnostics
+        if let Err(e) = state.email_reports.send_due_report(chrono::Local::now().naive_local()) {
+            eprintln!("Warning: Failed to send weekly email report: {}", e);
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(60));
+    }
+}
+
+// ============================================================================
+// SYSTEM TRAY COMMANDS
+// ============================================================================
+
+const TRAY_DAILY_CHALLENGE: &str = "daily_challenge";
+const TRAY_PARENT_DASHBOARD: &str = "parent_dashboard";
+const TRAY_TOGGLE_REMINDERS: &str = "toggle_reminders";
+const TRAY_QUIT: &str = "quit";
+
+const TRAY_PAUSE_LABEL: &str = "Pause reminders for today";
+const TRAY_RESUME_LABEL: &str = "Resume reminders";
+
 #[tauri::command]
-async fn generate_parental_session_token(
+async fn pause_reminders(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    state.security_service.generate_parental_session_token()
-        .map_err(|e| e.to_string())
+) -> Result<chrono::NaiveDateTime, String> {
+    let until = pause_reminders_for_today(&app, &state).map_err(|e| e.to_string())?;
+    Ok(until)
 }
 
 #[tauri::command]
-async fn get_quiz_progress(
+async fn resume_reminders(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-    session_id: u32,
-) -> Result<QuizProgress, String> {
-    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
-    
-    quiz_engine.get_quiz_progress(session_id)
-        .map_err(|e| e.to_string())
+) -> Result<(), String> {
+    state.notifications.resume_reminders().map_err(|e| e.to_string())?;
+    set_tray_item_title(&app, TRAY_TOGGLE_REMINDERS, TRAY_PAUSE_LABEL);
+    Ok(())
 }
 
 #[tauri::command]
-async fn verify_update_signature(
+async fn get_reminders_paused_until(
     state: State<'_, AppState>,
-    update_data: Vec<u8>,
-    signature: Vec<u8>,
-) -> Result<bool, String> {
-    state.security_service.verify_update_signature(&update_data, &signature)
+) -> Result<Option<chrono::NaiveDateTime>, String> {
+    state.notifications.reminders_paused_until(chrono::Local::now().naive_local())
         .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-async fn encrypt_sensitive_data(
-    state: State<'_, AppState>,
-    data: Vec<u8>,
-) -> Result<Vec<u8>, String> {
-    state.security_service.encrypt_sensitive_data(&data)
-        .map_err(|e| e.to_string())
+fn build_system_tray() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new(TRAY_DAILY_CHALLENGE, "Start daily challenge"))
+        .add_item(CustomMenuItem::new(TRAY_PARENT_DASHBOARD, "Open parent dashboard"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(TRAY_TOGGLE_REMINDERS, TRAY_PAUSE_LABEL))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new(TRAY_QUIT, "Quit QuizDD"));
+
+    SystemTray::new().with_menu(menu)
 }
 
-#[tauri::command]
-async fn decrypt_sensitive_data(
-    state: State<'_, AppState>,
-    encrypted_data: Vec<u8>,
-) -> Result<Vec<u8>, String> {
-    state.security_service.decrypt_sensitive_data(&encrypted_data)
-        .map_err(|e| e.to_string())
+/// Run the shutdown hooks, if they haven't run already, and report any that failed
+fn run_shutdown(shutdown: &ShutdownCoordinator) {
+    if let Some(report) = shutdown.shutdown() {
+        for (hook, error) in &report.failed_hooks {
+            eprintln!("❌ Shutdown step '{}' failed: {}", hook, error);
+        }
+    }
 }
 
-#[tauri::command]
-async fn verify_content_package(
-    state: State<'_, AppState>,
-    package_data: Vec<u8>,
-    expected_hash: String,
-) -> Result<bool, String> {
-    state.security_service.verify_content_package(&package_data, &expected_hash)
-        .map_err(|e| e.to_string())
+fn handle_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => show_main_window(app),
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            // The main window owns navigation and the parental challenge, so these just hand over
+            TRAY_DAILY_CHALLENGE | TRAY_PARENT_DASHBOARD => {
+                show_main_window(app);
+                if let Err(e) = app.emit_to(MAIN_WINDOW_LABEL, "tray-action", id.as_str()) {
+                    eprintln!("Warning: Failed to forward tray action: {}", e);
+                }
+            }
+            TRAY_TOGGLE_REMINDERS => {
+                let state = app.state::<AppState>();
+                let paused = state.notifications
+                    .reminders_paused_until(chrono::Local::now().naive_local())
+                    .map(|until| until.is_some())
+                    .unwrap_or(false);
+
+                let result = if paused {
+                    state.notifications.resume_reminders()
+                        .map(|_| set_tray_item_title(app, TRAY_TOGGLE_REMINDERS, TRAY_PAUSE_LABEL))
+                } else {
+                    pause_reminders_for_today(app, &state).map(|_| ())
+                };
+                if let Err(e) = result {
+                    eprintln!("Warning: Failed to toggle reminders: {}", e);
+                }
+            }
+            TRAY_QUIT => {
+                // `exit` ends the process without delivering `RunEvent::Exit`, so shut down first
+                run_shutdown(&app.state::<AppState>().shutdown);
+                app.exit(0);
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// Pause reminders until local midnight and reflect it in the tray menu
+fn pause_reminders_for_today(app: &tauri::AppHandle, state: &AppState) -> AppResult<chrono::NaiveDateTime> {
+    let until = (chrono::Local::now().date_naive() + chrono::Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .ok_or_else(|| AppError::Internal("Invalid pause time".to_string()))?;
+
+    state.notifications.pause_reminders(until)?;
+    set_tray_item_title(app, TRAY_TOGGLE_REMINDERS, TRAY_RESUME_LABEL);
+    Ok(until)
+}
+
+/// Show the number of pending assignments on the tray icon
+fn refresh_tray_badge(app: &tauri::AppHandle, state: &AppState) {
+    let pending_count = match state.assignment_manager.count_pending_assignments() {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("Warning: Failed to count pending assignments: {}", e);
+            return;
+        }
+    };
+
+    let tooltip = match pending_count {
+        0 => "QuizDD".to_string(),
+        1 => "QuizDD - 1 assignment pending".to_string(),
+        count => format!("QuizDD - {} assignments pending", count),
+    };
+
+    let tray = app.tray_handle();
+    if let Err(e) = tray.set_tooltip(&tooltip) {
+        eprintln!("Warning: Failed to update tray tooltip: {}", e);
+    }
+
+    // Only macOS can draw text next to the tray icon
+    #[cfg(target_os = "macos")]
+    {
+        let title = if pending_count == 0 { String::new() } else { pending_count.to_string() };
+        if let Err(e) = tray.set_title(&title) {
+            eprintln!("Warning: Failed to update tray badge: {}", e);
+        }
+    }
+}
+
+fn set_tray_item_title(app: &tauri::AppHandle, item_id: &str, title: &str) {
+    if let Err(e) = app.tray_handle().get_item(item_id).set_title(title) {
+        eprintln!("Warning: Failed to update tray menu: {}", e);
+    }
+}
+
+fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_window(MAIN_WINDOW_LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
 }
 
 // ============================================================================
-// UPDATE SERVICE COMMANDS
+// PARENT DASHBOARD COMMANDS
 // ============================================================================
 
+/// Reject the command unless the calling window holds a session granting at least `permission`.
+/// Dashboard commands use this instead of a token argument so only the dashboard window can call them.
+macro_rules! require_window_permission {
+    ($state:expr, $window:expr, $permission:expr) => {
+        $state.window_sessions
+            .authorize($window.label(), $permission)
+            .map_err(|e| e.to_string())?
+    };
+}
+
+/// Live activity pushed from the child's quiz to the parent dashboard
+#[derive(Debug, Clone, Serialize)]
+pub struct QuizActivityEvent {
+    pub session_id: SessionId,
+    pub question_id: QuestionId,
+    pub is_correct: bool,
+    pub progress: Option<QuizProgress>,
+}
+
+/// A gentle nudge for a child who seems to be rushing through answers
+#[derive(Debug, Clone, Serialize)]
+pub struct SlowDownEvent {
+    pub session_id: SessionId,
+    pub message: String,
+}
+
+/// Let an open parent dashboard follow along live, and nudge the child if they're rushing
+fn notify_quiz_activity(app: &tauri::AppHandle, quiz_engine: &QuizEngine, session_id: SessionId, result: &AnswerResult) {
+    let activity = QuizActivityEvent {
+        session_id,
+        question_id: result.question_id,
+        is_correct: result.is_correct,
+        progress: quiz_engine.get_quiz_progress(session_id).ok(),
+    };
+    if let Err(e) = app.emit_to(PARENT_DASHBOARD_LABEL, "quiz-activity", activity) {
+        eprintln!("Warning: Failed to notify parent dashboard: {}", e);
+    }
+
+    if quiz_engine.take_slow_down(session_id).unwrap_or(false) {
+        let event = SlowDownEvent {
+            session_id,
+            message: SLOW_DOWN_MESSAGE.to_string(),
+        };
+        if let Err(e) = app.emit_all("slow-down", &event) {
+            eprintln!("Warning: Failed to send slow-down nudge: {}", e);
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DashboardProfileSummary {
+    pub profile: Profile,
+    pub progress: Progress,
+    pub active_sessions: Vec<QuizProgress>,
+    pub assignments: AssignmentSummary,
+}
+
 #[tauri::command]
-async fn check_for_updates(
+async fn open_parent_dashboard(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<Vec<UpdateInfo>, String> {
-    state.update_service.check_for_updates().await
-        .map_err(|e| e.to_string())
+    session_token: String,
+) -> Result<WindowSession, String> {
+    let session = state.window_sessions.open(PARENT_DASHBOARD_LABEL, &session_token)
+        .map_err(|e| e.to_string())?;
+    
+    match app.get_window(PARENT_DASHBOARD_LABEL) {
+        Some(window) => window.set_focus().map_err(|e| e.to_string())?,
+        None => {
+            tauri::WindowBuilder::new(
+                &app,
+                PARENT_DASHBOARD_LABEL,
+                tauri::WindowUrl::App("index.html#/parent-dashboard".into()),
+            )
+            .title("QuizDD - Parent Dashboard")
+            .inner_size(1000.0, 700.0)
+            .min_inner_size(800.0, 600.0)
+            .build()
+            .map_err(|e| format!("Failed to open parent dashboard: {}", e))?;
+        }
+    }
+    
+    Ok(session)
 }
 
 #[tauri::command]
-async fn download_and_install_update(
+async fn close_parent_dashboard(
+    app: tauri::AppHandle,
     state: State<'_, AppState>,
-    update_info: UpdateInfo,
 ) -> Result<(), String> {
-    state.update_service.download_and_install_update(&update_info).await
-        .map_err(|e| e.to_string())
+    state.window_sessions.close(PARENT_DASHBOARD_LABEL)
+        .map_err(|e| e.to_string())?;
+    
+    if let Some(window) = app.get_window(PARENT_DASHBOARD_LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    
+    Ok(())
 }
 
 #[tauri::command]
-async fn rollback_to_backup(
+async fn get_dashboard_session(
+    window: tauri::Window,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    state.update_service.rollback_to_backup().await
+) -> Result<Option<WindowSession>, String> {
+    state.window_sessions.get_session(window.label())
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_current_version(
+async fn get_dashboard_overview(
+    window: tauri::Window,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    state.update_service.get_current_version().await
-        .map_err(|e| e.to_string())
+) -> Result<Vec<DashboardProfileSummary>, String> {
+    require_window_permission!(state, window, Permission::Parent);
+    
+    let profiles = state.profile_manager.get_all_profiles()
+        .map_err(|e| e.to_string())?;
+    let quiz_engine = state.quiz_engine.lock().map_err(|e| format!("Lock error: {}", e))?;
+    
+    let mut summaries = Vec::new();
+    for profile in profiles {
+        let profile_id = match profile.id {
+            Some(id) => id,
+            None => continue,
+        };
+        
+        let progress = state.profile_manager.get_progress(profile_id)
+            .map_err(|e| e.to_string())?;
+        let active_sessions = quiz_engine.get_active_sessions(Some(profile_id))
+            .into_iter()
+            .filter_map(|session| session.id)
+            .filter_map(|session_id| quiz_engine.get_quiz_progress(session_id).ok())
+            .collect();
+        
+        let assignments = state.assignment_manager
+            .get_summary(profile_id, chrono::Utc::now() - chrono::Duration::days(7))
+            .map_err(|e| e.to_string())?;
+        
+        summaries.push(DashboardProfileSummary { profile, progress, active_sessions, assignments });
+    }
+    
+    Ok(summaries)
 }
 
 #[tauri::command]
-async fn list_backups(
+async fn get_dashboard_profile_progress(
+    window: tauri::Window,
     state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    state.update_service.list_backups().await
+    profile_id: ProfileId,
+) -> Result<Progress, String> {
+    require_window_permission!(state, window, Permission::Parent);
+    
+    state.profile_manager.get_progress(profile_id)
         .map_err(|e| e.to_string())
 }
 
@@ -671,62 +5151,19 @@ pub struct AppSettings {
 
 #[tauri::command]
 async fn save_settings(
+    state: State<'_, AppState>,
     settings: AppSettings,
 ) -> Result<(), String> {
-    // Save settings to a local file in the app data directory
-    let app_data_dir = tauri::api::path::app_data_dir(&tauri::Config::default())
-        .ok_or("Failed to get app data directory")?;
-    
-    let settings_path = app_data_dir.join("settings.json");
-    
-    // Ensure the directory exists
-    if let Some(parent) = settings_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
-    }
-    
-    let settings_json = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-    
-    std::fs::write(&settings_path, settings_json)
-        .map_err(|e| format!("Failed to write settings file: {}", e))?;
-    
-    Ok(())
+    write_app_settings(&state.settings_path, &settings)
 }
 
 #[tauri::command]
-async fn load_settings() -> Result<AppSettings, String> {
-    let app_data_dir = tauri::api::path::app_data_dir(&tauri::Config::default())
-        .ok_or("Failed to get app data directory")?;
-    
-    let settings_path = app_data_dir.join("settings.json");
-    
-    if !settings_path.exists() {
-        // Return default settings if file doesn't exist
-        return Ok(AppSettings {
-            theme: "default".to_string(),
-            font_size: "medium".to_string(),
-            sound_enabled: true,
-            animations_enabled: true,
-            high_contrast_mode: false,
-            reduced_motion: false,
-            auto_save: true,
-            parental_controls_enabled: true,
-        });
-    }
-    
-    let settings_content = std::fs::read_to_string(&settings_path)
-        .map_err(|e| format!("Failed to read settings file: {}", e))?;
-    
-    let settings: AppSettings = serde_json::from_str(&settings_content)
-        .map_err(|e| format!("Failed to parse settings: {}", e))?;
-    
-    Ok(settings)
+async fn load_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+    read_app_settings(&state.settings_path)
 }
 
-#[tauri::command]
-async fn reset_settings() -> Result<AppSettings, String> {
-    let default_settings = AppSettings {
+fn default_app_settings() -> AppSettings {
+    AppSettings {
         theme: "default".to_string(),
         font_size: "medium".to_string(),
         sound_enabled: true,
@@ -735,21 +5172,54 @@ async fn reset_settings() -> Result<AppSettings, String> {
         reduced_motion: false,
         auto_save: true,
         parental_controls_enabled: true,
-    };
+    }
+}
+
+/// Saved settings, or the defaults if none have been saved yet
+fn read_app_settings(settings_path: &std::path::Path) -> Result<AppSettings, String> {
+    if !settings_path.exists() {
+        return Ok(default_app_settings());
+    }
+    
+    let settings_content = std::fs::read_to_string(settings_path)
+        .map_err(|e| format!("Failed to read settings file: {}", e))?;
+    
+    serde_json::from_str(&settings_content)
+        .map_err(|e| format!("Failed to parse settings: {}", e))
+}
+
+fn write_app_settings(settings_path: &std::path::Path, settings: &AppSettings) -> Result<(), String> {
+    // Ensure the directory exists
+    if let Some(parent) = settings_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+    
+    let settings_json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    
+    std::fs::write(settings_path, settings_json)
+        .map_err(|e| format!("Failed to write settings file: {}", e))
+}
+
+#[tauri::command]
+async fn reset_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+    let default_settings = default_app_settings();
     
     // Save the default settings
-    save_settings(default_settings.clone()).await?;
+    write_app_settings(&state.settings_path, &default_settings)?;
     
     Ok(default_settings)
 }
 
 #[tauri::command]
 async fn update_setting(
+    state: State<'_, AppState>,
     key: String,
     value: Value,
 ) -> Result<AppSettings, String> {
     // Load current settings
-    let mut settings = load_settings().await?;
+    let mut settings = read_app_settings(&state.settings_path)?;
     
     // Update the specific setting
     match key.as_str() {
@@ -797,7 +5267,7 @@ async fn update_setting(
     }
     
     // Save the updated settings
-    save_settings(settings.clone()).await?;
+    write_app_settings(&state.settings_path, &settings)?;
     
     Ok(settings)
 }
@@ -826,22 +5296,134 @@ async fn get_database_version(state: State<'_, AppState>) -> Result<u32, String>
         .map_err(|e| format!("Failed to get database version: {}", e))
 }
 
+/// Record the latest seeding progress and publish it as a `seeding-progress` event
+fn publish_seeding_progress(app_handle: &tauri::AppHandle, state: &AppState, progress: &SeedingProgress) {
+    if let Ok(mut latest) = state.seeding_progress.lock() {
+        *latest = Some(progress.clone());
+    }
+    if let Err(e) = app_handle.emit_all("seeding-progress", progress) {
+        eprintln!("Warning: Failed to emit seeding progress: {}", e);
+    }
+}
+
+/// Publish a failed or cancelled seeding run, keeping the step counts it had reached
+fn publish_seeding_failure(app_handle: &tauri::AppHandle, state: &AppState, error: &AppError) {
+    let cancelled = matches!(error, AppError::Cancelled(_));
+    if !cancelled {
+        eprintln!("Warning: Failed to seed database content: {}", error);
+    }
+    let latest = state.seeding_progress.lock()
+        .ok()
+        .and_then(|latest| latest.clone());
+    let (completed_steps, total_steps, questions_inserted, questions_total) = latest
+        .map(|p| (p.completed_steps, p.total_steps, p.questions_inserted, p.questions_total))
+        .unwrap_or((0, 0, 0, 0));
+    publish_seeding_progress(app_handle, state, &SeedingProgress {
+        current_step: if cancelled { "Cancelled" } else { "Failed" }.to_string(),
+        subject: None,
+        completed_steps,
+        total_steps,
+        // A cancelled run removes what it inserted
+        questions_inserted: if cancelled { 0 } else { questions_inserted },
+        questions_total,
+        finished: true,
+        cancelled,
+        error: if cancelled { None } else { Some(error.to_string()) },
+    });
+}
+
+/// Seed the database if needed, publishing progress as `seeding-progress` events. New
+/// installs seed the subjects chosen during onboarding instead, and installs onboarded that
+/// way only have the content they chose.
+fn run_background_seeding(app_handle: &tauri::AppHandle, state: &AppState) {
+    println!("🌱 Checking if seeding is needed...");
+    match state.onboarding.get_state() {
+        Ok(onboarding) if !onboarding.is_complete() => {
+            println!("🧭 Onboarding not finished, content will be seeded when subjects are chosen");
+            return;
+        }
+        Ok(onboarding) if onboarding.subjects.is_some() => return,
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: Failed to read onboarding state: {}", e),
+    }
+
+    let result = state.content_seeder.get()
+        .and_then(|content_seeder| content_seeder.seed_if_empty_with_progress(|progress| {
+            publish_seeding_progress(app_handle, state, progress);
+        }));
+
+    match result {
+        Ok(()) => println!("✅ Database seeding completed"),
+        Err(e) => publish_seeding_failure(app_handle, state, &e),
+    }
+}
+
 fn main() {
     println!("🚀 Starting QuiZDD application...");
     
-    // Initialize database
-    println!("📁 Getting app data directory...");
-    let app_data_dir = tauri::api::path::app_data_dir(&tauri::Config::default())
-        .expect("Failed to get app data directory");
+    // Portable installs keep everything next to the executable and never touch AppData
+    let portable_dir = std::env::current_exe()
+        .ok()
+        .and_then(|executable| portable_data_dir(&executable, std::env::args().skip(1)));
     
-    // Ensure app data directory exists
-    println!("📁 Creating app data directory...");
-    std::fs::create_dir_all(&app_data_dir)
-        .expect("Failed to create app data directory");
+    let (app_data_dir, default_app_dir) = match portable_dir {
+        Some(portable_dir) => {
+            println!("🧳 Running in portable mode from {:?}", portable_dir);
+            std::fs::create_dir_all(&portable_dir)
+                .expect("Failed to create portable data directory next to the executable");
+            
+            // Keep the WebView profile on the portable drive as well
+            #[cfg(windows)]
+            std::env::set_var("WEBVIEW2_USER_DATA_FOLDER", portable_dir.join("webview"));
+            
+            (portable_dir.clone(), portable_dir)
+        }
+        None => {
+            // Initialize database
+            println!("📁 Getting app data directory...");
+            let app_data_dir = tauri::api::path::app_data_dir(&tauri::Config::default())
+                .expect("Failed to get app data directory");
+            
+            // Ensure app data directory exists
+            println!("📁 Creating app data directory...");
+            std::fs::create_dir_all(&app_data_dir)
+                .expect("Failed to create app data directory");
+            
+            let default_app_dir = app_data_dir.join("Educational Quiz App");
+            std::fs::create_dir_all(&default_app_dir)
+                .expect("Failed to create app-specific directory");
+            
+            (app_data_dir, default_app_dir)
+        }
+    };
     
-    let app_specific_dir = app_data_dir.join("Educational Quiz App");
+    // Load config.toml (or $QUIZDD_CONFIG) with QUIZDD_* environment overrides
+    let config_path = AppConfig::resolve_path(&default_app_dir);
+    println!("⚙️ Loading configuration from {:?}...", config_path);
+    let config = match AppConfig::load(&config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Warning: {}; falling back to default configuration", e);
+            AppConfig::default()
+        }
+    };
+    let (app_data_dir, app_specific_dir) = match config.data_directory.clone() {
+        Some(data_directory) => (data_directory.clone(), data_directory),
+        None => (app_data_dir, default_app_dir),
+    };
     std::fs::create_dir_all(&app_specific_dir)
-        .expect("Failed to create app-specific directory");
+        .expect("Failed to create data directory");
+    
+    // Capture panics and log records to the local log directory as early as possible
+    let crash_dir = app_specific_dir.join("crashes");
+    CrashReporter::install_panic_hook(crash_dir.clone());
+    let log_level = config.log_level_filter().unwrap_or_else(|e| {
+        eprintln!("Warning: {}; logging at info", e);
+        log::LevelFilter::Info
+    });
+    if let Err(e) = FileLogger::open(&crash_dir, log_level).and_then(FileLogger::install) {
+        eprintln!("Warning: {}", e);
+    }
     
     let db_path = app_specific_dir.join("educational_quiz_app.db");
     let content_dir = app_specific_dir.join("content");
@@ -854,6 +5436,13 @@ fn main() {
     std::fs::create_dir_all(&content_dir)
         .expect("Failed to create content directory");
     
+    // A restore confirmed in the last run replaces the database before anything opens it
+    match apply_pending_restore(&db_path, &app_data_dir.join(RESTORE_DIRECTORY)) {
+        Ok(true) => println!("♻️ Restored the database from a backup"),
+        Ok(false) => {}
+        Err(e) => eprintln!("Warning: Failed to restore the backup: {}", e),
+    }
+    
     println!("🗄️ Creating database service...");
     let database_service = DatabaseService::new(&db_path)
         .expect("Failed to create database service");
@@ -862,18 +5451,8 @@ fn main() {
     database_service.initialize()
         .expect("Failed to initialize database");
 
-    // Ensure database is seeded with content
-    println!("🌱 Creating content seeder...");
-    let content_seeder = quizdd::services::ContentSeeder::new(database_service.manager());
-    
-    println!("🌱 Checking if seeding is needed...");
-    if let Err(e) = content_seeder.seed_if_empty() {
-        eprintln!("Warning: Failed to seed database content: {}", e);
-    }
-    println!("✅ Database seeding completed");
-
     println!("🏗️ Creating application state...");
-    let app_state = match AppState::new(database_service, content_dir, app_data_dir) {
+    let app_state = match AppState::new(database_service, content_dir, app_data_dir, crash_dir, &config) {
         Ok(state) => {
             println!("✅ Application state created successfully");
             state
@@ -884,6 +5463,34 @@ fn main() {
         }
     };
 
+    let session_snapshot_path = app_specific_dir.join("sessions.json");
+    match app_state.quiz_engine.lock() {
+        Ok(quiz_engine) => match quiz_engine.restore_sessions_snapshot(&session_snapshot_path) {
+            Ok(0) => {}
+            Ok(count) => println!("💾 Restored {} quiz session(s) from the previous run", count),
+            Err(e) => eprintln!("Warning: Failed to restore quiz sessions: {}", e),
+        },
+        Err(e) => eprintln!("Warning: Failed to lock quiz engine: {}", e),
+    }
+    app_state.register_shutdown_hooks(session_snapshot_path);
+
+    match app_state.crash_reporter.get_unacknowledged_reports() {
+        Ok(reports) if !reports.is_empty() => {
+            println!("💥 {} crash report(s) from previous sessions awaiting review", reports.len());
+        }
+        Ok(_) => {}
+        Err(e) => eprintln!("Warning: Failed to read crash reports: {}", e),
+    }
+
+    match app_state.storage.enforce_cap() {
+        Ok(Some(report)) => println!("💽 Storage was over its cap, freed {} bytes", report.bytes_freed),
+        Ok(None) => {}
+        Err(e) => eprintln!("Warning: Failed to check storage usage: {}", e),
+    }
+
+    app_state.telemetry.record_session_start();
+    let shutdown = app_state.shutdown.clone();
+
     println!("🚀 Building Tauri application...");
     tauri::Builder::default()
         .manage(app_state)
@@ -891,12 +5498,91 @@ fn main() {
             // Quiz Engine Commands
             get_questions,
             validate_answer,
+            evaluate_expression,
+            suggest_distractors,
             start_quiz_session,
+            start_assessment_session,
+            start_pass_and_play_session,
+            get_pass_and_play_summary,
+            start_coop_session,
+            get_coop_summary,
+            start_head_to_head_session,
+            finish_head_to_head_session,
+            get_head_to_head_results,
+            start_review_session,
+            get_due_review_questions,
+            record_review_result,
+            start_multiplication_check,
+            next_multiplication_check_question,
+            submit_multiplication_check_answer,
+            get_multiplication_check_result,
+            get_multiplication_check_readiness,
+            record_session_focus,
             submit_answer,
             get_current_question,
             calculate_score,
+            generate_result_card,
             pause_quiz,
             resume_quiz,
+            get_resumable_sessions,
+            get_boss_question_stats,
+            get_quiz_incidents,
+            
+            // Quiz Preset Commands
+            get_quiz_presets,
+            set_quiz_preset,
+            reset_quiz_preset,
+            quick_play,
+            
+            // Difficulty Curve Commands
+            get_difficulty_curves,
+            set_difficulty_curve,
+            clear_difficulty_curve,
+            
+            // Voice Input Commands
+            interpret_voice_answer,
+            transcribe_voice_answer,
+            submit_voice_answer,
+            get_voice_provider,
+            get_voice_thresholds,
+            set_voice_thresholds,
+            
+            // Phonics Commands
+            generate_phonics_questions,
+            get_phonics_synthesizer,
+            start_phonics_screening,
+            get_phonics_screening,
+            mark_phonics_screening_word,
+            get_phonics_screening_result,
+            get_phonics_grapheme_accuracy,
+            
+            // Explore Commands
+            get_explore_topics,
+            browse_explore_topic,
+            view_explore_question,
+            reveal_explore_answer,
+            set_practice_later,
+            get_practice_later,
+            start_practice_later_session,
+            get_mistakes,
+            start_mistakes_quiz,
+            get_achievements,
+            check_achievements_after_quiz,
+            
+            // Drawn Answer Commands
+            get_session_drawings,
+            get_profile_drawings,
+            get_drawing_recognizer,
+            
+            // Scratchpad Commands
+            save_scratchpad,
+            get_scratchpad,
+            get_session_scratchpads,
+            get_profile_scratchpads,
+            
+            // Question of the Day Commands
+            get_question_of_the_day,
+            answer_question_of_the_day,
             
             // Profile Management Commands
             create_profile,
@@ -905,7 +5591,20 @@ fn main() {
             update_profile,
             delete_profile,
             get_progress,
+            get_streak,
+            get_level_info,
+            grant_streak_freezes,
+            get_household_statistics,
+            reset_progress,
+            get_progress_resets,
+            restore_progress_reset,
             update_progress,
+            generate_certificate,
+            get_paper_blueprints,
+            generate_mock_paper,
+            export_mock_paper_pdf,
+            start_mock_paper_session,
+            get_reading_age_history,
             
             // Content Management Commands
             get_subjects,
@@ -914,17 +5613,82 @@ fn main() {
             add_question,
             update_question,
             delete_question,
+            get_retired_questions,
+            restore_question,
+            purge_retired_questions,
+            get_content_change_alerts,
+            get_question_versions,
+            revert_content_change,
+            dismiss_content_change,
+            flag_question,
+            get_questions_needing_attention,
+            get_question_phrasing_stats,
             get_content_statistics,
             load_content_pack,
+            uninstall_content_pack,
+            get_question_sources,
+            delete_questions_by_source,
+            export_content_pack,
+            import_markdown_questions,
+            import_asset,
+            record_question_audio,
+            attach_question_audio_file,
+            remove_question_audio,
+            create_spelling_list,
+            get_spelling_lists,
+            delete_spelling_list,
+            get_spelling_practice_schedule,
+            get_spelling_practice,
+            submit_spelling_attempt,
+            get_spelling_readiness,
+            get_curriculum_coverage,
+            start_sandbox_session,
+            request_hint,
+            record_hint_used,
+            get_session_replay,
+            audit_question_selection,
+            preview_question,
+            render_question_preview,
+            preview_custom_mix,
+            create_gap_mix,
+            get_content_inbox_path,
+            process_content_inbox,
+            get_question_cache_stats,
             verify_content_signature,
             
+            // Reading Passage Commands
+            create_passage,
+            get_passages,
+            get_passage_group,
+            set_passage_questions,
+            delete_passage,
+            start_passage_quiz_session,
+            get_current_passage,
+            
             // Content Seeding Commands
             seed_all_content,
+            cancel_seeding,
             is_content_seeded,
             seed_if_empty,
             reset_and_reseed_database,
+            get_seeding_progress,
             get_seeder_statistics,
             
+            // Onboarding Commands
+            get_onboarding_state,
+            set_parent_credential,
+            verify_parent_credential,
+            create_first_profile,
+            choose_onboarding_subjects,
+            seed_onboarding_content,
+            
+            // Household Commands
+            get_households,
+            get_active_household,
+            select_household,
+            create_household,
+            change_parent_credential,
+            
             // Custom Mix Commands
             create_custom_mix,
             get_custom_mix_by_id,
@@ -935,11 +5699,19 @@ fn main() {
             get_available_question_count,
             validate_mix_feasibility,
             
+            // Approval Queue Commands
+            request_mix_deletion,
+            request_progress_reset,
+            get_approval_requests,
+            approve_request,
+            reject_request,
+            
             // Security Commands
             validate_parental_access,
             generate_parental_challenge,
             validate_parental_feature_access,
-            generate_parental_session_token,
+            generate_admin_session_token,
+            get_session_permission,
             get_quiz_progress,
             verify_update_signature,
             encrypt_sensitive_data,
@@ -953,6 +5725,130 @@ fn main() {
             get_current_version,
             list_backups,
             
+            // Feature Flag Commands
+            get_feature_flags,
+            is_feature_enabled,
+            set_feature_flag,
+            set_profile_feature_flag,
+            
+            // Telemetry Commands
+            get_telemetry_settings,
+            preview_telemetry_payload,
+            set_telemetry_opt_in,
+            set_telemetry_endpoint,
+            submit_telemetry,
+            
+            // Diagnostics Commands
+            get_app_diagnostics,
+            export_support_bundle,
+            
+            // Crash Report Commands
+            get_crash_reports,
+            has_pending_crash_reports,
+            acknowledge_crash_report,
+            delete_crash_report,
+            submit_crash_report,
+            
+            // Storage Commands
+            get_storage_usage,
+            get_storage_settings,
+            update_storage_settings,
+            cleanup_storage,
+            get_cloud_backup_settings,
+            save_cloud_backup_settings,
+            set_cloud_backup_passphrase,
+            get_cloud_backup_status,
+            back_up_to_cloud_now,
+            list_cloud_backups,
+            prepare_cloud_restore,
+            list_local_backups,
+            prepare_local_restore,
+            confirm_restore,
+            cancel_restore,
+            
+            // History Retention Commands
+            get_locale,
+            set_locale,
+            get_curriculum,
+            set_curriculum,
+            get_curriculum_levels,
+            update_curriculum_level,
+            get_retention_settings,
+            update_retention_settings,
+            prune_history,
+            get_retention_reports,
+            check_asset_integrity,
+            get_asset_integrity_reports,
+            retire_broken_asset_questions,
+            
+            // API Token Commands
+            create_api_token,
+            get_api_tokens,
+            revoke_api_token,
+            
+            // Assignment Commands
+            create_assignment,
+            delete_assignment,
+            get_assignments,
+            get_pending_assignments,
+            get_assignment_quiz_config,
+            start_assignment_session,
+            record_assignment_attempt,
+            
+            // Classroom Commands
+            create_class,
+            get_classes,
+            delete_class,
+            add_class_members,
+            remove_class_member,
+            import_class_roster,
+            preview_profiles_csv,
+            import_profiles_csv,
+            get_profile_school_details,
+            assign_to_class,
+            get_class_report,
+            
+            // Email Report Commands
+            get_email_settings,
+            save_email_settings,
+            send_test_email,
+            preview_weekly_report,
+            get_email_report_status,
+            
+            // Calendar Export Commands
+            export_calendar,
+            get_calendar_feed_path,
+            
+            // Accessibility Commands
+            get_accessibility_settings,
+            set_accessibility_settings,
+            set_question_simplified_text,
+            
+            // Wellbeing Commands
+            get_break_status,
+            get_break_settings,
+            set_break_settings,
+            end_break,
+            
+            // Notification Commands
+            get_notification_preferences,
+            set_notification_preferences,
+            get_notification_templates,
+            set_notification_template,
+            reset_notification_template,
+            
+            // System Tray Commands
+            pause_reminders,
+            resume_reminders,
+            get_reminders_paused_until,
+            
+            // Parent Dashboard Commands
+            open_parent_dashboard,
+            close_parent_dashboard,
+            get_dashboard_session,
+            get_dashboard_overview,
+            get_dashboard_profile_progress,
+            
             // Settings Commands
             save_settings,
             load_settings,
@@ -963,11 +5859,43 @@ fn main() {
             get_database_stats,
             get_database_version
         ])
+        .system_tray(build_system_tray())
+        .on_system_tray_event(handle_tray_event)
+        .on_window_event(|event| {
+            // A closed dashboard must not leave its session usable by a re-created window
+            if let tauri::WindowEvent::Destroyed = event.event() {
+                let window = event.window();
+                if window.label() != MAIN_WINDOW_LABEL {
+                    let state = window.state::<AppState>();
+                    if let Err(e) = state.window_sessions.close(window.label()) {
+                        eprintln!("Warning: Failed to release window session: {}", e);
+                    }
+                }
+            }
+        })
         .setup(|app| {
             println!("🎉 Tauri setup complete - Application is ready!");
             
+            // Seed content in the background so the window shows immediately
+            let app_handle = app.handle();
+            std::thread::spawn(move || {
+                let state = app_handle.state::<AppState>();
+                run_background_seeding(&app_handle, &state);
+            });
+            
+            refresh_tray_badge(&app.handle(), &app.state::<AppState>());
+            
+            let scheduler_handle = app.handle();
+            std::thread::spawn(move || run_notification_scheduler(scheduler_handle));
+            
+            let inbox_handle = app.handle();
+            std::thread::spawn(move || run_content_inbox_watcher(inbox_handle));
+            
+            let maintenance_handle = app.handle();
+            std::thread::spawn(move || run_database_maintenance(maintenance_handle));
+            
             // Get the main window and ensure it's visible
-            if let Some(window) = app.get_window("main") {
+            if let Some(window) = app.get_window(MAIN_WINDOW_LABEL) {
                 println!("🪟 Found main window, ensuring it's visible...");
                 if let Err(e) = window.show() {
                     println!("❌ Failed to show window: {}", e);
@@ -989,8 +5917,14 @@ fn main() {
             
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(move |_app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                println!("🛑 Shutting down...");
+                run_shutdown(&shutdown);
+            }
+        });
     
     println!("🏁 Application has exited");
 }
\ No newline at end of file
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::{MixConfig, MixId, ProfileId};
+
+/// What the child has to complete for an assignment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AssignmentSource {
+    /// An existing custom mix
+    #[serde(rename = "mix")]
+    Mix { mix_id: MixId },
+    /// A one-off quiz definition stored with the assignment
+    #[serde(rename = "blueprint")]
+    Blueprint { config: MixConfig },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum AssignmentStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "overdue")]
+    Overdue,
+    #[serde(rename = "passed")]
+    Passed,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Assignment {
+    pub id: Option<u32>,
+    pub profile_id: ProfileId,
+    pub title: String,
+    pub source: AssignmentSource,
+    pub due_at: DateTime<Utc>,
+    pub minimum_score: u8, // percentage needed to pass
+    pub created_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub best_score: Option<u8>,
+    pub attempts: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAssignmentRequest {
+    pub profile_id: ProfileId,
+    pub title: String,
+    pub source: AssignmentSource,
+    pub due_at: DateTime<Utc>,
+    pub minimum_score: u8,
+}
+
+/// Assignment counts for one profile, used by the dashboard and weekly digest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentSummary {
+    pub profile_id: ProfileId,
+    pub pending: u32,
+    pub overdue: u32,
+    pub passed: u32,
+    pub failed: u32,
+}
+
+impl Assignment {
+    pub fn passed(&self) -> bool {
+        self.best_score.map_or(false, |score| score >= self.minimum_score)
+    }
+
+    /// Status at time `now`. A failed attempt stays open for retries until the due date.
+    pub fn status(&self, now: DateTime<Utc>) -> AssignmentStatus {
+        if self.passed() {
+            AssignmentStatus::Passed
+        } else if now <= self.due_at {
+            AssignmentStatus::Pending
+        } else if self.attempts > 0 {
+            AssignmentStatus::Failed
+        } else {
+            AssignmentStatus::Overdue
+        }
+    }
+}
+
+impl CreateAssignmentRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.title.trim().is_empty() {
+            return Err("Assignment title cannot be empty".to_string());
+        }
+
+        if self.minimum_score > 100 {
+            return Err("Minimum score must be a percentage between 0 and 100".to_string());
+        }
+
+        if let AssignmentSource::Blueprint { config } = &self.source {
+            config.validate()?;
+        }
+
+        Ok(())
+    }
+}
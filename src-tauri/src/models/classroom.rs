@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::{KeyStage, Profile, ProfileId};
+
+/// A class or group of student profiles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassGroup {
+    pub id: Option<u32>,
+    pub name: String,
+    pub description: Option<String>,
+    pub member_ids: Vec<ProfileId>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateClassRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A roster row that was not imported, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedRosterRow {
+    pub line: usize,
+    pub name: String,
+    pub reason: String,
+}
+
+/// Outcome of a roster import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterImportResult {
+    pub created: Vec<Profile>,
+    pub skipped: Vec<SkippedRosterRow>,
+}
+
+/// Which CSV column holds each profile field when importing profiles. Columns are
+/// numbered from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileCsvColumns {
+    pub name: usize,
+    pub year_group: Option<usize>,
+    pub key_stage: Option<usize>,
+}
+
+impl Default for ProfileCsvColumns {
+    /// `name,year group,key stage`, for files without a header row
+    fn default() -> Self {
+        Self {
+            name: 0,
+            year_group: Some(1),
+            key_stage: Some(2),
+        }
+    }
+}
+
+impl ProfileCsvColumns {
+    /// Map columns from a header row, or `None` if it has no name column and so is
+    /// probably not a header
+    pub fn from_header(fields: &[String]) -> Option<Self> {
+        let normalized: Vec<String> = fields.iter()
+            .map(|field| field.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase())
+            .collect();
+        let find = |aliases: &[&str]| normalized.iter().position(|field| aliases.contains(&field.as_str()));
+
+        Some(Self {
+            name: find(&["name", "studentname", "pupilname", "childname", "student", "pupil"])?,
+            year_group: find(&["yeargroup", "year", "yr"]),
+            key_stage: find(&["keystage", "ks", "stage"]),
+        })
+    }
+}
+
+/// What importing a row of a profile CSV would do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileImportRowStatus {
+    /// A new profile will be created
+    Ready,
+    /// A profile with this name already exists, or the name appears earlier in the file
+    Duplicate,
+    Invalid,
+}
+
+/// One data row of a profile CSV, as it would be imported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileImportRow {
+    pub line: usize,
+    pub name: String,
+    /// 0 for Reception, otherwise the school year
+    pub year_group: Option<u8>,
+    pub key_stage: Option<KeyStage>,
+    pub status: ProfileImportRowStatus,
+    /// Why the row is a duplicate or invalid
+    pub reason: Option<String>,
+}
+
+/// What importing a profile CSV would do, shown to the teacher before anything is created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileImportPreview {
+    pub columns: ProfileCsvColumns,
+    pub has_header: bool,
+    pub rows: Vec<ProfileImportRow>,
+}
+
+impl ProfileImportPreview {
+    pub fn count(&self, status: ProfileImportRowStatus) -> usize {
+        self.rows.iter().filter(|row| row.status == status).count()
+    }
+}
+
+/// The year group and key stage recorded for a student profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSchoolDetails {
+    pub profile_id: ProfileId,
+    pub year_group: Option<u8>,
+    pub key_stage: Option<KeyStage>,
+}
+
+/// Oldest year group the content covers (the end of KS3)
+pub const MAX_YEAR_GROUP: u8 = 9;
+
+/// Parse a year group such as `R`, `Reception`, `Year 4`, `Y4` or `4`. Reception is 0.
+pub fn parse_year_group(value: &str) -> Result<u8, String> {
+    let normalized: String = value.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    if matches!(normalized.as_str(), "r" | "yr" | "rec" | "reception") {
+        return Ok(0);
+    }
+
+    let number = normalized.strip_prefix("year")
+        .or_else(|| normalized.strip_prefix('y'))
+        .unwrap_or(&normalized);
+    match number.parse::<u8>() {
+        Ok(year) if (1..=MAX_YEAR_GROUP).contains(&year) => Ok(year),
+        _ => Err(format!("Unknown year group '{}' (expected Reception to Year {})", value.trim(), MAX_YEAR_GROUP)),
+    }
+}
+
+/// Parse a key stage such as `KS2`, `ks2`, `Key Stage 2` or `EYFS`
+pub fn parse_key_stage(value: &str) -> Result<KeyStage, String> {
+    let normalized: String = value.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase();
+    let normalized = match normalized.strip_prefix("KEYSTAGE") {
+        Some(number) => format!("KS{}", number),
+        None => normalized,
+    };
+    normalized.parse().map_err(|_| format!("Unknown key stage '{}'", value.trim()))
+}
+
+/// The key stage a year group is taught in
+pub fn key_stage_for_year_group(year_group: u8) -> KeyStage {
+    match year_group {
+        0 => KeyStage::EYFS,
+        1..=2 => KeyStage::KS1,
+        3..=6 => KeyStage::KS2,
+        _ => KeyStage::KS3,
+    }
+}
+
+/// Aggregate results for a class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassReport {
+    pub class_id: u32,
+    pub class_name: String,
+    pub member_count: u32,
+    pub questions_answered: u32,
+    pub correct_answers: u32,
+    pub average_accuracy: f32,
+    pub subjects: Vec<ClassSubjectReport>,
+    pub members: Vec<ClassMemberReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassSubjectReport {
+    pub subject: String,
+    pub questions_answered: u32,
+    pub correct_answers: u32,
+    pub accuracy: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassMemberReport {
+    pub profile_id: ProfileId,
+    pub name: String,
+    pub questions_answered: u32,
+    pub accuracy: f32,
+    pub pending_assignments: u32,
+}
+
+impl CreateClassRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Class name cannot be empty".to_string());
+        }
+
+        if self.name.len() > 50 {
+            return Err("Class name too long (max 50 characters)".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Accuracy as a percentage, or 0 when nothing has been answered
+pub fn accuracy_percentage(correct_answers: u32, questions_answered: u32) -> f32 {
+    if questions_answered == 0 {
+        0.0
+    } else {
+        correct_answers as f32 / questions_answered as f32 * 100.0
+    }
+}
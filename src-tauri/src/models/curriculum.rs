@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use super::KeyStage;
+
+/// The school system a family follows. Questions are written against England's key
+/// stages; other curricula are mapped onto them by the `curriculum_levels` table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Curriculum {
+    #[default]
+    England,
+    /// Scotland's Curriculum for Excellence
+    Scotland,
+    /// US grade levels
+    UnitedStates,
+}
+
+impl Curriculum {
+    pub const ALL: [Curriculum; 3] = [Curriculum::England, Curriculum::Scotland, Curriculum::UnitedStates];
+
+    /// The value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Curriculum::England => "england",
+            Curriculum::Scotland => "scotland",
+            Curriculum::UnitedStates => "united_states",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|curriculum| curriculum.as_str() == tag)
+    }
+}
+
+/// What a curriculum calls one key stage, and the question difficulties (1-5) expected there
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurriculumLevel {
+    pub curriculum: Curriculum,
+    pub key_stage: KeyStage,
+    pub label: String,
+    pub min_difficulty: u8,
+    pub max_difficulty: u8,
+}
+
+impl CurriculumLevel {
+    /// The key stage under its own name, expecting every difficulty
+    pub fn unmapped(curriculum: Curriculum, key_stage: KeyStage) -> Self {
+        Self {
+            curriculum,
+            key_stage,
+            label: key_stage.as_str().to_string(),
+            min_difficulty: 1,
+            max_difficulty: 5,
+        }
+    }
+
+    /// Difficulties to draw questions from, or `None` when the whole range is expected
+    pub fn difficulty_range(&self) -> Option<(u8, u8)> {
+        if (self.min_difficulty, self.max_difficulty) == (1, 5) {
+            None
+        } else {
+            Some((self.min_difficulty, self.max_difficulty))
+        }
+    }
+}
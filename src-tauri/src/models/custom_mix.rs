@@ -1,12 +1,12 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use super::KeyStage;
+use super::{KeyStage, MixId, ProfileId};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomMix {
-    pub id: Option<u32>,
+    pub id: Option<MixId>,
     pub name: String,
-    pub created_by: u32,
+    pub created_by: ProfileId,
     pub config: MixConfig,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
@@ -20,6 +20,9 @@ pub struct MixConfig {
     pub time_limit: Option<u32>, // in seconds
     pub difficulty_range: (u8, u8), // min, max difficulty (1-5)
     pub question_types: Option<Vec<String>>, // filter by question types
+    /// Only questions with at least one of these tags; empty allows every tag
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub randomize_order: bool,
     pub show_immediate_feedback: bool,
     pub allow_review: bool,
@@ -28,7 +31,7 @@ pub struct MixConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateMixRequest {
     pub name: String,
-    pub created_by: u32,
+    pub created_by: ProfileId,
     pub config: MixConfig,
 }
 
@@ -39,7 +42,7 @@ pub struct UpdateMixRequest {
 }
 
 impl CustomMix {
-    pub fn new(name: String, created_by: u32, config: MixConfig) -> Self {
+    pub fn new(name: String, created_by: ProfileId, config: MixConfig) -> Self {
         Self {
             id: None,
             name,
@@ -60,6 +63,7 @@ impl MixConfig {
             time_limit: None,
             difficulty_range: (1, 5),
             question_types: None,
+            tags: Vec::new(),
             randomize_order: true,
             show_immediate_feedback: true,
             allow_review: true,
@@ -81,6 +85,11 @@ impl MixConfig {
         self
     }
 
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
     pub fn validate(&self) -> Result<(), String> {
         if self.subjects.is_empty() {
             return Err("At least one subject must be selected".to_string());
@@ -0,0 +1,109 @@
+use crate::errors::AppError;
+use rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A typed row id. Each table's ids get their own type, so a mix id can't be passed where
+/// a profile id is expected.
+pub trait EntityId: Copy + fmt::Display + ToSql {
+    /// Table the id is the primary key of
+    const TABLE: &'static str;
+    /// What the row is called in messages
+    const NAME: &'static str;
+
+    fn get(self) -> u32;
+
+    /// The error for an id with no row
+    fn not_found(self) -> AppError;
+}
+
+macro_rules! entity_id {
+    ($(#[$meta:meta])* $name:ident, $table:literal, $label:literal, $not_found:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub u32);
+
+        impl EntityId for $name {
+            const TABLE: &'static str = $table;
+            const NAME: &'static str = $label;
+
+            fn get(self) -> u32 {
+                self.0
+            }
+
+            fn not_found(self) -> AppError {
+                ($not_found)(self)
+            }
+        }
+
+        impl From<u32> for $name {
+            fn from(id: u32) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl ToSql for $name {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                self.0.to_sql()
+            }
+        }
+
+        impl FromSql for $name {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                u32::column_result(value).map(Self)
+            }
+        }
+    };
+}
+
+fn not_found_message<I: EntityId>(id: I) -> AppError {
+    AppError::NotFound(format!("{} with id {} not found", I::NAME, id))
+}
+
+entity_id!(
+    /// Id of a row in `profiles`
+    ProfileId, "profiles", "Profile",
+    |id| AppError::ProfileNotFound { id }
+);
+entity_id!(
+    /// Id of a row in `questions`
+    QuestionId, "questions", "Question", not_found_message
+);
+entity_id!(
+    /// Id of a row in `quiz_sessions`
+    SessionId, "quiz_sessions", "Quiz session", not_found_message
+);
+entity_id!(
+    /// Id of a row in `custom_mixes`
+    MixId, "custom_mixes", "Custom mix", not_found_message
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_serialize_as_plain_numbers() {
+        assert_eq!(serde_json::to_string(&ProfileId(7)).unwrap(), "7");
+        assert_eq!(serde_json::from_str::<MixId>("12").unwrap(), MixId(12));
+
+        let mut times = std::collections::HashMap::new();
+        times.insert(QuestionId(3), 40u32);
+        let json = serde_json::to_string(&times).unwrap();
+        assert_eq!(json, r#"{"3":40}"#);
+        assert_eq!(serde_json::from_str::<std::collections::HashMap<QuestionId, u32>>(&json).unwrap(), times);
+    }
+}
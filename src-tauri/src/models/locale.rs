@@ -0,0 +1,357 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Where a family is, deciding how money, numbers and dates are written. Questions are
+/// written for the UK, so `EnGb` changes nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en-GB")]
+    EnGb,
+    #[serde(rename = "en-IE")]
+    EnIe,
+    #[serde(rename = "en-US")]
+    EnUs,
+    #[serde(rename = "en-CA")]
+    EnCa,
+    #[serde(rename = "en-AU")]
+    EnAu,
+    #[serde(rename = "fr-FR")]
+    FrFr,
+    #[serde(rename = "de-DE")]
+    DeDe,
+}
+
+/// How one locale writes things
+struct Conventions {
+    currency_symbol: &'static str,
+    /// `1,50 €` rather than `€1.50`
+    symbol_after: bool,
+    /// Amounts under one unit written in the minor unit, e.g. `50p`
+    minor_suffix: Option<&'static str>,
+    decimal_separator: char,
+    thousands_separator: char,
+    /// `%` written after a space
+    spaced_percent: bool,
+    date_order: DateOrder,
+    months: [&'static str; 12],
+    short_months: [&'static str; 12],
+    weekdays: [&'static str; 7],
+    /// Words replacing pound, pounds, penny and pence in money questions
+    money_words: [&'static str; 4],
+}
+
+enum DateOrder {
+    /// 16 October 2026
+    DayMonth,
+    /// October 16, 2026
+    MonthDay,
+    /// 16. Oktober 2026
+    DayDotMonth,
+}
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+const ENGLISH_SHORT_MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+const ENGLISH_WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+const EN_GB: Conventions = Conventions {
+    currency_symbol: "£",
+    symbol_after: false,
+    minor_suffix: Some("p"),
+    decimal_separator: '.',
+    thousands_separator: ',',
+    spaced_percent: false,
+    date_order: DateOrder::DayMonth,
+    months: ENGLISH_MONTHS,
+    short_months: ENGLISH_SHORT_MONTHS,
+    weekdays: ENGLISH_WEEKDAYS,
+    money_words: ["pound", "pounds", "penny", "pence"],
+};
+const EN_IE: Conventions = Conventions {
+    currency_symbol: "€",
+    minor_suffix: Some("c"),
+    money_words: ["euro", "euros", "cent", "cents"],
+    ..EN_GB
+};
+const EN_US: Conventions = Conventions {
+    currency_symbol: "$",
+    minor_suffix: Some("¢"),
+    date_order: DateOrder::MonthDay,
+    money_words: ["dollar", "dollars", "cent", "cents"],
+    ..EN_GB
+};
+const EN_AU: Conventions = Conventions {
+    currency_symbol: "$",
+    minor_suffix: Some("c"),
+    money_words: ["dollar", "dollars", "cent", "cents"],
+    ..EN_GB
+};
+const FR_FR: Conventions = Conventions {
+    currency_symbol: "€",
+    symbol_after: true,
+    minor_suffix: None,
+    decimal_separator: ',',
+    thousands_separator: '\u{a0}',
+    spaced_percent: true,
+    date_order: DateOrder::DayMonth,
+    months: [
+        "janvier", "février", "mars", "avril", "mai", "juin",
+        "juillet", "août", "septembre", "octobre", "novembre", "décembre",
+    ],
+    short_months: ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+    weekdays: ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+    money_words: ["euro", "euros", "centime", "centimes"],
+};
+const DE_DE: Conventions = Conventions {
+    thousands_separator: '.',
+    spaced_percent: true,
+    date_order: DateOrder::DayDotMonth,
+    months: [
+        "Januar", "Februar", "März", "April", "Mai", "Juni",
+        "Juli", "August", "September", "Oktober", "November", "Dezember",
+    ],
+    short_months: ["Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez."],
+    weekdays: ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+    money_words: ["Euro", "Euro", "Cent", "Cent"],
+    ..FR_FR
+};
+
+/// Characters kept off the ends of a word when looking for a sum of money in it
+const WORD_PUNCTUATION: &[char] = &['(', ')', '"', '\'', ',', '.', '?', '!', ':', ';'];
+
+impl Locale {
+    pub const ALL: [Locale; 7] = [
+        Locale::EnGb, Locale::EnIe, Locale::EnUs, Locale::EnCa, Locale::EnAu, Locale::FrFr, Locale::DeDe,
+    ];
+
+    /// The BCP 47 tag, e.g. `en-GB`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::EnGb => "en-GB",
+            Locale::EnIe => "en-IE",
+            Locale::EnUs => "en-US",
+            Locale::EnCa => "en-CA",
+            Locale::EnAu => "en-AU",
+            Locale::FrFr => "fr-FR",
+            Locale::DeDe => "de-DE",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        Self::ALL.iter().copied().find(|locale| locale.as_str().eq_ignore_ascii_case(tag))
+    }
+
+    fn conventions(&self) -> &'static Conventions {
+        match self {
+            Locale::EnGb => &EN_GB,
+            Locale::EnIe => &EN_IE,
+            Locale::EnUs | Locale::EnCa => &EN_US,
+            Locale::EnAu => &EN_AU,
+            Locale::FrFr => &FR_FR,
+            Locale::DeDe => &DE_DE,
+        }
+    }
+
+    /// A number with `decimals` places and the locale's separators, e.g. `1,234.5` or `1.234,5`
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let conventions = self.conventions();
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (whole, fraction) = match formatted.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut text = String::new();
+        if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            text.push('-');
+        }
+        for (index, digit) in whole.chars().enumerate() {
+            if index > 0 && (whole.len() - index) % 3 == 0 {
+                text.push(conventions.thousands_separator);
+            }
+            text.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            text.push(conventions.decimal_separator);
+            text.push_str(fraction);
+        }
+        text
+    }
+
+    /// A whole-number percentage, e.g. `85%` or `85 %`
+    pub fn format_percent(&self, value: u32) -> String {
+        if self.conventions().spaced_percent {
+            format!("{}\u{a0}%", value)
+        } else {
+            format!("{}%", value)
+        }
+    }
+
+    /// A sum of money given in the minor unit (pence, cents). Whole amounts drop the
+    /// decimals, and amounts under one unit use the minor unit where the locale does:
+    /// `50p`, `£2`, `£1.50`, `50¢`, `1,50 €`.
+    pub fn format_money(&self, minor_units: i64) -> String {
+        let conventions = self.conventions();
+        let sign = if minor_units < 0 { "-" } else { "" };
+        let amount = minor_units.unsigned_abs();
+        if let Some(suffix) = conventions.minor_suffix {
+            if amount < 100 {
+                return format!("{}{}{}", sign, amount, suffix);
+            }
+        }
+
+        let decimals = if amount % 100 == 0 { 0 } else { 2 };
+        let number = self.format_number(amount as f64 / 100.0, decimals);
+        if conventions.symbol_after {
+            format!("{}{}\u{a0}{}", sign, number, conventions.currency_symbol)
+        } else {
+            format!("{}{}{}", sign, conventions.currency_symbol, number)
+        }
+    }
+
+    /// A date in full, e.g. `16 October 2026` or `October 16, 2026`
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        match self.conventions().date_order {
+            DateOrder::MonthDay => format!("{}, {}", self.format_day_month(date), date.year()),
+            _ => format!("{} {}", self.format_day_month(date), date.year()),
+        }
+    }
+
+    /// A day and abbreviated month, e.g. `16 Oct` or `Oct 16, 2026`
+    pub fn format_short_date(&self, date: NaiveDate, with_year: bool) -> String {
+        let conventions = self.conventions();
+        let month = conventions.short_months[date.month0() as usize];
+        let day_month = match conventions.date_order {
+            DateOrder::DayMonth => format!("{} {}", date.day(), month),
+            DateOrder::MonthDay => format!("{} {}", month, date.day()),
+            DateOrder::DayDotMonth => format!("{}. {}", date.day(), month),
+        };
+        match (with_year, &conventions.date_order) {
+            (false, _) => day_month,
+            (true, DateOrder::MonthDay) => format!("{}, {}", day_month, date.year()),
+            (true, _) => format!("{} {}", day_month, date.year()),
+        }
+    }
+
+    /// A date with its weekday, e.g. `Friday 16 October` or `Friday, October 16, 2026`
+    pub fn format_weekday_date(&self, date: NaiveDate, with_year: bool) -> String {
+        let conventions = self.conventions();
+        let weekday = conventions.weekdays[date.weekday().num_days_from_monday() as usize];
+        let date_text = if with_year { self.format_date(date) } else { self.format_day_month(date) };
+        match conventions.date_order {
+            DateOrder::DayMonth => format!("{} {}", weekday, date_text),
+            _ => format!("{}, {}", weekday, date_text),
+        }
+    }
+
+    fn format_day_month(&self, date: NaiveDate) -> String {
+        let conventions = self.conventions();
+        let month = conventions.months[date.month0() as usize];
+        match conventions.date_order {
+            DateOrder::DayMonth => format!("{} {}", date.day(), month),
+            DateOrder::MonthDay => format!("{} {}", month, date.day()),
+            DateOrder::DayDotMonth => format!("{}. {}", date.day(), month),
+        }
+    }
+
+    /// Rewrite the UK sums of money in text written for UK families (`£1.50`, `50p`, "pence")
+    /// in this locale's currency. The amounts are kept, only the currency changes.
+    pub fn localize_money_text(&self, text: &str) -> String {
+        if *self == Locale::EnGb {
+            return text.to_string();
+        }
+        text.split(' ')
+            .map(|word| self.localize_money_word(word))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn localize_money_word(&self, word: &str) -> String {
+        let start = word.len() - word.trim_start_matches(WORD_PUNCTUATION).len();
+        let end = word.trim_end_matches(WORD_PUNCTUATION).len().max(start);
+        let (prefix, core, suffix) = (&word[..start], &word[start..end], &word[end..]);
+
+        let replaced = match uk_money_in_pence(core) {
+            Some(pence) => self.format_money(pence as i64),
+            None => match self.money_word(core) {
+                Some(replacement) => replacement,
+                None => return word.to_string(),
+            },
+        };
+        format!("{}{}{}", prefix, replaced, suffix)
+    }
+
+    /// This locale's word for a UK currency word, keeping an initial capital
+    fn money_word(&self, word: &str) -> Option<String> {
+        let words = self.conventions().money_words;
+        let replacement = match word.to_lowercase().as_str() {
+            "pound" => words[0],
+            "pounds" => words[1],
+            "penny" => words[2],
+            "pence" | "pennies" => words[3],
+            _ => return None,
+        };
+        if word.starts_with(char::is_uppercase) {
+            let mut chars = replacement.chars();
+            Some(chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect()))
+        } else {
+            Some(replacement.to_string())
+        }
+    }
+}
+
+/// Pence in a UK amount like `£1`, `£1.50`, `£1,000` or `50p`
+fn uk_money_in_pence(word: &str) -> Option<u64> {
+    if let Some(amount) = word.strip_prefix('£') {
+        let amount = amount.replace(',', "");
+        let (pounds, pence) = match amount.split_once('.') {
+            Some((pounds, pence)) if pence.len() == 2 => (pounds.to_string(), pence.to_string()),
+            Some(_) => return None,
+            None => (amount, "00".to_string()),
+        };
+        if pounds.is_empty() || !pounds.chars().all(|c| c.is_ascii_digit()) || !pence.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        return Some(pounds.parse::<u64>().ok()? * 100 + pence.parse::<u64>().ok()?);
+    }
+
+    let pence = word.strip_suffix('p')?;
+    if pence.is_empty() || !pence.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    pence.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locales_format_money_numbers_and_dates() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+
+        assert_eq!(Locale::EnGb.format_money(50), "50p");
+        assert_eq!(Locale::EnGb.format_money(150), "£1.50");
+        assert_eq!(Locale::EnUs.format_money(123_400), "$1,234");
+        assert_eq!(Locale::DeDe.format_money(150), "1,50\u{a0}€");
+        assert_eq!(Locale::FrFr.format_number(1234.5, 1), "1\u{a0}234,5");
+        assert_eq!(Locale::EnGb.format_date(date), "16 October 2026");
+        assert_eq!(Locale::EnUs.format_date(date), "October 16, 2026");
+        assert_eq!(Locale::DeDe.format_short_date(date, false), "16. Okt.");
+        assert_eq!(Locale::EnCa.format_short_date(date, true), "Oct 16, 2026");
+        assert_eq!(Locale::EnGb.format_weekday_date(date, false), "Friday 16 October");
+        assert_eq!(Locale::FrFr.format_weekday_date(date, true), "vendredi 16 octobre 2026");
+
+        let question = "A book costs £8 and a pen costs 50p. How many pence is that?";
+        assert_eq!(Locale::EnGb.localize_money_text(question), question);
+        assert_eq!(
+            Locale::EnUs.localize_money_text(question),
+            "A book costs $8 and a pen costs 50¢. How many cents is that?"
+        );
+        assert_eq!(Locale::EnIe.localize_money_text("(£1.50)"), "(€1.50)");
+        assert_eq!(Locale::from_tag("en-us"), Some(Locale::EnUs));
+    }
+}
@@ -1,11 +1,23 @@
+pub mod ids;
 pub mod profile;
 pub mod question;
 pub mod progress;
 pub mod custom_mix;
 pub mod quiz_session;
+pub mod assignment;
+pub mod classroom;
+pub mod passage;
+pub mod locale;
+pub mod curriculum;
 
+pub use ids::*;
 pub use profile::*;
 pub use question::*;
 pub use progress::*;
 pub use custom_mix::*;
-pub use quiz_session::*;
\ No newline at end of file
+pub use quiz_session::*;
+pub use assignment::*;
+pub use classroom::*;
+pub use passage::*;
+pub use locale::*;
+pub use curriculum::*;
\ No newline at end of file
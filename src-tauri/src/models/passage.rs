@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use super::{KeyStage, Question, QuestionId};
+
+/// Longest passage text accepted, in characters
+pub const MAX_PASSAGE_LENGTH: usize = 20_000;
+
+/// A reading passage shared by a group of StoryQuiz questions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Passage {
+    pub id: Option<u32>,
+    pub subject_id: u32,
+    pub key_stage: KeyStage,
+    pub title: String,
+    pub text: String,
+    pub question_ids: Vec<QuestionId>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePassageRequest {
+    pub subject_id: u32,
+    pub key_stage: KeyStage,
+    pub title: String,
+    pub text: String,
+}
+
+impl CreatePassageRequest {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.title.trim().is_empty() {
+            return Err("Passage title cannot be empty".to_string());
+        }
+        if self.text.trim().is_empty() {
+            return Err("Passage text cannot be empty".to_string());
+        }
+        if self.text.chars().count() > MAX_PASSAGE_LENGTH {
+            return Err(format!("Passage text cannot be longer than {} characters", MAX_PASSAGE_LENGTH));
+        }
+        Ok(())
+    }
+}
+
+/// A passage served together with its questions, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassageGroup {
+    pub passage: Passage,
+    pub questions: Vec<Question>,
+}
+
+/// "Read the passage, then answer its questions" section of a quiz session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassageBlock {
+    pub passage: Passage,
+    /// Index of the block's first question in the session
+    pub start_index: usize,
+    pub question_count: usize,
+}
+
+impl PassageBlock {
+    pub fn contains(&self, question_index: usize) -> bool {
+        question_index >= self.start_index && question_index < self.start_index + self.question_count
+    }
+}
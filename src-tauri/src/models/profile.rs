@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use super::ProfileId;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
-    pub id: Option<u32>,
+    pub id: Option<ProfileId>,
     pub name: String,
     pub avatar: String,
     pub created_at: Option<DateTime<Utc>>,
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use std::collections::HashMap;
-use super::KeyStage;
+use super::{KeyStage, Locale, ProfileId};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Progress {
@@ -23,10 +23,59 @@ pub struct SubjectProgress {
     pub last_activity: DateTime<Utc>,
 }
 
+/// Totals across every profile, for the family stats screen and the weekly report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HouseholdStatistics {
+    pub profile_count: u32,
+    /// All-time, across every profile
+    pub total_questions_answered: u32,
+    pub total_correct_answers: u32,
+    pub accuracy_percentage: u8,
+    /// Most practised subjects first
+    pub top_subjects: Vec<HouseholdSubjectTotal>,
+    /// The day the family answered the most questions
+    pub busiest_day: Option<BusiestDay>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HouseholdSubjectTotal {
+    pub subject: String,
+    pub questions_answered: u32,
+    pub correct_answers: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusiestDay {
+    pub date: NaiveDate,
+    pub questions_answered: u32,
+}
+
+impl HouseholdStatistics {
+    /// Short summary for the top of the weekly report email
+    pub fn render(&self, locale: Locale) -> String {
+        let mut text = format!(
+            "Family totals: {} questions answered all-time, {} correct.\n",
+            locale.format_number(self.total_questions_answered as f64, 0),
+            locale.format_percent(u32::from(self.accuracy_percentage)),
+        );
+        if let Some(top) = self.top_subjects.first() {
+            text.push_str(&format!("Favourite subject: {} ({} questions).\n", top.subject, top.questions_answered));
+        }
+        if let Some(day) = &self.busiest_day {
+            text.push_str(&format!(
+                "Busiest day: {} ({} questions).\n",
+                locale.format_weekday_date(day.date, true),
+                day.questions_answered,
+            ));
+        }
+        text
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgressRecord {
     pub id: Option<u32>,
-    pub profile_id: u32,
+    pub profile_id: ProfileId,
     pub subject: String,
     pub key_stage: KeyStage,
     pub questions_answered: u32,
@@ -37,7 +86,7 @@ pub struct ProgressRecord {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgressSummary {
-    pub profile_id: u32,
+    pub profile_id: ProfileId,
     pub subject_progress: HashMap<String, SubjectProgress>,
     pub total_questions_answered: u32,
     pub total_correct_answers: u32,
@@ -88,6 +137,30 @@ pub enum AchievementCategory {
     SubjectMastery,
 }
 
+impl AchievementCategory {
+    /// The value stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AchievementCategory::Accuracy => "accuracy",
+            AchievementCategory::Streak => "streak",
+            AchievementCategory::Completion => "completion",
+            AchievementCategory::Time => "time",
+            AchievementCategory::SubjectMastery => "subject_mastery",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "accuracy" => Some(AchievementCategory::Accuracy),
+            "streak" => Some(AchievementCategory::Streak),
+            "completion" => Some(AchievementCategory::Completion),
+            "time" => Some(AchievementCategory::Time),
+            "subject_mastery" => Some(AchievementCategory::SubjectMastery),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Streak {
     pub streak_type: StreakType,
@@ -97,6 +170,61 @@ pub struct Streak {
     pub last_updated: DateTime<Utc>,
 }
 
+/// A profile's run of days with at least one completed quiz, in the household's time zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyStreak {
+    pub profile_id: ProfileId,
+    /// Days with a quiz in the current run. It's still alive until a day passes with no
+    /// quiz and no freeze to cover it.
+    pub current_days: u32,
+    pub best_days: u32,
+    pub completed_today: bool,
+    /// Freeze tokens granted by a parent and not yet used
+    pub freezes_available: u32,
+    /// Missed days in the current run covered by a freeze, including any that will use one
+    /// when the next quiz keeps the streak going
+    pub frozen_days: Vec<NaiveDate>,
+    /// First completed quiz of the current run
+    pub started_at: Option<DateTime<Utc>>,
+    pub last_completed_at: Option<DateTime<Utc>>,
+}
+
+impl DailyStreak {
+    /// The streak as listed in a profile's progress
+    pub fn to_streak(&self) -> Streak {
+        Streak {
+            streak_type: StreakType::DailyActivity,
+            current_count: self.current_days,
+            best_count: self.best_days,
+            started_at: self.started_at,
+            last_updated: self.last_completed_at.unwrap_or_else(Utc::now),
+        }
+    }
+}
+
+/// Where a profile stands on the XP levels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelInfo {
+    pub profile_id: ProfileId,
+    pub level: u32,
+    pub total_xp: u32,
+    /// XP earned since reaching `level`
+    pub xp_into_level: u32,
+    pub xp_to_next_level: u32,
+    /// Newest first
+    pub recent_gains: Vec<XpGain>,
+}
+
+/// XP earned from one quiz
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XpGain {
+    pub xp: u32,
+    pub subject: String,
+    pub level_before: u32,
+    pub level_after: u32,
+    pub earned_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StreakType {
     #[serde(rename = "daily_activity")]
@@ -120,7 +248,7 @@ impl Progress {
 }
 
 impl ProgressRecord {
-    pub fn new(profile_id: u32, subject: String, key_stage: KeyStage) -> Self {
+    pub fn new(profile_id: ProfileId, subject: String, key_stage: KeyStage) -> Self {
         Self {
             id: None,
             profile_id,
@@ -1,11 +1,54 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::str::FromStr;
+use super::QuestionId;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum KeyStage {
+    /// Early Years Foundation Stage (Reception)
+    EYFS,
     KS1,
     KS2,
+    KS3,
+}
+
+impl KeyStage {
+    /// Every key stage, youngest first
+    pub const ALL: [KeyStage; 4] = [KeyStage::EYFS, KeyStage::KS1, KeyStage::KS2, KeyStage::KS3];
+
+    /// The value stored in the database and used in content packs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyStage::EYFS => "EYFS",
+            KeyStage::KS1 => "KS1",
+            KeyStage::KS2 => "KS2",
+            KeyStage::KS3 => "KS3",
+        }
+    }
+
+    /// Extra points for a correct answer. Difficulty 1-5 is rated within a key stage,
+    /// so a KS3 question is worth more than a KS2 question of the same difficulty.
+    pub fn points_bonus(&self) -> u32 {
+        match self {
+            KeyStage::EYFS | KeyStage::KS1 | KeyStage::KS2 => 0,
+            KeyStage::KS3 => 10,
+        }
+    }
+}
+
+impl FromStr for KeyStage {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "EYFS" => Ok(KeyStage::EYFS),
+            "KS1" => Ok(KeyStage::KS1),
+            "KS2" => Ok(KeyStage::KS2),
+            "KS3" => Ok(KeyStage::KS3),
+            other => Err(format!("Unknown key stage '{}'", other)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -20,11 +63,54 @@ pub enum QuestionType {
     FillBlank,
     #[serde(rename = "story_quiz")]
     StoryQuiz,
+    /// A typed word or phrase, marked leniently (see `ShortAnswerConfig`)
+    #[serde(rename = "short_answer")]
+    ShortAnswer,
+    /// Items dragged into the right order, e.g. numbers smallest first. The items are the
+    /// options and the answer is `Answer::Ordering`.
+    #[serde(rename = "ordering")]
+    Ordering,
+}
+
+impl QuestionType {
+    pub const ALL: [QuestionType; 7] = [
+        QuestionType::MultipleChoice,
+        QuestionType::DragDrop,
+        QuestionType::Hotspot,
+        QuestionType::FillBlank,
+        QuestionType::StoryQuiz,
+        QuestionType::ShortAnswer,
+        QuestionType::Ordering,
+    ];
+
+    /// The value stored in the database and used in content packs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuestionType::MultipleChoice => "multiple_choice",
+            QuestionType::DragDrop => "drag_drop",
+            QuestionType::Hotspot => "hotspot",
+            QuestionType::FillBlank => "fill_blank",
+            QuestionType::StoryQuiz => "story_quiz",
+            QuestionType::ShortAnswer => "short_answer",
+            QuestionType::Ordering => "ordering",
+        }
+    }
+}
+
+impl FromStr for QuestionType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        QuestionType::ALL.iter()
+            .find(|question_type| question_type.as_str() == value)
+            .cloned()
+            .ok_or_else(|| format!("Unknown question type '{}'", value))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Question {
-    pub id: Option<u32>,
+    pub id: Option<QuestionId>,
     pub subject_id: u32,
     pub key_stage: KeyStage,
     pub question_type: QuestionType,
@@ -34,6 +120,52 @@ pub struct Question {
     pub tags: Vec<String>,
     pub assets: Option<Vec<Asset>>,
     pub created_at: Option<DateTime<Utc>>,
+    /// Where the question came from; `None` for questions stored before this was tracked
+    #[serde(default)]
+    pub source: Option<QuestionSource>,
+    /// Why the answer is right, shown after the question is answered
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// What created a question, so content can be traced and cleaned up by origin
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuestionSource {
+    /// Built-in content written by the content seeder
+    Seeder { version: String },
+    /// Installed from a content pack
+    ContentPack { pack: String, version: String },
+    /// Written by a parent in the app
+    Parent { author: Option<String> },
+    /// Produced by one of the question generators, e.g. `phonics`
+    Generator { name: String },
+    /// Imported from a Markdown or CSV file
+    Import { file_name: Option<String> },
+}
+
+impl QuestionSource {
+    /// Short name of the kind of source, as stored in the database
+    pub fn kind(&self) -> &'static str {
+        match self {
+            QuestionSource::Seeder { .. } => "seeder",
+            QuestionSource::ContentPack { .. } => "content_pack",
+            QuestionSource::Parent { .. } => "parent",
+            QuestionSource::Generator { .. } => "generator",
+            QuestionSource::Import { .. } => "import",
+        }
+    }
+
+    /// Which source of that kind: the pack name, generator name, file name and so on
+    pub fn reference(&self) -> Option<&str> {
+        match self {
+            QuestionSource::Seeder { version } => Some(version),
+            QuestionSource::ContentPack { pack, .. } => Some(pack),
+            QuestionSource::Parent { author } => author.as_deref(),
+            QuestionSource::Generator { name } => Some(name),
+            QuestionSource::Import { file_name } => file_name.as_deref(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,12 +188,387 @@ pub struct Coordinate {
     pub label: Option<String>,
 }
 
+/// Key in `QuestionContent::additional_data` holding a hotspot question's `HotspotConfig`
+pub const HOTSPOT_KEY: &str = "hotspot";
+
+/// How far, in image pixels, a click may land outside a correct region and still count
+pub const DEFAULT_HOTSPOT_TOLERANCE: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// An area of a hotspot question's image, in image pixels
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum HotspotRegion {
+    Circle { x: f64, y: f64, radius: f64 },
+    /// `x` and `y` are the top-left corner
+    Rect { x: f64, y: f64, width: f64, height: f64 },
+    /// Corners in order around the edge; the last joins back to the first
+    Polygon { points: Vec<Point> },
+}
+
+impl HotspotRegion {
+    /// Why the region can't be clicked, if it can't
+    pub fn problem(&self) -> Option<&'static str> {
+        match self {
+            HotspotRegion::Circle { radius, .. } if !positive(*radius) => Some("Circle hotspots need a radius above zero"),
+            HotspotRegion::Rect { width, height, .. } if !positive(*width) || !positive(*height) => {
+                Some("Rectangle hotspots need a width and height above zero")
+            }
+            HotspotRegion::Polygon { points } if points.len() < 3 => Some("Polygon hotspots need at least three points"),
+            _ => None,
+        }
+    }
+
+    /// Distance from a point to the region; zero when the point is inside it
+    pub fn distance_to(&self, x: f64, y: f64) -> f64 {
+        match self {
+            HotspotRegion::Circle { x: cx, y: cy, radius } => ((x - cx).hypot(y - cy) - radius).max(0.0),
+            HotspotRegion::Rect { x: left, y: top, width, height } => {
+                let dx = (left - x).max(x - (left + width)).max(0.0);
+                let dy = (top - y).max(y - (top + height)).max(0.0);
+                dx.hypot(dy)
+            }
+            HotspotRegion::Polygon { points } => {
+                let edges = points.iter().zip(points.iter().cycle().skip(1));
+                let mut inside = false;
+                let mut distance = f64::INFINITY;
+                for (a, b) in edges {
+                    // Ray cast to the right, counting edge crossings
+                    if (a.y > y) != (b.y > y) && x < a.x + (y - a.y) * (b.x - a.x) / (b.y - a.y) {
+                        inside = !inside;
+                    }
+                    distance = distance.min(distance_to_segment(x, y, a, b));
+                }
+                if inside { 0.0 } else { distance }
+            }
+        }
+    }
+}
+
+impl From<&Coordinate> for HotspotRegion {
+    /// Older hotspots are points, or boxes centred on the point when given a size
+    fn from(coordinate: &Coordinate) -> Self {
+        match (coordinate.width, coordinate.height) {
+            (Some(width), Some(height)) => HotspotRegion::Rect {
+                x: coordinate.x - width / 2.0,
+                y: coordinate.y - height / 2.0,
+                width,
+                height,
+            },
+            _ => HotspotRegion::Circle { x: coordinate.x, y: coordinate.y, radius: 0.0 },
+        }
+    }
+}
+
+fn positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+fn distance_to_segment(x: f64, y: f64, a: &Point, b: &Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((x - a.x) * dx + (y - a.y) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    (x - (a.x + t * dx)).hypot(y - (a.y + t * dy))
+}
+
+/// Where a hotspot question's correct areas are and how precise a click must be. Without
+/// regions, the areas come from the question's answer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HotspotConfig {
+    /// A click in any of these is right
+    #[serde(default)]
+    pub regions: Vec<HotspotRegion>,
+    /// How far outside a region, in image pixels, a click may land and still count
+    #[serde(default = "default_hotspot_tolerance")]
+    pub tolerance: f64,
+}
+
+fn default_hotspot_tolerance() -> f64 {
+    DEFAULT_HOTSPOT_TOLERANCE
+}
+
+impl Default for HotspotConfig {
+    fn default() -> Self {
+        Self {
+            regions: Vec::new(),
+            tolerance: DEFAULT_HOTSPOT_TOLERANCE,
+        }
+    }
+}
+
+impl QuestionContent {
+    /// The question's hotspot regions and tolerance, or the defaults when not set
+    pub fn hotspot_config(&self) -> Result<HotspotConfig, serde_json::Error> {
+        match self.additional_data.as_ref().and_then(|data| data.get(HOTSPOT_KEY)) {
+            Some(value) => serde_json::from_value(value.clone()),
+            None => Ok(HotspotConfig::default()),
+        }
+    }
+}
+
+/// Key in `QuestionContent::additional_data` holding other wordings of the question's text
+pub const PHRASINGS_KEY: &str = "phrasings";
+
+impl QuestionContent {
+    /// Every wording of the question, the text itself first, so a question seen again
+    /// needn't look the same. Blank and repeated wordings are left out.
+    pub fn phrasings(&self) -> Result<Vec<String>, serde_json::Error> {
+        let alternatives: Vec<String> = match self.additional_data.as_ref().and_then(|data| data.get(PHRASINGS_KEY)) {
+            Some(value) => serde_json::from_value(value.clone())?,
+            None => Vec::new(),
+        };
+        let mut phrasings = vec![self.text.clone()];
+        for alternative in alternatives {
+            let alternative = alternative.trim();
+            if !alternative.is_empty() && !phrasings.iter().any(|phrasing| phrasing.trim() == alternative) {
+                phrasings.push(alternative.to_string());
+            }
+        }
+        Ok(phrasings)
+    }
+}
+
+/// Key in `QuestionContent::additional_data` holding the hints a child can ask for, in order
+pub const HINTS_KEY: &str = "hints";
+
+/// Help a child can ask for on a question, at the cost of some of its points
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Hint {
+    /// Take away this many wrong options from a multiple choice question. At least one
+    /// wrong option is always left.
+    EliminateOptions { count: usize },
+    /// Show the first letter of the answer, or of each blank
+    FirstLetter,
+    /// A clue written by the question's author
+    Text { text: String },
+}
+
+impl QuestionContent {
+    /// The hints written for the question; empty when there are none
+    pub fn hints(&self) -> Result<Vec<Hint>, serde_json::Error> {
+        match self.additional_data.as_ref().and_then(|data| data.get(HINTS_KEY)) {
+            Some(value) => serde_json::from_value(value.clone()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Key in `QuestionContent::additional_data` holding a question's `AnswerNormalization`
+pub const NORMALIZATION_KEY: &str = "normalization";
+
+/// A step applied to both the expected and the given answer before they are compared
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizationRule {
+    /// Compatibility-normalise Unicode and fold curly quotes, dashes and fraction slashes,
+    /// so pasted or autocorrected text matches what was typed
+    Unicode,
+    /// Drop surrounding whitespace and collapse runs of it
+    Trim,
+    CaseFold,
+    /// Numbers written in words become digits: "seven" is "7", "twenty-one" is "21"
+    NumberWords,
+    /// Sums of money become pence: "£1" and "£1.00" are "100p", as are "$1" and "100¢"
+    Currency,
+}
+
+/// The normalisation rules a question's text answers are marked with, in the order they
+/// are applied
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AnswerNormalization {
+    pub rules: Vec<NormalizationRule>,
+}
+
+impl Default for AnswerNormalization {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                NormalizationRule::Unicode,
+                NormalizationRule::Trim,
+                NormalizationRule::CaseFold,
+                NormalizationRule::NumberWords,
+                NormalizationRule::Currency,
+            ],
+        }
+    }
+}
+
+impl AnswerNormalization {
+    /// The same rules less one, e.g. without case folding for a case-sensitive answer
+    pub fn without(&self, rule: NormalizationRule) -> Self {
+        Self {
+            rules: self.rules.iter().copied().filter(|other| *other != rule).collect(),
+        }
+    }
+}
+
+impl QuestionContent {
+    /// How the question's text answers are normalised, or the defaults when not set
+    pub fn answer_normalization(&self) -> Result<AnswerNormalization, serde_json::Error> {
+        match self.additional_data.as_ref().and_then(|data| data.get(NORMALIZATION_KEY)) {
+            Some(value) => serde_json::from_value(value.clone()),
+            None => Ok(AnswerNormalization::default()),
+        }
+    }
+}
+
+/// Key in `QuestionContent::additional_data` holding a short-answer question's `ShortAnswerConfig`
+pub const SHORT_ANSWER_KEY: &str = "short_answer";
+
+/// How a short-answer question is marked. Answers are compared after normalising case,
+/// whitespace and punctuation, so "st. paul's" matches "St Pauls".
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ShortAnswerConfig {
+    /// Other answers that also count, e.g. synonyms for a vocabulary question
+    #[serde(default)]
+    pub accepted_answers: Vec<String>,
+    /// Most typing mistakes (edits) allowed; `None` allows more for longer answers
+    #[serde(default)]
+    pub max_edit_distance: Option<usize>,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+impl ShortAnswerConfig {
+    /// Edits allowed against an expected answer of this many characters
+    pub fn allowed_edits(&self, answer_length: usize) -> usize {
+        self.max_edit_distance.unwrap_or(match answer_length {
+            0..=4 => 0,
+            5..=8 => 1,
+            _ => 2,
+        })
+    }
+}
+
+impl QuestionContent {
+    /// The question's short-answer marking rules, or the defaults when none are set
+    pub fn short_answer_config(&self) -> Result<ShortAnswerConfig, serde_json::Error> {
+        match self.additional_data.as_ref().and_then(|data| data.get(SHORT_ANSWER_KEY)) {
+            Some(value) => serde_json::from_value(value.clone()),
+            None => Ok(ShortAnswerConfig::default()),
+        }
+    }
+}
+
+/// Key in `QuestionContent::additional_data` holding a question's `AudioPrompt`
+pub const AUDIO_PROMPT_KEY: &str = "audio_prompt";
+
+/// A clip read aloud with the question, e.g. a parent saying the word in a spelling test.
+/// The clip is also one of the question's audio assets.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AudioPrompt {
+    /// Path of the audio asset
+    pub file_path: String,
+    /// Whose voice it is, e.g. "Mum", shown to the parent managing clips
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl QuestionContent {
+    /// The clip to play with the question, if one has been recorded
+    pub fn audio_prompt(&self) -> Result<Option<AudioPrompt>, serde_json::Error> {
+        match self.additional_data.as_ref().and_then(|data| data.get(AUDIO_PROMPT_KEY)) {
+            Some(value) => serde_json::from_value(value.clone()).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Key in `QuestionContent::additional_data` mapping multiple-choice option text to an `OptionImage`
+pub const OPTION_IMAGES_KEY: &str = "option_images";
+
+/// A picture shown in place of (or beside) a multiple-choice option, e.g. the shapes in
+/// "which shape is a pentagon?". The option text stays the answer, so shuffling the options
+/// and marking are unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OptionImage {
+    /// Path of the image asset, relative to the app's assets like `image_url`
+    pub file_path: String,
+    /// Read out instead of the option text when set
+    #[serde(default)]
+    pub alt_text: Option<String>,
+}
+
+impl QuestionContent {
+    /// Images for the question's options, keyed by option text; empty for text-only options
+    pub fn option_images(&self) -> Result<HashMap<String, OptionImage>, serde_json::Error> {
+        match self.additional_data.as_ref().and_then(|data| data.get(OPTION_IMAGES_KEY)) {
+            Some(value) => serde_json::from_value(value.clone()),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    /// `assets` plus an image asset for every option image not already listed, so option
+    /// pictures are stored and served like any other question asset
+    pub fn assets_with_option_images(&self, assets: Option<&Vec<Asset>>) -> Vec<Asset> {
+        let mut all_assets = assets.cloned().unwrap_or_default();
+        let mut option_images: Vec<(String, OptionImage)> = self.option_images()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        option_images.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (option, image) in option_images {
+            if all_assets.iter().any(|asset| asset.file_path == image.file_path) {
+                continue;
+            }
+            all_assets.push(Asset {
+                id: None,
+                question_id: QuestionId(0), // Set when the question is stored
+                asset_type: AssetType::Image,
+                file_path: image.file_path,
+                alt_text: Some(image.alt_text.unwrap_or(option)),
+                file_size: None,
+                created_at: None,
+            });
+        }
+
+        all_assets
+    }
+}
+
+/// Key in `QuestionContent::additional_data` where explanations were written before
+/// questions had an `explanation` field. Packs and imports using it are still accepted.
+pub const EXPLANATION_KEY: &str = "explanation";
+
+impl QuestionContent {
+    /// An explanation written under `EXPLANATION_KEY`, for content from before the field
+    pub fn legacy_explanation(&self) -> Option<&str> {
+        self.additional_data.as_ref()
+            .and_then(|data| data.get(EXPLANATION_KEY))
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|explanation| !explanation.is_empty())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlankConfig {
     pub position: usize,
     pub expected_answer: String,
     pub case_sensitive: bool,
     pub accept_alternatives: Option<Vec<String>>,
+    /// Most typing mistakes (edits) allowed in this blank; `None` allows one in answers of
+    /// eight letters or more. Numbers must always be exactly right.
+    #[serde(default)]
+    pub max_edit_distance: Option<usize>,
+}
+
+impl BlankConfig {
+    /// Edits allowed against an expected answer of this many characters
+    pub fn allowed_edits(&self, answer_length: usize) -> usize {
+        self.max_edit_distance.unwrap_or(if answer_length >= 8 { 1 } else { 0 })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,12 +578,40 @@ pub enum Answer {
     Multiple(Vec<String>),
     Coordinates(Vec<Coordinate>),
     Mapping(HashMap<String, String>),
+    Drawing(Drawing),
+    /// Items in the order they were put, first to last. Written as `{"order": [...]}` so
+    /// it can't be mistaken for a multiple-answer list.
+    Ordering { order: Vec<String> },
+    /// Where on a hotspot question's image was clicked, in image pixels
+    Hotspot { x: f64, y: f64 },
+}
+
+/// A handwritten or drawn answer, e.g. a digit written on the canvas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Drawing {
+    /// Canvas size the strokes were drawn on
+    pub width: f32,
+    pub height: f32,
+    /// Pen strokes, each a list of `[x, y]` points in canvas coordinates
+    pub strokes: Vec<Vec<[f32; 2]>>,
+    /// The canvas rendered as a base64 PNG, for parent review
+    #[serde(default)]
+    pub image_png_base64: Option<String>,
+    /// What a recogniser read from the drawing. Always set by the backend, never trusted from the client.
+    #[serde(default)]
+    pub recognized_text: Option<String>,
+}
+
+impl Drawing {
+    pub fn point_count(&self) -> usize {
+        self.strokes.iter().map(|stroke| stroke.len()).sum()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Asset {
     pub id: Option<u32>,
-    pub question_id: u32,
+    pub question_id: QuestionId,
     pub asset_type: AssetType,
     pub file_path: String,
     pub alt_text: Option<String>,
@@ -123,6 +658,8 @@ impl Question {
             tags: Vec::new(),
             assets: None,
             created_at: None,
+            source: None,
+            explanation: None,
         }
     }
 
@@ -135,4 +672,34 @@ impl Question {
         self.tags = tags;
         self
     }
+
+    pub fn with_source(mut self, source: QuestionSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_explanation(mut self, explanation: &str) -> Self {
+        self.explanation = Some(explanation.to_string());
+        self
+    }
+
+    /// The hints to offer, in order: the ones written for the question, otherwise taking
+    /// away two wrong options or showing the first letter where the question type allows
+    pub fn available_hints(&self) -> Vec<Hint> {
+        let written = self.content.hints().unwrap_or_default();
+        if !written.is_empty() {
+            return written;
+        }
+        match self.question_type {
+            QuestionType::MultipleChoice => {
+                let wrong_options = self.content.options.as_ref().map_or(0, |options| options.len().saturating_sub(1));
+                match wrong_options.saturating_sub(1).min(2) {
+                    0 => Vec::new(),
+                    count => vec![Hint::EliminateOptions { count }],
+                }
+            }
+            QuestionType::FillBlank | QuestionType::ShortAnswer => vec![Hint::FirstLetter],
+            _ => Vec::new(),
+        }
+    }
 }
\ No newline at end of file
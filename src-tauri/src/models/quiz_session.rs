@@ -1,13 +1,13 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use super::{Answer, KeyStage};
+use super::{Answer, KeyStage, MixId, ProfileId, QuestionId, SessionId};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuizSession {
-    pub id: Option<u32>,
-    pub profile_id: u32,
-    pub mix_id: Option<u32>,
+    pub id: Option<SessionId>,
+    pub profile_id: ProfileId,
+    pub mix_id: Option<MixId>,
     pub subject_filter: Option<Vec<String>>,
     pub key_stage_filter: Option<Vec<KeyStage>>,
     pub started_at: Option<DateTime<Utc>>,
@@ -20,11 +20,11 @@ pub struct QuizSession {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionData {
-    pub question_order: Vec<u32>, // question IDs in order presented
+    pub question_order: Vec<QuestionId>, // question IDs in order presented
     pub current_question_index: usize,
-    pub time_per_question: HashMap<u32, u32>, // question_id -> time_spent
-    pub user_answers: HashMap<u32, Answer>, // question_id -> user_answer
-    pub feedback_shown: HashMap<u32, bool>, // question_id -> feedback_shown
+    pub time_per_question: HashMap<QuestionId, u32>, // question_id -> time_spent
+    pub user_answers: HashMap<QuestionId, Answer>, // question_id -> user_answer
+    pub feedback_shown: HashMap<QuestionId, bool>, // question_id -> feedback_shown
     pub paused_at: Option<DateTime<Utc>>,
     pub pause_duration: u32, // total pause time in seconds
 }
@@ -32,8 +32,8 @@ pub struct SessionData {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuestionAttempt {
     pub id: Option<u32>,
-    pub session_id: u32,
-    pub question_id: u32,
+    pub session_id: SessionId,
+    pub question_id: QuestionId,
     pub user_answer: Answer,
     pub is_correct: bool,
     pub time_taken: Option<u32>, // in seconds
@@ -53,8 +53,8 @@ pub struct QuizResult {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StartQuizRequest {
-    pub profile_id: u32,
-    pub mix_id: Option<u32>,
+    pub profile_id: ProfileId,
+    pub mix_id: Option<MixId>,
     pub subject_filter: Option<Vec<String>>,
     pub key_stage_filter: Option<Vec<KeyStage>>,
     pub question_count: Option<u32>,
@@ -62,17 +62,17 @@ pub struct StartQuizRequest {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitAnswerRequest {
-    pub session_id: u32,
-    pub question_id: u32,
+    pub session_id: SessionId,
+    pub question_id: QuestionId,
     pub user_answer: Answer,
     pub time_taken: Option<u32>,
 }
 
 impl QuizSession {
     pub fn new(
-        profile_id: u32,
+        profile_id: ProfileId,
         total_questions: u32,
-        mix_id: Option<u32>,
+        mix_id: Option<MixId>,
         subject_filter: Option<Vec<String>>,
         key_stage_filter: Option<Vec<KeyStage>>,
     ) -> Self {
@@ -121,16 +121,16 @@ impl SessionData {
         }
     }
 
-    pub fn set_question_order(&mut self, question_ids: Vec<u32>) {
+    pub fn set_question_order(&mut self, question_ids: Vec<QuestionId>) {
         self.question_order = question_ids;
         self.current_question_index = 0;
     }
 
-    pub fn current_question_id(&self) -> Option<u32> {
+    pub fn current_question_id(&self) -> Option<QuestionId> {
         self.question_order.get(self.current_question_index).copied()
     }
 
-    pub fn next_question(&mut self) -> Option<u32> {
+    pub fn next_question(&mut self) -> Option<QuestionId> {
         if self.current_question_index + 1 < self.question_order.len() {
             self.current_question_index += 1;
             self.current_question_id()
@@ -139,7 +139,7 @@ impl SessionData {
         }
     }
 
-    pub fn record_answer(&mut self, question_id: u32, answer: Answer, time_taken: u32) {
+    pub fn record_answer(&mut self, question_id: QuestionId, answer: Answer, time_taken: u32) {
         self.user_answers.insert(question_id, answer);
         self.time_per_question.insert(question_id, time_taken);
     }
@@ -158,8 +158,8 @@ impl SessionData {
 
 impl QuestionAttempt {
     pub fn new(
-        session_id: u32,
-        question_id: u32,
+        session_id: SessionId,
+        question_id: QuestionId,
         user_answer: Answer,
         is_correct: bool,
         attempt_order: u32,
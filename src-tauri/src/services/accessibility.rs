@@ -0,0 +1,410 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Question, ProfileId, QuestionId};
+use crate::database::DatabaseManager;
+use crate::services::{ContentManager, QuizConfig, QuizSession};
+use std::sync::Arc;
+use std::collections::HashMap;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+
+/// Key in `QuestionContent::additional_data` holding the simplified-vocabulary wording
+pub const SIMPLIFIED_TEXT_KEY: &str = "simplified_text";
+
+/// Key in `QuestionContent::additional_data` holding the syllable-chunked question text
+pub const SYLLABLE_TEXT_KEY: &str = "syllable_text";
+
+/// Separator placed between syllables in chunked text
+pub const SYLLABLE_SEPARATOR: char = '·';
+
+/// How much longer timers run with extended timers on
+const EXTENDED_TIMER_FACTOR: f32 = 1.5;
+
+/// Longest simplified wording accepted, in line with question text
+const MAX_SIMPLIFIED_TEXT_LENGTH: usize = 1000;
+
+/// Words shorter than this are left whole
+const MIN_CHUNKED_WORD_LENGTH: usize = 4;
+
+/// Per-profile accessibility settings, set by a parent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AccessibilitySettings {
+    pub profile_id: ProfileId,
+    /// Serve question text split into syllables (`e·le·phant`) alongside the original
+    pub syllable_hints: bool,
+    /// Serve a question's simplified wording in place of its text, where one exists
+    pub simplified_vocabulary: bool,
+    /// Give quiz and question timers more time
+    pub extended_timers: bool,
+}
+
+impl AccessibilitySettings {
+    fn defaults(profile_id: ProfileId) -> Self {
+        Self {
+            profile_id,
+            syllable_hints: false,
+            simplified_vocabulary: false,
+            extended_timers: false,
+        }
+    }
+
+    /// Factor the frontend applies to its own per-question timers
+    pub fn timer_multiplier(&self) -> f32 {
+        if self.extended_timers {
+            EXTENDED_TIMER_FACTOR
+        } else {
+            1.0
+        }
+    }
+
+    /// Lengthen a quiz's time limit before the session starts
+    pub fn adjust_config(&self, config: &mut QuizConfig) {
+        config.time_limit_seconds = config.time_limit_seconds
+            .map(|seconds| (seconds as f32 * self.timer_multiplier()).round() as u32);
+    }
+
+    /// Rewrite a question as this profile should see it. Only the wording changes,
+    /// so answers are checked exactly as before.
+    pub fn transform_question(&self, question: &mut Question) {
+        if self.simplified_vocabulary {
+            if let Some(simplified) = simplified_text(question) {
+                question.content.text = simplified;
+            }
+        }
+
+        if self.syllable_hints {
+            let chunked = syllable_hints(&question.content.text);
+            question.content.additional_data
+                .get_or_insert_with(HashMap::new)
+                .insert(SYLLABLE_TEXT_KEY.to_string(), serde_json::Value::String(chunked));
+        }
+    }
+
+    /// Rewrite every question in a session being handed to the frontend
+    pub fn transform_session(&self, session: &mut QuizSession) {
+        for question in session.questions.iter_mut() {
+            self.transform_question(question);
+        }
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Self::defaults(self.profile_id)
+    }
+}
+
+/// A question's simplified-vocabulary wording, if it has one
+pub fn simplified_text(question: &Question) -> Option<String> {
+    question.content.additional_data.as_ref()?
+        .get(SIMPLIFIED_TEXT_KEY)?
+        .as_str()
+        .map(|text| text.to_string())
+}
+
+/// Accessibility settings and content transforms for dyslexic and early readers
+pub struct AccessibilityService {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+}
+
+impl AccessibilityService {
+    /// Create a new accessibility service
+    pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>) -> Self {
+        Self {
+            db_manager,
+            content_manager,
+        }
+    }
+
+    /// Get a profile's settings, falling back to everything off
+    pub fn get_settings(&self, profile_id: ProfileId) -> AppResult<AccessibilitySettings> {
+        let stored = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT syllable_hints, simplified_vocabulary, extended_timers
+                 FROM accessibility_settings WHERE profile_id = ?1",
+                params![profile_id],
+                |row| Ok(AccessibilitySettings {
+                    profile_id,
+                    syllable_hints: row.get(0)?,
+                    simplified_vocabulary: row.get(1)?,
+                    extended_timers: row.get(2)?,
+                }),
+            ).optional()
+        })?;
+
+        Ok(stored.unwrap_or_else(|| AccessibilitySettings::defaults(profile_id)))
+    }
+
+    /// Save a profile's settings
+    pub fn set_settings(&self, settings: &AccessibilitySettings) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            if settings.is_default() {
+                conn.execute(
+                    "DELETE FROM accessibility_settings WHERE profile_id = ?1",
+                    params![settings.profile_id],
+                )?;
+                return Ok(());
+            }
+
+            conn.execute(
+                "INSERT OR REPLACE INTO accessibility_settings
+                 (profile_id, syllable_hints, simplified_vocabulary, extended_timers, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    settings.profile_id,
+                    settings.syllable_hints,
+                    settings.simplified_vocabulary,
+                    settings.extended_timers,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Store (or with `None`, remove) the simplified-vocabulary wording of a question
+    pub fn set_simplified_text(&self, question_id: QuestionId, text: Option<String>) -> AppResult<Question> {
+        let mut question = self.content_manager.get_question_by_id(question_id)?;
+        let additional_data = question.content.additional_data.get_or_insert_with(HashMap::new);
+
+        match text.as_deref().map(str::trim).filter(|text| !text.is_empty()) {
+            Some(text) => {
+                if text.chars().count() > MAX_SIMPLIFIED_TEXT_LENGTH {
+                    return Err(AppError::InvalidInput(format!(
+                        "Simplified text must be at most {} characters", MAX_SIMPLIFIED_TEXT_LENGTH
+                    )));
+                }
+                additional_data.insert(SIMPLIFIED_TEXT_KEY.to_string(), serde_json::Value::String(text.to_string()));
+            }
+            None => {
+                additional_data.remove(SIMPLIFIED_TEXT_KEY);
+            }
+        }
+        if additional_data.is_empty() {
+            question.content.additional_data = None;
+        }
+
+        self.content_manager.update_question(question_id, question)?;
+        self.content_manager.get_question_by_id(question_id)
+    }
+}
+
+/// Split every longer word of `text` into syllables, e.g. `The elephant` -> `The e·le·phant`.
+///
+/// Uses spelling rules rather than a dictionary, so the split is a reading hint, not a
+/// dictionary hyphenation: a single consonant between vowels starts the next syllable,
+/// a consonant pair is split down the middle, and a final consonant + "le" stays together.
+pub fn syllable_hints(text: &str) -> String {
+    let mut output = String::with_capacity(text.len() * 2);
+    let mut word = Vec::new();
+
+    for c in text.chars() {
+        if c.is_alphabetic() {
+            word.push(c);
+        } else {
+            push_chunked_word(&mut output, &word);
+            word.clear();
+            output.push(c);
+        }
+    }
+    push_chunked_word(&mut output, &word);
+
+    output
+}
+
+fn push_chunked_word(output: &mut String, word: &[char]) {
+    let breaks = syllable_breaks(word);
+    for (index, c) in word.iter().enumerate() {
+        if breaks.contains(&index) {
+            output.push(SYLLABLE_SEPARATOR);
+        }
+        output.push(*c);
+    }
+}
+
+/// Character positions at which a new syllable starts
+fn syllable_breaks(word: &[char]) -> Vec<usize> {
+    let len = word.len();
+    if len < MIN_CHUNKED_WORD_LENGTH {
+        return Vec::new();
+    }
+
+    let lower: Vec<char> = word.iter().map(|c| c.to_ascii_lowercase()).collect();
+    let is_vowel = |index: usize| {
+        matches!(lower[index], 'a' | 'e' | 'i' | 'o' | 'u') || (lower[index] == 'y' && index > 0)
+    };
+
+    // Runs of vowels, as [start, end)
+    let mut groups = Vec::new();
+    let mut index = 0;
+    while index < len {
+        if is_vowel(index) {
+            let start = index;
+            while index < len && is_vowel(index) {
+                index += 1;
+            }
+            groups.push((start, index));
+        } else {
+            index += 1;
+        }
+    }
+
+    // "table", "little": consonant + le is its own syllable
+    let ends_with_le = lower[len - 1] == 'e' && lower[len - 2] == 'l' && !is_vowel(len - 3);
+    // "make", "stone": a final e is silent
+    if groups.len() > 1 && !ends_with_le && groups.last() == Some(&(len - 1, len)) && lower[len - 1] == 'e' {
+        groups.pop();
+    }
+
+    let mut breaks = Vec::new();
+    for (position, pair) in groups.windows(2).enumerate() {
+        let (start, end) = (pair[0].1, pair[1].0);
+        let is_final_le = ends_with_le && position == groups.len() - 2;
+
+        let split = if is_final_le {
+            end - 2
+        } else {
+            match end - start {
+                1 => start,
+                2 if lower[start] == 'c' && lower[start + 1] == 'k' => end,
+                _ if is_digraph(lower[start], lower[start + 1]) => if end - start == 2 { start } else { start + 2 },
+                _ => start + 1,
+            }
+        };
+        if split > 0 && split < len {
+            breaks.push(split);
+        }
+    }
+
+    breaks
+}
+
+/// Consonant pairs read as one sound, which are never split
+fn is_digraph(first: char, second: char) -> bool {
+    matches!((first, second), ('c', 'h') | ('s', 'h') | ('t', 'h') | ('p', 'h') | ('w', 'h') | ('c', 'k') | ('n', 'g'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{Answer, KeyStage, QuestionContent, QuestionType};
+    use crate::services::{LateAnswer, QuizMode, SecurityService, DEFAULT_HINT_PENALTY_PERCENT};
+    use tempfile::tempdir;
+
+    fn create_test_accessibility_service() -> (AccessibilityService, Arc<DatabaseManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            security_service,
+            temp_dir.path().join("content"),
+        ));
+
+        (AccessibilityService::new(db_service.manager(), content_manager), db_service.manager(), temp_dir)
+    }
+
+    fn create_test_profile(db_manager: &DatabaseManager) -> ProfileId {
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_syllable_hints() {
+        assert_eq!(syllable_hints("elephant"), "e·le·phant");
+        assert_eq!(syllable_hints("Which animal is the biggest?"), "Which a·ni·mal is the big·gest?");
+        assert_eq!(syllable_hints("table"), "ta·ble");
+        assert_eq!(syllable_hints("little rabbit"), "lit·tle rab·bit");
+        assert_eq!(syllable_hints("make a rocket"), "make a rock·et");
+        assert_eq!(syllable_hints("3 + 4 = ?"), "3 + 4 = ?");
+    }
+
+    #[test]
+    fn test_settings_round_trip_and_timers() {
+        let (service, db, _temp_dir) = create_test_accessibility_service();
+        let profile_id = create_test_profile(&db);
+
+        assert_eq!(service.get_settings(profile_id).unwrap(), AccessibilitySettings::defaults(profile_id));
+
+        let settings = AccessibilitySettings {
+            profile_id,
+            syllable_hints: true,
+            simplified_vocabulary: false,
+            extended_timers: true,
+        };
+        service.set_settings(&settings).unwrap();
+        assert_eq!(service.get_settings(profile_id).unwrap(), settings);
+
+        let mut config = QuizConfig {
+            subject: "english".to_string(),
+            key_stage: KeyStage::KS1,
+            question_count: 5,
+            difficulty_range: None,
+            time_limit_seconds: Some(120),
+            randomize_questions: true,
+            randomize_answers: true,
+            max_consecutive_same_tag: None,
+            boss_question: false,
+            warm_up_questions: 0,
+            cool_down_review: false,
+            hint_penalty_percent: DEFAULT_HINT_PENALTY_PERCENT,
+            time_limit_per_question: None,
+            time_limit_total: None,
+            late_answers: LateAnswer::default(),
+            stop_when_mastered: false,
+            mode: QuizMode::default(),
+            allowed_question_types: Vec::new(),
+        };
+        settings.adjust_config(&mut config);
+        assert_eq!(config.time_limit_seconds, Some(180));
+    }
+
+    #[test]
+    fn test_simplified_text_is_served_when_enabled() {
+        let (service, _db, _temp_dir) = create_test_accessibility_service();
+        let subject_id = service.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "science").unwrap().id.unwrap();
+        let question_id = service.content_manager.add_question(Question::new(
+            subject_id,
+            KeyStage::KS2,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: "Which organ circulates blood?".to_string(),
+                options: Some(vec!["Heart".to_string(), "Lungs".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("Heart".to_string()),
+        )).unwrap();
+
+        let mut question = service.set_simplified_text(question_id, Some("Which part pumps blood?".to_string())).unwrap();
+        let settings = AccessibilitySettings {
+            simplified_vocabulary: true,
+            syllable_hints: true,
+            ..AccessibilitySettings::defaults(ProfileId(1))
+        };
+        settings.transform_question(&mut question);
+
+        assert_eq!(question.content.text, "Which part pumps blood?");
+        let hints = question.content.additional_data.as_ref().unwrap()[SYLLABLE_TEXT_KEY].as_str().unwrap();
+        assert_eq!(hints, "Which part pumps blood?");
+        assert!(matches!(question.correct_answer, Answer::Text(ref answer) if answer == "Heart"));
+
+        let question = service.set_simplified_text(question_id, None).unwrap();
+        assert!(simplified_text(&question).is_none());
+    }
+}
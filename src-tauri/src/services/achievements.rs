@@ -0,0 +1,280 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{Achievement, AchievementCategory, ProfileId};
+use crate::services::profile_manager::QuizResult;
+use crate::services::StreakTracker;
+use std::collections::HashMap;
+use std::sync::Arc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Local, Utc};
+
+/// What has to happen for an achievement to be earned
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AchievementCondition {
+    /// Questions answered in every subject together
+    QuestionsAnswered { count: u32 },
+    /// Questions answered in one subject, by name (e.g. `mathematics`)
+    SubjectQuestions { subject: String, count: u32 },
+    /// Different subjects with at least one question answered
+    SubjectsTried { count: u32 },
+    /// Every question right in a single quiz of at least `min_questions`
+    PerfectQuiz { min_questions: u32 },
+    /// Days in a row with a completed quiz, as the daily streak counts them
+    PracticeStreak { days: u32 },
+}
+
+/// One achievement that can be earned, as stored in `achievement_rules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AchievementRule {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub icon: String,
+    pub category: AchievementCategory,
+    pub condition: AchievementCondition,
+}
+
+/// A badge as the frontend shows it: every rule, with when it was earned if it has been
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Badge {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub icon: String,
+    pub category: AchievementCategory,
+    pub earned_at: Option<DateTime<Utc>>,
+}
+
+/// What a profile has done so far, for testing conditions against
+struct ProfileFacts {
+    questions_answered: u32,
+    subject_questions: HashMap<String, u32>,
+    streak_days: u32,
+}
+
+impl AchievementCondition {
+    fn is_met(&self, facts: &ProfileFacts, quiz_result: &QuizResult) -> bool {
+        match self {
+            AchievementCondition::QuestionsAnswered { count } => facts.questions_answered >= *count,
+            AchievementCondition::SubjectQuestions { subject, count } => {
+                facts.subject_questions.get(subject).copied().unwrap_or(0) >= *count
+            }
+            AchievementCondition::SubjectsTried { count } => {
+                facts.subject_questions.values().filter(|&&answered| answered > 0).count() as u32 >= *count
+            }
+            AchievementCondition::PerfectQuiz { min_questions } => {
+                !quiz_result.practice
+                    && quiz_result.questions_answered >= *min_questions
+                    && quiz_result.correct_answers == quiz_result.questions_answered
+            }
+            AchievementCondition::PracticeStreak { days } => facts.streak_days >= *days,
+        }
+    }
+}
+
+/// Awards achievements by checking each rule in `achievement_rules` after a quiz
+pub struct AchievementManager {
+    db_manager: Arc<DatabaseManager>,
+    streaks: StreakTracker,
+}
+
+impl AchievementManager {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            streaks: StreakTracker::new(db_manager.clone()),
+            db_manager,
+        }
+    }
+
+    /// Every achievement that can be earned, in display order
+    pub fn get_rules(&self) -> AppResult<Vec<AchievementRule>> {
+        let rows: Vec<(String, String, String, String, String, String)> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, name, description, icon, category, condition FROM achievement_rules ORDER BY sort_order, id"
+            )?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+            })?;
+            rows.collect()
+        })?;
+
+        let mut rules = Vec::with_capacity(rows.len());
+        for (id, name, description, icon, category, condition) in rows {
+            let category = AchievementCategory::parse(&category)
+                .ok_or_else(|| AppError::Internal(format!("Achievement '{}' has unknown category '{}'", id, category)))?;
+            match serde_json::from_str(&condition) {
+                Ok(condition) => rules.push(AchievementRule { id, name, description, icon, category, condition }),
+                // A rule this version doesn't understand is left for a newer one to award
+                Err(e) => log::warn!("Skipping achievement '{}': {}", id, e),
+            }
+        }
+        Ok(rules)
+    }
+
+    /// Every badge, earned or not, for the profile's badge shelf
+    pub fn get_achievements(&self, profile_id: ProfileId) -> AppResult<Vec<Badge>> {
+        let earned = self.earned_at(profile_id)?;
+        Ok(self.get_rules()?
+            .into_iter()
+            .map(|rule| Badge {
+                earned_at: earned.get(&rule.id).copied(),
+                id: rule.id,
+                name: rule.name,
+                description: rule.description,
+                icon: rule.icon,
+                category: rule.category,
+            })
+            .collect())
+    }
+
+    /// Award every achievement the profile has now earned, given the quiz it just finished.
+    /// Returns only those earned for the first time.
+    pub fn check_after_quiz(&self, profile_id: ProfileId, quiz_result: &QuizResult) -> AppResult<Vec<Achievement>> {
+        let earned = self.earned_at(profile_id)?;
+        let facts = self.load_facts(profile_id)?;
+
+        let new_achievements: Vec<Achievement> = self.get_rules()?
+            .into_iter()
+            .filter(|rule| !earned.contains_key(&rule.id) && rule.condition.is_met(&facts, quiz_result))
+            .map(|rule| Achievement {
+                id: rule.id,
+                name: rule.name,
+                description: rule.description,
+                icon: rule.icon,
+                earned_at: Utc::now(),
+                category: rule.category,
+            })
+            .collect();
+
+        self.db_manager.transaction(|tx| {
+            for achievement in &new_achievements {
+                tx.execute(
+                    "INSERT OR IGNORE INTO achievements (profile_id, achievement_id, name, description, icon, category, earned_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        profile_id,
+                        achievement.id,
+                        achievement.name,
+                        achievement.description,
+                        achievement.icon,
+                        achievement.category.as_str(),
+                        achievement.earned_at.to_rfc3339(),
+                    ],
+                )?;
+            }
+            Ok(())
+        })?;
+
+        Ok(new_achievements)
+    }
+
+    fn earned_at(&self, profile_id: ProfileId) -> AppResult<HashMap<String, DateTime<Utc>>> {
+        let rows: Vec<(String, String)> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT achievement_id, earned_at FROM achievements WHERE profile_id = ?1")?;
+            let rows = stmt.query_map(params![profile_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })?;
+
+        Ok(rows.into_iter()
+            .map(|(id, earned_at)| {
+                let earned_at = DateTime::parse_from_rfc3339(&earned_at)
+                    .map(|earned_at| earned_at.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now());
+                (id, earned_at)
+            })
+            .collect())
+    }
+
+    /// Answer counts from the profile's progress, and its daily quiz streak
+    fn load_facts(&self, profile_id: ProfileId) -> AppResult<ProfileFacts> {
+        let subject_rows: Vec<(String, u32)> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT subject, SUM(questions_answered) FROM progress WHERE profile_id = ?1 GROUP BY subject"
+            )?;
+            let rows = stmt.query_map(params![profile_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })?;
+        let streak_days = self.streaks.get_streak(profile_id, &Local::now())?.current_days;
+
+        let subject_questions: HashMap<String, u32> = subject_rows.into_iter().collect();
+        Ok(ProfileFacts {
+            questions_answered: subject_questions.values().sum(),
+            subject_questions,
+            streak_days,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::CreateProfileRequest;
+    use crate::services::{ProfileManager, SecurityService};
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    fn quiz(subject: &str, questions_answered: u32, correct_answers: u32) -> QuizResult {
+        QuizResult {
+            subject: subject.to_string(),
+            key_stage: "KS2".to_string(),
+            questions_answered,
+            correct_answers,
+            time_spent_seconds: 60,
+            session_id: None,
+            practice: false,
+        }
+    }
+
+    fn ids(achievements: &[Achievement]) -> Vec<&str> {
+        achievements.iter().map(|achievement| achievement.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_rules_award_each_badge_once() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let profile_manager = ProfileManager::new(db_manager.clone(), Arc::new(SecurityService::new().unwrap()));
+        let profile_id = profile_manager.create_profile(CreateProfileRequest {
+            name: "Ada".to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        }).unwrap().id.unwrap();
+        let manager = AchievementManager::new(db_manager.clone());
+
+        let earned = profile_manager.update_progress(profile_id, quiz("mathematics", 5, 5)).unwrap();
+        assert_eq!(ids(&earned), vec!["first_steps", "perfect_score"]);
+        assert!(manager.check_after_quiz(profile_id, &quiz("mathematics", 5, 5)).unwrap().is_empty());
+
+        // Quizzes on the six days before today make a week
+        db_manager.execute(|conn| {
+            for days_ago in 1..=6 {
+                conn.execute(
+                    "INSERT INTO quiz_completions (profile_id, completed_at) VALUES (?1, ?2)",
+                    params![profile_id, (Local::now() - Duration::days(days_ago)).to_rfc3339()],
+                )?;
+            }
+            Ok(())
+        }).unwrap();
+        let earned = profile_manager.update_progress(profile_id, quiz("mathematics", 95, 60)).unwrap();
+        assert_eq!(ids(&earned), vec!["quick_learner", "week_streak", "century", "maths_100"]);
+
+        let badges = manager.get_achievements(profile_id).unwrap();
+        assert_eq!(badges.len(), 7);
+        let unearned: Vec<&str> = badges.iter()
+            .filter(|badge| badge.earned_at.is_none())
+            .map(|badge| badge.id.as_str())
+            .collect();
+        assert_eq!(unearned, vec!["subject_explorer"]);
+
+        // Earned badges still show in progress, under the category they were saved with
+        let progress = profile_manager.get_progress(profile_id).unwrap();
+        assert!(progress.achievements.iter().any(|achievement| achievement.id == "maths_100"
+            && achievement.category.as_str() == "subject_mastery"));
+    }
+}
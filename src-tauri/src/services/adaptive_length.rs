@@ -0,0 +1,97 @@
+use crate::services::answer_timing::TimedAnswer;
+use serde::{Deserialize, Serialize};
+
+/// Answers needed before a quiz can end early, however well or badly it's going
+const MIN_ANSWERS_TO_STOP: usize = 5;
+
+/// Accuracy the child must be confidently above to have mastered the questions
+const MASTERY_ACCURACY: f64 = 0.8;
+
+/// z-score for the one-sided 95% confidence the lower bound is taken at
+const MASTERY_CONFIDENCE_Z: f64 = 1.645;
+
+/// Answers compared at the start and end of a quiz to spot tiredness
+const FATIGUE_WINDOW: usize = 3;
+
+/// How many times slower the latest answers must be than the first ones to suggest tiredness
+const FATIGUE_SLOWDOWN: f64 = 1.5;
+
+/// Why a "stop when mastered" quiz ended before its last question
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EarlyStop {
+    /// Enough right answers to be confident the child has mastered this
+    Mastered,
+    /// Answers getting slower and less accurate: time for a break
+    Tired,
+}
+
+/// Whether a quiz should end now, judged from its answers so far as timed by the engine
+pub fn check_early_stop(answers: &[TimedAnswer]) -> Option<EarlyStop> {
+    if answers.len() < MIN_ANSWERS_TO_STOP {
+        return None;
+    }
+
+    let correct = answers.iter().filter(|answer| answer.is_correct).count();
+    if wilson_lower_bound(correct, answers.len()) >= MASTERY_ACCURACY {
+        return Some(EarlyStop::Mastered);
+    }
+
+    if answers.len() >= FATIGUE_WINDOW * 2 {
+        let first = &answers[..FATIGUE_WINDOW];
+        let last = &answers[answers.len() - FATIGUE_WINDOW..];
+        let correct_in = |window: &[TimedAnswer]| window.iter().filter(|answer| answer.is_correct).count();
+        if mean_elapsed_ms(last) >= mean_elapsed_ms(first) * FATIGUE_SLOWDOWN && correct_in(last) < correct_in(first) {
+            return Some(EarlyStop::Tired);
+        }
+    }
+
+    None
+}
+
+/// Lower bound of the Wilson score interval for the true accuracy behind `correct` of `total`
+fn wilson_lower_bound(correct: usize, total: usize) -> f64 {
+    let n = total as f64;
+    let p = correct as f64 / n;
+    let z2 = MASTERY_CONFIDENCE_Z * MASTERY_CONFIDENCE_Z;
+    let centre = p + z2 / (2.0 * n);
+    let margin = MASTERY_CONFIDENCE_Z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+    (centre - margin) / (1.0 + z2 / n)
+}
+
+fn mean_elapsed_ms(answers: &[TimedAnswer]) -> f64 {
+    answers.iter().map(|answer| answer.elapsed_ms as f64).sum::<f64>() / answers.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answers(outcomes: &[(u64, bool)]) -> Vec<TimedAnswer> {
+        outcomes.iter()
+            .map(|&(elapsed_ms, is_correct)| TimedAnswer { elapsed_ms, is_correct })
+            .collect()
+    }
+
+    #[test]
+    fn test_stops_on_confident_mastery_or_tiredness() {
+        // Four right isn't enough to stop on, however quick
+        assert_eq!(check_early_stop(&answers(&[(4000, true); 4])), None);
+
+        // 5/5 still leaves room for doubt; eleven in a row does not
+        assert_eq!(check_early_stop(&answers(&[(4000, true); 5])), None);
+        let mut steady = answers(&[(4000, true); 11]);
+        assert_eq!(check_early_stop(&steady), Some(EarlyStop::Mastered));
+        steady[3].is_correct = false;
+        steady[7].is_correct = false;
+        assert_eq!(check_early_stop(&steady), None);
+
+        // Slowing down while getting more wrong
+        let tiring = answers(&[(4000, true), (5000, true), (4500, false), (6000, true), (9000, false), (8000, false), (10000, true)]);
+        assert_eq!(check_early_stop(&tiring), Some(EarlyStop::Tired));
+
+        // Slowing down on harder questions but still getting them right is fine
+        let thoughtful = answers(&[(4000, true), (5000, false), (4500, true), (6000, true), (9000, true), (8000, true), (10000, false)]);
+        assert_eq!(check_early_stop(&thoughtful), None);
+    }
+}
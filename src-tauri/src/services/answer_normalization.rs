@@ -0,0 +1,166 @@
+use crate::models::{AnswerNormalization, NormalizationRule};
+use unicode_normalization::UnicodeNormalization;
+
+/// Number words up to nineteen, by value
+const UNITS: &[&str] = &[
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+/// Tens from twenty, by value divided by ten
+const TENS: &[(&str, u64)] = &[
+    ("twenty", 2), ("thirty", 3), ("forty", 4), ("fifty", 5),
+    ("sixty", 6), ("seventy", 7), ("eighty", 8), ("ninety", 9),
+];
+
+/// Apply a question's normalisation rules to an answer, in order. Expected and given
+/// answers both go through this, so they're compared on equal terms.
+pub fn normalize_answer(text: &str, normalization: &AnswerNormalization) -> String {
+    normalization.rules.iter().fold(text.to_string(), |text, rule| match rule {
+        NormalizationRule::Unicode => fold_unicode(&text),
+        NormalizationRule::Trim => text.split_whitespace().collect::<Vec<_>>().join(" "),
+        NormalizationRule::CaseFold => text.to_lowercase(),
+        NormalizationRule::NumberWords => replace_number_words(&text),
+        NormalizationRule::Currency => replace_money(&text),
+    })
+}
+
+fn fold_unicode(text: &str) -> String {
+    text.nfkc()
+        .map(|c| match c {
+            '\u{2018}' | '\u{2019}' | '\u{201B}' | '\u{02BC}' => '\'',
+            '\u{201C}' | '\u{201D}' | '\u{201F}' => '"',
+            '\u{2010}'..='\u{2015}' | '\u{2212}' => '-',
+            // What NFKC leaves of vulgar fractions like ½
+            '\u{2044}' => '/',
+            other => other,
+        })
+        .collect()
+}
+
+fn number_word_value(word: &str) -> Option<u64> {
+    let word = word.to_lowercase();
+    UNITS.iter().position(|unit| *unit == word).map(|value| value as u64)
+        .or_else(|| TENS.iter().find(|(tens, _)| *tens == word).map(|&(_, value)| value * 10))
+}
+
+fn is_number_word(word: &str) -> bool {
+    let lower = word.to_lowercase();
+    lower == "hundred" || lower == "thousand"
+        || word.split('-').all(|part| number_word_value(part).is_some())
+}
+
+/// Value of a run of number words such as "three hundred and forty-two"
+fn number_words_value(words: &[&str]) -> u64 {
+    let (mut total, mut current) = (0, 0);
+    for word in words {
+        match word.to_lowercase().as_str() {
+            "hundred" => current = current.max(1) * 100,
+            "thousand" => {
+                total += current.max(1) * 1000;
+                current = 0;
+            }
+            "and" => {}
+            _ => current += word.split('-').filter_map(number_word_value).sum::<u64>(),
+        }
+    }
+    total + current
+}
+
+/// Swap each run of number words for its digits. "and" joins a run only between number
+/// words, so "one and a half" keeps its "and".
+fn replace_number_words(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if !words.iter().any(|word| is_number_word(word)) {
+        return text.to_string();
+    }
+
+    let mut output = Vec::with_capacity(words.len());
+    let mut index = 0;
+    while index < words.len() {
+        if !is_number_word(words[index]) {
+            output.push(words[index].to_string());
+            index += 1;
+            continue;
+        }
+        let mut end = index + 1;
+        while end < words.len() {
+            let joins = words[end].eq_ignore_ascii_case("and")
+                && words.get(end + 1).map_or(false, |next| is_number_word(next));
+            if is_number_word(words[end]) || joins {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+        output.push(number_words_value(&words[index..end]).to_string());
+        index = end;
+    }
+    output.join(" ")
+}
+
+/// Pence (or cents) in an amount like `£1`, `£1.5`, `£0.50` or `100p`. Money questions
+/// are asked in the family's currency, so `$1`, `€0.50`, `50¢` and `50c` are read too.
+fn money_in_pence(word: &str) -> Option<u64> {
+    if let Some(amount) = word.strip_prefix(['£', '$', '€']) {
+        let (pounds, pence) = match amount.split_once('.') {
+            Some((pounds, pence)) if !pence.is_empty() && pence.len() <= 2 => (pounds, format!("{:0<2}", pence)),
+            Some(_) => return None,
+            None => (amount, "00".to_string()),
+        };
+        if pounds.is_empty() || !pounds.chars().all(|c| c.is_ascii_digit()) || !pence.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        return Some(pounds.parse::<u64>().ok()? * 100 + pence.parse::<u64>().ok()?);
+    }
+
+    let pence = word.strip_suffix(['p', 'P', '¢', 'c'])?;
+    if pence.is_empty() || !pence.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    pence.parse().ok()
+}
+
+/// Write every sum of money in pence, so pounds and pence forms of it match
+fn replace_money(text: &str) -> String {
+    if !text.split_whitespace().any(|word| money_in_pence(word).is_some()) {
+        return text.to_string();
+    }
+    text.split_whitespace()
+        .map(|word| money_in_pence(word).map_or_else(|| word.to_string(), |pence| format!("{}p", pence)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_are_normalized_by_each_rule() {
+        let rules = AnswerNormalization::default();
+        let same = |a: &str, b: &str| normalize_answer(a, &rules) == normalize_answer(b, &rules);
+
+        assert!(same("  Seven ", "7"));
+        assert!(same("twenty-one", "21"));
+        assert!(same("Three hundred and forty-two", "342"));
+        assert!(same("two thousand and five", "2005"));
+        assert!(same("seven apples", "7 apples"));
+        assert!(same("£1", "100p"));
+        assert!(same("£1.50", "150p"));
+        assert!(same("£0.5", "50P"));
+        assert!(same("$1.50", "150¢"));
+        assert!(same("St Paul\u{2019}s", "st paul's"));
+        assert!(same("\u{FF17}", "7"));
+        assert!(same("\u{BD}", "1/2"));
+        assert!(same("café", "cafe\u{301}"));
+        assert!(!same("seventeen", "seventy"));
+        assert!(!same("£1.505", "150p"));
+        assert_eq!(normalize_answer("one and a half", &rules), "1 and a half");
+
+        // Only the rules asked for are applied
+        let case_sensitive = rules.without(NormalizationRule::CaseFold);
+        assert_eq!(normalize_answer(" Paris ", &case_sensitive), "Paris");
+        let trim_only = AnswerNormalization { rules: vec![NormalizationRule::Trim] };
+        assert_eq!(normalize_answer("  seven   pounds ", &trim_only), "seven pounds");
+    }
+}
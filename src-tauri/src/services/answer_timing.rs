@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Answers quicker than this can't have involved reading the question
+const RAPID_ANSWER_MS: u64 = 500;
+
+/// Rapid answers among the last `RAPID_WINDOW` that flag a session
+const RAPID_ANSWERS_TO_FLAG: usize = 3;
+const RAPID_WINDOW: usize = 5;
+
+/// Answers quicker than this count as quick guesses
+const QUICK_GUESS_MS: u64 = 2000;
+
+/// Quick guesses among the last `GUESS_WINDOW`, at least half of them wrong, that flag a session
+const QUICK_GUESSES_TO_FLAG: usize = 4;
+const GUESS_WINDOW: usize = 6;
+
+/// Shown with the "slow down" nudge
+pub const SLOW_DOWN_MESSAGE: &str = "Take your time! Read each question carefully before you answer.";
+
+/// Why a session's answers look like they weren't really attempted
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimingFlag {
+    /// Several answers in under half a second
+    RepeatedRapidAnswers,
+    /// Quick answers, mostly wrong: tapping through options until one sticks
+    RapidGuessing,
+}
+
+/// One answer as timed by the engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedAnswer {
+    pub elapsed_ms: u64,
+    pub is_correct: bool,
+}
+
+/// How quickly a session's questions were answered, measured by the engine rather than
+/// trusted from the client
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnswerTiming {
+    /// When the current question was put in front of the child
+    pub question_started_at: Option<DateTime<Utc>>,
+    pub answers: Vec<TimedAnswer>,
+    /// Set the first time answers look rushed; flagged sessions don't count towards mastery
+    pub flag: Option<TimingFlag>,
+    /// A "slow down" nudge is waiting to be shown
+    pub slow_down_pending: bool,
+    /// Answers before this have already led to a nudge, so each nudge needs fresh evidence
+    pub window_start: usize,
+}
+
+impl AnswerTiming {
+    /// Time an answer submitted at `now`, starting the clock for the next question.
+    /// Returns the pattern spotted, if this answer completed one.
+    pub fn record(&mut self, started_at: DateTime<Utc>, now: DateTime<Utc>, is_correct: bool) -> Option<TimingFlag> {
+        let elapsed_ms = (now - self.question_started_at.unwrap_or(started_at)).num_milliseconds().max(0) as u64;
+        self.answers.push(TimedAnswer { elapsed_ms, is_correct });
+        self.question_started_at = Some(now);
+
+        let pattern = self.spot_pattern()?;
+        self.flag.get_or_insert(pattern);
+        self.slow_down_pending = true;
+        self.window_start = self.answers.len();
+        Some(pattern)
+    }
+
+    /// Restart the clock, e.g. after a pause, so time away isn't counted
+    pub fn restart_clock(&mut self, now: DateTime<Utc>) {
+        self.question_started_at = Some(now);
+    }
+
+    /// Whether a nudge was waiting; clears it
+    pub fn take_slow_down(&mut self) -> bool {
+        std::mem::replace(&mut self.slow_down_pending, false)
+    }
+
+    fn spot_pattern(&self) -> Option<TimingFlag> {
+        let fresh = &self.answers[self.window_start.min(self.answers.len())..];
+        let last = |count: usize| &fresh[fresh.len().saturating_sub(count)..];
+
+        let rapid = last(RAPID_WINDOW).iter().filter(|answer| answer.elapsed_ms < RAPID_ANSWER_MS).count();
+        if rapid >= RAPID_ANSWERS_TO_FLAG {
+            return Some(TimingFlag::RepeatedRapidAnswers);
+        }
+
+        let quick: Vec<&TimedAnswer> = last(GUESS_WINDOW).iter()
+            .filter(|answer| answer.elapsed_ms < QUICK_GUESS_MS)
+            .collect();
+        let quick_wrong = quick.iter().filter(|answer| !answer.is_correct).count();
+        if quick.len() >= QUICK_GUESSES_TO_FLAG && quick_wrong * 2 >= quick.len() {
+            return Some(TimingFlag::RapidGuessing);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn answer_after(timing: &mut AnswerTiming, now: &mut DateTime<Utc>, elapsed_ms: i64, is_correct: bool) -> Option<TimingFlag> {
+        let started_at = *now;
+        *now += Duration::milliseconds(elapsed_ms);
+        timing.record(started_at, *now, is_correct)
+    }
+
+    #[test]
+    fn test_repeated_rapid_answers_flag_once_per_window() {
+        let mut timing = AnswerTiming::default();
+        let mut now = Utc::now();
+
+        assert_eq!(answer_after(&mut timing, &mut now, 8000, true), None);
+        assert_eq!(answer_after(&mut timing, &mut now, 300, true), None);
+        assert_eq!(answer_after(&mut timing, &mut now, 5000, true), None);
+        assert_eq!(answer_after(&mut timing, &mut now, 200, true), None);
+        assert_eq!(answer_after(&mut timing, &mut now, 100, false), Some(TimingFlag::RepeatedRapidAnswers));
+        assert_eq!(timing.flag, Some(TimingFlag::RepeatedRapidAnswers));
+        assert!(timing.take_slow_down());
+        assert!(!timing.take_slow_down());
+
+        // The next nudge needs three new rapid answers
+        assert_eq!(answer_after(&mut timing, &mut now, 100, true), None);
+        assert_eq!(answer_after(&mut timing, &mut now, 100, true), None);
+        assert!(answer_after(&mut timing, &mut now, 100, true).is_some());
+    }
+
+    #[test]
+    fn test_quick_wrong_guesses_flag_but_quick_right_answers_do_not() {
+        let mut confident = AnswerTiming::default();
+        let mut now = Utc::now();
+        for _ in 0..6 {
+            assert_eq!(answer_after(&mut confident, &mut now, 1200, true), None);
+        }
+        assert_eq!(confident.flag, None);
+
+        let mut guessing = AnswerTiming::default();
+        let outcomes = [false, true, false, true, false];
+        let flags: Vec<_> = outcomes.iter()
+            .map(|&is_correct| answer_after(&mut guessing, &mut now, 900, is_correct))
+            .collect();
+        assert_eq!(flags[3], Some(TimingFlag::RapidGuessing));
+        assert_eq!(guessing.flag, Some(TimingFlag::RapidGuessing));
+    }
+}
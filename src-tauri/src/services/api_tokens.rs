@@ -0,0 +1,249 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::ProfileId;
+use crate::database::{row_exists, DatabaseManager};
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use chrono::{DateTime, Utc};
+
+/// Start of every token, so a leaked one is easy to recognise
+const TOKEN_PREFIX: &str = "qdd_";
+/// Random bytes in a token
+const TOKEN_BYTES: usize = 32;
+/// Characters of the token kept in the clear, so parents can tell tokens apart
+const VISIBLE_PREFIX_LENGTH: usize = 8;
+
+/// What an integration may do with a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiTokenScope {
+    /// Read the profile's progress and quiz history
+    #[serde(rename = "progress:read")]
+    ProgressRead,
+    /// Read subjects and questions
+    #[serde(rename = "content:read")]
+    ContentRead,
+}
+
+/// A token as parents see it; the secret itself is never stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: u32,
+    pub profile_id: ProfileId,
+    pub name: String,
+    /// The first few characters of the secret
+    pub prefix: String,
+    pub scopes: Vec<ApiTokenScope>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
+/// A newly created token. The secret is only available here, so it must be shown to the
+/// parent straight away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+/// Parent-issued tokens that let the local API and command line tools read a profile's data
+pub struct ApiTokenService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ApiTokenService {
+    /// Create a new API token service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Issue a token for a profile with the given scopes
+    pub fn create_token(&self, profile_id: ProfileId, name: &str, scopes: &[ApiTokenScope]) -> AppResult<CreatedApiToken> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput("Token name cannot be empty".to_string()));
+        }
+        if scopes.is_empty() {
+            return Err(AppError::InvalidInput("A token needs at least one scope".to_string()));
+        }
+        let mut scopes = scopes.to_vec();
+        scopes.dedup();
+
+        let mut random = [0u8; TOKEN_BYTES];
+        getrandom::getrandom(&mut random)
+            .map_err(|e| AppError::Security(format!("Failed to generate token: {}", e)))?;
+        let secret = format!("{}{}", TOKEN_PREFIX, hex::encode(random));
+        let prefix = secret[..TOKEN_PREFIX.len() + VISIBLE_PREFIX_LENGTH].to_string();
+        let scopes_json = serde_json::to_string(&scopes)?;
+
+        let id = self.db_manager.execute(|conn| {
+            if !row_exists(conn, profile_id)? {
+                return Ok(None);
+            }
+            conn.execute(
+                "INSERT INTO api_tokens (profile_id, name, token_hash, token_prefix, scopes, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![profile_id, name, hash_secret(&secret), prefix, scopes_json, Utc::now().to_rfc3339()],
+            )?;
+            Ok(Some(conn.last_insert_rowid() as u32))
+        })?.ok_or(AppError::ProfileNotFound { id: profile_id })?;
+
+        Ok(CreatedApiToken {
+            token: self.get_token(id)?,
+            secret,
+        })
+    }
+
+    /// Tokens, newest first, optionally for one profile
+    pub fn list_tokens(&self, profile_id: Option<ProfileId>) -> AppResult<Vec<ApiToken>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, profile_id, name, token_prefix, scopes, created_at, last_used_at, revoked_at
+                 FROM api_tokens
+                 WHERE ?1 IS NULL OR profile_id = ?1
+                 ORDER BY id DESC",
+            )?;
+            let rows = stmt.query_map(params![profile_id], row_to_token)?;
+            rows.collect()
+        })?)
+    }
+
+    /// Stop a token from working. Revoked tokens stay listed so parents can see what was
+    /// issued.
+    pub fn revoke_token(&self, token_id: u32) -> AppResult<ApiToken> {
+        let updated = self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE api_tokens SET revoked_at = COALESCE(revoked_at, ?2) WHERE id = ?1",
+                params![token_id, Utc::now().to_rfc3339()],
+            )
+        })?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("API token {} not found", token_id)));
+        }
+        self.get_token(token_id)
+    }
+
+    /// Check a secret presented by an integration allows `scope`, recording that it was used
+    pub fn authenticate(&self, secret: &str, scope: ApiTokenScope) -> AppResult<ApiToken> {
+        let token = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT id, profile_id, name, token_prefix, scopes, created_at, last_used_at, revoked_at
+                 FROM api_tokens WHERE token_hash = ?1",
+                params![hash_secret(secret.trim())],
+                row_to_token,
+            ).optional()
+        })?;
+
+        let token = match token {
+            Some(token) if !token.is_revoked() => token,
+            _ => return Err(AppError::Authentication("Invalid or revoked API token".to_string())),
+        };
+        if !token.scopes.contains(&scope) {
+            return Err(AppError::PermissionDenied(format!("Token '{}' doesn't allow {:?}", token.name, scope)));
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE api_tokens SET last_used_at = ?2 WHERE id = ?1",
+                params![token.id, Utc::now().to_rfc3339()],
+            )
+        })?;
+        Ok(token)
+    }
+
+    fn get_token(&self, token_id: u32) -> AppResult<ApiToken> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT id, profile_id, name, token_prefix, scopes, created_at, last_used_at, revoked_at
+                 FROM api_tokens WHERE id = ?1",
+                params![token_id],
+                row_to_token,
+            )
+        })?)
+    }
+}
+
+fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+fn row_to_token(row: &Row) -> Result<ApiToken, rusqlite::Error> {
+    let parse = |index: usize, value: String| {
+        DateTime::parse_from_rfc3339(&value)
+            .map(|date| date.with_timezone(&Utc))
+            .map_err(|_| rusqlite::Error::InvalidColumnType(index, "api_tokens".to_string(), rusqlite::types::Type::Text))
+    };
+    let scopes = serde_json::from_str(&row.get::<_, String>(4)?)
+        .map_err(|_| rusqlite::Error::InvalidColumnType(4, "scopes".to_string(), rusqlite::types::Type::Text))?;
+
+    Ok(ApiToken {
+        id: row.get(0)?,
+        profile_id: row.get(1)?,
+        name: row.get(2)?,
+        prefix: row.get(3)?,
+        scopes,
+        created_at: parse(5, row.get(5)?)?,
+        last_used_at: row.get::<_, Option<String>>(6)?.map(|value| parse(6, value)).transpose()?,
+        revoked_at: row.get::<_, Option<String>>(7)?.map(|value| parse(7, value)).transpose()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_tokens_are_hashed_scoped_and_revocable() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let profile_id = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+
+        let service = ApiTokenService::new(db_manager.clone());
+        assert!(service.create_token(profile_id, "Dashboard", &[]).is_err());
+        assert!(matches!(
+            service.create_token(ProfileId(9999), "Dashboard", &[ApiTokenScope::ProgressRead]),
+            Err(AppError::ProfileNotFound { id: ProfileId(9999) })
+        ));
+
+        let created = service.create_token(profile_id, "Dashboard", &[ApiTokenScope::ProgressRead]).unwrap();
+        assert!(created.secret.starts_with(&created.token.prefix));
+        let stored: String = db_manager.execute(|conn| {
+            conn.query_row("SELECT token_hash FROM api_tokens WHERE id = ?1", params![created.token.id], |row| row.get(0))
+        }).unwrap();
+        assert!(!stored.contains(&created.secret[TOKEN_PREFIX.len()..]));
+
+        let used = service.authenticate(&created.secret, ApiTokenScope::ProgressRead).unwrap();
+        assert_eq!(used.profile_id, profile_id);
+        assert!(matches!(
+            service.authenticate(&created.secret, ApiTokenScope::ContentRead),
+            Err(AppError::PermissionDenied(_))
+        ));
+        assert!(service.authenticate("qdd_guess", ApiTokenScope::ProgressRead).is_err());
+        assert!(service.list_tokens(Some(profile_id)).unwrap()[0].last_used_at.is_some());
+
+        assert!(service.revoke_token(created.token.id).unwrap().is_revoked());
+        assert!(matches!(
+            service.authenticate(&created.secret, ApiTokenScope::ProgressRead),
+            Err(AppError::Authentication(_))
+        ));
+        assert_eq!(service.list_tokens(None).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::errors::{AppError, AppResult};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use chrono::Utc;
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Name of the app log inside the log directory; the rotated log gets a `.1` suffix
+pub const LOG_FILE_NAME: &str = "quizdd.log";
+/// The log is rotated at startup once it grows past this size
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Backend for the `log` facade that appends records to the app log in the log directory,
+/// so service warnings end up in support bundles
+pub struct FileLogger {
+    file: Mutex<File>,
+    level: LevelFilter,
+}
+
+impl FileLogger {
+    /// Open the app log in `log_directory`, rotating it first if it has grown too large
+    pub fn open(log_directory: &Path, level: LevelFilter) -> AppResult<Self> {
+        fs::create_dir_all(log_directory)?;
+        let path = log_directory.join(LOG_FILE_NAME);
+        if fs::metadata(&path).map_or(false, |metadata| metadata.len() > MAX_LOG_BYTES) {
+            fs::rename(&path, log_directory.join(format!("{}.1", LOG_FILE_NAME)))?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+            level,
+        })
+    }
+
+    /// Install this logger as the global `log` backend. Can only succeed once per process.
+    pub fn install(self) -> AppResult<()> {
+        let level = self.level;
+        log::set_logger(Box::leak(Box::new(self)))
+            .map_err(|e| AppError::Internal(format!("Failed to install logger: {}", e)))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // A poisoned lock or failed write must never take the app down with it
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(
+                file,
+                "{} {:<5} {}: {}",
+                Utc::now().to_rfc3339(),
+                record.level(),
+                record.target(),
+                record.args(),
+            );
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Level;
+    use tempfile::tempdir;
+
+    fn log_at(logger: &FileLogger, level: Level, message: &str) {
+        logger.log(&Record::builder()
+            .level(level)
+            .target("quizdd::test")
+            .args(format_args!("{}", message))
+            .build());
+    }
+
+    #[test]
+    fn test_records_below_the_level_are_dropped() {
+        let temp_dir = tempdir().unwrap();
+        let logger = FileLogger::open(temp_dir.path(), LevelFilter::Warn).unwrap();
+
+        log_at(&logger, Level::Warn, "Failed to refresh the question cache");
+        log_at(&logger, Level::Info, "Question cache refreshed");
+        logger.flush();
+
+        let contents = fs::read_to_string(temp_dir.path().join(LOG_FILE_NAME)).unwrap();
+        assert!(contents.contains("WARN  quizdd::test: Failed to refresh the question cache"), "{}", contents);
+        assert!(!contents.contains("Question cache refreshed"));
+    }
+
+    #[test]
+    fn test_large_logs_are_rotated_on_open() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join(LOG_FILE_NAME);
+        fs::write(&path, vec![b'x'; MAX_LOG_BYTES as usize + 1]).unwrap();
+
+        FileLogger::open(temp_dir.path(), LevelFilter::Info).unwrap();
+
+        assert_eq!(fs::metadata(&path).unwrap().len(), 0);
+        assert!(temp_dir.path().join(format!("{}.1", LOG_FILE_NAME)).exists());
+    }
+}
@@ -0,0 +1,327 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{MixId, ProfileId};
+use crate::database::DatabaseManager;
+use crate::services::{CustomMixManager, ProfileManager, ResetScope};
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Longest note a child can leave with a request
+const MAX_REASON_LENGTH: usize = 500;
+
+/// A destructive action that needs a parent's go-ahead
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PendingAction {
+    DeleteMix { mix_id: MixId },
+    ResetProgress {
+        profile_id: ProfileId,
+        #[serde(default)]
+        scope: ResetScope,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Approved => "approved",
+            ApprovalStatus::Rejected => "rejected",
+        }
+    }
+
+    fn from_db(value: &str) -> Self {
+        match value {
+            "approved" => ApprovalStatus::Approved,
+            "rejected" => ApprovalStatus::Rejected,
+            _ => ApprovalStatus::Pending,
+        }
+    }
+}
+
+/// A request waiting for (or already given) a parent's decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    pub id: u32,
+    pub action: PendingAction,
+    /// What the parent is asked to confirm, e.g. "Delete the custom mix 'Times tables'"
+    pub summary: String,
+    pub requested_by: Option<ProfileId>,
+    pub reason: Option<String>,
+    pub status: ApprovalStatus,
+    pub requested_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+/// Holds destructive actions asked for from child-reachable screens until a parent confirms
+/// or rejects them
+pub struct ApprovalQueue {
+    db_manager: Arc<DatabaseManager>,
+    custom_mix_manager: Arc<CustomMixManager>,
+    profile_manager: Arc<ProfileManager>,
+}
+
+impl ApprovalQueue {
+    /// Create a new approval queue
+    pub fn new(
+        db_manager: Arc<DatabaseManager>,
+        custom_mix_manager: Arc<CustomMixManager>,
+        profile_manager: Arc<ProfileManager>,
+    ) -> Self {
+        Self {
+            db_manager,
+            custom_mix_manager,
+            profile_manager,
+        }
+    }
+
+    /// Queue an action for a parent to decide on. Asking again for an action that is already
+    /// waiting returns the existing request.
+    pub fn request(&self, action: PendingAction, requested_by: Option<ProfileId>, reason: Option<String>) -> AppResult<ApprovalRequest> {
+        let reason = reason.map(|reason| reason.trim().to_string()).filter(|reason| !reason.is_empty());
+        if reason.as_ref().map_or(false, |reason| reason.chars().count() > MAX_REASON_LENGTH) {
+            return Err(AppError::InvalidInput(format!("Reason must be at most {} characters", MAX_REASON_LENGTH)));
+        }
+
+        let summary = self.describe(&action)?;
+        let action_json = serde_json::to_string(&action)?;
+
+        let existing = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT id FROM approval_requests WHERE action = ?1 AND status = 'pending'",
+                params![action_json],
+                |row| row.get::<_, u32>(0),
+            ).optional()
+        })?;
+        if let Some(id) = existing {
+            return self.get_request(id);
+        }
+
+        let id = self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO approval_requests (action, summary, requested_by, reason, status, requested_at)
+                 VALUES (?1, ?2, ?3, ?4, 'pending', ?5)",
+                params![action_json, summary, requested_by, reason, Utc::now().to_rfc3339()],
+            )?;
+            Ok(conn.last_insert_rowid() as u32)
+        })?;
+
+        self.get_request(id)
+    }
+
+    pub fn get_request(&self, id: u32) -> AppResult<ApprovalRequest> {
+        self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT id, action, summary, requested_by, reason, status, requested_at, decided_at
+                 FROM approval_requests WHERE id = ?1",
+                params![id],
+                Self::row_to_request,
+            ).optional()
+        })?.ok_or_else(|| AppError::NotFound(format!("Approval request {} not found", id)))
+    }
+
+    /// Requests with the given status (all of them when `None`), newest first
+    pub fn list_requests(&self, status: Option<ApprovalStatus>) -> AppResult<Vec<ApprovalRequest>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, action, summary, requested_by, reason, status, requested_at, decided_at
+                 FROM approval_requests WHERE ?1 IS NULL OR status = ?1
+                 ORDER BY requested_at DESC, id DESC"
+            )?;
+            let rows = stmt.query_map(params![status.map(|status| status.as_str())], Self::row_to_request)?;
+            rows.collect()
+        })?)
+    }
+
+    /// Carry out a pending request. If the action fails the request stays pending.
+    pub fn approve(&self, id: u32) -> AppResult<ApprovalRequest> {
+        let request = self.pending_request(id)?;
+
+        match request.action {
+            PendingAction::DeleteMix { mix_id } => self.custom_mix_manager.delete_custom_mix(mix_id)?,
+            PendingAction::ResetProgress { profile_id, scope } => {
+                self.profile_manager.reset_progress(profile_id, scope)?;
+            }
+        }
+
+        self.decide(id, ApprovalStatus::Approved)
+    }
+
+    /// Turn down a pending request without doing anything
+    pub fn reject(&self, id: u32) -> AppResult<ApprovalRequest> {
+        self.pending_request(id)?;
+        self.decide(id, ApprovalStatus::Rejected)
+    }
+
+    fn pending_request(&self, id: u32) -> AppResult<ApprovalRequest> {
+        let request = self.get_request(id)?;
+        if request.status != ApprovalStatus::Pending {
+            return Err(AppError::InvalidInput(format!(
+                "Approval request {} was already {}", id, request.status.as_str()
+            )));
+        }
+        Ok(request)
+    }
+
+    fn decide(&self, id: u32, status: ApprovalStatus) -> AppResult<ApprovalRequest> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE approval_requests SET status = ?2, decided_at = ?3 WHERE id = ?1",
+                params![id, status.as_str(), Utc::now().to_rfc3339()],
+            )
+        })?;
+        self.get_request(id)
+    }
+
+    /// Parent-facing description of an action; also checks its target exists
+    fn describe(&self, action: &PendingAction) -> AppResult<String> {
+        Ok(match action {
+            PendingAction::DeleteMix { mix_id } => {
+                let mix = self.custom_mix_manager.get_custom_mix_by_id(*mix_id)?;
+                format!("Delete the custom mix '{}'", mix.name)
+            }
+            PendingAction::ResetProgress { profile_id, scope } => {
+                let profile = self.profile_manager.get_profile_by_id(*profile_id)?;
+                match scope {
+                    ResetScope::Everything => format!("Reset all progress for {}", profile.name),
+                    ResetScope::Subject { subject } => format!("Reset {} progress for {}", subject, profile.name),
+                    ResetScope::Tag { tag } => format!("Reset '{}' progress for {}", tag, profile.name),
+                }
+            }
+        })
+    }
+
+    fn row_to_request(row: &Row) -> rusqlite::Result<ApprovalRequest> {
+        let action_json: String = row.get(1)?;
+        let action = serde_json::from_str(&action_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(1, "action".to_string(), rusqlite::types::Type::Text))?;
+        let status: String = row.get(5)?;
+        let requested_at: String = row.get(6)?;
+        let decided_at: Option<String> = row.get(7)?;
+
+        Ok(ApprovalRequest {
+            id: row.get(0)?,
+            action,
+            summary: row.get(2)?,
+            requested_by: row.get(3)?,
+            reason: row.get(4)?,
+            status: ApprovalStatus::from_db(&status),
+            requested_at: parse_timestamp(&requested_at).unwrap_or_else(Utc::now),
+            decided_at: decided_at.as_deref().and_then(parse_timestamp),
+        })
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::CreateProfileRequest;
+    use crate::services::{QuizResult, SecurityService};
+    use tempfile::tempdir;
+
+    fn create_test_queue() -> (ApprovalQueue, Arc<CustomMixManager>, Arc<ProfileManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let custom_mix_manager = Arc::new(CustomMixManager::new(db_service.manager()));
+        let profile_manager = Arc::new(ProfileManager::new(db_service.manager(), Arc::new(SecurityService::new().unwrap())));
+        let queue = ApprovalQueue::new(db_service.manager(), custom_mix_manager.clone(), profile_manager.clone());
+
+        (queue, custom_mix_manager, profile_manager, temp_dir)
+    }
+
+    fn create_profile(profile_manager: &ProfileManager) -> ProfileId {
+        profile_manager.create_profile(CreateProfileRequest {
+            name: "Sam".to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        }).unwrap().id.unwrap()
+    }
+
+    fn create_mix(queue: &ApprovalQueue, profile_id: ProfileId, name: &str) -> MixId {
+        let now = Utc::now().to_rfc3339();
+        queue.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO custom_mixes (name, created_by, config, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+                params![
+                    name,
+                    profile_id,
+                    r#"{"subjects":["mathematics"],"key_stages":["KS1"],"question_count":5,"time_limit":null,"difficulty_range":[1,3],"question_types":null,"randomize_order":true,"show_immediate_feedback":true,"allow_review":true}"#,
+                    now
+                ],
+            )?;
+            Ok(MixId(conn.last_insert_rowid() as u32))
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_approve_runs_action_once() {
+        let (queue, custom_mix_manager, profile_manager, _temp_dir) = create_test_queue();
+        let profile_id = create_profile(&profile_manager);
+        let mix_id = create_mix(&queue, profile_id, "Times tables");
+
+        let request = queue.request(PendingAction::DeleteMix { mix_id }, Some(profile_id), Some("Made it by mistake".to_string())).unwrap();
+        assert_eq!(request.summary, "Delete the custom mix 'Times tables'");
+        assert_eq!(request.status, ApprovalStatus::Pending);
+
+        // Asking twice doesn't queue a duplicate, and nothing happens until a parent approves
+        assert_eq!(queue.request(PendingAction::DeleteMix { mix_id }, Some(profile_id), None).unwrap().id, request.id);
+        assert!(custom_mix_manager.get_custom_mix_by_id(mix_id).is_ok());
+
+        let approved = queue.approve(request.id).unwrap();
+        assert_eq!(approved.status, ApprovalStatus::Approved);
+        assert!(approved.decided_at.is_some());
+        assert!(custom_mix_manager.get_custom_mix_by_id(mix_id).is_err());
+        assert!(queue.approve(request.id).is_err());
+        assert!(queue.reject(request.id).is_err());
+    }
+
+    #[test]
+    fn test_reject_leaves_progress_alone() {
+        let (queue, _custom_mix_manager, profile_manager, _temp_dir) = create_test_queue();
+        let profile_id = create_profile(&profile_manager);
+        profile_manager.update_progress(profile_id, QuizResult {
+            subject: "Mathematics".to_string(),
+            key_stage: "KS1".to_string(),
+            questions_answered: 10,
+            correct_answers: 8,
+            time_spent_seconds: 300,
+            session_id: None,
+            practice: false,
+        }).unwrap();
+
+        assert!(queue.request(PendingAction::ResetProgress { profile_id: ProfileId(999), scope: ResetScope::Everything }, None, None).is_err());
+
+        let rejected = queue.request(PendingAction::ResetProgress { profile_id, scope: ResetScope::Everything }, Some(profile_id), None).unwrap();
+        queue.reject(rejected.id).unwrap();
+        assert_eq!(profile_manager.get_progress(profile_id).unwrap().total_questions_answered, 10);
+
+        // A rejected request can be asked for again
+        let request = queue.request(PendingAction::ResetProgress { profile_id, scope: ResetScope::Everything }, Some(profile_id), None).unwrap();
+        assert_ne!(request.id, rejected.id);
+        assert_eq!(queue.list_requests(Some(ApprovalStatus::Pending)).unwrap().len(), 1);
+        assert_eq!(queue.list_requests(None).unwrap().len(), 2);
+
+        queue.approve(request.id).unwrap();
+        assert_eq!(profile_manager.get_progress(profile_id).unwrap().total_questions_answered, 0);
+    }
+}
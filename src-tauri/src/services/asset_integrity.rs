@@ -0,0 +1,441 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{QuestionContent, QuestionId};
+use crate::services::content_manager::ContentManager;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use rusqlite::params;
+use chrono::{DateTime, Duration, Utc};
+
+/// Hours between checks run from maintenance
+const CHECK_INTERVAL_HOURS: i64 = 24;
+
+/// Hex digits of a file's SHA-256 the asset pipeline names it by
+const NAME_HASH_LENGTH: usize = 16;
+
+/// Reason given for questions retired because their assets can't be repaired
+pub const BROKEN_ASSET_REASON: &str = "Asset file missing or damaged";
+
+/// What is wrong with an asset file
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetProblem {
+    Missing,
+    /// The file is there but its contents have changed since it was first seen
+    HashMismatch,
+}
+
+/// An asset file restored from a pack archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairedAsset {
+    pub file_path: String,
+    pub problem: AssetProblem,
+    /// File name of the archive the good copy came from
+    pub archive: String,
+}
+
+/// An asset file no pack archive has a good copy of, with the questions that use it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenAsset {
+    pub file_path: String,
+    pub problem: AssetProblem,
+    pub question_ids: Vec<QuestionId>,
+}
+
+/// What an integrity check found and fixed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetIntegrityReport {
+    pub ran_at: DateTime<Utc>,
+    pub files_checked: u32,
+    /// Files seen for the first time, whose hash is now kept to check them against
+    pub hashes_recorded: u32,
+    pub repaired: Vec<RepairedAsset>,
+    pub broken: Vec<BrokenAsset>,
+}
+
+impl AssetIntegrityReport {
+    /// Questions that can't be shown properly until their assets are replaced
+    pub fn broken_questions(&self) -> Vec<QuestionId> {
+        let mut question_ids: Vec<QuestionId> = self.broken.iter()
+            .flat_map(|asset| asset.question_ids.iter().copied())
+            .collect();
+        question_ids.sort();
+        question_ids.dedup();
+        question_ids
+    }
+}
+
+/// A question and a file path, or its content JSON
+type QuestionFile = (QuestionId, String);
+
+/// A file inside a pack archive, by the hash of its contents
+struct ArchiveEntry {
+    archive: PathBuf,
+    index: usize,
+    file_name: String,
+    digest: String,
+}
+
+/// Checks that every image and audio file questions refer to is there and unchanged.
+/// A file is expected to match the hash it had when first checked, or for files from the
+/// asset pipeline the hash in its name. Missing or damaged files are restored from the
+/// zip pack archives in the archive directory when one has a copy with the right hash.
+pub struct AssetIntegrityChecker {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+    /// Relative asset paths, like `image_url`, are relative to this
+    content_directory: PathBuf,
+    archive_directory: PathBuf,
+}
+
+impl AssetIntegrityChecker {
+    pub fn new(
+        db_manager: Arc<DatabaseManager>,
+        content_manager: Arc<ContentManager>,
+        content_directory: PathBuf,
+        archive_directory: PathBuf,
+    ) -> Self {
+        Self { db_manager, content_manager, content_directory, archive_directory }
+    }
+
+    /// Folder pack archives used for repairs are kept in
+    pub fn archive_dir(&self) -> &Path {
+        &self.archive_directory
+    }
+
+    /// Check unless a check ran in the last day. Returns `None` when skipped.
+    pub fn check_if_due(&self, now: DateTime<Utc>) -> AppResult<Option<AssetIntegrityReport>> {
+        if let Some(last_run) = self.get_reports(1)?.into_iter().next() {
+            if now - last_run.ran_at < Duration::hours(CHECK_INTERVAL_HOURS) {
+                return Ok(None);
+            }
+        }
+        self.check(now).map(Some)
+    }
+
+    /// Check every asset of questions still in use, repairing what can be repaired
+    pub fn check(&self, now: DateTime<Utc>) -> AppResult<AssetIntegrityReport> {
+        let references = self.load_references()?;
+        let recorded_hashes: HashMap<String, String> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT file_path, content_hash FROM asset_hashes")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        })?;
+
+        let mut report = AssetIntegrityReport {
+            ran_at: now,
+            files_checked: 0,
+            hashes_recorded: 0,
+            repaired: Vec::new(),
+            broken: Vec::new(),
+        };
+        let mut new_hashes = Vec::new();
+        let mut archive_entries: Option<Vec<ArchiveEntry>> = None;
+
+        for (file_path, question_ids) in references {
+            report.files_checked += 1;
+            let path = self.resolve(&file_path);
+            let recorded = recorded_hashes.get(&file_path).cloned();
+            let expected = recorded.clone().or_else(|| name_hash(&path));
+
+            let problem = match fs::read(&path) {
+                Ok(bytes) => {
+                    let digest = sha256_hex(&bytes);
+                    if expected.as_ref().map_or(false, |expected| !digest.starts_with(expected.as_str())) {
+                        Some(AssetProblem::HashMismatch)
+                    } else {
+                        if recorded.is_none() {
+                            new_hashes.push((file_path.clone(), digest));
+                        }
+                        None
+                    }
+                }
+                Err(_) => Some(AssetProblem::Missing),
+            };
+            let problem = match problem {
+                Some(problem) => problem,
+                None => continue,
+            };
+
+            if archive_entries.is_none() {
+                archive_entries = Some(self.index_archives()?);
+            }
+            let file_name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            let copy = archive_entries.iter().flatten().find(|entry| match &expected {
+                Some(expected) => entry.digest.starts_with(expected.as_str()),
+                None => entry.file_name == file_name,
+            });
+
+            match copy {
+                Some(entry) => {
+                    let bytes = read_archive_entry(entry)?;
+                    if let Some(parent) = path.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&path, &bytes)?;
+                    if recorded.is_none() {
+                        new_hashes.push((file_path.clone(), entry.digest.clone()));
+                    }
+                    report.repaired.push(RepairedAsset {
+                        file_path,
+                        problem,
+                        archive: entry.archive.file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                    });
+                }
+                None => report.broken.push(BrokenAsset { file_path, problem, question_ids }),
+            }
+        }
+
+        report.hashes_recorded = new_hashes.len() as u32;
+        let summary = serde_json::to_string(&report)?;
+        self.db_manager.transaction(|tx| {
+            for (file_path, content_hash) in &new_hashes {
+                tx.execute(
+                    "INSERT OR REPLACE INTO asset_hashes (file_path, content_hash, recorded_at) VALUES (?1, ?2, ?3)",
+                    params![file_path, content_hash, now.to_rfc3339()],
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO asset_integrity_runs (ran_at, summary) VALUES (?1, ?2)",
+                params![now.to_rfc3339(), summary],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(report)
+    }
+
+    /// Check again, then retire the questions whose assets are still broken
+    pub fn retire_broken_questions(&self, now: DateTime<Utc>) -> AppResult<Vec<QuestionId>> {
+        let question_ids = self.check(now)?.broken_questions();
+        for question_id in &question_ids {
+            self.content_manager.retire_question(*question_id, BROKEN_ASSET_REASON)?;
+        }
+        Ok(question_ids)
+    }
+
+    /// Recent check results, newest first
+    pub fn get_reports(&self, limit: u32) -> AppResult<Vec<AssetIntegrityReport>> {
+        let summaries: Vec<String> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT summary FROM asset_integrity_runs ORDER BY id DESC LIMIT ?1")?;
+            let rows = stmt.query_map(params![limit], |row| row.get(0))?;
+            rows.collect()
+        })?;
+
+        summaries.iter()
+            .map(|summary| serde_json::from_str(summary).map_err(AppError::from))
+            .collect()
+    }
+
+    /// Every local asset file questions still in use refer to, with those questions
+    fn load_references(&self) -> AppResult<BTreeMap<String, Vec<QuestionId>>> {
+        let (assets, contents): (Vec<QuestionFile>, Vec<QuestionFile>) = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT question_id, file_path FROM assets
+                 WHERE question_id NOT IN (SELECT question_id FROM retired_questions)"
+            )?;
+            let assets = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT id, content FROM questions
+                 WHERE id NOT IN (SELECT question_id FROM retired_questions)"
+            )?;
+            let contents = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((assets, contents))
+        })?;
+
+        let image_urls = contents.into_iter().filter_map(|(question_id, content)| {
+            serde_json::from_str::<QuestionContent>(&content).ok()
+                .and_then(|content| content.image_url)
+                .map(|image_url| (question_id, image_url))
+        });
+
+        let mut references: BTreeMap<String, Vec<QuestionId>> = BTreeMap::new();
+        for (question_id, file_path) in assets.into_iter().chain(image_urls) {
+            if is_remote(&file_path) {
+                continue;
+            }
+            let question_ids = references.entry(file_path).or_default();
+            if !question_ids.contains(&question_id) {
+                question_ids.push(question_id);
+            }
+        }
+        Ok(references)
+    }
+
+    fn resolve(&self, file_path: &str) -> PathBuf {
+        let path = Path::new(file_path);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.content_directory.join(path)
+        }
+    }
+
+    /// Hash every file in the pack archives. Archives that can't be read are skipped.
+    fn index_archives(&self) -> AppResult<Vec<ArchiveEntry>> {
+        let directory = match fs::read_dir(&self.archive_directory) {
+            Ok(directory) => directory,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut entries = Vec::new();
+        for archive_path in directory {
+            let archive_path = archive_path?.path();
+            if archive_path.extension().map_or(true, |extension| !extension.eq_ignore_ascii_case("zip")) {
+                continue;
+            }
+            let mut archive = match zip::ZipArchive::new(fs::File::open(&archive_path)?) {
+                Ok(archive) => archive,
+                Err(e) => {
+                    eprintln!("Warning: Skipping unreadable pack archive {}: {}", archive_path.display(), e);
+                    continue;
+                }
+            };
+
+            for index in 0..archive.len() {
+                let mut file = match archive.by_index(index) {
+                    Ok(file) if !file.is_dir() => file,
+                    _ => continue,
+                };
+                let mut bytes = Vec::new();
+                if file.read_to_end(&mut bytes).is_err() {
+                    continue;
+                }
+                entries.push(ArchiveEntry {
+                    archive: archive_path.clone(),
+                    index,
+                    file_name: file.name().rsplit('/').next().unwrap_or_default().to_string(),
+                    digest: sha256_hex(&bytes),
+                });
+            }
+        }
+        Ok(entries)
+    }
+}
+
+fn read_archive_entry(entry: &ArchiveEntry) -> AppResult<Vec<u8>> {
+    let zip_error = |e: zip::result::ZipError| {
+        AppError::ContentManagement(format!("Failed to read pack archive {}: {}", entry.archive.display(), e))
+    };
+    let mut archive = zip::ZipArchive::new(fs::File::open(&entry.archive)?).map_err(zip_error)?;
+    let mut file = archive.by_index(entry.index).map_err(zip_error)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// The hash prefix the asset pipeline names files by, if `path` is named that way
+fn name_hash(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    if stem.len() == NAME_HASH_LENGTH && stem.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()) {
+        Some(stem.to_string())
+    } else {
+        None
+    }
+}
+
+fn is_remote(file_path: &str) -> bool {
+    file_path.contains("://") || file_path.starts_with("data:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{Answer, KeyStage, Question, QuestionType};
+    use crate::services::SecurityService;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use zip::write::FileOptions;
+
+    #[test]
+    fn test_broken_assets_are_repaired_from_archives_or_reported() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let content_directory = temp_dir.path().join("content");
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            content_directory.clone(),
+        ));
+        let checker = AssetIntegrityChecker::new(
+            db_service.manager(),
+            content_manager.clone(),
+            content_directory.clone(),
+            content_directory.join("packs"),
+        );
+
+        // One picture named by its hash as the pipeline does, one with an ordinary name
+        let triangle = b"<svg>triangle</svg>".to_vec();
+        let triangle_path = format!("assets/images/{}.svg", &sha256_hex(&triangle)[..NAME_HASH_LENGTH]);
+        let add_question = |text: &str, image_url: &str| {
+            content_manager.add_question(Question::new(
+                1,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["Yes".to_string(), "No".to_string()]),
+                    story: None,
+                    image_url: Some(image_url.to_string()),
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("Yes".to_string()),
+            )).unwrap()
+        };
+        add_question("Is this a triangle?", &triangle_path);
+        let square_question = add_question("Is this a square?", "assets/images/square.svg");
+        add_question("Is this a flag?", "https://example.com/flag.png");
+
+        fs::create_dir_all(content_directory.join("assets/images")).unwrap();
+        fs::write(content_directory.join(&triangle_path), &triangle).unwrap();
+        fs::write(content_directory.join("assets/images/square.svg"), b"<svg>square</svg>").unwrap();
+
+        // First check records the square's hash; the triangle's is in its name
+        let report = checker.check(Utc::now()).unwrap();
+        assert_eq!((report.files_checked, report.hashes_recorded), (2, 2));
+        assert!(report.broken.is_empty());
+        assert_eq!(checker.check_if_due(Utc::now()).unwrap().map(|report| report.files_checked), None);
+
+        // The triangle goes missing and the square is overwritten; only the triangle is in a pack
+        fs::remove_file(content_directory.join(&triangle_path)).unwrap();
+        fs::write(content_directory.join("assets/images/square.svg"), b"<svg>damaged</svg>").unwrap();
+        fs::create_dir_all(checker.archive_dir()).unwrap();
+        let mut zip = zip::ZipWriter::new(fs::File::create(checker.archive_dir().join("shapes.zip")).unwrap());
+        zip.start_file("images/triangle-copy.svg", FileOptions::default()).unwrap();
+        zip.write_all(&triangle).unwrap();
+        zip.finish().unwrap();
+
+        let report = checker.check(Utc::now()).unwrap();
+        assert_eq!(report.repaired.len(), 1);
+        assert_eq!((report.repaired[0].problem, report.repaired[0].archive.as_str()), (AssetProblem::Missing, "shapes.zip"));
+        assert_eq!(fs::read(content_directory.join(&triangle_path)).unwrap(), triangle);
+        assert_eq!(report.broken.len(), 1);
+        assert_eq!(report.broken[0].problem, AssetProblem::HashMismatch);
+        assert_eq!(report.broken_questions(), vec![square_question]);
+
+        assert_eq!(checker.retire_broken_questions(Utc::now()).unwrap(), vec![square_question]);
+        let retired = content_manager.get_retired_questions().unwrap();
+        assert_eq!(retired.len(), 1);
+        assert_eq!(retired[0].question_id, square_question);
+        assert!(checker.check(Utc::now()).unwrap().broken.is_empty());
+    }
+}
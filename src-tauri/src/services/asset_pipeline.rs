@@ -0,0 +1,409 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Asset, AssetType, QuestionId};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageEncoder, GenericImageView};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::DecoderOptions;
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Longest side, in pixels, images are scaled down to. Question images never show larger.
+pub const MAX_IMAGE_DIMENSION: u32 = 1280;
+
+/// JPEG quality used when a photo compresses better as JPEG than as lossless WebP
+const JPEG_QUALITY: u8 = 85;
+
+/// Sample rate all audio is converted to; plenty for speech and short sounds
+pub const AUDIO_SAMPLE_RATE: u32 = 22_050;
+
+/// Loudness audio is brought to, as an RMS level in dBFS
+const TARGET_RMS_DBFS: f32 = -20.0;
+
+/// Loudest peak allowed after normalisation, so quiet clips with a sharp peak don't clip
+const PEAK_LIMIT_DBFS: f32 = -1.0;
+
+/// Most a clip is amplified, so near-silent recordings don't become loud hiss
+const MAX_GAIN_DB: f32 = 24.0;
+
+/// An image after optimisation
+#[derive(Debug, Clone)]
+pub struct OptimizedImage {
+    pub bytes: Vec<u8>,
+    /// `webp`, or `jpg` for photos that are much smaller that way
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Brings asset files into the content directory, optimising them on the way in so large
+/// photos don't bloat content or slow question loading and every sound plays at the same
+/// volume
+pub struct AssetPipeline {
+    asset_directory: PathBuf,
+}
+
+impl AssetPipeline {
+    /// Create a new asset pipeline storing files under `asset_directory`
+    pub fn new(asset_directory: PathBuf) -> Self {
+        Self { asset_directory }
+    }
+
+    /// Copy a file into the asset directory, optimising images and normalising audio to
+    /// 16-bit mono WAV at `AUDIO_SAMPLE_RATE`. The returned asset is ready
+    /// to attach to a question. Files are named by content, so importing the same picture
+    /// twice stores it once.
+    pub fn import_asset(&self, source: &Path, asset_type: AssetType, alt_text: Option<String>) -> AppResult<Asset> {
+        let original = fs::read(source)
+            .map_err(|e| AppError::ContentManagement(format!("Failed to read {}: {}", source.display(), e)))?;
+        let extension = source.extension().map(|extension| extension.to_string_lossy().to_string());
+        self.import_bytes(original, extension.as_deref(), asset_type, alt_text)
+    }
+
+    /// Like `import_asset`, for data that isn't in a file yet, e.g. a recording made in the
+    /// app. `extension` is the file type the data would have on disk, if known.
+    pub fn import_bytes(&self, original: Vec<u8>, extension: Option<&str>, asset_type: AssetType, alt_text: Option<String>) -> AppResult<Asset> {
+        let original_extension = extension.unwrap_or_default().to_ascii_lowercase();
+
+        let (bytes, extension) = match asset_type {
+            // Vector and animated images are kept as they are
+            AssetType::Image if original_extension != "svg" && original_extension != "gif" => {
+                let optimized = optimize_image(&original)?;
+                (optimized.bytes, optimized.extension.to_string())
+            }
+            AssetType::Audio => {
+                let hint = if original_extension.is_empty() { None } else { Some(original_extension.as_str()) };
+                (normalize_audio(&original, hint)?, "wav".to_string())
+            }
+            _ => (original, original_extension),
+        };
+
+        let directory = self.asset_directory.join(match asset_type {
+            AssetType::Image => "images",
+            AssetType::Audio => "audio",
+            AssetType::Animation => "animations",
+        });
+        fs::create_dir_all(&directory)?;
+
+        let digest = hex::encode(Sha256::digest(&bytes));
+        let mut file_name = digest[..16].to_string();
+        if !extension.is_empty() {
+            file_name = format!("{}.{}", file_name, extension);
+        }
+        let path = directory.join(file_name);
+        if !path.exists() {
+            fs::write(&path, &bytes)?;
+        }
+
+        Ok(Asset {
+            id: None,
+            question_id: QuestionId(0), // Set when the question is stored
+            asset_type,
+            file_path: path.to_string_lossy().into_owned(),
+            alt_text,
+            file_size: Some(bytes.len() as u64),
+            created_at: None,
+        })
+    }
+}
+
+/// Upright an image, scale it down to `MAX_IMAGE_DIMENSION` and re-encode it. Re-encoding
+/// from pixels drops EXIF and other metadata, including camera location.
+pub fn optimize_image(original: &[u8]) -> AppResult<OptimizedImage> {
+    let mut image = image::load_from_memory(original)
+        .map_err(|e| AppError::InvalidInput(format!("Not a supported image: {}", e)))?;
+
+    image = match exif_orientation(original) {
+        Some(2) => image.fliph(),
+        Some(3) => image.rotate180(),
+        Some(4) => image.flipv(),
+        Some(5) => image.rotate90().fliph(),
+        Some(6) => image.rotate90(),
+        Some(7) => image.rotate270().fliph(),
+        Some(8) => image.rotate270(),
+        _ => image,
+    };
+
+    let (width, height) = image.dimensions();
+    if width > MAX_IMAGE_DIMENSION || height > MAX_IMAGE_DIMENSION {
+        image = image.resize(MAX_IMAGE_DIMENSION, MAX_IMAGE_DIMENSION, FilterType::Lanczos3);
+    }
+    let (width, height) = image.dimensions();
+
+    let has_alpha = image.color().has_alpha();
+    let pixels: DynamicImage = if has_alpha {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    };
+
+    let mut webp = Vec::new();
+    WebPEncoder::new_lossless(&mut webp)
+        .write_image(pixels.as_bytes(), width, height, pixels.color())
+        .map_err(|e| AppError::ContentManagement(format!("Failed to encode image: {}", e)))?;
+
+    // Lossless WebP suits drawings and diagrams; photos are usually far smaller as JPEG
+    if !has_alpha {
+        let mut jpeg = Vec::new();
+        JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+            .write_image(pixels.as_bytes(), width, height, pixels.color())
+            .map_err(|e| AppError::ContentManagement(format!("Failed to encode image: {}", e)))?;
+        if jpeg.len() < webp.len() {
+            return Ok(OptimizedImage { bytes: jpeg, extension: "jpg", width, height });
+        }
+    }
+
+    Ok(OptimizedImage { bytes: webp, extension: "webp", width, height })
+}
+
+/// Decode a WAV, MP3, Ogg Vorbis or FLAC clip, bring it to a standard loudness and return it
+/// as 16-bit mono WAV at `AUDIO_SAMPLE_RATE`. `extension` helps identify the format.
+pub fn normalize_audio(original: &[u8], extension: Option<&str>) -> AppResult<Vec<u8>> {
+    let (samples, sample_rate) = decode_audio(original, extension)?;
+    let mut samples = resample(&samples, sample_rate, AUDIO_SAMPLE_RATE);
+
+    let rms = (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len().max(1) as f32).sqrt();
+    let peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    if rms > f32::EPSILON {
+        let gain = (db_to_amplitude(TARGET_RMS_DBFS) / rms)
+            .min(db_to_amplitude(PEAK_LIMIT_DBFS) / peak)
+            .min(db_to_amplitude(MAX_GAIN_DB));
+        for sample in &mut samples {
+            *sample *= gain;
+        }
+    }
+
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: AUDIO_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut wav = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut wav, spec)
+        .map_err(|e| AppError::ContentManagement(format!("Failed to encode audio: {}", e)))?;
+    for sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .map_err(|e| AppError::ContentManagement(format!("Failed to encode audio: {}", e)))?;
+    }
+    writer.finalize()
+        .map_err(|e| AppError::ContentManagement(format!("Failed to encode audio: {}", e)))?;
+
+    Ok(wav.into_inner())
+}
+
+/// Decode audio to mono samples between -1 and 1, with the sample rate
+fn decode_audio(original: &[u8], extension: Option<&str>) -> AppResult<(Vec<f32>, u32)> {
+    let unsupported = |e: SymphoniaError| AppError::InvalidInput(format!("Not a supported audio file: {}", e));
+
+    let mut hint = Hint::new();
+    if let Some(extension) = extension {
+        hint.with_extension(extension);
+    }
+    let source = MediaSourceStream::new(Box::new(Cursor::new(original.to_vec())), Default::default());
+    let mut format = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(unsupported)?
+        .format;
+
+    let track = format.default_track()
+        .ok_or_else(|| AppError::InvalidInput("The audio file has no audio in it".to_string()))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(unsupported)?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(unsupported(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A damaged packet is skipped rather than losing the whole clip
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(unsupported(e)),
+        };
+        let spec = *decoded.spec();
+        sample_rate = Some(spec.rate);
+        let channels = spec.channels.count().max(1);
+
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(buffer.samples()
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32));
+    }
+
+    match sample_rate {
+        Some(sample_rate) if !samples.is_empty() => Ok((samples, sample_rate)),
+        _ => Err(AppError::InvalidInput("The audio file has no audio in it".to_string())),
+    }
+}
+
+/// Linear resampling, which is fine for speech
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let step = from as f64 / to as f64;
+    let length = (samples.len() as f64 / step).floor() as usize;
+    (0..length)
+        .map(|index| {
+            let position = index as f64 * step;
+            let before = position.floor() as usize;
+            let after = (before + 1).min(samples.len() - 1);
+            let fraction = (position - before as f64) as f32;
+            samples[before] + (samples[after] - samples[before]) * fraction
+        })
+        .collect()
+}
+
+fn db_to_amplitude(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// The EXIF orientation of a JPEG, if it has one. Cameras store photos sideways and rely on
+/// this tag, which is lost when the image is re-encoded.
+fn exif_orientation(jpeg: &[u8]) -> Option<u16> {
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    // Walk the segments looking for APP1 "Exif"
+    let mut offset = 2;
+    while offset + 4 <= jpeg.len() && jpeg[offset] == 0xFF {
+        let marker = jpeg[offset + 1];
+        let length = u16::from_be_bytes([jpeg[offset + 2], jpeg[offset + 3]]) as usize;
+        let segment = jpeg.get(offset + 4..offset + 2 + length)?;
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return tiff_orientation(&segment[6..]);
+        }
+        if marker == 0xDA {
+            return None;
+        }
+        offset += 2 + length;
+    }
+    None
+}
+
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    let little_endian = match tiff.get(0..2)? {
+        b"II" => true,
+        b"MM" => false,
+        _ => return None,
+    };
+    let read_u16 = |at: usize| -> Option<u16> {
+        let bytes = [*tiff.get(at)?, *tiff.get(at + 1)?];
+        Some(if little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    };
+    let read_u32 = |at: usize| -> Option<u32> {
+        let bytes = [*tiff.get(at)?, *tiff.get(at + 1)?, *tiff.get(at + 2)?, *tiff.get(at + 3)?];
+        Some(if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    };
+
+    let ifd = read_u32(4)? as usize;
+    let entries = read_u16(ifd)? as usize;
+    (0..entries)
+        .map(|entry| ifd + 2 + entry * 12)
+        .find(|&at| read_u16(at) == Some(0x0112))
+        .and_then(|at| read_u16(at + 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_large_images_are_scaled_down_and_stored_once() {
+        let temp_dir = tempdir().unwrap();
+        let pipeline = AssetPipeline::new(temp_dir.path().join("assets"));
+
+        // A flat-colour diagram compresses best as lossless WebP
+        let picture = RgbImage::from_pixel(2560, 640, Rgb([200, 40, 40]));
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(picture)
+            .write_to(&mut Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .unwrap();
+        let source = temp_dir.path().join("Holiday Photo.PNG");
+        fs::write(&source, &png).unwrap();
+
+        let asset = pipeline.import_asset(&source, AssetType::Image, Some("A red flag".to_string())).unwrap();
+        assert!(asset.file_path.ends_with(".webp"));
+        let stored = image::open(&asset.file_path).unwrap();
+        assert_eq!(stored.dimensions(), (MAX_IMAGE_DIMENSION, MAX_IMAGE_DIMENSION / 4));
+        assert_eq!(asset.file_size, Some(fs::metadata(&asset.file_path).unwrap().len()));
+
+        let again = pipeline.import_asset(&source, AssetType::Image, None).unwrap();
+        assert_eq!(again.file_path, asset.file_path);
+
+        fs::write(&source, b"not really a picture").unwrap();
+        assert!(pipeline.import_asset(&source, AssetType::Image, None).is_err());
+    }
+
+    #[test]
+    fn test_audio_is_converted_to_mono_wav_at_a_standard_level() {
+        // A quiet stereo tone at 44.1kHz
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44_100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut quiet = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut quiet, spec).unwrap();
+        for index in 0..44_100 {
+            let sample = ((index as f32 * 440.0 * std::f32::consts::TAU / 44_100.0).sin() * 1000.0) as i16;
+            writer.write_sample(sample).unwrap();
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let normalized = normalize_audio(&quiet.into_inner(), Some("wav")).unwrap();
+        let mut reader = hound::WavReader::new(Cursor::new(normalized)).unwrap();
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.spec().sample_rate, AUDIO_SAMPLE_RATE);
+
+        let samples: Vec<f32> = reader.samples::<i16>().map(|sample| sample.unwrap() as f32 / i16::MAX as f32).collect();
+        assert_eq!(samples.len(), AUDIO_SAMPLE_RATE as usize);
+        let rms = (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt();
+        assert!((20.0 * rms.log10() - TARGET_RMS_DBFS).abs() < 0.5);
+
+        assert!(normalize_audio(b"RIFF", Some("wav")).is_err());
+    }
+
+    #[test]
+    fn test_exif_orientation_is_read_from_jpeg() {
+        // Big-endian TIFF with one IFD entry: orientation 6 (rotate 90 degrees)
+        let mut tiff = b"MM\0\x2A\0\0\0\x08\0\x01".to_vec();
+        tiff.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1, 0x00, 0x06, 0, 0]);
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend_from_slice(&tiff);
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((app1.len() + 2) as u16).to_be_bytes());
+        jpeg.extend_from_slice(&app1);
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02]);
+
+        assert_eq!(exif_orientation(&jpeg), Some(6));
+        assert_eq!(exif_orientation(b"\x89PNG"), None);
+    }
+}
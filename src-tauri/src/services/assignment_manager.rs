@@ -0,0 +1,414 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Assignment, AssignmentSource, AssignmentStatus, AssignmentSummary, CreateAssignmentRequest, MixConfig, EntityId, ProfileId};
+use crate::database::{row_exists, DatabaseManager};
+use crate::services::QuizSession;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension, Row};
+use chrono::{DateTime, Utc};
+
+const SOURCE_MIX: &str = "mix";
+const SOURCE_BLUEPRINT: &str = "blueprint";
+
+const ASSIGNMENT_COLUMNS: &str = "id, profile_id, title, source_type, mix_id, blueprint, due_at, minimum_score,
+     created_at, completed_at, best_score, attempts";
+
+/// Homework assignment manager: parents set work, children complete it
+pub struct AssignmentManager {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl AssignmentManager {
+    /// Create a new assignment manager
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Create an assignment for a profile
+    pub fn create_assignment(&self, request: CreateAssignmentRequest) -> AppResult<Assignment> {
+        request.validate().map_err(AppError::InvalidInput)?;
+
+        let (source_type, mix_id, blueprint) = match &request.source {
+            AssignmentSource::Mix { mix_id } => (SOURCE_MIX, Some(*mix_id), None),
+            AssignmentSource::Blueprint { config } => (SOURCE_BLUEPRINT, None, Some(serde_json::to_string(config)?)),
+        };
+
+        let assignment_id = self.db_manager.transaction(|tx| {
+            if !row_exists(tx, request.profile_id)? {
+                return Ok(None);
+            }
+
+            if let Some(mix_id) = mix_id {
+                if !row_exists(tx, mix_id)? {
+                    return Ok(Some(Err(mix_id.not_found())));
+                }
+            }
+
+            tx.execute(
+                "INSERT INTO assignments
+                 (profile_id, title, source_type, mix_id, blueprint, due_at, minimum_score, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    request.profile_id,
+                    request.title.trim(),
+                    source_type,
+                    mix_id,
+                    blueprint,
+                    request.due_at.to_rfc3339(),
+                    request.minimum_score,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+
+            Ok(Some(Ok(tx.last_insert_rowid() as u32)))
+        })?;
+
+        match assignment_id {
+            Some(Ok(id)) => self.get_assignment(id),
+            Some(Err(e)) => Err(e),
+            None => Err(AppError::ProfileNotFound { id: request.profile_id }),
+        }
+    }
+
+    /// Get an assignment by ID
+    pub fn get_assignment(&self, assignment_id: u32) -> AppResult<Assignment> {
+        let assignment = self.db_manager.execute(|conn| {
+            conn.query_row(
+                &format!("SELECT {} FROM assignments WHERE id = ?1", ASSIGNMENT_COLUMNS),
+                params![assignment_id],
+                Self::row_to_assignment,
+            ).optional()
+        })?;
+
+        assignment.ok_or_else(|| AppError::NotFound(format!("Assignment with id {} not found", assignment_id)))
+    }
+
+    /// Get every assignment for a profile, soonest due first
+    pub fn get_assignments_for_profile(&self, profile_id: ProfileId) -> AppResult<Vec<Assignment>> {
+        self.query_assignments(
+            &format!("SELECT {} FROM assignments WHERE profile_id = ?1 ORDER BY due_at", ASSIGNMENT_COLUMNS),
+            params![profile_id],
+        )
+    }
+
+    /// Assignments the child still has to pass (including overdue ones), soonest due first
+    pub fn get_pending_assignments(&self, profile_id: ProfileId) -> AppResult<Vec<Assignment>> {
+        let now = Utc::now();
+        Ok(self.get_assignments_for_profile(profile_id)?
+            .into_iter()
+            .filter(|assignment| matches!(
+                assignment.status(now),
+                AssignmentStatus::Pending | AssignmentStatus::Overdue
+            ))
+            .collect())
+    }
+
+    /// Number of open assignments across all profiles, shown on the tray icon
+    pub fn count_pending_assignments(&self) -> AppResult<u32> {
+        let now = Utc::now();
+        Ok(self.query_assignments(
+            &format!("SELECT {} FROM assignments WHERE completed_at IS NULL", ASSIGNMENT_COLUMNS),
+            [],
+        )?
+            .iter()
+            .filter(|assignment| assignment.status(now) == AssignmentStatus::Pending)
+            .count() as u32)
+    }
+
+    /// Quiz configuration the child should run for an assignment
+    pub fn get_quiz_config(&self, assignment_id: u32) -> AppResult<MixConfig> {
+        match self.get_assignment(assignment_id)?.source {
+            AssignmentSource::Blueprint { config } => Ok(config),
+            AssignmentSource::Mix { mix_id } => {
+                let config_json = self.db_manager.execute(|conn| {
+                    conn.query_row(
+                        "SELECT config FROM custom_mixes WHERE id = ?1",
+                        params![mix_id],
+                        |row| row.get::<_, String>(0),
+                    ).optional()
+                })?.ok_or_else(|| mix_id.not_found())?;
+
+                Ok(serde_json::from_str(&config_json)?)
+            }
+        }
+    }
+
+    /// Record a finished session as an attempt, scoring `score_percentage`. The session must
+    /// have been started for this assignment by the assignment's profile. The best score
+    /// counts, and the assignment is completed once it passes.
+    pub fn record_attempt(&self, assignment_id: u32, session: &QuizSession, score_percentage: u8) -> AppResult<Assignment> {
+        if score_percentage > 100 {
+            return Err(AppError::InvalidInput("Score must be a percentage between 0 and 100".to_string()));
+        }
+
+        let assignment = self.get_assignment(assignment_id)?;
+        if session.assignment_id != Some(assignment_id) || session.profile_id != assignment.profile_id {
+            return Err(AppError::InvalidInput("That quiz wasn't played for this assignment".to_string()));
+        }
+        if !session.is_completed() {
+            return Err(AppError::InvalidInput("The assignment's quiz hasn't been finished".to_string()));
+        }
+        if assignment.passed() {
+            return Err(AppError::InvalidInput("Assignment has already been passed".to_string()));
+        }
+
+        let best_score = assignment.best_score.map_or(score_percentage, |best| best.max(score_percentage));
+        let completed_at = if best_score >= assignment.minimum_score {
+            Some(Utc::now().to_rfc3339())
+        } else {
+            None
+        };
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE assignments SET best_score = ?1, attempts = attempts + 1, completed_at = ?2 WHERE id = ?3",
+                params![best_score, completed_at, assignment_id],
+            )?;
+            Ok(())
+        })?;
+
+        self.get_assignment(assignment_id)
+    }
+
+    /// Delete an assignment
+    pub fn delete_assignment(&self, assignment_id: u32) -> AppResult<()> {
+        let deleted = self.db_manager.execute(|conn| {
+            conn.execute("DELETE FROM assignments WHERE id = ?1", params![assignment_id])
+        })?;
+
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Assignment with id {} not found", assignment_id)));
+        }
+        Ok(())
+    }
+
+    /// Count assignments by status for a profile, limited to those due since `since`
+    pub fn get_summary(&self, profile_id: ProfileId, since: DateTime<Utc>) -> AppResult<AssignmentSummary> {
+        let now = Utc::now();
+        let mut summary = AssignmentSummary {
+            profile_id,
+            pending: 0,
+            overdue: 0,
+            passed: 0,
+            failed: 0,
+        };
+
+        for assignment in self.get_assignments_for_profile(profile_id)? {
+            if assignment.due_at < since {
+                continue;
+            }
+
+            match assignment.status(now) {
+                AssignmentStatus::Pending => summary.pending += 1,
+                AssignmentStatus::Overdue => summary.overdue += 1,
+                AssignmentStatus::Passed => summary.passed += 1,
+                AssignmentStatus::Failed => summary.failed += 1,
+            }
+        }
+
+        Ok(summary)
+    }
+
+    fn query_assignments<P: rusqlite::Params>(&self, sql: &str, query_params: P) -> AppResult<Vec<Assignment>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map(query_params, Self::row_to_assignment)?;
+
+            let mut assignments = Vec::new();
+            for assignment in rows {
+                assignments.push(assignment?);
+            }
+            Ok(assignments)
+        })?)
+    }
+
+    fn row_to_assignment(row: &Row) -> rusqlite::Result<Assignment> {
+        let source_type: String = row.get(3)?;
+        let source = match source_type.as_str() {
+            SOURCE_MIX => AssignmentSource::Mix { mix_id: row.get(4)? },
+            _ => {
+                let blueprint: String = row.get(5)?;
+                let config = serde_json::from_str(&blueprint)
+                    .map_err(|_| rusqlite::Error::InvalidColumnType(5, "blueprint".to_string(), rusqlite::types::Type::Text))?;
+                AssignmentSource::Blueprint { config }
+            }
+        };
+
+        Ok(Assignment {
+            id: Some(row.get(0)?),
+            profile_id: row.get(1)?,
+            title: row.get(2)?,
+            source,
+            due_at: parse_timestamp(row, 6)?,
+            minimum_score: row.get(7)?,
+            created_at: Some(parse_timestamp(row, 8)?),
+            completed_at: match row.get::<_, Option<String>>(9)? {
+                Some(_) => Some(parse_timestamp(row, 9)?),
+                None => None,
+            },
+            best_score: row.get(10)?,
+            attempts: row.get(11)?,
+        })
+    }
+}
+
+fn parse_timestamp(row: &Row, index: usize) -> rusqlite::Result<DateTime<Utc>> {
+    let value: String = row.get(index)?;
+    DateTime::parse_from_rfc3339(&value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| rusqlite::Error::InvalidColumnType(index, "timestamp".to_string(), rusqlite::types::Type::Text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{Answer, KeyStage, MixId, Question, QuestionContent, QuestionType};
+    use crate::services::{ContentManager, QuizEngine, SecurityService};
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    fn create_test_assignment_manager() -> (AssignmentManager, Arc<DatabaseManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let manager = AssignmentManager::new(db_service.manager());
+
+        (manager, db_service.manager(), temp_dir)
+    }
+
+    fn create_test_profile(db_manager: &DatabaseManager) -> ProfileId {
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Homework Tester', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap()
+    }
+
+    /// Play a one-question quiz started for `assignment_id` to the end
+    fn play_assignment(db_manager: &Arc<DatabaseManager>, profile_id: ProfileId, assignment_id: u32, finish: bool) -> QuizSession {
+        let temp_dir = tempdir().unwrap();
+        let content_manager = Arc::new(ContentManager::new(
+            db_manager.clone(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let question_id = content_manager.add_question(Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: "2 + 2".to_string(),
+                options: Some(vec!["4".to_string(), "5".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("4".to_string()),
+        )).unwrap();
+
+        let mut quiz_engine = QuizEngine::new(db_manager.clone(), content_manager.clone());
+        let question = content_manager.get_question_by_id(question_id).unwrap();
+        let session = quiz_engine.start_assignment_session(profile_id, assignment_id, "Homework", None, vec![question]).unwrap();
+        let session_id = session.id.unwrap();
+        if !finish {
+            return session;
+        }
+        quiz_engine.submit_answer(session_id, Answer::Text("4".to_string()), 5).unwrap();
+        quiz_engine.get_completed_session(session_id).unwrap()
+    }
+
+    fn blueprint_request(profile_id: ProfileId, due_at: DateTime<Utc>) -> CreateAssignmentRequest {
+        CreateAssignmentRequest {
+            profile_id,
+            title: "Number bonds".to_string(),
+            source: AssignmentSource::Blueprint {
+                config: MixConfig::new(vec!["mathematics".to_string()], vec![KeyStage::KS1], 10),
+            },
+            due_at,
+            minimum_score: 70,
+        }
+    }
+
+    #[test]
+    fn test_attempts_until_passed() {
+        let (manager, db, _temp_dir) = create_test_assignment_manager();
+        let profile_id = create_test_profile(&db);
+
+        let assignment = manager.create_assignment(blueprint_request(profile_id, Utc::now() + Duration::days(2))).unwrap();
+        let assignment_id = assignment.id.unwrap();
+        assert_eq!(manager.get_pending_assignments(profile_id).unwrap().len(), 1);
+        assert_eq!(manager.count_pending_assignments().unwrap(), 1);
+        assert_eq!(manager.get_quiz_config(assignment_id).unwrap().question_count, 10);
+
+        let session = play_assignment(&db, profile_id, assignment_id, true);
+        let attempt = manager.record_attempt(assignment_id, &session, 50).unwrap();
+        assert_eq!(attempt.status(Utc::now()), AssignmentStatus::Pending);
+        assert!(attempt.completed_at.is_none());
+
+        let attempt = manager.record_attempt(assignment_id, &session, 80).unwrap();
+        assert_eq!(attempt.status(Utc::now()), AssignmentStatus::Passed);
+        assert_eq!(attempt.attempts, 2);
+        assert!(attempt.completed_at.is_some());
+
+        assert!(manager.get_pending_assignments(profile_id).unwrap().is_empty());
+        assert!(manager.record_attempt(assignment_id, &session, 90).is_err());
+    }
+
+    #[test]
+    fn test_attempts_need_a_finished_session_for_the_assignment() {
+        let (manager, db, _temp_dir) = create_test_assignment_manager();
+        let profile_id = create_test_profile(&db);
+        let assignment_id = manager.create_assignment(blueprint_request(profile_id, Utc::now() + Duration::days(2))).unwrap().id.unwrap();
+        let other_assignment_id = manager.create_assignment(blueprint_request(profile_id, Utc::now() + Duration::days(2))).unwrap().id.unwrap();
+
+        let unfinished = play_assignment(&db, profile_id, assignment_id, false);
+        assert!(matches!(manager.record_attempt(assignment_id, &unfinished, 100), Err(AppError::InvalidInput(_))));
+
+        let other_assignment = play_assignment(&db, profile_id, other_assignment_id, true);
+        assert!(matches!(manager.record_attempt(assignment_id, &other_assignment, 100), Err(AppError::InvalidInput(_))));
+
+        let other_profile = play_assignment(&db, create_test_profile(&db), assignment_id, true);
+        assert!(matches!(manager.record_attempt(assignment_id, &other_profile, 100), Err(AppError::InvalidInput(_))));
+
+        assert_eq!(manager.get_assignment(assignment_id).unwrap().attempts, 0);
+    }
+
+    #[test]
+    fn test_summary_and_validation() {
+        let (manager, db, _temp_dir) = create_test_assignment_manager();
+        let profile_id = create_test_profile(&db);
+        let week_ago = Utc::now() - Duration::days(7);
+
+        let overdue = manager.create_assignment(blueprint_request(profile_id, Utc::now() - Duration::days(1))).unwrap();
+        let failed = manager.create_assignment(blueprint_request(profile_id, Utc::now() - Duration::days(2))).unwrap();
+        db.execute(|conn| conn.execute(
+            "UPDATE assignments SET best_score = 20, attempts = 1 WHERE id = ?1",
+            params![failed.id],
+        )).unwrap();
+        manager.create_assignment(blueprint_request(profile_id, Utc::now() + Duration::days(1))).unwrap();
+
+        let summary = manager.get_summary(profile_id, week_ago).unwrap();
+        assert_eq!((summary.pending, summary.overdue, summary.failed, summary.passed), (1, 1, 1, 0));
+        assert_eq!(overdue.status(Utc::now()), AssignmentStatus::Overdue);
+
+        let mut request = blueprint_request(profile_id, Utc::now());
+        request.minimum_score = 120;
+        assert!(matches!(manager.create_assignment(request), Err(AppError::InvalidInput(_))));
+
+        let mut request = blueprint_request(profile_id, Utc::now());
+        request.source = AssignmentSource::Mix { mix_id: MixId(999) };
+        assert!(matches!(manager.create_assignment(request), Err(AppError::NotFound(_))));
+
+        assert!(matches!(
+            manager.create_assignment(blueprint_request(ProfileId(999), Utc::now())),
+            Err(AppError::ProfileNotFound { .. })
+        ));
+    }
+}
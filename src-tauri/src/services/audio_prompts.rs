@@ -0,0 +1,186 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Asset, AssetType, AudioPrompt, AUDIO_PROMPT_KEY, QuestionId};
+use crate::services::{AssetPipeline, ContentManager, SpokenAudio};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Attaches parent-recorded clips to questions, so a spelling or dictation test can be
+/// read out in a familiar voice. Clips go through the asset pipeline like any other audio.
+pub struct AudioPromptService {
+    content_manager: Arc<ContentManager>,
+    asset_pipeline: Arc<AssetPipeline>,
+}
+
+impl AudioPromptService {
+    /// Create a new audio prompt service
+    pub fn new(content_manager: Arc<ContentManager>, asset_pipeline: Arc<AssetPipeline>) -> Self {
+        Self {
+            content_manager,
+            asset_pipeline,
+        }
+    }
+
+    /// Save a clip recorded in the app as a question's prompt, replacing any earlier one
+    pub fn record_prompt(&self, question_id: QuestionId, recording: &SpokenAudio, label: Option<String>) -> AppResult<AudioPrompt> {
+        if recording.data.is_empty() {
+            return Err(AppError::InvalidInput("The recording is empty".to_string()));
+        }
+        let asset = self.asset_pipeline.import_bytes(
+            recording.data.clone(),
+            extension_for_mime_type(&recording.mime_type),
+            AssetType::Audio,
+            Some(prompt_alt_text(&label)),
+        )?;
+        self.set_prompt(question_id, Some(asset), label)
+    }
+
+    /// Use an audio file the parent already has as a question's prompt
+    pub fn attach_prompt_file(&self, question_id: QuestionId, path: &Path, label: Option<String>) -> AppResult<AudioPrompt> {
+        let asset = self.asset_pipeline.import_asset(path, AssetType::Audio, Some(prompt_alt_text(&label)))?;
+        self.set_prompt(question_id, Some(asset), label)
+    }
+
+    /// Stop playing a clip with the question. The audio file is left for other questions
+    /// that use the same recording.
+    pub fn remove_prompt(&self, question_id: QuestionId) -> AppResult<()> {
+        self.set_prompt(question_id, None, None).map(|_| ())
+    }
+
+    fn set_prompt(
+        &self,
+        question_id: QuestionId,
+        clip: Option<Asset>,
+        label: Option<String>,
+    ) -> AppResult<AudioPrompt> {
+        let mut question = self.content_manager.get_question_by_id(question_id)?;
+        let previous = question.content.audio_prompt()
+            .map_err(|e| AppError::InvalidQuestion(format!("Invalid audio prompt: {}", e)))?;
+
+        let mut assets = question.assets.take().unwrap_or_default();
+        if let Some(previous) = &previous {
+            assets.retain(|asset| asset.file_path != previous.file_path);
+        }
+
+        let data = question.content.additional_data.get_or_insert_with(Default::default);
+        let prompt = match clip {
+            Some(asset) => {
+                let prompt = AudioPrompt { file_path: asset.file_path.clone(), label };
+                assets.push(asset);
+                data.insert(AUDIO_PROMPT_KEY.to_string(), serde_json::to_value(&prompt)?);
+                prompt
+            }
+            None => {
+                data.remove(AUDIO_PROMPT_KEY);
+                previous.ok_or_else(|| AppError::NotFound(format!("Question {} has no audio prompt", question_id)))?
+            }
+        };
+        question.assets = Some(assets);
+
+        self.content_manager.update_question(question_id, question)?;
+        Ok(prompt)
+    }
+}
+
+fn prompt_alt_text(label: &Option<String>) -> String {
+    match label {
+        Some(label) => format!("Question read aloud by {}", label),
+        None => "Question read aloud".to_string(),
+    }
+}
+
+/// File extension for a recording's MIME type, to help identify the audio format
+fn extension_for_mime_type(mime_type: &str) -> Option<&'static str> {
+    match mime_type.split(';').next().unwrap_or_default().trim() {
+        "audio/wav" | "audio/wave" | "audio/x-wav" => Some("wav"),
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        "audio/ogg" => Some("ogg"),
+        "audio/flac" => Some("flac"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{Answer, KeyStage, Question, QuestionContent, QuestionType};
+    use crate::services::SecurityService;
+    use std::io::Cursor;
+    use tempfile::tempdir;
+
+    fn recording(sample_count: usize) -> Vec<u8> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut wav, spec).unwrap();
+        for index in 0..sample_count {
+            writer.write_sample(((index % 40) as i16 - 20) * 200).unwrap();
+        }
+        writer.finalize().unwrap();
+        wav.into_inner()
+    }
+
+    #[test]
+    fn test_recorded_prompt_replaces_earlier_clip() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let service = AudioPromptService::new(
+            content_manager.clone(),
+            Arc::new(AssetPipeline::new(temp_dir.path().join("assets"))),
+        );
+
+        let question_id = content_manager.add_question(Question::new(
+            1,
+            KeyStage::KS2,
+            QuestionType::ShortAnswer,
+            QuestionContent {
+                text: "Spell the word you hear".to_string(),
+                options: None,
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("necessary".to_string()),
+        )).unwrap();
+
+        let first = service.attach_prompt_file(question_id, &{
+            let path = temp_dir.path().join("necessary.wav");
+            std::fs::write(&path, recording(8_000)).unwrap();
+            path
+        }, None).unwrap();
+
+        let prompt = service.record_prompt(
+            question_id,
+            &SpokenAudio { mime_type: "audio/wav;codecs=1".to_string(), data: recording(12_000) },
+            Some("Dad".to_string()),
+        ).unwrap();
+        assert_ne!(prompt.file_path, first.file_path);
+
+        let question = content_manager.get_question_by_id(question_id).unwrap();
+        assert_eq!(question.content.audio_prompt().unwrap(), Some(prompt.clone()));
+        let assets = question.assets.unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].file_path, prompt.file_path);
+        assert_eq!(assets[0].alt_text.as_deref(), Some("Question read aloud by Dad"));
+
+        service.remove_prompt(question_id).unwrap();
+        let question = content_manager.get_question_by_id(question_id).unwrap();
+        assert_eq!(question.content.audio_prompt().unwrap(), None);
+        assert!(question.assets.unwrap().is_empty());
+        assert!(service.remove_prompt(question_id).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::errors::AppResult;
+use crate::models::{ProfileId, QuestionId};
+use crate::database::DatabaseManager;
+use std::sync::Arc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Most recent boss attempts returned with the stats
+const RECENT_ATTEMPT_COUNT: u32 = 10;
+
+/// One attempt at a boss question
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BossAttempt {
+    pub question_id: QuestionId,
+    pub difficulty_level: u8,
+    pub is_correct: bool,
+    pub attempted_at: DateTime<Utc>,
+}
+
+/// How a profile has done on boss questions over time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BossQuestionStats {
+    pub profile_id: ProfileId,
+    pub attempts: u32,
+    pub successes: u32,
+    pub success_rate: u8,
+    /// Boss questions beaten in a row, up to the latest attempt
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// Newest first
+    pub recent: Vec<BossAttempt>,
+}
+
+/// Records boss question attempts
+pub struct BossQuestionTracker {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl BossQuestionTracker {
+    /// Create a new boss question tracker
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Record an answered boss question
+    pub fn record(&self, profile_id: ProfileId, question_id: QuestionId, is_correct: bool) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO boss_question_attempts (profile_id, question_id, difficulty_level, is_correct, attempted_at)
+                 SELECT ?1, id, difficulty_level, ?3, ?4 FROM questions WHERE id = ?2",
+                params![profile_id, question_id, is_correct, Utc::now().to_rfc3339()],
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Success on boss questions for a profile
+    pub fn get_stats(&self, profile_id: ProfileId) -> AppResult<BossQuestionStats> {
+        let outcomes: Vec<bool> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT is_correct FROM boss_question_attempts WHERE profile_id = ?1 ORDER BY attempted_at, id"
+            )?;
+            let rows = stmt.query_map(params![profile_id], |row| row.get(0))?;
+            rows.collect()
+        })?;
+
+        let recent = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT question_id, difficulty_level, is_correct, attempted_at FROM boss_question_attempts
+                 WHERE profile_id = ?1 ORDER BY attempted_at DESC, id DESC LIMIT ?2"
+            )?;
+            let rows = stmt.query_map(params![profile_id, RECENT_ATTEMPT_COUNT], |row| {
+                let attempted_at: String = row.get(3)?;
+                Ok(BossAttempt {
+                    question_id: row.get(0)?,
+                    difficulty_level: row.get(1)?,
+                    is_correct: row.get(2)?,
+                    attempted_at: DateTime::parse_from_rfc3339(&attempted_at)
+                        .map(|attempted_at| attempted_at.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })?;
+            rows.collect()
+        })?;
+
+        let attempts = outcomes.len() as u32;
+        let successes = outcomes.iter().filter(|&&is_correct| is_correct).count() as u32;
+        let mut current_streak = 0;
+        let mut best_streak = 0;
+        for &is_correct in &outcomes {
+            current_streak = if is_correct { current_streak + 1 } else { 0 };
+            best_streak = best_streak.max(current_streak);
+        }
+
+        Ok(BossQuestionStats {
+            profile_id,
+            attempts,
+            successes,
+            success_rate: (successes * 100).checked_div(attempts).unwrap_or(0) as u8,
+            current_streak,
+            best_streak,
+            recent,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_boss_stats_track_streaks() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let (profile_id, question_id) = db_service.manager().execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            let profile_id = ProfileId(conn.last_insert_rowid() as u32);
+            conn.execute(
+                "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level)
+                 VALUES ((SELECT id FROM subjects WHERE name = 'mathematics'), 'KS1', 'multiple_choice', '{}', '\"A\"', 3)",
+                [],
+            )?;
+            Ok((profile_id, QuestionId(conn.last_insert_rowid() as u32)))
+        }).unwrap();
+
+        let tracker = BossQuestionTracker::new(db_service.manager());
+        assert_eq!(tracker.get_stats(profile_id).unwrap().attempts, 0);
+
+        for is_correct in [true, true, false, true] {
+            tracker.record(profile_id, question_id, is_correct).unwrap();
+        }
+
+        let stats = tracker.get_stats(profile_id).unwrap();
+        assert_eq!(stats.attempts, 4);
+        assert_eq!(stats.successes, 3);
+        assert_eq!(stats.success_rate, 75);
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.best_streak, 2);
+        assert_eq!(stats.recent.len(), 4);
+        assert_eq!(stats.recent[0].difficulty_level, 3);
+    }
+}
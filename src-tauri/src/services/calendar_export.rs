@@ -0,0 +1,266 @@
+use crate::errors::AppResult;
+use crate::models::{Assignment, AssignmentStatus, ProfileId};
+use crate::services::{AssignmentManager, NotificationService, ProfileManager};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+
+const PRODUCT_ID: &str = "-//QuizDD//Practice Calendar//EN";
+const UID_DOMAIN: &str = "quizdd.local";
+const UTC_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Length of the calendar block shown for a practice session or assignment
+const EVENT_MINUTES: i64 = 30;
+
+/// Longest content line allowed by RFC 5545, in octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Exports assignments and practice reminders as iCalendar (`.ics`) feeds
+/// that household calendar apps can import or subscribe to as a local file
+pub struct CalendarExporter {
+    profile_manager: Arc<ProfileManager>,
+    assignment_manager: Arc<AssignmentManager>,
+    notifications: Arc<NotificationService>,
+    feed_dir: PathBuf,
+}
+
+impl CalendarExporter {
+    /// Create a new exporter writing live feeds to `feed_dir`
+    pub fn new(
+        profile_manager: Arc<ProfileManager>,
+        assignment_manager: Arc<AssignmentManager>,
+        notifications: Arc<NotificationService>,
+        feed_dir: PathBuf,
+    ) -> Self {
+        Self {
+            profile_manager,
+            assignment_manager,
+            notifications,
+            feed_dir,
+        }
+    }
+
+    /// Build the calendar for one profile: open assignments plus the daily practice reminder
+    pub fn export_profile_calendar(&self, profile_id: ProfileId) -> AppResult<String> {
+        let profile = self.profile_manager.get_profile_by_id(profile_id)?;
+        let preferences = self.notifications.get_preferences(profile_id)?;
+        let now = Utc::now();
+
+        let mut calendar = IcsWriter::new(&format!("{}'s practice", profile.name));
+
+        if preferences.reminders_enabled {
+            if let Ok(reminder_time) = NaiveTime::parse_from_str(&preferences.reminder_time, "%H:%M") {
+                calendar.daily_reminder(profile_id, &profile.name, reminder_time, preferences.daily_question_goal, now);
+            }
+        }
+
+        for assignment in self.assignment_manager.get_assignments_for_profile(profile_id)? {
+            if matches!(assignment.status(now), AssignmentStatus::Pending | AssignmentStatus::Overdue) {
+                calendar.assignment(&assignment, now);
+            }
+        }
+
+        Ok(calendar.finish())
+    }
+
+    /// Write a profile's calendar to `path`
+    pub fn write_calendar(&self, profile_id: ProfileId, path: &Path) -> AppResult<()> {
+        let calendar = self.export_profile_calendar(profile_id)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, calendar)?;
+        Ok(())
+    }
+
+    /// Path of the live feed for a profile, (re)written so it is current
+    pub fn refresh_feed(&self, profile_id: ProfileId) -> AppResult<PathBuf> {
+        let path = self.feed_dir.join(format!("profile-{}.ics", profile_id));
+        self.write_calendar(profile_id, &path)?;
+        Ok(path)
+    }
+
+    /// Rewrite every live feed that has already been created
+    pub fn refresh_existing_feeds(&self) -> AppResult<usize> {
+        if !self.feed_dir.exists() {
+            return Ok(0);
+        }
+
+        let mut refreshed = 0;
+        for entry in fs::read_dir(&self.feed_dir)? {
+            let file_name = entry?.file_name();
+            let profile_id = file_name.to_str()
+                .and_then(|name| name.strip_prefix("profile-"))
+                .and_then(|name| name.strip_suffix(".ics"))
+                .and_then(|id| id.parse::<u32>().ok())
+                .map(ProfileId);
+
+            if let Some(profile_id) = profile_id {
+                match self.refresh_feed(profile_id) {
+                    Ok(_) => refreshed += 1,
+                    Err(e) => log::warn!("Failed to refresh calendar feed for profile {}: {}", profile_id, e),
+                }
+            }
+        }
+
+        Ok(refreshed)
+    }
+}
+
+/// Minimal RFC 5545 writer
+struct IcsWriter {
+    lines: Vec<String>,
+}
+
+impl IcsWriter {
+    fn new(calendar_name: &str) -> Self {
+        let mut writer = Self { lines: Vec::new() };
+        writer.line("BEGIN:VCALENDAR");
+        writer.line("VERSION:2.0");
+        writer.line(&format!("PRODID:{}", PRODUCT_ID));
+        writer.line("CALSCALE:GREGORIAN");
+        writer.line(&format!("X-WR-CALNAME:{}", escape_text(calendar_name)));
+        writer
+    }
+
+    fn daily_reminder(&mut self, profile_id: ProfileId, name: &str, time: NaiveTime, goal: u32, now: DateTime<Utc>) {
+        // Floating local time so the reminder stays at the same clock time across DST changes
+        let start = now.date_naive().and_time(time);
+
+        self.line("BEGIN:VEVENT");
+        self.line(&format!("UID:reminder-{}@{}", profile_id, UID_DOMAIN));
+        self.line(&format!("DTSTAMP:{}", now.format(UTC_FORMAT)));
+        self.line(&format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+        self.line(&format!("DTEND:{}", (start + Duration::minutes(EVENT_MINUTES)).format("%Y%m%dT%H%M%S")));
+        self.line("RRULE:FREQ=DAILY");
+        self.line(&format!("SUMMARY:{}", escape_text(&format!("{}'s daily quiz practice", name))));
+        self.line(&format!("DESCRIPTION:{}", escape_text(&format!("Today's goal: {} questions", goal))));
+        self.alarm("Time for daily quiz practice");
+        self.line("END:VEVENT");
+    }
+
+    fn assignment(&mut self, assignment: &Assignment, now: DateTime<Utc>) {
+        let due_at = assignment.due_at;
+
+        self.line("BEGIN:VEVENT");
+        self.line(&format!("UID:assignment-{}@{}", assignment.id.unwrap_or_default(), UID_DOMAIN));
+        self.line(&format!("DTSTAMP:{}", now.format(UTC_FORMAT)));
+        self.line(&format!("DTSTART:{}", (due_at - Duration::minutes(EVENT_MINUTES)).format(UTC_FORMAT)));
+        self.line(&format!("DTEND:{}", due_at.format(UTC_FORMAT)));
+        self.line(&format!("SUMMARY:{}", escape_text(&format!("Homework: {}", assignment.title))));
+        self.line(&format!("DESCRIPTION:{}", escape_text(&format!(
+            "Pass mark: {}%. Attempts so far: {}.",
+            assignment.minimum_score, assignment.attempts
+        ))));
+        self.alarm("Homework is due soon");
+        self.line("END:VEVENT");
+    }
+
+    fn alarm(&mut self, description: &str) {
+        self.line("BEGIN:VALARM");
+        self.line("ACTION:DISPLAY");
+        self.line("TRIGGER:-PT15M");
+        self.line(&format!("DESCRIPTION:{}", escape_text(description)));
+        self.line("END:VALARM");
+    }
+
+    fn line(&mut self, content: &str) {
+        self.lines.push(fold_line(content));
+    }
+
+    fn finish(mut self) -> String {
+        self.line("END:VCALENDAR");
+        let mut calendar = self.lines.join("\r\n");
+        calendar.push_str("\r\n");
+        calendar
+    }
+}
+
+/// Escape a TEXT value
+fn escape_text(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line at 75 octets without splitting a UTF-8 character
+fn fold_line(content: &str) -> String {
+    let mut folded = String::new();
+    let mut line_octets = 0;
+
+    for c in content.chars() {
+        if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{AssignmentSource, CreateAssignmentRequest, CreateProfileRequest, KeyStage, MixConfig};
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_escape_and_fold() {
+        assert_eq!(escape_text("Maths; times tables, 2x\nand 3x"), "Maths\\; times tables\\, 2x\\nand 3x");
+
+        let folded = fold_line(&format!("SUMMARY:{}", "é".repeat(60)));
+        assert!(folded.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), format!("SUMMARY:{}", "é".repeat(60)));
+    }
+
+    #[test]
+    fn test_profile_calendar_contains_reminder_and_assignments() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let profile_manager = Arc::new(ProfileManager::new(db_service.manager(), security_service));
+        let assignment_manager = Arc::new(AssignmentManager::new(db_service.manager()));
+        let notifications = Arc::new(NotificationService::new(db_service.manager()));
+        let exporter = CalendarExporter::new(
+            profile_manager.clone(),
+            assignment_manager.clone(),
+            notifications,
+            temp_dir.path().join("calendars"),
+        );
+
+        let profile_id = profile_manager.create_profile(CreateProfileRequest {
+            name: "Ada".to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        }).unwrap().id.unwrap();
+        assignment_manager.create_assignment(CreateAssignmentRequest {
+            profile_id,
+            title: "Spelling, week 3".to_string(),
+            source: AssignmentSource::Blueprint {
+                config: MixConfig::new(vec!["english".to_string()], vec![KeyStage::KS2], 10),
+            },
+            due_at: Utc::now() + Duration::days(2),
+            minimum_score: 80,
+        }).unwrap();
+
+        let feed_path = exporter.refresh_feed(profile_id).unwrap();
+        let calendar = fs::read_to_string(&feed_path).unwrap();
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        assert!(calendar.contains("RRULE:FREQ=DAILY"));
+        assert!(calendar.contains("SUMMARY:Homework: Spelling\\, week 3"));
+        assert_eq!(exporter.refresh_existing_feeds().unwrap(), 1);
+    }
+}
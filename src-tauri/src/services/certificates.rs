@@ -0,0 +1,251 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Achievement, AchievementCategory, Locale, ProfileId};
+use crate::services::{FormattingService, ProfileManager};
+use crate::services::pdf::{self, Font};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// A4 landscape, in PDF points
+const PAGE_WIDTH: f32 = 842.0;
+const PAGE_HEIGHT: f32 = 595.0;
+
+/// Generates printable PDF certificates for earned achievements
+pub struct CertificateGenerator {
+    profile_manager: Arc<ProfileManager>,
+    formatting: Arc<FormattingService>,
+    output_dir: PathBuf,
+}
+
+impl CertificateGenerator {
+    /// Create a new generator saving certificates under `output_dir`
+    pub fn new(profile_manager: Arc<ProfileManager>, formatting: Arc<FormattingService>, output_dir: PathBuf) -> Self {
+        Self {
+            profile_manager,
+            formatting,
+            output_dir,
+        }
+    }
+
+    /// Generate (or regenerate) the certificate for an achievement a profile has earned
+    pub fn generate_certificate(&self, profile_id: ProfileId, achievement_id: &str) -> AppResult<PathBuf> {
+        let profile = self.profile_manager.get_profile_by_id(profile_id)?;
+        let achievement = self.profile_manager.get_progress(profile_id)?
+            .achievements
+            .into_iter()
+            .find(|achievement| achievement.id == achievement_id)
+            .ok_or_else(|| AppError::NotFound(format!(
+                "Achievement '{}' has not been earned by {}", achievement_id, profile.name
+            )))?;
+
+        self.write_certificate(profile_id, &profile.name, &achievement)
+    }
+
+    /// Generate certificates for achievements just earned, logging rather than failing on errors
+    pub fn generate_for_new_achievements(&self, profile_id: ProfileId, achievements: &[Achievement]) -> Vec<PathBuf> {
+        let profile = match self.profile_manager.get_profile_by_id(profile_id) {
+            Ok(profile) => profile,
+            Err(e) => {
+                log::warn!("Failed to load profile {} for certificates: {}", profile_id, e);
+                return Vec::new();
+            }
+        };
+
+        achievements.iter()
+            .filter_map(|achievement| match self.write_certificate(profile_id, &profile.name, achievement) {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    log::warn!("Failed to generate certificate for '{}': {}", achievement.id, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn write_certificate(&self, profile_id: ProfileId, name: &str, achievement: &Achievement) -> AppResult<PathBuf> {
+        fs::create_dir_all(&self.output_dir)?;
+
+        let file_name: String = format!("{}-{}.pdf", profile_id, achievement.id)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+            .collect();
+        let path = self.output_dir.join(file_name);
+
+        let locale = self.formatting.get_locale()?;
+        fs::write(&path, render_certificate_pdf(name, achievement, locale))?;
+        Ok(path)
+    }
+}
+
+/// Accent colour for the border and seal, by achievement category
+fn accent_colour(category: &AchievementCategory) -> (f32, f32, f32) {
+    match category {
+        AchievementCategory::Accuracy => (0.80, 0.20, 0.25),
+        AchievementCategory::Streak => (0.90, 0.45, 0.10),
+        AchievementCategory::Completion => (0.15, 0.40, 0.75),
+        AchievementCategory::Time => (0.45, 0.25, 0.65),
+        AchievementCategory::SubjectMastery => (0.15, 0.55, 0.35),
+    }
+}
+
+/// Render a one-page certificate as a PDF using the standard (non-embedded) fonts
+pub fn render_certificate_pdf(name: &str, achievement: &Achievement, locale: Locale) -> Vec<u8> {
+    let (r, g, b) = accent_colour(&achievement.category);
+    let mut content = String::new();
+
+    // Cream background with a thick accent border and a thin gold inner border
+    content.push_str(&format!("1 0.98 0.92 rg 0 0 {} {} re f\n", PAGE_WIDTH, PAGE_HEIGHT));
+    content.push_str(&format!("{} {} {} RG 12 w 24 24 {} {} re S\n", r, g, b, PAGE_WIDTH - 48.0, PAGE_HEIGHT - 48.0));
+    content.push_str(&format!("0.83 0.69 0.22 RG 2 w 44 44 {} {} re S\n", PAGE_WIDTH - 88.0, PAGE_HEIGHT - 88.0));
+
+    // Gold stars in the corners
+    for (x, y) in [(72.0, 72.0), (PAGE_WIDTH - 72.0, 72.0), (72.0, PAGE_HEIGHT - 72.0), (PAGE_WIDTH - 72.0, PAGE_HEIGHT - 72.0)] {
+        content.push_str(&star_path(x, y, 14.0));
+        content.push_str("0.83 0.69 0.22 rg f\n");
+    }
+
+    content.push_str(&centred_text("Certificate of Achievement", 40.0, 470.0, (r, g, b)));
+    content.push_str(&centred_text("This certificate is proudly presented to", 16.0, 415.0, (0.3, 0.3, 0.3)));
+    content.push_str(&centred_text(name, 44.0, 350.0, (0.1, 0.1, 0.1)));
+    content.push_str(&format!("0.83 0.69 0.22 RG 1.5 w {} 335 m {} 335 l S\n", PAGE_WIDTH / 2.0 - 200.0, PAGE_WIDTH / 2.0 + 200.0));
+    content.push_str(&centred_text("for earning", 16.0, 300.0, (0.3, 0.3, 0.3)));
+    content.push_str(&centred_text(&achievement.name, 28.0, 260.0, (r, g, b)));
+    content.push_str(&centred_text(&achievement.description, 14.0, 228.0, (0.3, 0.3, 0.3)));
+
+    // Seal with a star, bottom centre
+    let seal_x = PAGE_WIDTH / 2.0;
+    content.push_str(&circle_path(seal_x, 130.0, 42.0));
+    content.push_str(&format!("{} {} {} rg f\n", r, g, b));
+    content.push_str(&star_path(seal_x, 130.0, 28.0));
+    content.push_str("1 0.98 0.92 rg f\n");
+
+    content.push_str(&text_at(&format!("Date: {}", locale.format_date(achievement.earned_at.date_naive())), 14.0, 110.0, 100.0, (0.3, 0.3, 0.3)));
+    let brand = "QuizDD";
+    content.push_str(&text_at(brand, 14.0, PAGE_WIDTH - 110.0 - text_width(brand, 14.0), 100.0, (0.3, 0.3, 0.3)));
+
+    pdf::build_pdf(&[content], PAGE_WIDTH, PAGE_HEIGHT)
+}
+
+/// Shrink text that would run past the inner border
+fn fitted_size(text: &str, size: f32) -> f32 {
+    let max_width = PAGE_WIDTH - 160.0;
+    let width = text_width(text, size);
+    if width > max_width {
+        size * max_width / width
+    } else {
+        size
+    }
+}
+
+fn centred_text(text: &str, size: f32, y: f32, colour: (f32, f32, f32)) -> String {
+    let size = fitted_size(text, size);
+    text_at(text, size, (PAGE_WIDTH - text_width(text, size)) / 2.0, y, colour)
+}
+
+fn text_at(text: &str, size: f32, x: f32, y: f32, colour: (f32, f32, f32)) -> String {
+    pdf::text_at(text, Font::Bold, size, x, y, colour)
+}
+
+fn text_width(text: &str, size: f32) -> f32 {
+    pdf::text_width(text, Font::Bold, size)
+}
+
+fn star_path(cx: f32, cy: f32, radius: f32) -> String {
+    let mut path = String::new();
+    for point in 0..10 {
+        let r = if point % 2 == 0 { radius } else { radius * 0.4 };
+        let angle = std::f32::consts::FRAC_PI_2 + point as f32 * std::f32::consts::PI / 5.0;
+        let (x, y) = (cx + r * angle.cos(), cy + r * angle.sin());
+        path.push_str(&format!("{:.2} {:.2} {} ", x, y, if point == 0 { "m" } else { "l" }));
+    }
+    path.push_str("h ");
+    path
+}
+
+fn circle_path(cx: f32, cy: f32, radius: f32) -> String {
+    // Four Bezier quarter arcs
+    let k = radius * 0.5523;
+    format!(
+        "{x0:.2} {cy:.2} m {x0:.2} {a:.2} {b:.2} {y1:.2} {cx:.2} {y1:.2} c \
+         {c:.2} {y1:.2} {x1:.2} {a:.2} {x1:.2} {cy:.2} c \
+         {x1:.2} {d:.2} {c:.2} {y0:.2} {cx:.2} {y0:.2} c \
+         {b:.2} {y0:.2} {x0:.2} {d:.2} {x0:.2} {cy:.2} c h ",
+        x0 = cx - radius, x1 = cx + radius, y0 = cy - radius, y1 = cy + radius,
+        a = cy + k, b = cx - k, c = cx + k, d = cy - k, cx = cx, cy = cy,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::CreateProfileRequest;
+    use crate::services::profile_manager::QuizResult;
+    use crate::services::SecurityService;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    fn test_achievement() -> Achievement {
+        Achievement {
+            id: "first_steps".to_string(),
+            name: "First Steps".to_string(),
+            description: "Answered your first question!".to_string(),
+            icon: "👶".to_string(),
+            earned_at: Utc::now(),
+            category: AchievementCategory::Completion,
+        }
+    }
+
+    #[test]
+    fn test_pdf_structure() {
+        let pdf = String::from_utf8(render_certificate_pdf("Zoë (Year 3)", &test_achievement(), Locale::EnGb)).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(Zo\\353 \\(Year 3\\)) Tj"));
+
+        // startxref must point at the cross-reference table
+        let startxref: usize = pdf.rsplit("startxref\n").next().unwrap()
+            .lines().next().unwrap()
+            .parse().unwrap();
+        assert!(pdf[startxref..].starts_with("xref\n"));
+    }
+
+    #[test]
+    fn test_generate_certificate_for_earned_achievement() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let profile_manager = Arc::new(ProfileManager::new(db_service.manager(), security_service));
+        let formatting = Arc::new(FormattingService::new(db_service.manager()));
+        let generator = CertificateGenerator::new(profile_manager.clone(), formatting, temp_dir.path().join("certificates"));
+
+        let profile_id = profile_manager.create_profile(CreateProfileRequest {
+            name: "Ada".to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        }).unwrap().id.unwrap();
+
+        assert!(generator.generate_certificate(profile_id, "first_steps").is_err());
+
+        let new_achievements = profile_manager.update_progress(profile_id, QuizResult {
+            subject: "Mathematics".to_string(),
+            key_stage: "KS2".to_string(),
+            questions_answered: 1,
+            correct_answers: 1,
+            time_spent_seconds: 20,
+            session_id: None,
+            practice: false,
+        }).unwrap();
+        assert!(new_achievements.iter().any(|achievement| achievement.id == "first_steps"));
+
+        let paths = generator.generate_for_new_achievements(profile_id, &new_achievements);
+        assert_eq!(paths.len(), new_achievements.len());
+
+        let path = generator.generate_certificate(profile_id, "first_steps").unwrap();
+        assert!(fs::read(path).unwrap().starts_with(b"%PDF"));
+    }
+}
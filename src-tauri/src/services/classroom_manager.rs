@@ -0,0 +1,586 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Assignment, AssignmentStatus, ClassGroup, ClassMemberReport, ClassReport, ClassSubjectReport, CreateAssignmentRequest, CreateClassRequest, CreateProfileRequest, KeyStage, ProfileCsvColumns, ProfileImportPreview, ProfileImportRow, ProfileImportRowStatus, ProfileSchoolDetails, RosterImportResult, SkippedRosterRow, accuracy_percentage, key_stage_for_year_group, parse_key_stage, parse_year_group, ProfileId};
+use crate::database::DatabaseManager;
+use crate::services::{AssignmentManager, ProfileManager};
+use crate::services::households::HOUSEHOLD_PROFILES_SQL;
+use std::sync::Arc;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use rusqlite::{params, OptionalExtension};
+use chrono::{DateTime, Utc};
+
+/// Avatar given to imported students when the roster does not name one
+const DEFAULT_ROSTER_AVATAR: &str = "avatar1";
+
+/// Classroom manager for teacher mode: classes, roster import, group assignments and reports
+pub struct ClassroomManager {
+    db_manager: Arc<DatabaseManager>,
+    profile_manager: Arc<ProfileManager>,
+    assignment_manager: Arc<AssignmentManager>,
+}
+
+impl ClassroomManager {
+    /// Create a new classroom manager
+    pub fn new(
+        db_manager: Arc<DatabaseManager>,
+        profile_manager: Arc<ProfileManager>,
+        assignment_manager: Arc<AssignmentManager>,
+    ) -> Self {
+        Self {
+            db_manager,
+            profile_manager,
+            assignment_manager,
+        }
+    }
+
+    /// Create an empty class
+    pub fn create_class(&self, request: CreateClassRequest) -> AppResult<ClassGroup> {
+        request.validate().map_err(AppError::InvalidInput)?;
+
+        let class_id = self.db_manager.execute(|conn| {
+            let existing: Option<u32> = conn.query_row(
+                "SELECT id FROM classes WHERE name = ?1 COLLATE NOCASE",
+                params![request.name.trim()],
+                |row| row.get(0),
+            ).optional()?;
+            if existing.is_some() {
+                return Ok(None);
+            }
+
+            conn.execute(
+                "INSERT INTO classes (name, description, created_at) VALUES (?1, ?2, ?3)",
+                params![request.name.trim(), request.description, Utc::now().to_rfc3339()],
+            )?;
+            Ok(Some(conn.last_insert_rowid() as u32))
+        })?;
+
+        match class_id {
+            Some(id) => self.get_class(id),
+            None => Err(AppError::InvalidInput("Class name already exists".to_string())),
+        }
+    }
+
+    /// Get a class with its members
+    pub fn get_class(&self, class_id: u32) -> AppResult<ClassGroup> {
+        let class = self.db_manager.execute(|conn| {
+            let class = conn.query_row(
+                "SELECT id, name, description, created_at FROM classes WHERE id = ?1",
+                params![class_id],
+                |row| Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?, row.get::<_, String>(3)?)),
+            ).optional()?;
+
+            let (id, name, description, created_at) = match class {
+                Some(class) => class,
+                None => return Ok(None),
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT profile_id FROM class_members WHERE class_id = ?1 ORDER BY profile_id"
+            )?;
+            let member_ids = stmt.query_map(params![id], |row| row.get(0))?
+                .collect::<Result<Vec<ProfileId>, _>>()?;
+
+            Ok(Some(ClassGroup {
+                id: Some(id),
+                name,
+                description,
+                member_ids,
+                created_at: DateTime::parse_from_rfc3339(&created_at)
+                    .map(|timestamp| timestamp.with_timezone(&Utc))
+                    .ok(),
+            }))
+        })?;
+
+        class.ok_or_else(|| AppError::NotFound(format!("Class with id {} not found", class_id)))
+    }
+
+    /// Get all classes, by name
+    pub fn get_classes(&self) -> AppResult<Vec<ClassGroup>> {
+        let class_ids = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT id FROM classes ORDER BY name COLLATE NOCASE")?;
+            let ids = stmt.query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<u32>, _>>()?;
+            Ok(ids)
+        })?;
+
+        class_ids.into_iter().map(|id| self.get_class(id)).collect()
+    }
+
+    /// Delete a class. Student profiles are kept.
+    pub fn delete_class(&self, class_id: u32) -> AppResult<()> {
+        let deleted = self.db_manager.execute(|conn| {
+            conn.execute("DELETE FROM classes WHERE id = ?1", params![class_id])
+        })?;
+
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Class with id {} not found", class_id)));
+        }
+        Ok(())
+    }
+
+    /// Add existing profiles to a class
+    pub fn add_members(&self, class_id: u32, profile_ids: &[ProfileId]) -> AppResult<ClassGroup> {
+        self.get_class(class_id)?;
+        for profile_id in profile_ids {
+            self.profile_manager.get_profile_by_id(*profile_id)?;
+        }
+
+        self.db_manager.transaction(|tx| {
+            for profile_id in profile_ids {
+                tx.execute(
+                    "INSERT OR IGNORE INTO class_members (class_id, profile_id, joined_at) VALUES (?1, ?2, ?3)",
+                    params![class_id, profile_id, Utc::now().to_rfc3339()],
+                )?;
+            }
+            Ok(())
+        })?;
+
+        self.get_class(class_id)
+    }
+
+    /// Remove a profile from a class
+    pub fn remove_member(&self, class_id: u32, profile_id: ProfileId) -> AppResult<ClassGroup> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "DELETE FROM class_members WHERE class_id = ?1 AND profile_id = ?2",
+                params![class_id, profile_id],
+            )?;
+            Ok(())
+        })?;
+
+        self.get_class(class_id)
+    }
+
+    /// Create student profiles from a roster CSV (`name[,avatar]` per row, optional header)
+    /// and add them to the class. Invalid and duplicate rows are skipped, not fatal.
+    pub fn import_roster_csv(&self, class_id: u32, csv: &str) -> AppResult<RosterImportResult> {
+        self.get_class(class_id)?;
+
+        let mut result = RosterImportResult {
+            created: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        for (index, line) in csv.lines().enumerate() {
+            let line_number = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let fields = parse_csv_line(line);
+            let name = fields.first().map(|field| field.trim().to_string()).unwrap_or_default();
+            if line_number == 1 && name.eq_ignore_ascii_case("name") {
+                continue;
+            }
+
+            let avatar = fields.get(1)
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+                .unwrap_or(DEFAULT_ROSTER_AVATAR)
+                .to_string();
+
+            let request = CreateProfileRequest {
+                name: name.clone(),
+                avatar,
+                theme_preference: None,
+            };
+
+            match self.profile_manager.create_profile(request) {
+                Ok(profile) => result.created.push(profile),
+                Err(e) => result.skipped.push(SkippedRosterRow {
+                    line: line_number,
+                    name,
+                    reason: e.to_string(),
+                }),
+            }
+        }
+
+        let created_ids: Vec<ProfileId> = result.created.iter().filter_map(|profile| profile.id).collect();
+        self.add_members(class_id, &created_ids)?;
+
+        Ok(result)
+    }
+
+    /// Check a profile CSV without creating anything. Columns are read from the header
+    /// row unless `columns` is given; files without a header use `name,year group,key stage`.
+    pub fn preview_profiles_csv(&self, path: &Path, columns: Option<ProfileCsvColumns>) -> AppResult<ProfileImportPreview> {
+        let csv = fs::read_to_string(path)?;
+        let mut lines = csv.lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty())
+            .peekable();
+
+        let header = lines.peek().and_then(|(_, line)| ProfileCsvColumns::from_header(&parse_csv_line(line)));
+        let has_header = header.is_some();
+        if has_header {
+            lines.next();
+        }
+        let columns = columns.or(header).unwrap_or_default();
+
+        let mut seen_names: HashSet<String> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT name FROM profiles WHERE id IN ({})", HOUSEHOLD_PROFILES_SQL
+            ))?;
+            let names = stmt.query_map([], |row| row.get::<_, String>(0))?
+                .map(|name| name.map(|name| name.to_lowercase()))
+                .collect::<Result<HashSet<_>, _>>()?;
+            Ok(names)
+        })?;
+
+        let rows = lines.map(|(line, text)| {
+            let fields = parse_csv_line(text);
+            let field = |column: Option<usize>| column
+                .and_then(|column| fields.get(column))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty());
+
+            let name = field(Some(columns.name)).unwrap_or_default().to_string();
+            let year_group = field(columns.year_group).map(parse_year_group).transpose();
+            let key_stage = field(columns.key_stage).map(parse_key_stage).transpose();
+
+            let mut row = ProfileImportRow {
+                line,
+                name,
+                year_group: year_group.as_ref().ok().copied().flatten(),
+                key_stage: key_stage.as_ref().ok().copied().flatten(),
+                status: ProfileImportRowStatus::Ready,
+                reason: None,
+            };
+            let problem = match (year_group, key_stage) {
+                _ if row.name.is_empty() => Some("Profile name cannot be empty".to_string()),
+                _ if row.name.len() > 50 => Some("Profile name too long (max 50 characters)".to_string()),
+                (Err(e), _) | (_, Err(e)) => Some(e),
+                (Ok(Some(year_group)), Ok(Some(key_stage))) if key_stage_for_year_group(year_group) != key_stage => Some(format!(
+                    "Year group {} is not in {}", year_group, key_stage.as_str()
+                )),
+                _ => None,
+            };
+
+            if let Some(problem) = problem {
+                row.status = ProfileImportRowStatus::Invalid;
+                row.reason = Some(problem);
+            } else if !seen_names.insert(row.name.to_lowercase()) {
+                row.status = ProfileImportRowStatus::Duplicate;
+                row.reason = Some("Profile name already exists".to_string());
+            } else if row.key_stage.is_none() {
+                row.key_stage = row.year_group.map(key_stage_for_year_group);
+            }
+            row
+        }).collect();
+
+        Ok(ProfileImportPreview { columns, has_header, rows })
+    }
+
+    /// Create a student profile for every ready row of a profile CSV, recording its year
+    /// group and key stage. Duplicate and invalid rows are skipped.
+    pub fn import_profiles_csv(&self, path: &Path, columns: Option<ProfileCsvColumns>) -> AppResult<RosterImportResult> {
+        let preview = self.preview_profiles_csv(path, columns)?;
+        let mut result = RosterImportResult {
+            created: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        for row in preview.rows {
+            if row.status != ProfileImportRowStatus::Ready {
+                result.skipped.push(SkippedRosterRow {
+                    line: row.line,
+                    name: row.name,
+                    reason: row.reason.unwrap_or_default(),
+                });
+                continue;
+            }
+
+            let request = CreateProfileRequest {
+                name: row.name.clone(),
+                avatar: DEFAULT_ROSTER_AVATAR.to_string(),
+                theme_preference: None,
+            };
+            let profile = match self.profile_manager.create_profile(request) {
+                Ok(profile) => profile,
+                Err(e) => {
+                    result.skipped.push(SkippedRosterRow {
+                        line: row.line,
+                        name: row.name,
+                        reason: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            if let (Some(profile_id), true) = (profile.id, row.year_group.is_some() || row.key_stage.is_some()) {
+                self.db_manager.execute(|conn| conn.execute(
+                    "INSERT OR REPLACE INTO profile_school_details (profile_id, year_group, key_stage) VALUES (?1, ?2, ?3)",
+                    params![profile_id, row.year_group, row.key_stage.map(|key_stage| key_stage.as_str())],
+                ))?;
+            }
+            result.created.push(profile);
+        }
+
+        Ok(result)
+    }
+
+    /// The year group and key stage recorded for a profile, if any
+    pub fn get_school_details(&self, profile_id: ProfileId) -> AppResult<Option<ProfileSchoolDetails>> {
+        let details = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT year_group, key_stage FROM profile_school_details WHERE profile_id = ?1",
+                params![profile_id],
+                |row| Ok((row.get::<_, Option<u8>>(0)?, row.get::<_, Option<String>>(1)?)),
+            ).optional()
+        })?;
+
+        Ok(details.map(|(year_group, key_stage)| ProfileSchoolDetails {
+            profile_id,
+            year_group,
+            key_stage: key_stage.and_then(|key_stage| key_stage.parse::<KeyStage>().ok()),
+        }))
+    }
+
+    /// Set the same assignment for every member of a class
+    pub fn assign_to_class(&self, class_id: u32, template: CreateAssignmentRequest) -> AppResult<Vec<Assignment>> {
+        template.validate().map_err(AppError::InvalidInput)?;
+
+        self.get_class(class_id)?.member_ids
+            .into_iter()
+            .map(|profile_id| self.assignment_manager.create_assignment(CreateAssignmentRequest {
+                profile_id,
+                ..template.clone()
+            }))
+            .collect()
+    }
+
+    /// Aggregate progress and assignment status across a class
+    pub fn get_class_report(&self, class_id: u32) -> AppResult<ClassReport> {
+        let class = self.get_class(class_id)?;
+        let now = Utc::now();
+
+        let rows = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT p.id, p.name, pr.subject, COALESCE(pr.questions_answered, 0), COALESCE(pr.correct_answers, 0)
+                 FROM class_members cm
+                 JOIN profiles p ON p.id = cm.profile_id
+                 LEFT JOIN progress pr ON pr.profile_id = p.id
+                 WHERE cm.class_id = ?1
+                 ORDER BY p.name COLLATE NOCASE"
+            )?;
+            let rows = stmt.query_map(params![class_id], |row| {
+                Ok((
+                    row.get::<_, ProfileId>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, u32>(3)?,
+                    row.get::<_, u32>(4)?,
+                ))
+            })?.collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })?;
+
+        let mut members: Vec<(ProfileId, String, u32, u32)> = Vec::new();
+        let mut subjects: BTreeMap<String, (u32, u32)> = BTreeMap::new();
+
+        for (profile_id, name, subject, answered, correct) in rows {
+            match members.last_mut() {
+                Some(member) if member.0 == profile_id => {
+                    member.2 += answered;
+                    member.3 += correct;
+                }
+                _ => members.push((profile_id, name, answered, correct)),
+            }
+
+            if let Some(subject) = subject {
+                let totals = subjects.entry(subject).or_insert((0, 0));
+                totals.0 += answered;
+                totals.1 += correct;
+            }
+        }
+
+        let mut member_reports = Vec::new();
+        for (profile_id, name, answered, correct) in members {
+            let pending_assignments = self.assignment_manager.get_assignments_for_profile(profile_id)?
+                .iter()
+                .filter(|assignment| matches!(
+                    assignment.status(now),
+                    AssignmentStatus::Pending | AssignmentStatus::Overdue
+                ))
+                .count() as u32;
+
+            member_reports.push(ClassMemberReport {
+                profile_id,
+                name,
+                questions_answered: answered,
+                accuracy: accuracy_percentage(correct, answered),
+                pending_assignments,
+            });
+        }
+
+        let questions_answered = subjects.values().map(|totals| totals.0).sum();
+        let correct_answers = subjects.values().map(|totals| totals.1).sum();
+
+        Ok(ClassReport {
+            class_id,
+            class_name: class.name,
+            member_count: member_reports.len() as u32,
+            questions_answered,
+            correct_answers,
+            average_accuracy: accuracy_percentage(correct_answers, questions_answered),
+            subjects: subjects.into_iter()
+                .map(|(subject, (answered, correct))| ClassSubjectReport {
+                    subject,
+                    questions_answered: answered,
+                    correct_answers: correct,
+                    accuracy: accuracy_percentage(correct, answered),
+                })
+                .collect(),
+            members: member_reports,
+        })
+    }
+}
+
+/// Split one CSV line into fields, honouring double-quoted fields and `""` escapes
+pub fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{AssignmentSource, KeyStage, MixConfig};
+    use crate::services::SecurityService;
+    use chrono::Duration;
+    use tempfile::tempdir;
+
+    fn create_test_classroom_manager() -> (ClassroomManager, Arc<DatabaseManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let profile_manager = Arc::new(ProfileManager::new(db_service.manager(), security_service));
+        let assignment_manager = Arc::new(AssignmentManager::new(db_service.manager()));
+        let manager = ClassroomManager::new(db_service.manager(), profile_manager, assignment_manager);
+
+        (manager, db_service.manager(), temp_dir)
+    }
+
+    #[test]
+    fn test_parse_csv_line() {
+        assert_eq!(parse_csv_line("Ada,avatar2"), vec!["Ada", "avatar2"]);
+        assert_eq!(parse_csv_line("\"Smith, Jo\",\"say \"\"hi\"\"\""), vec!["Smith, Jo", "say \"hi\""]);
+        assert_eq!(parse_csv_line("Solo"), vec!["Solo"]);
+    }
+
+    #[test]
+    fn test_roster_import_skips_bad_rows() {
+        let (manager, _db, _temp_dir) = create_test_classroom_manager();
+        let class = manager.create_class(CreateClassRequest {
+            name: "Year 3 Oak".to_string(),
+            description: None,
+        }).unwrap();
+        let class_id = class.id.unwrap();
+
+        let result = manager.import_roster_csv(class_id, "name,avatar\nAda,avatar2\nBen\n\nAda\n,avatar3\n").unwrap();
+        assert_eq!(result.created.len(), 2);
+        assert_eq!(result.skipped.len(), 2);
+        assert_eq!(result.skipped[0].line, 5);
+        assert_eq!(manager.get_class(class_id).unwrap().member_ids.len(), 2);
+
+        assert!(manager.create_class(CreateClassRequest {
+            name: "year 3 oak".to_string(),
+            description: None,
+        }).is_err());
+    }
+
+    #[test]
+    fn test_profile_csv_preview_and_import() {
+        let (manager, _db, temp_dir) = create_test_classroom_manager();
+        manager.profile_manager.create_profile(CreateProfileRequest {
+            name: "Ada".to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        }).unwrap();
+
+        let path = temp_dir.path().join("roster.csv");
+        fs::write(&path, "Key Stage,Pupil Name,Year\nKS2,Ben,Year 4\n,ada,3\n,Cal,R\nKS1,Dee,5\n,Eve,Year 12\n,ben,4\n,,2\n").unwrap();
+
+        let preview = manager.preview_profiles_csv(&path, None).unwrap();
+        assert!(preview.has_header);
+        assert_eq!(preview.columns, ProfileCsvColumns { name: 1, year_group: Some(2), key_stage: Some(0) });
+        assert_eq!(preview.count(ProfileImportRowStatus::Ready), 2);
+        assert_eq!(preview.count(ProfileImportRowStatus::Duplicate), 2);
+        assert_eq!(preview.count(ProfileImportRowStatus::Invalid), 3);
+        assert_eq!(preview.rows[2].key_stage, Some(KeyStage::EYFS));
+        // Previewing creates nothing
+        assert_eq!(manager.profile_manager.get_all_profiles().unwrap().len(), 1);
+
+        let result = manager.import_profiles_csv(&path, None).unwrap();
+        let created: Vec<&str> = result.created.iter().map(|profile| profile.name.as_str()).collect();
+        assert_eq!(created, vec!["Ben", "Cal"]);
+        assert_eq!(result.skipped.iter().map(|row| row.line).collect::<Vec<_>>(), vec![3, 5, 6, 7, 8]);
+
+        let details = manager.get_school_details(result.created[0].id.unwrap()).unwrap().unwrap();
+        assert_eq!(details.year_group, Some(4));
+        assert_eq!(details.key_stage, Some(KeyStage::KS2));
+
+        // A headerless file uses name, year group, key stage
+        fs::write(&path, "Fin,Year 8\n").unwrap();
+        let preview = manager.preview_profiles_csv(&path, None).unwrap();
+        assert!(!preview.has_header);
+        assert_eq!(preview.rows[0].key_stage, Some(KeyStage::KS3));
+    }
+
+    #[test]
+    fn test_class_assignments_and_report() {
+        let (manager, db, _temp_dir) = create_test_classroom_manager();
+        let class_id = manager.create_class(CreateClassRequest {
+            name: "Maple".to_string(),
+            description: Some("Year 4".to_string()),
+        }).unwrap().id.unwrap();
+        let roster = manager.import_roster_csv(class_id, "Ada\nBen").unwrap();
+        let ada_id = roster.created[0].id.unwrap();
+
+        db.execute(|conn| conn.execute(
+            "UPDATE progress SET questions_answered = 10, correct_answers = 8 WHERE profile_id = ?1 AND subject = 'Mathematics' AND key_stage = 'KS2'",
+            params![ada_id],
+        )).unwrap();
+
+        let assignments = manager.assign_to_class(class_id, CreateAssignmentRequest {
+            profile_id: ProfileId(0),
+            title: "Fractions".to_string(),
+            source: AssignmentSource::Blueprint {
+                config: MixConfig::new(vec!["mathematics".to_string()], vec![KeyStage::KS2], 10),
+            },
+            due_at: Utc::now() + Duration::days(3),
+            minimum_score: 60,
+        }).unwrap();
+        assert_eq!(assignments.len(), 2);
+
+        let report = manager.get_class_report(class_id).unwrap();
+        assert_eq!(report.member_count, 2);
+        assert_eq!(report.questions_answered, 10);
+        assert!((report.average_accuracy - 80.0).abs() < f32::EPSILON);
+        assert!(report.members.iter().all(|member| member.pending_assignments == 1));
+    }
+}
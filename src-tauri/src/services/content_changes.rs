@@ -0,0 +1,301 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{Answer, QuestionContent, QuestionId};
+use crate::services::ContentManager;
+use std::sync::Arc;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Where a change to question content came from. Changes made without one are raised
+/// as content change alerts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentWriteOrigin {
+    /// A parent editing a question
+    ParentEdit,
+    /// A parent undoing a change flagged as suspicious
+    Revert,
+}
+
+impl ContentWriteOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentWriteOrigin::ParentEdit => "parent_edit",
+            ContentWriteOrigin::Revert => "revert",
+        }
+    }
+}
+
+/// Run `f`, crediting any question content it changes to `origin`. Must be called inside
+/// a transaction so no other write sees the origin.
+pub(crate) fn with_write_origin<T, F>(conn: &Connection, origin: ContentWriteOrigin, f: F) -> rusqlite::Result<T>
+where
+    F: FnOnce() -> rusqlite::Result<T>,
+{
+    conn.execute("INSERT INTO content_write_origin (origin) VALUES (?1)", params![origin.as_str()])?;
+    let result = f()?;
+    conn.execute("DELETE FROM content_write_origin", [])?;
+    Ok(result)
+}
+
+/// A question as it was before one change to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionVersion {
+    pub id: u32,
+    pub question_id: QuestionId,
+    pub content: QuestionContent,
+    pub correct_answer: Answer,
+    pub difficulty_level: u8,
+    pub tags: Vec<String>,
+    /// Who made the change that replaced this version, e.g. `parent_edit`;
+    /// `None` when it was made outside the app
+    pub origin: Option<String>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// A change to question content that didn't come from a parent or a pack install
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentChangeAlert {
+    pub id: u32,
+    pub question_id: QuestionId,
+    /// The question as it was before the change, which reverting restores
+    pub previous: QuestionVersion,
+    pub detected_at: DateTime<Utc>,
+    pub reverted_at: Option<DateTime<Utc>>,
+    pub dismissed_at: Option<DateTime<Utc>>,
+}
+
+/// Version history of question content, and the audit log of suspicious changes to it.
+/// The history is kept by a trigger on the questions table, so changes made with other
+/// tools are caught as well as ones made by the app.
+pub struct ContentChangeMonitor {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+}
+
+const ALERT_COLUMNS: &str = "a.id, a.question_id, a.detected_at, a.reverted_at, a.dismissed_at,
+    v.id, v.content, v.correct_answer, v.difficulty_level, v.tags, v.origin, v.replaced_at";
+
+impl ContentChangeMonitor {
+    pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>) -> Self {
+        Self {
+            db_manager,
+            content_manager,
+        }
+    }
+
+    /// Alerts the parent hasn't been told about yet, marked as told. Questions whose content
+    /// changed underneath the app are dropped from the question cache too.
+    pub fn take_unannounced(&self) -> AppResult<Vec<ContentChangeAlert>> {
+        let alerts = self.db_manager.transaction(|tx| {
+            let alerts = Self::query_alerts(tx, "a.announced_at IS NULL", params![])?;
+            if !alerts.is_empty() {
+                tx.execute(
+                    "UPDATE content_change_alerts SET announced_at = ?1 WHERE announced_at IS NULL",
+                    params![Utc::now().to_rfc3339()],
+                )?;
+            }
+            Ok(alerts)
+        })?;
+        if !alerts.is_empty() {
+            self.content_manager.invalidate_question_cache();
+        }
+        Ok(alerts)
+    }
+
+    /// Audit log of suspicious changes, newest first. Reverted and dismissed alerts are
+    /// left out unless `include_resolved` is set.
+    pub fn get_alerts(&self, include_resolved: bool) -> AppResult<Vec<ContentChangeAlert>> {
+        Ok(self.db_manager.execute(|conn| {
+            Self::query_alerts(
+                conn,
+                "?1 OR (a.reverted_at IS NULL AND a.dismissed_at IS NULL)",
+                params![include_resolved],
+            )
+        })?)
+    }
+
+    /// Earlier versions of a question, newest first
+    pub fn get_versions(&self, question_id: QuestionId) -> AppResult<Vec<QuestionVersion>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, content, correct_answer, difficulty_level, tags, origin, replaced_at, question_id
+                 FROM question_versions WHERE question_id = ?1 ORDER BY id DESC"
+            )?;
+            let rows = stmt.query_map(params![question_id], |row| Self::row_to_version(row, 0, 7))?;
+            rows.collect()
+        })?)
+    }
+
+    /// Put a question back as it was before a suspicious change. The change being undone
+    /// stays in the version history.
+    pub fn revert(&self, alert_id: u32) -> AppResult<ContentChangeAlert> {
+        let alert = self.get_alert(alert_id)?;
+        if alert.reverted_at.is_some() {
+            return Err(AppError::InvalidInput("This change has already been reverted".to_string()));
+        }
+        let previous = &alert.previous;
+        let content_json = serde_json::to_string(&previous.content)?;
+        let correct_answer_json = serde_json::to_string(&previous.correct_answer)?;
+        let tags_json = serde_json::to_string(&previous.tags)?;
+
+        self.db_manager.transaction(|tx| {
+            with_write_origin(tx, ContentWriteOrigin::Revert, || {
+                tx.execute(
+                    "UPDATE questions SET content = ?2, correct_answer = ?3, difficulty_level = ?4, tags = ?5 WHERE id = ?1",
+                    params![alert.question_id, content_json, correct_answer_json, previous.difficulty_level, tags_json],
+                )
+            })?;
+            tx.execute(
+                "UPDATE content_change_alerts SET reverted_at = ?2 WHERE id = ?1",
+                params![alert_id, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        self.content_manager.invalidate_question_cache();
+        self.get_alert(alert_id)
+    }
+
+    /// Accept a flagged change as intended, keeping it in the audit log
+    pub fn dismiss(&self, alert_id: u32) -> AppResult<ContentChangeAlert> {
+        self.get_alert(alert_id)?;
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE content_change_alerts SET dismissed_at = ?2 WHERE id = ?1 AND dismissed_at IS NULL",
+                params![alert_id, Utc::now().to_rfc3339()],
+            )
+        })?;
+        self.get_alert(alert_id)
+    }
+
+    fn get_alert(&self, alert_id: u32) -> AppResult<ContentChangeAlert> {
+        self.db_manager.execute(|conn| {
+            Self::query_alerts(conn, "a.id = ?1", params![alert_id]).map(|alerts| alerts.into_iter().next())
+        })?
+        .ok_or_else(|| AppError::NotFound(format!("Content change alert {} not found", alert_id)))
+    }
+
+    fn query_alerts(conn: &Connection, filter: &str, params: &[&dyn rusqlite::ToSql]) -> rusqlite::Result<Vec<ContentChangeAlert>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM content_change_alerts a JOIN question_versions v ON v.id = a.version_id
+             WHERE {} ORDER BY a.detected_at DESC, a.id DESC",
+            ALERT_COLUMNS, filter
+        ))?;
+        let rows = stmt.query_map(params, |row| {
+            let reverted_at: Option<String> = row.get(3)?;
+            let dismissed_at: Option<String> = row.get(4)?;
+            Ok(ContentChangeAlert {
+                id: row.get(0)?,
+                question_id: row.get(1)?,
+                detected_at: parse_timestamp(&row.get::<_, String>(2)?, 2)?,
+                reverted_at: reverted_at.map(|value| parse_timestamp(&value, 3)).transpose()?,
+                dismissed_at: dismissed_at.map(|value| parse_timestamp(&value, 4)).transpose()?,
+                previous: Self::row_to_version(row, 5, 1)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Read a version from the seven columns starting at `first`, with the question id at `question_column`
+    fn row_to_version(row: &rusqlite::Row, first: usize, question_column: usize) -> rusqlite::Result<QuestionVersion> {
+        let content: String = row.get(first + 1)?;
+        let correct_answer: String = row.get(first + 2)?;
+        let tags: Option<String> = row.get(first + 4)?;
+        Ok(QuestionVersion {
+            id: row.get(first)?,
+            question_id: row.get(question_column)?,
+            content: serde_json::from_str(&content)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(first + 1, "content".to_string(), rusqlite::types::Type::Text))?,
+            correct_answer: serde_json::from_str(&correct_answer)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(first + 2, "correct_answer".to_string(), rusqlite::types::Type::Text))?,
+            difficulty_level: row.get::<_, Option<u8>>(first + 3)?.unwrap_or(1),
+            tags: tags.and_then(|tags| serde_json::from_str(&tags).ok()).unwrap_or_default(),
+            origin: row.get(first + 5)?,
+            replaced_at: parse_timestamp(&row.get::<_, String>(first + 6)?, first + 6)?,
+        })
+    }
+}
+
+fn parse_timestamp(value: &str, index: usize) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| rusqlite::Error::InvalidColumnType(index, "timestamp".to_string(), rusqlite::types::Type::Text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{KeyStage, Question, QuestionType};
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_changes_outside_the_app_are_flagged_and_revertible() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let content_manager = Arc::new(ContentManager::new(
+            db_manager.clone(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let question = Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: "What is 2 + 2?".to_string(),
+                options: Some(vec!["3".to_string(), "4".to_string(), "5".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("4".to_string()),
+        );
+        let question_id = content_manager.add_question(question.clone()).unwrap();
+        let monitor = ContentChangeMonitor::new(db_manager.clone(), content_manager.clone());
+
+        // A parent's edit is kept in the history without raising an alert
+        let mut edited = question.clone();
+        edited.content.text = "What is two plus two?".to_string();
+        content_manager.update_question(question_id, edited).unwrap();
+        assert!(monitor.take_unannounced().unwrap().is_empty());
+        let versions = monitor.get_versions(question_id).unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].origin.as_deref(), Some("parent_edit"));
+        assert_eq!(versions[0].content.text, "What is 2 + 2?");
+
+        // Something writing to the database directly is flagged once
+        db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE questions SET correct_answer = '\"5\"' WHERE id = ?1",
+                params![question_id],
+            )
+        }).unwrap();
+        let alerts = monitor.take_unannounced().unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].question_id, question_id);
+        assert!(alerts[0].previous.origin.is_none());
+        assert!(monitor.take_unannounced().unwrap().is_empty());
+        assert_eq!(monitor.get_alerts(false).unwrap().len(), 1);
+
+        let reverted = monitor.revert(alerts[0].id).unwrap();
+        assert!(reverted.reverted_at.is_some());
+        let restored = content_manager.get_question_by_id(question_id).unwrap();
+        assert!(matches!(restored.correct_answer, Answer::Text(ref answer) if answer == "4"));
+        assert_eq!(restored.content.text, "What is two plus two?");
+        assert!(monitor.revert(alerts[0].id).is_err());
+
+        // The revert is recorded too, but isn't itself suspicious
+        assert_eq!(monitor.get_versions(question_id).unwrap()[0].origin.as_deref(), Some("revert"));
+        assert!(monitor.take_unannounced().unwrap().is_empty());
+        assert!(monitor.get_alerts(false).unwrap().is_empty());
+        assert_eq!(monitor.get_alerts(true).unwrap().len(), 1);
+    }
+}
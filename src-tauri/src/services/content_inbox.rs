@@ -0,0 +1,330 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{KeyStage, QuestionSource};
+use crate::services::classroom_manager::parse_csv_line;
+use crate::services::markdown_import::{MarkdownImportError, MarkdownQuestion, MarkdownQuestionFile};
+use crate::services::{ContentManager, MarkdownImporter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Files younger than this may still be being copied in, so they're left for the next scan
+pub const INBOX_SETTLE_TIME: Duration = Duration::from_secs(3);
+
+/// Where imported and rejected files are moved, inside the inbox
+const PROCESSED_DIR: &str = "processed";
+const FAILED_DIR: &str = "failed";
+
+/// Columns a question CSV must have; `options`, `difficulty` and `tags` are optional
+const CSV_REQUIRED_COLUMNS: [&str; 4] = ["subject", "key_stage", "question", "answer"];
+
+/// Kinds of file the inbox imports, by extension
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxFileKind {
+    /// `.json` content pack
+    ContentPack,
+    /// `.md` file in the Markdown question format
+    Markdown,
+    /// `.csv` of questions, one per row
+    Csv,
+}
+
+impl InboxFileKind {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(InboxFileKind::ContentPack),
+            "md" => Some(InboxFileKind::Markdown),
+            "csv" => Some(InboxFileKind::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// What happened to one dropped file. Saved next to the moved file as `<name>.report.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxReport {
+    pub file_name: String,
+    /// `None` for files the inbox doesn't know how to import
+    pub kind: Option<InboxFileKind>,
+    /// False if the file was moved to `failed/`
+    pub succeeded: bool,
+    pub imported_questions: usize,
+    pub duplicates: usize,
+    pub errors: Vec<String>,
+    /// Where the file is now
+    pub moved_to: String,
+    pub processed_at: DateTime<Utc>,
+}
+
+/// The `content/inbox` folder: content packs, Markdown question files and question CSVs
+/// dropped there are validated and imported, then moved to `processed/` or `failed/`
+/// with a report
+pub struct ContentInbox {
+    content_manager: Arc<ContentManager>,
+    markdown_importer: Arc<MarkdownImporter>,
+    inbox_dir: PathBuf,
+}
+
+impl ContentInbox {
+    /// Create an inbox watching `inbox_dir`
+    pub fn new(content_manager: Arc<ContentManager>, markdown_importer: Arc<MarkdownImporter>, inbox_dir: PathBuf) -> Self {
+        Self {
+            content_manager,
+            markdown_importer,
+            inbox_dir,
+        }
+    }
+
+    /// The folder files are dropped into
+    pub fn inbox_dir(&self) -> &Path {
+        &self.inbox_dir
+    }
+
+    /// Import every file that has sat in the inbox for at least `min_age`, returning a
+    /// report per file. Hidden files and folders are ignored.
+    pub fn process_pending(&self, min_age: Duration) -> AppResult<Vec<InboxReport>> {
+        fs::create_dir_all(&self.inbox_dir)?;
+
+        let mut pending = Vec::new();
+        for entry in fs::read_dir(&self.inbox_dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            let age = metadata.modified().ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .unwrap_or_default();
+
+            if metadata.is_file() && !hidden && age >= min_age {
+                pending.push(entry.path());
+            }
+        }
+        pending.sort();
+
+        pending.iter().map(|path| self.process_file(path)).collect()
+    }
+
+    /// Import one file from the inbox and move it out
+    pub fn process_file(&self, path: &Path) -> AppResult<InboxReport> {
+        let file_name = path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| AppError::InvalidInput(format!("'{}' is not a file", path.display())))?;
+        let kind = InboxFileKind::from_path(path);
+
+        let mut report = InboxReport {
+            file_name: file_name.clone(),
+            kind,
+            succeeded: false,
+            imported_questions: 0,
+            duplicates: 0,
+            errors: Vec::new(),
+            moved_to: String::new(),
+            processed_at: Utc::now(),
+        };
+
+        let outcome = match kind {
+            Some(kind) => self.import(path, kind, &mut report),
+            None => Err(AppError::InvalidInput(
+                "Only .json content packs, .md question files and .csv question lists can be imported".to_string()
+            )),
+        };
+        match outcome {
+            // A file where every question was rejected hasn't really been imported
+            Ok(()) => report.succeeded = report.imported_questions > 0 || report.errors.is_empty(),
+            Err(e) => report.errors.insert(0, e.to_string()),
+        }
+
+        let destination_dir = self.inbox_dir.join(if report.succeeded { PROCESSED_DIR } else { FAILED_DIR });
+        fs::create_dir_all(&destination_dir)?;
+        let mut destination = destination_dir.join(&file_name);
+        if destination.exists() {
+            destination = destination_dir.join(format!("{}-{}", report.processed_at.format("%Y%m%d-%H%M%S"), file_name));
+        }
+        fs::rename(path, &destination)?;
+        report.moved_to = destination.to_string_lossy().into_owned();
+
+        let mut report_path = destination.into_os_string();
+        report_path.push(".report.json");
+        fs::write(report_path, serde_json::to_vec_pretty(&report)?)?;
+
+        Ok(report)
+    }
+
+    fn import(&self, path: &Path, kind: InboxFileKind, report: &mut InboxReport) -> AppResult<()> {
+        match kind {
+            InboxFileKind::ContentPack => {
+                let before = self.content_manager.get_content_statistics()?.total_questions;
+                self.content_manager.load_content_pack(path)?;
+                let after = self.content_manager.get_content_statistics()?.total_questions;
+                report.imported_questions = after.saturating_sub(before) as usize;
+            }
+            InboxFileKind::Markdown => {
+                let imported = self.markdown_importer.import_file(path)?;
+                report.imported_questions = imported.imported.len();
+                report.duplicates = imported.duplicates;
+                report.errors.extend(imported.errors.iter().map(describe_error));
+            }
+            InboxFileKind::Csv => {
+                let (files, errors) = parse_question_csv(&fs::read_to_string(path)?)?;
+                report.errors.extend(errors.iter().map(describe_error));
+                for file in &files {
+                    let source = QuestionSource::Import {
+                        file_name: path.file_name().map(|name| name.to_string_lossy().to_string()),
+                    };
+                    match self.markdown_importer.import_parsed(file, Vec::new(), source) {
+                        Ok(imported) => {
+                            report.imported_questions += imported.imported.len();
+                            report.duplicates += imported.duplicates;
+                            report.errors.extend(imported.errors.iter().map(describe_error));
+                        }
+                        Err(e) => report.errors.extend(file.questions.iter().map(|question| {
+                            format!("Row {}: {}", question.line, e)
+                        })),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn describe_error(error: &MarkdownImportError) -> String {
+    format!("Line {}: {}", error.line, error.message)
+}
+
+/// Parse a question CSV into one question file per subject and key stage. Needs a header
+/// row naming the columns; `options`, `answer` (for short answers) and `tags` separate
+/// their values with `|`.
+pub fn parse_question_csv(csv: &str) -> AppResult<(Vec<MarkdownQuestionFile>, Vec<MarkdownImportError>)> {
+    let mut lines = csv.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+    let header: Vec<String> = lines.next()
+        .map(|(_, line)| parse_csv_line(line).iter().map(|column| column.trim().to_lowercase()).collect())
+        .unwrap_or_default();
+    let column = |name: &str| header.iter().position(|column| column == name);
+
+    for required in CSV_REQUIRED_COLUMNS {
+        if column(required).is_none() {
+            return Err(AppError::InvalidInput(format!("The CSV header needs a '{}' column", required)));
+        }
+    }
+
+    let mut files: Vec<MarkdownQuestionFile> = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, line) in lines {
+        let line_number = index + 1;
+        let fields = parse_csv_line(line);
+        let field = |name: &str| column(name)
+            .and_then(|position| fields.get(position))
+            .map(|value| value.trim())
+            .unwrap_or("");
+        let split = |value: &str| -> Vec<String> {
+            value.split('|').map(str::trim).filter(|part| !part.is_empty()).map(str::to_string).collect()
+        };
+
+        let key_stage = match KeyStage::from_str(field("key_stage")) {
+            Ok(key_stage) => key_stage,
+            Err(e) => {
+                errors.push(MarkdownImportError { line: line_number, message: e });
+                continue;
+            }
+        };
+        let difficulty = match field("difficulty") {
+            "" => 1,
+            value => match value.parse::<u8>() {
+                Ok(difficulty @ 1..=5) => difficulty,
+                _ => {
+                    errors.push(MarkdownImportError {
+                        line: line_number,
+                        message: format!("Difficulty must be a number from 1 to 5, not '{}'", value),
+                    });
+                    continue;
+                }
+            },
+        };
+
+        let answers = split(field("answer"));
+        let options: Vec<(String, bool)> = split(field("options")).into_iter()
+            .map(|option| {
+                let correct = answers.first() == Some(&option);
+                (option, correct)
+            })
+            .collect();
+        let question = MarkdownQuestion {
+            line: line_number,
+            text: field("question").to_string(),
+            // With options, the answer only says which one is right
+            answers: if options.is_empty() { answers } else { Vec::new() },
+            options,
+            difficulty,
+            tags: split(field("tags")).into_iter().map(|tag| tag.to_lowercase()).collect(),
+        };
+
+        let subject = field("subject").to_string();
+        match files.iter_mut().find(|file| file.subject == subject && file.key_stage == key_stage) {
+            Some(file) => file.questions.push(question),
+            None => files.push(MarkdownQuestionFile { subject, key_stage, questions: vec![question] }),
+        }
+    }
+
+    Ok((files, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_dropped_files_are_imported_and_moved_with_reports() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let importer = Arc::new(MarkdownImporter::new(content_manager.clone()));
+        let inbox_dir = temp_dir.path().join("content").join("inbox");
+        let inbox = ContentInbox::new(content_manager, importer, inbox_dir.clone());
+
+        fs::create_dir_all(&inbox_dir).unwrap();
+        fs::write(inbox_dir.join("sums.csv"), "\
+subject,key_stage,question,answer,options,difficulty
+mathematics,KS1,What is 2 + 2?,4,3|4|5,1
+mathematics,KS1,Spell the number 3,three,,2
+english,KS9,Which key stage?,none,,1
+").unwrap();
+        fs::write(inbox_dir.join("notes.txt"), "not content").unwrap();
+
+        // Nothing is touched until files have settled
+        assert!(inbox.process_pending(Duration::from_secs(3600)).unwrap().is_empty());
+
+        let reports = inbox.process_pending(Duration::ZERO).unwrap();
+        assert_eq!(reports.len(), 2);
+
+        let notes = &reports[0];
+        assert!(!notes.succeeded);
+        assert_eq!(notes.kind, None);
+        assert!(inbox_dir.join("failed").join("notes.txt").exists());
+        assert!(inbox_dir.join("failed").join("notes.txt.report.json").exists());
+
+        let sums = &reports[1];
+        assert!(sums.succeeded);
+        assert_eq!(sums.kind, Some(InboxFileKind::Csv));
+        assert_eq!(sums.imported_questions, 2);
+        assert_eq!(sums.errors.len(), 1);
+        assert!(sums.errors[0].starts_with("Line 4:"));
+        assert!(inbox_dir.join("processed").join("sums.csv").exists());
+        assert!(!inbox_dir.join("sums.csv").exists());
+
+        // The inbox's own folders aren't picked up again
+        assert!(inbox.process_pending(Duration::ZERO).unwrap().is_empty());
+    }
+}
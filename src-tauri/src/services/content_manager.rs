@@ -1,31 +1,55 @@
 use crate::errors::{AppError, AppResult};
-use crate::models::{Question, Subject, Asset, KeyStage, QuestionType};
+use crate::models::{Question, QuestionSource, Hint, Subject, Asset, AssetType, KeyStage, QuestionType, Answer, EntityId, QuestionId};
 use crate::database::DatabaseManager;
 use crate::services::SecurityService;
+use crate::services::question_cache::{QuestionCache, QuestionCacheStats};
+use crate::services::quiz_engine::QuestionRandomizer;
+use crate::services::households::{record_question_household, OTHER_HOUSEHOLD_QUESTIONS_SQL};
+use crate::services::content_changes::{with_write_origin, ContentWriteOrigin};
 use std::sync::Arc;
 use std::path::{Path, PathBuf};
 use std::fs;
-use rusqlite::{params, Row};
+use rusqlite::{params, Connection, Row};
 use serde_json;
 
+/// How long retired questions are kept before they can be purged
+pub const RETIRED_QUESTION_RETENTION_DAYS: i64 = 90;
+
+/// Tables recording quiz history against a question
+const QUESTION_HISTORY_TABLES: &[&str] = &[
+    "question_attempts",
+    "boss_question_attempts",
+    "answer_drawings",
+    "scratchpad_entries",
+    "question_of_the_day",
+    "question_of_the_day_completions",
+    "answered_questions",
+    "session_events",
+    "question_reviews",
+    "question_flags",
+    "mistakes",
+];
+
 /// Content manager for loading and managing quiz content
 pub struct ContentManager {
     db_manager: Arc<DatabaseManager>,
-    security_service: SecurityService,
+    security_service: Arc<SecurityService>,
     content_directory: PathBuf,
+    question_cache: QuestionCache,
 }
 
 impl ContentManager {
     /// Create a new content manager
     pub fn new(
         db_manager: Arc<DatabaseManager>,
-        security_service: SecurityService,
+        security_service: Arc<SecurityService>,
         content_directory: PathBuf,
     ) -> Self {
         Self {
             db_manager,
             security_service,
             content_directory,
+            question_cache: QuestionCache::new(),
         }
     }
     
@@ -114,44 +138,63 @@ impl ContentManager {
         })?)
     }
     
-    /// Get questions by subject and key stage
+    /// Get questions by subject and key stage, served from the question cache when possible
     pub fn get_questions_by_subject(
         &self,
         subject_name: &str,
         key_stage: Option<KeyStage>,
         difficulty_range: Option<(u8, u8)>,
         limit: Option<usize>,
+    ) -> AppResult<Vec<Question>> {
+        let mut questions = match self.question_cache.get(subject_name, key_stage, difficulty_range) {
+            Some(questions) => questions,
+            None => {
+                let all_questions = self.load_questions_by_subject(subject_name, key_stage)?;
+                self.question_cache.insert(subject_name, key_stage, all_questions);
+                self.question_cache.get(subject_name, key_stage, difficulty_range)
+                    .unwrap_or_default()
+            }
+        };
+
+        QuestionRandomizer::new().shuffle_questions(&mut questions);
+        if let Some(limit_count) = limit {
+            questions.truncate(limit_count);
+        }
+
+        Ok(questions)
+    }
+    
+    /// Drop all cached questions so the next fetch reloads from the database
+    pub fn invalidate_question_cache(&self) {
+        self.question_cache.invalidate();
+    }
+    
+    /// Get question cache statistics for diagnostics
+    pub fn get_question_cache_stats(&self) -> QuestionCacheStats {
+        self.question_cache.stats()
+    }
+    
+    /// Query all questions for a subject and key stage directly from the database
+    fn load_questions_by_subject(
+        &self,
+        subject_name: &str,
+        key_stage: Option<KeyStage>,
     ) -> AppResult<Vec<Question>> {
         Ok(self.db_manager.execute(|conn| {
-            let mut query = "SELECT q.id, q.subject_id, q.key_stage, q.question_type, q.content, q.correct_answer, q.difficulty_level, q.tags, q.created_at
+            let mut query = "SELECT q.id, q.subject_id, q.key_stage, q.question_type, q.content, q.correct_answer, q.difficulty_level, q.tags, q.created_at, qs.details, qe.explanation
                              FROM questions q
                              JOIN subjects s ON q.subject_id = s.id
-                             WHERE s.name = ?1".to_string();
+                             LEFT JOIN question_sources qs ON qs.question_id = q.id
+                             LEFT JOIN question_explanations qe ON qe.question_id = q.id
+                             WHERE s.name = ?1
+                             AND q.id NOT IN (SELECT question_id FROM retired_questions)".to_string();
+            query.push_str(&format!(" AND q.id NOT IN ({})", OTHER_HOUSEHOLD_QUESTIONS_SQL));
             
             let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(subject_name.to_string())];
-            let mut param_index = 2;
             
             if let Some(ks) = key_stage {
-                query.push_str(&format!(" AND q.key_stage = ?{}", param_index));
-                params_vec.push(Box::new(match ks {
-                    KeyStage::KS1 => "KS1".to_string(),
-                    KeyStage::KS2 => "KS2".to_string(),
-                }));
-                param_index += 1;
-            }
-            
-            if let Some((min_diff, max_diff)) = difficulty_range {
-                query.push_str(&format!(" AND q.difficulty_level BETWEEN ?{} AND ?{}", param_index, param_index + 1));
-                params_vec.push(Box::new(min_diff));
-                params_vec.push(Box::new(max_diff));
-                param_index += 2;
-            }
-            
-            query.push_str(" ORDER BY RANDOM()");
-            
-            if let Some(limit_count) = limit {
-                query.push_str(&format!(" LIMIT ?{}", param_index));
-                params_vec.push(Box::new(limit_count as i64));
+                query.push_str(" AND q.key_stage = ?2");
+                params_vec.push(Box::new(ks.as_str().to_string()));
             }
             
             let mut stmt = conn.prepare(&query)?;
@@ -168,7 +211,7 @@ impl ContentManager {
             for question_result in question_iter {
                 let mut q = question_result?;
                 // Load assets for this question
-                q.assets = Some(self.get_question_assets(q.id.unwrap_or(0))?);
+                q.assets = Some(Self::get_question_assets(conn, q.id.unwrap_or(QuestionId(0)))?);
                 questions.push(q);
             }
             
@@ -177,11 +220,14 @@ impl ContentManager {
     }
     
     /// Get a specific question by ID
-    pub fn get_question_by_id(&self, question_id: u32) -> AppResult<Question> {
+    pub fn get_question_by_id(&self, question_id: QuestionId) -> AppResult<Question> {
         self.db_manager.execute(|conn| {
             let mut stmt = conn.prepare(
-                "SELECT id, subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at
-                 FROM questions WHERE id = ?1"
+                "SELECT q.id, q.subject_id, q.key_stage, q.question_type, q.content, q.correct_answer, q.difficulty_level, q.tags, q.created_at, qs.details, qe.explanation
+                 FROM questions q
+                 LEFT JOIN question_sources qs ON qs.question_id = q.id
+                 LEFT JOIN question_explanations qe ON qe.question_id = q.id
+                 WHERE q.id = ?1"
             )?;
             
             let question = stmt.query_row(params![question_id], |row| {
@@ -189,21 +235,21 @@ impl ContentManager {
             })?;
             
             let mut q = question;
-            q.assets = Some(self.get_question_assets(question_id)?);
+            q.assets = Some(Self::get_question_assets(conn, question_id)?);
             
             Ok(q)
         }).map_err(|e| match e {
-            crate::database::DatabaseError::Sqlite(rusqlite::Error::QueryReturnedNoRows) => AppError::NotFound(format!("Question with id {} not found", question_id)),
+            crate::database::DatabaseError::Sqlite(rusqlite::Error::QueryReturnedNoRows) => question_id.not_found(),
             _ => AppError::DatabaseConnection(e),
         })
     }
     
     /// Add a new question to the database
-    pub fn add_question(&self, question: Question) -> AppResult<u32> {
+    pub fn add_question(&self, question: Question) -> AppResult<QuestionId> {
         // Validate question data
         self.validate_question(&question)?;
         
-        Ok(self.db_manager.transaction(|tx| {
+        let question_id = self.db_manager.transaction(|tx| {
             // Insert question
             let content_json = serde_json::to_string(&question.content)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
@@ -211,17 +257,8 @@ impl ContentManager {
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
             let tags_json = serde_json::to_string(&question.tags)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-            let key_stage_str = match question.key_stage {
-                KeyStage::KS1 => "KS1",
-                KeyStage::KS2 => "KS2",
-            };
-            let question_type_str = match question.question_type {
-                QuestionType::MultipleChoice => "multiple_choice",
-                QuestionType::DragDrop => "drag_drop",
-                QuestionType::Hotspot => "hotspot",
-                QuestionType::FillBlank => "fill_blank",
-                QuestionType::StoryQuiz => "story_quiz",
-            };
+            let key_stage_str = question.key_stage.as_str();
+            let question_type_str = question.question_type.as_str();
             
             tx.execute(
                 "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
@@ -238,126 +275,207 @@ impl ContentManager {
                 ],
             )?;
             
-            let question_id = tx.last_insert_rowid() as u32;
+            let question_id = QuestionId(tx.last_insert_rowid() as u32);
             
-            // Insert assets if any
-            if let Some(assets) = &question.assets {
-                for asset in assets {
-                    let asset_type_str = match asset.asset_type {
-                        crate::models::AssetType::Image => "image",
-                        crate::models::AssetType::Audio => "audio",
-                        crate::models::AssetType::Animation => "animation",
-                    };
-                    
-                    tx.execute(
-                        "INSERT INTO assets (question_id, asset_type, file_path, alt_text, file_size, created_at)
-                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                        params![
-                            question_id,
-                            asset_type_str,
-                            asset.file_path,
-                            asset.alt_text,
-                            asset.file_size,
-                            chrono::Utc::now().to_rfc3339()
-                        ],
-                    )?;
-                }
+            // Insert assets, including any option images
+            for asset in &question.content.assets_with_option_images(question.assets.as_ref()) {
+                let asset_type_str = match asset.asset_type {
+                    crate::models::AssetType::Image => "image",
+                    crate::models::AssetType::Audio => "audio",
+                    crate::models::AssetType::Animation => "animation",
+                };
+                
+                tx.execute(
+                    "INSERT INTO assets (question_id, asset_type, file_path, alt_text, file_size, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        question_id,
+                        asset_type_str,
+                        asset.file_path,
+                        asset.alt_text,
+                        asset.file_size,
+                        chrono::Utc::now().to_rfc3339()
+                    ],
+                )?;
             }
             
+            if let Some(source) = &question.source {
+                record_question_source(tx, question_id, source)?;
+            }
+            record_question_explanation(tx, question_id, question.explanation.as_deref(), &question.content)?;
+            
             Ok(question_id)
-        })?)
+        })?;
+        
+        self.question_cache.invalidate();
+        Ok(question_id)
     }
     
     /// Update an existing question
-    pub fn update_question(&self, question_id: u32, question: Question) -> AppResult<()> {
+    pub fn update_question(&self, question_id: QuestionId, question: Question) -> AppResult<()> {
         // Validate question data
         self.validate_question(&question)?;
         
         // Verify question exists
         let _existing = self.get_question_by_id(question_id)?;
         
-        Ok(self.db_manager.transaction(|tx| {
+        self.db_manager.transaction(|tx| {
             let content_json = serde_json::to_string(&question.content)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
             let correct_answer_json = serde_json::to_string(&question.correct_answer)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
             let tags_json = serde_json::to_string(&question.tags)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-            let key_stage_str = match question.key_stage {
-                KeyStage::KS1 => "KS1",
-                KeyStage::KS2 => "KS2",
-            };
-            let question_type_str = match question.question_type {
-                QuestionType::MultipleChoice => "multiple_choice",
-                QuestionType::DragDrop => "drag_drop",
-                QuestionType::Hotspot => "hotspot",
-                QuestionType::FillBlank => "fill_blank",
-                QuestionType::StoryQuiz => "story_quiz",
-            };
+            let key_stage_str = question.key_stage.as_str();
+            let question_type_str = question.question_type.as_str();
             
-            // Update question
-            tx.execute(
-                "UPDATE questions SET subject_id = ?1, key_stage = ?2, question_type = ?3, content = ?4, 
-                 correct_answer = ?5, difficulty_level = ?6, tags = ?7 WHERE id = ?8",
-                params![
-                    question.subject_id,
-                    key_stage_str,
-                    question_type_str,
-                    content_json,
-                    correct_answer_json,
-                    question.difficulty_level,
-                    tags_json,
-                    question_id
-                ],
-            )?;
+            // Update question, keeping what it replaces in the version history
+            with_write_origin(tx, ContentWriteOrigin::ParentEdit, || {
+                tx.execute(
+                    "UPDATE questions SET subject_id = ?1, key_stage = ?2, question_type = ?3, content = ?4, 
+                     correct_answer = ?5, difficulty_level = ?6, tags = ?7 WHERE id = ?8",
+                    params![
+                        question.subject_id,
+                        key_stage_str,
+                        question_type_str,
+                        content_json,
+                        correct_answer_json,
+                        question.difficulty_level,
+                        tags_json,
+                        question_id
+                    ],
+                )
+            })?;
             
             // Delete existing assets
             tx.execute("DELETE FROM assets WHERE question_id = ?1", params![question_id])?;
             
-            // Insert new assets if any
-            if let Some(assets) = &question.assets {
-                for asset in assets {
-                    let asset_type_str = match asset.asset_type {
-                        crate::models::AssetType::Image => "image",
-                        crate::models::AssetType::Audio => "audio",
-                        crate::models::AssetType::Animation => "animation",
-                    };
-                    
-                    tx.execute(
-                        "INSERT INTO assets (question_id, asset_type, file_path, alt_text, file_size, created_at)
-                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                        params![
-                            question_id,
-                            asset_type_str,
-                            asset.file_path,
-                            asset.alt_text,
-                            asset.file_size,
-                            chrono::Utc::now().to_rfc3339()
-                        ],
-                    )?;
-                }
+            // Insert new assets, including any option images
+            for asset in &question.content.assets_with_option_images(question.assets.as_ref()) {
+                let asset_type_str = match asset.asset_type {
+                    crate::models::AssetType::Image => "image",
+                    crate::models::AssetType::Audio => "audio",
+                    crate::models::AssetType::Animation => "animation",
+                };
+                
+                tx.execute(
+                    "INSERT INTO assets (question_id, asset_type, file_path, alt_text, file_size, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        question_id,
+                        asset_type_str,
+                        asset.file_path,
+                        asset.alt_text,
+                        asset.file_size,
+                        chrono::Utc::now().to_rfc3339()
+                    ],
+                )?;
             }
             
+            record_question_explanation(tx, question_id, question.explanation.as_deref(), &question.content)?;
+            
             Ok(())
-        })?)
+        })?;
+        
+        self.question_cache.invalidate();
+        Ok(())
     }
     
-    /// Delete a question
-    pub fn delete_question(&self, question_id: u32) -> AppResult<()> {
+    /// Delete a question. The question is retired rather than removed: it is no longer
+    /// asked, but past sessions and reports can still show it until it is purged.
+    pub fn delete_question(&self, question_id: QuestionId) -> AppResult<()> {
+        self.retire_question(question_id, "Deleted by a parent")
+    }
+    
+    /// Retire a question so it is no longer asked, noting why
+    pub fn retire_question(&self, question_id: QuestionId, reason: &str) -> AppResult<()> {
         // Verify question exists
         let _existing = self.get_question_by_id(question_id)?;
         
-        Ok(self.db_manager.transaction(|tx| {
-            // Delete assets first (foreign key constraint)
-            tx.execute("DELETE FROM assets WHERE question_id = ?1", params![question_id])?;
-            
-            // Delete question
-            tx.execute("DELETE FROM questions WHERE id = ?1", params![question_id])?;
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO retired_questions (question_id, reason, retired_at) VALUES (?1, ?2, ?3)",
+                params![question_id, reason, chrono::Utc::now().to_rfc3339()],
+            )
+        })?;
+        
+        self.question_cache.invalidate();
+        Ok(())
+    }
+    
+    /// Questions that have been retired and are waiting to be purged, newest first
+    pub fn get_retired_questions(&self) -> AppResult<Vec<RetiredQuestion>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT r.question_id, q.content, r.reason, r.retired_at
+                 FROM retired_questions r JOIN questions q ON q.id = r.question_id
+                 ORDER BY r.retired_at DESC, r.question_id"
+            )?;
             
-            Ok(())
+            let rows = stmt.query_map([], |row| {
+                let content: crate::models::QuestionContent = serde_json::from_str(&row.get::<_, String>(1)?)
+                    .map_err(|_| rusqlite::Error::InvalidColumnType(1, "content".to_string(), rusqlite::types::Type::Text))?;
+                let retired_at = chrono::DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                    .map_err(|_| rusqlite::Error::InvalidColumnType(3, "retired_at".to_string(), rusqlite::types::Type::Text))?
+                    .with_timezone(&chrono::Utc);
+                
+                Ok(RetiredQuestion {
+                    question_id: row.get(0)?,
+                    text: content.text,
+                    reason: row.get(2)?,
+                    retired_at,
+                })
+            })?;
+            rows.collect()
         })?)
     }
     
+    /// Put a retired question back into the question bank
+    pub fn restore_question(&self, question_id: QuestionId) -> AppResult<()> {
+        let restored = self.db_manager.execute(|conn| {
+            conn.execute("DELETE FROM retired_questions WHERE question_id = ?1", params![question_id])
+        })?;
+        if restored == 0 {
+            return Err(AppError::NotFound(format!("Question {} is not retired", question_id)));
+        }
+        
+        self.question_cache.invalidate();
+        Ok(())
+    }
+    
+    /// Permanently remove questions retired more than `retention` ago, with their assets
+    /// and any history recorded against them. Returns the ids removed.
+    pub fn purge_retired_questions(&self, retention: chrono::Duration) -> AppResult<Vec<QuestionId>> {
+        let cutoff = (chrono::Utc::now() - retention).to_rfc3339();
+        
+        let question_ids = self.db_manager.transaction(|tx| {
+            let question_ids: Vec<QuestionId> = {
+                let mut stmt = tx.prepare("SELECT question_id FROM retired_questions WHERE retired_at <= ?1")?;
+                let rows = stmt.query_map(params![cutoff], |row| row.get(0))?;
+                rows.collect::<Result<_, _>>()?
+            };
+            
+            for question_id in &question_ids {
+                // Delete assets and provenance first (foreign key constraint)
+                tx.execute("DELETE FROM assets WHERE question_id = ?1", params![question_id])?;
+                tx.execute("DELETE FROM question_sources WHERE question_id = ?1", params![question_id])?;
+                tx.execute("DELETE FROM question_explanations WHERE question_id = ?1", params![question_id])?;
+                tx.execute("DELETE FROM content_change_alerts WHERE question_id = ?1", params![question_id])?;
+                tx.execute("DELETE FROM question_versions WHERE question_id = ?1", params![question_id])?;
+                tx.execute("DELETE FROM retired_questions WHERE question_id = ?1", params![question_id])?;
+                tx.execute("DELETE FROM question_of_the_day_completions WHERE question_id = ?1", params![question_id])?;
+                tx.execute("DELETE FROM questions WHERE id = ?1", params![question_id])?;
+            }
+            
+            Ok(question_ids)
+        })?;
+        
+        if !question_ids.is_empty() {
+            self.db_manager.schedule_compaction();
+        }
+        Ok(question_ids)
+    }
+    
     /// Get content statistics
     pub fn get_content_statistics(&self) -> AppResult<ContentStatistics> {
         Ok(self.db_manager.execute(|conn| {
@@ -407,7 +525,7 @@ impl ContentManager {
     
     /// Install content pack into database
     fn install_content_pack(&self, content_pack: ContentPack) -> AppResult<()> {
-        Ok(self.db_manager.transaction(|tx| {
+        self.db_manager.transaction(|tx| {
             
             // Install subjects first
             for subject in &content_pack.subjects {
@@ -424,6 +542,12 @@ impl ContentManager {
                 )?;
             }
             
+            // Questions keep the provenance they were exported with, otherwise they belong to this pack
+            let pack_source = QuestionSource::ContentPack {
+                pack: content_pack.name.clone(),
+                version: content_pack.version.clone(),
+            };
+            
             // Install questions
             for question in &content_pack.questions {
                 let content_json = serde_json::to_string(&question.content)
@@ -440,18 +564,9 @@ impl ContentManager {
                     |row| row.get(0)
                 )?;
                 
-                let key_stage_str = match question.key_stage {
-                    KeyStage::KS1 => "KS1",
-                    KeyStage::KS2 => "KS2",
-                };
+                let key_stage_str = question.key_stage.as_str();
                 
-                let question_type_str = match question.question_type {
-                    QuestionType::MultipleChoice => "multiple_choice",
-                    QuestionType::DragDrop => "drag_drop",
-                    QuestionType::Hotspot => "hotspot",
-                    QuestionType::FillBlank => "fill_blank",
-                    QuestionType::StoryQuiz => "story_quiz",
-                };
+                let question_type_str = question.question_type.as_str();
                 
                 tx.execute(
                     "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
@@ -468,37 +583,150 @@ impl ContentManager {
                     ],
                 )?;
                 
-                let question_id = tx.last_insert_rowid() as u32;
+                let question_id = QuestionId(tx.last_insert_rowid() as u32);
                 
-                // Install assets
-                if let Some(assets) = &question.assets {
-                    for asset in assets {
-                        let asset_type_str = match asset.asset_type {
-                            crate::models::AssetType::Image => "image",
-                            crate::models::AssetType::Audio => "audio",
-                            crate::models::AssetType::Animation => "animation",
-                        };
-                        
-                        tx.execute(
-                            "INSERT INTO assets (question_id, asset_type, file_path, alt_text, file_size, created_at)
-                             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                            params![
-                                question_id,
-                                asset_type_str,
-                                asset.file_path,
-                                asset.alt_text,
-                                asset.file_size,
-                                chrono::Utc::now().to_rfc3339()
-                            ],
-                        )?;
-                    }
+                // Install assets, including any option images
+                for asset in &question.content.assets_with_option_images(question.assets.as_ref()) {
+                    let asset_type_str = match asset.asset_type {
+                        crate::models::AssetType::Image => "image",
+                        crate::models::AssetType::Audio => "audio",
+                        crate::models::AssetType::Animation => "animation",
+                    };
+                    
+                    tx.execute(
+                        "INSERT INTO assets (question_id, asset_type, file_path, alt_text, file_size, created_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![
+                            question_id,
+                            asset_type_str,
+                            asset.file_path,
+                            asset.alt_text,
+                            asset.file_size,
+                            chrono::Utc::now().to_rfc3339()
+                        ],
+                    )?;
                 }
+                
+                record_question_source(tx, question_id, question.source.as_ref().unwrap_or(&pack_source))?;
+                record_question_explanation(tx, question_id, question.explanation.as_deref(), &question.content)?;
             }
             
             Ok(())
+        })?;
+        
+        self.question_cache.invalidate();
+        Ok(())
+    }
+    
+    /// Every source questions have come from, with how many questions each still has.
+    /// Questions stored before sources were tracked are counted under `unknown`.
+    pub fn get_question_sources(&self) -> AppResult<Vec<QuestionSourceSummary>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT COALESCE(qs.kind, 'unknown'), qs.reference, COUNT(*)
+                 FROM questions q LEFT JOIN question_sources qs ON qs.question_id = q.id
+                 WHERE q.id NOT IN (SELECT question_id FROM retired_questions)
+                 GROUP BY qs.kind, qs.reference
+                 ORDER BY qs.kind, qs.reference"
+            )?;
+            
+            let rows = stmt.query_map([], |row| {
+                Ok(QuestionSourceSummary {
+                    kind: row.get(0)?,
+                    reference: row.get(1)?,
+                    question_count: row.get(2)?,
+                })
+            })?;
+            rows.collect()
         })?)
     }
     
+    /// Remove every question from one source, e.g. everything installed from one content
+    /// pack. Questions that quiz history refers to are retired rather than deleted.
+    pub fn delete_questions_by_source(&self, kind: &str, reference: Option<&str>) -> AppResult<QuestionRemoval> {
+        let reason = match reference {
+            Some(reference) => format!("Removed with {} '{}'", kind, reference),
+            None => format!("Removed with {}", kind),
+        };
+        
+        let removal = self.db_manager.transaction(|tx| {
+            let question_ids: Vec<QuestionId> = {
+                let mut stmt = tx.prepare(
+                    "SELECT question_id FROM question_sources WHERE kind = ?1 AND reference IS ?2
+                     AND question_id NOT IN (SELECT question_id FROM retired_questions)"
+                )?;
+                let rows = stmt.query_map(params![kind, reference], |row| row.get(0))?;
+                rows.collect::<Result<_, _>>()?
+            };
+            
+            remove_questions(tx, &question_ids, &reason)
+        })?;
+        
+        self.question_cache.invalidate();
+        if !removal.deleted.is_empty() {
+            self.db_manager.schedule_compaction();
+        }
+        Ok(removal)
+    }
+    
+    /// Uninstall a content pack, identified by its name, removing its questions and their
+    /// assets. Questions with quiz history are retired so past sessions still make sense.
+    pub fn uninstall_content_pack(&self, pack_id: &str) -> AppResult<QuestionRemoval> {
+        let removal = self.delete_questions_by_source("content_pack", Some(pack_id))?;
+        if removal.deleted.is_empty() && removal.retired.is_empty() {
+            return Err(AppError::NotFound(format!("No installed content pack named '{}'", pack_id)));
+        }
+        Ok(removal)
+    }
+    
+    /// Write questions out as a content pack, each carrying where it came from. Limited to
+    /// one subject when `subject` is given. Returns how many questions were exported.
+    pub fn export_content_pack(&self, name: &str, subject: Option<&str>, pack_path: &Path) -> AppResult<usize> {
+        let subjects: Vec<Subject> = self.get_subjects()?
+            .into_iter()
+            .filter(|candidate| subject.map_or(true, |wanted| candidate.name == wanted))
+            .collect();
+        if let Some(wanted) = subject {
+            if subjects.is_empty() {
+                return Err(AppError::NotFound(format!("Subject '{}' not found", wanted)));
+            }
+        }
+        
+        let mut questions = Vec::new();
+        for subject in &subjects {
+            for question in self.load_questions_by_subject(&subject.name, None)? {
+                questions.push(ContentPackQuestion {
+                    subject_name: subject.name.clone(),
+                    key_stage: question.key_stage,
+                    question_type: question.question_type,
+                    content: question.content,
+                    correct_answer: question.correct_answer,
+                    difficulty_level: question.difficulty_level,
+                    tags: question.tags,
+                    assets: question.assets.filter(|assets| !assets.is_empty()),
+                    source: question.source,
+                    explanation: question.explanation,
+                });
+            }
+        }
+        
+        let content_pack = ContentPack {
+            version: chrono::Utc::now().format("%Y.%m.%d").to_string(),
+            name: name.to_string(),
+            description: None,
+            subjects,
+            questions,
+            signature: None,
+        };
+        
+        if let Some(parent) = pack_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(pack_path, serde_json::to_vec_pretty(&content_pack)?)?;
+        
+        Ok(content_pack.questions.len())
+    }
+    
     /// Convert database row to Question
     fn row_to_question(&self, row: &Row) -> Result<Question, rusqlite::Error> {
         let content_json: String = row.get(4)?;
@@ -515,27 +743,25 @@ impl ContentManager {
         let tags = serde_json::from_str(&tags_json)
             .map_err(|_| rusqlite::Error::InvalidColumnType(7, "tags".to_string(), rusqlite::types::Type::Text))?;
         
-        let key_stage = match row.get::<_, String>(2)?.as_str() {
-            "KS1" => KeyStage::KS1,
-            "KS2" => KeyStage::KS2,
-            _ => return Err(rusqlite::Error::InvalidColumnType(2, "key_stage".to_string(), rusqlite::types::Type::Text)),
-        };
+        let key_stage = row.get::<_, String>(2)?.parse::<KeyStage>()
+            .map_err(|_| rusqlite::Error::InvalidColumnType(2, "key_stage".to_string(), rusqlite::types::Type::Text))?;
         
-        let question_type = match row.get::<_, String>(3)?.as_str() {
-            "multiple_choice" => QuestionType::MultipleChoice,
-            "drag_drop" => QuestionType::DragDrop,
-            "hotspot" => QuestionType::Hotspot,
-            "fill_blank" => QuestionType::FillBlank,
-            "story_quiz" => QuestionType::StoryQuiz,
-            _ => return Err(rusqlite::Error::InvalidColumnType(3, "question_type".to_string(), rusqlite::types::Type::Text)),
-        };
+        let question_type = row.get::<_, String>(3)?.parse::<QuestionType>()
+            .map_err(|_| rusqlite::Error::InvalidColumnType(3, "question_type".to_string(), rusqlite::types::Type::Text))?;
         
         let created_at = chrono::DateTime::parse_from_rfc3339(&created_at_str)
             .map_err(|_| rusqlite::Error::InvalidColumnType(8, "created_at".to_string(), rusqlite::types::Type::Text))?
             .with_timezone(&chrono::Utc);
         
+        let source = row.get::<_, Option<String>>(9)?
+            .map(|details| serde_json::from_str(&details))
+            .transpose()
+            .map_err(|_| rusqlite::Error::InvalidColumnType(9, "source".to_string(), rusqlite::types::Type::Text))?;
+        
+        let explanation = row.get::<_, Option<String>>(10)?;
+        
         Ok(Question {
-            id: Some(row.get::<_, u32>(0)?),
+            id: Some(row.get(0)?),
             subject_id: row.get::<_, u32>(1)?,
             key_stage,
             question_type,
@@ -545,18 +771,44 @@ impl ContentManager {
             tags,
             assets: None, // Will be loaded separately
             created_at: Some(created_at),
+            source,
+            explanation,
         })
     }
     
-    /// Get assets for a question
-    fn get_question_assets(&self, _question_id: u32) -> Result<Vec<Asset>, rusqlite::Error> {
-        // This would be called within a database transaction, so we need to handle it differently
-        // For now, return empty vector - this should be implemented properly in a real system
-        Ok(Vec::new())
+    /// Get assets for a question, including its option images
+    fn get_question_assets(conn: &Connection, question_id: QuestionId) -> Result<Vec<Asset>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT id, question_id, asset_type, file_path, alt_text, file_size, created_at
+             FROM assets WHERE question_id = ?1 ORDER BY id"
+        )?;
+
+        let assets = stmt.query_map(params![question_id], |row| {
+            let asset_type = match row.get::<_, String>(2)?.as_str() {
+                "audio" => AssetType::Audio,
+                "animation" => AssetType::Animation,
+                _ => AssetType::Image,
+            };
+            let created_at: Option<String> = row.get(6)?;
+
+            Ok(Asset {
+                id: row.get(0)?,
+                question_id: row.get(1)?,
+                asset_type,
+                file_path: row.get(3)?,
+                alt_text: row.get(4)?,
+                file_size: row.get(5)?,
+                created_at: created_at
+                    .and_then(|created_at| chrono::DateTime::parse_from_rfc3339(&created_at).ok())
+                    .map(|created_at| created_at.with_timezone(&chrono::Utc)),
+            })
+        })?;
+
+        assets.collect()
     }
     
-    /// Validate question data
-    fn validate_question(&self, question: &Question) -> AppResult<()> {
+    /// Validate question data, as done before a question is saved
+    pub fn validate_question(&self, question: &Question) -> AppResult<()> {
         if question.content.text.trim().is_empty() {
             return Err(AppError::InvalidQuestion("Question text cannot be empty".to_string()));
         }
@@ -565,25 +817,78 @@ impl ContentManager {
             return Err(AppError::InvalidQuestion("Difficulty level must be between 1 and 5".to_string()));
         }
         
+        question.content.phrasings()
+            .map_err(|e| AppError::InvalidQuestion(format!("Invalid phrasings: {}", e)))?;
+        
+        let hints = question.content.hints()
+            .map_err(|e| AppError::InvalidQuestion(format!("Invalid hints: {}", e)))?;
+        for hint in &hints {
+            match hint {
+                Hint::EliminateOptions { count } => {
+                    let wrong_options = question.content.options.as_ref().map_or(0, |options| options.len().saturating_sub(1));
+                    if question.question_type != QuestionType::MultipleChoice || *count == 0 || *count >= wrong_options {
+                        return Err(AppError::InvalidQuestion(
+                            "Option-eliminating hints need a multiple choice question with a wrong option left over".to_string()
+                        ));
+                    }
+                },
+                Hint::FirstLetter => {
+                    if !matches!(question.question_type, QuestionType::FillBlank | QuestionType::ShortAnswer) {
+                        return Err(AppError::InvalidQuestion(
+                            "First-letter hints are only for fill-in-the-blank and short answer questions".to_string()
+                        ));
+                    }
+                },
+                Hint::Text { text } => {
+                    if text.trim().is_empty() {
+                        return Err(AppError::InvalidQuestion("Hints cannot be empty".to_string()));
+                    }
+                },
+            }
+        }
+        
         // Validate question type specific content
         match question.question_type {
             QuestionType::MultipleChoice => {
                 if question.content.options.is_none() || question.content.options.as_ref().unwrap().is_empty() {
                     return Err(AppError::InvalidQuestion("Multiple choice questions must have options".to_string()));
                 }
+                let options = question.content.options.as_ref().unwrap();
+                let option_images = question.content.option_images()
+                    .map_err(|e| AppError::InvalidQuestion(format!("Invalid option images: {}", e)))?;
+                for (option, image) in &option_images {
+                    if !options.contains(option) {
+                        return Err(AppError::InvalidQuestion(format!("Option image given for unknown option '{}'", option)));
+                    }
+                    if image.file_path.trim().is_empty() {
+                        return Err(AppError::InvalidQuestion(format!("Option '{}' has an empty image path", option)));
+                    }
+                }
             },
             QuestionType::Hotspot => {
                 if question.content.image_url.is_none() {
                     return Err(AppError::InvalidQuestion("Hotspot questions must have an image".to_string()));
                 }
-                if question.content.hotspots.is_none() || question.content.hotspots.as_ref().unwrap().is_empty() {
+                let config = question.content.hotspot_config()
+                    .map_err(|e| AppError::InvalidQuestion(format!("Invalid hotspot settings: {}", e)))?;
+                if config.regions.is_empty() && question.content.hotspots.as_ref().map_or(true, |hotspots| hotspots.is_empty()) {
                     return Err(AppError::InvalidQuestion("Hotspot questions must have hotspot coordinates".to_string()));
                 }
+                if let Some(problem) = config.regions.iter().find_map(|region| region.problem()) {
+                    return Err(AppError::InvalidQuestion(problem.to_string()));
+                }
+                if !config.tolerance.is_finite() || config.tolerance < 0.0 {
+                    return Err(AppError::InvalidQuestion("Hotspot tolerance can't be negative".to_string()));
+                }
             },
             QuestionType::FillBlank => {
                 if question.content.blanks.is_none() || question.content.blanks.as_ref().unwrap().is_empty() {
                     return Err(AppError::InvalidQuestion("Fill-in-blank questions must have blank configurations".to_string()));
                 }
+                let blanks = question.content.blanks.as_ref().unwrap();
+                if blanks.iter().enumerate().any(|(i, blank)| blanks[..i].iter().any(|other| other.position == blank.position)) {
+                    return Err(AppError::InvalidQuestion("Each blank in a fill-in-blank question needs its own position".to_string()));
+                }
             },
             QuestionType::StoryQuiz => {
                 if question.content.story.is_none() || question.content.story.as_ref().unwrap().trim().is_empty() {
@@ -593,6 +898,33 @@ impl ContentManager {
             QuestionType::DragDrop => {
                 // Drag drop validation would depend on specific implementation
             },
+            QuestionType::ShortAnswer => {
+                if !matches!(&question.correct_answer, Answer::Text(answer) if !answer.trim().is_empty()) {
+                    return Err(AppError::InvalidQuestion("Short answer questions must have a text answer".to_string()));
+                }
+                question.content.short_answer_config()
+                    .map_err(|e| AppError::InvalidQuestion(format!("Invalid short answer settings: {}", e)))?;
+            },
+            QuestionType::Ordering => {
+                let items = question.content.options.as_deref().unwrap_or(&[]);
+                if items.len() < 2 {
+                    return Err(AppError::InvalidQuestion("Ordering questions need at least two items".to_string()));
+                }
+                if items.iter().enumerate().any(|(i, item)| item.trim().is_empty() || items[..i].contains(item)) {
+                    return Err(AppError::InvalidQuestion("Each item in an ordering question must be different and not empty".to_string()));
+                }
+                let order = match &question.correct_answer {
+                    Answer::Ordering { order } => order,
+                    _ => return Err(AppError::InvalidQuestion("Ordering questions must have the items in order as their answer".to_string())),
+                };
+                let mut expected: Vec<&String> = items.iter().collect();
+                let mut given: Vec<&String> = order.iter().collect();
+                expected.sort();
+                given.sort();
+                if expected != given {
+                    return Err(AppError::InvalidQuestion("The answer must put every item in order exactly once".to_string()));
+                }
+            },
         }
         
         Ok(())
@@ -621,6 +953,119 @@ pub struct ContentPackQuestion {
     pub difficulty_level: u8,
     pub tags: Vec<String>,
     pub assets: Option<Vec<Asset>>,
+    /// Where the question originally came from, kept when packs are exported
+    #[serde(default)]
+    pub source: Option<QuestionSource>,
+    /// Why the answer is right, shown after answering. Older packs put this under
+    /// `explanation` in `content.additional_data`, which is still read.
+    #[serde(default)]
+    pub explanation: Option<String>,
+}
+
+/// Questions taken out of the question bank
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct QuestionRemoval {
+    /// Deleted outright, along with their assets
+    pub deleted: Vec<QuestionId>,
+    /// Kept for the quiz history that refers to them, but no longer asked
+    pub retired: Vec<QuestionId>,
+}
+
+/// A question taken out of use but kept for history
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RetiredQuestion {
+    pub question_id: QuestionId,
+    pub text: String,
+    pub reason: String,
+    pub retired_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// How many questions came from one source
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct QuestionSourceSummary {
+    /// `seeder`, `content_pack`, `parent`, `generator`, `import`, or `unknown` for
+    /// questions stored before sources were tracked
+    pub kind: String,
+    pub reference: Option<String>,
+    pub question_count: u32,
+}
+
+/// Take questions out of the question bank. Questions that quiz history refers to are
+/// retired with `reason`, so deleting them can't cascade into children's progress; the
+/// rest are deleted along with their assets.
+pub(crate) fn remove_questions(conn: &Connection, question_ids: &[QuestionId], reason: &str) -> rusqlite::Result<QuestionRemoval> {
+    let mut removal = QuestionRemoval::default();
+    for &question_id in question_ids {
+        if has_history(conn, question_id)? {
+            conn.execute(
+                "INSERT INTO retired_questions (question_id, reason, retired_at) VALUES (?1, ?2, ?3)",
+                params![question_id, reason, chrono::Utc::now().to_rfc3339()],
+            )?;
+            removal.retired.push(question_id);
+        } else {
+            conn.execute("DELETE FROM assets WHERE question_id = ?1", params![question_id])?;
+            conn.execute("DELETE FROM question_sources WHERE question_id = ?1", params![question_id])?;
+            conn.execute("DELETE FROM question_explanations WHERE question_id = ?1", params![question_id])?;
+            conn.execute("DELETE FROM content_change_alerts WHERE question_id = ?1", params![question_id])?;
+            conn.execute("DELETE FROM question_versions WHERE question_id = ?1", params![question_id])?;
+            conn.execute("DELETE FROM questions WHERE id = ?1", params![question_id])?;
+            removal.deleted.push(question_id);
+        }
+    }
+    Ok(removal)
+}
+
+/// Whether any quiz history refers to a question
+fn has_history(conn: &Connection, question_id: QuestionId) -> rusqlite::Result<bool> {
+    for table in QUESTION_HISTORY_TABLES {
+        let referenced: bool = conn.query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE question_id = ?1)", table),
+            params![question_id],
+            |row| row.get(0),
+        )?;
+        if referenced {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Record where a question came from, replacing anything recorded before
+pub(crate) fn record_question_source(conn: &Connection, question_id: QuestionId, source: &QuestionSource) -> rusqlite::Result<()> {
+    let details = serde_json::to_string(source)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO question_sources (question_id, kind, reference, details, recorded_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![question_id, source.kind(), source.reference(), details, chrono::Utc::now().to_rfc3339()],
+    )?;
+    // What parents write or import is theirs; everything else is shared
+    if matches!(source, QuestionSource::Parent { .. } | QuestionSource::Import { .. }) {
+        record_question_household(conn, question_id)?;
+    }
+    Ok(())
+}
+
+/// Store a question's explanation, or the one under `EXPLANATION_KEY` in its content when
+/// it has none of its own, replacing anything stored before
+pub(crate) fn record_question_explanation(
+    conn: &Connection,
+    question_id: QuestionId,
+    explanation: Option<&str>,
+    content: &crate::models::QuestionContent,
+) -> rusqlite::Result<()> {
+    let explanation = explanation
+        .map(str::trim)
+        .filter(|explanation| !explanation.is_empty())
+        .or_else(|| content.legacy_explanation());
+    match explanation {
+        Some(explanation) => conn.execute(
+            "INSERT OR REPLACE INTO question_explanations (question_id, explanation) VALUES (?1, ?2)",
+            params![question_id, explanation],
+        )?,
+        None => conn.execute("DELETE FROM question_explanations WHERE question_id = ?1", params![question_id])?,
+    };
+    Ok(())
 }
 
 /// Content statistics
@@ -664,7 +1109,7 @@ mod tests {
         let db_service = DatabaseService::new(&db_path).unwrap();
         db_service.initialize().unwrap();
         
-        let security_service = SecurityService::new().unwrap();
+        let security_service = Arc::new(SecurityService::new().unwrap());
         let content_dir = temp_dir.path().join("content");
         fs::create_dir_all(&content_dir).unwrap();
         
@@ -722,9 +1167,291 @@ mod tests {
             tags: Vec::new(),
             assets: None,
             created_at: None,
+            source: None,
+            explanation: None,
         };
         
         let result = content_manager.validate_question(&invalid_question);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_question_cache_hits_and_invalidation() {
+        let (content_manager, _temp_dir) = create_test_content_manager();
+        
+        let question = |text: &str, difficulty_level: u8| Question {
+            id: None,
+            subject_id: 1,
+            key_stage: KeyStage::KS1,
+            question_type: QuestionType::MultipleChoice,
+            content: crate::models::QuestionContent {
+                text: text.to_string(),
+                options: Some(vec!["A".to_string(), "B".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            correct_answer: crate::models::Answer::Text("A".to_string()),
+            difficulty_level,
+            tags: Vec::new(),
+            assets: None,
+            created_at: None,
+            source: None,
+            explanation: None,
+        };
+        let subject_name = content_manager.get_subjects().unwrap()
+            .into_iter()
+            .find(|s| s.id == Some(1))
+            .unwrap()
+            .name;
+        
+        content_manager.add_question(question("Easy question", 1)).unwrap();
+        content_manager.add_question(question("Hard question", 4)).unwrap();
+        
+        let easy = content_manager.get_questions_by_subject(&subject_name, Some(KeyStage::KS1), Some((1, 2)), None).unwrap();
+        assert_eq!(easy.len(), 1);
+        let all = content_manager.get_questions_by_subject(&subject_name, Some(KeyStage::KS1), None, Some(10)).unwrap();
+        assert_eq!(all.len(), 2);
+        
+        let stats = content_manager.get_question_cache_stats();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.cached_buckets, 2);
+        
+        // Adding content invalidates the cache so the new question is visible
+        content_manager.add_question(question("Another easy question", 2)).unwrap();
+        let easy = content_manager.get_questions_by_subject(&subject_name, Some(KeyStage::KS1), Some((1, 2)), None).unwrap();
+        assert_eq!(easy.len(), 2);
+        assert_eq!(content_manager.get_question_cache_stats().misses, 2);
+    }
+
+    #[test]
+    fn test_image_options_are_stored_as_assets() {
+        let (content_manager, _temp_dir) = create_test_content_manager();
+
+        let question = |option_images: serde_json::Value| Question {
+            id: None,
+            subject_id: 1,
+            key_stage: KeyStage::KS1,
+            question_type: QuestionType::MultipleChoice,
+            content: crate::models::QuestionContent {
+                text: "Which shape is a pentagon?".to_string(),
+                options: Some(vec!["pentagon".to_string(), "hexagon".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: Some(std::collections::HashMap::from([
+                    (crate::models::OPTION_IMAGES_KEY.to_string(), option_images),
+                ])),
+            },
+            correct_answer: Answer::Text("pentagon".to_string()),
+            difficulty_level: 1,
+            tags: Vec::new(),
+            assets: None,
+            created_at: None,
+            source: None,
+            explanation: None,
+        };
+
+        let question_id = content_manager.add_question(question(serde_json::json!({
+            "pentagon": { "file_path": "images/pentagon.svg" },
+            "hexagon": { "file_path": "images/hexagon.svg", "alt_text": "Shape B" }
+        }))).unwrap();
+
+        let stored = content_manager.get_question_by_id(question_id).unwrap();
+        let mut assets = stored.assets.unwrap();
+        assets.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].file_path, "images/hexagon.svg");
+        assert_eq!(assets[0].alt_text.as_deref(), Some("Shape B"));
+        assert_eq!(assets[1].alt_text.as_deref(), Some("pentagon"));
+        assert!(assets.iter().all(|asset| asset.asset_type == AssetType::Image && asset.question_id == question_id));
+
+        // Images must belong to one of the options
+        let unknown_option = question(serde_json::json!({ "octagon": { "file_path": "images/octagon.svg" } }));
+        assert!(content_manager.add_question(unknown_option).is_err());
+    }
+
+    #[test]
+    fn test_questions_can_be_traced_and_removed_by_pack() {
+        let (content_manager, temp_dir) = create_test_content_manager();
+
+        let pack_question = |text: &str| ContentPackQuestion {
+            subject_name: "mathematics".to_string(),
+            key_stage: KeyStage::KS2,
+            question_type: QuestionType::MultipleChoice,
+            content: crate::models::QuestionContent {
+                text: text.to_string(),
+                options: Some(vec!["4".to_string(), "5".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            correct_answer: Answer::Text("4".to_string()),
+            difficulty_level: 2,
+            tags: Vec::new(),
+            assets: None,
+            source: None,
+            explanation: None,
+        };
+        let pack = ContentPack {
+            version: "1.2".to_string(),
+            name: "Summer Maths".to_string(),
+            description: None,
+            subjects: Vec::new(),
+            questions: vec![pack_question("What is 2 + 2?"), pack_question("What is 8 - 4?")],
+            signature: None,
+        };
+        let pack_path = temp_dir.path().join("summer.json");
+        fs::write(&pack_path, serde_json::to_vec(&pack).unwrap()).unwrap();
+        content_manager.load_content_pack(&pack_path).unwrap();
+
+        let maths_id = content_manager.get_subjects().unwrap()
+            .into_iter()
+            .find(|subject| subject.name == "mathematics")
+            .and_then(|subject| subject.id)
+            .unwrap();
+        let parent_question = content_manager.add_question(Question::new(
+            maths_id,
+            KeyStage::KS2,
+            QuestionType::MultipleChoice,
+            pack_question("What is 1 + 3?").content,
+            Answer::Text("4".to_string()),
+        ).with_source(QuestionSource::Parent { author: Some("Mum".to_string()) })).unwrap();
+
+        let stored = content_manager.get_questions_by_subject("mathematics", Some(KeyStage::KS2), None, None).unwrap();
+        assert_eq!(stored.iter().filter(|question| question.source == Some(QuestionSource::ContentPack {
+            pack: "Summer Maths".to_string(),
+            version: "1.2".to_string(),
+        })).count(), 2);
+
+        let sources = content_manager.get_question_sources().unwrap();
+        let from_pack = sources.iter()
+            .find(|summary| summary.kind == "content_pack" && summary.reference.as_deref() == Some("Summer Maths"))
+            .unwrap();
+        assert_eq!(from_pack.question_count, 2);
+
+        // Exports carry each question's source
+        let export_path = temp_dir.path().join("export").join("maths.json");
+        assert_eq!(content_manager.export_content_pack("Backup", Some("mathematics"), &export_path).unwrap(), 3);
+        let exported: ContentPack = serde_json::from_slice(&fs::read(&export_path).unwrap()).unwrap();
+        assert!(exported.questions.iter().any(|question| question.source == Some(QuestionSource::Parent {
+            author: Some("Mum".to_string()),
+        })));
+
+        assert_eq!(content_manager.delete_questions_by_source("content_pack", Some("Summer Maths")).unwrap().deleted.len(), 2);
+        let remaining = content_manager.get_questions_by_subject("mathematics", None, None, None).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, Some(parent_question));
+    }
+
+    #[test]
+    fn test_uninstalled_pack_questions_with_history_are_retired() {
+        let (content_manager, temp_dir) = create_test_content_manager();
+
+        let question = |text: &str| ContentPackQuestion {
+            subject_name: "geography".to_string(),
+            key_stage: KeyStage::KS1,
+            question_type: QuestionType::MultipleChoice,
+            content: crate::models::QuestionContent {
+                text: text.to_string(),
+                options: Some(vec!["Yes".to_string(), "No".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            correct_answer: Answer::Text("Yes".to_string()),
+            difficulty_level: 1,
+            tags: Vec::new(),
+            assets: Some(vec![Asset {
+                id: None,
+                question_id: QuestionId(0),
+                asset_type: AssetType::Image,
+                file_path: format!("images/{}.png", text.len()),
+                alt_text: None,
+                file_size: None,
+                created_at: None,
+            }]),
+            source: None,
+            explanation: None,
+        };
+        let pack = ContentPack {
+            version: "1.0".to_string(),
+            name: "Rivers".to_string(),
+            description: None,
+            subjects: Vec::new(),
+            questions: vec![question("Is the Thames a river?"), question("Is the Severn long?")],
+            signature: None,
+        };
+        let pack_path = temp_dir.path().join("rivers.json");
+        fs::write(&pack_path, serde_json::to_vec(&pack).unwrap()).unwrap();
+        content_manager.load_content_pack(&pack_path).unwrap();
+
+        let installed = content_manager.get_questions_by_subject("geography", None, None, None).unwrap();
+        let answered = installed.iter().find(|q| q.content.text == "Is the Thames a river?").unwrap().id.unwrap();
+        content_manager.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO question_of_the_day (qotd_date, key_stage, question_id) VALUES ('2024-01-01', 'KS1', ?1)",
+                params![answered],
+            )
+        }).unwrap();
+
+        let removal = content_manager.uninstall_content_pack("Rivers").unwrap();
+        assert_eq!(removal.retired, vec![answered]);
+        assert_eq!(removal.deleted.len(), 1);
+        assert!(content_manager.get_questions_by_subject("geography", None, None, None).unwrap().is_empty());
+        // History can still look the retired question up, assets and all
+        assert_eq!(content_manager.get_question_by_id(answered).unwrap().assets.unwrap().len(), 1);
+        assert!(content_manager.get_question_by_id(removal.deleted[0]).is_err());
+        assert!(content_manager.uninstall_content_pack("Rivers").is_err());
+    }
+
+    #[test]
+    fn test_deleted_questions_are_retired_until_purged() {
+        let (content_manager, _temp_dir) = create_test_content_manager();
+
+        let question_id = content_manager.add_question(Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::MultipleChoice,
+            crate::models::QuestionContent {
+                text: "Which is bigger, 3 or 7?".to_string(),
+                options: Some(vec!["3".to_string(), "7".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("7".to_string()),
+        )).unwrap();
+        let subject_name = content_manager.get_subjects().unwrap()
+            .into_iter()
+            .find(|s| s.id == Some(1))
+            .unwrap()
+            .name;
+
+        content_manager.delete_question(question_id).unwrap();
+        assert!(content_manager.get_questions_by_subject(&subject_name, None, None, None).unwrap().is_empty());
+        assert!(content_manager.get_question_by_id(question_id).is_ok());
+        let retired = content_manager.get_retired_questions().unwrap();
+        assert_eq!(retired.len(), 1);
+        assert_eq!(retired[0].text, "Which is bigger, 3 or 7?");
+
+        content_manager.restore_question(question_id).unwrap();
+        assert_eq!(content_manager.get_questions_by_subject(&subject_name, None, None, None).unwrap().len(), 1);
+
+        // Only questions retired longer ago than the retention period are purged
+        content_manager.delete_question(question_id).unwrap();
+        assert!(content_manager.purge_retired_questions(chrono::Duration::days(RETIRED_QUESTION_RETENTION_DAYS)).unwrap().is_empty());
+        assert_eq!(content_manager.purge_retired_questions(chrono::Duration::zero()).unwrap(), vec![question_id]);
+        assert!(content_manager.get_question_by_id(question_id).is_err());
+    }
 }
\ No newline at end of file
@@ -1,53 +1,212 @@
-use crate::errors::AppResult;
-use crate::models::{Question, QuestionContent, Answer, KeyStage, QuestionType, AssetType, BlankConfig};
+use crate::errors::{AppError, AppResult};
+use crate::models::{Question, QuestionContent, QuestionSource, Answer, KeyStage, QuestionType, AssetType, BlankConfig, OPTION_IMAGES_KEY, QuestionId};
 use crate::database::DatabaseManager;
+use crate::services::distractors::{options_with_distractors, ArithmeticProblem, Operation};
+use crate::services::{ContentManager, StorageManager};
+use crate::services::content_manager::remove_questions;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::collections::HashMap;
 use serde_json;
 
+/// Recorded as the source of every built-in question, so seeded content can be told apart
+/// from content added later
+const SEEDER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Questions inserted between progress reports
+const PROGRESS_INTERVAL: usize = 25;
+
+/// A single content seeding step: label, subject name, and the function building its questions
+type SeedStep = (&'static str, &'static str, fn(&ContentSeeder, u32) -> AppResult<Vec<Question>>);
+
+/// Ordered list of steps run by `seed_all_content`
+const SEED_STEPS: &[SeedStep] = &[
+    ("Mathematics", "mathematics", ContentSeeder::mathematics_questions),
+    ("Geography", "geography", ContentSeeder::geography_questions),
+    ("English", "english", ContentSeeder::english_questions),
+    ("Science", "science", ContentSeeder::science_questions),
+    ("General Knowledge", "general_knowledge", ContentSeeder::general_knowledge_questions),
+    ("Times Tables", "times_tables", ContentSeeder::times_tables_questions),
+    ("Flags & Capitals", "flags_capitals", ContentSeeder::flags_capitals_questions),
+    // Additional interactive content
+    ("Interactive Mathematics", "mathematics", ContentSeeder::interactive_mathematics_questions),
+    ("Interactive Geography", "geography", ContentSeeder::interactive_geography_questions),
+    ("Interactive English", "english", ContentSeeder::interactive_english_questions),
+    ("Interactive Science", "science", ContentSeeder::interactive_science_questions),
+    // Early Years (Reception) and Key Stage 3 starter content
+    ("Early Years Mathematics", "mathematics", ContentSeeder::early_years_mathematics_questions),
+    ("Early Years English", "english", ContentSeeder::early_years_english_questions),
+    ("KS3 Mathematics", "mathematics", ContentSeeder::ks3_mathematics_questions),
+    ("KS3 Science", "science", ContentSeeder::ks3_science_questions),
+];
+
+/// Steps seeding a single key stage, rerun for databases seeded before that key stage existed
+const KEY_STAGE_SEED_STEPS: &[(KeyStage, &[SeedStep])] = &[
+    (KeyStage::EYFS, &[
+        ("Early Years Mathematics", "mathematics", ContentSeeder::early_years_mathematics_questions),
+        ("Early Years English", "english", ContentSeeder::early_years_english_questions),
+    ]),
+    (KeyStage::KS3, &[
+        ("KS3 Mathematics", "mathematics", ContentSeeder::ks3_mathematics_questions),
+        ("KS3 Science", "science", ContentSeeder::ks3_science_questions),
+    ]),
+];
+
+/// Progress of a seeding run, reported at each step and every few questions within it
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SeedingProgress {
+    pub current_step: String,
+    /// Name of the subject the current step adds questions to
+    pub subject: Option<String>,
+    pub completed_steps: usize,
+    pub total_steps: usize,
+    pub questions_inserted: usize,
+    pub questions_total: usize,
+    pub finished: bool,
+    pub cancelled: bool,
+    pub error: Option<String>,
+}
+
+/// What a content reset did
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ContentReset {
+    /// Copy of the database taken before anything was removed
+    pub backup_path: PathBuf,
+    pub questions_removed: usize,
+    /// Questions quiz history refers to, retired rather than deleted so children's
+    /// progress survives the reset
+    pub questions_retired: usize,
+    /// Questions parents wrote or imported, which a reset leaves alone
+    pub questions_kept: usize,
+    pub questions_seeded: usize,
+}
+
 /// Content seeder for populating the database with initial educational content
 pub struct ContentSeeder {
     db_manager: Arc<DatabaseManager>,
+    content_manager: Option<Arc<ContentManager>>,
+    cancel_requested: AtomicBool,
 }
 
 impl ContentSeeder {
     /// Create a new content seeder
     pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
-        Self { db_manager }
+        Self {
+            db_manager,
+            content_manager: None,
+            cancel_requested: AtomicBool::new(false),
+        }
+    }
+
+    /// Invalidate `content_manager`'s question cache whenever seeding adds or removes
+    /// questions, so quizzes never serve deleted questions or miss new ones
+    pub fn with_content_manager(mut self, content_manager: Arc<ContentManager>) -> Self {
+        self.content_manager = Some(content_manager);
+        self
+    }
+
+    /// Ask a seeding run in progress to stop. Questions it has already inserted are removed.
+    pub fn cancel_seeding(&self) {
+        self.cancel_requested.store(true, Ordering::SeqCst);
     }
 
     /// Seed all educational content
     pub fn seed_all_content(&self) -> AppResult<()> {
+        self.seed_all_content_with_progress(|_| {})
+    }
+
+    /// Seed all educational content, reporting progress as questions are inserted
+    pub fn seed_all_content_with_progress<F>(&self, on_progress: F) -> AppResult<()>
+    where
+        F: FnMut(&SeedingProgress),
+    {
         println!("Starting comprehensive content seeding...");
+        self.run_seed_steps(&SEED_STEPS.iter().collect::<Vec<_>>(), on_progress)
+    }
 
-        // Get subject IDs
-        let subjects = self.get_subjects()?;
-        let mut subject_map = HashMap::new();
-        for subject in subjects {
-            subject_map.insert(subject.name.clone(), subject.id.unwrap());
+    /// Seed only the given subjects, by name, reporting progress as questions are inserted
+    pub fn seed_subjects_with_progress<F>(&self, subjects: &[String], on_progress: F) -> AppResult<()>
+    where
+        F: FnMut(&SeedingProgress),
+    {
+        println!("Seeding content for {}...", subjects.join(", "));
+        let steps: Vec<&SeedStep> = SEED_STEPS.iter()
+            .filter(|(_, subject_name, _)| subjects.iter().any(|subject| subject == subject_name))
+            .collect();
+        self.run_seed_steps(&steps, on_progress)
+    }
+
+    /// Names of the subjects that have built-in content
+    pub fn seedable_subjects() -> Vec<&'static str> {
+        let mut subjects: Vec<&'static str> = Vec::new();
+        for (_, subject_name, _) in SEED_STEPS {
+            if !subjects.contains(subject_name) {
+                subjects.push(subject_name);
+            }
+        }
+        subjects
+    }
+
+    fn run_seed_steps<F>(&self, steps: &[&SeedStep], mut on_progress: F) -> AppResult<()>
+    where
+        F: FnMut(&SeedingProgress),
+    {
+        self.cancel_requested.store(false, Ordering::SeqCst);
+        let subject_map = self.subject_ids()?;
+
+        // Build every step's questions first, so progress can be reported against the total
+        let mut prepared = Vec::with_capacity(steps.len());
+        for (label, subject_name, build_questions) in steps.iter() {
+            prepared.push((*label, *subject_name, build_questions(self, subject_map[*subject_name])?));
+        }
+        let total_steps = prepared.len();
+        let questions_total: usize = prepared.iter().map(|(_, _, questions)| questions.len()).sum();
+
+        let mut inserted_ids = Vec::with_capacity(questions_total);
+        for (index, (label, subject_name, questions)) in prepared.into_iter().enumerate() {
+            for (position, question) in questions.into_iter().enumerate() {
+                if position % PROGRESS_INTERVAL == 0 {
+                    on_progress(&SeedingProgress {
+                        current_step: label.to_string(),
+                        subject: Some(subject_name.to_string()),
+                        completed_steps: index,
+                        total_steps,
+                        questions_inserted: inserted_ids.len(),
+                        questions_total,
+                        finished: false,
+                        cancelled: false,
+                        error: None,
+                    });
+                }
+
+                if self.cancel_requested.swap(false, Ordering::SeqCst) {
+                    println!("Content seeding cancelled, removing {} inserted questions", inserted_ids.len());
+                    self.remove_questions(&inserted_ids)?;
+                    return Err(AppError::Cancelled("Content seeding was cancelled".to_string()));
+                }
+                inserted_ids.push(self.add_question(question)?);
+            }
         }
 
-        // Seed content for each subject
-        self.seed_mathematics_content(subject_map["mathematics"])?;
-        self.seed_geography_content(subject_map["geography"])?;
-        self.seed_english_content(subject_map["english"])?;
-        self.seed_science_content(subject_map["science"])?;
-        self.seed_general_knowledge_content(subject_map["general_knowledge"])?;
-        self.seed_times_tables_content(subject_map["times_tables"])?;
-        self.seed_flags_capitals_content(subject_map["flags_capitals"])?;
-
-        // Seed additional interactive content
-        self.seed_interactive_mathematics_content(subject_map["mathematics"])?;
-        self.seed_interactive_geography_content(subject_map["geography"])?;
-        self.seed_interactive_english_content(subject_map["english"])?;
-        self.seed_interactive_science_content(subject_map["science"])?;
+        on_progress(&SeedingProgress {
+            current_step: "Complete".to_string(),
+            subject: None,
+            completed_steps: total_steps,
+            total_steps,
+            questions_inserted: inserted_ids.len(),
+            questions_total,
+            finished: true,
+            cancelled: false,
+            error: None,
+        });
 
         println!("Content seeding completed successfully!");
         Ok(())
     }
 
-    /// Seed Mathematics content (KS1 & KS2 timetables, arithmetic, shapes) - EXPANDED
-    fn seed_mathematics_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build Mathematics content (KS1 & KS2 timetables, arithmetic, shapes) - EXPANDED
+    fn mathematics_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding Mathematics content...");
 
         let questions = vec![
@@ -66,7 +225,8 @@ impl ContentSeeder {
                     additional_data: None,
                 },
                 Answer::Text("1".to_string()),
-            ).with_difficulty(1).with_tags(vec!["addition".to_string(), "basic_arithmetic".to_string()]),
+            ).with_difficulty(1).with_tags(vec!["addition".to_string(), "basic_arithmetic".to_string()])
+            .with_explanation("Adding zero doesn't change a number, so 1 + 0 is still 1."),
 
             Question::new(
                 subject_id,
@@ -82,7 +242,8 @@ impl ContentSeeder {
                     additional_data: None,
                 },
                 Answer::Text("5".to_string()),
-            ).with_difficulty(1).with_tags(vec!["addition".to_string(), "basic_arithmetic".to_string()]),
+            ).with_difficulty(1).with_tags(vec!["addition".to_string(), "basic_arithmetic".to_string()])
+            .with_explanation("Start at 2 and count on 3 more: 3, 4, 5."),
 
             Question::new(
                 subject_id,
@@ -132,7 +293,8 @@ impl ContentSeeder {
                     additional_data: None,
                 },
                 Answer::Text("3".to_string()),
-            ).with_difficulty(1).with_tags(vec!["shapes".to_string(), "geometry".to_string()]),
+            ).with_difficulty(1).with_tags(vec!["shapes".to_string(), "geometry".to_string()])
+            .with_explanation("Tri means three: a triangle has three straight sides."),
 
             Question::new(
                 subject_id,
@@ -148,7 +310,8 @@ impl ContentSeeder {
                     additional_data: None,
                 },
                 Answer::Text("4".to_string()),
-            ).with_difficulty(1).with_tags(vec!["shapes".to_string(), "geometry".to_string()]),
+            ).with_difficulty(1).with_tags(vec!["shapes".to_string(), "geometry".to_string()])
+            .with_explanation("A square has four straight sides, all the same length."),
 
             // More KS1 Addition Questions
             Question::new(
@@ -2506,15 +2669,11 @@ impl ContentSeeder {
             ).with_difficulty(4).with_tags(vec!["multiplication".to_string(), "times_tables".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed Geography content (world flags, maps, capital cities)
-    fn seed_geography_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build Geography content (world flags, maps, capital cities)
+    fn geography_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding Geography content...");
 
         let questions = vec![
@@ -3273,15 +3432,11 @@ impl ContentSeeder {
             ).with_difficulty(3).with_tags(vec!["latitude".to_string(), "geography_terms".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed English content (spelling, vocabulary, grammar)
-    fn seed_english_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build English content (spelling, vocabulary, grammar)
+    fn english_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding English content...");
 
         let questions = vec![
@@ -3367,6 +3522,7 @@ impl ContentSeeder {
                         expected_answer: "sun".to_string(),
                         case_sensitive: false,
                         accept_alternatives: Some(vec!["Sun".to_string()]),
+                        max_edit_distance: None,
                     }]),
                     additional_data: None,
                 },
@@ -3514,6 +3670,7 @@ impl ContentSeeder {
                         expected_answer: "read".to_string(),
                         case_sensitive: false,
                         accept_alternatives: Some(vec!["Read".to_string()]),
+                        max_edit_distance: None,
                     }]),
                     additional_data: None,
                 },
@@ -3596,15 +3753,11 @@ impl ContentSeeder {
             ).with_difficulty(3).with_tags(vec!["reading_comprehension".to_string(), "emotions".to_string(), "story_quiz".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed Science content (plants, animals, human body topics)
-    fn seed_science_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build Science content (plants, animals, human body topics)
+    fn science_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding Science content...");
 
         let questions = vec![
@@ -4365,15 +4518,11 @@ impl ContentSeeder {
             ).with_difficulty(3).with_tags(vec!["soil".to_string(), "rocks".to_string(), "earth".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed General Knowledge content (history, culture, interesting facts)
-    fn seed_general_knowledge_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build General Knowledge content (history, culture, interesting facts)
+    fn general_knowledge_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding General Knowledge content...");
 
         let questions = vec![
@@ -5095,15 +5244,11 @@ impl ContentSeeder {
             ).with_difficulty(3).with_tags(vec!["geography".to_string(), "languages".to_string(), "south_america".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed Times Tables content - Complete 144 questions (1x1 to 12x12)
-    fn seed_times_tables_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build Times Tables content - Complete 144 questions (1x1 to 12x12)
+    fn times_tables_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding Times Tables content - Generating 144 questions (1x1 to 12x12)...");
 
         let mut questions = Vec::new();
@@ -5113,39 +5258,6 @@ impl ContentSeeder {
             for b in 1..=12 {
                 let result = a * b;
                 
-                // Generate distractors (wrong answers)
-                let mut distractors = vec![];
-                if result > 1 { distractors.push(result - 1); }
-                if result > 2 { distractors.push(result - 2); }
-                distractors.push(result + 1);
-                distractors.push(result + 2);
-                if a > 1 { distractors.push((a - 1) * b); }
-                if b > 1 { distractors.push(a * (b - 1)); }
-                
-                // Remove duplicates and correct answer
-                distractors.retain(|&x| x != result && x > 0);
-                distractors.sort();
-                distractors.dedup();
-                
-                // Select 3 distractors
-                let selected_distractors: Vec<String> = if distractors.len() >= 3 {
-                    vec![
-                        distractors[0].to_string(),
-                        distractors[distractors.len() / 2].to_string(),
-                        distractors[distractors.len() - 1].to_string(),
-                    ]
-                } else {
-                    let mut d = distractors.iter().map(|x| x.to_string()).collect::<Vec<_>>();
-                    while d.len() < 3 {
-                        d.push((result + d.len() as i32 + 3).to_string());
-                    }
-                    d
-                };
-                
-                // Create options with correct answer
-                let mut options = selected_distractors;
-                options.push(result.to_string());
-                
                 // Determine difficulty and key stage
                 let (difficulty, key_stage) = if a <= 2 || b <= 2 || a == 10 || b == 10 {
                     (1, KeyStage::KS1)
@@ -5157,6 +5269,11 @@ impl ContentSeeder {
                     (4, KeyStage::KS2)
                 };
                 
+                // Wrong options are mistakes a child might make, harder ones for harder facts
+                let problem = ArithmeticProblem::new(a, Operation::Multiply, b);
+                let options = options_with_distractors(&problem, difficulty, 3)
+                    .unwrap_or_else(|| vec![result.to_string()]);
+                
                 let question = Question::new(
                     subject_id,
                     key_stage,
@@ -5184,15 +5301,11 @@ impl ContentSeeder {
         println!("Generated {} times tables questions", questions.len());
         
         // Add all generated questions to database
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed Flags & Capitals content - Comprehensive world geography
-    fn seed_flags_capitals_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build Flags & Capitals content - Comprehensive world geography
+    fn flags_capitals_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding Flags & Capitals content...");
 
         let questions = vec![
@@ -6486,15 +6599,11 @@ impl ContentSeeder {
             ).with_difficulty(5).with_tags(vec!["capitals".to_string(), "africa".to_string(), "south_africa".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed interactive Mathematics content with different question types
-    fn seed_interactive_mathematics_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build interactive Mathematics content with different question types
+    fn interactive_mathematics_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding interactive Mathematics content...");
 
         let questions = vec![
@@ -6520,6 +6629,36 @@ impl ContentSeeder {
                 Answer::Text("1, 2, 3, 4, 5".to_string()),
             ).with_difficulty(2).with_tags(vec!["ordering".to_string(), "numbers".to_string()]),
 
+            // Image choice - Picking a shape from pictures
+            Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: "Which shape is a triangle?".to_string(),
+                    options: Some(vec![
+                        "circle".to_string(),
+                        "square".to_string(),
+                        "triangle".to_string(),
+                        "rectangle".to_string()
+                    ]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: Some(HashMap::from([(
+                        OPTION_IMAGES_KEY.to_string(),
+                        serde_json::json!({
+                            "circle": { "file_path": "assets/images/mathematics/circle.svg", "alt_text": "Shape A" },
+                            "square": { "file_path": "assets/images/mathematics/square.svg", "alt_text": "Shape B" },
+                            "triangle": { "file_path": "assets/images/mathematics/triangle.svg", "alt_text": "Shape C" },
+                            "rectangle": { "file_path": "assets/images/mathematics/rectangle.svg", "alt_text": "Shape D" }
+                        }),
+                    )])),
+                },
+                Answer::Text("triangle".to_string()),
+            ).with_difficulty(1).with_tags(vec!["shapes".to_string(), "geometry".to_string()]),
+
             // Hotspot - Identifying shapes
             Question::new(
                 subject_id,
@@ -6561,6 +6700,7 @@ impl ContentSeeder {
                         expected_answer: "6".to_string(),
                         case_sensitive: false,
                         accept_alternatives: Some(vec!["six".to_string()]),
+                        max_edit_distance: None,
                     }]),
                     additional_data: None,
                 },
@@ -6601,15 +6741,11 @@ impl ContentSeeder {
             ).with_difficulty(4).with_tags(vec!["fractions".to_string(), "decimals".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed interactive Geography content
-    fn seed_interactive_geography_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build interactive Geography content
+    fn interactive_geography_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding interactive Geography content...");
 
         let questions = vec![
@@ -6672,6 +6808,7 @@ impl ContentSeeder {
                         expected_answer: "Madrid".to_string(),
                         case_sensitive: false,
                         accept_alternatives: None,
+                        max_edit_distance: None,
                     }]),
                     additional_data: None,
                 },
@@ -6679,15 +6816,11 @@ impl ContentSeeder {
             ).with_difficulty(3).with_tags(vec!["capitals".to_string(), "europe".to_string(), "fill_blank".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed interactive English content
-    fn seed_interactive_english_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build interactive English content
+    fn interactive_english_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding interactive English content...");
 
         let questions = vec![
@@ -6762,6 +6895,7 @@ impl ContentSeeder {
                         expected_answer: "were".to_string(),
                         case_sensitive: false,
                         accept_alternatives: None,
+                        max_edit_distance: None,
                     }]),
                     additional_data: None,
                 },
@@ -6784,6 +6918,7 @@ impl ContentSeeder {
                         expected_answer: "had".to_string(),
                         case_sensitive: false,
                         accept_alternatives: None,
+                        max_edit_distance: None,
                     }]),
                     additional_data: None,
                 },
@@ -6894,6 +7029,7 @@ impl ContentSeeder {
                         expected_answer: "journal".to_string(),
                         case_sensitive: false,
                         accept_alternatives: Some(vec!["logbook".to_string()]),
+                        max_edit_distance: None,
                     }]),
                     additional_data: None,
                 },
@@ -6901,15 +7037,11 @@ impl ContentSeeder {
             ).with_difficulty(3).with_tags(vec!["vocabulary".to_string(), "academic_language".to_string(), "fill_blank".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
-
-        Ok(())
+        Ok(questions)
     }
 
-    /// Seed interactive Science content
-    fn seed_interactive_science_content(&self, subject_id: u32) -> AppResult<()> {
+    /// Build interactive Science content
+    fn interactive_science_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
         println!("Seeding interactive Science content...");
 
         let questions = vec![
@@ -6983,6 +7115,7 @@ impl ContentSeeder {
                         expected_answer: "roots".to_string(),
                         case_sensitive: false,
                         accept_alternatives: None,
+                        max_edit_distance: None,
                     }]),
                     additional_data: None,
                 },
@@ -6990,11 +7123,79 @@ impl ContentSeeder {
             ).with_difficulty(2).with_tags(vec!["plants".to_string(), "biology".to_string(), "fill_blank".to_string()]),
         ];
 
-        for question in questions {
-            self.add_question(question)?;
-        }
+        Ok(questions)
+    }
 
-        Ok(())
+    /// Build Early Years (Reception) Mathematics: counting, one more/one less and simple shapes
+    fn early_years_mathematics_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
+        println!("Seeding Early Years Mathematics content...");
+
+        let questions = vec![
+            choice_question(subject_id, KeyStage::EYFS, "How many stars? ⭐⭐⭐", &["2", "3", "4"], "3", 1, &["counting"]),
+            choice_question(subject_id, KeyStage::EYFS, "How many apples? 🍎🍎🍎🍎🍎", &["4", "5", "6"], "5", 1, &["counting"]),
+            choice_question(subject_id, KeyStage::EYFS, "What number comes after 4?", &["3", "5", "6"], "5", 1, &["counting", "number_order"]),
+            choice_question(subject_id, KeyStage::EYFS, "What is one more than 6?", &["5", "7", "8"], "7", 2, &["one_more_one_less"]),
+            choice_question(subject_id, KeyStage::EYFS, "What is one less than 9?", &["8", "10", "7"], "8", 2, &["one_more_one_less"]),
+            choice_question(subject_id, KeyStage::EYFS, "Which shape is round?", &["Square", "Circle", "Triangle"], "Circle", 1, &["shapes"]),
+            choice_question(subject_id, KeyStage::EYFS, "How many sides does a triangle have?", &["3", "4", "5"], "3", 2, &["shapes"]),
+            choice_question(subject_id, KeyStage::EYFS, "Which is more: 🐟🐟 or 🐟🐟🐟🐟?", &["🐟🐟", "🐟🐟🐟🐟"], "🐟🐟🐟🐟", 1, &["more_fewer"]),
+        ];
+
+        Ok(questions)
+    }
+
+    /// Build Early Years (Reception) English: initial sounds and rhymes
+    fn early_years_english_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
+        println!("Seeding Early Years English content...");
+
+        let questions = vec![
+            choice_question(subject_id, KeyStage::EYFS, "Which word starts with the sound 's'?", &["sun", "moon", "cat"], "sun", 1, &["phonics", "initial_sounds"]),
+            choice_question(subject_id, KeyStage::EYFS, "Which word starts with the sound 'b'?", &["dog", "ball", "fish"], "ball", 1, &["phonics", "initial_sounds"]),
+            choice_question(subject_id, KeyStage::EYFS, "Which word starts with the sound 'm'?", &["map", "pen", "top"], "map", 1, &["phonics", "initial_sounds"]),
+            choice_question(subject_id, KeyStage::EYFS, "Which word rhymes with 'cat'?", &["hat", "cup", "dog"], "hat", 2, &["rhyming"]),
+            choice_question(subject_id, KeyStage::EYFS, "Which word rhymes with 'log'?", &["leg", "frog", "sun"], "frog", 2, &["rhyming"]),
+            choice_question(subject_id, KeyStage::EYFS, "Which letter makes the first sound in 'pig'?", &["p", "g", "b"], "p", 2, &["phonics", "letters"]),
+        ];
+
+        Ok(questions)
+    }
+
+    /// Build KS3 Mathematics: algebra, negative numbers, percentages, ratio and primes
+    fn ks3_mathematics_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
+        println!("Seeding KS3 Mathematics content...");
+
+        let questions = vec![
+            choice_question(subject_id, KeyStage::KS3, "Solve 3x + 5 = 20", &["x = 3", "x = 5", "x = 15", "x = 25"], "x = 5", 2, &["algebra", "equations"]),
+            choice_question(subject_id, KeyStage::KS3, "Expand 2(x + 4)", &["2x + 4", "2x + 8", "x + 8", "2x + 6"], "2x + 8", 2, &["algebra", "expanding_brackets"]),
+            choice_question(subject_id, KeyStage::KS3, "What is -7 + 3?", &["-10", "-4", "4", "10"], "-4", 1, &["negative_numbers"]),
+            choice_question(subject_id, KeyStage::KS3, "What is -6 × -4?", &["-24", "-10", "10", "24"], "24", 2, &["negative_numbers"]),
+            choice_question(subject_id, KeyStage::KS3, "What is 15% of 80?", &["8", "12", "15", "20"], "12", 2, &["percentages"]),
+            choice_question(subject_id, KeyStage::KS3, "Share £35 in the ratio 2:5. How much is the larger share?", &["£10", "£14", "£21", "£25"], "£25", 3, &["ratio"]),
+            choice_question(subject_id, KeyStage::KS3, "Which of these is a prime number?", &["21", "27", "29", "33"], "29", 2, &["primes"]),
+            choice_question(subject_id, KeyStage::KS3, "What is the next term in 3, 7, 11, 15, ...?", &["17", "18", "19", "20"], "19", 1, &["sequences"]),
+            choice_question(subject_id, KeyStage::KS3, "The interior angles of a triangle add up to...", &["90°", "180°", "270°", "360°"], "180°", 1, &["angles", "geometry"]),
+            choice_question(subject_id, KeyStage::KS3, "What is 2³ × 2²?", &["2⁵", "2⁶", "4⁵", "4⁶"], "2⁵", 3, &["indices"]),
+        ];
+
+        Ok(questions)
+    }
+
+    /// Build KS3 Science: cells, particles, elements, forces and energy
+    fn ks3_science_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
+        println!("Seeding KS3 Science content...");
+
+        let questions = vec![
+            choice_question(subject_id, KeyStage::KS3, "Which part of a cell controls its activities?", &["Nucleus", "Cell membrane", "Cytoplasm", "Cell wall"], "Nucleus", 1, &["biology", "cells"]),
+            choice_question(subject_id, KeyStage::KS3, "Where does photosynthesis take place?", &["Mitochondria", "Chloroplasts", "Ribosomes", "Vacuole"], "Chloroplasts", 2, &["biology", "photosynthesis"]),
+            choice_question(subject_id, KeyStage::KS3, "What is the chemical symbol for sodium?", &["S", "So", "Na", "Sd"], "Na", 2, &["chemistry", "elements"]),
+            choice_question(subject_id, KeyStage::KS3, "In which state are particles most tightly packed?", &["Solid", "Liquid", "Gas"], "Solid", 1, &["chemistry", "particles"]),
+            choice_question(subject_id, KeyStage::KS3, "What is the pH of a neutral solution?", &["1", "7", "10", "14"], "7", 2, &["chemistry", "acids_alkalis"]),
+            choice_question(subject_id, KeyStage::KS3, "What unit is force measured in?", &["Joules", "Watts", "Newtons", "Pascals"], "Newtons", 1, &["physics", "forces"]),
+            choice_question(subject_id, KeyStage::KS3, "A car travels 100 m in 5 s. What is its speed?", &["5 m/s", "20 m/s", "95 m/s", "500 m/s"], "20 m/s", 3, &["physics", "speed"]),
+            choice_question(subject_id, KeyStage::KS3, "Which of these is a renewable energy resource?", &["Coal", "Natural gas", "Wind", "Oil"], "Wind", 1, &["physics", "energy"]),
+        ];
+
+        Ok(questions)
     }
 
     /// Check if content has already been seeded
@@ -7005,14 +7206,33 @@ impl ContentSeeder {
 
     /// Seed content only if it hasn't been seeded already
     pub fn seed_if_empty(&self) -> AppResult<()> {
+        self.seed_if_empty_with_progress(|_| {})
+    }
+
+    /// Seed content only if it hasn't been seeded already, reporting progress
+    pub fn seed_if_empty_with_progress<F>(&self, mut on_progress: F) -> AppResult<()>
+    where
+        F: FnMut(&SeedingProgress),
+    {
         let is_seeded = self.is_content_seeded()?;
         
         if !is_seeded {
             println!("Database is empty, seeding with comprehensive educational content...");
-            self.seed_all_content()?;
+            self.seed_all_content_with_progress(on_progress)?;
         } else {
             println!("Content already exists, checking for missing subjects...");
             self.seed_missing_subjects()?;
+            on_progress(&SeedingProgress {
+                current_step: "Complete".to_string(),
+                subject: None,
+                completed_steps: 1,
+                total_steps: 1,
+                questions_inserted: 0,
+                questions_total: 0,
+                finished: true,
+                cancelled: false,
+                error: None,
+            });
         }
         
         Ok(())
@@ -7040,9 +7260,10 @@ impl ContentSeeder {
                     conn.execute("DELETE FROM questions WHERE subject_id = ?1", [times_tables_id])?;
                     Ok(())
                 })?;
+                self.questions_changed();
                 
                 // Reseed with updated content
-                self.seed_times_tables_content(times_tables_id)?;
+                self.add_questions(self.times_tables_questions(times_tables_id)?)?;
             }
         }
 
@@ -7059,9 +7280,10 @@ impl ContentSeeder {
                     conn.execute("DELETE FROM questions WHERE subject_id = ?1", [flags_capitals_id])?;
                     Ok(())
                 })?;
+                self.questions_changed();
                 
                 // Reseed with updated content
-                self.seed_flags_capitals_content(flags_capitals_id)?;
+                self.add_questions(self.flags_capitals_questions(flags_capitals_id)?)?;
             }
         }
 
@@ -7083,17 +7305,100 @@ impl ContentSeeder {
                     conn.execute("DELETE FROM questions WHERE subject_id = ?1", [english_id])?;
                     Ok(())
                 })?;
+                self.questions_changed();
                 
                 // Reseed English content with updated questions (no drag-drop)
-                self.seed_english_content(english_id)?;
-                self.seed_interactive_english_content(english_id)?;
+                self.add_questions(self.english_questions(english_id)?)?;
+                self.add_questions(self.interactive_english_questions(english_id)?)?;
+            }
+        }
+
+        // Databases seeded before EYFS and KS3 existed have no content for them yet
+        for (key_stage, steps) in KEY_STAGE_SEED_STEPS {
+            if self.count_questions_for_key_stage(*key_stage)? > 0 {
+                continue;
+            }
+            for (label, subject_name, build_questions) in steps.iter() {
+                if let Some(&subject_id) = subject_map.get(*subject_name) {
+                    println!("Seeding missing {} content...", label);
+                    self.add_questions(build_questions(self, subject_id)?)?;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Replace built-in and installed content with a fresh seed, keeping questions parents
+    /// wrote or imported. Questions with quiz history are retired rather than deleted. The
+    /// database is backed up first, and the reset runs in one transaction so a failure
+    /// leaves content as it was.
+    pub fn reset_content(&self, storage: &StorageManager) -> AppResult<ContentReset> {
+        let backup_path = storage.backup_database()?;
+
+        let subject_map = self.subject_ids()?;
+        let mut questions = Vec::new();
+        for (_, subject_name, build_questions) in SEED_STEPS {
+            questions.extend(build_questions(self, subject_map[*subject_name])?);
+        }
+
+        let (removal, questions_kept) = self.db_manager.transaction(|tx| {
+            let question_ids: Vec<QuestionId> = {
+                let mut stmt = tx.prepare(
+                    "SELECT id FROM questions
+                     WHERE id NOT IN (SELECT question_id FROM question_sources WHERE kind IN ('parent', 'import'))
+                       AND id NOT IN (SELECT question_id FROM retired_questions)"
+                )?;
+                let rows = stmt.query_map([], |row| row.get(0))?;
+                rows.collect::<Result<_, _>>()?
+            };
+            let removal = remove_questions(tx, &question_ids, "Replaced by a content reset")?;
+            let kept: u32 = tx.query_row(
+                "SELECT COUNT(*) FROM questions WHERE id NOT IN (SELECT question_id FROM retired_questions)",
+                [],
+                |row| row.get(0),
+            )?;
+            for question in &questions {
+                insert_question(tx, question)?;
+            }
+            Ok((removal, kept as usize))
+        })?;
+        if !removal.deleted.is_empty() {
+            self.db_manager.schedule_compaction();
+        }
+        self.questions_changed();
+
+        println!(
+            "Content reset: removed {}, retired {}, kept {} parent questions, seeded {}",
+            removal.deleted.len(), removal.retired.len(), questions_kept, questions.len()
+        );
+        Ok(ContentReset {
+            backup_path,
+            questions_removed: removal.deleted.len(),
+            questions_retired: removal.retired.len(),
+            questions_kept,
+            questions_seeded: questions.len(),
+        })
+    }
+
+    fn count_questions_for_key_stage(&self, key_stage: KeyStage) -> AppResult<u32> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT COUNT(*) FROM questions WHERE key_stage = ?1",
+                [key_stage.as_str()],
+                |row| row.get(0),
+            )
+        })?)
+    }
+
     // Helper methods
+    fn subject_ids(&self) -> AppResult<HashMap<String, u32>> {
+        Ok(self.get_subjects()?
+            .into_iter()
+            .map(|subject| (subject.name, subject.id.unwrap()))
+            .collect())
+    }
+
     fn get_subjects(&self) -> AppResult<Vec<crate::models::Subject>> {
         Ok(self.db_manager.execute(|conn| {
             let mut stmt = conn.prepare(
@@ -7120,71 +7425,48 @@ impl ContentSeeder {
         })?)
     }
 
-    fn add_question(&self, question: Question) -> AppResult<u32> {
-        Ok(self.db_manager.transaction(|tx| {
-            let content_json = serde_json::to_string(&question.content)
-                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-            let correct_answer_json = serde_json::to_string(&question.correct_answer)
-                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-            let tags_json = serde_json::to_string(&question.tags)
-                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-            
-            let key_stage_str = match question.key_stage {
-                KeyStage::KS1 => "KS1",
-                KeyStage::KS2 => "KS2",
-            };
-            
-            let question_type_str = match question.question_type {
-                QuestionType::MultipleChoice => "multiple_choice",
-                QuestionType::DragDrop => "drag_drop",
-                QuestionType::Hotspot => "hotspot",
-                QuestionType::FillBlank => "fill_blank",
-                QuestionType::StoryQuiz => "story_quiz",
-            };
-            
-            tx.execute(
-                "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-                rusqlite::params![
-                    question.subject_id,
-                    key_stage_str,
-                    question_type_str,
-                    content_json,
-                    correct_answer_json,
-                    question.difficulty_level,
-                    tags_json,
-                    chrono::Utc::now().to_rfc3339()
-                ],
-            )?;
-            
-            let question_id = tx.last_insert_rowid() as u32;
-            
-            // Insert assets if any
-            if let Some(assets) = &question.assets {
-                for asset in assets {
-                    let asset_type_str = match asset.asset_type {
-                        AssetType::Image => "image",
-                        AssetType::Audio => "audio",
-                        AssetType::Animation => "animation",
-                    };
-                    
-                    tx.execute(
-                        "INSERT INTO assets (question_id, asset_type, file_path, alt_text, file_size, created_at)
-                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                        rusqlite::params![
-                            question_id,
-                            asset_type_str,
-                            asset.file_path,
-                            asset.alt_text,
-                            asset.file_size,
-                            chrono::Utc::now().to_rfc3339()
-                        ],
-                    )?;
-                }
+    fn add_questions(&self, questions: Vec<Question>) -> AppResult<()> {
+        for question in questions {
+            self.add_question(question)?;
+        }
+        Ok(())
+    }
+
+    /// Remove every built-in question, e.g. what an interrupted seeding run left behind
+    pub fn remove_seeded_questions(&self) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "DELETE FROM questions WHERE id IN (SELECT question_id FROM question_sources WHERE kind = 'seeder')",
+                [],
+            )
+        })?;
+        self.questions_changed();
+        Ok(())
+    }
+
+    /// Delete questions by id; their assets and source records go with them
+    fn remove_questions(&self, question_ids: &[QuestionId]) -> AppResult<()> {
+        self.db_manager.transaction(|tx| {
+            let mut stmt = tx.prepare("DELETE FROM questions WHERE id = ?1")?;
+            for question_id in question_ids {
+                stmt.execute([question_id])?;
             }
-            
-            Ok(question_id)
-        })?)
+            Ok(())
+        })?;
+        self.questions_changed();
+        Ok(())
+    }
+
+    fn add_question(&self, question: Question) -> AppResult<QuestionId> {
+        let question_id = self.db_manager.transaction(|tx| insert_question(tx, &question))?;
+        self.questions_changed();
+        Ok(question_id)
+    }
+
+    fn questions_changed(&self) {
+        if let Some(content_manager) = &self.content_manager {
+            content_manager.invalidate_question_cache();
+        }
     }
 
     pub fn get_content_statistics(&self) -> AppResult<ContentStatistics> {
@@ -7234,7 +7516,95 @@ impl ContentSeeder {
     }
 }
 
+/// Build a multiple choice question from string slices, for compact seed lists
+fn choice_question(
+    subject_id: u32,
+    key_stage: KeyStage,
+    text: &str,
+    options: &[&str],
+    answer: &str,
+    difficulty: u8,
+    tags: &[&str],
+) -> Question {
+    Question::new(
+        subject_id,
+        key_stage,
+        QuestionType::MultipleChoice,
+        QuestionContent {
+            text: text.to_string(),
+            options: Some(options.iter().map(|option| option.to_string()).collect()),
+            story: None,
+            image_url: None,
+            hotspots: None,
+            blanks: None,
+            additional_data: None,
+        },
+        Answer::Text(answer.to_string()),
+    ).with_difficulty(difficulty).with_tags(tags.iter().map(|tag| tag.to_string()).collect())
+}
+
 /// Content statistics structure
+/// Insert a question with its assets, source and explanation, defaulting the source to this seeder
+fn insert_question(tx: &rusqlite::Connection, question: &Question) -> rusqlite::Result<QuestionId> {
+    let content_json = serde_json::to_string(&question.content)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let correct_answer_json = serde_json::to_string(&question.correct_answer)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let tags_json = serde_json::to_string(&question.tags)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    
+    let key_stage_str = question.key_stage.as_str();
+    
+    let question_type_str = question.question_type.as_str();
+    
+    tx.execute(
+        "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            question.subject_id,
+            key_stage_str,
+            question_type_str,
+            content_json,
+            correct_answer_json,
+            question.difficulty_level,
+            tags_json,
+            chrono::Utc::now().to_rfc3339()
+        ],
+    )?;
+    
+    let question_id = QuestionId(tx.last_insert_rowid() as u32);
+    
+    // Insert assets, including any option images
+    for asset in &question.content.assets_with_option_images(question.assets.as_ref()) {
+        let asset_type_str = match asset.asset_type {
+            AssetType::Image => "image",
+            AssetType::Audio => "audio",
+            AssetType::Animation => "animation",
+        };
+        
+        tx.execute(
+            "INSERT INTO assets (question_id, asset_type, file_path, alt_text, file_size, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                question_id,
+                asset_type_str,
+                asset.file_path,
+                asset.alt_text,
+                asset.file_size,
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )?;
+    }
+    
+    let source = question.source.clone().unwrap_or_else(|| QuestionSource::Seeder {
+        version: SEEDER_VERSION.to_string(),
+    });
+    crate::services::content_manager::record_question_source(tx, question_id, &source)?;
+    crate::services::content_manager::record_question_explanation(tx, question_id, question.explanation.as_deref(), &question.content)?;
+    
+    Ok(question_id)
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ContentStatistics {
     pub total_questions: u32,
@@ -7293,4 +7663,137 @@ mod tests {
         let stats2 = seeder.get_content_statistics().unwrap();
         assert_eq!(stats1.total_questions, stats2.total_questions);
     }
+
+    #[test]
+    fn test_seeding_reports_progress() {
+        let (seeder, _temp_dir) = create_test_seeder();
+        
+        let mut updates = Vec::new();
+        seeder.seed_if_empty_with_progress(|progress| updates.push(progress.clone())).unwrap();
+        
+        assert!(updates.len() > SEED_STEPS.len());
+        assert_eq!(updates[0].completed_steps, 0);
+        assert_eq!(updates[0].subject.as_deref(), Some("mathematics"));
+        assert!(updates.windows(2).all(|pair| pair[0].questions_inserted <= pair[1].questions_inserted));
+        let last = updates.last().unwrap();
+        assert!(last.finished);
+        assert_eq!(last.completed_steps, last.total_steps);
+        assert_eq!(last.questions_inserted, last.questions_total);
+        assert_eq!(seeder.get_content_statistics().unwrap().total_questions as usize, last.questions_total);
+    }
+
+    #[test]
+    fn test_cancelled_seeding_removes_inserted_questions() {
+        let (seeder, _temp_dir) = create_test_seeder();
+
+        let result = seeder.seed_all_content_with_progress(|progress| {
+            if progress.questions_inserted > 0 {
+                seeder.cancel_seeding();
+            }
+        });
+        assert!(matches!(result, Err(AppError::Cancelled(_))));
+        assert_eq!(seeder.get_content_statistics().unwrap().total_questions, 0);
+
+        // A cancelled run doesn't stop the next one
+        seeder.seed_all_content().unwrap();
+        assert!(seeder.get_content_statistics().unwrap().total_questions > 0);
+    }
+
+    #[test]
+    fn test_removing_seeded_questions_invalidates_question_cache() {
+        let (seeder, temp_dir) = create_test_seeder();
+        let content_manager = Arc::new(ContentManager::new(
+            seeder.db_manager.clone(),
+            Arc::new(crate::services::SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let seeder = seeder.with_content_manager(content_manager.clone());
+        seeder.seed_all_content().unwrap();
+
+        let cached = content_manager.get_questions_by_subject("mathematics", None, None, None).unwrap();
+        assert!(!cached.is_empty());
+
+        seeder.remove_seeded_questions().unwrap();
+        assert_eq!(seeder.get_content_statistics().unwrap().total_questions, 0);
+        assert!(content_manager.get_questions_by_subject("mathematics", None, None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reset_backs_up_and_keeps_parent_questions() {
+        let (seeder, temp_dir) = create_test_seeder();
+        seeder.seed_all_content().unwrap();
+        let seeded = seeder.get_content_statistics().unwrap().total_questions as usize;
+
+        let subject_id = seeder.subject_ids().unwrap()["mathematics"];
+        let mut parent_question = seeder.mathematics_questions(subject_id).unwrap().remove(0);
+        parent_question.source = Some(QuestionSource::Parent { author: None });
+        let parent_question_id = seeder.add_question(parent_question).unwrap();
+
+        let storage = StorageManager::new(seeder.db_manager.clone(), crate::services::StorageLocations {
+            asset_directory: temp_dir.path().join("assets"),
+            backup_directory: temp_dir.path().join("backups"),
+            log_directory: temp_dir.path().join("crashes"),
+        });
+        seeder.db_manager.run_scheduled_compaction().unwrap();
+        let reset = seeder.reset_content(&storage).unwrap();
+        assert!(reset.backup_path.exists());
+        assert_eq!(reset.questions_removed, seeded);
+        assert!(seeder.db_manager.run_scheduled_compaction().unwrap().is_some());
+        assert_eq!(reset.questions_kept, 1);
+        assert_eq!(reset.questions_seeded, seeded);
+        assert!(storage.last_backup_at().unwrap().is_some());
+
+        let still_there: bool = seeder.db_manager.execute(|conn| {
+            conn.query_row("SELECT EXISTS(SELECT 1 FROM questions WHERE id = ?1)", [parent_question_id], |row| row.get(0))
+        }).unwrap();
+        assert!(still_there);
+        assert_eq!(seeder.get_content_statistics().unwrap().total_questions as usize, seeded + 1);
+    }
+
+    #[test]
+    fn test_reset_retires_questions_with_history() {
+        let (seeder, temp_dir) = create_test_seeder();
+        seeder.seed_all_content().unwrap();
+        let seeded = seeder.get_content_statistics().unwrap().total_questions as usize;
+
+        let answered_question_id: QuestionId = seeder.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                [chrono::Utc::now().to_rfc3339()],
+            )?;
+            let profile_id = conn.last_insert_rowid();
+            let question_id: QuestionId = conn.query_row("SELECT MIN(id) FROM questions", [], |row| row.get(0))?;
+            conn.execute(
+                "INSERT INTO mistakes (profile_id, question_id, times_wrong, correct_since, first_wrong_at, last_wrong_at)
+                 VALUES (?1, ?2, 2, 0, ?3, ?3)",
+                rusqlite::params![profile_id, question_id, chrono::Utc::now().to_rfc3339()],
+            )?;
+            conn.execute(
+                "INSERT INTO question_reviews (profile_id, question_id, ease_factor, interval_days, repetitions, due_at, last_reviewed_at)
+                 VALUES (?1, ?2, 2.5, 1, 1, ?3, ?3)",
+                rusqlite::params![profile_id, question_id, chrono::Utc::now().to_rfc3339()],
+            )?;
+            Ok(question_id)
+        }).unwrap();
+
+        let storage = StorageManager::new(seeder.db_manager.clone(), crate::services::StorageLocations {
+            asset_directory: temp_dir.path().join("assets"),
+            backup_directory: temp_dir.path().join("backups"),
+            log_directory: temp_dir.path().join("crashes"),
+        });
+        let reset = seeder.reset_content(&storage).unwrap();
+        assert_eq!(reset.questions_retired, 1);
+        assert_eq!(reset.questions_removed, seeded - 1);
+
+        let (mistakes, reviews, retired): (u32, u32, bool) = seeder.db_manager.execute(|conn| {
+            Ok((
+                conn.query_row("SELECT COUNT(*) FROM mistakes WHERE question_id = ?1", [answered_question_id], |row| row.get(0))?,
+                conn.query_row("SELECT COUNT(*) FROM question_reviews WHERE question_id = ?1", [answered_question_id], |row| row.get(0))?,
+                conn.query_row("SELECT EXISTS(SELECT 1 FROM retired_questions WHERE question_id = ?1)", [answered_question_id], |row| row.get(0))?,
+            ))
+        }).unwrap();
+        assert_eq!(mistakes, 1);
+        assert_eq!(reviews, 1);
+        assert!(retired);
+    }
 }
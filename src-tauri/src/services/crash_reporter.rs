@@ -0,0 +1,255 @@
+use crate::errors::{AppError, AppResult};
+use crate::config::apply_proxy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use url::Url;
+
+const CRASH_FILE_PREFIX: &str = "crash-";
+const CRASH_FILE_EXTENSION: &str = "json";
+
+/// A panic captured by the crash reporter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub occurred_at: DateTime<Utc>,
+    pub app_version: String,
+    pub os: String,
+    pub thread: Option<String>,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    pub acknowledged: bool,
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
+/// Crash reporter that persists panics to a local crash log directory
+pub struct CrashReporter {
+    crash_dir: PathBuf,
+    client: reqwest::Client,
+}
+
+impl CrashReporter {
+    /// Create a new crash reporter storing reports in `crash_dir`, submitting through `proxy` if set
+    pub fn new(crash_dir: PathBuf, proxy: Option<&str>) -> AppResult<Self> {
+        fs::create_dir_all(&crash_dir)?;
+
+        let builder = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .user_agent("EducationalQuizApp/1.0");
+        let client = apply_proxy(builder, proxy)?
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self { crash_dir, client })
+    }
+
+    /// Install a panic hook that writes a crash report before running the previous hook.
+    /// The hook only touches the filesystem so it is safe to run while other locks are held.
+    pub fn install_panic_hook(crash_dir: PathBuf) {
+        let previous_hook = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            let payload = info.payload();
+            let message = if let Some(message) = payload.downcast_ref::<&str>() {
+                message.to_string()
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                "Unknown panic payload".to_string()
+            };
+            let location = info.location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+
+            let report = Self::build_report(message, location);
+            if let Err(e) = Self::write_report(&crash_dir, &report) {
+                eprintln!("Failed to write crash report: {}", e);
+            }
+            previous_hook(info);
+        }));
+    }
+
+    /// List stored crash reports, newest first
+    pub fn list_reports(&self) -> AppResult<Vec<CrashReport>> {
+        let mut reports = Vec::new();
+
+        for entry in fs::read_dir(&self.crash_dir)? {
+            let path = entry?.path();
+            if !Self::is_crash_file(&path) {
+                continue;
+            }
+
+            match fs::read_to_string(&path).map_err(AppError::from)
+                .and_then(|data| serde_json::from_str::<CrashReport>(&data).map_err(AppError::from))
+            {
+                Ok(report) => reports.push(report),
+                Err(e) => log::warn!("Skipping unreadable crash report {:?}: {}", path, e),
+            }
+        }
+
+        reports.sort_by_key(|report| std::cmp::Reverse(report.occurred_at));
+        Ok(reports)
+    }
+
+    /// Crash reports the parent has not yet reviewed
+    pub fn get_unacknowledged_reports(&self) -> AppResult<Vec<CrashReport>> {
+        Ok(self.list_reports()?
+            .into_iter()
+            .filter(|report| !report.acknowledged)
+            .collect())
+    }
+
+    /// Get a single crash report
+    pub fn get_report(&self, report_id: &str) -> AppResult<CrashReport> {
+        let path = self.report_path(report_id)?;
+        if !path.exists() {
+            return Err(AppError::NotFound(format!("Crash report not found: {}", report_id)));
+        }
+
+        let data = fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    /// Mark a crash report as reviewed so it no longer surfaces on launch
+    pub fn acknowledge_report(&self, report_id: &str) -> AppResult<CrashReport> {
+        let mut report = self.get_report(report_id)?;
+        report.acknowledged = true;
+        Self::write_report(&self.crash_dir, &report)?;
+        Ok(report)
+    }
+
+    /// Permanently delete a crash report
+    pub fn delete_report(&self, report_id: &str) -> AppResult<()> {
+        let path = self.report_path(report_id)?;
+        if !path.exists() {
+            return Err(AppError::NotFound(format!("Crash report not found: {}", report_id)));
+        }
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// Submit a crash report to `endpoint`. Callers are responsible for obtaining consent.
+    pub async fn submit_report(&self, report_id: &str, endpoint: &str) -> AppResult<CrashReport> {
+        let url = Url::parse(endpoint)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid crash report endpoint: {}", e)))?;
+        if url.scheme() != "https" {
+            return Err(AppError::InvalidInput("Crash report endpoint must use HTTPS".to_string()));
+        }
+
+        let mut report = self.get_report(report_id)?;
+
+        let response = self.client
+            .post(url)
+            .json(&report)
+            .send()
+            .await
+            .map_err(|e| AppError::Telemetry(format!("Failed to send crash report: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Telemetry(format!(
+                "Crash report endpoint responded with status: {}",
+                response.status()
+            )));
+        }
+
+        report.submitted_at = Some(Utc::now());
+        report.acknowledged = true;
+        Self::write_report(&self.crash_dir, &report)?;
+
+        Ok(report)
+    }
+
+    fn build_report(message: String, location: Option<String>) -> CrashReport {
+        let occurred_at = Utc::now();
+
+        CrashReport {
+            id: format!("{}{}", CRASH_FILE_PREFIX, occurred_at.format("%Y%m%d%H%M%S%3f")),
+            occurred_at,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            thread: std::thread::current().name().map(|name| name.to_string()),
+            message,
+            location,
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+            acknowledged: false,
+            submitted_at: None,
+        }
+    }
+
+    fn write_report(crash_dir: &Path, report: &CrashReport) -> AppResult<()> {
+        fs::create_dir_all(crash_dir)?;
+        let path = crash_dir.join(format!("{}.{}", report.id, CRASH_FILE_EXTENSION));
+        fs::write(path, serde_json::to_string_pretty(report)?)?;
+        Ok(())
+    }
+
+    fn report_path(&self, report_id: &str) -> AppResult<PathBuf> {
+        let valid = report_id.starts_with(CRASH_FILE_PREFIX)
+            && report_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !valid {
+            return Err(AppError::InvalidInput(format!("Invalid crash report id: {}", report_id)));
+        }
+
+        Ok(self.crash_dir.join(format!("{}.{}", report_id, CRASH_FILE_EXTENSION)))
+    }
+
+    fn is_crash_file(path: &Path) -> bool {
+        let is_json = path.extension().and_then(|e| e.to_str()) == Some(CRASH_FILE_EXTENSION);
+        let has_prefix = path.file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.starts_with(CRASH_FILE_PREFIX))
+            .unwrap_or(false);
+        is_json && has_prefix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn sample_report(id: &str) -> CrashReport {
+        CrashReport {
+            id: id.to_string(),
+            occurred_at: Utc::now(),
+            app_version: "1.0.0".to_string(),
+            os: "test".to_string(),
+            thread: Some("main".to_string()),
+            message: "boom".to_string(),
+            location: Some("src/main.rs:1:1".to_string()),
+            backtrace: String::new(),
+            acknowledged: false,
+            submitted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_reports_surface_until_acknowledged() {
+        let temp_dir = tempdir().unwrap();
+        let reporter = CrashReporter::new(temp_dir.path().join("crashes"), None).unwrap();
+
+        CrashReporter::write_report(&reporter.crash_dir, &sample_report("crash-20240101120000000")).unwrap();
+        fs::write(reporter.crash_dir.join("notes.txt"), "ignored").unwrap();
+
+        assert_eq!(reporter.list_reports().unwrap().len(), 1);
+        assert_eq!(reporter.get_unacknowledged_reports().unwrap().len(), 1);
+
+        let report = reporter.acknowledge_report("crash-20240101120000000").unwrap();
+        assert!(report.acknowledged);
+        assert!(reporter.get_unacknowledged_reports().unwrap().is_empty());
+
+        reporter.delete_report("crash-20240101120000000").unwrap();
+        assert!(reporter.list_reports().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_report_ids_cannot_escape_crash_directory() {
+        let temp_dir = tempdir().unwrap();
+        let reporter = CrashReporter::new(temp_dir.path().join("crashes"), None).unwrap();
+
+        assert!(matches!(reporter.get_report("../secrets"), Err(AppError::InvalidInput(_))));
+        assert!(matches!(reporter.get_report("crash-missing"), Err(AppError::NotFound(_))));
+    }
+}
@@ -0,0 +1,373 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{CreateMixRequest, CustomMix, KeyStage, MixConfig, ProfileId, QuestionId};
+use crate::database::DatabaseManager;
+use crate::services::{CurriculumService, CustomMixManager};
+use std::collections::BTreeSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Most objectives a gap mix covers, so practice stays focused
+const MAX_GAP_OBJECTIVES: usize = 3;
+
+/// A National Curriculum (England) objective and the question tags that practise it
+#[derive(Debug, Clone, Copy)]
+pub struct CurriculumObjective {
+    pub code: &'static str,
+    pub subject: &'static str,
+    pub key_stage: KeyStage,
+    /// Short name, e.g. for a mix title
+    pub topic: &'static str,
+    pub description: &'static str,
+    pub tags: &'static [&'static str],
+}
+
+macro_rules! objective {
+    ($code:expr, $subject:expr, $key_stage:ident, $topic:expr, $description:expr, [$($tag:expr),+]) => {
+        CurriculumObjective {
+            code: $code,
+            subject: $subject,
+            key_stage: KeyStage::$key_stage,
+            topic: $topic,
+            description: $description,
+            tags: &[$($tag),+],
+        }
+    };
+}
+
+/// Curriculum objectives mapped to question tags
+pub const CURRICULUM_OBJECTIVES: &[CurriculumObjective] = &[
+    objective!("ks1-ma-number", "mathematics", KS1, "Counting", "Count, read and write numbers to 100 and compare them", ["counting", "numbers", "number_sequence", "comparison"]),
+    objective!("ks1-ma-add-sub", "mathematics", KS1, "Adding and subtracting", "Add and subtract one-digit and two-digit numbers", ["addition", "subtraction", "basic_arithmetic"]),
+    objective!("ks1-ma-mult-div", "mathematics", KS1, "Multiplying and dividing", "Recall multiplication and division facts for the 2, 5 and 10 times tables", ["multiplication", "division", "doubling", "halving", "times_tables"]),
+    objective!("ks1-ma-fractions", "mathematics", KS1, "Halves and quarters", "Recognise, find and name halves and quarters", ["fractions", "halves"]),
+    objective!("ks1-ma-measure", "mathematics", KS1, "Measuring, money and time", "Measure length, mass and capacity, recognise coins and tell the time", ["measurement", "money", "time", "calendar"]),
+    objective!("ks1-ma-shape", "mathematics", KS1, "Shapes", "Recognise and name common 2-D and 3-D shapes", ["shapes", "geometry"]),
+    objective!("ks1-ma-position", "mathematics", KS1, "Position and patterns", "Describe position, direction and movement, and continue patterns", ["position", "direction", "patterns", "sequences"]),
+    objective!("ks1-en-phonics", "english", KS1, "Phonics", "Read words by blending the sounds of letters and letter groups", ["phonics", "sounds", "rhyming", "initial_sounds"]),
+    objective!("ks1-en-spelling", "english", KS1, "Spelling", "Spell common words, name the letters of the alphabet and add simple suffixes", ["spelling", "alphabet", "alphabetical_order", "plurals"]),
+    objective!("ks1-sc-animals", "science", KS1, "Animals and humans", "Identify and compare common animals, and name the parts of the human body", ["animals", "human_body", "senses", "baby_animals"]),
+    objective!("ks1-sc-plants", "science", KS1, "Plants", "Identify common plants and describe how they grow", ["plants", "plant_parts", "growth"]),
+    objective!("ks1-sc-materials", "science", KS1, "Everyday materials", "Identify everyday materials and describe their properties", ["materials", "properties"]),
+    objective!("ks1-sc-seasons", "science", KS1, "Seasons and weather", "Observe changes across the four seasons and the weather", ["seasons", "weather"]),
+    objective!("ks1-ge-world", "geography", KS1, "Continents and oceans", "Name and locate the world's continents and oceans", ["continents", "oceans"]),
+    objective!("ks1-ge-uk", "geography", KS1, "The United Kingdom", "Name the countries and capital cities of the United Kingdom", ["uk", "united_kingdom", "london"]),
+    objective!("ks2-ma-calculation", "mathematics", KS2, "Written calculation", "Add, subtract, multiply and divide with formal written methods and solve problems", ["arithmetic", "calculation", "word_problems"]),
+    objective!("ks2-ma-times-tables", "mathematics", KS2, "Times tables", "Recall multiplication and division facts up to 12 × 12", ["times_tables", "multiplication"]),
+    objective!("ks2-ma-fractions", "mathematics", KS2, "Fractions, decimals and percentages", "Compare and calculate with fractions, decimals and percentages", ["fractions", "decimals", "percentages", "equivalence"]),
+    objective!("ks2-ma-measure", "mathematics", KS2, "Measurement", "Convert between units and find perimeter and area", ["measurement", "measurements", "units", "perimeter", "area"]),
+    objective!("ks2-ma-geometry", "mathematics", KS2, "Shapes and angles", "Describe the properties of shapes and measure angles", ["geometry", "shapes", "angles"]),
+    objective!("ks2-en-grammar", "english", KS2, "Grammar and punctuation", "Use word classes, tenses and punctuation correctly", ["grammar", "punctuation", "tenses", "parts_of_speech", "adjectives", "adverbs"]),
+    objective!("ks2-en-vocabulary", "english", KS2, "Vocabulary", "Explain the meaning of words, including synonyms and antonyms", ["vocabulary", "synonyms", "antonyms"]),
+    objective!("ks2-en-reading", "english", KS2, "Reading comprehension", "Retrieve information from texts and infer their meaning", ["reading_comprehension", "main_idea"]),
+    objective!("ks2-sc-living-things", "science", KS2, "Living things and habitats", "Classify living things and describe habitats, food chains and life cycles", ["habitats", "food_chains", "classification", "ecosystems", "life_cycles"]),
+    objective!("ks2-sc-body", "science", KS2, "The human body", "Describe the digestive and circulatory systems, teeth and skeleton", ["human_body", "organs", "heart", "circulation", "teeth", "bones"]),
+    objective!("ks2-sc-matter", "science", KS2, "States of matter", "Group materials as solids, liquids and gases and describe the water cycle", ["states_of_matter", "water_cycle", "gases"]),
+    objective!("ks2-sc-physics", "science", KS2, "Forces, light and electricity", "Explain forces, magnets, light and simple electrical circuits", ["forces", "gravity", "magnetism", "light", "electricity", "circuits"]),
+    objective!("ks2-sc-space", "science", KS2, "Earth and space", "Describe the movement of the Earth and Moon relative to the Sun", ["space", "planets", "solar_system", "moon", "sun"]),
+    objective!("ks2-ge-places", "geography", KS2, "Countries and capitals", "Locate the world's countries and capital cities", ["capitals", "countries", "cities"]),
+    objective!("ks2-ge-physical", "geography", KS2, "Physical geography", "Describe rivers, mountains, volcanoes and other physical features", ["rivers", "mountains", "volcanoes", "geology"]),
+];
+
+/// How much of one objective a profile has practised
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectiveCoverage {
+    pub code: String,
+    pub subject: String,
+    pub key_stage: KeyStage,
+    /// The key stage as the family's curriculum names it
+    pub key_stage_label: String,
+    pub topic: String,
+    pub description: String,
+    /// Questions in the bank for this objective; none means the bank can't cover it yet
+    pub available_questions: u32,
+    /// Different questions for this objective the profile has answered
+    pub answered_questions: u32,
+    pub correct_answers: u32,
+    pub last_practised: Option<DateTime<Utc>>,
+}
+
+impl ObjectiveCoverage {
+    fn is_untouched(&self) -> bool {
+        self.available_questions > 0 && self.answered_questions == 0
+    }
+
+    /// Share of the objective's questions answered, 0.0 to 1.0
+    fn coverage(&self) -> f64 {
+        if self.available_questions == 0 {
+            return 1.0;
+        }
+        f64::from(self.answered_questions) / f64::from(self.available_questions)
+    }
+}
+
+/// Which curriculum objectives a profile has and hasn't practised
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub profile_id: ProfileId,
+    pub objectives: Vec<ObjectiveCoverage>,
+    /// Codes of objectives with questions in the bank that the profile hasn't answered
+    pub untouched: Vec<String>,
+    /// Objectives with at least one answered question, out of those the bank can cover
+    pub covered_objectives: u32,
+    pub coverable_objectives: u32,
+    pub coverage_percentage: u8,
+}
+
+/// Compares what a profile has answered against the curriculum objectives and builds
+/// mixes aimed at the gaps
+pub struct CurriculumCoverageService {
+    db_manager: Arc<DatabaseManager>,
+    custom_mix_manager: Arc<CustomMixManager>,
+    curriculum: CurriculumService,
+}
+
+impl CurriculumCoverageService {
+    /// Create a new curriculum coverage service
+    pub fn new(db_manager: Arc<DatabaseManager>, custom_mix_manager: Arc<CustomMixManager>) -> Self {
+        Self {
+            curriculum: CurriculumService::new(db_manager.clone()),
+            db_manager,
+            custom_mix_manager,
+        }
+    }
+
+    /// Note that a profile has answered a question
+    pub fn record_answer(&self, profile_id: ProfileId, question_id: QuestionId, is_correct: bool) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO answered_questions (profile_id, question_id, times_answered, times_correct, last_answered_at)
+                 VALUES (?1, ?2, 1, ?3, ?4)
+                 ON CONFLICT (profile_id, question_id) DO UPDATE SET
+                    times_answered = times_answered + 1,
+                    times_correct = times_correct + excluded.times_correct,
+                    last_answered_at = excluded.last_answered_at",
+                params![profile_id, question_id, is_correct as u32, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Coverage of every objective, optionally for one key stage
+    pub fn get_coverage_report(&self, profile_id: ProfileId, key_stage: Option<KeyStage>) -> AppResult<CoverageReport> {
+        let questions = self.load_tagged_questions(profile_id)?;
+        let levels = self.curriculum.get_levels(self.curriculum.get_curriculum()?)?;
+
+        let objectives: Vec<ObjectiveCoverage> = CURRICULUM_OBJECTIVES.iter()
+            .filter(|objective| key_stage.map_or(true, |key_stage| objective.key_stage == key_stage))
+            .map(|objective| {
+                let key_stage_label = levels.iter()
+                    .find(|level| level.key_stage == objective.key_stage)
+                    .map_or_else(|| objective.key_stage.as_str().to_string(), |level| level.label.clone());
+                let matching = questions.iter().filter(|question| question.practises(objective));
+                let mut coverage = ObjectiveCoverage {
+                    code: objective.code.to_string(),
+                    subject: objective.subject.to_string(),
+                    key_stage: objective.key_stage,
+                    key_stage_label,
+                    topic: objective.topic.to_string(),
+                    description: objective.description.to_string(),
+                    available_questions: 0,
+                    answered_questions: 0,
+                    correct_answers: 0,
+                    last_practised: None,
+                };
+                for question in matching {
+                    coverage.available_questions += 1;
+                    if let Some(answered) = &question.answered {
+                        coverage.answered_questions += 1;
+                        coverage.correct_answers += answered.times_correct;
+                        coverage.last_practised = coverage.last_practised.max(Some(answered.last_answered_at));
+                    }
+                }
+                coverage
+            })
+            .collect();
+
+        let coverable = objectives.iter().filter(|objective| objective.available_questions > 0).count() as u32;
+        let covered = objectives.iter().filter(|objective| objective.answered_questions > 0).count() as u32;
+        Ok(CoverageReport {
+            profile_id,
+            untouched: objectives.iter()
+                .filter(|objective| objective.is_untouched())
+                .map(|objective| objective.code.clone())
+                .collect(),
+            covered_objectives: covered,
+            coverable_objectives: coverable,
+            coverage_percentage: (covered * 100).checked_div(coverable).unwrap_or(0) as u8,
+            objectives,
+        })
+    }
+
+    /// Save a custom mix for the profile aimed at its biggest gaps in a key stage: untouched
+    /// objectives first, then the least practised
+    pub fn create_gap_mix(&self, profile_id: ProfileId, key_stage: KeyStage, question_count: u32) -> AppResult<CustomMix> {
+        let report = self.get_coverage_report(profile_id, Some(key_stage))?;
+
+        let mut gaps: Vec<&ObjectiveCoverage> = report.objectives.iter()
+            .filter(|objective| objective.answered_questions < objective.available_questions)
+            .collect();
+        gaps.sort_by(|a, b| {
+            b.is_untouched().cmp(&a.is_untouched())
+                .then(a.coverage().partial_cmp(&b.coverage()).unwrap_or(std::cmp::Ordering::Equal))
+                .then(b.available_questions.cmp(&a.available_questions))
+        });
+        gaps.truncate(MAX_GAP_OBJECTIVES);
+        if gaps.is_empty() {
+            return Err(AppError::NotFound(format!(
+                "No {} curriculum gaps left to practise",
+                self.curriculum.label(key_stage)?
+            )));
+        }
+
+        let tags: BTreeSet<String> = gaps.iter()
+            .flat_map(|gap| objective_tags(&gap.code))
+            .map(|tag| tag.to_string())
+            .collect();
+        let questions = self.load_tagged_questions(profile_id)?;
+        let matching: Vec<&TaggedQuestion> = questions.iter()
+            .filter(|question| question.key_stage == key_stage && question.tags.iter().any(|tag| tags.contains(tag)))
+            .collect();
+        let subjects: BTreeSet<String> = matching.iter().map(|question| question.subject.clone()).collect();
+
+        let config = MixConfig::new(
+            subjects.into_iter().collect(),
+            vec![key_stage],
+            question_count.min(matching.len() as u32),
+        )
+        .with_tags(tags.into_iter().collect());
+
+        let topics: Vec<&str> = gaps.iter().map(|gap| gap.topic.as_str()).collect();
+        self.custom_mix_manager.create_custom_mix(CreateMixRequest {
+            name: format!("Catch up: {}", topics.join(", ")),
+            created_by: profile_id,
+            config,
+        })
+    }
+
+    /// Every question still in use with its tags, and whether the profile has answered it
+    fn load_tagged_questions(&self, profile_id: ProfileId) -> AppResult<Vec<TaggedQuestion>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT s.name, q.key_stage, q.tags, a.times_correct, a.last_answered_at
+                 FROM questions q
+                 JOIN subjects s ON s.id = q.subject_id
+                 LEFT JOIN answered_questions a ON a.question_id = q.id AND a.profile_id = ?1
+                 WHERE q.tags IS NOT NULL
+                   AND q.id NOT IN (SELECT question_id FROM retired_questions)"
+            )?;
+            let rows = stmt.query_map(params![profile_id], |row| {
+                let answered = match row.get::<_, Option<String>>(4)? {
+                    Some(last_answered_at) => Some(AnsweredQuestion {
+                        times_correct: row.get(3)?,
+                        last_answered_at: DateTime::parse_from_rfc3339(&last_answered_at)
+                            .map_err(|_| rusqlite::Error::InvalidColumnType(4, "last_answered_at".to_string(), rusqlite::types::Type::Text))?
+                            .with_timezone(&Utc),
+                    }),
+                    None => None,
+                };
+                Ok(TaggedQuestion {
+                    subject: row.get(0)?,
+                    key_stage: KeyStage::from_str(&row.get::<_, String>(1)?)
+                        .map_err(|_| rusqlite::Error::InvalidColumnType(1, "key_stage".to_string(), rusqlite::types::Type::Text))?,
+                    tags: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+                    answered,
+                })
+            })?;
+            rows.collect()
+        })?)
+    }
+}
+
+struct AnsweredQuestion {
+    times_correct: u32,
+    last_answered_at: DateTime<Utc>,
+}
+
+struct TaggedQuestion {
+    subject: String,
+    key_stage: KeyStage,
+    tags: Vec<String>,
+    answered: Option<AnsweredQuestion>,
+}
+
+impl TaggedQuestion {
+    fn practises(&self, objective: &CurriculumObjective) -> bool {
+        self.key_stage == objective.key_stage
+            && self.tags.iter().any(|tag| objective.tags.contains(&tag.as_str()))
+    }
+}
+
+fn objective_tags(code: &str) -> &'static [&'static str] {
+    CURRICULUM_OBJECTIVES.iter()
+        .find(|objective| objective.code == code)
+        .map(|objective| objective.tags)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::Curriculum;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_report_finds_untouched_objectives_and_mix_targets_them() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let (profile_id, addition_id) = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            let profile_id = ProfileId(conn.last_insert_rowid() as u32);
+
+            let mut ids = Vec::new();
+            for (subject, tags) in [
+                ("mathematics", r#"["addition", "basic_arithmetic"]"#),
+                ("mathematics", r#"["addition"]"#),
+                ("mathematics", r#"["shapes", "geometry"]"#),
+                ("times_tables", r#"["times_tables"]"#),
+                ("times_tables", r#"["times_tables"]"#),
+            ] {
+                conn.execute(
+                    "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
+                     VALUES ((SELECT id FROM subjects WHERE name = ?1), 'KS1', 'multiple_choice', '{}', '\"4\"', 1, ?2, ?3)",
+                    params![subject, tags, Utc::now().to_rfc3339()],
+                )?;
+                ids.push(QuestionId(conn.last_insert_rowid() as u32));
+            }
+            Ok((profile_id, ids[0]))
+        }).unwrap();
+
+        let service = CurriculumCoverageService::new(db_manager.clone(), Arc::new(CustomMixManager::new(db_manager.clone())));
+        service.record_answer(profile_id, addition_id, true).unwrap();
+        service.record_answer(profile_id, addition_id, false).unwrap();
+
+        let report = service.get_coverage_report(profile_id, Some(KeyStage::KS1)).unwrap();
+        let add_sub = report.objectives.iter().find(|objective| objective.code == "ks1-ma-add-sub").unwrap();
+        assert_eq!((add_sub.available_questions, add_sub.answered_questions, add_sub.correct_answers), (2, 1, 1));
+        assert_eq!(report.untouched, vec!["ks1-ma-mult-div", "ks1-ma-shape"]);
+        assert_eq!((report.covered_objectives, report.coverable_objectives, report.coverage_percentage), (1, 3, 33));
+
+        let mix = service.create_gap_mix(profile_id, KeyStage::KS1, 10).unwrap();
+        assert_eq!(mix.name, "Catch up: Multiplying and dividing, Shapes, Adding and subtracting");
+        assert_eq!(mix.config.subjects, vec!["mathematics", "times_tables"]);
+        assert_eq!(mix.config.question_count, 5);
+        assert!(mix.config.tags.contains(&"times_tables".to_string()));
+        assert!(service.create_gap_mix(profile_id, KeyStage::KS2, 10).is_err());
+
+        // Families on another curriculum see its names for the key stages
+        CurriculumService::new(db_manager).set_curriculum(Curriculum::Scotland).unwrap();
+        let report = service.get_coverage_report(profile_id, Some(KeyStage::KS1)).unwrap();
+        assert!(report.objectives.iter().all(|objective| objective.key_stage_label == "First Level"));
+    }
+}
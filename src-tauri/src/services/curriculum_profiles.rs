@@ -0,0 +1,176 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{Curriculum, CurriculumLevel, KeyStage};
+use crate::services::households::ACTIVE_HOUSEHOLD_SQL;
+use std::str::FromStr;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use chrono::Utc;
+
+/// SQL condition keeping questions (aliased `q`) within the difficulties the active
+/// household's curriculum expects at their key stage
+pub(crate) const CURRICULUM_DIFFICULTY_SQL: &str = "q.difficulty_level BETWEEN
+     COALESCE((SELECT cl.min_difficulty FROM curriculum_levels cl
+               WHERE cl.key_stage = q.key_stage
+                 AND cl.curriculum = COALESCE((SELECT curriculum FROM curriculum_settings
+                                               WHERE household_id = (SELECT household_id FROM active_household WHERE id = 1)), 'england')), 1)
+     AND COALESCE((SELECT cl.max_difficulty FROM curriculum_levels cl
+                    WHERE cl.key_stage = q.key_stage
+                      AND cl.curriculum = COALESCE((SELECT curriculum FROM curriculum_settings
+                                                    WHERE household_id = (SELECT household_id FROM active_household WHERE id = 1)), 'england')), 5)";
+
+/// The curriculum each household follows, naming key stages the way the family knows them
+/// and setting the difficulties questions are drawn from
+pub struct CurriculumService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl CurriculumService {
+    /// Create a new curriculum service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// The active household's curriculum; England until a parent picks another
+    pub fn get_curriculum(&self) -> AppResult<Curriculum> {
+        let tag: Option<String> = self.db_manager.execute(|conn| {
+            conn.query_row(
+                &format!("SELECT curriculum FROM curriculum_settings WHERE household_id = {}", ACTIVE_HOUSEHOLD_SQL),
+                [],
+                |row| row.get(0),
+            ).optional()
+        })?;
+
+        Ok(match tag {
+            Some(tag) => Curriculum::from_tag(&tag).unwrap_or_else(|| {
+                log::warn!("Unknown curriculum '{}', using {}", tag, Curriculum::default().as_str());
+                Curriculum::default()
+            }),
+            None => Curriculum::default(),
+        })
+    }
+
+    /// Save the active household's curriculum
+    pub fn set_curriculum(&self, curriculum: Curriculum) -> AppResult<Curriculum> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO curriculum_settings (household_id, curriculum, updated_at) VALUES ({}, ?1, ?2)",
+                    ACTIVE_HOUSEHOLD_SQL
+                ),
+                params![curriculum.as_str(), Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+        self.get_curriculum()
+    }
+
+    /// Every key stage as `curriculum` knows it, youngest first
+    pub fn get_levels(&self, curriculum: Curriculum) -> AppResult<Vec<CurriculumLevel>> {
+        let rows: Vec<(String, String, u8, u8)> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT key_stage, label, min_difficulty, max_difficulty FROM curriculum_levels WHERE curriculum = ?1"
+            )?;
+            let rows = stmt.query_map(params![curriculum.as_str()], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?;
+            rows.collect()
+        })?;
+
+        Ok(KeyStage::ALL.iter()
+            .map(|&key_stage| {
+                rows.iter()
+                    .find(|(stored, ..)| KeyStage::from_str(stored).ok() == Some(key_stage))
+                    .map(|(_, label, min_difficulty, max_difficulty)| CurriculumLevel {
+                        curriculum,
+                        key_stage,
+                        label: label.clone(),
+                        min_difficulty: *min_difficulty,
+                        max_difficulty: *max_difficulty,
+                    })
+                    .unwrap_or_else(|| CurriculumLevel::unmapped(curriculum, key_stage))
+            })
+            .collect())
+    }
+
+    /// One key stage as the active household's curriculum knows it
+    pub fn level(&self, key_stage: KeyStage) -> AppResult<CurriculumLevel> {
+        let levels = self.get_levels(self.get_curriculum()?)?;
+        Ok(levels.into_iter()
+            .find(|level| level.key_stage == key_stage)
+            .unwrap_or_else(|| CurriculumLevel::unmapped(Curriculum::default(), key_stage)))
+    }
+
+    /// What the active household's curriculum calls a key stage
+    pub fn label(&self, key_stage: KeyStage) -> AppResult<String> {
+        Ok(self.level(key_stage)?.label)
+    }
+
+    /// Rename a key stage or change the difficulties expected there
+    pub fn update_level(&self, level: CurriculumLevel) -> AppResult<CurriculumLevel> {
+        let label = level.label.trim();
+        if label.is_empty() {
+            return Err(AppError::InvalidInput("A curriculum level needs a name".to_string()));
+        }
+        if level.min_difficulty < 1 || level.max_difficulty > 5 || level.min_difficulty > level.max_difficulty {
+            return Err(AppError::InvalidInput("Difficulties must be a range within 1 to 5".to_string()));
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO curriculum_levels (curriculum, key_stage, label, min_difficulty, max_difficulty)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![level.curriculum.as_str(), level.key_stage.as_str(), label, level.min_difficulty, level.max_difficulty],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(CurriculumLevel { label: label.to_string(), ..level })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_curriculum_relabels_key_stages_and_narrows_difficulty() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let service = CurriculumService::new(db_manager.clone());
+        assert_eq!(service.get_curriculum().unwrap(), Curriculum::England);
+        assert_eq!(service.label(KeyStage::KS2).unwrap(), "KS2");
+        assert_eq!(service.level(KeyStage::KS2).unwrap().difficulty_range(), None);
+
+        assert_eq!(service.set_curriculum(Curriculum::Scotland).unwrap(), Curriculum::Scotland);
+        assert_eq!(service.label(KeyStage::KS2).unwrap(), "Second Level");
+        assert_eq!(service.level(KeyStage::KS2).unwrap().difficulty_range(), Some((2, 5)));
+        let labels: Vec<String> = service.get_levels(Curriculum::UnitedStates).unwrap()
+            .into_iter().map(|level| level.label).collect();
+        assert_eq!(labels, vec!["Pre-K", "Kindergarten-Grade 1", "Grades 2-5", "Grades 6-8"]);
+
+        // The same expectations apply to questions picked in SQL
+        let count_ks2 = |min_difficulty: u8| -> u32 {
+            db_manager.execute(|conn| conn.query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM (SELECT 'KS2' AS key_stage, ?1 AS difficulty_level) q WHERE {}",
+                    CURRICULUM_DIFFICULTY_SQL
+                ),
+                params![min_difficulty],
+                |row| row.get(0),
+            )).unwrap()
+        };
+        assert_eq!((count_ks2(1), count_ks2(2)), (0, 1));
+
+        let mut level = service.level(KeyStage::KS2).unwrap();
+        level.min_difficulty = 4;
+        assert!(service.update_level(CurriculumLevel { max_difficulty: 3, ..level.clone() }).is_err());
+        service.update_level(level).unwrap();
+        assert_eq!(service.level(KeyStage::KS2).unwrap().difficulty_range(), Some((4, 5)));
+    }
+}
@@ -1,6 +1,8 @@
 use crate::errors::{AppError, AppResult};
-use crate::models::{CustomMix, CreateMixRequest, UpdateMixRequest, MixConfig, KeyStage};
+use crate::models::{CustomMix, CreateMixRequest, UpdateMixRequest, MixConfig, EntityId, MixId, ProfileId, QuestionId};
 use crate::database::DatabaseManager;
+use crate::services::households::{HOUSEHOLD_PROFILES_SQL, OTHER_HOUSEHOLD_QUESTIONS_SQL};
+use crate::services::curriculum_profiles::CURRICULUM_DIFFICULTY_SQL;
 use std::sync::Arc;
 use rusqlite::{params, Row};
 use serde_json;
@@ -28,25 +30,27 @@ impl CustomMixManager {
             let config_json = serde_json::to_string(&mix.config)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
 
+            // Set updated_at too; the column's CURRENT_TIMESTAMP default isn't RFC 3339
+            let now = Utc::now().to_rfc3339();
             tx.execute(
-                "INSERT INTO custom_mixes (name, created_by, config, created_at)
-                 VALUES (?1, ?2, ?3, ?4)",
+                "INSERT INTO custom_mixes (name, created_by, config, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?4)",
                 params![
                     mix.name,
                     mix.created_by,
                     config_json,
-                    Utc::now().to_rfc3339()
+                    now
                 ],
             )?;
 
-            Ok(tx.last_insert_rowid() as u32)
+            Ok(MixId(tx.last_insert_rowid() as u32))
         })?;
 
         self.get_custom_mix_by_id(mix_id)
     }
 
     /// Get a custom mix by ID
-    pub fn get_custom_mix_by_id(&self, mix_id: u32) -> AppResult<CustomMix> {
+    pub fn get_custom_mix_by_id(&self, mix_id: MixId) -> AppResult<CustomMix> {
         self.db_manager.execute(|conn| {
             let mut stmt = conn.prepare(
                 "SELECT id, name, created_by, config, created_at, updated_at
@@ -60,19 +64,20 @@ impl CustomMixManager {
             Ok(mix)
         }).map_err(|e| match e {
             crate::database::DatabaseError::Sqlite(rusqlite::Error::QueryReturnedNoRows) => {
-                AppError::NotFound(format!("Custom mix with id {} not found", mix_id))
+                mix_id.not_found()
             }
             _ => AppError::DatabaseConnection(e),
         })
     }
 
-    /// Get all custom mixes
+    /// Get all custom mixes made in the active household
     pub fn get_all_custom_mixes(&self) -> AppResult<Vec<CustomMix>> {
         Ok(self.db_manager.execute(|conn| {
-            let mut stmt = conn.prepare(
+            let mut stmt = conn.prepare(&format!(
                 "SELECT id, name, created_by, config, created_at, updated_at
-                 FROM custom_mixes ORDER BY created_at DESC"
-            )?;
+                 FROM custom_mixes WHERE created_by IN ({}) ORDER BY created_at DESC",
+                HOUSEHOLD_PROFILES_SQL
+            ))?;
 
             let mix_iter = stmt.query_map([], |row| {
                 Ok(self.row_to_custom_mix(row)?)
@@ -88,7 +93,7 @@ impl CustomMixManager {
     }
 
     /// Get custom mixes created by a specific profile
-    pub fn get_custom_mixes_by_profile(&self, profile_id: u32) -> AppResult<Vec<CustomMix>> {
+    pub fn get_custom_mixes_by_profile(&self, profile_id: ProfileId) -> AppResult<Vec<CustomMix>> {
         Ok(self.db_manager.execute(|conn| {
             let mut stmt = conn.prepare(
                 "SELECT id, name, created_by, config, created_at, updated_at
@@ -109,7 +114,7 @@ impl CustomMixManager {
     }
 
     /// Update an existing custom mix
-    pub fn update_custom_mix(&self, mix_id: u32, updates: UpdateMixRequest) -> AppResult<CustomMix> {
+    pub fn update_custom_mix(&self, mix_id: MixId, updates: UpdateMixRequest) -> AppResult<CustomMix> {
         // Verify mix exists
         let _existing_mix = self.get_custom_mix_by_id(mix_id)?;
 
@@ -163,7 +168,7 @@ impl CustomMixManager {
     }
 
     /// Delete a custom mix
-    pub fn delete_custom_mix(&self, mix_id: u32) -> AppResult<()> {
+    pub fn delete_custom_mix(&self, mix_id: MixId) -> AppResult<()> {
         // Verify mix exists
         let _existing_mix = self.get_custom_mix_by_id(mix_id)?;
 
@@ -181,80 +186,31 @@ impl CustomMixManager {
     /// Get available question count for a mix configuration
     pub fn get_available_question_count(&self, config: &MixConfig) -> AppResult<u32> {
         Ok(self.db_manager.execute(|conn| {
-            let mut query = "SELECT COUNT(DISTINCT q.id) FROM questions q
-                             JOIN subjects s ON q.subject_id = s.id
-                             WHERE 1=1".to_string();
-
-            let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-            let mut param_index = 1;
+            let (query, params_vec) = mix_question_query("COUNT(DISTINCT q.id)", config);
 
-            // Filter by subjects
-            if !config.subjects.is_empty() {
-                let placeholders: Vec<String> = config.subjects.iter()
-                    .map(|_| {
-                        let placeholder = format!("?{}", param_index);
-                        param_index += 1;
-                        placeholder
-                    })
-                    .collect();
-                query.push_str(&format!(" AND s.name IN ({})", placeholders.join(", ")));
-                
-                for subject in &config.subjects {
-                    params_vec.push(Box::new(subject.clone()));
-                }
-            }
+            let mut stmt = conn.prepare(&query)?;
+            let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter()
+                .map(|p| p.as_ref())
+                .collect();
 
-            // Filter by key stages
-            if !config.key_stages.is_empty() {
-                let placeholders: Vec<String> = config.key_stages.iter()
-                    .map(|_| {
-                        let placeholder = format!("?{}", param_index);
-                        param_index += 1;
-                        placeholder
-                    })
-                    .collect();
-                query.push_str(&format!(" AND q.key_stage IN ({})", placeholders.join(", ")));
-                
-                for key_stage in &config.key_stages {
-                    let ks_str = match key_stage {
-                        KeyStage::KS1 => "KS1",
-                        KeyStage::KS2 => "KS2",
-                    };
-                    params_vec.push(Box::new(ks_str.to_string()));
-                }
-            }
+            let count: i32 = stmt.query_row(params_refs.as_slice(), |row| row.get(0))?;
+            Ok(count as u32)
+        })?)
+    }
 
-            // Filter by difficulty range
-            query.push_str(&format!(" AND q.difficulty_level BETWEEN ?{} AND ?{}", param_index, param_index + 1));
-            params_vec.push(Box::new(config.difficulty_range.0));
-            params_vec.push(Box::new(config.difficulty_range.1));
-            param_index += 2;
-
-            // Filter by question types if specified
-            if let Some(ref question_types) = config.question_types {
-                if !question_types.is_empty() {
-                    let placeholders: Vec<String> = question_types.iter()
-                        .map(|_| {
-                            let placeholder = format!("?{}", param_index);
-                            param_index += 1;
-                            placeholder
-                        })
-                        .collect();
-                    query.push_str(&format!(" AND q.question_type IN ({})", placeholders.join(", ")));
-                    
-                    for question_type in question_types {
-                        params_vec.push(Box::new(question_type.clone()));
-                    }
-                }
-            }
+    /// Draw question ids for a mix at random, up to its question count
+    pub fn pick_question_ids(&self, config: &MixConfig) -> AppResult<Vec<QuestionId>> {
+        Ok(self.db_manager.execute(|conn| {
+            let (mut query, params_vec) = mix_question_query("DISTINCT q.id", config);
+            query.push_str(&format!(" ORDER BY RANDOM() LIMIT {}", config.question_count));
 
             let mut stmt = conn.prepare(&query)?;
             let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter()
                 .map(|p| p.as_ref())
                 .collect();
 
-            let count: i32 = stmt.query_row(params_refs.as_slice(), |row| row.get(0))?;
-            Ok(count as u32)
+            let ids = stmt.query_map(params_refs.as_slice(), |row| row.get(0))?;
+            ids.collect()
         })?)
     }
 
@@ -294,9 +250,9 @@ impl CustomMixManager {
         };
 
         Ok(CustomMix {
-            id: Some(row.get::<_, u32>(0)?),
+            id: Some(row.get(0)?),
             name: row.get::<_, String>(1)?,
-            created_by: row.get::<_, u32>(2)?,
+            created_by: row.get(2)?,
             config,
             created_at: Some(created_at),
             updated_at,
@@ -304,6 +260,98 @@ impl CustomMixManager {
     }
 }
 
+/// Query selecting `columns` from the questions a mix configuration can draw on, with its parameters
+fn mix_question_query(columns: &str, config: &MixConfig) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut query = format!(
+        "SELECT {} FROM questions q
+         JOIN subjects s ON q.subject_id = s.id
+         WHERE q.id NOT IN (SELECT question_id FROM retired_questions)
+           AND q.id NOT IN ({})",
+        columns, OTHER_HOUSEHOLD_QUESTIONS_SQL
+    );
+
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let mut param_index = 1;
+
+    // Filter by subjects
+    if !config.subjects.is_empty() {
+        let placeholders: Vec<String> = config.subjects.iter()
+            .map(|_| {
+                let placeholder = format!("?{}", param_index);
+                param_index += 1;
+                placeholder
+            })
+            .collect();
+        query.push_str(&format!(" AND s.name IN ({})", placeholders.join(", ")));
+        
+        for subject in &config.subjects {
+            params_vec.push(Box::new(subject.clone()));
+        }
+    }
+
+    // Filter by key stages
+    if !config.key_stages.is_empty() {
+        let placeholders: Vec<String> = config.key_stages.iter()
+            .map(|_| {
+                let placeholder = format!("?{}", param_index);
+                param_index += 1;
+                placeholder
+            })
+            .collect();
+        query.push_str(&format!(" AND q.key_stage IN ({})", placeholders.join(", ")));
+        
+        for key_stage in &config.key_stages {
+            params_vec.push(Box::new(key_stage.as_str().to_string()));
+        }
+    }
+
+    // Filter by difficulty range, within what the family's curriculum expects at each key stage
+    query.push_str(&format!(" AND {}", CURRICULUM_DIFFICULTY_SQL));
+    query.push_str(&format!(" AND q.difficulty_level BETWEEN ?{} AND ?{}", param_index, param_index + 1));
+    params_vec.push(Box::new(config.difficulty_range.0));
+    params_vec.push(Box::new(config.difficulty_range.1));
+    param_index += 2;
+
+    // Filter by question types if specified
+    if let Some(ref question_types) = config.question_types {
+        if !question_types.is_empty() {
+            let placeholders: Vec<String> = question_types.iter()
+                .map(|_| {
+                    let placeholder = format!("?{}", param_index);
+                    param_index += 1;
+                    placeholder
+                })
+                .collect();
+            query.push_str(&format!(" AND q.question_type IN ({})", placeholders.join(", ")));
+            
+            for question_type in question_types {
+                params_vec.push(Box::new(question_type.clone()));
+            }
+        }
+    }
+
+    // Filter by tags if specified
+    if !config.tags.is_empty() {
+        let placeholders: Vec<String> = config.tags.iter()
+            .map(|_| {
+                let placeholder = format!("?{}", param_index);
+                param_index += 1;
+                placeholder
+            })
+            .collect();
+        query.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM json_each(q.tags) WHERE value IN ({}))",
+            placeholders.join(", ")
+        ));
+
+        for tag in &config.tags {
+            params_vec.push(Box::new(tag.clone()));
+        }
+    }
+
+    (query, params_vec)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,7 +383,7 @@ mod tests {
         
         let request = CreateMixRequest {
             name: "Test Mix".to_string(),
-            created_by: 1,
+            created_by: ProfileId(1),
             config,
         };
         
@@ -344,7 +392,7 @@ mod tests {
         
         let mix = result.unwrap();
         assert_eq!(mix.name, "Test Mix");
-        assert_eq!(mix.created_by, 1);
+        assert_eq!(mix.created_by, ProfileId(1));
         assert!(mix.id.is_some());
     }
 
@@ -361,7 +409,7 @@ mod tests {
         
         let request = CreateMixRequest {
             name: "Test Mix".to_string(),
-            created_by: 1,
+            created_by: ProfileId(1),
             config,
         };
         
@@ -387,7 +435,7 @@ mod tests {
         
         let request = CreateMixRequest {
             name: "Original Mix".to_string(),
-            created_by: 1,
+            created_by: ProfileId(1),
             config,
         };
         
@@ -418,7 +466,7 @@ mod tests {
         
         let request = CreateMixRequest {
             name: "Test Mix".to_string(),
-            created_by: 1,
+            created_by: ProfileId(1),
             config,
         };
         
@@ -453,13 +501,13 @@ mod tests {
         
         let request1 = CreateMixRequest {
             name: "Profile 1 Mix".to_string(),
-            created_by: 1,
+            created_by: ProfileId(1),
             config: config1,
         };
         
         let request2 = CreateMixRequest {
             name: "Profile 2 Mix".to_string(),
-            created_by: 2,
+            created_by: ProfileId(2),
             config: config2,
         };
         
@@ -467,12 +515,12 @@ mod tests {
         manager.create_custom_mix(request2).unwrap();
         
         // Get mixes for profile 1
-        let profile1_mixes = manager.get_custom_mixes_by_profile(1).unwrap();
+        let profile1_mixes = manager.get_custom_mixes_by_profile(ProfileId(1)).unwrap();
         assert_eq!(profile1_mixes.len(), 1);
         assert_eq!(profile1_mixes[0].name, "Profile 1 Mix");
         
         // Get mixes for profile 2
-        let profile2_mixes = manager.get_custom_mixes_by_profile(2).unwrap();
+        let profile2_mixes = manager.get_custom_mixes_by_profile(ProfileId(2)).unwrap();
         assert_eq!(profile2_mixes.len(), 1);
         assert_eq!(profile2_mixes[0].name, "Profile 2 Mix");
     }
@@ -0,0 +1,254 @@
+use crate::errors::AppResult;
+use crate::database::{DatabaseManager, MigrationManager, PoolStats};
+use crate::services::{ContentManager, ContentStatistics, CrashReporter, StorageManager};
+use std::sync::Arc;
+use rusqlite::params;
+use serde::Serialize;
+use chrono::{DateTime, Utc};
+
+/// Most recent errors included in a diagnostics report
+const RECENT_ERROR_LIMIT: usize = 10;
+
+/// State of the database file itself
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseHealth {
+    pub schema_version: u32,
+    /// Whether SQLite's quick integrity check passed
+    pub integrity_ok: bool,
+    /// The first problem the integrity check found, if any
+    pub integrity_problem: Option<String>,
+    pub size_bytes: u64,
+    /// Space held by deleted rows that compaction hasn't handed back yet
+    pub free_bytes: u64,
+    pub journal_mode: String,
+}
+
+/// What's known about content updates without contacting the update servers
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateStatus {
+    pub content_version: Option<String>,
+    pub auto_check: bool,
+    pub repositories: usize,
+    /// Why the update service couldn't be queried, if it couldn't
+    pub error: Option<String>,
+}
+
+/// Where a recent error was recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorSource {
+    /// The app panicked and wrote a crash report
+    Crash,
+    /// A question failed during a quiz and was replaced
+    Quiz,
+    /// A weekly progress email couldn't be sent
+    Email,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentError {
+    pub source: ErrorSource,
+    pub message: String,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Everything a diagnostics screen or support export needs in one place
+#[derive(Debug, Clone, Serialize)]
+pub struct AppDiagnostics {
+    pub generated_at: DateTime<Utc>,
+    pub app_version: String,
+    pub database: DatabaseHealth,
+    pub pool: PoolStats,
+    pub pending_migrations: Vec<u32>,
+    pub last_backup_at: Option<DateTime<Utc>>,
+    pub content: ContentStatistics,
+    pub update: UpdateStatus,
+    /// Newest first
+    pub recent_errors: Vec<RecentError>,
+}
+
+/// Gathers app health from the database, storage, content and crash logs
+pub struct DiagnosticsService {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+    storage: Arc<StorageManager>,
+    crash_reporter: Arc<CrashReporter>,
+}
+
+impl DiagnosticsService {
+    /// Create a new diagnostics service
+    pub fn new(
+        db_manager: Arc<DatabaseManager>,
+        content_manager: Arc<ContentManager>,
+        storage: Arc<StorageManager>,
+        crash_reporter: Arc<CrashReporter>,
+    ) -> Self {
+        Self {
+            db_manager,
+            content_manager,
+            storage,
+            crash_reporter,
+        }
+    }
+
+    /// Collect a diagnostics report. The update service is loaded lazily and checked
+    /// asynchronously, so its status is passed in by the caller.
+    pub fn collect(&self, update: UpdateStatus) -> AppResult<AppDiagnostics> {
+        let migration_manager = MigrationManager::new();
+        let (database, pending_migrations, logged_errors) = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("PRAGMA quick_check")?;
+            let problems: Vec<String> = stmt.query_map([], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            let integrity_ok = problems.iter().all(|problem| problem == "ok");
+            let (size_bytes, free_bytes): (i64, i64) = conn.query_row(
+                "SELECT page_count * page_size, freelist_count * page_size
+                 FROM pragma_page_count(), pragma_page_size(), pragma_freelist_count()",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            let database = DatabaseHealth {
+                schema_version: migration_manager.get_current_version(conn)?,
+                integrity_ok,
+                integrity_problem: if integrity_ok { None } else { problems.into_iter().next() },
+                size_bytes: size_bytes as u64,
+                free_bytes: free_bytes as u64,
+                journal_mode: conn.query_row("PRAGMA journal_mode", [], |row| row.get(0))?,
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT stage, error, occurred_at FROM quiz_incidents ORDER BY occurred_at DESC LIMIT ?1",
+            )?;
+            let mut logged_errors = stmt.query_map(params![RECENT_ERROR_LIMIT as u32], |row| {
+                Ok(RecentError {
+                    source: ErrorSource::Quiz,
+                    message: format!("{}: {}", row.get::<_, String>(0)?, row.get::<_, String>(1)?),
+                    occurred_at: parse_occurred_at(row, 2)?,
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT report_date, error, attempted_at FROM email_report_log
+                 WHERE NOT succeeded ORDER BY attempted_at DESC LIMIT ?1",
+            )?;
+            let email_errors = stmt.query_map(params![RECENT_ERROR_LIMIT as u32], |row| {
+                Ok(RecentError {
+                    source: ErrorSource::Email,
+                    message: format!(
+                        "Progress email for {}: {}",
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<String>>(1)?.unwrap_or_else(|| "unknown error".to_string())
+                    ),
+                    occurred_at: parse_occurred_at(row, 2)?,
+                })
+            })?;
+            for error in email_errors {
+                logged_errors.push(error?);
+            }
+
+            Ok((database, migration_manager.get_pending_migrations(conn)?, logged_errors))
+        })?;
+
+        let mut recent_errors: Vec<RecentError> = self.crash_reporter.list_reports()?
+            .into_iter()
+            .take(RECENT_ERROR_LIMIT)
+            .map(|report| RecentError {
+                source: ErrorSource::Crash,
+                message: report.message,
+                occurred_at: report.occurred_at,
+            })
+            .chain(logged_errors)
+            .collect();
+        recent_errors.sort_by_key(|error| std::cmp::Reverse(error.occurred_at));
+        recent_errors.truncate(RECENT_ERROR_LIMIT);
+
+        Ok(AppDiagnostics {
+            generated_at: Utc::now(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            database,
+            pool: self.db_manager.get_pool_stats()?,
+            pending_migrations,
+            last_backup_at: self.storage.last_backup_at()?,
+            content: self.content_manager.get_content_statistics()?,
+            update,
+            recent_errors,
+        })
+    }
+}
+
+/// An RFC 3339 timestamp column as a UTC time
+fn parse_occurred_at(row: &rusqlite::Row, index: usize) -> rusqlite::Result<DateTime<Utc>> {
+    let occurred_at: String = row.get(index)?;
+    DateTime::parse_from_rfc3339(&occurred_at)
+        .map(|date| date.with_timezone(&Utc))
+        .map_err(|_| rusqlite::Error::InvalidColumnType(index, "occurred_at".to_string(), rusqlite::types::Type::Text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::{SecurityService, StorageLocations};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_diagnostics_cover_database_backups_and_errors() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let locations = StorageLocations {
+            asset_directory: temp_dir.path().join("assets"),
+            backup_directory: temp_dir.path().join("backups"),
+            log_directory: temp_dir.path().join("crashes"),
+        };
+        fs::create_dir_all(locations.backup_directory.join("backup_20240301_120000")).unwrap();
+        let service = DiagnosticsService::new(
+            db_manager.clone(),
+            Arc::new(ContentManager::new(
+                db_manager.clone(),
+                Arc::new(SecurityService::new().unwrap()),
+                temp_dir.path().join("content"),
+            )),
+            Arc::new(StorageManager::new(db_manager.clone(), locations.clone())),
+            Arc::new(CrashReporter::new(locations.log_directory.clone(), None).unwrap()),
+        );
+
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            conn.execute(
+                "INSERT INTO quiz_incidents (session_id, profile_id, stage, error, occurred_at)
+                 VALUES (1, ?1, 'display', 'missing image', ?2)",
+                params![conn.last_insert_rowid(), Utc::now().to_rfc3339()],
+            )?;
+            conn.execute(
+                "INSERT INTO email_report_log (report_date, attempted_at, succeeded, error)
+                 VALUES ('2024-03-01', ?1, 0, 'connection refused'), ('2024-02-23', ?1, 1, NULL)",
+                params![(Utc::now() - chrono::Duration::hours(1)).to_rfc3339()],
+            )
+        }).unwrap();
+
+        let diagnostics = service.collect(UpdateStatus {
+            content_version: Some("1.0.0".to_string()),
+            auto_check: true,
+            repositories: 1,
+            error: None,
+        }).unwrap();
+        assert!(diagnostics.database.integrity_ok);
+        assert_eq!(diagnostics.database.schema_version, db_service.get_version().unwrap());
+        assert!(diagnostics.pending_migrations.is_empty());
+        assert_eq!(diagnostics.last_backup_at.unwrap().to_rfc3339(), "2024-03-01T12:00:00+00:00");
+        assert_eq!(diagnostics.recent_errors.len(), 2);
+        assert_eq!(diagnostics.recent_errors[0].source, ErrorSource::Quiz);
+        assert_eq!(diagnostics.recent_errors[0].message, "display: missing image");
+        assert_eq!(diagnostics.recent_errors[1].source, ErrorSource::Email);
+        assert_eq!(diagnostics.recent_errors[1].message, "Progress email for 2024-03-01: connection refused");
+
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert!(json["pool"]["max_connections"].as_u64().unwrap() > 0);
+    }
+}
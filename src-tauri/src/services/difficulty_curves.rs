@@ -0,0 +1,213 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{KeyStage, ProfileId};
+use crate::database::{row_exists, DatabaseManager};
+use crate::services::QuizConfig;
+use std::str::FromStr;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+
+/// A parent's override of the level a profile plays one subject at, e.g. KS2 maths for a
+/// child who is otherwise KS1
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DifficultyCurve {
+    pub profile_id: ProfileId,
+    /// Subject name, e.g. `mathematics`
+    pub subject: String,
+    /// Key stage questions are drawn from for this subject
+    pub key_stage: KeyStage,
+    /// Difficulty band within that key stage; `None` leaves the quiz's own range
+    pub difficulty_range: Option<(u8, u8)>,
+}
+
+/// Per-profile, per-subject key stage and difficulty overrides, applied to every quiz
+/// before its questions are chosen
+pub struct DifficultyCurveService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl DifficultyCurveService {
+    /// Create a new difficulty curve service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Every override set for a profile, by subject
+    pub fn get_curves(&self, profile_id: ProfileId) -> AppResult<Vec<DifficultyCurve>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT c.profile_id, s.name, c.key_stage, c.min_difficulty, c.max_difficulty
+                 FROM difficulty_curves c JOIN subjects s ON s.id = c.subject_id
+                 WHERE c.profile_id = ?1 ORDER BY s.name"
+            )?;
+            let rows = stmt.query_map(params![profile_id], Self::row_to_curve)?;
+            rows.collect()
+        })?)
+    }
+
+    /// The override for one subject, if a parent has set one
+    pub fn get_curve(&self, profile_id: ProfileId, subject: &str) -> AppResult<Option<DifficultyCurve>> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT c.profile_id, s.name, c.key_stage, c.min_difficulty, c.max_difficulty
+                 FROM difficulty_curves c JOIN subjects s ON s.id = c.subject_id
+                 WHERE c.profile_id = ?1 AND s.name = ?2",
+                params![profile_id, subject],
+                Self::row_to_curve,
+            ).optional()
+        })?)
+    }
+
+    /// Set (or replace) the override for a profile and subject
+    pub fn set_curve(&self, curve: &DifficultyCurve) -> AppResult<DifficultyCurve> {
+        if let Some((min, max)) = curve.difficulty_range {
+            if min < 1 || max > 5 || min > max {
+                return Err(AppError::InvalidInput(
+                    "Difficulty ranges run from 1 to 5, lowest first".to_string()
+                ));
+            }
+        }
+
+        let (profile_exists, subject_id) = self.db_manager.execute(|conn| {
+            let profile_exists = row_exists(conn, curve.profile_id)?;
+            let subject_id: Option<u32> = conn.query_row(
+                "SELECT id FROM subjects WHERE name = ?1",
+                params![curve.subject],
+                |row| row.get(0),
+            ).optional()?;
+            Ok((profile_exists, subject_id))
+        })?;
+
+        if !profile_exists {
+            return Err(AppError::ProfileNotFound { id: curve.profile_id });
+        }
+        let subject_id = subject_id
+            .ok_or_else(|| AppError::NotFound(format!("Subject '{}' not found", curve.subject)))?;
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO difficulty_curves
+                 (profile_id, subject_id, key_stage, min_difficulty, max_difficulty, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    curve.profile_id,
+                    subject_id,
+                    curve.key_stage.as_str(),
+                    curve.difficulty_range.map(|(min, _)| min),
+                    curve.difficulty_range.map(|(_, max)| max),
+                    Utc::now().to_rfc3339()
+                ],
+            )
+        })?;
+
+        Ok(curve.clone())
+    }
+
+    /// Remove a subject's override so the profile's usual key stage applies again
+    pub fn clear_curve(&self, profile_id: ProfileId, subject: &str) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "DELETE FROM difficulty_curves
+                 WHERE profile_id = ?1 AND subject_id = (SELECT id FROM subjects WHERE name = ?2)",
+                params![profile_id, subject],
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Point a quiz at the key stage and difficulty a parent chose for this subject.
+    /// The parent's choice wins over whatever the quiz asked for.
+    pub fn adjust_config(&self, profile_id: ProfileId, config: &mut QuizConfig) -> AppResult<()> {
+        if let Some(curve) = self.get_curve(profile_id, &config.subject)? {
+            config.key_stage = curve.key_stage;
+            if curve.difficulty_range.is_some() {
+                config.difficulty_range = curve.difficulty_range;
+            }
+        }
+        Ok(())
+    }
+
+    fn row_to_curve(row: &Row) -> rusqlite::Result<DifficultyCurve> {
+        let key_stage: String = row.get(2)?;
+        let min: Option<u8> = row.get(3)?;
+        let max: Option<u8> = row.get(4)?;
+
+        Ok(DifficultyCurve {
+            profile_id: row.get(0)?,
+            subject: row.get(1)?,
+            key_stage: KeyStage::from_str(&key_stage)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(2, "key_stage".to_string(), rusqlite::types::Type::Text))?,
+            difficulty_range: min.zip(max),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::{LateAnswer, QuizMode, DEFAULT_HINT_PENALTY_PERCENT};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_curve_overrides_key_stage_for_one_subject() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let profile_id = db_service.manager().execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+
+        let service = DifficultyCurveService::new(db_service.manager());
+        let mut curve = DifficultyCurve {
+            profile_id,
+            subject: "mathematics".to_string(),
+            key_stage: KeyStage::KS2,
+            difficulty_range: Some((4, 2)),
+        };
+        assert!(service.set_curve(&curve).is_err());
+        curve.difficulty_range = Some((1, 3));
+        service.set_curve(&curve).unwrap();
+        assert!(service.set_curve(&DifficultyCurve { profile_id: ProfileId(999), ..curve.clone() }).is_err());
+
+        let mut maths = QuizConfig {
+            subject: "mathematics".to_string(),
+            key_stage: KeyStage::KS1,
+            question_count: 10,
+            difficulty_range: None,
+            time_limit_seconds: None,
+            randomize_questions: true,
+            randomize_answers: true,
+            max_consecutive_same_tag: None,
+            boss_question: false,
+            warm_up_questions: 0,
+            cool_down_review: false,
+            hint_penalty_percent: DEFAULT_HINT_PENALTY_PERCENT,
+            time_limit_per_question: None,
+            time_limit_total: None,
+            late_answers: LateAnswer::default(),
+            stop_when_mastered: false,
+            mode: QuizMode::default(),
+            allowed_question_types: Vec::new(),
+        };
+        let mut english = QuizConfig { subject: "english".to_string(), ..maths.clone() };
+
+        service.adjust_config(profile_id, &mut maths).unwrap();
+        service.adjust_config(profile_id, &mut english).unwrap();
+        assert_eq!(maths.key_stage, KeyStage::KS2);
+        assert_eq!(maths.difficulty_range, Some((1, 3)));
+        assert_eq!(english.key_stage, KeyStage::KS1);
+        assert_eq!(service.get_curves(profile_id).unwrap(), vec![curve]);
+
+        service.clear_curve(profile_id, "mathematics").unwrap();
+        assert!(service.get_curves(profile_id).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+
+/// The four operations children meet in arithmetic questions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    fn apply(self, left: i64, right: i64) -> Option<i64> {
+        match self {
+            Operation::Add => left.checked_add(right),
+            Operation::Subtract => left.checked_sub(right),
+            Operation::Multiply => left.checked_mul(right),
+            Operation::Divide if right != 0 && left % right == 0 => Some(left / right),
+            Operation::Divide => None,
+        }
+    }
+
+    fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '+' => Some(Operation::Add),
+            '-' | '−' => Some(Operation::Subtract),
+            '×' | 'x' | '*' => Some(Operation::Multiply),
+            '÷' | '/' => Some(Operation::Divide),
+            _ => None,
+        }
+    }
+}
+
+/// A single-step sum such as `7 × 8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArithmeticProblem {
+    pub left: i64,
+    pub operation: Operation,
+    pub right: i64,
+}
+
+impl ArithmeticProblem {
+    pub fn new(left: i64, operation: Operation, right: i64) -> Self {
+        Self { left, operation, right }
+    }
+
+    /// Find the sum in question text like "What is 7 × 8?". Returns `None` if there isn't
+    /// one, or it doesn't work out to a whole number.
+    pub fn parse(text: &str) -> Option<Self> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut index = 0;
+        while index < chars.len() {
+            if chars[index].is_ascii_digit() {
+                let (left, after_left) = read_number(&chars, index);
+                let left = left.map(|left| if is_negated(&chars, index) { -left } else { left });
+                let operator_index = skip_spaces(&chars, after_left);
+                let operation = chars.get(operator_index).copied().and_then(Operation::from_symbol);
+                let mut right_index = skip_spaces(&chars, operator_index + 1);
+                if matches!(chars.get(right_index), Some('-' | '−')) {
+                    right_index += 1;
+                }
+                if let (Some(operation), Some(true)) = (operation, chars.get(right_index).map(|c| c.is_ascii_digit())) {
+                    let (right, _) = read_number(&chars, right_index);
+                    let right = right.map(|right| if is_negated(&chars, right_index) { -right } else { right });
+                    let problem = Self::new(left?, operation, right?);
+                    return problem.answer().map(|_| problem);
+                }
+                index = after_left;
+            } else {
+                index += 1;
+            }
+        }
+        None
+    }
+
+    /// The right answer, if it is a whole number
+    pub fn answer(&self) -> Option<i64> {
+        self.operation.apply(self.left, self.right)
+    }
+}
+
+fn read_number(chars: &[char], start: usize) -> (Option<i64>, usize) {
+    let end = chars[start..].iter().position(|c| !c.is_ascii_digit()).map_or(chars.len(), |length| start + length);
+    (chars[start..end].iter().collect::<String>().parse().ok(), end)
+}
+
+/// Whether the number starting at `start` has a minus sign of its own, rather than one
+/// that subtracts it from a number before
+fn is_negated(chars: &[char], start: usize) -> bool {
+    if start == 0 || !matches!(chars[start - 1], '-' | '−') {
+        return false;
+    }
+    !chars[..start - 1].iter().rev().find(|c| !c.is_whitespace()).map_or(false, |c| c.is_ascii_digit())
+}
+
+fn skip_spaces(chars: &[char], start: usize) -> usize {
+    chars[start.min(chars.len())..].iter().position(|c| !c.is_whitespace()).map_or(chars.len(), |length| start + length)
+}
+
+/// The mistake a wrong option stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DistractorKind {
+    /// Miscounting by one
+    OffByOne,
+    /// Recalling a neighbouring fact, e.g. 7 × 7 for 7 × 8
+    NeighbouringFact,
+    /// Writing the digits the wrong way round
+    DigitSwap,
+    /// Doing a different operation, e.g. adding instead of multiplying
+    WrongOperation,
+    /// Slipping a place value, e.g. forgetting to carry a ten
+    OffByTen,
+}
+
+/// A plausible wrong answer and the mistake behind it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Distractor {
+    pub value: String,
+    pub kind: DistractorKind,
+}
+
+/// Plausible wrong options for an arithmetic question, one mistake of each kind before
+/// repeating any. Easier questions lead with mistakes that are easy to rule out (a
+/// different operation, a slipped ten), harder ones with near misses (off by one, a
+/// neighbouring fact). Returns `None` if the problem has no whole-number answer.
+pub fn generate_distractors(problem: &ArithmeticProblem, difficulty: u8, count: usize) -> Option<Vec<Distractor>> {
+    let answer = problem.answer()?;
+    let preference: &[DistractorKind] = if difficulty <= 2 {
+        &[
+            DistractorKind::WrongOperation,
+            DistractorKind::OffByTen,
+            DistractorKind::DigitSwap,
+            DistractorKind::OffByOne,
+            DistractorKind::NeighbouringFact,
+        ]
+    } else {
+        &[
+            DistractorKind::OffByOne,
+            DistractorKind::NeighbouringFact,
+            DistractorKind::DigitSwap,
+            DistractorKind::WrongOperation,
+            DistractorKind::OffByTen,
+        ]
+    };
+
+    let mut candidates: Vec<Vec<i64>> = preference.iter()
+        .map(|kind| candidates_of_kind(problem, answer, *kind))
+        .collect();
+    let mut chosen: Vec<Distractor> = Vec::with_capacity(count);
+    // Round-robin across kinds so the options don't all share one mistake
+    while chosen.len() < count && candidates.iter().any(|values| !values.is_empty()) {
+        for (kind, values) in preference.iter().zip(candidates.iter_mut()) {
+            if chosen.len() == count {
+                break;
+            }
+            while !values.is_empty() {
+                let value = values.remove(0);
+                let usable = value != answer
+                    && (value >= 0 || answer < 0)
+                    && !chosen.iter().any(|distractor| distractor.value == value.to_string());
+                if usable {
+                    chosen.push(Distractor { value: value.to_string(), kind: *kind });
+                    break;
+                }
+            }
+        }
+    }
+
+    // Tiny sums may not have enough distinct mistakes, so count upwards from the answer
+    let mut step = 2;
+    while chosen.len() < count {
+        let value = (answer + step).to_string();
+        if !chosen.iter().any(|distractor| distractor.value == value) {
+            chosen.push(Distractor { value, kind: DistractorKind::OffByOne });
+        }
+        step += 1;
+    }
+    Some(chosen)
+}
+
+/// Multiple-choice options for a problem: the answer plus `count` distractors
+pub fn options_with_distractors(problem: &ArithmeticProblem, difficulty: u8, count: usize) -> Option<Vec<String>> {
+    let mut options: Vec<String> = generate_distractors(problem, difficulty, count)?
+        .into_iter()
+        .map(|distractor| distractor.value)
+        .collect();
+    options.push(problem.answer()?.to_string());
+    Some(options)
+}
+
+fn candidates_of_kind(problem: &ArithmeticProblem, answer: i64, kind: DistractorKind) -> Vec<i64> {
+    let ArithmeticProblem { left, operation, right } = *problem;
+    match kind {
+        DistractorKind::OffByOne => vec![answer + 1, answer - 1],
+        DistractorKind::OffByTen => vec![answer + 10, answer - 10],
+        DistractorKind::DigitSwap => {
+            let digits: Vec<char> = answer.abs().to_string().chars().collect();
+            if digits.len() < 2 {
+                return Vec::new();
+            }
+            let mut swapped = digits.clone();
+            let last = swapped.len() - 1;
+            swapped.swap(last - 1, last);
+            let value: i64 = swapped.iter().collect::<String>().parse().unwrap_or(answer);
+            vec![if answer < 0 { -value } else { value }]
+        }
+        DistractorKind::WrongOperation => {
+            let others: &[Operation] = match operation {
+                Operation::Add => &[Operation::Subtract, Operation::Multiply],
+                Operation::Subtract => &[Operation::Add],
+                Operation::Multiply => &[Operation::Add],
+                Operation::Divide => &[Operation::Subtract, Operation::Multiply],
+            };
+            let mut values: Vec<i64> = others.iter().filter_map(|other| other.apply(left, right)).collect();
+            if operation == Operation::Subtract {
+                // Taking the smaller number from the bigger one whichever way round it was
+                values.push((right - left).abs());
+            }
+            values
+        }
+        DistractorKind::NeighbouringFact => [(left + 1, right), (left, right + 1), (left - 1, right), (left, right - 1)]
+            .iter()
+            .filter(|(neighbour_left, neighbour_right)| *neighbour_left > 0 && *neighbour_right > 0)
+            .filter_map(|(neighbour_left, neighbour_right)| operation.apply(*neighbour_left, *neighbour_right))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distractors_are_plausible_mistakes() {
+        let problem = ArithmeticProblem::parse("What is 7 × 8?").unwrap();
+        assert_eq!(problem, ArithmeticProblem::new(7, Operation::Multiply, 8));
+        assert_eq!(ArithmeticProblem::parse("What is 7 ÷ 2?"), None);
+        assert_eq!(ArithmeticProblem::parse("Name a shape with 4 sides"), None);
+        assert_eq!(ArithmeticProblem::parse("What is -6 × -4?"), Some(ArithmeticProblem::new(-6, Operation::Multiply, -4)));
+        assert_eq!(ArithmeticProblem::parse("What is 9 - 4?").unwrap().answer(), Some(5));
+
+        let hard = generate_distractors(&problem, 4, 3).unwrap();
+        let values: Vec<&str> = hard.iter().map(|distractor| distractor.value.as_str()).collect();
+        assert_eq!(values, vec!["57", "64", "65"]);
+        assert_eq!(hard[1].kind, DistractorKind::NeighbouringFact);
+        assert_eq!(hard[2].kind, DistractorKind::DigitSwap);
+
+        let easy = generate_distractors(&problem, 1, 3).unwrap();
+        assert_eq!(easy[0], Distractor { value: "15".to_string(), kind: DistractorKind::WrongOperation });
+        assert_eq!(easy[1].kind, DistractorKind::OffByTen);
+
+        // Never the answer, never negative for a positive answer, never repeated
+        for text in ["1 + 1", "2 - 1", "1 × 1", "10 ÷ 5", "12 × 12"] {
+            let problem = ArithmeticProblem::parse(text).unwrap();
+            let options = options_with_distractors(&problem, 3, 3).unwrap();
+            assert_eq!(options.len(), 4, "{}", text);
+            let mut unique = options.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), 4, "{:?}", options);
+            assert!(options.iter().all(|option| !option.starts_with('-')), "{:?}", options);
+        }
+    }
+}
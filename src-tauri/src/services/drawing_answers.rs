@@ -0,0 +1,281 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Answer, Drawing, ProfileId, QuestionId, SessionId};
+use crate::database::DatabaseManager;
+use std::sync::{Arc, RwLock};
+use rusqlite::{params, Row};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Most points accepted in one drawing, so a scribble can't bloat the database
+const MAX_DRAWING_POINTS: usize = 20_000;
+
+/// Largest rendered PNG accepted, as base64 text
+const MAX_DRAWING_IMAGE_LENGTH: usize = 512 * 1024;
+
+/// Reads a drawing, e.g. a digit recogniser for number-writing practice. Returns the text it
+/// read, or `None` when it can't tell; unrecognised drawings are left for a parent to review.
+pub trait DrawingRecognizer: Send + Sync {
+    fn name(&self) -> &str;
+    fn recognize(&self, drawing: &Drawing) -> AppResult<Option<String>>;
+}
+
+/// A drawn answer kept for parent review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DrawingSubmission {
+    pub id: u32,
+    pub profile_id: ProfileId,
+    pub session_id: Option<SessionId>,
+    pub question_id: QuestionId,
+    pub drawing: Drawing,
+    pub recognized_text: Option<String>,
+    pub is_correct: bool,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// Checks, recognises and stores handwritten answers
+pub struct DrawingAnswerService {
+    db_manager: Arc<DatabaseManager>,
+    recognizer: RwLock<Option<Arc<dyn DrawingRecognizer>>>,
+}
+
+impl DrawingAnswerService {
+    /// Create a drawing answer service with no recogniser installed
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            db_manager,
+            recognizer: RwLock::new(None),
+        }
+    }
+
+    /// Install (or with `None`, remove) the drawing recogniser
+    pub fn set_recognizer(&self, recognizer: Option<Arc<dyn DrawingRecognizer>>) -> AppResult<()> {
+        *self.recognizer.write()
+            .map_err(|_| AppError::Internal("Drawing recognizer lock poisoned".to_string()))? = recognizer;
+        Ok(())
+    }
+
+    /// Name of the installed recogniser, if any
+    pub fn recognizer_name(&self) -> Option<String> {
+        self.recognizer.read().ok()?.as_ref().map(|recognizer| recognizer.name().to_string())
+    }
+
+    /// Get a submitted answer ready for marking. Drawings are checked and run through the
+    /// recogniser; any `recognized_text` sent by the client is discarded. Other answers pass through.
+    pub fn prepare_answer(&self, answer: Answer) -> AppResult<Answer> {
+        match answer {
+            Answer::Drawing(mut drawing) => {
+                validate_drawing(&drawing)?;
+
+                let recognizer = self.recognizer.read()
+                    .map_err(|_| AppError::Internal("Drawing recognizer lock poisoned".to_string()))?
+                    .clone();
+                drawing.recognized_text = match recognizer {
+                    Some(recognizer) => recognizer.recognize(&drawing)?
+                        .map(|text| text.trim().to_string())
+                        .filter(|text| !text.is_empty()),
+                    None => None,
+                };
+
+                Ok(Answer::Drawing(drawing))
+            }
+            other => Ok(other),
+        }
+    }
+
+    /// Store a marked drawing for parent review
+    pub fn record(
+        &self,
+        profile_id: ProfileId,
+        session_id: Option<SessionId>,
+        question_id: QuestionId,
+        drawing: &Drawing,
+        is_correct: bool,
+    ) -> AppResult<u32> {
+        let drawing_json = serde_json::to_string(drawing)?;
+
+        Ok(self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO answer_drawings (profile_id, session_id, question_id, drawing, recognized_text, is_correct, submitted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    profile_id,
+                    session_id,
+                    question_id,
+                    drawing_json,
+                    drawing.recognized_text,
+                    is_correct,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(conn.last_insert_rowid() as u32)
+        })?)
+    }
+
+    /// Drawings submitted during one quiz session, in the order given
+    pub fn get_session_drawings(&self, session_id: SessionId) -> AppResult<Vec<DrawingSubmission>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, profile_id, session_id, question_id, drawing, recognized_text, is_correct, submitted_at
+                 FROM answer_drawings WHERE session_id = ?1 ORDER BY id"
+            )?;
+            let rows = stmt.query_map(params![session_id], Self::row_to_submission)?;
+            rows.collect()
+        })?)
+    }
+
+    /// A profile's most recent drawings, newest first
+    pub fn get_profile_drawings(&self, profile_id: ProfileId, limit: u32) -> AppResult<Vec<DrawingSubmission>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, profile_id, session_id, question_id, drawing, recognized_text, is_correct, submitted_at
+                 FROM answer_drawings WHERE profile_id = ?1 ORDER BY submitted_at DESC, id DESC LIMIT ?2"
+            )?;
+            let rows = stmt.query_map(params![profile_id, limit], Self::row_to_submission)?;
+            rows.collect()
+        })?)
+    }
+
+    fn row_to_submission(row: &Row) -> rusqlite::Result<DrawingSubmission> {
+        let drawing_json: String = row.get(4)?;
+        let drawing = serde_json::from_str(&drawing_json)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(4, "drawing".to_string(), rusqlite::types::Type::Text))?;
+        let submitted_at: String = row.get(7)?;
+
+        Ok(DrawingSubmission {
+            id: row.get(0)?,
+            profile_id: row.get(1)?,
+            session_id: row.get(2)?,
+            question_id: row.get(3)?,
+            drawing,
+            recognized_text: row.get(5)?,
+            is_correct: row.get(6)?,
+            submitted_at: DateTime::parse_from_rfc3339(&submitted_at)
+                .map(|submitted_at| submitted_at.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+}
+
+/// Reject empty, oversized or malformed drawings
+pub(crate) fn validate_drawing(drawing: &Drawing) -> AppResult<()> {
+    if !(drawing.width > 0.0 && drawing.height > 0.0) {
+        return Err(AppError::InvalidInput("Drawing canvas must have a size".to_string()));
+    }
+
+    let points = drawing.point_count();
+    if points == 0 {
+        return Err(AppError::InvalidInput("Drawing is empty".to_string()));
+    }
+    if points > MAX_DRAWING_POINTS {
+        return Err(AppError::InvalidInput("Drawing has too many points".to_string()));
+    }
+    if drawing.strokes.iter().flatten().any(|[x, y]| !x.is_finite() || !y.is_finite()) {
+        return Err(AppError::InvalidInput("Drawing has invalid points".to_string()));
+    }
+
+    if let Some(image) = &drawing.image_png_base64 {
+        if image.len() > MAX_DRAWING_IMAGE_LENGTH {
+            return Err(AppError::InvalidInput("Drawing image is too large".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    /// Reads any single vertical stroke as a "1"
+    struct OneRecognizer;
+
+    impl DrawingRecognizer for OneRecognizer {
+        fn name(&self) -> &str {
+            "ones"
+        }
+
+        fn recognize(&self, drawing: &Drawing) -> AppResult<Option<String>> {
+            let vertical = drawing.strokes.len() == 1
+                && drawing.strokes[0].iter().all(|[x, _]| (x - drawing.strokes[0][0][0]).abs() < 5.0);
+            Ok(if vertical { Some("1".to_string()) } else { None })
+        }
+    }
+
+    fn create_test_drawing_service() -> (DrawingAnswerService, Arc<DatabaseManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        (DrawingAnswerService::new(db_service.manager()), db_service.manager(), temp_dir)
+    }
+
+    fn drawing(strokes: Vec<Vec<[f32; 2]>>) -> Drawing {
+        Drawing {
+            width: 100.0,
+            height: 100.0,
+            strokes,
+            image_png_base64: None,
+            recognized_text: Some("7".to_string()),
+        }
+    }
+
+    fn prepared(service: &DrawingAnswerService, drawing: Drawing) -> Drawing {
+        match service.prepare_answer(Answer::Drawing(drawing)).unwrap() {
+            Answer::Drawing(drawing) => drawing,
+            other => panic!("Expected a drawing, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_prepare_answer_uses_recognizer_not_client() {
+        let (service, _db, _temp_dir) = create_test_drawing_service();
+        let one = drawing(vec![vec![[50.0, 10.0], [51.0, 50.0], [50.0, 90.0]]]);
+
+        // Without a recogniser nothing is read, whatever the client claimed
+        assert!(prepared(&service, one.clone()).recognized_text.is_none());
+
+        service.set_recognizer(Some(Arc::new(OneRecognizer))).unwrap();
+        assert_eq!(prepared(&service, one).recognized_text.as_deref(), Some("1"));
+        assert!(prepared(&service, drawing(vec![vec![[10.0, 10.0], [90.0, 90.0]]])).recognized_text.is_none());
+
+        assert!(service.prepare_answer(Answer::Drawing(drawing(Vec::new()))).is_err());
+        assert!(service.prepare_answer(Answer::Drawing(drawing(vec![vec![[f32::NAN, 0.0]]]))).is_err());
+        assert!(matches!(service.prepare_answer(Answer::Text("1".to_string())).unwrap(), Answer::Text(_)));
+    }
+
+    #[test]
+    fn test_record_and_review_drawings() {
+        let (service, db, _temp_dir) = create_test_drawing_service();
+        let (profile_id, question_id) = db.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            let profile_id = ProfileId(conn.last_insert_rowid() as u32);
+            conn.execute(
+                "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer)
+                 VALUES ((SELECT id FROM subjects WHERE name = 'mathematics'), 'KS1', 'fill_blank', '{}', '\"1\"')",
+                [],
+            )?;
+            Ok((profile_id, QuestionId(conn.last_insert_rowid() as u32)))
+        }).unwrap();
+
+        service.set_recognizer(Some(Arc::new(OneRecognizer))).unwrap();
+        let one = prepared(&service, drawing(vec![vec![[50.0, 10.0], [50.0, 90.0]]]));
+        service.record(profile_id, Some(SessionId(4)), question_id, &one, true).unwrap();
+        service.record(profile_id, Some(SessionId(5)), question_id, &one, true).unwrap();
+
+        let session = service.get_session_drawings(SessionId(4)).unwrap();
+        assert_eq!(session.len(), 1);
+        assert_eq!(session[0].recognized_text.as_deref(), Some("1"));
+        assert_eq!(session[0].drawing.strokes.len(), 1);
+
+        let recent = service.get_profile_drawings(profile_id, 10).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].session_id, Some(SessionId(5)));
+    }
+}
@@ -0,0 +1,782 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{accuracy_percentage, AssignmentSummary, HouseholdStatistics, Locale, ProfileId};
+use crate::services::{AssignmentManager, FormattingService, ProfileManager, ReadingAgeEstimate, ReadingAgeService};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use base64::Engine;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Days covered by one weekly report, ending on the send day
+const REPORT_PERIOD_DAYS: i64 = 7;
+
+/// Wait this long after a failed send before trying again
+const RETRY_AFTER_MINUTES: i64 = 60;
+
+/// Give up on an unresponsive mail server after this long
+const SMTP_TIMEOUT_SECS: u64 = 30;
+
+/// How the connection to the mail server is secured
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// Plain connection, only sensible for a relay on the local network
+    None,
+    /// Upgrade a plain connection with STARTTLS (usually port 587)
+    StartTls,
+    /// TLS from the first byte (usually port 465)
+    Tls,
+}
+
+impl SmtpSecurity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SmtpSecurity::None => "none",
+            SmtpSecurity::StartTls => "starttls",
+            SmtpSecurity::Tls => "tls",
+        }
+    }
+
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(SmtpSecurity::None),
+            "starttls" => Some(SmtpSecurity::StartTls),
+            "tls" => Some(SmtpSecurity::Tls),
+            _ => None,
+        }
+    }
+}
+
+/// Parent-configured mail server and weekly schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    /// Never sent back to the frontend; leave empty when saving to keep the stored password
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
+    pub from_address: String,
+    /// One or more recipients, separated by commas
+    pub to_address: String,
+    /// Day the report is sent, 0 = Monday
+    pub send_weekday: u32,
+    /// Local hour (0-23) from which the report is sent
+    pub send_hour: u32,
+}
+
+impl SmtpSettings {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.host.trim().is_empty() {
+            return Err(AppError::InvalidInput("SMTP host is required".to_string()));
+        }
+        if self.port == 0 {
+            return Err(AppError::InvalidInput("SMTP port must be greater than 0".to_string()));
+        }
+        validate_address(&self.from_address)?;
+        if self.recipients().is_empty() {
+            return Err(AppError::InvalidInput("At least one recipient address is required".to_string()));
+        }
+        for recipient in self.recipients() {
+            validate_address(recipient)?;
+        }
+        if self.send_weekday > 6 {
+            return Err(AppError::InvalidInput("Send day must be between 0 (Monday) and 6 (Sunday)".to_string()));
+        }
+        if self.send_hour > 23 {
+            return Err(AppError::InvalidInput("Send hour must be between 0 and 23".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn recipients(&self) -> Vec<&str> {
+        self.to_address.split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .collect()
+    }
+}
+
+/// Outcome of recent weekly report deliveries, shown in diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailDeliveryStatus {
+    pub configured: bool,
+    pub enabled: bool,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Error from the most recent attempt, if it failed
+    pub last_error: Option<String>,
+}
+
+/// One profile's practice over the report period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    pub profile_id: ProfileId,
+    pub profile_name: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub questions_answered: u32,
+    pub days_practised: u32,
+    pub overall_accuracy: u8,
+    pub assignments: AssignmentSummary,
+    /// Latest reading age estimate, once enough reading questions have been answered
+    pub reading_age: Option<ReadingAgeEstimate>,
+}
+
+impl WeeklyDigest {
+    /// Plain-text section for the report email
+    pub fn render(&self, locale: Locale) -> String {
+        let mut text = format!(
+            "{}\n{}\n\nQuestions answered: {}\nDays practised: {} of {}\nOverall accuracy: {}\n",
+            self.profile_name,
+            "-".repeat(self.profile_name.chars().count()),
+            locale.format_number(self.questions_answered as f64, 0),
+            self.days_practised,
+            REPORT_PERIOD_DAYS,
+            locale.format_percent(u32::from(self.overall_accuracy)),
+        );
+
+        let assignments = &self.assignments;
+        if assignments.pending + assignments.overdue + assignments.passed + assignments.failed > 0 {
+            text.push_str(&format!(
+                "Homework: {} passed, {} not passed, {} overdue, {} still to do\n",
+                assignments.passed, assignments.failed, assignments.overdue, assignments.pending,
+            ));
+        }
+
+        if let Some(reading_age) = &self.reading_age {
+            text.push_str(&format!("Reading age: {}\n", reading_age.describe()));
+        }
+
+        text
+    }
+}
+
+/// A plain-text email ready to hand to the mail server
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+impl EmailMessage {
+    /// Format as an RFC 5322 message with CRLF line endings and dot-stuffed body lines
+    fn to_wire_format(&self, now: DateTime<Utc>) -> String {
+        let mut message = String::new();
+        message.push_str(&format!("From: QuizDD <{}>\r\n", self.from));
+        message.push_str(&format!("To: {}\r\n", self.to.join(", ")));
+        message.push_str(&format!("Subject: {}\r\n", encode_header(&self.subject)));
+        message.push_str(&format!("Date: {}\r\n", now.to_rfc2822()));
+        message.push_str(&format!("Message-ID: <{}.quizdd@{}>\r\n", now.timestamp_millis(), domain_of(&self.from)));
+        message.push_str("MIME-Version: 1.0\r\n");
+        message.push_str("Content-Type: text/plain; charset=utf-8\r\n");
+        message.push_str("Content-Transfer-Encoding: 8bit\r\n\r\n");
+
+        for line in self.body.lines() {
+            if line.starts_with('.') {
+                message.push('.');
+            }
+            message.push_str(line);
+            message.push_str("\r\n");
+        }
+
+        message
+    }
+}
+
+/// Sends the weekly per-profile digest to parents by email
+pub struct EmailReportService {
+    db_manager: Arc<DatabaseManager>,
+    profile_manager: Arc<ProfileManager>,
+    assignment_manager: Arc<AssignmentManager>,
+    reading_age: Arc<ReadingAgeService>,
+    formatting: FormattingService,
+}
+
+impl EmailReportService {
+    pub fn new(
+        db_manager: Arc<DatabaseManager>,
+        profile_manager: Arc<ProfileManager>,
+        assignment_manager: Arc<AssignmentManager>,
+        reading_age: Arc<ReadingAgeService>,
+    ) -> Self {
+        Self {
+            formatting: FormattingService::new(db_manager.clone()),
+            db_manager,
+            profile_manager,
+            assignment_manager,
+            reading_age,
+        }
+    }
+
+    /// Get the stored mail settings, if email reports have been set up
+    pub fn get_settings(&self) -> AppResult<Option<SmtpSettings>> {
+        let row = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT enabled, host, port, security, username, password, from_address, to_address, send_weekday, send_hour
+                 FROM email_settings WHERE id = 1",
+                [],
+                |row| Ok((
+                    row.get::<_, bool>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, u16>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, String>(7)?,
+                    row.get::<_, u32>(8)?,
+                    row.get::<_, u32>(9)?,
+                )),
+            ).optional()
+        })?;
+
+        let (enabled, host, port, security, username, password, from_address, to_address, send_weekday, send_hour) = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        let security = SmtpSecurity::from_str(&security)
+            .ok_or_else(|| AppError::Internal(format!("Unknown SMTP security mode: {}", security)))?;
+
+        Ok(Some(SmtpSettings {
+            enabled,
+            host,
+            port,
+            security,
+            username,
+            password,
+            from_address,
+            to_address,
+            send_weekday,
+            send_hour,
+        }))
+    }
+
+    /// Save mail settings. An empty password keeps the one already stored.
+    pub fn save_settings(&self, settings: &SmtpSettings) -> AppResult<()> {
+        settings.validate()?;
+
+        let username = settings.username.as_deref()
+            .map(str::trim)
+            .filter(|username| !username.is_empty());
+        let password = settings.password.as_deref().filter(|password| !password.is_empty());
+        let now = Utc::now().to_rfc3339();
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO email_settings
+                 (id, enabled, host, port, security, username, password, from_address, to_address, send_weekday, send_hour, updated_at)
+                 VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 ON CONFLICT(id) DO UPDATE SET
+                    enabled = excluded.enabled,
+                    host = excluded.host,
+                    port = excluded.port,
+                    security = excluded.security,
+                    username = excluded.username,
+                    password = COALESCE(excluded.password, email_settings.password),
+                    from_address = excluded.from_address,
+                    to_address = excluded.to_address,
+                    send_weekday = excluded.send_weekday,
+                    send_hour = excluded.send_hour,
+                    updated_at = excluded.updated_at",
+                params![
+                    settings.enabled,
+                    settings.host.trim(),
+                    settings.port,
+                    settings.security.as_str(),
+                    username,
+                    password,
+                    settings.from_address.trim(),
+                    settings.to_address.trim(),
+                    settings.send_weekday,
+                    settings.send_hour,
+                    now,
+                ],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Build a digest for every profile covering the week ending on `period_end`
+    pub fn build_weekly_digests(&self, period_end: NaiveDate) -> AppResult<Vec<WeeklyDigest>> {
+        let period_start = period_end - Duration::days(REPORT_PERIOD_DAYS - 1);
+        let since = period_start.and_hms_opt(0, 0, 0)
+            .ok_or_else(|| AppError::Internal("Invalid report period".to_string()))?
+            .and_utc();
+
+        let mut digests = Vec::new();
+        for profile in self.profile_manager.get_all_profiles()? {
+            let profile_id = match profile.id {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let (questions_answered, days_practised) = self.db_manager.execute(|conn| {
+                conn.query_row(
+                    "SELECT COALESCE(SUM(questions_answered), 0), COUNT(*) FROM daily_activity
+                     WHERE profile_id = ?1 AND activity_date BETWEEN ?2 AND ?3 AND questions_answered > 0",
+                    params![
+                        profile_id,
+                        period_start.format(DATE_FORMAT).to_string(),
+                        period_end.format(DATE_FORMAT).to_string(),
+                    ],
+                    |row| Ok((row.get::<_, u32>(0)?, row.get::<_, u32>(1)?)),
+                )
+            })?;
+
+            let progress = self.profile_manager.get_progress(profile_id)?;
+            let overall_accuracy = accuracy_percentage(
+                progress.total_correct_answers,
+                progress.total_questions_answered,
+            ).round() as u8;
+
+            digests.push(WeeklyDigest {
+                profile_id,
+                profile_name: profile.name,
+                period_start,
+                period_end,
+                questions_answered,
+                days_practised,
+                overall_accuracy,
+                assignments: self.assignment_manager.get_summary(profile_id, since)?,
+                reading_age: self.reading_age.record_estimate(profile_id)?,
+            });
+        }
+
+        Ok(digests)
+    }
+
+    /// Send the weekly report if it is due at local time `now`.
+    /// Returns whether a report was sent; failures are recorded for diagnostics.
+    pub fn send_due_report(&self, now: NaiveDateTime) -> AppResult<bool> {
+        let settings = match self.get_settings()? {
+            Some(settings) if settings.enabled => settings,
+            _ => return Ok(false),
+        };
+
+        let today = now.date();
+        if today.weekday().num_days_from_monday() != settings.send_weekday || now.hour() < settings.send_hour {
+            return Ok(false);
+        }
+
+        let report_date = today.format(DATE_FORMAT).to_string();
+        let (sent, last_attempt) = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT COALESCE(MAX(succeeded), 0), MAX(attempted_at) FROM email_report_log WHERE report_date = ?1",
+                params![report_date],
+                |row| Ok((row.get::<_, bool>(0)?, row.get::<_, Option<String>>(1)?)),
+            )
+        })?;
+
+        if sent {
+            return Ok(false);
+        }
+        let retry_after = Utc::now() - Duration::minutes(RETRY_AFTER_MINUTES);
+        let recently_failed = last_attempt
+            .and_then(|attempted_at| DateTime::parse_from_rfc3339(&attempted_at).ok())
+            .map_or(false, |attempted_at| attempted_at > retry_after);
+        if recently_failed {
+            return Ok(false);
+        }
+
+        let result = self.profile_manager.get_household_statistics()
+            .and_then(|household| {
+                let digests = self.build_weekly_digests(today)?;
+                let locale = self.formatting.get_locale()?;
+                send_message(&settings, &weekly_report_message(&settings, &household, &digests, locale))
+            });
+
+        let error = result.as_ref().err().map(|e| e.to_string());
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO email_report_log (report_date, attempted_at, succeeded, error) VALUES (?1, ?2, ?3, ?4)",
+                params![report_date, Utc::now().to_rfc3339(), error.is_none(), error],
+            )?;
+            Ok(())
+        })?;
+
+        result.map(|_| true)
+    }
+
+    /// Send a short message with the stored settings so parents can check they work
+    pub fn send_test_email(&self) -> AppResult<()> {
+        let settings = self.get_settings()?
+            .ok_or_else(|| AppError::NotFound("Email reports have not been set up".to_string()))?;
+
+        let message = EmailMessage {
+            from: settings.from_address.clone(),
+            to: settings.recipients().into_iter().map(String::from).collect(),
+            subject: "QuizDD test email".to_string(),
+            body: "This is a test message from QuizDD. Weekly reports will be sent to this address.".to_string(),
+        };
+
+        send_message(&settings, &message)
+    }
+
+    /// Latest delivery outcome for the diagnostics screen
+    pub fn get_delivery_status(&self) -> AppResult<EmailDeliveryStatus> {
+        let settings = self.get_settings()?;
+
+        let (last_attempt, last_error, last_success) = self.db_manager.execute(|conn| {
+            let last_attempt = conn.query_row(
+                "SELECT attempted_at, error FROM email_report_log ORDER BY id DESC LIMIT 1",
+                [],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)),
+            ).optional()?;
+            let last_success = conn.query_row(
+                "SELECT MAX(attempted_at) FROM email_report_log WHERE succeeded = 1",
+                [],
+                |row| row.get::<_, Option<String>>(0),
+            )?;
+            Ok((last_attempt.as_ref().map(|(at, _)| at.clone()), last_attempt.and_then(|(_, error)| error), last_success))
+        })?;
+
+        let parse = |value: Option<String>| value
+            .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+            .map(|value| value.with_timezone(&Utc));
+
+        Ok(EmailDeliveryStatus {
+            configured: settings.is_some(),
+            enabled: settings.map_or(false, |settings| settings.enabled),
+            last_attempt_at: parse(last_attempt),
+            last_success_at: parse(last_success),
+            last_error,
+        })
+    }
+}
+
+fn weekly_report_message(settings: &SmtpSettings, household: &HouseholdStatistics, digests: &[WeeklyDigest], locale: Locale) -> EmailMessage {
+    let mut body = String::from("Here is this week's QuizDD practice summary.\n\n");
+    if household.total_questions_answered > 0 {
+        body.push_str(&household.render(locale));
+        body.push('\n');
+    }
+    for digest in digests {
+        body.push_str(&digest.render(locale));
+        body.push('\n');
+    }
+    if digests.is_empty() {
+        body.push_str("There are no learner profiles yet.\n");
+    }
+
+    let subject = match digests.first() {
+        Some(digest) => format!(
+            "QuizDD weekly report: {} to {}",
+            locale.format_short_date(digest.period_start, false),
+            locale.format_short_date(digest.period_end, true),
+        ),
+        None => "QuizDD weekly report".to_string(),
+    };
+
+    EmailMessage {
+        from: settings.from_address.clone(),
+        to: settings.recipients().into_iter().map(String::from).collect(),
+        subject,
+        body,
+    }
+}
+
+/// Reject addresses that are obviously malformed or could inject extra headers
+fn validate_address(address: &str) -> AppResult<()> {
+    let address = address.trim();
+    let valid = address.contains('@')
+        && !address.starts_with('@')
+        && !address.ends_with('@')
+        && !address.chars().any(|c| c.is_whitespace() || c.is_control() || c == '<' || c == '>' || c == ',');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::InvalidInput(format!("Invalid email address: {}", address)))
+    }
+}
+
+fn domain_of(address: &str) -> &str {
+    address.rsplit('@').next().unwrap_or("localhost")
+}
+
+/// RFC 2047 encode a header value when it is not plain ASCII
+fn encode_header(value: &str) -> String {
+    let value = value.replace(['\r', '\n'], " ");
+    if value.is_ascii() {
+        value
+    } else {
+        format!("=?UTF-8?B?{}?=", base64::engine::general_purpose::STANDARD.encode(value))
+    }
+}
+
+// ============================================================================
+// SMTP CLIENT
+// ============================================================================
+
+enum SmtpStream {
+    Plain(TcpStream),
+    Tls(native_tls::TlsStream<TcpStream>),
+}
+
+impl Read for SmtpStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            SmtpStream::Plain(stream) => stream.read(buf),
+            SmtpStream::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for SmtpStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            SmtpStream::Plain(stream) => stream.write(buf),
+            SmtpStream::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            SmtpStream::Plain(stream) => stream.flush(),
+            SmtpStream::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+struct SmtpConnection {
+    reader: BufReader<SmtpStream>,
+}
+
+impl SmtpConnection {
+    /// Send a command (or nothing, for the greeting) and check the reply code
+    fn command(&mut self, line: Option<&str>, expected: &[u16]) -> AppResult<String> {
+        if let Some(line) = line {
+            let stream = self.reader.get_mut();
+            stream.write_all(line.as_bytes())?;
+            stream.write_all(b"\r\n")?;
+            stream.flush()?;
+        }
+
+        // Multi-line replies use "250-" on every line but the last
+        let mut reply = String::new();
+        loop {
+            let mut reply_line = String::new();
+            if self.reader.read_line(&mut reply_line)? == 0 {
+                return Err(AppError::Email("Mail server closed the connection".to_string()));
+            }
+            reply.push_str(&reply_line);
+            if reply_line.as_bytes().get(3) != Some(&b'-') {
+                break;
+            }
+        }
+
+        let code = reply.get(..3).and_then(|code| code.parse::<u16>().ok()).unwrap_or(0);
+        if expected.contains(&code) {
+            Ok(reply)
+        } else {
+            // Never echo credentials back in error messages
+            let sent = match line {
+                Some(line) if line.starts_with("AUTH") => "AUTH",
+                Some(line) => line,
+                None => "connect",
+            };
+            Err(AppError::Email(format!("Mail server rejected {}: {}", sent, reply.trim())))
+        }
+    }
+}
+
+fn tls_error(e: impl std::fmt::Display) -> AppError {
+    AppError::Email(format!("TLS error: {}", e))
+}
+
+fn send_message(settings: &SmtpSettings, message: &EmailMessage) -> AppResult<()> {
+    let timeout = StdDuration::from_secs(SMTP_TIMEOUT_SECS);
+    let address = (settings.host.as_str(), settings.port).to_socket_addrs()?
+        .next()
+        .ok_or_else(|| AppError::Email(format!("Could not resolve mail server {}", settings.host)))?;
+
+    let tcp = TcpStream::connect_timeout(&address, timeout)
+        .map_err(|e| AppError::Email(format!("Could not connect to {}:{}: {}", settings.host, settings.port, e)))?;
+    tcp.set_read_timeout(Some(timeout))?;
+    tcp.set_write_timeout(Some(timeout))?;
+
+    let connector = native_tls::TlsConnector::new().map_err(tls_error)?;
+    let stream = match settings.security {
+        SmtpSecurity::Tls => SmtpStream::Tls(connector.connect(&settings.host, tcp).map_err(tls_error)?),
+        SmtpSecurity::None | SmtpSecurity::StartTls => SmtpStream::Plain(tcp),
+    };
+
+    let mut connection = SmtpConnection { reader: BufReader::new(stream) };
+    let helo = format!("EHLO {}", domain_of(&settings.from_address));
+    connection.command(None, &[220])?;
+    connection.command(Some(&helo), &[250])?;
+
+    if settings.security == SmtpSecurity::StartTls {
+        connection.command(Some("STARTTLS"), &[220])?;
+        let tcp = match connection.reader.into_inner() {
+            SmtpStream::Plain(tcp) => tcp,
+            SmtpStream::Tls(_) => return Err(AppError::Internal("Connection is already encrypted".to_string())),
+        };
+        let stream = SmtpStream::Tls(connector.connect(&settings.host, tcp).map_err(tls_error)?);
+        connection = SmtpConnection { reader: BufReader::new(stream) };
+        connection.command(Some(&helo), &[250])?;
+    }
+
+    if let Some(username) = settings.username.as_deref() {
+        let password = settings.password.as_deref().unwrap_or_default();
+        let credentials = base64::engine::general_purpose::STANDARD
+            .encode(format!("\0{}\0{}", username, password));
+        connection.command(Some(&format!("AUTH PLAIN {}", credentials)), &[235])?;
+    }
+
+    connection.command(Some(&format!("MAIL FROM:<{}>", message.from)), &[250])?;
+    for recipient in &message.to {
+        connection.command(Some(&format!("RCPT TO:<{}>", recipient)), &[250, 251])?;
+    }
+    connection.command(Some("DATA"), &[354])?;
+
+    let mut data = message.to_wire_format(Utc::now());
+    data.push('.');
+    connection.command(Some(&data), &[250])?;
+
+    // The message is accepted at this point, so a failed goodbye doesn't matter
+    let _ = connection.command(Some("QUIT"), &[221]);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::CreateProfileRequest;
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    fn create_test_email_reports() -> (EmailReportService, Arc<ProfileManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let profile_manager = Arc::new(ProfileManager::new(db_service.manager(), security_service));
+        let assignment_manager = Arc::new(AssignmentManager::new(db_service.manager()));
+        let reading_age = Arc::new(ReadingAgeService::new(db_service.manager()));
+        let service = EmailReportService::new(db_service.manager(), profile_manager.clone(), assignment_manager, reading_age);
+
+        (service, profile_manager, temp_dir)
+    }
+
+    fn test_settings() -> SmtpSettings {
+        SmtpSettings {
+            enabled: false,
+            host: "smtp.example.com".to_string(),
+            port: 587,
+            security: SmtpSecurity::StartTls,
+            username: Some("parent@example.com".to_string()),
+            password: Some("secret".to_string()),
+            from_address: "quizdd@example.com".to_string(),
+            to_address: "parent@example.com, carer@example.com".to_string(),
+            send_weekday: 6,
+            send_hour: 18,
+        }
+    }
+
+    #[test]
+    fn test_settings_validation_and_password_retention() {
+        let (service, _, _temp_dir) = create_test_email_reports();
+        assert!(service.get_settings().unwrap().is_none());
+
+        let mut settings = test_settings();
+        settings.to_address = "parent@example.com\r\nBcc: someone@example.com".to_string();
+        assert!(service.save_settings(&settings).is_err());
+
+        service.save_settings(&test_settings()).unwrap();
+        let mut settings = test_settings();
+        settings.password = None;
+        settings.send_hour = 9;
+        service.save_settings(&settings).unwrap();
+
+        let stored = service.get_settings().unwrap().unwrap();
+        assert_eq!(stored.send_hour, 9);
+        assert_eq!(stored.password.as_deref(), Some("secret"));
+        assert_eq!(stored.recipients(), vec!["parent@example.com", "carer@example.com"]);
+        assert!(!serde_json::to_string(&stored).unwrap().contains("secret"));
+    }
+
+    #[test]
+    fn test_weekly_digest_counts_recent_activity() {
+        let (service, profile_manager, _temp_dir) = create_test_email_reports();
+        let profile_id = profile_manager.create_profile(CreateProfileRequest {
+            name: "Ada".to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        }).unwrap().id.unwrap();
+
+        let period_end = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        service.db_manager.execute(|conn| {
+            for (date, answered) in [("2024-03-10", 12), ("2024-03-05", 8), ("2024-03-01", 30)] {
+                conn.execute(
+                    "INSERT INTO daily_activity (profile_id, activity_date, questions_answered) VALUES (?1, ?2, ?3)",
+                    params![profile_id, date, answered],
+                )?;
+            }
+            Ok(())
+        }).unwrap();
+
+        let digests = service.build_weekly_digests(period_end).unwrap();
+        assert_eq!(digests.len(), 1);
+        assert_eq!(digests[0].questions_answered, 20);
+        assert_eq!(digests[0].days_practised, 2);
+        assert!(digests[0].render(Locale::EnGb).contains("Days practised: 2 of 7"));
+
+        let household = service.profile_manager.get_household_statistics().unwrap();
+        assert_eq!(household.profile_count, 1);
+        assert_eq!(household.busiest_day.as_ref().unwrap().questions_answered, 30);
+
+        let message = weekly_report_message(&test_settings(), &household, &digests, Locale::EnGb);
+        assert_eq!(message.subject, "QuizDD weekly report: 4 Mar to 10 Mar 2024");
+        let message = weekly_report_message(&test_settings(), &household, &digests, Locale::EnUs);
+        assert_eq!(message.subject, "QuizDD weekly report: Mar 4 to Mar 10, 2024");
+        assert_eq!(message.to.len(), 2);
+    }
+
+    #[test]
+    fn test_wire_format_is_dot_stuffed() {
+        let message = EmailMessage {
+            from: "quizdd@example.com".to_string(),
+            to: vec!["parent@example.com".to_string()],
+            subject: "Zoë's report".to_string(),
+            body: "Line one\n.hidden line".to_string(),
+        };
+
+        let wire = message.to_wire_format(Utc::now());
+        assert!(wire.contains("Subject: =?UTF-8?B?"));
+        assert!(wire.contains("\r\n\r\nLine one\r\n..hidden line\r\n"));
+    }
+
+    #[test]
+    fn test_disabled_reports_are_not_sent() {
+        let (service, _, _temp_dir) = create_test_email_reports();
+        service.save_settings(&test_settings()).unwrap();
+
+        // 2024-03-10 is a Sunday, the configured send day
+        let now = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap().and_hms_opt(19, 0, 0).unwrap();
+        assert!(!service.send_due_report(now).unwrap());
+
+        let status = service.get_delivery_status().unwrap();
+        assert!(status.configured);
+        assert!(!status.enabled);
+        assert!(status.last_attempt_at.is_none());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{LevelInfo, ProfileId, XpGain};
+use crate::services::quiz_engine::{AnswerResult, QuizMode, QuizSession};
+use std::collections::HashMap;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use chrono::{DateTime, Utc};
+
+/// XP for a right answer to a difficulty 1 question, before multipliers
+pub const XP_PER_CORRECT_ANSWER: u32 = 10;
+
+/// Extra XP for each step of difficulty above 1, as a share of the base
+const DIFFICULTY_STEP_MULTIPLIER: f64 = 0.25;
+
+/// Answers this quick earn `QUICK_ANSWER_MULTIPLIER` times the XP
+const QUICK_ANSWER_SECONDS: u32 = 10;
+const QUICK_ANSWER_MULTIPLIER: f64 = 1.2;
+
+/// Each right answer in a row after the first adds this much, up to `MAX_STREAK_MULTIPLIER`
+const STREAK_STEP_MULTIPLIER: f64 = 0.1;
+const MAX_STREAK_MULTIPLIER: f64 = 1.5;
+
+/// XP between level 1 and 2; each level after needs this much more than the one before
+pub const LEVEL_XP_STEP: u32 = 100;
+
+/// Gains listed in a profile's level info
+const RECENT_XP_GAINS: u32 = 10;
+
+/// XP earned in a quiz, by `player` alone in a shared quiz or by everyone otherwise.
+/// Right answers earn more on harder questions, when quick, and in a run of right answers;
+/// partly right ones earn their share of the base. Hints cost XP as they cost points.
+/// Practice isn't scored, so earns none.
+pub fn quiz_xp(session: &QuizSession, player: Option<ProfileId>) -> u32 {
+    if session.config.mode == QuizMode::Practice {
+        return 0;
+    }
+    let difficulties: HashMap<_, u8> = session.questions.iter()
+        .filter_map(|question| question.id.map(|id| (id, question.difficulty_level)))
+        .collect();
+
+    let mut run = 0;
+    session.answers.iter()
+        .filter(|answer| player.map_or(true, |player| answer.answered_by.map_or(true, |by| by == player)))
+        .map(|answer| {
+            run = if answer.is_correct { run + 1 } else { 0 };
+            let difficulty = difficulties.get(&answer.question_id).copied().unwrap_or(1);
+            answer_xp(answer, difficulty, run, session.config.hint_penalty_percent)
+        })
+        .sum()
+}
+
+/// XP for one answer, `run` being right answers in a row up to and including it
+fn answer_xp(answer: &AnswerResult, difficulty: u8, run: u32, hint_penalty_percent: u8) -> u32 {
+    let difficulty_multiplier = 1.0 + (difficulty.clamp(1, 5) - 1) as f64 * DIFFICULTY_STEP_MULTIPLIER;
+    let base = XP_PER_CORRECT_ANSWER as f64 * difficulty_multiplier;
+
+    let xp = if answer.is_correct {
+        let speed_multiplier = match answer.time_taken {
+            Some(seconds) if seconds <= QUICK_ANSWER_SECONDS => QUICK_ANSWER_MULTIPLIER,
+            _ => 1.0,
+        };
+        let streak_multiplier = (1.0 + run.saturating_sub(1) as f64 * STREAK_STEP_MULTIPLIER).min(MAX_STREAK_MULTIPLIER);
+        base * speed_multiplier * streak_multiplier
+    } else {
+        base * answer.partial_credit.unwrap_or(0.0) as f64
+    };
+
+    let penalty_percent = (answer.hints_used * hint_penalty_percent as u32).min(100);
+    (xp * (100 - penalty_percent) as f64 / 100.0).round() as u32
+}
+
+/// Total XP needed to reach `level`
+pub fn xp_for_level(level: u32) -> u32 {
+    LEVEL_XP_STEP * level.saturating_sub(1) * level / 2
+}
+
+/// The level `total_xp` reaches, starting from 1
+pub fn level_for_xp(total_xp: u32) -> u32 {
+    let mut level = 1;
+    while xp_for_level(level + 1) <= total_xp {
+        level += 1;
+    }
+    level
+}
+
+/// xp, subject, level_before, level_after, earned_at
+type XpGainRow = (u32, String, u32, u32, String);
+
+/// Keeps each profile's total XP, the level it gives them and the gains that made it up
+pub struct ExperienceTracker {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ExperienceTracker {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Add XP from a quiz in `subject` to the profile's total
+    pub fn award(&self, profile_id: ProfileId, xp: u32, subject: &str) -> AppResult<XpGain> {
+        let earned_at = Utc::now();
+        let (level_before, level_after) = self.db_manager.transaction(|tx| {
+            let total_before: u32 = tx.query_row(
+                "SELECT total_xp FROM profile_xp WHERE profile_id = ?1",
+                params![profile_id],
+                |row| row.get(0),
+            ).optional()?.unwrap_or(0);
+            let total_after = total_before.saturating_add(xp);
+            let (level_before, level_after) = (level_for_xp(total_before), level_for_xp(total_after));
+
+            tx.execute(
+                "INSERT OR REPLACE INTO profile_xp (profile_id, total_xp, level, updated_at) VALUES (?1, ?2, ?3, ?4)",
+                params![profile_id, total_after, level_after, earned_at.to_rfc3339()],
+            )?;
+            tx.execute(
+                "INSERT INTO xp_gains (profile_id, xp, subject, level_before, level_after, earned_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![profile_id, xp, subject, level_before, level_after, earned_at.to_rfc3339()],
+            )?;
+            Ok((level_before, level_after))
+        })?;
+
+        Ok(XpGain {
+            xp,
+            subject: subject.to_string(),
+            level_before,
+            level_after,
+            earned_at,
+        })
+    }
+
+    /// The profile's level, how far through it they are, and their latest gains
+    pub fn get_level_info(&self, profile_id: ProfileId) -> AppResult<LevelInfo> {
+        let (total_xp, gains): (u32, Vec<XpGainRow>) = self.db_manager.execute(|conn| {
+            let total_xp = conn.query_row(
+                "SELECT total_xp FROM profile_xp WHERE profile_id = ?1",
+                params![profile_id],
+                |row| row.get(0),
+            ).optional()?.unwrap_or(0);
+
+            let mut stmt = conn.prepare(
+                "SELECT xp, subject, level_before, level_after, earned_at FROM xp_gains
+                 WHERE profile_id = ?1 ORDER BY earned_at DESC, id DESC LIMIT ?2"
+            )?;
+            let gains = stmt.query_map(params![profile_id, RECENT_XP_GAINS], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+            })?.collect::<Result<Vec<_>, _>>()?;
+            Ok((total_xp, gains))
+        })?;
+
+        let mut recent_gains = Vec::with_capacity(gains.len());
+        for (xp, subject, level_before, level_after, earned_at) in gains {
+            let earned_at = DateTime::parse_from_rfc3339(&earned_at)
+                .map_err(|e| AppError::Internal(format!("Bad XP timestamp: {}", e)))?
+                .with_timezone(&Utc);
+            recent_gains.push(XpGain { xp, subject, level_before, level_after, earned_at });
+        }
+
+        let level = level_for_xp(total_xp);
+        Ok(LevelInfo {
+            profile_id,
+            level,
+            total_xp,
+            xp_into_level: total_xp - xp_for_level(level),
+            xp_to_next_level: xp_for_level(level + 1) - total_xp,
+            recent_gains,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::Answer;
+    use tempfile::tempdir;
+
+    fn answer(is_correct: bool, time_taken: u32) -> AnswerResult {
+        AnswerResult {
+            question_id: crate::models::QuestionId(1),
+            is_correct,
+            points: if is_correct { 10 } else { 0 },
+            correct_answer: Answer::Text("4".to_string()),
+            explanation: None,
+            time_taken: Some(time_taken),
+            is_boss_question: false,
+            hints_used: 0,
+            blank_results: Vec::new(),
+            partial_credit: None,
+            late: None,
+            answered_by: None,
+            attempts: 1,
+            try_again: false,
+        }
+    }
+
+    #[test]
+    fn test_xp_multipliers_and_levels() {
+        // Base, harder, quick, and the third right in a row
+        assert_eq!(answer_xp(&answer(true, 30), 1, 1, 25), 10);
+        assert_eq!(answer_xp(&answer(true, 30), 5, 1, 25), 20);
+        assert_eq!(answer_xp(&answer(true, 5), 1, 1, 25), 12);
+        assert_eq!(answer_xp(&answer(true, 30), 1, 3, 25), 12);
+        assert_eq!(answer_xp(&answer(false, 5), 5, 0, 25), 0);
+        let hinted = AnswerResult { hints_used: 2, ..answer(true, 30) };
+        assert_eq!(answer_xp(&hinted, 1, 1, 25), 5);
+
+        assert_eq!((level_for_xp(0), level_for_xp(99), level_for_xp(100), level_for_xp(300)), (1, 1, 2, 3));
+
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+        let profile_id = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+        let tracker = ExperienceTracker::new(db_manager);
+
+        let info = tracker.get_level_info(profile_id).unwrap();
+        assert_eq!((info.level, info.total_xp, info.xp_to_next_level), (1, 0, 100));
+
+        assert_eq!(tracker.award(profile_id, 80, "mathematics").unwrap().level_after, 1);
+        let gain = tracker.award(profile_id, 60, "mathematics").unwrap();
+        assert_eq!((gain.level_before, gain.level_after), (1, 2));
+
+        let info = tracker.get_level_info(profile_id).unwrap();
+        assert_eq!((info.level, info.total_xp, info.xp_into_level, info.xp_to_next_level), (2, 140, 40, 160));
+        assert_eq!(info.recent_gains.iter().map(|gain| gain.xp).collect::<Vec<_>>(), vec![60, 80]);
+    }
+}
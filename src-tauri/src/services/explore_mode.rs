@@ -0,0 +1,284 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{Answer, KeyStage, ProfileId, Question, QuestionId};
+use crate::services::ContentManager;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// A tag questions can be browsed by, with how many questions carry it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExploreTopic {
+    pub topic: String,
+    pub question_count: usize,
+}
+
+/// A question as listed while exploring, with what the profile has already done with it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExploreItem {
+    pub question: Question,
+    pub viewed: bool,
+    pub answer_revealed: bool,
+    pub practice_later: bool,
+}
+
+/// The answer to a question, shown on request while exploring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevealedAnswer {
+    pub question_id: QuestionId,
+    pub correct_answer: Answer,
+    pub explanation: Option<String>,
+}
+
+/// A question the profile has set aside to practise
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PracticeLaterItem {
+    pub question: Question,
+    pub marked_at: DateTime<Utc>,
+}
+
+/// Untimed, unscored browsing of questions by topic. Views and revealed answers are
+/// recorded as exposure only: nothing here touches scores, progress or review schedules.
+pub struct ExploreService {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+}
+
+impl ExploreService {
+    pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>) -> Self {
+        Self {
+            db_manager,
+            content_manager,
+        }
+    }
+
+    /// Topics in a subject and key stage, alphabetically
+    pub fn get_topics(&self, subject: &str, key_stage: KeyStage) -> AppResult<Vec<ExploreTopic>> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for question in self.content_manager.get_questions_by_subject(subject, Some(key_stage), None, None)? {
+            for tag in question.tags {
+                *counts.entry(tag).or_insert(0) += 1;
+            }
+        }
+        Ok(counts.into_iter()
+            .map(|(topic, question_count)| ExploreTopic { topic, question_count })
+            .collect())
+    }
+
+    /// Questions on a topic, easiest first; every question in the subject when `topic` is `None`
+    pub fn browse(&self, profile_id: ProfileId, subject: &str, key_stage: KeyStage, topic: Option<&str>) -> AppResult<Vec<ExploreItem>> {
+        let mut questions = self.content_manager.get_questions_by_subject(subject, Some(key_stage), None, None)?;
+        if let Some(topic) = topic {
+            questions.retain(|question| question.tags.iter().any(|tag| tag == topic));
+        }
+        questions.sort_by_key(|question| (question.difficulty_level, question.id.map(|id| id.0)));
+
+        let exposures = self.get_exposures(profile_id)?;
+        let marked = self.get_marked_ids(profile_id)?;
+        Ok(questions.into_iter()
+            .map(|question| {
+                let question_id = question.id.unwrap_or(QuestionId(0));
+                let revealed = exposures.get(&question_id).copied();
+                ExploreItem {
+                    viewed: revealed.is_some(),
+                    answer_revealed: revealed.unwrap_or(false),
+                    practice_later: marked.contains(&question_id),
+                    question,
+                }
+            })
+            .collect())
+    }
+
+    /// Open a question, noting that the profile has seen it
+    pub fn view_question(&self, profile_id: ProfileId, question_id: QuestionId) -> AppResult<Question> {
+        let question = self.content_manager.get_question_by_id(question_id)?;
+        self.record_exposure(profile_id, question_id, false)?;
+        Ok(question)
+    }
+
+    /// Show a question's answer, noting that it was revealed rather than worked out
+    pub fn reveal_answer(&self, profile_id: ProfileId, question_id: QuestionId) -> AppResult<RevealedAnswer> {
+        let question = self.content_manager.get_question_by_id(question_id)?;
+        self.record_exposure(profile_id, question_id, true)?;
+        Ok(RevealedAnswer {
+            question_id,
+            explanation: question.explanation,
+            correct_answer: question.correct_answer,
+        })
+    }
+
+    /// Add a question to, or take it off, the profile's practise-later list
+    pub fn set_practice_later(&self, profile_id: ProfileId, question_id: QuestionId, marked: bool) -> AppResult<()> {
+        if marked {
+            // Fails early on a question that doesn't exist
+            self.content_manager.get_question_by_id(question_id)?;
+        }
+        self.db_manager.execute(|conn| {
+            if marked {
+                conn.execute(
+                    "INSERT OR IGNORE INTO practice_later (profile_id, question_id, marked_at) VALUES (?1, ?2, ?3)",
+                    params![profile_id, question_id, Utc::now().to_rfc3339()],
+                )?;
+            } else {
+                conn.execute(
+                    "DELETE FROM practice_later WHERE profile_id = ?1 AND question_id = ?2",
+                    params![profile_id, question_id],
+                )?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// The profile's practise-later list, oldest first. Questions retired since are left out.
+    pub fn get_practice_later(&self, profile_id: ProfileId) -> AppResult<Vec<PracticeLaterItem>> {
+        let rows = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT question_id, marked_at FROM practice_later
+                 WHERE profile_id = ?1 AND question_id NOT IN (SELECT question_id FROM retired_questions)
+                 ORDER BY marked_at, question_id"
+            )?;
+            let rows = stmt.query_map(params![profile_id], |row| Ok((row.get::<_, QuestionId>(0)?, row.get::<_, String>(1)?)))?;
+            rows.collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let mut items = Vec::with_capacity(rows.len());
+        for (question_id, marked_at) in rows {
+            let marked_at = DateTime::parse_from_rfc3339(&marked_at)
+                .map(|marked_at| marked_at.with_timezone(&Utc))
+                .map_err(|e| AppError::Internal(format!("Bad practice-later timestamp: {}", e)))?;
+            match self.content_manager.get_question_by_id(question_id) {
+                Ok(question) => items.push(PracticeLaterItem { question, marked_at }),
+                Err(e) => log::warn!("Skipping practice-later question {}: {}", question_id, e),
+            }
+        }
+        Ok(items)
+    }
+
+    fn record_exposure(&self, profile_id: ProfileId, question_id: QuestionId, revealed: bool) -> AppResult<()> {
+        let now = Utc::now().to_rfc3339();
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO explore_exposures (profile_id, question_id, view_count, answer_revealed, first_viewed_at, last_viewed_at)
+                 VALUES (?1, ?2, 1, ?3, ?4, ?4)
+                 ON CONFLICT (profile_id, question_id) DO UPDATE SET
+                    view_count = view_count + (NOT ?3),
+                    answer_revealed = answer_revealed OR ?3,
+                    last_viewed_at = ?4",
+                params![profile_id, question_id, revealed, now],
+            )?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Whether the answer was revealed, for each question the profile has explored
+    fn get_exposures(&self, profile_id: ProfileId) -> AppResult<HashMap<QuestionId, bool>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT question_id, answer_revealed FROM explore_exposures WHERE profile_id = ?1"
+            )?;
+            let rows = stmt.query_map(params![profile_id], |row| Ok((row.get::<_, QuestionId>(0)?, row.get::<_, bool>(1)?)))?;
+            rows.collect()
+        })?)
+    }
+
+    fn get_marked_ids(&self, profile_id: ProfileId) -> AppResult<HashSet<QuestionId>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT question_id FROM practice_later WHERE profile_id = ?1")?;
+            let rows = stmt.query_map(params![profile_id], |row| row.get::<_, QuestionId>(0))?;
+            rows.collect()
+        })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::CreateProfileRequest;
+    use crate::services::{ProfileManager, SecurityService};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_exploring_records_exposure_and_practice_later() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let content_manager = Arc::new(ContentManager::new(
+            db_manager.clone(),
+            security_service.clone(),
+            temp_dir.path().join("content"),
+        ));
+        let profile_id = ProfileManager::new(db_manager.clone(), security_service)
+            .create_profile(CreateProfileRequest {
+                name: "Ada".to_string(),
+                avatar: "avatar1".to_string(),
+                theme_preference: None,
+            }).unwrap().id.unwrap();
+
+        db_manager.execute(|conn| {
+            for (n, tags) in [(1, r#"["doubling"]"#), (2, r#"["doubling", "addition"]"#), (3, r#"["addition"]"#)] {
+                conn.execute(
+                    "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
+                     VALUES ((SELECT id FROM subjects WHERE name = 'mathematics'), 'KS2', 'fill_blank', ?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        format!(r#"{{"text":"What is {} + {}?"}}"#, n, n),
+                        format!("\"{}\"", n * 2),
+                        4 - n,
+                        tags,
+                        Utc::now().to_rfc3339(),
+                    ],
+                )?;
+                conn.execute(
+                    "INSERT INTO question_explanations (question_id, explanation) VALUES (?1, ?2)",
+                    params![conn.last_insert_rowid(), format!("Double {}", n)],
+                )?;
+            }
+            Ok(())
+        }).unwrap();
+
+        let service = ExploreService::new(db_manager.clone(), content_manager);
+        assert_eq!(service.get_topics("mathematics", KeyStage::KS2).unwrap(), vec![
+            ExploreTopic { topic: "addition".to_string(), question_count: 2 },
+            ExploreTopic { topic: "doubling".to_string(), question_count: 2 },
+        ]);
+
+        let items = service.browse(profile_id, "mathematics", KeyStage::KS2, Some("doubling")).unwrap();
+        assert_eq!(items.len(), 2);
+        // Easiest first
+        assert_eq!(items[0].question.content.text, "What is 2 + 2?");
+        assert!(items.iter().all(|item| !item.viewed && !item.practice_later));
+
+        let question_id = items[0].question.id.unwrap();
+        service.view_question(profile_id, question_id).unwrap();
+        let revealed = service.reveal_answer(profile_id, question_id).unwrap();
+        assert!(matches!(revealed.correct_answer, Answer::Text(ref answer) if answer == "4"));
+        assert_eq!(revealed.explanation.as_deref(), Some("Double 2"));
+        service.set_practice_later(profile_id, question_id, true).unwrap();
+        service.set_practice_later(profile_id, question_id, true).unwrap();
+
+        let items = service.browse(profile_id, "mathematics", KeyStage::KS2, Some("doubling")).unwrap();
+        assert!(items[0].viewed && items[0].answer_revealed && items[0].practice_later);
+        assert!(!items[1].viewed);
+        assert_eq!(service.get_practice_later(profile_id).unwrap().len(), 1);
+
+        // Recorded as exposure only, never as an answer
+        let answered: i64 = db_manager.execute(|conn| {
+            conn.query_row("SELECT COUNT(*) FROM answered_questions", [], |row| row.get(0))
+        }).unwrap();
+        assert_eq!(answered, 0);
+        let views: i64 = db_manager.execute(|conn| {
+            conn.query_row("SELECT view_count FROM explore_exposures", [], |row| row.get(0))
+        }).unwrap();
+        assert_eq!(views, 1);
+
+        service.set_practice_later(profile_id, question_id, false).unwrap();
+        assert!(service.get_practice_later(profile_id).unwrap().is_empty());
+    }
+}
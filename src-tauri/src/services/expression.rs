@@ -0,0 +1,245 @@
+use crate::errors::{AppError, AppResult};
+
+/// Longest expression accepted, so answer checking stays cheap
+const MAX_EXPRESSION_LENGTH: usize = 64;
+
+/// Deepest bracket nesting accepted
+const MAX_NESTING_DEPTH: usize = 16;
+
+/// Largest exponent accepted for `^`
+const MAX_EXPONENT: f64 = 12.0;
+
+/// Evaluate a small arithmetic expression such as `3+4`, `2 × 5`, `14 ÷ 2` or `(1.5 + 2) * 4`.
+///
+/// Supports numbers (with optional decimals), `+ - * / ^`, the `× x ÷` symbols children type,
+/// unary minus and brackets. Anything else (names, functions, huge powers) is rejected.
+pub fn evaluate(expression: &str) -> AppResult<f64> {
+    if expression.chars().count() > MAX_EXPRESSION_LENGTH {
+        return Err(invalid("Expression is too long"));
+    }
+
+    let tokens = tokenize(expression)?;
+    if tokens.is_empty() {
+        return Err(invalid("Expression is empty"));
+    }
+
+    let mut parser = Parser { tokens, position: 0, depth: 0 };
+    let value = parser.expression()?;
+    if parser.position != parser.tokens.len() {
+        return Err(invalid("Unexpected characters after the expression"));
+    }
+    if !value.is_finite() {
+        return Err(invalid("Expression does not have a finite value"));
+    }
+
+    Ok(value)
+}
+
+/// Compare a submitted answer with the expected one by value.
+///
+/// Returns `None` when the expected answer is not numeric, so callers can fall back to text matching.
+pub fn answers_equal(expected: &str, submitted: &str) -> Option<bool> {
+    let expected = evaluate(expected).ok()?;
+    Some(evaluate(submitted).map_or(false, |submitted| numbers_equal(expected, submitted)))
+}
+
+/// Equal within floating point error, so `0.1 + 0.2` matches `0.3`
+pub fn numbers_equal(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Format a computed value the way it would be written as an answer: `7`, `3.5`, `-0.25`
+pub fn format_number(value: f64) -> String {
+    let rounded = (value * 1e9).round() / 1e9;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        let text = format!("{:.9}", rounded);
+        text.trim_end_matches('0').to_string()
+    }
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::InvalidInput(message.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Plus,
+    Minus,
+    Times,
+    Divide,
+    Power,
+    Open,
+    Close,
+}
+
+fn tokenize(expression: &str) -> AppResult<Vec<Token>> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let token = match c {
+            ' ' | '\t' => {
+                index += 1;
+                continue;
+            }
+            '0'..='9' | '.' => {
+                let start = index;
+                while index < chars.len() && (chars[index].is_ascii_digit() || chars[index] == '.') {
+                    index += 1;
+                }
+                let number: String = chars[start..index].iter().collect();
+                let value = number.parse::<f64>()
+                    .map_err(|_| invalid(&format!("'{}' is not a number", number)))?;
+                tokens.push(Token::Number(value));
+                continue;
+            }
+            '+' => Token::Plus,
+            '-' | '−' => Token::Minus,
+            '*' | '×' | 'x' | 'X' => Token::Times,
+            '/' | '÷' => Token::Divide,
+            '^' => Token::Power,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            other => return Err(invalid(&format!("'{}' is not allowed in an answer", other))),
+        };
+        tokens.push(token);
+        index += 1;
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent over: expression = term (('+' | '-') term)*,
+/// term = factor (('*' | '/') factor)*, factor = unary ('^' factor)?, unary = '-' unary | atom
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn expression(&mut self) -> AppResult<f64> {
+        let mut value = self.term()?;
+        while let Some(token @ (Token::Plus | Token::Minus)) = self.peek() {
+            self.position += 1;
+            let rhs = self.term()?;
+            value = if token == Token::Plus { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> AppResult<f64> {
+        let mut value = self.factor()?;
+        while let Some(token @ (Token::Times | Token::Divide)) = self.peek() {
+            self.position += 1;
+            let rhs = self.factor()?;
+            value = if token == Token::Times {
+                value * rhs
+            } else if rhs == 0.0 {
+                return Err(invalid("Cannot divide by zero"));
+            } else {
+                value / rhs
+            };
+        }
+        Ok(value)
+    }
+
+    fn factor(&mut self) -> AppResult<f64> {
+        let base = self.unary()?;
+        if self.peek() == Some(Token::Power) {
+            self.position += 1;
+            let exponent = self.factor()?;
+            if exponent.abs() > MAX_EXPONENT {
+                return Err(invalid("Power is too large"));
+            }
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    fn unary(&mut self) -> AppResult<f64> {
+        if self.peek() == Some(Token::Minus) {
+            self.position += 1;
+            return Ok(-self.unary()?);
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> AppResult<f64> {
+        match self.advance() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Open) => {
+                self.depth += 1;
+                if self.depth > MAX_NESTING_DEPTH {
+                    return Err(invalid("Too many brackets"));
+                }
+                let value = self.expression()?;
+                if self.advance() != Some(Token::Close) {
+                    return Err(invalid("Missing closing bracket"));
+                }
+                self.depth -= 1;
+                Ok(value)
+            }
+            _ => Err(invalid("Expected a number")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_arithmetic() {
+        assert_eq!(evaluate("3+4").unwrap(), 7.0);
+        assert_eq!(evaluate("2 × 5").unwrap(), 10.0);
+        assert_eq!(evaluate("14÷2").unwrap(), 7.0);
+        assert_eq!(evaluate("2 + 3 * 4").unwrap(), 14.0);
+        assert_eq!(evaluate("(2 + 3) * 4").unwrap(), 20.0);
+        assert_eq!(evaluate("-3 + 10").unwrap(), 7.0);
+        assert_eq!(evaluate("2^3^2").unwrap(), 512.0);
+        assert_eq!(evaluate(".5 * 4").unwrap(), 2.0);
+    }
+
+    #[test]
+    fn test_evaluate_rejects_unsafe_input() {
+        assert!(evaluate("").is_err());
+        assert!(evaluate("7 / 0").is_err());
+        assert!(evaluate("2 ^ 1000").is_err());
+        assert!(evaluate("seven").is_err());
+        assert!(evaluate("(1 + 2").is_err());
+        assert!(evaluate("1.2.3").is_err());
+        assert!(evaluate(&format!("{}1{}", "(".repeat(20), ")".repeat(20))).is_err());
+        assert!(evaluate(&"1+".repeat(40)).is_err());
+    }
+
+    #[test]
+    fn test_answers_equal_and_formatting() {
+        assert_eq!(answers_equal("7", "14/2"), Some(true));
+        assert_eq!(answers_equal("7", "7.0"), Some(true));
+        assert_eq!(answers_equal("0.3", "0.1 + 0.2"), Some(true));
+        assert_eq!(answers_equal("7", "8"), Some(false));
+        assert_eq!(answers_equal("7", "seven"), Some(false));
+        assert_eq!(answers_equal("Paris", "paris"), None);
+
+        assert_eq!(format_number(7.0), "7");
+        assert_eq!(format_number(3.5), "3.5");
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(-0.25), "-0.25");
+    }
+}
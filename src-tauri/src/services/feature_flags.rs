@@ -0,0 +1,358 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::ProfileId;
+use crate::database::DatabaseManager;
+use std::sync::Arc;
+use std::collections::HashMap;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+
+/// Well-known feature flag keys
+pub mod flags {
+    pub const ADAPTIVE_ENGINE: &str = "adaptive_engine";
+    pub const SYNC: &str = "sync";
+    pub const AI_SUGGESTIONS: &str = "ai_suggestions";
+    pub const CLASSROOM_MODE: &str = "classroom_mode";
+}
+
+/// Static definition of a feature flag and its default value
+struct FlagDefinition {
+    key: &'static str,
+    description: &'static str,
+    default_enabled: bool,
+}
+
+/// Registry of all known feature flags. Flags not listed here are rejected.
+const FLAG_DEFINITIONS: &[FlagDefinition] = &[
+    FlagDefinition {
+        key: flags::ADAPTIVE_ENGINE,
+        description: "Adaptive difficulty engine that adjusts questions to the child's performance",
+        default_enabled: false,
+    },
+    FlagDefinition {
+        key: flags::SYNC,
+        description: "Synchronise profiles and progress between devices",
+        default_enabled: false,
+    },
+    FlagDefinition {
+        key: flags::AI_SUGGESTIONS,
+        description: "Suggested follow-up topics based on quiz results",
+        default_enabled: false,
+    },
+    FlagDefinition {
+        key: flags::CLASSROOM_MODE,
+        description: "Teacher mode with classes, roster import, group assignments and class reports",
+        default_enabled: false,
+    },
+];
+
+/// Where the effective value of a flag came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum FlagSource {
+    #[serde(rename = "default")]
+    Default,
+    #[serde(rename = "global")]
+    Global,
+    #[serde(rename = "profile")]
+    Profile,
+}
+
+/// Resolved state of a feature flag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlag {
+    pub key: String,
+    pub description: String,
+    pub enabled: bool,
+    pub default_enabled: bool,
+    pub source: FlagSource,
+}
+
+/// Feature flag service for gating experimental subsystems
+pub struct FeatureFlagService {
+    db_manager: Arc<DatabaseManager>,
+    configured_defaults: HashMap<String, bool>,
+}
+
+impl FeatureFlagService {
+    /// Create a new feature flag service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            db_manager,
+            configured_defaults: HashMap::new(),
+        }
+    }
+
+    /// Replace built-in defaults with values from the app configuration.
+    /// Unknown flag names are ignored with a warning.
+    pub fn with_defaults(mut self, defaults: &HashMap<String, bool>) -> Self {
+        for (key, enabled) in defaults {
+            match Self::definition(key) {
+                Ok(definition) => {
+                    self.configured_defaults.insert(definition.key.to_string(), *enabled);
+                },
+                Err(_) => log::warn!("Ignoring unknown feature flag in configuration: {}", key),
+            }
+        }
+        self
+    }
+
+    /// Check whether a flag is enabled, taking profile overrides into account
+    pub fn is_enabled(&self, key: &str, profile_id: Option<ProfileId>) -> AppResult<bool> {
+        Ok(self.resolve_flag(key, profile_id)?.enabled)
+    }
+
+    /// Get the resolved state of every known flag
+    pub fn get_flags(&self, profile_id: Option<ProfileId>) -> AppResult<Vec<FeatureFlag>> {
+        let global_values = self.get_global_values()?;
+        let profile_values = match profile_id {
+            Some(id) => self.get_profile_values(id)?,
+            None => HashMap::new(),
+        };
+
+        Ok(FLAG_DEFINITIONS.iter()
+            .map(|definition| self.resolve(
+                definition,
+                global_values.get(definition.key).copied(),
+                profile_values.get(definition.key).copied(),
+            ))
+            .collect())
+    }
+
+    /// Set the global value of a flag
+    pub fn set_flag(&self, key: &str, enabled: bool) -> AppResult<FeatureFlag> {
+        let definition = Self::definition(key)?;
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO feature_flags (flag_key, enabled, updated_at) VALUES (?1, ?2, ?3)",
+                params![definition.key, enabled, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        self.resolve_flag(key, None)
+    }
+
+    /// Remove the global value of a flag so the default applies again
+    pub fn reset_flag(&self, key: &str) -> AppResult<FeatureFlag> {
+        let definition = Self::definition(key)?;
+
+        self.db_manager.execute(|conn| {
+            conn.execute("DELETE FROM feature_flags WHERE flag_key = ?1", params![definition.key])?;
+            Ok(())
+        })?;
+
+        self.resolve_flag(key, None)
+    }
+
+    /// Override a flag for a single profile
+    pub fn set_profile_override(&self, profile_id: ProfileId, key: &str, enabled: bool) -> AppResult<FeatureFlag> {
+        let definition = Self::definition(key)?;
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO profile_feature_flags (profile_id, flag_key, enabled, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![profile_id, definition.key, enabled, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        self.resolve_flag(key, Some(profile_id))
+    }
+
+    /// Remove a profile override so the global value applies again
+    pub fn clear_profile_override(&self, profile_id: ProfileId, key: &str) -> AppResult<FeatureFlag> {
+        let definition = Self::definition(key)?;
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "DELETE FROM profile_feature_flags WHERE profile_id = ?1 AND flag_key = ?2",
+                params![profile_id, definition.key],
+            )?;
+            Ok(())
+        })?;
+
+        self.resolve_flag(key, Some(profile_id))
+    }
+
+    /// Resolve a single flag: profile override, then global value, then default
+    fn resolve_flag(&self, key: &str, profile_id: Option<ProfileId>) -> AppResult<FeatureFlag> {
+        let definition = Self::definition(key)?;
+
+        let (global_value, profile_value) = self.db_manager.execute(|conn| {
+            let global_value: Option<bool> = conn.query_row(
+                "SELECT enabled FROM feature_flags WHERE flag_key = ?1",
+                params![definition.key],
+                |row| row.get(0),
+            ).optional()?;
+
+            let profile_value: Option<bool> = match profile_id {
+                Some(id) => conn.query_row(
+                    "SELECT enabled FROM profile_feature_flags WHERE profile_id = ?1 AND flag_key = ?2",
+                    params![id, definition.key],
+                    |row| row.get(0),
+                ).optional()?,
+                None => None,
+            };
+
+            Ok((global_value, profile_value))
+        })?;
+
+        Ok(self.resolve(definition, global_value, profile_value))
+    }
+
+    fn resolve(
+        &self,
+        definition: &FlagDefinition,
+        global_value: Option<bool>,
+        profile_value: Option<bool>,
+    ) -> FeatureFlag {
+        let default_enabled = self.configured_defaults.get(definition.key)
+            .copied()
+            .unwrap_or(definition.default_enabled);
+
+        let (enabled, source) = match (profile_value, global_value) {
+            (Some(value), _) => (value, FlagSource::Profile),
+            (None, Some(value)) => (value, FlagSource::Global),
+            (None, None) => (default_enabled, FlagSource::Default),
+        };
+
+        FeatureFlag {
+            key: definition.key.to_string(),
+            description: definition.description.to_string(),
+            enabled,
+            default_enabled,
+            source,
+        }
+    }
+
+    fn get_global_values(&self) -> AppResult<HashMap<String, bool>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT flag_key, enabled FROM feature_flags")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+            })?;
+
+            let mut values = HashMap::new();
+            for row in rows {
+                let (key, enabled) = row?;
+                values.insert(key, enabled);
+            }
+
+            Ok(values)
+        })?)
+    }
+
+    fn get_profile_values(&self, profile_id: ProfileId) -> AppResult<HashMap<String, bool>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT flag_key, enabled FROM profile_feature_flags WHERE profile_id = ?1"
+            )?;
+            let rows = stmt.query_map(params![profile_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+            })?;
+
+            let mut values = HashMap::new();
+            for row in rows {
+                let (key, enabled) = row?;
+                values.insert(key, enabled);
+            }
+
+            Ok(values)
+        })?)
+    }
+
+    fn definition(key: &str) -> AppResult<&'static FlagDefinition> {
+        FLAG_DEFINITIONS.iter()
+            .find(|definition| definition.key == key)
+            .ok_or_else(|| AppError::InvalidInput(format!("Unknown feature flag: {}", key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    fn create_test_feature_flag_service() -> (FeatureFlagService, Arc<DatabaseManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let service = FeatureFlagService::new(db_service.manager());
+
+        (service, db_service.manager(), temp_dir)
+    }
+
+    fn create_test_profile(db_manager: &DatabaseManager) -> ProfileId {
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Flag Tester', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_defaults_and_unknown_flags() {
+        let (service, _db, _temp_dir) = create_test_feature_flag_service();
+
+        let all_flags = service.get_flags(None).unwrap();
+        assert_eq!(all_flags.len(), FLAG_DEFINITIONS.len());
+        assert!(all_flags.iter().all(|f| f.source == FlagSource::Default));
+
+        assert!(!service.is_enabled(flags::ADAPTIVE_ENGINE, None).unwrap());
+        assert!(matches!(service.is_enabled("does_not_exist", None), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_global_value_and_reset() {
+        let (service, _db, _temp_dir) = create_test_feature_flag_service();
+
+        let flag = service.set_flag(flags::SYNC, true).unwrap();
+        assert!(flag.enabled);
+        assert_eq!(flag.source, FlagSource::Global);
+
+        let flag = service.reset_flag(flags::SYNC).unwrap();
+        assert!(!flag.enabled);
+        assert_eq!(flag.source, FlagSource::Default);
+    }
+
+    #[test]
+    fn test_profile_override_takes_precedence() {
+        let (service, db, _temp_dir) = create_test_feature_flag_service();
+        let profile_id = create_test_profile(&db);
+
+        service.set_flag(flags::AI_SUGGESTIONS, true).unwrap();
+        service.set_profile_override(profile_id, flags::AI_SUGGESTIONS, false).unwrap();
+
+        assert!(service.is_enabled(flags::AI_SUGGESTIONS, None).unwrap());
+        assert!(!service.is_enabled(flags::AI_SUGGESTIONS, Some(profile_id)).unwrap());
+
+        let flag = service.clear_profile_override(profile_id, flags::AI_SUGGESTIONS).unwrap();
+        assert!(flag.enabled);
+        assert_eq!(flag.source, FlagSource::Global);
+    }
+
+    #[test]
+    fn test_configured_defaults() {
+        let (service, _db, _temp_dir) = create_test_feature_flag_service();
+        let mut defaults = HashMap::new();
+        defaults.insert(flags::SYNC.to_string(), true);
+        defaults.insert("retired_flag".to_string(), true);
+        let service = service.with_defaults(&defaults);
+
+        let flag = service.reset_flag(flags::SYNC).unwrap();
+        assert!(flag.enabled);
+        assert!(flag.default_enabled);
+        assert_eq!(flag.source, FlagSource::Default);
+
+        service.set_flag(flags::SYNC, false).unwrap();
+        assert!(!service.is_enabled(flags::SYNC, None).unwrap());
+    }
+}
@@ -0,0 +1,134 @@
+use crate::errors::AppResult;
+use crate::database::DatabaseManager;
+use crate::models::{Answer, Locale, Question};
+use crate::services::households::ACTIVE_HOUSEHOLD_SQL;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use chrono::Utc;
+
+/// Tag on questions about money, which are asked in the family's own currency
+pub const MONEY_TAG: &str = "money";
+
+/// Each household's locale, used wherever questions and reports write money, numbers
+/// and dates
+pub struct FormattingService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl FormattingService {
+    /// Create a new formatting service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// The active household's locale; UK until a parent picks another
+    pub fn get_locale(&self) -> AppResult<Locale> {
+        let tag: Option<String> = self.db_manager.execute(|conn| {
+            conn.query_row(
+                &format!("SELECT locale FROM locale_settings WHERE household_id = {}", ACTIVE_HOUSEHOLD_SQL),
+                [],
+                |row| row.get(0),
+            ).optional()
+        })?;
+
+        Ok(match tag {
+            Some(tag) => Locale::from_tag(&tag).unwrap_or_else(|| {
+                log::warn!("Unknown locale '{}', using {}", tag, Locale::default().as_str());
+                Locale::default()
+            }),
+            None => Locale::default(),
+        })
+    }
+
+    /// Save the active household's locale
+    pub fn set_locale(&self, locale: Locale) -> AppResult<Locale> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO locale_settings (household_id, locale, updated_at) VALUES ({}, ?1, ?2)",
+                    ACTIVE_HOUSEHOLD_SQL
+                ),
+                params![locale.as_str(), Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+        self.get_locale()
+    }
+}
+
+/// Ask a money question written for UK families in `locale`'s currency. The text, options
+/// and answer are all rewritten so the question still marks correctly.
+pub fn localize_money_question(question: &mut Question, locale: Locale) {
+    if locale == Locale::EnGb || !question.tags.iter().any(|tag| tag == MONEY_TAG) {
+        return;
+    }
+
+    let content = &mut question.content;
+    content.text = locale.localize_money_text(&content.text);
+    if let Some(options) = content.options.as_mut() {
+        for option in options.iter_mut() {
+            *option = locale.localize_money_text(option);
+        }
+    }
+    if let Some(blanks) = content.blanks.as_mut() {
+        for blank in blanks.iter_mut() {
+            blank.expected_answer = locale.localize_money_text(&blank.expected_answer);
+            for alternative in blank.accept_alternatives.iter_mut().flatten() {
+                *alternative = locale.localize_money_text(alternative);
+            }
+        }
+    }
+
+    match &mut question.correct_answer {
+        Answer::Text(answer) => *answer = locale.localize_money_text(answer),
+        Answer::Multiple(answers) => {
+            for answer in answers.iter_mut() {
+                *answer = locale.localize_money_text(answer);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{KeyStage, QuestionContent, QuestionType};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_locale_setting_rewrites_money_questions() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+
+        let service = FormattingService::new(db_service.manager());
+        assert_eq!(service.get_locale().unwrap(), Locale::EnGb);
+        assert_eq!(service.set_locale(Locale::EnUs).unwrap(), Locale::EnUs);
+
+        let mut question = Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: "How much is 50p + 20p + 10p?".to_string(),
+                options: Some(vec!["70p".to_string(), "80p".to_string(), "£1".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("80p".to_string()),
+        ).with_tags(vec![MONEY_TAG.to_string()]);
+
+        localize_money_question(&mut question, Locale::EnGb);
+        assert_eq!(question.content.text, "How much is 50p + 20p + 10p?");
+
+        localize_money_question(&mut question, service.get_locale().unwrap());
+        assert_eq!(question.content.text, "How much is 50¢ + 20¢ + 10¢?");
+        assert_eq!(question.content.options.unwrap(), vec!["70¢", "80¢", "$1"]);
+        assert!(matches!(question.correct_answer, Answer::Text(ref answer) if answer == "80¢"));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{ProfileId, SessionId};
+use crate::database::DatabaseManager;
+use crate::services::quiz_engine::SessionMode;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Months, Utc};
+
+/// How often the pruning job runs
+const PRUNE_INTERVAL_HOURS: i64 = 24;
+
+/// Detailed per-question records that are pruned once they pass the retention period.
+/// Each entry is the table, the column recording when the row was made, and a label for
+/// the report. Progress, answer counts and other aggregated stats are kept forever.
+const DETAIL_TABLES: &[(&str, &str, &str)] = &[
+    ("answer_drawings", "submitted_at", "Drawn answers"),
+    ("scratchpad_entries", "updated_at", "Scratchpad working"),
+    ("spelling_attempts", "attempted_at", "Spelling attempts"),
+    ("quiz_incidents", "occurred_at", "Quiz incidents"),
+];
+
+/// Parent-configured retention for quiz history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionSettings {
+    /// Months detailed per-question records are kept for, or `None` to keep them forever
+    pub detail_retention_months: Option<u32>,
+}
+
+/// How many records of one kind a pruning run removed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrunedRecords {
+    pub label: String,
+    pub count: u32,
+}
+
+/// What a pruning run condensed and removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub ran_at: DateTime<Utc>,
+    /// Detailed records from before this were pruned
+    pub cutoff: DateTime<Utc>,
+    /// Session timelines replaced by a one-line summary
+    pub sessions_condensed: u32,
+    pub records_removed: Vec<PrunedRecords>,
+}
+
+impl RetentionReport {
+    pub fn total_removed(&self) -> u32 {
+        self.records_removed.iter().map(|records| records.count).sum()
+    }
+}
+
+/// Summary kept for a quiz session after its timeline has been pruned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CondensedSession {
+    pub session_id: SessionId,
+    pub profile_id: ProfileId,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub questions_answered: u32,
+    pub correct_answers: u32,
+    pub hints_used: u32,
+    pub mode: SessionMode,
+    pub times_left: u32,
+}
+
+/// Prunes detailed quiz history past the parent's retention period, condensing session
+/// timelines into summaries so aggregated stats stay intact
+pub struct HistoryRetentionService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl HistoryRetentionService {
+    /// Create a new history retention service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Get the retention settings
+    pub fn get_settings(&self) -> AppResult<RetentionSettings> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT detail_retention_months FROM retention_settings WHERE id = 1",
+                [],
+                |row| Ok(RetentionSettings { detail_retention_months: row.get(0)? }),
+            )
+        })?)
+    }
+
+    /// Save the retention settings. They take effect on the next pruning run.
+    pub fn update_settings(&self, settings: &RetentionSettings) -> AppResult<RetentionSettings> {
+        if settings.detail_retention_months == Some(0) {
+            return Err(AppError::InvalidInput("History must be kept for at least one month".to_string()));
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE retention_settings SET detail_retention_months = ?1, updated_at = ?2 WHERE id = 1",
+                params![settings.detail_retention_months, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        self.get_settings()
+    }
+
+    /// Prune unless a run happened in the last day. Returns `None` when skipped, or when
+    /// history is kept forever.
+    pub fn prune_if_due(&self, now: DateTime<Utc>) -> AppResult<Option<RetentionReport>> {
+        let last_run = self.get_reports(1)?.into_iter().next();
+        if let Some(last_run) = last_run {
+            if now - last_run.ran_at < Duration::hours(PRUNE_INTERVAL_HOURS) {
+                return Ok(None);
+            }
+        }
+        self.prune(now)
+    }
+
+    /// Condense session timelines and remove detailed records older than the retention
+    /// period. Returns `None` when history is kept forever.
+    pub fn prune(&self, now: DateTime<Utc>) -> AppResult<Option<RetentionReport>> {
+        let months = match self.get_settings()?.detail_retention_months {
+            Some(months) => months,
+            None => return Ok(None),
+        };
+        let cutoff = now.checked_sub_months(Months::new(months))
+            .ok_or_else(|| AppError::InvalidInput(format!("Invalid retention period of {} months", months)))?;
+        let cutoff_text = cutoff.to_rfc3339();
+
+        let report = self.db_manager.transaction(|tx| {
+            // Sessions whose last event is before the cutoff are summarised, then their
+            // timeline removed
+            let sessions_condensed = tx.execute(
+                "INSERT OR REPLACE INTO condensed_sessions
+                    (session_id, profile_id, started_at, completed_at, questions_answered, correct_answers, hints_used, mode, times_left, condensed_at)
+                 SELECT session_id, MIN(profile_id), MIN(occurred_at),
+                        MAX(CASE WHEN kind = 'completed' THEN occurred_at END),
+                        SUM(kind = 'answered'),
+                        SUM(kind = 'answered' AND json_extract(details, '$.is_correct') = 1),
+                        SUM(kind = 'hint_used'),
+                        COALESCE(MAX(CASE WHEN kind = 'started' THEN json_extract(details, '$.mode') END), 'standard'),
+                        SUM(kind = 'left_session'),
+                        ?2
+                 FROM session_events
+                 GROUP BY session_id
+                 HAVING MAX(occurred_at) < ?1",
+                params![cutoff_text, now.to_rfc3339()],
+            )? as u32;
+            let events_removed = tx.execute(
+                "DELETE FROM session_events WHERE session_id IN (SELECT session_id FROM condensed_sessions)",
+                [],
+            )? as u32;
+
+            let mut records_removed = vec![PrunedRecords {
+                label: "Session timeline events".to_string(),
+                count: events_removed,
+            }];
+            for (table, column, label) in DETAIL_TABLES {
+                let count = tx.execute(
+                    &format!("DELETE FROM {} WHERE {} < ?1", table, column),
+                    params![cutoff_text],
+                )? as u32;
+                records_removed.push(PrunedRecords { label: label.to_string(), count });
+            }
+
+            let report = RetentionReport {
+                ran_at: now,
+                cutoff,
+                sessions_condensed,
+                records_removed,
+            };
+            let summary = serde_json::to_string(&report)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+            tx.execute(
+                "INSERT INTO retention_runs (ran_at, cutoff, summary) VALUES (?1, ?2, ?3)",
+                params![now.to_rfc3339(), cutoff_text, summary],
+            )?;
+            Ok(report)
+        })?;
+
+        if report.total_removed() > 0 {
+            self.db_manager.schedule_compaction();
+        }
+        Ok(Some(report))
+    }
+
+    /// Reports from the most recent pruning runs, newest first
+    pub fn get_reports(&self, limit: u32) -> AppResult<Vec<RetentionReport>> {
+        let summaries: Vec<String> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT summary FROM retention_runs ORDER BY id DESC LIMIT ?1")?;
+            let rows = stmt.query_map(params![limit], |row| row.get(0))?;
+            rows.collect()
+        })?;
+
+        summaries.iter()
+            .map(|summary| serde_json::from_str(summary).map_err(AppError::from))
+            .collect()
+    }
+
+    /// The summary kept for a session whose timeline was pruned
+    pub fn get_condensed_session(&self, session_id: SessionId) -> AppResult<Option<CondensedSession>> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT session_id, profile_id, started_at, completed_at, questions_answered, correct_answers, hints_used, mode, times_left
+                 FROM condensed_sessions WHERE session_id = ?1",
+                params![session_id],
+                |row| {
+                    let parse = |index: usize, value: String| {
+                        DateTime::parse_from_rfc3339(&value)
+                            .map(|date| date.with_timezone(&Utc))
+                            .map_err(|_| rusqlite::Error::InvalidColumnType(index, "condensed_sessions".to_string(), rusqlite::types::Type::Text))
+                    };
+                    Ok(CondensedSession {
+                        session_id: row.get(0)?,
+                        profile_id: row.get(1)?,
+                        started_at: parse(2, row.get(2)?)?,
+                        completed_at: row.get::<_, Option<String>>(3)?.map(|value| parse(3, value)).transpose()?,
+                        questions_answered: row.get(4)?,
+                        correct_answers: row.get(5)?,
+                        hints_used: row.get(6)?,
+                        mode: SessionMode::parse(&row.get::<_, String>(7)?).unwrap_or_default(),
+                        times_left: row.get(8)?,
+                    })
+                },
+            ).optional()
+        })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::Answer;
+    use crate::services::{SessionEvent, SessionReplayLog};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_old_sessions_are_condensed_and_details_pruned() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let profile_id = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+
+        let replay_log = SessionReplayLog::new(db_manager.clone());
+        for session_id in [SessionId(1), SessionId(2)] {
+            replay_log.record(session_id, profile_id, None, &SessionEvent::Started { question_count: 1, mode: SessionMode::Assessment }).unwrap();
+            replay_log.record(session_id, profile_id, Some((0, None)), &SessionEvent::Answered {
+                answer: Answer::Text("4".to_string()),
+                is_correct: true,
+                points: 10,
+                reported_time_seconds: 5,
+            }).unwrap();
+            replay_log.record(session_id, profile_id, None, &SessionEvent::Completed { correct_answers: 1, total_questions: 1 }).unwrap();
+        }
+        let two_years_ago = (Utc::now() - Duration::days(730)).to_rfc3339();
+        db_manager.execute(|conn| {
+            conn.execute("UPDATE session_events SET occurred_at = ?1 WHERE session_id = 1", params![two_years_ago])?;
+            conn.execute(
+                "INSERT INTO quiz_incidents (session_id, profile_id, stage, error, occurred_at) VALUES (1, ?1, 'display', 'broken', ?2)",
+                params![profile_id, two_years_ago],
+            )
+        }).unwrap();
+
+        let service = HistoryRetentionService::new(db_manager.clone());
+        assert_eq!(service.get_settings().unwrap().detail_retention_months, Some(12));
+
+        let now = Utc::now();
+        let report = service.prune_if_due(now).unwrap().unwrap();
+        assert_eq!(report.sessions_condensed, 1);
+        assert_eq!(report.total_removed(), 4);
+        assert!(report.records_removed.contains(&PrunedRecords { label: "Quiz incidents".to_string(), count: 1 }));
+
+        let condensed = service.get_condensed_session(SessionId(1)).unwrap().unwrap();
+        assert_eq!(condensed.questions_answered, 1);
+        assert_eq!(condensed.correct_answers, 1);
+        assert_eq!(condensed.mode, SessionMode::Assessment);
+        assert!(condensed.completed_at.is_some());
+        assert!(service.get_condensed_session(SessionId(2)).unwrap().is_none());
+        assert!(replay_log.get_replay(SessionId(2)).is_ok());
+        assert_eq!(replay_log.last_session_id().unwrap(), 2);
+
+        // Already ran today
+        assert!(service.prune_if_due(now + Duration::hours(1)).unwrap().is_none());
+        assert_eq!(service.get_reports(10).unwrap().len(), 1);
+
+        service.update_settings(&RetentionSettings { detail_retention_months: None }).unwrap();
+        assert!(service.prune(now).unwrap().is_none());
+    }
+}
@@ -0,0 +1,364 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use std::sync::Arc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use chrono::{DateTime, Duration, Utc};
+
+/// The household every install starts with, and that existing data belongs to
+pub const DEFAULT_HOUSEHOLD_ID: u32 = 1;
+/// Shortest PIN or passphrase a parent may choose
+const MIN_CREDENTIAL_LENGTH: usize = 4;
+/// Rounds of hashing, so a copied database can't be brute-forced quickly
+const CREDENTIAL_HASH_ITERATIONS: u32 = 100_000;
+const SALT_BYTES: usize = 16;
+/// Wrong PINs in a row allowed before the PIN is locked
+const FREE_CREDENTIAL_ATTEMPTS: u32 = 5;
+/// First lockout, doubled for each further wrong PIN up to `MAX_LOCKOUT_SECONDS`
+const BASE_LOCKOUT_SECONDS: i64 = 30;
+const MAX_LOCKOUT_SECONDS: i64 = 60 * 60;
+const MAX_NAME_LENGTH: usize = 50;
+
+/// The household the app was last opened as
+pub(crate) const ACTIVE_HOUSEHOLD_SQL: &str = "(SELECT household_id FROM active_household WHERE id = 1)";
+/// Ids of the profiles in the active household. Profiles with no household recorded
+/// belong to the default one.
+pub(crate) const HOUSEHOLD_PROFILES_SQL: &str = "SELECT p.id FROM profiles p
+     LEFT JOIN profile_households ph ON ph.profile_id = p.id
+     WHERE COALESCE(ph.household_id, 1) = (SELECT household_id FROM active_household WHERE id = 1)";
+/// Ids of questions parents in other households wrote or imported. Everything else is
+/// shared.
+pub(crate) const OTHER_HOUSEHOLD_QUESTIONS_SQL: &str = "SELECT question_id FROM question_households
+     WHERE household_id != (SELECT household_id FROM active_household WHERE id = 1)";
+
+/// An independent family sharing the computer, with its own parent PIN, profiles and
+/// questions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Household {
+    pub id: u32,
+    pub name: String,
+    pub profile_count: u32,
+    /// Whether a parent PIN has been set
+    pub has_credential: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Keeps families on a shared computer apart. One household is active at a time, chosen
+/// when the app starts; profiles and parent-written questions are only visible in the
+/// household they were made in.
+pub struct HouseholdManager {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl HouseholdManager {
+    /// Create a new household manager
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Every household, in the order they were added
+    pub fn get_households(&self) -> AppResult<Vec<Household>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT h.id, h.name, h.credential_hash IS NOT NULL, h.created_at,
+                        (SELECT COUNT(*) FROM profiles p
+                         LEFT JOIN profile_households ph ON ph.profile_id = p.id
+                         WHERE COALESCE(ph.household_id, 1) = h.id)
+                 FROM households h ORDER BY h.id"
+            )?;
+            let households = stmt.query_map([], row_to_household)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(households)
+        })?)
+    }
+
+    pub fn get_household(&self, household_id: u32) -> AppResult<Household> {
+        self.get_households()?
+            .into_iter()
+            .find(|household| household.id == household_id)
+            .ok_or_else(|| AppError::NotFound(format!("Household with id {} not found", household_id)))
+    }
+
+    pub fn active_household_id(&self) -> AppResult<u32> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(&format!("SELECT {}", ACTIVE_HOUSEHOLD_SQL), [], |row| row.get(0))
+        })?)
+    }
+
+    pub fn get_active_household(&self) -> AppResult<Household> {
+        self.get_household(self.active_household_id()?)
+    }
+
+    /// Add a household with its own parent PIN. It is not made active.
+    pub fn create_household(&self, name: &str, credential: &str) -> AppResult<Household> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidInput("Household name cannot be empty".to_string()));
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(AppError::InvalidInput(format!(
+                "Household name too long (max {} characters)", MAX_NAME_LENGTH
+            )));
+        }
+        let credential = HashedCredential::new(credential)?;
+
+        let household_id = self.db_manager.transaction(|tx| {
+            let name_taken: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM households WHERE LOWER(name) = LOWER(?1))",
+                params![name],
+                |row| row.get(0),
+            )?;
+            if name_taken {
+                return Ok(None);
+            }
+
+            tx.execute(
+                "INSERT INTO households (name, created_at) VALUES (?1, ?2)",
+                params![name, Utc::now().to_rfc3339()],
+            )?;
+            let household_id = tx.last_insert_rowid() as u32;
+            credential.store(tx, household_id)?;
+            Ok(Some(household_id))
+        })?.ok_or_else(|| AppError::InvalidInput("Household name already exists".to_string()))?;
+
+        self.get_household(household_id)
+    }
+
+    /// Make `household_id` the active household
+    pub fn select_household(&self, household_id: u32) -> AppResult<Household> {
+        let household = self.get_household(household_id)?;
+        self.db_manager.execute(|conn| {
+            conn.execute("UPDATE active_household SET household_id = ?1 WHERE id = 1", params![household_id])
+        })?;
+        Ok(household)
+    }
+
+    /// Change the active household's parent PIN
+    pub fn set_credential(&self, credential: &str) -> AppResult<()> {
+        let credential = HashedCredential::new(credential)?;
+        let household_id = self.active_household_id()?;
+        self.db_manager.execute(|conn| credential.store(conn, household_id))?;
+        Ok(())
+    }
+
+    /// Whether `credential` matches the active household's parent PIN. Always false if
+    /// none has been set. After `FREE_CREDENTIAL_ATTEMPTS` wrong PINs in a row the PIN is
+    /// locked for a while, longer with each further wrong PIN, and even the right PIN is
+    /// refused until the lockout ends.
+    pub fn verify_credential(&self, credential: &str, now: DateTime<Utc>) -> AppResult<bool> {
+        let stored = self.db_manager.execute(|conn| {
+            conn.query_row(
+                &format!(
+                    "SELECT id, credential_salt, credential_hash, credential_iterations, failed_credential_attempts,
+                            credential_locked_until
+                     FROM households WHERE id = {} AND credential_hash IS NOT NULL",
+                    ACTIVE_HOUSEHOLD_SQL
+                ),
+                [],
+                |row| Ok((
+                    row.get::<_, u32>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, u32>(3)?,
+                    row.get::<_, u32>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                )),
+            ).optional()
+        })?;
+        let (household_id, salt, hash, iterations, failed_attempts, locked_until) = match stored {
+            Some(stored) => stored,
+            None => return Ok(false),
+        };
+
+        let locked_until = locked_until
+            .and_then(|locked_until| DateTime::parse_from_rfc3339(&locked_until).ok())
+            .map(|locked_until| locked_until.with_timezone(&Utc));
+        if let Some(locked_until) = locked_until.filter(|locked_until| *locked_until > now) {
+            return Err(AppError::Authentication(format!(
+                "Too many wrong PINs. Try again in {} seconds.",
+                (locked_until - now).num_seconds().max(1)
+            )));
+        }
+
+        if hash_credential(&salt, credential, iterations) == hash {
+            if failed_attempts > 0 {
+                self.db_manager.execute(|conn| {
+                    conn.execute(
+                        "UPDATE households SET failed_credential_attempts = 0, credential_locked_until = NULL WHERE id = ?1",
+                        params![household_id],
+                    )
+                })?;
+            }
+            return Ok(true);
+        }
+
+        let failed_attempts = failed_attempts + 1;
+        let locked_until = (failed_attempts >= FREE_CREDENTIAL_ATTEMPTS).then(|| {
+            let doublings = (failed_attempts - FREE_CREDENTIAL_ATTEMPTS).min(16);
+            now + Duration::seconds((BASE_LOCKOUT_SECONDS << doublings).min(MAX_LOCKOUT_SECONDS))
+        });
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE households SET failed_credential_attempts = ?1, credential_locked_until = ?2 WHERE id = ?3",
+                params![failed_attempts, locked_until.map(|locked_until| locked_until.to_rfc3339()), household_id],
+            )
+        })?;
+        Ok(false)
+    }
+}
+
+fn row_to_household(row: &rusqlite::Row) -> rusqlite::Result<Household> {
+    Ok(Household {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        has_credential: row.get(2)?,
+        created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+            .map_err(|_| rusqlite::Error::InvalidColumnType(3, "created_at".to_string(), rusqlite::types::Type::Text))?
+            .with_timezone(&Utc),
+        profile_count: row.get(4)?,
+    })
+}
+
+/// A parent PIN salted and hashed, ready to be stored
+pub(crate) struct HashedCredential {
+    salt: String,
+    hash: String,
+}
+
+impl HashedCredential {
+    pub(crate) fn new(credential: &str) -> AppResult<Self> {
+        if credential.chars().count() < MIN_CREDENTIAL_LENGTH {
+            return Err(AppError::InvalidInput(format!(
+                "The PIN must be at least {} characters", MIN_CREDENTIAL_LENGTH
+            )));
+        }
+
+        let mut salt = [0u8; SALT_BYTES];
+        getrandom::getrandom(&mut salt)
+            .map_err(|e| AppError::Security(format!("Failed to generate salt: {}", e)))?;
+        let salt = hex::encode(salt);
+        let hash = hash_credential(&salt, credential, CREDENTIAL_HASH_ITERATIONS);
+        Ok(Self { salt, hash })
+    }
+
+    /// Make this the parent PIN of `household_id`
+    pub(crate) fn store(&self, conn: &Connection, household_id: u32) -> rusqlite::Result<()> {
+        conn.execute(
+            "UPDATE households SET credential_salt = ?1, credential_hash = ?2, credential_iterations = ?3,
+                 failed_credential_attempts = 0, credential_locked_until = NULL
+             WHERE id = ?4",
+            params![self.salt, self.hash, CREDENTIAL_HASH_ITERATIONS, household_id],
+        )?;
+        Ok(())
+    }
+}
+
+/// Put a newly created profile in the active household
+pub(crate) fn record_profile_household(conn: &Connection, profile_id: crate::models::ProfileId) -> rusqlite::Result<()> {
+    conn.execute(
+        &format!("INSERT OR REPLACE INTO profile_households (profile_id, household_id) VALUES (?1, {})", ACTIVE_HOUSEHOLD_SQL),
+        params![profile_id],
+    )?;
+    Ok(())
+}
+
+/// Keep a parent-written or imported question to the active household
+pub(crate) fn record_question_household(conn: &Connection, question_id: crate::models::QuestionId) -> rusqlite::Result<()> {
+    conn.execute(
+        &format!("INSERT OR REPLACE INTO question_households (question_id, household_id) VALUES (?1, {})", ACTIVE_HOUSEHOLD_SQL),
+        params![question_id],
+    )?;
+    Ok(())
+}
+
+fn hash_credential(salt: &str, credential: &str, iterations: u32) -> String {
+    let mut digest = Sha256::digest(format!("{}{}", salt, credential).as_bytes());
+    for _ in 1..iterations {
+        digest = Sha256::digest(digest);
+    }
+    hex::encode(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::CreateProfileRequest;
+    use crate::services::{ProfileManager, SecurityService};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_households_keep_profiles_and_credentials_apart() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+        let households = HouseholdManager::new(db_manager.clone());
+        let profile_manager = ProfileManager::new(db_manager.clone(), Arc::new(SecurityService::new().unwrap()));
+
+        assert_eq!(households.active_household_id().unwrap(), DEFAULT_HOUSEHOLD_ID);
+        assert!(!households.verify_credential("2468", Utc::now()).unwrap());
+        households.set_credential("2468").unwrap();
+        let sam = |name: &str| CreateProfileRequest {
+            name: name.to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        };
+        profile_manager.create_profile(sam("Sam")).unwrap();
+
+        assert!(households.create_household("  ", "1357").is_err());
+        assert!(households.create_household("Other family", "13").is_err());
+        let other = households.create_household("Other family", "1357").unwrap();
+        assert!(other.has_credential);
+        assert!(households.create_household("other FAMILY", "1357").is_err());
+        assert_eq!(households.active_household_id().unwrap(), DEFAULT_HOUSEHOLD_ID);
+
+        households.select_household(other.id).unwrap();
+        // A household with no profiles yet gets the usual default one
+        let profiles = profile_manager.get_all_profiles().unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "Default User");
+        assert!(!households.verify_credential("2468", Utc::now()).unwrap());
+        assert!(households.verify_credential("1357", Utc::now()).unwrap());
+        // Names only have to be unique within a household
+        let other_sam = profile_manager.create_profile(sam("Sam")).unwrap();
+        assert_eq!(profile_manager.get_all_profiles().unwrap().len(), 2);
+
+        households.select_household(DEFAULT_HOUSEHOLD_ID).unwrap();
+        let profiles = profile_manager.get_all_profiles().unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_ne!(profiles[0].id, other_sam.id);
+        let counts: Vec<u32> = households.get_households().unwrap().iter().map(|household| household.profile_count).collect();
+        assert_eq!(counts, vec![1, 2]);
+        assert!(households.select_household(99).is_err());
+    }
+
+    #[test]
+    fn test_wrong_pins_lock_the_credential() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let households = HouseholdManager::new(db_service.manager());
+        households.set_credential("2468").unwrap();
+        let now = Utc::now();
+
+        for _ in 0..FREE_CREDENTIAL_ATTEMPTS {
+            assert!(!households.verify_credential("0000", now).unwrap());
+        }
+        // Locked: even the right PIN is refused until the lockout ends
+        assert!(matches!(households.verify_credential("2468", now), Err(AppError::Authentication(_))));
+        let unlocked = now + Duration::seconds(BASE_LOCKOUT_SECONDS);
+        assert!(matches!(households.verify_credential("2468", unlocked - Duration::seconds(1)), Err(AppError::Authentication(_))));
+
+        // Each further wrong PIN doubles the lockout
+        assert!(!households.verify_credential("0000", unlocked).unwrap());
+        assert!(households.verify_credential("2468", unlocked + Duration::seconds(BASE_LOCKOUT_SECONDS)).is_err());
+        let unlocked = unlocked + Duration::seconds(BASE_LOCKOUT_SECONDS * 2);
+        assert!(households.verify_credential("2468", unlocked).unwrap());
+
+        // The right PIN starts the count again
+        assert!(!households.verify_credential("0000", unlocked).unwrap());
+        assert!(households.verify_credential("2468", unlocked).unwrap());
+    }
+}
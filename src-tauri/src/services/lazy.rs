@@ -0,0 +1,87 @@
+use crate::errors::{AppError, AppResult};
+use std::sync::{Arc, Mutex};
+
+type Initializer<T> = Box<dyn Fn() -> AppResult<T> + Send + Sync>;
+
+/// A service that is constructed on first use instead of at startup.
+/// A failed initialization is not cached, so the next call retries.
+pub struct LazyService<T> {
+    name: &'static str,
+    initializer: Initializer<T>,
+    instance: Mutex<Option<Arc<T>>>,
+}
+
+impl<T> LazyService<T> {
+    /// Create a lazily initialized service
+    pub fn new<F>(name: &'static str, initializer: F) -> Self
+    where
+        F: Fn() -> AppResult<T> + Send + Sync + 'static,
+    {
+        Self {
+            name,
+            initializer: Box::new(initializer),
+            instance: Mutex::new(None),
+        }
+    }
+
+    /// Get the service, initializing it if this is the first use
+    pub fn get(&self) -> AppResult<Arc<T>> {
+        let mut instance = self.instance.lock()
+            .map_err(|_| AppError::Internal(format!("{} initialization lock poisoned", self.name)))?;
+
+        if let Some(service) = instance.as_ref() {
+            return Ok(service.clone());
+        }
+
+        println!("⏳ Initializing {} on first use...", self.name);
+        let service = Arc::new((self.initializer)()?);
+        *instance = Some(service.clone());
+        Ok(service)
+    }
+
+    /// Whether the service has been initialized yet
+    pub fn is_initialized(&self) -> bool {
+        self.instance.lock()
+            .map(|instance| instance.is_some())
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_initializes_once_on_first_use() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let lazy = LazyService::new("test service", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(42u32)
+        });
+
+        assert!(!lazy.is_initialized());
+        assert_eq!(*lazy.get().unwrap(), 42);
+        assert_eq!(*lazy.get().unwrap(), 42);
+        assert!(lazy.is_initialized());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_failed_initialization_is_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let lazy = LazyService::new("flaky service", move || {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(AppError::Internal("not ready".to_string()))
+            } else {
+                Ok("ready")
+            }
+        });
+
+        assert!(lazy.get().is_err());
+        assert!(!lazy.is_initialized());
+        assert_eq!(*lazy.get().unwrap(), "ready");
+    }
+}
@@ -0,0 +1,410 @@
+//! Questions written as plain Markdown, for parents who'd rather use a text editor than
+//! the content browser. A file looks like this:
+//!
+//! ```text
+//! ---
+//! subject: mathematics
+//! key_stage: KS1
+//! difficulty: 2
+//! tags: addition, number bonds
+//! ---
+//!
+//! Q: What is 2 + 3?
+//! - [ ] 4
+//! - [x] 5
+//! - [ ] 6
+//!
+//! Q: What is the capital of France?
+//! A: Paris
+//! difficulty: 3
+//! tags: capitals
+//! ```
+//!
+//! The front matter sets defaults for every question in the file. Each `Q:` starts a
+//! question. Ticked `- [x]` options make a multiple choice question; `A:` lines make a
+//! short answer question, with any further `A:` lines accepted too. `difficulty:` and
+//! `tags:` under a question override or add to the defaults. Headings, `<!-- -->`
+//! comments and blank lines are ignored.
+
+use crate::errors::{AppError, AppResult};
+use crate::models::{Answer, KeyStage, Question, QuestionContent, QuestionId, QuestionSource, QuestionType, ShortAnswerConfig, SHORT_ANSWER_KEY};
+use crate::services::ContentManager;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+
+/// A question read from a Markdown file, before it's matched to a subject
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownQuestion {
+    /// Line the question's `Q:` is on
+    pub line: usize,
+    pub text: String,
+    /// Options and whether each is ticked; empty for short answer questions
+    pub options: Vec<(String, bool)>,
+    /// `A:` lines, the first being the expected answer
+    pub answers: Vec<String>,
+    pub difficulty: u8,
+    pub tags: Vec<String>,
+}
+
+/// A parsed Markdown question file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkdownQuestionFile {
+    pub subject: String,
+    pub key_stage: KeyStage,
+    pub questions: Vec<MarkdownQuestion>,
+}
+
+/// A problem with one part of a file; the rest is still imported
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MarkdownImportError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// What happened when a Markdown file was imported
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarkdownImportReport {
+    /// Ids of the questions added
+    pub imported: Vec<QuestionId>,
+    /// Questions already in the subject and key stage with the same text
+    pub duplicates: usize,
+    pub errors: Vec<MarkdownImportError>,
+}
+
+/// Imports Markdown question files into the question bank
+pub struct MarkdownImporter {
+    content_manager: Arc<ContentManager>,
+}
+
+impl MarkdownImporter {
+    /// Create a new Markdown importer
+    pub fn new(content_manager: Arc<ContentManager>) -> Self {
+        Self { content_manager }
+    }
+
+    /// Import a Markdown question file from disk
+    pub fn import_file(&self, path: &Path) -> AppResult<MarkdownImportReport> {
+        let markdown = fs::read_to_string(path)
+            .map_err(|e| AppError::ContentManagement(format!("Failed to read {}: {}", path.display(), e)))?;
+        let (file, errors) = parse_markdown_questions(&markdown)?;
+        self.import_parsed(&file, errors, QuestionSource::Import {
+            file_name: path.file_name().map(|name| name.to_string_lossy().to_string()),
+        })
+    }
+
+    /// Import questions from Markdown text. Questions with mistakes are reported and
+    /// skipped; the front matter has to be right for anything to be imported.
+    pub fn import_str(&self, markdown: &str) -> AppResult<MarkdownImportReport> {
+        let (file, errors) = parse_markdown_questions(markdown)?;
+        self.import_parsed(&file, errors, QuestionSource::Import { file_name: None })
+    }
+
+    /// Store questions already parsed (from Markdown or another format), adding any
+    /// problems to the parse errors given and recording `source` against each question
+    pub fn import_parsed(
+        &self,
+        file: &MarkdownQuestionFile,
+        mut errors: Vec<MarkdownImportError>,
+        source: QuestionSource,
+    ) -> AppResult<MarkdownImportReport> {
+        let (subject_id, subject_name) = self.content_manager.get_subjects()?
+            .into_iter()
+            .find(|subject| subject.name.eq_ignore_ascii_case(&file.subject)
+                || subject.display_name.eq_ignore_ascii_case(&file.subject))
+            .and_then(|subject| Some((subject.id?, subject.name)))
+            .ok_or_else(|| AppError::NotFound(format!("Subject '{}' not found", file.subject)))?;
+
+        let existing: Vec<String> = self.content_manager
+            .get_questions_by_subject(&subject_name, Some(file.key_stage), None, None)?
+            .into_iter()
+            .map(|question| question.content.text)
+            .collect();
+
+        let mut report = MarkdownImportReport::default();
+        for parsed in &file.questions {
+            if existing.contains(&parsed.text) {
+                report.duplicates += 1;
+                continue;
+            }
+
+            let result = build_question(parsed, subject_id, file.key_stage)
+                .and_then(|question| self.content_manager.add_question(question.with_source(source.clone())));
+            match result {
+                Ok(question_id) => report.imported.push(question_id),
+                Err(e) => errors.push(MarkdownImportError { line: parsed.line, message: e.to_string() }),
+            }
+        }
+
+        errors.sort_by_key(|error| error.line);
+        report.errors = errors;
+        Ok(report)
+    }
+}
+
+/// Parse a Markdown question file. Mistakes in individual questions come back alongside the
+/// questions that parsed; a missing or broken front matter is an error.
+pub fn parse_markdown_questions(markdown: &str) -> AppResult<(MarkdownQuestionFile, Vec<MarkdownImportError>)> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let invalid = |message: &str| AppError::InvalidInput(format!("Invalid question file: {}", message));
+
+    if lines.first().map(|line| line.trim()) != Some("---") {
+        return Err(invalid("it must start with front matter between '---' lines"));
+    }
+    let front_matter_end = lines.iter()
+        .skip(1)
+        .position(|line| line.trim() == "---")
+        .map(|index| index + 1)
+        .ok_or_else(|| invalid("the front matter has no closing '---'"))?;
+
+    let mut metadata = HashMap::new();
+    for line in &lines[1..front_matter_end] {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (key, value) = split_field(line).ok_or_else(|| invalid(&format!("'{}' is not a 'key: value' line", line.trim())))?;
+        metadata.insert(key, value);
+    }
+
+    let subject = metadata.get("subject")
+        .filter(|subject| !subject.is_empty())
+        .ok_or_else(|| invalid("the front matter needs a subject"))?
+        .to_string();
+    let key_stage = metadata.get("key_stage")
+        .ok_or_else(|| invalid("the front matter needs a key_stage"))
+        .and_then(|key_stage| KeyStage::from_str(key_stage).map_err(|e| invalid(&e)))?;
+    let default_difficulty = match metadata.get("difficulty") {
+        Some(difficulty) => parse_difficulty(difficulty).map_err(|e| invalid(&e))?,
+        None => 1,
+    };
+    let default_tags = metadata.get("tags").map(|tags| parse_tags(tags)).unwrap_or_default();
+
+    let mut questions: Vec<MarkdownQuestion> = Vec::new();
+    let mut errors = Vec::new();
+    let mut in_comment = false;
+
+    for (index, raw_line) in lines.iter().enumerate().skip(front_matter_end + 1) {
+        let line_number = index + 1;
+        let line = raw_line.trim();
+        let error = |message: String| MarkdownImportError { line: line_number, message };
+
+        if in_comment || line.starts_with("<!--") {
+            in_comment = !line.contains("-->");
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(text) = strip_prefix_ignore_case(line, "Q:") {
+            questions.push(MarkdownQuestion {
+                line: line_number,
+                text: text.trim().to_string(),
+                options: Vec::new(),
+                answers: Vec::new(),
+                difficulty: default_difficulty,
+                tags: default_tags.clone(),
+            });
+            continue;
+        }
+
+        let question = match questions.last_mut() {
+            Some(question) => question,
+            None => {
+                errors.push(error("Text before the first 'Q:' line".to_string()));
+                continue;
+            }
+        };
+
+        if let Some(option) = line.strip_prefix("- [x]").or_else(|| line.strip_prefix("- [X]")) {
+            question.options.push((option.trim().to_string(), true));
+        } else if let Some(option) = line.strip_prefix("- [ ]") {
+            question.options.push((option.trim().to_string(), false));
+        } else if let Some(answer) = strip_prefix_ignore_case(line, "A:") {
+            question.answers.push(answer.trim().to_string());
+        } else if let Some(difficulty) = strip_prefix_ignore_case(line, "difficulty:") {
+            match parse_difficulty(difficulty.trim()) {
+                Ok(difficulty) => question.difficulty = difficulty,
+                Err(message) => errors.push(error(message)),
+            }
+        } else if let Some(tags) = strip_prefix_ignore_case(line, "tags:") {
+            for tag in parse_tags(tags) {
+                if !question.tags.contains(&tag) {
+                    question.tags.push(tag);
+                }
+            }
+        } else if question.options.is_empty() && question.answers.is_empty() {
+            // A question running over several lines
+            question.text.push(' ');
+            question.text.push_str(line);
+        } else {
+            errors.push(error(format!("Didn't understand '{}'", line)));
+        }
+    }
+
+    Ok((MarkdownQuestionFile { subject, key_stage, questions }, errors))
+}
+
+/// Turn a parsed question into one ready to store
+fn build_question(parsed: &MarkdownQuestion, subject_id: u32, key_stage: KeyStage) -> AppResult<Question> {
+    let invalid = |message: &str| AppError::InvalidQuestion(message.to_string());
+
+    let (question_type, content_options, additional_data, correct_answer) = match (parsed.options.is_empty(), parsed.answers.is_empty()) {
+        (false, true) => {
+            let ticked: Vec<&String> = parsed.options.iter()
+                .filter(|(_, correct)| *correct)
+                .map(|(option, _)| option)
+                .collect();
+            if ticked.len() != 1 {
+                return Err(invalid("Tick exactly one option with '- [x]'"));
+            }
+            let options = parsed.options.iter().map(|(option, _)| option.clone()).collect();
+            (QuestionType::MultipleChoice, Some(options), None, Answer::Text(ticked[0].clone()))
+        }
+        (true, false) => {
+            let config = ShortAnswerConfig {
+                accepted_answers: parsed.answers[1..].to_vec(),
+                ..ShortAnswerConfig::default()
+            };
+            let additional_data = HashMap::from([(SHORT_ANSWER_KEY.to_string(), serde_json::to_value(config)?)]);
+            (QuestionType::ShortAnswer, None, Some(additional_data), Answer::Text(parsed.answers[0].clone()))
+        }
+        (false, false) => return Err(invalid("A question has either options or 'A:' answers, not both")),
+        (true, true) => return Err(invalid("The question has no options or 'A:' answer")),
+    };
+
+    Ok(Question::new(
+        subject_id,
+        key_stage,
+        question_type,
+        QuestionContent {
+            text: parsed.text.clone(),
+            options: content_options,
+            story: None,
+            image_url: None,
+            hotspots: None,
+            blanks: None,
+            additional_data,
+        },
+        correct_answer,
+    )
+    .with_difficulty(parsed.difficulty)
+    .with_tags(parsed.tags.clone()))
+}
+
+fn split_field(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(':')?;
+    Some((key.trim().to_lowercase(), value.trim().to_string()))
+}
+
+fn strip_prefix_ignore_case<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    if line.len() >= prefix.len() && line.is_char_boundary(prefix.len()) && line[..prefix.len()].eq_ignore_ascii_case(prefix) {
+        Some(&line[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+fn parse_difficulty(value: &str) -> Result<u8, String> {
+    match value.parse::<u8>() {
+        Ok(difficulty @ 1..=5) => Ok(difficulty),
+        _ => Err(format!("Difficulty must be a number from 1 to 5, not '{}'", value)),
+    }
+}
+
+fn parse_tags(value: &str) -> Vec<String> {
+    value.split(',')
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    const SAMPLE: &str = "---
+subject: Mathematics
+key_stage: KS1
+difficulty: 2
+tags: addition
+---
+
+# Adding up
+
+Q: What is 2 + 3?
+- [ ] 4
+- [x] 5
+- [ ] 6
+
+Q: Write the number that comes
+after nine in words.
+A: ten
+A: 10
+difficulty: 1
+tags: counting
+
+Q: Which is biggest?
+- [x] 9
+- [x] 8
+
+Q: What is 1 + 1?
+A: 2
+difficulty: 7
+";
+
+    #[test]
+    fn test_parse_markdown_questions() {
+        let (file, errors) = parse_markdown_questions(SAMPLE).unwrap();
+        assert_eq!(file.subject, "Mathematics");
+        assert_eq!(file.key_stage, KeyStage::KS1);
+        assert_eq!(file.questions.len(), 4);
+
+        let multiline = &file.questions[1];
+        assert_eq!(multiline.text, "Write the number that comes after nine in words.");
+        assert_eq!(multiline.answers, vec!["ten", "10"]);
+        assert_eq!(multiline.difficulty, 1);
+        assert_eq!(multiline.tags, vec!["addition", "counting"]);
+
+        assert_eq!(errors, vec![MarkdownImportError {
+            line: 28,
+            message: "Difficulty must be a number from 1 to 5, not '7'".to_string(),
+        }]);
+
+        assert!(parse_markdown_questions("Q: No front matter?\nA: no").is_err());
+        assert!(parse_markdown_questions("---\nsubject: english\n---\nQ: Which key stage?\nA: none").is_err());
+    }
+
+    #[test]
+    fn test_import_reports_bad_questions_and_skips_duplicates() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let importer = MarkdownImporter::new(content_manager.clone());
+
+        let report = importer.import_str(SAMPLE).unwrap();
+        // The difficulty error is reported, but that question still imports at the default
+        assert_eq!(report.imported.len(), 3);
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(report.errors[0].line, 22);
+
+        let short_answer = content_manager.get_question_by_id(report.imported[1]).unwrap();
+        assert_eq!(short_answer.question_type, QuestionType::ShortAnswer);
+        assert_eq!(short_answer.content.short_answer_config().unwrap().accepted_answers, vec!["10"]);
+
+        let again = importer.import_str(SAMPLE).unwrap();
+        assert!(again.imported.is_empty());
+        assert_eq!(again.duplicates, 3);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{ProfileId, Question, QuestionId};
+use crate::services::ContentManager;
+use std::sync::Arc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Right answers in a row, after the last wrong one, that take a question out of the bank
+pub const CORRECT_ANSWERS_TO_CLEAR: u32 = 2;
+
+/// Most questions in one mistakes quiz
+pub const MISTAKES_QUIZ_QUESTIONS: usize = 15;
+
+/// A question the profile got wrong and hasn't yet put right
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MistakeItem {
+    pub question: Question,
+    pub times_wrong: u32,
+    /// Right answers since it was last got wrong
+    pub correct_since: u32,
+    pub last_wrong_at: DateTime<Utc>,
+}
+
+/// Every question each profile has answered wrongly, kept for retrying until it's been
+/// answered right `CORRECT_ANSWERS_TO_CLEAR` times in a row
+pub struct MistakesBank {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+}
+
+impl MistakesBank {
+    pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>) -> Self {
+        Self {
+            db_manager,
+            content_manager,
+        }
+    }
+
+    /// Note an answer: a wrong one goes in the bank, and right ones count towards clearing it
+    pub fn record_answer(&self, profile_id: ProfileId, question_id: QuestionId, is_correct: bool) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            if is_correct {
+                conn.execute(
+                    "UPDATE mistakes SET correct_since = correct_since + 1 WHERE profile_id = ?1 AND question_id = ?2",
+                    params![profile_id, question_id],
+                )?;
+                conn.execute(
+                    "DELETE FROM mistakes WHERE profile_id = ?1 AND question_id = ?2 AND correct_since >= ?3",
+                    params![profile_id, question_id, CORRECT_ANSWERS_TO_CLEAR],
+                )?;
+            } else {
+                conn.execute(
+                    "INSERT INTO mistakes (profile_id, question_id, times_wrong, correct_since, first_wrong_at, last_wrong_at)
+                     VALUES (?1, ?2, 1, 0, ?3, ?3)
+                     ON CONFLICT (profile_id, question_id) DO UPDATE SET
+                        times_wrong = times_wrong + 1,
+                        correct_since = 0,
+                        last_wrong_at = ?3",
+                    params![profile_id, question_id, Utc::now().to_rfc3339()],
+                )?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// The profile's mistakes, optionally in one subject, most often wrong first. Questions
+    /// retired since are left out.
+    pub fn get_mistakes(&self, profile_id: ProfileId, subject: Option<&str>) -> AppResult<Vec<MistakeItem>> {
+        let rows = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT m.question_id, m.times_wrong, m.correct_since, m.last_wrong_at FROM mistakes m
+                 JOIN questions q ON q.id = m.question_id
+                 JOIN subjects s ON s.id = q.subject_id
+                 WHERE m.profile_id = ?1 AND (?2 IS NULL OR s.name = ?2)
+                   AND m.question_id NOT IN (SELECT question_id FROM retired_questions)
+                 ORDER BY m.times_wrong DESC, m.last_wrong_at DESC, m.question_id"
+            )?;
+            let rows = stmt.query_map(params![profile_id, subject], |row| {
+                Ok((row.get::<_, QuestionId>(0)?, row.get::<_, u32>(1)?, row.get::<_, u32>(2)?, row.get::<_, String>(3)?))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let mut items = Vec::with_capacity(rows.len());
+        for (question_id, times_wrong, correct_since, last_wrong_at) in rows {
+            let last_wrong_at = DateTime::parse_from_rfc3339(&last_wrong_at)
+                .map(|last_wrong_at| last_wrong_at.with_timezone(&Utc))
+                .map_err(|e| AppError::Internal(format!("Bad mistake timestamp: {}", e)))?;
+            match self.content_manager.get_question_by_id(question_id) {
+                Ok(question) => items.push(MistakeItem { question, times_wrong, correct_since, last_wrong_at }),
+                Err(e) => log::warn!("Skipping mistake on question {}: {}", question_id, e),
+            }
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_mistakes_stay_until_answered_right_twice() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let (profile_id, maths_id, tables_id) = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            let profile_id = ProfileId(conn.last_insert_rowid() as u32);
+
+            let mut ids = Vec::new();
+            for subject in ["mathematics", "times_tables"] {
+                conn.execute(
+                    "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
+                     VALUES ((SELECT id FROM subjects WHERE name = ?1), 'KS1', 'fill_blank', '{\"text\": \"2 + 2?\"}', '\"4\"', 1, '[]', ?2)",
+                    params![subject, Utc::now().to_rfc3339()],
+                )?;
+                ids.push(QuestionId(conn.last_insert_rowid() as u32));
+            }
+            Ok((profile_id, ids[0], ids[1]))
+        }).unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let content_manager = Arc::new(ContentManager::new(db_manager.clone(), security_service, temp_dir.path().join("content")));
+        let bank = MistakesBank::new(db_manager, content_manager);
+
+        bank.record_answer(profile_id, maths_id, true).unwrap();
+        assert!(bank.get_mistakes(profile_id, None).unwrap().is_empty());
+
+        bank.record_answer(profile_id, maths_id, false).unwrap();
+        bank.record_answer(profile_id, tables_id, false).unwrap();
+        assert_eq!(bank.get_mistakes(profile_id, None).unwrap().len(), 2);
+        let maths = bank.get_mistakes(profile_id, Some("mathematics")).unwrap();
+        assert_eq!(maths.iter().map(|item| item.question.id).collect::<Vec<_>>(), vec![Some(maths_id)]);
+
+        // A slip after a right answer starts the count again
+        bank.record_answer(profile_id, maths_id, true).unwrap();
+        bank.record_answer(profile_id, maths_id, false).unwrap();
+        let maths = &bank.get_mistakes(profile_id, Some("mathematics")).unwrap()[0];
+        assert_eq!((maths.times_wrong, maths.correct_since), (2, 0));
+
+        bank.record_answer(profile_id, maths_id, true).unwrap();
+        assert_eq!(bank.get_mistakes(profile_id, Some("mathematics")).unwrap()[0].correct_since, 1);
+        bank.record_answer(profile_id, maths_id, true).unwrap();
+        assert!(bank.get_mistakes(profile_id, Some("mathematics")).unwrap().is_empty());
+        assert_eq!(bank.get_mistakes(profile_id, None).unwrap().len(), 1);
+    }
+}
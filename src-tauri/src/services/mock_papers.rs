@@ -0,0 +1,498 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Answer, KeyStage, Question, QuestionType};
+use crate::database::DatabaseManager;
+use crate::services::ContentManager;
+use crate::services::pdf::{self, Font, A4_HEIGHT, A4_WIDTH};
+use crate::services::quiz_engine::QuestionRandomizer;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+const MARGIN: f32 = 56.0;
+const BODY_SIZE: f32 = 12.0;
+const LINE_HEIGHT: f32 = 16.0;
+/// Room left of a question's text for its number
+const NUMBER_WIDTH: f32 = 28.0;
+/// Room right of a question's text for its marks
+const MARKS_WIDTH: f32 = 48.0;
+const TEXT_COLOUR: (f32, f32, f32) = (0.1, 0.1, 0.1);
+const MUTED_COLOUR: (f32, f32, f32) = (0.4, 0.4, 0.4);
+
+/// Question types that work both on screen and on paper
+const PAPER_QUESTION_TYPES: [QuestionType; 3] = [
+    QuestionType::MultipleChoice,
+    QuestionType::FillBlank,
+    QuestionType::ShortAnswer,
+];
+
+/// Topics making up a paper: the tags a question may have to count for the topic, the
+/// difficulty range, and how many questions to set
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperSection {
+    pub topic: &'static str,
+    pub tags: &'static [&'static str],
+    pub difficulty: (u8, u8),
+    pub questions: usize,
+}
+
+/// The shape of a SATs-style paper
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperBlueprint {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub subject: &'static str,
+    pub key_stage: KeyStage,
+    pub time_limit_minutes: u32,
+    pub sections: &'static [PaperSection],
+}
+
+impl PaperBlueprint {
+    pub fn question_count(&self) -> usize {
+        self.sections.iter().map(|section| section.questions).sum()
+    }
+}
+
+/// Papers modelled on the KS1 and KS2 maths tests, with their time limits
+pub const PAPER_BLUEPRINTS: &[PaperBlueprint] = &[
+    PaperBlueprint {
+        id: "ks1_maths_arithmetic",
+        title: "KS1 Mathematics Paper 1: arithmetic",
+        subject: "mathematics",
+        key_stage: KeyStage::KS1,
+        time_limit_minutes: 20,
+        sections: &[
+            PaperSection { topic: "Addition", tags: &["addition"], difficulty: (1, 2), questions: 8 },
+            PaperSection { topic: "Subtraction", tags: &["subtraction"], difficulty: (1, 2), questions: 7 },
+            PaperSection { topic: "Multiplication and division", tags: &["multiplication", "division", "doubling", "halving"], difficulty: (1, 3), questions: 6 },
+            PaperSection { topic: "Fractions", tags: &["fractions"], difficulty: (1, 3), questions: 4 },
+        ],
+    },
+    PaperBlueprint {
+        id: "ks1_maths_reasoning",
+        title: "KS1 Mathematics Paper 2: reasoning",
+        subject: "mathematics",
+        key_stage: KeyStage::KS1,
+        time_limit_minutes: 35,
+        sections: &[
+            PaperSection { topic: "Number and place value", tags: &["counting", "numbers", "comparison", "number_sequence"], difficulty: (1, 3), questions: 10 },
+            PaperSection { topic: "Patterns", tags: &["patterns", "sequences"], difficulty: (1, 3), questions: 5 },
+            PaperSection { topic: "Shape", tags: &["shapes", "geometry"], difficulty: (1, 3), questions: 8 },
+            PaperSection { topic: "Money, time and measures", tags: &["money", "time", "measurement"], difficulty: (1, 3), questions: 8 },
+            PaperSection { topic: "Problem solving", tags: &["word_problems"], difficulty: (1, 3), questions: 4 },
+        ],
+    },
+    PaperBlueprint {
+        id: "ks2_maths_arithmetic",
+        title: "KS2 Mathematics Paper 1: arithmetic",
+        subject: "mathematics",
+        key_stage: KeyStage::KS2,
+        time_limit_minutes: 30,
+        sections: &[
+            PaperSection { topic: "Addition and subtraction", tags: &["addition", "subtraction"], difficulty: (1, 3), questions: 8 },
+            PaperSection { topic: "Multiplication", tags: &["multiplication", "times_tables"], difficulty: (2, 4), questions: 8 },
+            PaperSection { topic: "Division", tags: &["division"], difficulty: (2, 4), questions: 8 },
+            PaperSection { topic: "Fractions, decimals and percentages", tags: &["fractions", "decimals", "percentages"], difficulty: (2, 5), questions: 12 },
+        ],
+    },
+    PaperBlueprint {
+        id: "ks2_maths_reasoning",
+        title: "KS2 Mathematics Paper 2: reasoning",
+        subject: "mathematics",
+        key_stage: KeyStage::KS2,
+        time_limit_minutes: 40,
+        sections: &[
+            PaperSection { topic: "Number and place value", tags: &["numbers", "comparison", "number_sequence", "patterns", "sequences"], difficulty: (2, 4), questions: 6 },
+            PaperSection { topic: "Problem solving", tags: &["word_problems", "money"], difficulty: (2, 5), questions: 6 },
+            PaperSection { topic: "Fractions, decimals and percentages", tags: &["fractions", "decimals", "percentages"], difficulty: (2, 5), questions: 4 },
+            PaperSection { topic: "Geometry", tags: &["shapes", "geometry"], difficulty: (2, 4), questions: 4 },
+            PaperSection { topic: "Measurement", tags: &["measurement", "time"], difficulty: (2, 4), questions: 4 },
+        ],
+    },
+];
+
+/// A question on a mock paper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperQuestion {
+    /// Question number as printed, from 1
+    pub number: usize,
+    pub topic: String,
+    pub question: Question,
+}
+
+/// A generated paper, kept so the same questions can be sat on screen and printed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MockPaper {
+    pub id: u32,
+    pub blueprint_id: String,
+    pub title: String,
+    pub key_stage: KeyStage,
+    pub time_limit_seconds: u32,
+    pub questions: Vec<PaperQuestion>,
+    /// Topics the bank didn't have enough questions for, and what was done instead
+    pub shortfalls: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Assembles SATs-style papers from the question bank and prints them with a mark scheme
+pub struct MockPaperGenerator {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+    output_dir: PathBuf,
+}
+
+impl MockPaperGenerator {
+    /// Create a new generator saving printable papers under `output_dir`
+    pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>, output_dir: PathBuf) -> Self {
+        Self {
+            db_manager,
+            content_manager,
+            output_dir,
+        }
+    }
+
+    pub fn get_blueprints(&self) -> &'static [PaperBlueprint] {
+        PAPER_BLUEPRINTS
+    }
+
+    /// Pick questions for each section of a blueprint, easiest first, and save the paper.
+    /// Sections the bank can't fill are topped up with other questions of the right
+    /// difficulty, and the shortfall noted on the paper.
+    pub fn generate(&self, blueprint_id: &str) -> AppResult<MockPaper> {
+        let blueprint = PAPER_BLUEPRINTS.iter()
+            .find(|blueprint| blueprint.id == blueprint_id)
+            .ok_or_else(|| AppError::NotFound(format!("Paper blueprint '{}' not found", blueprint_id)))?;
+
+        let mut pool = self.content_manager.get_questions_by_subject(blueprint.subject, Some(blueprint.key_stage), None, None)?;
+        pool.retain(|question| {
+            question.id.is_some()
+                && question.content.image_url.is_none()
+                && PAPER_QUESTION_TYPES.contains(&question.question_type)
+        });
+        let randomizer = QuestionRandomizer::new();
+        randomizer.shuffle_questions(&mut pool);
+
+        let mut used = HashSet::new();
+        let mut picked: Vec<(&'static str, Question)> = Vec::new();
+        let mut shortfalls = Vec::new();
+        for section in blueprint.sections {
+            let in_range = |question: &Question| {
+                (section.difficulty.0..=section.difficulty.1).contains(&question.difficulty_level)
+            };
+            let on_topic: Vec<&Question> = pool.iter()
+                .filter(|question| !used.contains(&question.id) && in_range(question))
+                .filter(|question| question.tags.iter().any(|tag| section.tags.contains(&tag.as_str())))
+                .take(section.questions)
+                .collect();
+            let found = on_topic.len();
+            let top_up: Vec<&Question> = pool.iter()
+                .filter(|question| !used.contains(&question.id) && in_range(question))
+                .filter(|question| !on_topic.iter().any(|chosen| chosen.id == question.id))
+                .take(section.questions - found)
+                .collect();
+
+            if found < section.questions {
+                shortfalls.push(format!(
+                    "{}: only {} of {} questions in the bank, {} other question(s) used instead",
+                    section.topic, found, section.questions, top_up.len()
+                ));
+            }
+            for question in on_topic.into_iter().chain(top_up) {
+                used.insert(question.id);
+                picked.push((section.topic, question.clone()));
+            }
+        }
+
+        if picked.is_empty() {
+            return Err(AppError::ContentManagement(format!(
+                "There are no {} {} questions that can go on a paper", blueprint.key_stage.as_str(), blueprint.subject
+            )));
+        }
+        // Like the real papers, questions get harder as the paper goes on
+        picked.sort_by_key(|(_, question)| question.difficulty_level);
+
+        let created_at = Utc::now();
+        let paper_id = self.db_manager.transaction(|tx| {
+            tx.execute(
+                "INSERT INTO mock_papers (blueprint_id, shortfalls, created_at) VALUES (?1, ?2, ?3)",
+                params![
+                    blueprint.id,
+                    serde_json::to_string(&shortfalls).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?,
+                    created_at.to_rfc3339()
+                ],
+            )?;
+            let paper_id = tx.last_insert_rowid() as u32;
+            for (position, (topic, question)) in picked.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO mock_paper_questions (paper_id, position, question_id, topic) VALUES (?1, ?2, ?3, ?4)",
+                    params![paper_id, position as u32, question.id, topic],
+                )?;
+            }
+            Ok(paper_id)
+        })?;
+
+        Ok(MockPaper {
+            id: paper_id,
+            blueprint_id: blueprint.id.to_string(),
+            title: blueprint.title.to_string(),
+            key_stage: blueprint.key_stage,
+            time_limit_seconds: blueprint.time_limit_minutes * 60,
+            questions: picked.into_iter()
+                .enumerate()
+                .map(|(index, (topic, question))| PaperQuestion {
+                    number: index + 1,
+                    topic: topic.to_string(),
+                    question,
+                })
+                .collect(),
+            shortfalls,
+            created_at,
+        })
+    }
+
+    /// A paper generated earlier. Questions deleted since are left out.
+    pub fn get_paper(&self, paper_id: u32) -> AppResult<MockPaper> {
+        let (blueprint_id, shortfalls, created_at, rows) = self.db_manager.execute(|conn| {
+            let paper = conn.query_row(
+                "SELECT blueprint_id, shortfalls, created_at FROM mock_papers WHERE id = ?1",
+                params![paper_id],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)),
+            ).optional()?;
+            let (blueprint_id, shortfalls, created_at) = match paper {
+                Some(paper) => paper,
+                None => return Ok(None),
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT question_id, topic FROM mock_paper_questions WHERE paper_id = ?1 ORDER BY position"
+            )?;
+            let rows = stmt.query_map(params![paper_id], |row| Ok((row.get(0)?, row.get::<_, String>(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Some((blueprint_id, shortfalls, created_at, rows)))
+        })?.ok_or_else(|| AppError::NotFound(format!("Mock paper with id {} not found", paper_id)))?;
+
+        let blueprint = PAPER_BLUEPRINTS.iter()
+            .find(|blueprint| blueprint.id == blueprint_id)
+            .ok_or_else(|| AppError::NotFound(format!("Paper blueprint '{}' not found", blueprint_id)))?;
+        let questions = rows.into_iter()
+            .map(|(question_id, topic)| Ok((self.content_manager.get_question_by_id(question_id)?, topic)))
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(MockPaper {
+            id: paper_id,
+            blueprint_id,
+            title: blueprint.title.to_string(),
+            key_stage: blueprint.key_stage,
+            time_limit_seconds: blueprint.time_limit_minutes * 60,
+            questions: questions.into_iter()
+                .enumerate()
+                .map(|(index, (question, topic))| PaperQuestion { number: index + 1, topic, question })
+                .collect(),
+            shortfalls: serde_json::from_str(&shortfalls)?,
+            created_at: DateTime::parse_from_rfc3339(&created_at)
+                .map(|created_at| created_at.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+
+    /// Save a paper as a printable PDF, followed by its mark scheme, and return its path
+    pub fn write_pdf(&self, paper: &MockPaper) -> AppResult<PathBuf> {
+        fs::create_dir_all(&self.output_dir)?;
+        let path = self.output_dir.join(format!("mock-paper-{}-{}.pdf", paper.id, paper.blueprint_id));
+        fs::write(&path, render_paper_pdf(paper))?;
+        Ok(path)
+    }
+}
+
+/// The answer as it should appear in the mark scheme
+fn answer_text(answer: &Answer) -> String {
+    match answer {
+        Answer::Text(text) => text.clone(),
+        Answer::Multiple(answers) => answers.join(", "),
+        _ => "Marked on screen".to_string(),
+    }
+}
+
+/// Lays text out top to bottom, starting a new page when one fills up
+struct PageWriter {
+    pages: Vec<String>,
+    y: f32,
+}
+
+impl PageWriter {
+    fn new() -> Self {
+        Self {
+            pages: vec![String::new()],
+            y: A4_HEIGHT - MARGIN,
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(String::new());
+        self.y = A4_HEIGHT - MARGIN;
+    }
+
+    /// Start a new page unless `height` more points fit on this one
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn text(&mut self, text: &str, font: Font, size: f32, x: f32, colour: (f32, f32, f32)) {
+        let ops = pdf::text_at(text, font, size, x, self.y, colour);
+        self.pages.last_mut().expect("there is always a page").push_str(&ops);
+    }
+
+    fn line(&mut self, x1: f32, x2: f32) {
+        let ops = format!("0.6 0.6 0.6 RG 0.75 w {:.1} {:.1} m {:.1} {:.1} l S\n", x1, self.y, x2, self.y);
+        self.pages.last_mut().expect("there is always a page").push_str(&ops);
+    }
+
+    /// The pages, numbered at the foot
+    fn finish(self) -> Vec<String> {
+        let count = self.pages.len();
+        self.pages.into_iter()
+            .enumerate()
+            .map(|(index, mut page)| {
+                let label = format!("Page {} of {}", index + 1, count);
+                let x = (A4_WIDTH - pdf::text_width(&label, Font::Regular, 9.0)) / 2.0;
+                page.push_str(&pdf::text_at(&label, Font::Regular, 9.0, x, MARGIN / 2.0, MUTED_COLOUR));
+                page
+            })
+            .collect()
+    }
+}
+
+/// Render a paper and its mark scheme as an A4 PDF
+pub fn render_paper_pdf(paper: &MockPaper) -> Vec<u8> {
+    let mut writer = PageWriter::new();
+    let text_x = MARGIN + NUMBER_WIDTH;
+    let text_width = A4_WIDTH - text_x - MARGIN - MARKS_WIDTH;
+
+    writer.text(&paper.title, Font::Bold, 20.0, MARGIN, TEXT_COLOUR);
+    writer.y -= 24.0;
+    let details = format!(
+        "Time allowed: {} minutes    Total marks: {}",
+        paper.time_limit_seconds / 60, paper.questions.len()
+    );
+    writer.text(&details, Font::Regular, BODY_SIZE, MARGIN, MUTED_COLOUR);
+    writer.y -= 32.0;
+    writer.text("Name", Font::Bold, BODY_SIZE, MARGIN, TEXT_COLOUR);
+    writer.line(MARGIN + 40.0, MARGIN + 260.0);
+    writer.text("Date", Font::Bold, BODY_SIZE, MARGIN + 290.0, TEXT_COLOUR);
+    writer.line(MARGIN + 325.0, A4_WIDTH - MARGIN);
+    writer.y -= 40.0;
+
+    for paper_question in &paper.questions {
+        let question = &paper_question.question;
+        let lines = pdf::wrap_text(&question.content.text, Font::Regular, BODY_SIZE, text_width);
+        let options: Vec<String> = match question.question_type {
+            QuestionType::MultipleChoice => question.content.options.clone().unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let height = (lines.len() + options.len()) as f32 * LINE_HEIGHT + 44.0;
+        writer.ensure_space(height);
+
+        writer.text(&paper_question.number.to_string(), Font::Bold, BODY_SIZE, MARGIN, TEXT_COLOUR);
+        let marks = "1 mark";
+        let marks_x = A4_WIDTH - MARGIN - pdf::text_width(marks, Font::Regular, 10.0);
+        writer.text(marks, Font::Regular, 10.0, marks_x, MUTED_COLOUR);
+        for line in &lines {
+            writer.text(line, Font::Regular, BODY_SIZE, text_x, TEXT_COLOUR);
+            writer.y -= LINE_HEIGHT;
+        }
+        for (index, option) in options.iter().enumerate() {
+            let letter = (b'A' + (index % 26) as u8) as char;
+            writer.text(&format!("{}   {}", letter, option), Font::Regular, BODY_SIZE, text_x + 12.0, TEXT_COLOUR);
+            writer.y -= LINE_HEIGHT;
+        }
+        writer.y -= 12.0;
+        writer.text("Answer", Font::Bold, 10.0, text_x, MUTED_COLOUR);
+        writer.line(text_x + 48.0, text_x + 220.0);
+        writer.y -= 32.0;
+    }
+
+    writer.new_page();
+    writer.text(&format!("Mark scheme: {}", paper.title), Font::Bold, 16.0, MARGIN, TEXT_COLOUR);
+    writer.y -= 28.0;
+    for paper_question in &paper.questions {
+        let lines = pdf::wrap_text(&answer_text(&paper_question.question.correct_answer), Font::Regular, BODY_SIZE, text_width);
+        writer.ensure_space(lines.len() as f32 * LINE_HEIGHT + 6.0);
+        writer.text(&paper_question.number.to_string(), Font::Bold, BODY_SIZE, MARGIN, TEXT_COLOUR);
+        writer.text(&paper_question.topic, Font::Regular, 9.0, A4_WIDTH - MARGIN - pdf::text_width(&paper_question.topic, Font::Regular, 9.0), MUTED_COLOUR);
+        for line in &lines {
+            writer.text(line, Font::Regular, BODY_SIZE, text_x, TEXT_COLOUR);
+            writer.y -= LINE_HEIGHT;
+        }
+        writer.y -= 6.0;
+    }
+
+    pdf::build_pdf(&writer.finish(), A4_WIDTH, A4_HEIGHT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::QuestionContent;
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_generate_paper_follows_blueprint_and_prints() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let generator = MockPaperGenerator::new(db_service.manager(), content_manager.clone(), temp_dir.path().join("papers"));
+        assert!(generator.generate("ks1_maths_arithmetic").is_err());
+
+        let subject_id = content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for index in 0..30u8 {
+            let tag = if index < 10 { "addition" } else { "subtraction" };
+            content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: format!("What is {} {} 1?", index, if index < 10 { "+" } else { "-" }),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            ).with_difficulty(index % 2 + 1).with_tags(vec![tag.to_string()])).unwrap();
+        }
+
+        let paper = generator.generate("ks1_maths_arithmetic").unwrap();
+        assert_eq!(paper.time_limit_seconds, 20 * 60);
+        assert_eq!(paper.questions.len(), 25);
+        assert_eq!(paper.questions.iter().filter(|question| question.topic == "Addition").count(), 8);
+        assert!(paper.questions.windows(2).all(|pair| pair[0].question.difficulty_level <= pair[1].question.difficulty_level));
+        // Nothing is tagged for the last two sections, so they are topped up
+        assert_eq!(paper.shortfalls.len(), 2);
+
+        let saved = generator.get_paper(paper.id).unwrap();
+        let ids = |paper: &MockPaper| paper.questions.iter().map(|question| question.question.id).collect::<Vec<_>>();
+        assert_eq!(ids(&saved), ids(&paper));
+        assert_eq!(saved.shortfalls, paper.shortfalls);
+
+        let pdf = String::from_utf8(fs::read(generator.write_pdf(&saved).unwrap()).unwrap()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.contains("(Mark scheme: KS1 Mathematics Paper 1: arithmetic) Tj"));
+        assert!(pdf.contains("(What is 0 + 1?) Tj") || pdf.contains("(What is 1 + 1?) Tj"));
+        assert!(generator.get_paper(999).is_err());
+    }
+}
@@ -1,18 +1,161 @@
 pub mod security;
 pub mod profile_manager;
 pub mod content_manager;
+pub mod question_cache;
 pub mod content_seeder;
 pub mod quiz_engine;
 pub mod custom_mix_manager;
 pub mod update_service;
+pub mod feature_flags;
+pub mod telemetry;
+pub mod crash_reporter;
+pub mod shutdown;
+pub mod lazy;
+pub mod window_sessions;
+pub mod notifications;
+pub mod assignment_manager;
+pub mod classroom_manager;
+pub mod calendar_export;
+pub mod email_reports;
+pub mod certificates;
+pub mod question_of_the_day;
+pub mod passage_manager;
+pub mod expression;
+pub mod answer_normalization;
+pub mod accessibility;
+pub mod voice_input;
+pub mod drawing_answers;
+pub mod scratchpad;
+pub mod wellbeing;
+pub mod approval_queue;
+pub mod boss_questions;
+pub mod phonics;
+pub mod answer_timing;
+pub mod adaptive_length;
+pub mod quiz_presets;
+pub mod result_cards;
+pub mod difficulty_curves;
+pub mod markdown_import;
+pub mod content_inbox;
+pub mod asset_pipeline;
+pub mod audio_prompts;
+pub mod spelling_lists;
+pub mod curriculum_coverage;
+pub mod session_replay;
+pub mod storage_manager;
+pub mod history_retention;
+pub mod selection_audit;
+pub mod distractors;
+pub mod question_quality;
+pub mod api_tokens;
+pub mod diagnostics;
+pub mod support_bundle;
+pub mod onboarding;
+pub mod households;
+pub mod pdf;
+pub mod mock_papers;
+pub mod reading_age;
+pub mod spaced_repetition;
+pub mod multiplication_check;
+pub mod phonics_screening;
+pub mod explore_mode;
+pub mod content_changes;
+pub mod formatting;
+pub mod curriculum_profiles;
+pub mod mistakes;
+pub mod achievements;
+pub mod streaks;
+pub mod experience;
+pub mod asset_integrity;
+pub mod cloud_backup;
+pub mod restore;
+pub mod app_log;
 
-pub use security::{SecurityService, ParentalChallenge};
-pub use profile_manager::{ProfileManager, ProfileUpdateRequest, QuizResult};
-pub use content_manager::{ContentManager, ContentPack, ContentPackQuestion, ContentStatistics};
-pub use content_seeder::ContentSeeder;
+pub use security::{SecurityService, ParentalChallenge, Permission};
+pub use profile_manager::{
+    ProfileManager, ProfileUpdateRequest, QuizResult, ResetScope, ProgressReset, ProgressSnapshot, HeadToHeadResult, MatchOutcome
+};
+pub use content_manager::{ContentManager, ContentPack, ContentPackQuestion, ContentStatistics, QuestionSourceSummary, QuestionRemoval, RetiredQuestion, RETIRED_QUESTION_RETENTION_DAYS};
+pub use question_cache::{QuestionCache, QuestionCacheStats};
+pub use content_seeder::{ContentSeeder, ContentReset, SeedingProgress};
 pub use quiz_engine::{
     QuizEngine, QuestionRandomizer, QuizTimer, QuizConfig, QuizSession, 
-    AnswerResult, Score, PerformanceLevel, QuizProgress
+    AnswerResult, BlankResult, Score, PerformanceLevel, QuizProgress, QuizIncident,
+    SessionMode, QuizMode, LateAnswer, IntegrityNote, IntegrityNoteKind, HintReveal, HintResult,
+    PlayerScore, PassAndPlaySummary, CoopPlayer, PlayerContribution, CoopSummary, HeadToHeadRound, HeadToHeadSummary,
+    BOSS_BONUS_POINTS, DEFAULT_HINT_PENALTY_PERCENT, QUESTION_REPLACED_MESSAGE, SANDBOX_PROFILE_ID,
+    MIN_PASS_AND_PLAY_PLAYERS, MAX_PASS_AND_PLAY_PLAYERS, DEFAULT_COOP_TARGET_PERCENT
 };
 pub use custom_mix_manager::CustomMixManager;
-pub use update_service::{UpdateService, UpdateInfo, UpdateConfig, ContentPackage, PackageMetadata};
\ No newline at end of file
+pub use update_service::{UpdateService, UpdateInfo, UpdateConfig, ContentPackage, PackageMetadata};
+pub use feature_flags::{FeatureFlagService, FeatureFlag, FlagSource};
+pub use telemetry::{TelemetryService, TelemetrySettings, TelemetryPayload};
+pub use crash_reporter::{CrashReporter, CrashReport};
+pub use app_log::{FileLogger, LOG_FILE_NAME};
+pub use shutdown::{ShutdownCoordinator, ShutdownReport};
+pub use lazy::LazyService;
+pub use window_sessions::{WindowSessionRegistry, WindowSession, MAIN_WINDOW_LABEL, PARENT_DASHBOARD_LABEL};
+pub use notifications::{NotificationService, NotificationKind, NotificationPreferences, NotificationTemplate, PendingNotification};
+pub use assignment_manager::AssignmentManager;
+pub use classroom_manager::ClassroomManager;
+pub use calendar_export::CalendarExporter;
+pub use email_reports::{EmailReportService, SmtpSettings, SmtpSecurity, EmailDeliveryStatus, WeeklyDigest};
+pub use certificates::CertificateGenerator;
+pub use mock_papers::{MockPaperGenerator, MockPaper, PaperQuestion, PaperBlueprint, PaperSection};
+pub use reading_age::{ReadingAgeService, ReadingAgeEstimate, READING_TAGS};
+pub use spaced_repetition::{ReviewScheduler, QuestionReview};
+pub use multiplication_check::{
+    MultiplicationCheckService, MultiplicationCheck, CheckQuestion, CheckAnswerReceipt, CheckResult, MarkedCheckAnswer,
+    CheckReadiness, ReadinessLevel
+};
+pub use phonics_screening::{
+    PhonicsScreeningService, PhonicsScreening, ScreeningWord, ScreeningResult, GraphemeAccuracy
+};
+pub use explore_mode::{ExploreService, ExploreTopic, ExploreItem, RevealedAnswer, PracticeLaterItem};
+pub use content_changes::{ContentChangeMonitor, ContentChangeAlert, QuestionVersion, ContentWriteOrigin};
+pub use formatting::{FormattingService, localize_money_question, MONEY_TAG};
+pub use curriculum_profiles::CurriculumService;
+pub use mistakes::{MistakesBank, MistakeItem, CORRECT_ANSWERS_TO_CLEAR, MISTAKES_QUIZ_QUESTIONS};
+pub use achievements::{AchievementManager, AchievementRule, AchievementCondition, Badge};
+pub use streaks::{StreakTracker, MAX_STREAK_FREEZES};
+pub use experience::{ExperienceTracker, XP_PER_CORRECT_ANSWER, LEVEL_XP_STEP};
+pub use question_of_the_day::{QuestionOfTheDayService, DailyQuestion, DailyQuestionCompletion};
+pub use passage_manager::PassageManager;
+pub use accessibility::{AccessibilityService, AccessibilitySettings};
+pub use voice_input::{VoiceInputService, SpeechToTextProvider, SpokenAudio, Transcription, VoiceThresholds, VoiceDecision, VoiceInterpretation};
+pub use drawing_answers::{DrawingAnswerService, DrawingRecognizer, DrawingSubmission};
+pub use scratchpad::{ScratchpadService, ScratchpadWork, ScratchpadEntry};
+pub use wellbeing::{WellbeingService, BreakSettings, BreakStatus};
+pub use approval_queue::{ApprovalQueue, ApprovalRequest, ApprovalStatus, PendingAction};
+pub use boss_questions::{BossQuestionTracker, BossQuestionStats, BossAttempt};
+pub use phonics::{PhonicsService, PhonicsAudio, SpeechSynthesizer, PHONICS_AUDIO_KEY};
+pub use answer_timing::{AnswerTiming, TimingFlag, SLOW_DOWN_MESSAGE};
+pub use adaptive_length::EarlyStop;
+pub use quiz_presets::{QuizPresetService, QuizPreset};
+pub use result_cards::{ResultCardGenerator, ResultCard};
+pub use difficulty_curves::{DifficultyCurveService, DifficultyCurve};
+pub use markdown_import::{MarkdownImporter, MarkdownImportReport, MarkdownImportError};
+pub use content_inbox::{ContentInbox, InboxReport, InboxFileKind, INBOX_SETTLE_TIME};
+pub use asset_integrity::{AssetIntegrityChecker, AssetIntegrityReport, AssetProblem, BrokenAsset, RepairedAsset};
+pub use asset_pipeline::{AssetPipeline, OptimizedImage, normalize_audio, MAX_IMAGE_DIMENSION, AUDIO_SAMPLE_RATE};
+pub use audio_prompts::AudioPromptService;
+pub use spelling_lists::{SpellingListService, SpellingList, SpellingListWord, SpellingWord, CreateSpellingListRequest, PracticeDay, SpellingAttemptResult, SpellingReadiness, WordReadiness};
+pub use curriculum_coverage::{CurriculumCoverageService, CurriculumObjective, CoverageReport, ObjectiveCoverage, CURRICULUM_OBJECTIVES};
+pub use session_replay::{SessionReplayLog, SessionReplay, SessionEvent, ReplayEvent};
+pub use storage_manager::{StorageManager, StorageLocations, StorageUsage, StorageSettings, CleanupReport, DatabaseBackup};
+pub use history_retention::{HistoryRetentionService, RetentionSettings, RetentionReport, PrunedRecords, CondensedSession};
+pub use selection_audit::{SelectionAudit, SelectionBucket, MAX_AUDIT_RUNS};
+pub use distractors::{ArithmeticProblem, Operation, Distractor, DistractorKind, generate_distractors, options_with_distractors};
+pub use question_quality::{QuestionQualityService, QuestionQuality, QualityIssue, PhrasingStats};
+pub use api_tokens::{ApiTokenService, ApiToken, ApiTokenScope, CreatedApiToken};
+pub use diagnostics::{DiagnosticsService, AppDiagnostics, DatabaseHealth, UpdateStatus, RecentError, ErrorSource};
+pub use cloud_backup::{
+    CloudBackupService, CloudBackupSettings, CloudBackupStatus, CloudBackup, BackupDestination, S3Bucket, MAX_CLOUD_BACKUPS_KEPT
+};
+pub use restore::{
+    RestoreService, RestoreComponent, RestoreSelection, RestorePreview, BackupSource, ChangeKind, ProfileChange,
+    QuestionChanges, SettingsChange, apply_pending_restore, RESTORE_DIRECTORY
+};
+pub use support_bundle::{SupportBundleExporter, SupportBundleSummary, EXCLUDED_DATA};
+pub use onboarding::{OnboardingService, OnboardingState, OnboardingStep};
+pub use households::{HouseholdManager, Household, DEFAULT_HOUSEHOLD_ID};
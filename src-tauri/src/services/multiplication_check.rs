@@ -0,0 +1,493 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::ProfileId;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+
+/// Questions in one check, as in the statutory Year 4 check
+pub const CHECK_QUESTIONS: usize = 25;
+/// Time to type each answer
+pub const SECONDS_PER_QUESTION: u32 = 6;
+/// Pause between questions, during which nothing can be typed
+pub const PAUSE_SECONDS: u32 = 3;
+/// Longest answer the check accepts; no product in the check has more digits
+const MAX_ANSWER_DIGITS: usize = 3;
+/// Allowance for the answer's trip from the keyboard to here
+const LATENCY_ALLOWANCE_MS: i64 = 500;
+const LOWEST_TABLE: u32 = 2;
+const HIGHEST_TABLE: u32 = 12;
+/// The tables the check asks about more often, as children find them hardest
+const HARDER_TABLES: &[u32] = &[6, 7, 8, 9, 12];
+/// Completed checks the readiness indicator looks back over
+const READINESS_CHECKS: usize = 3;
+/// Average score over recent checks for each readiness level
+const READY_AVERAGE: f32 = 23.0;
+const NEARLY_READY_AVERAGE: f32 = 18.0;
+
+/// A times tables check in progress. Questions are revealed one at a time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiplicationCheck {
+    pub id: u32,
+    pub profile_id: ProfileId,
+    pub question_count: usize,
+    pub seconds_per_question: u32,
+    pub pause_seconds: u32,
+    pub started_at: DateTime<Utc>,
+}
+
+/// One multiplication to answer, e.g. 7 × 8
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CheckQuestion {
+    /// 1-based, as shown to the child ("Question 3 of 25")
+    pub position: usize,
+    pub multiplicand: u32,
+    pub multiplier: u32,
+    pub shown_at: DateTime<Utc>,
+}
+
+/// Whether an answer was taken. Like the real check, marks aren't shown until the end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckAnswerReceipt {
+    pub position: usize,
+    /// The answer came after the time for the question ran out, so scores nothing
+    pub timed_out: bool,
+    pub check_complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkedCheckAnswer {
+    pub position: usize,
+    pub multiplicand: u32,
+    pub multiplier: u32,
+    /// What was typed; None if nothing was entered in time
+    pub answer: Option<String>,
+    pub correct: bool,
+    pub timed_out: bool,
+}
+
+/// A finished check, scored one mark per correct answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub check_id: u32,
+    pub profile_id: ProfileId,
+    pub score: u32,
+    pub total: u32,
+    pub answers: Vec<MarkedCheckAnswer>,
+    pub completed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessLevel {
+    NotStarted,
+    Practising,
+    NearlyReady,
+    Ready,
+}
+
+/// How ready a child looks for the real check, from their recent practice checks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckReadiness {
+    pub profile_id: ProfileId,
+    pub level: ReadinessLevel,
+    pub checks_completed: u32,
+    /// Average over the last few checks
+    pub recent_average: Option<f32>,
+    pub best_score: Option<u32>,
+    /// Tables missed most often in recent checks, worst first
+    pub tables_to_practise: Vec<u32>,
+}
+
+/// Practice for the Multiplication Tables Check: 25 questions from the 2 to 12 times
+/// tables, 6 seconds each to type the answer, scored out of 25 at the end
+pub struct MultiplicationCheckService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl MultiplicationCheckService {
+    /// Create a new multiplication check service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Start a check with a fresh set of questions
+    pub fn start_check(&self, profile_id: ProfileId) -> AppResult<MultiplicationCheck> {
+        let questions = generate_questions()?;
+        let started_at = Utc::now();
+
+        let id = self.db_manager.transaction(|tx| {
+            tx.execute(
+                "INSERT INTO multiplication_checks (profile_id, started_at) VALUES (?1, ?2)",
+                params![profile_id, started_at.to_rfc3339()],
+            )?;
+            let check_id = tx.last_insert_rowid() as u32;
+            for (index, (multiplicand, multiplier)) in questions.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO multiplication_check_questions (check_id, position, multiplicand, multiplier)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![check_id, index + 1, multiplicand, multiplier],
+                )?;
+            }
+            Ok(check_id)
+        })?;
+
+        Ok(MultiplicationCheck {
+            id,
+            profile_id,
+            question_count: questions.len(),
+            seconds_per_question: SECONDS_PER_QUESTION,
+            pause_seconds: PAUSE_SECONDS,
+            started_at,
+        })
+    }
+
+    /// Show the next question, starting its timer. A question whose time ran out with no
+    /// answer scores nothing and is skipped. None once the check is over.
+    pub fn next_question(&self, check_id: u32) -> AppResult<Option<CheckQuestion>> {
+        self.next_question_at(check_id, Utc::now())
+    }
+
+    fn next_question_at(&self, check_id: u32, now: DateTime<Utc>) -> AppResult<Option<CheckQuestion>> {
+        Ok(self.db_manager.transaction(|tx| {
+            loop {
+                let current = match current_question(tx, check_id)? {
+                    Some(current) => current,
+                    None => {
+                        complete_check(tx, check_id, now)?;
+                        return Ok(None);
+                    }
+                };
+
+                match current.shown_at {
+                    Some(shown_at) if now - shown_at > time_allowed() => {
+                        tx.execute(
+                            "UPDATE multiplication_check_questions SET answered_at = ?1, correct = 0
+                             WHERE check_id = ?2 AND position = ?3",
+                            params![(shown_at + time_allowed()).to_rfc3339(), check_id, current.position],
+                        )?;
+                    }
+                    Some(shown_at) => return Ok(Some(current.into_question(shown_at))),
+                    None => {
+                        tx.execute(
+                            "UPDATE multiplication_check_questions SET shown_at = ?1 WHERE check_id = ?2 AND position = ?3",
+                            params![now.to_rfc3339(), check_id, current.position],
+                        )?;
+                        return Ok(Some(current.into_question(now)));
+                    }
+                }
+            }
+        })?)
+    }
+
+    /// Take the typed answer to the question on screen. Only digits count, as on the real
+    /// check's number pad.
+    pub fn submit_answer(&self, check_id: u32, answer: &str) -> AppResult<CheckAnswerReceipt> {
+        self.submit_answer_at(check_id, answer, Utc::now())
+    }
+
+    fn submit_answer_at(&self, check_id: u32, answer: &str, now: DateTime<Utc>) -> AppResult<CheckAnswerReceipt> {
+        let answer = answer.trim();
+        if answer.len() > MAX_ANSWER_DIGITS || !answer.chars().all(|c| c.is_ascii_digit()) {
+            return Err(AppError::InvalidInput(format!(
+                "Answers are whole numbers of up to {} digits", MAX_ANSWER_DIGITS
+            )));
+        }
+
+        self.db_manager.transaction(|tx| {
+            let current = match current_question(tx, check_id)? {
+                Some(current) => current,
+                None => return Ok(Err(AppError::QuizEngine("This check is already finished".to_string()))),
+            };
+            let shown_at = match current.shown_at {
+                Some(shown_at) => shown_at,
+                None => return Ok(Err(AppError::QuizEngine("The next question hasn't been shown yet".to_string()))),
+            };
+
+            let timed_out = now - shown_at > time_allowed();
+            let correct = !timed_out && answer.parse::<u32>().ok() == Some(current.multiplicand * current.multiplier);
+            tx.execute(
+                "UPDATE multiplication_check_questions SET answer = ?1, correct = ?2, answered_at = ?3
+                 WHERE check_id = ?4 AND position = ?5",
+                params![answer, correct, now.to_rfc3339(), check_id, current.position],
+            )?;
+
+            let check_complete = current_question(tx, check_id)?.is_none();
+            if check_complete {
+                complete_check(tx, check_id, now)?;
+            }
+            Ok(Ok(CheckAnswerReceipt {
+                position: current.position,
+                timed_out,
+                check_complete,
+            }))
+        })?
+    }
+
+    /// Marks for a finished check
+    pub fn get_result(&self, check_id: u32) -> AppResult<CheckResult> {
+        let check = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT profile_id, score, completed_at FROM multiplication_checks WHERE id = ?1",
+                params![check_id],
+                |row| Ok((
+                    ProfileId(row.get(0)?),
+                    row.get::<_, Option<u32>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                )),
+            ).optional()
+        })?;
+        let (profile_id, score, completed_at) = match check {
+            Some((profile_id, Some(score), Some(completed_at))) => (profile_id, score, completed_at),
+            Some(_) => return Err(AppError::QuizEngine("This check hasn't finished yet".to_string())),
+            None => return Err(AppError::NotFound(format!("Multiplication check with id {} not found", check_id))),
+        };
+
+        let answers = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT position, multiplicand, multiplier, answer, correct, shown_at, answered_at
+                 FROM multiplication_check_questions WHERE check_id = ?1 ORDER BY position"
+            )?;
+            let answers = stmt.query_map(params![check_id], |row| {
+                let answer: Option<String> = row.get(3)?;
+                let shown_at = parse_time(row.get(5)?);
+                let answered_at = parse_time(row.get(6)?);
+                Ok(MarkedCheckAnswer {
+                    position: row.get(0)?,
+                    multiplicand: row.get(1)?,
+                    multiplier: row.get(2)?,
+                    timed_out: match (shown_at, answered_at) {
+                        (Some(shown_at), Some(answered_at)) => answer.is_none() || answered_at - shown_at > time_allowed(),
+                        _ => true,
+                    },
+                    answer,
+                    correct: row.get::<_, Option<bool>>(4)?.unwrap_or(false),
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+            Ok(answers)
+        })?;
+
+        Ok(CheckResult {
+            check_id,
+            profile_id,
+            score,
+            total: answers.len() as u32,
+            answers,
+            completed_at: parse_time(Some(completed_at))
+                .ok_or_else(|| AppError::Internal("Invalid check completion time".to_string()))?,
+        })
+    }
+
+    /// How ready the profile looks for the real check
+    pub fn get_readiness(&self, profile_id: ProfileId) -> AppResult<CheckReadiness> {
+        let scores: Vec<(u32, u32)> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, score FROM multiplication_checks
+                 WHERE profile_id = ?1 AND completed_at IS NOT NULL ORDER BY completed_at DESC, id DESC"
+            )?;
+            let scores = stmt.query_map(params![profile_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(scores)
+        })?;
+
+        let recent = &scores[..scores.len().min(READINESS_CHECKS)];
+        let recent_average = if recent.is_empty() {
+            None
+        } else {
+            Some(recent.iter().map(|&(_, score)| score as f32).sum::<f32>() / recent.len() as f32)
+        };
+        let level = match recent_average {
+            None => ReadinessLevel::NotStarted,
+            Some(average) if recent.len() >= READINESS_CHECKS && average >= READY_AVERAGE => ReadinessLevel::Ready,
+            Some(average) if average >= NEARLY_READY_AVERAGE => ReadinessLevel::NearlyReady,
+            Some(_) => ReadinessLevel::Practising,
+        };
+
+        let mut misses: BTreeMap<u32, u32> = BTreeMap::new();
+        for &(check_id, _) in recent {
+            for answer in self.get_result(check_id)?.answers.iter().filter(|answer| !answer.correct) {
+                for table in [answer.multiplicand, answer.multiplier] {
+                    *misses.entry(table).or_insert(0) += 1;
+                }
+            }
+        }
+        let mut tables_to_practise: Vec<(u32, u32)> = misses.into_iter().collect();
+        tables_to_practise.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        Ok(CheckReadiness {
+            profile_id,
+            level,
+            checks_completed: scores.len() as u32,
+            recent_average,
+            best_score: scores.iter().map(|&(_, score)| score).max(),
+            tables_to_practise: tables_to_practise.into_iter().take(3).map(|(table, _)| table).collect(),
+        })
+    }
+}
+
+/// The first question in a check not yet answered or timed out
+struct PendingQuestion {
+    position: usize,
+    multiplicand: u32,
+    multiplier: u32,
+    shown_at: Option<DateTime<Utc>>,
+}
+
+impl PendingQuestion {
+    fn into_question(self, shown_at: DateTime<Utc>) -> CheckQuestion {
+        CheckQuestion {
+            position: self.position,
+            multiplicand: self.multiplicand,
+            multiplier: self.multiplier,
+            shown_at,
+        }
+    }
+}
+
+fn current_question(conn: &Connection, check_id: u32) -> rusqlite::Result<Option<PendingQuestion>> {
+    conn.query_row(
+        "SELECT position, multiplicand, multiplier, shown_at FROM multiplication_check_questions
+         WHERE check_id = ?1 AND answered_at IS NULL ORDER BY position LIMIT 1",
+        params![check_id],
+        |row| Ok(PendingQuestion {
+            position: row.get(0)?,
+            multiplicand: row.get(1)?,
+            multiplier: row.get(2)?,
+            shown_at: parse_time(row.get(3)?),
+        }),
+    ).optional()
+}
+
+/// Score a check once every question is answered or timed out
+fn complete_check(conn: &Connection, check_id: u32, now: DateTime<Utc>) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE multiplication_checks SET completed_at = ?1,
+            score = (SELECT COUNT(*) FROM multiplication_check_questions WHERE check_id = ?2 AND correct = 1)
+         WHERE id = ?2 AND completed_at IS NULL",
+        params![now.to_rfc3339(), check_id],
+    )?;
+    Ok(())
+}
+
+fn time_allowed() -> Duration {
+    Duration::seconds(SECONDS_PER_QUESTION as i64) + Duration::milliseconds(LATENCY_ALLOWANCE_MS)
+}
+
+fn parse_time(value: Option<String>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+        .map(|value| value.with_timezone(&Utc))
+}
+
+/// Pick the check's questions: no 1 times table, no fact asked twice (7 × 8 and 8 × 7
+/// count as the same), and the harder tables more likely
+fn generate_questions() -> AppResult<Vec<(u32, u32)>> {
+    let mut candidates = Vec::new();
+    for a in LOWEST_TABLE..=HIGHEST_TABLE {
+        for b in a..=HIGHEST_TABLE {
+            let weight = 1 + HARDER_TABLES.contains(&a) as u32 + HARDER_TABLES.contains(&b) as u32;
+            candidates.push(((a, b), weight));
+        }
+    }
+
+    let mut questions = Vec::with_capacity(CHECK_QUESTIONS);
+    while questions.len() < CHECK_QUESTIONS {
+        let total: u32 = candidates.iter().map(|&(_, weight)| weight).sum();
+        let mut pick = random_below(total)?;
+        let index = candidates.iter()
+            .position(|&(_, weight)| {
+                if pick < weight {
+                    true
+                } else {
+                    pick -= weight;
+                    false
+                }
+            })
+            .unwrap_or(candidates.len() - 1);
+        let ((a, b), _) = candidates.swap_remove(index);
+        questions.push(if random_below(2)? == 0 { (a, b) } else { (b, a) });
+    }
+    Ok(questions)
+}
+
+fn random_below(bound: u32) -> AppResult<u32> {
+    let mut bytes = [0u8; 4];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| AppError::Internal(format!("Failed to generate random number: {}", e)))?;
+    Ok(u32::from_le_bytes(bytes) % bound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_is_timed_per_question_and_scored_at_the_end() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+        let service = MultiplicationCheckService::new(db_manager.clone());
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )
+        }).unwrap();
+        let profile_id = ProfileId(1);
+        assert_eq!(service.get_readiness(profile_id).unwrap().level, ReadinessLevel::NotStarted);
+
+        let check = service.start_check(profile_id).unwrap();
+        assert_eq!(check.question_count, CHECK_QUESTIONS);
+        assert!(service.submit_answer(check.id, "12").is_err());
+        assert!(service.get_result(check.id).is_err());
+
+        let mut now = Utc::now();
+        let mut facts = Vec::new();
+        for position in 1..=CHECK_QUESTIONS {
+            let question = service.next_question_at(check.id, now).unwrap().unwrap();
+            assert_eq!(question.position, position);
+            let (a, b) = (question.multiplicand, question.multiplier);
+            assert!((LOWEST_TABLE..=HIGHEST_TABLE).contains(&a) && (LOWEST_TABLE..=HIGHEST_TABLE).contains(&b));
+            facts.push((a.min(b), a.max(b)));
+
+            match position {
+                // Too slow: typed after the six seconds were up
+                1 => {
+                    let receipt = service.submit_answer_at(check.id, &(a * b).to_string(), now + Duration::seconds(8)).unwrap();
+                    assert!(receipt.timed_out);
+                }
+                // Nothing typed at all; the next question moves on without it
+                2 => {}
+                3 => {
+                    assert!(service.submit_answer_at(check.id, "7x", now).is_err());
+                    service.submit_answer_at(check.id, &(a * b + 1).to_string(), now + Duration::seconds(2)).unwrap();
+                }
+                _ => {
+                    let receipt = service.submit_answer_at(check.id, &(a * b).to_string(), now + Duration::seconds(3)).unwrap();
+                    assert_eq!(receipt.check_complete, position == CHECK_QUESTIONS);
+                }
+            }
+            now += Duration::seconds((SECONDS_PER_QUESTION + PAUSE_SECONDS) as i64);
+        }
+        facts.sort();
+        facts.dedup();
+        assert_eq!(facts.len(), CHECK_QUESTIONS);
+        assert!(service.next_question(check.id).unwrap().is_none());
+
+        let result = service.get_result(check.id).unwrap();
+        assert_eq!((result.score, result.total), (22, 25));
+        assert!(result.answers[0].timed_out && !result.answers[0].correct);
+        assert!(result.answers[1].timed_out && result.answers[1].answer.is_none());
+        assert!(!result.answers[2].timed_out && !result.answers[2].correct);
+
+        let readiness = service.get_readiness(profile_id).unwrap();
+        assert_eq!(readiness.level, ReadinessLevel::NearlyReady);
+        assert_eq!(readiness.best_score, Some(22));
+        assert!(!readiness.tables_to_practise.is_empty());
+    }
+}
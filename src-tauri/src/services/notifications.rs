@@ -0,0 +1,578 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::ProfileId;
+use crate::database::DatabaseManager;
+use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+
+const TIME_FORMAT: &str = "%H:%M";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Streak warnings go out in the early evening, leaving time to practise before bed
+const STREAK_WARNING_TIME: &str = "18:00";
+
+/// Minimum streak length (in days) worth warning about
+const MIN_STREAK_TO_WARN: u32 = 2;
+
+/// Maximum length of a parent-configured template field
+const MAX_TEMPLATE_LENGTH: usize = 200;
+
+/// Kinds of desktop notification
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum NotificationKind {
+    #[serde(rename = "daily_reminder")]
+    DailyReminder,
+    #[serde(rename = "goal_nearly_met")]
+    GoalNearlyMet,
+    #[serde(rename = "streak_at_risk")]
+    StreakAtRisk,
+    #[serde(rename = "update_installed")]
+    UpdateInstalled,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 4] = [
+        NotificationKind::DailyReminder,
+        NotificationKind::GoalNearlyMet,
+        NotificationKind::StreakAtRisk,
+        NotificationKind::UpdateInstalled,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::DailyReminder => "daily_reminder",
+            NotificationKind::GoalNearlyMet => "goal_nearly_met",
+            NotificationKind::StreakAtRisk => "streak_at_risk",
+            NotificationKind::UpdateInstalled => "update_installed",
+        }
+    }
+
+    /// Built-in template. Placeholders: `{name}`, `{goal}`, `{remaining}`, `{streak}`, `{version}`.
+    fn default_template(&self) -> (&'static str, &'static str) {
+        match self {
+            NotificationKind::DailyReminder => (
+                "Time for a quiz!",
+                "Hi {name}, your daily practice is waiting for you.",
+            ),
+            NotificationKind::GoalNearlyMet => (
+                "Almost there!",
+                "{name}, just {remaining} more questions to reach today's goal of {goal}.",
+            ),
+            NotificationKind::StreakAtRisk => (
+                "Keep your streak going!",
+                "{name}, you've practised {streak} days in a row. Do a quick quiz today to keep it up!",
+            ),
+            NotificationKind::UpdateInstalled => (
+                "New content installed",
+                "Version {version} has been installed. Come and try the new questions!",
+            ),
+        }
+    }
+}
+
+/// Per-profile notification preferences, set by a parent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NotificationPreferences {
+    pub profile_id: ProfileId,
+    pub reminders_enabled: bool,
+    /// Local time (`HH:MM`) of the daily practice reminder
+    pub reminder_time: String,
+    /// Start of quiet hours (`HH:MM`); may wrap past midnight
+    pub quiet_hours_start: Option<String>,
+    /// End of quiet hours (`HH:MM`)
+    pub quiet_hours_end: Option<String>,
+    pub daily_question_goal: u32,
+}
+
+impl NotificationPreferences {
+    fn defaults(profile_id: ProfileId) -> Self {
+        Self {
+            profile_id,
+            reminders_enabled: true,
+            reminder_time: "16:00".to_string(),
+            quiet_hours_start: Some("20:00".to_string()),
+            quiet_hours_end: Some("07:00".to_string()),
+            daily_question_goal: 20,
+        }
+    }
+
+    /// Whether `time` falls inside the quiet hours
+    pub fn is_quiet_time(&self, time: NaiveTime) -> bool {
+        let (start, end) = match (&self.quiet_hours_start, &self.quiet_hours_end) {
+            (Some(start), Some(end)) => match (parse_time(start), parse_time(end)) {
+                (Ok(start), Ok(end)) => (start, end),
+                _ => return false,
+            },
+            _ => return false,
+        };
+
+        if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+/// Notification message template, either built-in or parent-configured
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationTemplate {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    pub is_custom: bool,
+}
+
+/// A rendered notification ready to be shown by the OS
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PendingNotification {
+    pub profile_id: Option<ProfileId>,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+}
+
+/// Notification service that decides which reminders are due. Delivery is left to the shell.
+pub struct NotificationService {
+    db_manager: Arc<DatabaseManager>,
+    paused_until: Mutex<Option<NaiveDateTime>>,
+}
+
+impl NotificationService {
+    /// Create a new notification service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self {
+            db_manager,
+            paused_until: Mutex::new(None),
+        }
+    }
+
+    /// Pause practice reminders for every profile until local time `until`
+    pub fn pause_reminders(&self, until: NaiveDateTime) -> AppResult<()> {
+        *self.lock_paused_until()? = Some(until);
+        Ok(())
+    }
+
+    /// Resume practice reminders immediately
+    pub fn resume_reminders(&self) -> AppResult<()> {
+        *self.lock_paused_until()? = None;
+        Ok(())
+    }
+
+    /// When reminders resume, if they are paused at local time `now`
+    pub fn reminders_paused_until(&self, now: NaiveDateTime) -> AppResult<Option<NaiveDateTime>> {
+        Ok(self.lock_paused_until()?.filter(|until| *until > now))
+    }
+
+    /// Get a profile's preferences, falling back to defaults
+    pub fn get_preferences(&self, profile_id: ProfileId) -> AppResult<NotificationPreferences> {
+        let stored = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT reminders_enabled, reminder_time, quiet_hours_start, quiet_hours_end, daily_question_goal
+                 FROM notification_preferences WHERE profile_id = ?1",
+                params![profile_id],
+                |row| Ok(NotificationPreferences {
+                    profile_id,
+                    reminders_enabled: row.get(0)?,
+                    reminder_time: row.get(1)?,
+                    quiet_hours_start: row.get(2)?,
+                    quiet_hours_end: row.get(3)?,
+                    daily_question_goal: row.get(4)?,
+                }),
+            ).optional()
+        })?;
+
+        Ok(stored.unwrap_or_else(|| NotificationPreferences::defaults(profile_id)))
+    }
+
+    /// Save a profile's preferences
+    pub fn set_preferences(&self, preferences: &NotificationPreferences) -> AppResult<()> {
+        parse_time(&preferences.reminder_time)?;
+        match (&preferences.quiet_hours_start, &preferences.quiet_hours_end) {
+            (Some(start), Some(end)) => {
+                parse_time(start)?;
+                parse_time(end)?;
+            },
+            (None, None) => {},
+            _ => return Err(AppError::InvalidInput(
+                "Quiet hours need both a start and an end time".to_string()
+            )),
+        }
+        if preferences.daily_question_goal == 0 {
+            return Err(AppError::InvalidInput("Daily question goal must be at least 1".to_string()));
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO notification_preferences
+                 (profile_id, reminders_enabled, reminder_time, quiet_hours_start, quiet_hours_end, daily_question_goal, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    preferences.profile_id,
+                    preferences.reminders_enabled,
+                    preferences.reminder_time,
+                    preferences.quiet_hours_start,
+                    preferences.quiet_hours_end,
+                    preferences.daily_question_goal,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Get every template, with parent overrides applied
+    pub fn get_templates(&self) -> AppResult<Vec<NotificationTemplate>> {
+        let custom = self.get_custom_templates()?;
+
+        Ok(NotificationKind::ALL.iter()
+            .map(|kind| match custom.get(kind.as_str()) {
+                Some((title, body)) => NotificationTemplate {
+                    kind: *kind,
+                    title: title.clone(),
+                    body: body.clone(),
+                    is_custom: true,
+                },
+                None => {
+                    let (title, body) = kind.default_template();
+                    NotificationTemplate {
+                        kind: *kind,
+                        title: title.to_string(),
+                        body: body.to_string(),
+                        is_custom: false,
+                    }
+                }
+            })
+            .collect())
+    }
+
+    /// Replace the template for a notification kind
+    pub fn set_template(&self, kind: NotificationKind, title: &str, body: &str) -> AppResult<()> {
+        for (field, value) in [("title", title), ("body", body)] {
+            if value.trim().is_empty() || value.len() > MAX_TEMPLATE_LENGTH {
+                return Err(AppError::InvalidInput(format!(
+                    "Template {} must be between 1 and {} characters",
+                    field, MAX_TEMPLATE_LENGTH
+                )));
+            }
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO notification_templates (kind, title, body, updated_at) VALUES (?1, ?2, ?3, ?4)",
+                params![kind.as_str(), title.trim(), body.trim(), Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Restore the built-in template for a notification kind
+    pub fn reset_template(&self, kind: NotificationKind) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            conn.execute("DELETE FROM notification_templates WHERE kind = ?1", params![kind.as_str()])?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Record questions answered by a profile on a (local) day
+    pub fn record_practice(&self, profile_id: ProfileId, questions_answered: u32, date: NaiveDate) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO daily_activity (profile_id, activity_date, questions_answered) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(profile_id, activity_date)
+                 DO UPDATE SET questions_answered = questions_answered + excluded.questions_answered",
+                params![profile_id, date.format(DATE_FORMAT).to_string(), questions_answered],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Work out which notifications are due at local time `now` and mark them as sent,
+    /// so each kind goes out at most once per profile per day
+    pub fn take_due_notifications(&self, now: NaiveDateTime) -> AppResult<Vec<PendingNotification>> {
+        let today = now.date();
+        let time = now.time();
+        let streak_warning_time = parse_time(STREAK_WARNING_TIME)?;
+        let mut due = Vec::new();
+
+        if self.reminders_paused_until(now)?.is_some() {
+            return Ok(due);
+        }
+
+        for (profile_id, name) in self.get_profiles()? {
+            let preferences = self.get_preferences(profile_id)?;
+            if !preferences.reminders_enabled || preferences.is_quiet_time(time) {
+                continue;
+            }
+
+            let answered_today = self.questions_answered_on(profile_id, today)?;
+            let reminder_time = parse_time(&preferences.reminder_time)?;
+            let mut values = HashMap::new();
+            values.insert("name", name);
+
+            let mut candidates = Vec::new();
+            if answered_today == 0 && time >= reminder_time {
+                candidates.push(NotificationKind::DailyReminder);
+            }
+
+            let goal = preferences.daily_question_goal;
+            if answered_today < goal && answered_today * 4 >= goal * 3 {
+                values.insert("goal", goal.to_string());
+                values.insert("remaining", (goal - answered_today).to_string());
+                candidates.push(NotificationKind::GoalNearlyMet);
+            }
+
+            if answered_today == 0 && time >= streak_warning_time {
+                let streak = self.streak_ending(profile_id, today - Duration::days(1))?;
+                if streak >= MIN_STREAK_TO_WARN {
+                    values.insert("streak", streak.to_string());
+                    candidates.push(NotificationKind::StreakAtRisk);
+                }
+            }
+
+            for kind in candidates {
+                if self.was_sent(Some(profile_id), kind, today)? {
+                    continue;
+                }
+
+                let notification = self.render(Some(profile_id), kind, &values)?;
+                self.log_sent(Some(profile_id), kind, today)?;
+                due.push(notification);
+            }
+        }
+
+        Ok(due)
+    }
+
+    /// Build the notification shown after a content update is installed
+    pub fn update_installed(&self, version: &str, today: NaiveDate) -> AppResult<PendingNotification> {
+        let mut values = HashMap::new();
+        values.insert("version", version.to_string());
+
+        let notification = self.render(None, NotificationKind::UpdateInstalled, &values)?;
+        self.log_sent(None, NotificationKind::UpdateInstalled, today)?;
+        Ok(notification)
+    }
+
+    fn render(
+        &self,
+        profile_id: Option<ProfileId>,
+        kind: NotificationKind,
+        values: &HashMap<&str, String>,
+    ) -> AppResult<PendingNotification> {
+        let template = self.get_templates()?
+            .into_iter()
+            .find(|template| template.kind == kind)
+            .ok_or_else(|| AppError::Internal(format!("Missing notification template: {}", kind.as_str())))?;
+
+        let fill = |text: &str| values.iter().fold(text.to_string(), |text, (key, value)| {
+            text.replace(&format!("{{{}}}", key), value)
+        });
+
+        Ok(PendingNotification {
+            profile_id,
+            kind,
+            title: fill(&template.title),
+            body: fill(&template.body),
+        })
+    }
+
+    fn lock_paused_until(&self) -> AppResult<std::sync::MutexGuard<'_, Option<NaiveDateTime>>> {
+        self.paused_until.lock()
+            .map_err(|_| AppError::Internal("Notification pause lock poisoned".to_string()))
+    }
+
+    fn get_profiles(&self) -> AppResult<Vec<(ProfileId, String)>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT id, name FROM profiles ORDER BY id")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+            let mut profiles = Vec::new();
+            for row in rows {
+                profiles.push(row?);
+            }
+            Ok(profiles)
+        })?)
+    }
+
+    fn get_custom_templates(&self) -> AppResult<HashMap<String, (String, String)>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT kind, title, body FROM notification_templates")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, (row.get::<_, String>(1)?, row.get::<_, String>(2)?)))
+            })?;
+
+            let mut templates = HashMap::new();
+            for row in rows {
+                let (kind, template) = row?;
+                templates.insert(kind, template);
+            }
+            Ok(templates)
+        })?)
+    }
+
+    fn questions_answered_on(&self, profile_id: ProfileId, date: NaiveDate) -> AppResult<u32> {
+        Ok(self.db_manager.execute(|conn| {
+            Ok(conn.query_row(
+                "SELECT questions_answered FROM daily_activity WHERE profile_id = ?1 AND activity_date = ?2",
+                params![profile_id, date.format(DATE_FORMAT).to_string()],
+                |row| row.get(0),
+            ).optional()?.unwrap_or(0))
+        })?)
+    }
+
+    /// Number of consecutive practice days ending on `last_day`
+    fn streak_ending(&self, profile_id: ProfileId, last_day: NaiveDate) -> AppResult<u32> {
+        let mut streak = 0;
+        let mut day = last_day;
+
+        while self.questions_answered_on(profile_id, day)? > 0 {
+            streak += 1;
+            day -= Duration::days(1);
+        }
+
+        Ok(streak)
+    }
+
+    fn was_sent(&self, profile_id: Option<ProfileId>, kind: NotificationKind, date: NaiveDate) -> AppResult<bool> {
+        Ok(self.db_manager.execute(|conn| {
+            let count: u32 = conn.query_row(
+                "SELECT COUNT(*) FROM notification_log
+                 WHERE profile_id IS ?1 AND kind = ?2 AND sent_on = ?3",
+                params![profile_id, kind.as_str(), date.format(DATE_FORMAT).to_string()],
+                |row| row.get(0),
+            )?;
+            Ok(count > 0)
+        })?)
+    }
+
+    fn log_sent(&self, profile_id: Option<ProfileId>, kind: NotificationKind, date: NaiveDate) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO notification_log (profile_id, kind, sent_on, sent_at) VALUES (?1, ?2, ?3, ?4)",
+                params![profile_id, kind.as_str(), date.format(DATE_FORMAT).to_string(), Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+}
+
+fn parse_time(value: &str) -> AppResult<NaiveTime> {
+    NaiveTime::parse_from_str(value, TIME_FORMAT)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid time '{}', expected HH:MM", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    fn create_test_notification_service() -> (NotificationService, Arc<DatabaseManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let service = NotificationService::new(db_service.manager());
+
+        (service, db_service.manager(), temp_dir)
+    }
+
+    fn create_test_profile(db_manager: &DatabaseManager, name: &str) -> ProfileId {
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES (?1, 'avatar1', ?2)",
+                params![name, Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap()
+    }
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_past_midnight() {
+        let preferences = NotificationPreferences::defaults(ProfileId(1));
+
+        assert!(preferences.is_quiet_time(parse_time("21:30").unwrap()));
+        assert!(preferences.is_quiet_time(parse_time("06:59").unwrap()));
+        assert!(!preferences.is_quiet_time(parse_time("07:00").unwrap()));
+        assert!(!preferences.is_quiet_time(parse_time("16:00").unwrap()));
+    }
+
+    #[test]
+    fn test_daily_reminder_and_streak_sent_once() {
+        let (service, db, _temp_dir) = create_test_notification_service();
+        let profile_id = create_test_profile(&db, "Ada");
+        let today = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+
+        for days_ago in 1..=3 {
+            service.record_practice(profile_id, 5, today - Duration::days(days_ago)).unwrap();
+        }
+
+        assert!(service.take_due_notifications(at("2024-03-06", "15:00")).unwrap().is_empty());
+
+        let due = service.take_due_notifications(at("2024-03-06", "16:30")).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].kind, NotificationKind::DailyReminder);
+        assert!(due[0].body.contains("Ada"));
+
+        let due = service.take_due_notifications(at("2024-03-06", "18:15")).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].kind, NotificationKind::StreakAtRisk);
+        assert!(due[0].body.contains("3 days"));
+
+        // Quiet hours suppress everything
+        assert!(service.take_due_notifications(at("2024-03-06", "20:30")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_paused_reminders_are_held_back() {
+        let (service, db, _temp_dir) = create_test_notification_service();
+        create_test_profile(&db, "Ada");
+
+        service.pause_reminders(at("2024-03-07", "00:00")).unwrap();
+        assert!(service.reminders_paused_until(at("2024-03-06", "16:30")).unwrap().is_some());
+        assert!(service.take_due_notifications(at("2024-03-06", "16:30")).unwrap().is_empty());
+
+        service.resume_reminders().unwrap();
+        assert_eq!(service.take_due_notifications(at("2024-03-06", "16:30")).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_goal_nearly_met_uses_custom_template() {
+        let (service, db, _temp_dir) = create_test_notification_service();
+        let profile_id = create_test_profile(&db, "Sam");
+        let today = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+
+        service.set_template(NotificationKind::GoalNearlyMet, "Nearly!", "{remaining} to go, {name}").unwrap();
+        service.record_practice(profile_id, 16, today).unwrap();
+
+        let due = service.take_due_notifications(at("2024-03-06", "17:00")).unwrap();
+        assert_eq!(due, vec![PendingNotification {
+            profile_id: Some(profile_id),
+            kind: NotificationKind::GoalNearlyMet,
+            title: "Nearly!".to_string(),
+            body: "4 to go, Sam".to_string(),
+        }]);
+
+        service.reset_template(NotificationKind::GoalNearlyMet).unwrap();
+        assert!(service.get_templates().unwrap().iter().all(|t| !t.is_custom));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{CreateProfileRequest, Profile, ProfileId};
+use crate::services::{ContentSeeder, ProfileManager, SeedingProgress, DEFAULT_HOUSEHOLD_ID};
+use crate::services::households::HashedCredential;
+use std::sync::Arc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Where a first run has got to. Steps are completed in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    /// Choose the PIN that unlocks parent features
+    ParentCredential,
+    /// Create the first child's profile
+    FirstProfile,
+    /// Pick which subjects to install content for
+    Subjects,
+    /// Install the chosen content
+    SeedContent,
+    Complete,
+}
+
+impl OnboardingStep {
+    fn as_str(self) -> &'static str {
+        match self {
+            OnboardingStep::ParentCredential => "parent_credential",
+            OnboardingStep::FirstProfile => "first_profile",
+            OnboardingStep::Subjects => "subjects",
+            OnboardingStep::SeedContent => "seed_content",
+            OnboardingStep::Complete => "complete",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "parent_credential" => Some(OnboardingStep::ParentCredential),
+            "first_profile" => Some(OnboardingStep::FirstProfile),
+            "subjects" => Some(OnboardingStep::Subjects),
+            "seed_content" => Some(OnboardingStep::SeedContent),
+            "complete" => Some(OnboardingStep::Complete),
+            _ => None,
+        }
+    }
+}
+
+/// Saved onboarding progress, so a first run can pick up where it was left
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingState {
+    pub step: OnboardingStep,
+    pub first_profile_id: Option<ProfileId>,
+    /// Subjects chosen for content, by name
+    pub subjects: Option<Vec<String>>,
+    /// Subjects that can be chosen
+    pub available_subjects: Vec<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl OnboardingState {
+    pub fn is_complete(&self) -> bool {
+        self.step == OnboardingStep::Complete
+    }
+}
+
+/// Walks a new install through setting a parent PIN, creating the first profile, choosing
+/// subjects and installing their content
+pub struct OnboardingService {
+    db_manager: Arc<DatabaseManager>,
+    profile_manager: Arc<ProfileManager>,
+}
+
+impl OnboardingService {
+    /// Create a new onboarding service
+    pub fn new(db_manager: Arc<DatabaseManager>, profile_manager: Arc<ProfileManager>) -> Self {
+        Self {
+            db_manager,
+            profile_manager,
+        }
+    }
+
+    /// Current onboarding progress
+    pub fn get_state(&self) -> AppResult<OnboardingState> {
+        let (step, first_profile_id, subjects, completed_at) = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT step, first_profile_id, subjects, completed_at FROM onboarding_state WHERE id = 1",
+                [],
+                |row| Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<ProfileId>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                )),
+            )
+        })?;
+
+        Ok(OnboardingState {
+            step: OnboardingStep::parse(&step)
+                .ok_or_else(|| AppError::Internal(format!("Unknown onboarding step '{}'", step)))?,
+            first_profile_id,
+            subjects: subjects.map(|subjects| serde_json::from_str(&subjects)).transpose()?,
+            available_subjects: ContentSeeder::seedable_subjects().into_iter().map(str::to_string).collect(),
+            completed_at: completed_at
+                .map(|value| DateTime::parse_from_rfc3339(&value).map(|date| date.with_timezone(&Utc)))
+                .transpose()
+                .map_err(|e| AppError::Internal(format!("Invalid onboarding completion time: {}", e)))?,
+        })
+    }
+
+    /// Set the parent PIN or passphrase. A first run sets up the default household.
+    pub fn set_parent_credential(&self, credential: &str) -> AppResult<OnboardingState> {
+        self.expect_step(OnboardingStep::ParentCredential)?;
+        let credential = HashedCredential::new(credential)?;
+
+        self.db_manager.transaction(|tx| {
+            credential.store(tx, DEFAULT_HOUSEHOLD_ID)?;
+            set_step(tx, OnboardingStep::FirstProfile)
+        })?;
+        self.get_state()
+    }
+
+    /// Create the first child's profile
+    pub fn create_first_profile(&self, request: CreateProfileRequest) -> AppResult<Profile> {
+        self.expect_step(OnboardingStep::FirstProfile)?;
+        let profile = self.profile_manager.create_profile(request)?;
+        self.db_manager.transaction(|tx| {
+            tx.execute(
+                "UPDATE onboarding_state SET first_profile_id = ?1 WHERE id = 1",
+                params![profile.id],
+            )?;
+            set_step(tx, OnboardingStep::Subjects)
+        })?;
+        Ok(profile)
+    }
+
+    /// Choose which subjects to install content for
+    pub fn choose_subjects(&self, subjects: &[String]) -> AppResult<OnboardingState> {
+        self.expect_step(OnboardingStep::Subjects)?;
+        let available = ContentSeeder::seedable_subjects();
+        if subjects.is_empty() {
+            return Err(AppError::InvalidInput("Choose at least one subject".to_string()));
+        }
+        if let Some(unknown) = subjects.iter().find(|subject| !available.contains(&subject.as_str())) {
+            return Err(AppError::InvalidInput(format!("Unknown subject '{}'", unknown)));
+        }
+        let mut chosen = subjects.to_vec();
+        chosen.sort();
+        chosen.dedup();
+
+        let chosen_json = serde_json::to_string(&chosen)?;
+        self.db_manager.transaction(|tx| {
+            tx.execute("UPDATE onboarding_state SET subjects = ?1 WHERE id = 1", params![chosen_json])?;
+            set_step(tx, OnboardingStep::SeedContent)
+        })?;
+        self.get_state()
+    }
+
+    /// Install content for the chosen subjects, reporting progress after each step. If an
+    /// earlier attempt was interrupted, its partly installed content is removed first.
+    pub fn seed_content<F>(&self, seeder: &ContentSeeder, on_progress: F) -> AppResult<OnboardingState>
+    where
+        F: FnMut(&SeedingProgress),
+    {
+        self.expect_step(OnboardingStep::SeedContent)?;
+        let subjects = self.get_state()?.subjects.unwrap_or_default();
+
+        seeder.remove_seeded_questions()?;
+        seeder.seed_subjects_with_progress(&subjects, on_progress)?;
+
+        self.db_manager.transaction(|tx| {
+            tx.execute(
+                "UPDATE onboarding_state SET completed_at = ?1 WHERE id = 1",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            set_step(tx, OnboardingStep::Complete)
+        })?;
+        self.get_state()
+    }
+
+    fn expect_step(&self, expected: OnboardingStep) -> AppResult<()> {
+        let current = self.get_state()?.step;
+        if current != expected {
+            return Err(AppError::InvalidInput(format!(
+                "Onboarding is at the '{}' step, not '{}'", current.as_str(), expected.as_str()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn set_step(conn: &rusqlite::Connection, step: OnboardingStep) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE onboarding_state SET step = ?1, updated_at = ?2 WHERE id = 1",
+        params![step.as_str(), Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::{HouseholdManager, SecurityService};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_onboarding_steps_run_in_order() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let service = OnboardingService::new(
+            db_manager.clone(),
+            Arc::new(ProfileManager::new(db_manager.clone(), Arc::new(SecurityService::new().unwrap()))),
+        );
+        let households = HouseholdManager::new(db_manager.clone());
+        assert_eq!(service.get_state().unwrap().step, OnboardingStep::ParentCredential);
+        assert!(!households.verify_credential("1234", Utc::now()).unwrap());
+        assert!(service.choose_subjects(&["mathematics".to_string()]).is_err());
+        assert!(service.set_parent_credential("12").is_err());
+
+        service.set_parent_credential("2468").unwrap();
+        assert!(households.verify_credential("2468", Utc::now()).unwrap());
+        assert!(!households.verify_credential("2469", Utc::now()).unwrap());
+
+        let profile = service.create_first_profile(CreateProfileRequest {
+            name: "Sam".to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        }).unwrap();
+        let state = service.get_state().unwrap();
+        assert_eq!(state.first_profile_id, profile.id);
+        assert_eq!(state.step, OnboardingStep::Subjects);
+
+        assert!(service.choose_subjects(&["astrology".to_string()]).is_err());
+        let state = service.choose_subjects(&["times_tables".to_string()]).unwrap();
+        assert_eq!(state.step, OnboardingStep::SeedContent);
+
+        let seeder = ContentSeeder::new(db_manager.clone());
+        let mut updates = Vec::new();
+        let state = service.seed_content(&seeder, |progress| updates.push(progress.clone())).unwrap();
+        assert!(state.is_complete());
+        assert!(state.completed_at.is_some());
+        assert!(updates.last().unwrap().finished);
+
+        let stats = seeder.get_content_statistics().unwrap();
+        assert!(stats.questions_by_subject["times_tables"] > 0);
+        assert_eq!(stats.questions_by_subject["mathematics"], 0);
+        assert!(service.set_parent_credential("1357").is_err());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{CreatePassageRequest, KeyStage, Passage, PassageGroup, QuestionType, QuestionId};
+use crate::database::DatabaseManager;
+use crate::services::ContentManager;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension, Row};
+use chrono::{DateTime, Utc};
+
+/// Passage bank: shared reading texts and the StoryQuiz questions that refer to them
+pub struct PassageManager {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+}
+
+impl PassageManager {
+    /// Create a new passage manager
+    pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>) -> Self {
+        Self {
+            db_manager,
+            content_manager,
+        }
+    }
+
+    /// Store a new passage with no questions yet
+    pub fn create_passage(&self, request: CreatePassageRequest) -> AppResult<Passage> {
+        request.validate().map_err(AppError::InvalidInput)?;
+
+        let passage_id = self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO passages (subject_id, key_stage, title, text, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    request.subject_id,
+                    request.key_stage.as_str(),
+                    request.title.trim(),
+                    request.text,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            Ok(conn.last_insert_rowid() as u32)
+        })?;
+
+        self.get_passage(passage_id)
+    }
+
+    /// Get a passage and the ids of its questions, in order
+    pub fn get_passage(&self, passage_id: u32) -> AppResult<Passage> {
+        let passage = self.db_manager.execute(|conn| {
+            let passage = conn.query_row(
+                "SELECT id, subject_id, key_stage, title, text, created_at FROM passages WHERE id = ?1",
+                params![passage_id],
+                Self::row_to_passage,
+            ).optional()?;
+
+            match passage {
+                Some(mut passage) => {
+                    passage.question_ids = Self::load_question_ids(conn, passage_id)?;
+                    Ok(Some(passage))
+                }
+                None => Ok(None),
+            }
+        })?;
+
+        passage.ok_or_else(|| AppError::NotFound(format!("Passage with id {} not found", passage_id)))
+    }
+
+    /// List passages, optionally for one subject and key stage
+    pub fn get_passages(&self, subject_id: Option<u32>, key_stage: Option<KeyStage>) -> AppResult<Vec<Passage>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, subject_id, key_stage, title, text, created_at FROM passages
+                 WHERE (?1 IS NULL OR subject_id = ?1) AND (?2 IS NULL OR key_stage = ?2)
+                 ORDER BY title"
+            )?;
+            let rows = stmt.query_map(
+                params![subject_id, key_stage.map(|key_stage| key_stage.as_str())],
+                Self::row_to_passage,
+            )?;
+
+            let mut passages = Vec::new();
+            for passage in rows {
+                let mut passage = passage?;
+                passage.question_ids = Self::load_question_ids(conn, passage.id.unwrap_or_default())?;
+                passages.push(passage);
+            }
+            Ok(passages)
+        })?)
+    }
+
+    /// Delete a passage. Its questions stay in the bank but are no longer grouped.
+    pub fn delete_passage(&self, passage_id: u32) -> AppResult<()> {
+        let deleted = self.db_manager.transaction(|tx| {
+            tx.execute("DELETE FROM passage_questions WHERE passage_id = ?1", params![passage_id])?;
+            tx.execute("DELETE FROM passages WHERE id = ?1", params![passage_id])
+        })?;
+
+        if deleted == 0 {
+            return Err(AppError::NotFound(format!("Passage with id {} not found", passage_id)));
+        }
+        Ok(())
+    }
+
+    /// Replace a passage's questions with `question_ids`, in the order given.
+    /// A question belongs to at most one passage, so it is moved if linked elsewhere.
+    pub fn set_passage_questions(&self, passage_id: u32, question_ids: &[QuestionId]) -> AppResult<Passage> {
+        let passage = self.get_passage(passage_id)?;
+
+        for &question_id in question_ids {
+            let question = self.content_manager.get_question_by_id(question_id)?;
+            if question.question_type != QuestionType::StoryQuiz {
+                return Err(AppError::InvalidInput(format!(
+                    "Question {} is not a story question", question_id
+                )));
+            }
+            if question.key_stage != passage.key_stage {
+                return Err(AppError::InvalidInput(format!(
+                    "Question {} is for a different key stage than the passage", question_id
+                )));
+            }
+        }
+
+        self.db_manager.transaction(|tx| {
+            tx.execute("DELETE FROM passage_questions WHERE passage_id = ?1", params![passage_id])?;
+            for (position, question_id) in question_ids.iter().enumerate() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO passage_questions (question_id, passage_id, position) VALUES (?1, ?2, ?3)",
+                    params![question_id, passage_id, position as u32],
+                )?;
+            }
+            Ok(())
+        })?;
+
+        self.get_passage(passage_id)
+    }
+
+    /// A passage with its questions, ready to serve
+    pub fn get_passage_group(&self, passage_id: u32) -> AppResult<PassageGroup> {
+        let passage = self.get_passage(passage_id)?;
+        let questions = passage.question_ids.iter()
+            .map(|&question_id| self.content_manager.get_question_by_id(question_id))
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(PassageGroup { passage, questions })
+    }
+
+    /// Pick up to `block_count` random passages for a subject, each with up to
+    /// `questions_per_block` of its questions, for "read, then answer" quiz blocks
+    pub fn get_quiz_blocks(
+        &self,
+        subject: &str,
+        key_stage: KeyStage,
+        block_count: usize,
+        questions_per_block: usize,
+    ) -> AppResult<Vec<PassageGroup>> {
+        if block_count == 0 || questions_per_block == 0 {
+            return Err(AppError::InvalidInput("Block and question counts must be at least 1".to_string()));
+        }
+
+        let passage_ids = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT p.id FROM passages p
+                 JOIN subjects s ON s.id = p.subject_id
+                 WHERE s.name = ?1 AND p.key_stage = ?2
+                   AND EXISTS (SELECT 1 FROM passage_questions pq WHERE pq.passage_id = p.id
+                               AND pq.question_id NOT IN (SELECT question_id FROM retired_questions))
+                 ORDER BY RANDOM() LIMIT ?3"
+            )?;
+            let rows = stmt.query_map(
+                params![subject, key_stage.as_str(), block_count as u32],
+                |row| row.get::<_, u32>(0),
+            )?;
+            rows.collect::<Result<Vec<_>, _>>()
+        })?;
+
+        if passage_ids.is_empty() {
+            return Err(AppError::QuizEngine(
+                "No reading passages available for the specified criteria".to_string()
+            ));
+        }
+
+        passage_ids.into_iter()
+            .map(|passage_id| {
+                let mut group = self.get_passage_group(passage_id)?;
+                group.questions.truncate(questions_per_block);
+                group.passage.question_ids.truncate(questions_per_block);
+                Ok(group)
+            })
+            .collect()
+    }
+
+    fn load_question_ids(conn: &rusqlite::Connection, passage_id: u32) -> rusqlite::Result<Vec<QuestionId>> {
+        let mut stmt = conn.prepare(
+            "SELECT question_id FROM passage_questions
+             WHERE passage_id = ?1 AND question_id NOT IN (SELECT question_id FROM retired_questions)
+             ORDER BY position"
+        )?;
+        let rows = stmt.query_map(params![passage_id], |row| row.get::<_, QuestionId>(0))?;
+        rows.collect()
+    }
+
+    fn row_to_passage(row: &Row) -> rusqlite::Result<Passage> {
+        let key_stage = row.get::<_, String>(2)?.parse::<KeyStage>()
+            .map_err(|_| rusqlite::Error::InvalidColumnType(2, "key_stage".to_string(), rusqlite::types::Type::Text))?;
+        let created_at = row.get::<_, Option<String>>(5)?
+            .and_then(|created_at| DateTime::parse_from_rfc3339(&created_at).ok())
+            .map(|created_at| created_at.with_timezone(&Utc));
+
+        Ok(Passage {
+            id: Some(row.get(0)?),
+            subject_id: row.get(1)?,
+            key_stage,
+            title: row.get(3)?,
+            text: row.get(4)?,
+            question_ids: Vec::new(),
+            created_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    fn create_test_passage_manager() -> (PassageManager, Arc<DatabaseManager>, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            security_service,
+            temp_dir.path().join("content"),
+        ));
+
+        (PassageManager::new(db_service.manager(), content_manager), db_service.manager(), temp_dir)
+    }
+
+    fn insert_question(manager: &PassageManager, db: &DatabaseManager, question_type: QuestionType) -> QuestionId {
+        manager.content_manager.add_question(crate::models::Question {
+            id: None,
+            subject_id: english_subject_id(db),
+            key_stage: KeyStage::KS2,
+            question_type,
+            content: crate::models::QuestionContent {
+                text: "Who found the key?".to_string(),
+                options: Some(vec!["Sam".to_string(), "Ann".to_string()]),
+                story: Some("Sam looked under the mat and found the old brass key.".to_string()),
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            correct_answer: crate::models::Answer::Text("Sam".to_string()),
+            difficulty_level: 2,
+            tags: Vec::new(),
+            assets: None,
+            created_at: None,
+            source: None,
+            explanation: None,
+        }).unwrap()
+    }
+
+    fn english_subject_id(db: &DatabaseManager) -> u32 {
+        db.execute(|conn| conn.query_row("SELECT id FROM subjects WHERE name = 'english'", [], |row| row.get(0))).unwrap()
+    }
+
+    #[test]
+    fn test_passage_questions_are_grouped_in_order() {
+        let (manager, db, _temp_dir) = create_test_passage_manager();
+        let passage = manager.create_passage(CreatePassageRequest {
+            subject_id: english_subject_id(&db),
+            key_stage: KeyStage::KS2,
+            title: "The Lost Key".to_string(),
+            text: "Sam looked under the mat and found the old brass key.".to_string(),
+        }).unwrap();
+        let passage_id = passage.id.unwrap();
+
+        let first = insert_question(&manager, &db, QuestionType::StoryQuiz);
+        let second = insert_question(&manager, &db, QuestionType::StoryQuiz);
+        let not_a_story = insert_question(&manager, &db, QuestionType::MultipleChoice);
+
+        assert!(manager.set_passage_questions(passage_id, &[first, not_a_story]).is_err());
+
+        let passage = manager.set_passage_questions(passage_id, &[second, first]).unwrap();
+        assert_eq!(passage.question_ids, vec![second, first]);
+
+        let group = manager.get_passage_group(passage_id).unwrap();
+        assert_eq!(group.questions.iter().map(|q| q.id.unwrap()).collect::<Vec<_>>(), vec![second, first]);
+
+        let blocks = manager.get_quiz_blocks("english", KeyStage::KS2, 3, 1).unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].questions.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_passage_keeps_questions() {
+        let (manager, db, _temp_dir) = create_test_passage_manager();
+        let passage_id = manager.create_passage(CreatePassageRequest {
+            subject_id: english_subject_id(&db),
+            key_stage: KeyStage::KS2,
+            title: "Seasons".to_string(),
+            text: "Leaves fall in autumn.".to_string(),
+        }).unwrap().id.unwrap();
+        let question_id = insert_question(&manager, &db, QuestionType::StoryQuiz);
+        manager.set_passage_questions(passage_id, &[question_id]).unwrap();
+
+        manager.delete_passage(passage_id).unwrap();
+
+        assert!(manager.get_passage(passage_id).is_err());
+        assert!(manager.content_manager.get_question_by_id(question_id).is_ok());
+        assert!(manager.get_quiz_blocks("english", KeyStage::KS2, 1, 4).is_err());
+    }
+}
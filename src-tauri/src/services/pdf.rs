@@ -0,0 +1,139 @@
+/// A4 portrait, in PDF points
+pub const A4_WIDTH: f32 = 595.0;
+pub const A4_HEIGHT: f32 = 842.0;
+
+/// Helvetica-Bold advance widths (per 1000 em) for ASCII 32..=126, used to lay out text
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Helvetica advance widths (per 1000 em) for ASCII 32..=126
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Width assumed for characters outside the tables (accented Latin-1 letters)
+const DEFAULT_GLYPH_WIDTH: u16 = 611;
+
+/// The standard fonts every PDF built here can use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(&self) -> &'static str {
+        match self {
+            Font::Bold => "F1",
+            Font::Regular => "F2",
+        }
+    }
+
+    fn widths(&self) -> &'static [u16; 95] {
+        match self {
+            Font::Bold => &HELVETICA_BOLD_WIDTHS,
+            Font::Regular => &HELVETICA_WIDTHS,
+        }
+    }
+}
+
+pub fn text_width(text: &str, font: Font, size: f32) -> f32 {
+    let units: u32 = text.chars()
+        .map(|c| match c as u32 {
+            code @ 32..=126 => font.widths()[(code - 32) as usize],
+            _ => DEFAULT_GLYPH_WIDTH,
+        } as u32)
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// Content stream operators drawing `text` with its baseline starting at (x, y)
+pub fn text_at(text: &str, font: Font, size: f32, x: f32, y: f32, (r, g, b): (f32, f32, f32)) -> String {
+    format!(
+        "BT /{} {:.1} Tf {} {} {} rg {:.1} {:.1} Td ({}) Tj ET\n",
+        font.resource(), size, r, g, b, x, y, pdf_string(text)
+    )
+}
+
+/// Break text into lines no wider than `max_width`, at spaces where possible
+pub fn wrap_text(text: &str, font: Font, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if line.is_empty() || text_width(&candidate, font, size) <= max_width {
+                line = candidate;
+            } else {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Encode text as a WinAnsi PDF string literal; characters outside Latin-1 become '?'
+pub fn pdf_string(text: &str) -> String {
+    let mut encoded = String::new();
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                encoded.push('\\');
+                encoded.push(c);
+            }
+            ' '..='~' => encoded.push(c),
+            '\u{a0}'..='\u{ff}' => encoded.push_str(&format!("\\{:03o}", c as u32)),
+            _ => encoded.push('?'),
+        }
+    }
+    encoded
+}
+
+/// Assemble a PDF with one page per content stream, all `width` by `height` points
+pub fn build_pdf(pages: &[String], width: f32, height: f32) -> Vec<u8> {
+    let page_refs: Vec<String> = (0..pages.len()).map(|index| format!("{} 0 R", 5 + index * 2)).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", page_refs.join(" "), pages.len()),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+    ];
+    for (index, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            width, height, 6 + index * 2
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", index + 1, object));
+    }
+
+    let xref_offset = pdf.len();
+    pdf.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for offset in offsets {
+        pdf.push_str(&format!("{:010} 00000 n \n", offset));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1, xref_offset
+    ));
+
+    pdf.into_bytes()
+}
@@ -0,0 +1,301 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Answer, Asset, AssetType, KeyStage, Question, QuestionContent, QuestionId, QuestionSource, QuestionType};
+use crate::services::{ContentManager, normalize_audio};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use serde::{Deserialize, Serialize};
+
+/// Key in `QuestionContent::additional_data` holding a phonics question's `PhonicsAudio`
+pub const PHONICS_AUDIO_KEY: &str = "phonics_audio";
+
+/// Options offered per generated question, including the right one
+const OPTIONS_PER_QUESTION: usize = 4;
+
+/// A KS1 phonics sound and words that begin with it
+pub struct Phoneme {
+    /// Written between slashes in questions, e.g. `sh` for /sh/
+    pub sound: &'static str,
+    pub words: &'static [&'static str],
+}
+
+/// Sounds questions are generated for, following the usual phase 2-3 teaching order
+pub const PHONEMES: &[Phoneme] = &[
+    Phoneme { sound: "s", words: &["sun", "sock", "sand"] },
+    Phoneme { sound: "m", words: &["map", "moon", "mug"] },
+    Phoneme { sound: "t", words: &["tap", "tent", "tiger"] },
+    Phoneme { sound: "p", words: &["pig", "pan", "pen"] },
+    Phoneme { sound: "b", words: &["bat", "bus", "bed"] },
+    Phoneme { sound: "f", words: &["fish", "fan", "fox"] },
+    Phoneme { sound: "sh", words: &["ship", "shop", "shell"] },
+    Phoneme { sound: "ch", words: &["chip", "chin", "cheese"] },
+    Phoneme { sound: "th", words: &["thumb", "thin", "think"] },
+    Phoneme { sound: "qu", words: &["queen", "quiz", "quack"] },
+];
+
+/// Audio clips attached to a phonics question, as paths the frontend can play
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PhonicsAudio {
+    /// The sound on its own, played with the question
+    pub sound: String,
+    /// Each option read aloud, keyed by option text
+    pub options: HashMap<String, String>,
+}
+
+/// Pluggable text-to-speech engine used to create phonics audio that hasn't been supplied
+pub trait SpeechSynthesizer: Send + Sync {
+    fn name(&self) -> &str;
+    /// Speak a word, or a lone sound written between slashes like `/sh/`. Returns WAV data.
+    fn synthesize(&self, text: &str) -> AppResult<Vec<u8>>;
+}
+
+/// Keeps phonics audio in the audio directory and generates KS1 "which word begins with
+/// the /sh/ sound?" questions that use it. Clips already in the directory (bundled or from a
+/// content pack) are used as they are; missing ones come from the installed synthesiser.
+pub struct PhonicsService {
+    content_manager: Arc<ContentManager>,
+    audio_directory: PathBuf,
+    synthesizer: RwLock<Option<Arc<dyn SpeechSynthesizer>>>,
+}
+
+impl PhonicsService {
+    /// Create a phonics service with no speech synthesiser installed
+    pub fn new(content_manager: Arc<ContentManager>, audio_directory: PathBuf) -> Self {
+        Self {
+            content_manager,
+            audio_directory,
+            synthesizer: RwLock::new(None),
+        }
+    }
+
+    /// Install (or with `None`, remove) the speech synthesiser
+    pub fn set_synthesizer(&self, synthesizer: Option<Arc<dyn SpeechSynthesizer>>) -> AppResult<()> {
+        *self.synthesizer.write()
+            .map_err(|_| AppError::Internal("Speech synthesiser lock poisoned".to_string()))? = synthesizer;
+        Ok(())
+    }
+
+    /// Name of the installed synthesiser, if any
+    pub fn synthesizer_name(&self) -> Option<String> {
+        self.synthesizer.read().ok()?.as_ref().map(|synthesizer| synthesizer.name().to_string())
+    }
+
+    /// Path of the clip for a sound (`sh`) or a word (`ship`), generating it if it's missing
+    pub fn ensure_audio(&self, text: &str, is_sound: bool) -> AppResult<PathBuf> {
+        if text.is_empty() || !text.chars().all(|c| c.is_ascii_lowercase()) {
+            return Err(AppError::InvalidInput(format!("'{}' is not a phonics sound or word", text)));
+        }
+
+        let file_name = format!("{}_{}.wav", if is_sound { "sound" } else { "word" }, text);
+        let path = self.audio_directory.join(file_name);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let synthesizer = self.synthesizer.read()
+            .map_err(|_| AppError::Internal("Speech synthesiser lock poisoned".to_string()))?
+            .clone()
+            .ok_or_else(|| AppError::NotFound(format!(
+                "No audio for '{}' and no speech synthesiser is installed to create it", text
+            )))?;
+
+        let spoken = if is_sound { format!("/{}/", text) } else { text.to_string() };
+        let audio = synthesizer.synthesize(&spoken)?;
+        // Level synthesised clips with every other sound in the app
+        let audio = normalize_audio(&audio, Some("wav")).unwrap_or_else(|e| {
+            log::warn!("Keeping '{}' audio from {} as it is: {}", text, synthesizer.name(), e);
+            audio
+        });
+        fs::create_dir_all(&self.audio_directory)?;
+        fs::write(&path, audio)?;
+        Ok(path)
+    }
+
+    /// Build one initial-sound question per phoneme. Wrong options are words from the
+    /// following phonemes, so every option starts with a different sound.
+    pub fn build_questions(&self, subject_id: u32) -> AppResult<Vec<Question>> {
+        PHONEMES.iter().enumerate().map(|(index, phoneme)| {
+            let answer = phoneme.words[0];
+            let mut options = vec![answer.to_string()];
+            options.extend(
+                PHONEMES.iter()
+                    .cycle()
+                    .skip(index + 1)
+                    .take(OPTIONS_PER_QUESTION - 1)
+                    .enumerate()
+                    .map(|(offset, other)| other.words[offset % other.words.len()].to_string())
+            );
+
+            let audio = PhonicsAudio {
+                sound: self.audio_path(phoneme.sound, true)?,
+                options: options.iter()
+                    .map(|option| Ok((option.clone(), self.audio_path(option, false)?)))
+                    .collect::<AppResult<_>>()?,
+            };
+
+            let mut assets = vec![audio_asset(&audio.sound, format!("The /{}/ sound", phoneme.sound))];
+            let mut option_clips: Vec<_> = audio.options.iter().collect();
+            option_clips.sort();
+            assets.extend(option_clips.into_iter().map(|(option, path)| audio_asset(path, option.clone())));
+
+            let mut question = Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: format!("Which word begins with the /{}/ sound?", phoneme.sound),
+                    options: Some(options),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: Some(HashMap::from([(
+                        PHONICS_AUDIO_KEY.to_string(),
+                        serde_json::to_value(&audio)?,
+                    )])),
+                },
+                Answer::Text(answer.to_string()),
+            )
+            .with_difficulty(if phoneme.sound.len() > 1 { 2 } else { 1 })
+            .with_tags(vec!["phonics".to_string(), "initial_sounds".to_string(), phoneme.sound.to_string()])
+            .with_source(QuestionSource::Generator { name: "phonics".to_string() });
+            question.assets = Some(assets);
+            Ok(question)
+        }).collect()
+    }
+
+    /// Generate the phonics questions into the English subject, skipping ones already there.
+    /// Returns the ids of the questions added.
+    pub fn install_questions(&self) -> AppResult<Vec<QuestionId>> {
+        let subject_id = self.content_manager.get_subjects()?
+            .into_iter()
+            .find(|subject| subject.name == "english")
+            .and_then(|subject| subject.id)
+            .ok_or_else(|| AppError::NotFound("The English subject is missing".to_string()))?;
+
+        let existing: Vec<String> = self.content_manager
+            .get_questions_by_subject("english", Some(KeyStage::KS1), None, None)?
+            .into_iter()
+            .map(|question| question.content.text)
+            .collect();
+
+        let mut added = Vec::new();
+        for question in self.build_questions(subject_id)? {
+            if existing.contains(&question.content.text) {
+                continue;
+            }
+            added.push(self.content_manager.add_question(question)?);
+        }
+
+        Ok(added)
+    }
+
+    fn audio_path(&self, text: &str, is_sound: bool) -> AppResult<String> {
+        Ok(self.ensure_audio(text, is_sound)?.to_string_lossy().into_owned())
+    }
+}
+
+fn audio_asset(path: &str, alt_text: String) -> Asset {
+    Asset {
+        id: None,
+        question_id: QuestionId(0), // Set when the question is stored
+        asset_type: AssetType::Audio,
+        file_path: path.to_string(),
+        alt_text: Some(alt_text),
+        file_size: fs::metadata(path).ok().map(|metadata| metadata.len()),
+        created_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::SecurityService;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    /// Records what it was asked to say
+    struct RecordingSynthesizer(Mutex<Vec<String>>);
+
+    impl SpeechSynthesizer for RecordingSynthesizer {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn synthesize(&self, text: &str) -> AppResult<Vec<u8>> {
+            self.0.lock().unwrap().push(text.to_string());
+            Ok(b"RIFF".to_vec())
+        }
+    }
+
+    fn create_test_phonics_service() -> (PhonicsService, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            security_service,
+            temp_dir.path().join("content"),
+        ));
+
+        let service = PhonicsService::new(content_manager, temp_dir.path().join("phonics_audio"));
+        (service, temp_dir)
+    }
+
+    #[test]
+    fn test_audio_is_generated_once_and_bundled_clips_are_used() {
+        let (service, temp_dir) = create_test_phonics_service();
+        assert!(service.ensure_audio("ship", false).is_err());
+
+        // A clip already in the directory needs no synthesiser
+        fs::create_dir_all(temp_dir.path().join("phonics_audio")).unwrap();
+        fs::write(temp_dir.path().join("phonics_audio").join("word_ship.wav"), b"bundled").unwrap();
+        assert!(service.ensure_audio("ship", false).is_ok());
+
+        let synthesizer = Arc::new(RecordingSynthesizer(Mutex::new(Vec::new())));
+        service.set_synthesizer(Some(synthesizer.clone())).unwrap();
+        assert_eq!(service.synthesizer_name().as_deref(), Some("recording"));
+
+        let path = service.ensure_audio("sh", true).unwrap();
+        assert!(path.ends_with("sound_sh.wav"));
+        service.ensure_audio("sh", true).unwrap();
+        service.ensure_audio("ship", false).unwrap();
+        assert_eq!(*synthesizer.0.lock().unwrap(), vec!["/sh/".to_string()]);
+
+        assert!(service.ensure_audio("../secret", false).is_err());
+    }
+
+    #[test]
+    fn test_install_questions_references_audio() {
+        let (service, _temp_dir) = create_test_phonics_service();
+        service.set_synthesizer(Some(Arc::new(RecordingSynthesizer(Mutex::new(Vec::new()))))).unwrap();
+
+        let added = service.install_questions().unwrap();
+        assert_eq!(added.len(), PHONEMES.len());
+        // Running again doesn't duplicate questions
+        assert!(service.install_questions().unwrap().is_empty());
+
+        let question = service.content_manager.get_question_by_id(added[6]).unwrap();
+        assert_eq!(question.content.text, "Which word begins with the /sh/ sound?");
+        let options = question.content.options.clone().unwrap();
+        assert_eq!(options.len(), OPTIONS_PER_QUESTION);
+        assert!(matches!(&question.correct_answer, Answer::Text(answer) if answer == "ship"));
+        assert_eq!(options.iter().filter(|option| option.starts_with("sh")).count(), 1);
+
+        let audio: PhonicsAudio = serde_json::from_value(
+            question.content.additional_data.unwrap()[PHONICS_AUDIO_KEY].clone()
+        ).unwrap();
+        assert!(audio.sound.ends_with("sound_sh.wav"));
+        assert_eq!(audio.options.len(), OPTIONS_PER_QUESTION);
+
+        let assets = question.assets.unwrap();
+        assert_eq!(assets.len(), OPTIONS_PER_QUESTION + 1);
+        assert!(assets.iter().all(|asset| asset.asset_type == AssetType::Audio));
+    }
+}
@@ -0,0 +1,481 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::ProfileId;
+use crate::services::PhonicsService;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Real and pseudo-words the screening practice draws from, shipped as content
+const SCREENING_WORDS: &str = include_str!("../../assets/phonics/screening_words.json");
+/// Words in each of the two sections, as in the statutory Year 1 check
+pub const WORDS_PER_SECTION: usize = 20;
+/// Of each section's words, how many are pseudo-words
+pub const PSEUDO_WORDS_PER_SECTION: usize = 10;
+/// Words read correctly to meet the expected standard. The real threshold has been 32 of
+/// 40 every year since 2014.
+pub const EXPECTED_STANDARD: u32 = 32;
+
+/// A word from the bundled screening list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScreeningWordEntry {
+    pub word: String,
+    /// The graphemes a child sounds out, in order. Split digraphs are written `a_e`.
+    pub graphemes: Vec<String>,
+    /// 1 for simple words, 2 for harder graphemes and longer clusters
+    pub section: u8,
+    /// A made-up word, shown with an alien so it isn't mistaken for a real one
+    pub pseudo: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScreeningWordList {
+    words: Vec<ScreeningWordEntry>,
+}
+
+/// One word to read aloud in a screening
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningWord {
+    /// 1-based, as on the marking sheet
+    pub position: usize,
+    pub word: String,
+    pub graphemes: Vec<String>,
+    pub section: u8,
+    pub pseudo: bool,
+    /// Clip of the word read aloud, to play once it has been marked; None if there is no
+    /// clip and no speech synthesiser to make one
+    pub audio: Option<String>,
+    /// None until the adult listening has marked it
+    pub read_correctly: Option<bool>,
+}
+
+/// A screening in progress or finished
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhonicsScreening {
+    pub id: u32,
+    pub profile_id: ProfileId,
+    pub words: Vec<ScreeningWord>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// How often a grapheme was sounded out correctly
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GraphemeAccuracy {
+    pub grapheme: String,
+    pub attempts: u32,
+    pub correct: u32,
+    pub accuracy: f32,
+    /// Clip of the grapheme's sound, for practising it. Split digraphs have none.
+    pub sound_audio: Option<String>,
+}
+
+/// A marked screening
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningResult {
+    pub screening_id: u32,
+    pub profile_id: ProfileId,
+    pub score: u32,
+    pub total: u32,
+    pub expected_standard: u32,
+    pub met_expected_standard: bool,
+    pub real_word_score: u32,
+    pub pseudo_word_score: u32,
+    pub section_scores: [u32; 2],
+    /// Every grapheme in the screening, least accurate first
+    pub grapheme_accuracy: Vec<GraphemeAccuracy>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Practice for the KS1 Phonics Screening Check: 40 words in two sections, half of them
+/// pseudo-words, read aloud to an adult who marks each one. Misread graphemes are
+/// recorded so accuracy can be reported per phoneme.
+pub struct PhonicsScreeningService {
+    db_manager: Arc<DatabaseManager>,
+    phonics: Arc<PhonicsService>,
+    words: Vec<ScreeningWordEntry>,
+}
+
+impl PhonicsScreeningService {
+    /// Create a new screening service using the bundled word list
+    pub fn new(db_manager: Arc<DatabaseManager>, phonics: Arc<PhonicsService>) -> AppResult<Self> {
+        let list: ScreeningWordList = serde_json::from_str(SCREENING_WORDS)?;
+        Ok(Self {
+            db_manager,
+            phonics,
+            words: list.words,
+        })
+    }
+
+    /// Start a screening with a fresh selection of words
+    pub fn start_screening(&self, profile_id: ProfileId) -> AppResult<PhonicsScreening> {
+        let mut words = Vec::with_capacity(WORDS_PER_SECTION * 2);
+        for section in 1..=2 {
+            let mut section_words = Vec::with_capacity(WORDS_PER_SECTION);
+            for pseudo in [false, true] {
+                let wanted = if pseudo { PSEUDO_WORDS_PER_SECTION } else { WORDS_PER_SECTION - PSEUDO_WORDS_PER_SECTION };
+                let candidates: Vec<&ScreeningWordEntry> = self.words.iter()
+                    .filter(|entry| entry.section == section && entry.pseudo == pseudo)
+                    .collect();
+                if candidates.len() < wanted {
+                    return Err(AppError::Internal(format!(
+                        "The screening word list has too few {} words for section {}",
+                        if pseudo { "pseudo" } else { "real" }, section
+                    )));
+                }
+                section_words.extend(shuffled(candidates)?.into_iter().take(wanted));
+            }
+            words.extend(shuffled(section_words)?);
+        }
+
+        let started_at = Utc::now();
+        let id = self.db_manager.transaction(|tx| {
+            tx.execute(
+                "INSERT INTO phonics_screenings (profile_id, started_at) VALUES (?1, ?2)",
+                params![profile_id, started_at.to_rfc3339()],
+            )?;
+            let screening_id = tx.last_insert_rowid() as u32;
+            for (index, entry) in words.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO phonics_screening_words (screening_id, position, word, graphemes, section, pseudo)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        screening_id,
+                        index + 1,
+                        entry.word,
+                        serde_json::to_string(&entry.graphemes).unwrap_or_default(),
+                        entry.section,
+                        entry.pseudo,
+                    ],
+                )?;
+            }
+            Ok(screening_id)
+        })?;
+
+        self.get_screening(id)
+    }
+
+    /// A screening and how far its marking has got
+    pub fn get_screening(&self, screening_id: u32) -> AppResult<PhonicsScreening> {
+        let screening = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT profile_id, started_at, completed_at FROM phonics_screenings WHERE id = ?1",
+                params![screening_id],
+                |row| Ok((
+                    ProfileId(row.get(0)?),
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                )),
+            ).optional()
+        })?;
+        let (profile_id, started_at, completed_at) = screening.ok_or_else(|| {
+            AppError::NotFound(format!("Phonics screening with id {} not found", screening_id))
+        })?;
+
+        let words = self.db_manager.execute(|conn| load_words(conn, screening_id))?
+            .into_iter()
+            .map(|word| ScreeningWord {
+                audio: self.phonics.ensure_audio(&word.word, false).ok()
+                    .map(|path| path.to_string_lossy().into_owned()),
+                position: word.position,
+                word: word.word,
+                graphemes: word.graphemes,
+                section: word.section,
+                pseudo: word.pseudo,
+                read_correctly: word.read_correctly,
+            })
+            .collect();
+
+        Ok(PhonicsScreening {
+            id: screening_id,
+            profile_id,
+            words,
+            started_at: parse_time(Some(started_at))
+                .ok_or_else(|| AppError::Internal("Invalid screening start time".to_string()))?,
+            completed_at: parse_time(completed_at),
+        })
+    }
+
+    /// Mark one word as read correctly or not. For a misread word, `misread_graphemes` are
+    /// the graphemes the child got wrong; if none are given, the whole word counts as
+    /// misread. Words can be re-marked until the last one is marked.
+    pub fn mark_word(
+        &self,
+        screening_id: u32,
+        position: usize,
+        read_correctly: bool,
+        misread_graphemes: Vec<String>,
+    ) -> AppResult<Option<ScreeningResult>> {
+        let complete = self.db_manager.transaction(|tx| {
+            let state = tx.query_row(
+                "SELECT s.completed_at, w.graphemes
+                 FROM phonics_screening_words w JOIN phonics_screenings s ON s.id = w.screening_id
+                 WHERE w.screening_id = ?1 AND w.position = ?2",
+                params![screening_id, position],
+                |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?)),
+            ).optional()?;
+            let graphemes = match state {
+                Some((Some(_), _)) => return Ok(Err(AppError::QuizEngine("This screening is already finished".to_string()))),
+                Some((None, graphemes)) => parse_graphemes(&graphemes),
+                None => return Ok(Err(AppError::NotFound(format!(
+                    "Word {} of phonics screening {} not found", position, screening_id
+                )))),
+            };
+
+            if read_correctly && !misread_graphemes.is_empty() {
+                return Ok(Err(AppError::InvalidInput("A word read correctly has no misread graphemes".to_string())));
+            }
+            if let Some(unknown) = misread_graphemes.iter().find(|grapheme| !graphemes.contains(grapheme)) {
+                return Ok(Err(AppError::InvalidInput(format!("'{}' is not one of this word's graphemes", unknown))));
+            }
+
+            tx.execute(
+                "UPDATE phonics_screening_words SET read_correctly = ?1, misread_graphemes = ?2, marked_at = ?3
+                 WHERE screening_id = ?4 AND position = ?5",
+                params![
+                    read_correctly,
+                    serde_json::to_string(&misread_graphemes).unwrap_or_default(),
+                    Utc::now().to_rfc3339(),
+                    screening_id,
+                    position,
+                ],
+            )?;
+
+            let unmarked: u32 = tx.query_row(
+                "SELECT COUNT(*) FROM phonics_screening_words WHERE screening_id = ?1 AND read_correctly IS NULL",
+                params![screening_id],
+                |row| row.get(0),
+            )?;
+            if unmarked == 0 {
+                tx.execute(
+                    "UPDATE phonics_screenings SET completed_at = ?1,
+                        score = (SELECT COUNT(*) FROM phonics_screening_words WHERE screening_id = ?2 AND read_correctly = 1)
+                     WHERE id = ?2",
+                    params![Utc::now().to_rfc3339(), screening_id],
+                )?;
+            }
+            Ok(Ok(unmarked == 0))
+        })??;
+
+        if complete {
+            self.get_result(screening_id).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Marks for a finished screening
+    pub fn get_result(&self, screening_id: u32) -> AppResult<ScreeningResult> {
+        let screening = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT profile_id, score, completed_at FROM phonics_screenings WHERE id = ?1",
+                params![screening_id],
+                |row| Ok((
+                    ProfileId(row.get(0)?),
+                    row.get::<_, Option<u32>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                )),
+            ).optional()
+        })?;
+        let (profile_id, score, completed_at) = match screening {
+            Some((profile_id, Some(score), Some(completed_at))) => (profile_id, score, completed_at),
+            Some(_) => return Err(AppError::QuizEngine("This screening hasn't been fully marked yet".to_string())),
+            None => return Err(AppError::NotFound(format!("Phonics screening with id {} not found", screening_id))),
+        };
+
+        let words = self.db_manager.execute(|conn| load_words(conn, screening_id))?;
+        let count = |filter: &dyn Fn(&StoredWord) -> bool| {
+            words.iter().filter(|word| word.read_correctly == Some(true) && filter(word)).count() as u32
+        };
+
+        Ok(ScreeningResult {
+            screening_id,
+            profile_id,
+            score,
+            total: words.len() as u32,
+            expected_standard: EXPECTED_STANDARD,
+            met_expected_standard: score >= EXPECTED_STANDARD,
+            real_word_score: count(&|word| !word.pseudo),
+            pseudo_word_score: count(&|word| word.pseudo),
+            section_scores: [count(&|word| word.section == 1), count(&|word| word.section == 2)],
+            grapheme_accuracy: self.grapheme_accuracy(&words),
+            completed_at: parse_time(Some(completed_at))
+                .ok_or_else(|| AppError::Internal("Invalid screening completion time".to_string()))?,
+        })
+    }
+
+    /// Per-grapheme accuracy across every finished screening for the profile, least
+    /// accurate first
+    pub fn get_grapheme_accuracy(&self, profile_id: ProfileId) -> AppResult<Vec<GraphemeAccuracy>> {
+        let words = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id FROM phonics_screenings WHERE profile_id = ?1 AND completed_at IS NOT NULL"
+            )?;
+            let ids = stmt.query_map(params![profile_id], |row| row.get::<_, u32>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut words = Vec::new();
+            for id in ids {
+                words.extend(load_words(conn, id)?);
+            }
+            Ok(words)
+        })?;
+        Ok(self.grapheme_accuracy(&words))
+    }
+
+    fn grapheme_accuracy(&self, words: &[StoredWord]) -> Vec<GraphemeAccuracy> {
+        let mut tallies: BTreeMap<&str, (u32, u32)> = BTreeMap::new();
+        for word in words.iter().filter(|word| word.read_correctly.is_some()) {
+            let whole_word_misread = word.read_correctly == Some(false) && word.misread_graphemes.is_empty();
+            for grapheme in &word.graphemes {
+                let tally = tallies.entry(grapheme.as_str()).or_insert((0, 0));
+                tally.0 += 1;
+                if !whole_word_misread && !word.misread_graphemes.contains(grapheme) {
+                    tally.1 += 1;
+                }
+            }
+        }
+
+        let mut accuracy: Vec<GraphemeAccuracy> = tallies.into_iter()
+            .map(|(grapheme, (attempts, correct))| GraphemeAccuracy {
+                grapheme: grapheme.to_string(),
+                attempts,
+                correct,
+                accuracy: correct as f32 / attempts as f32,
+                sound_audio: self.phonics.ensure_audio(grapheme, true).ok()
+                    .map(|path| path.to_string_lossy().into_owned()),
+            })
+            .collect();
+        accuracy.sort_by(|a, b| {
+            a.accuracy.partial_cmp(&b.accuracy).unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.attempts.cmp(&a.attempts))
+                .then(a.grapheme.cmp(&b.grapheme))
+        });
+        accuracy
+    }
+}
+
+struct StoredWord {
+    position: usize,
+    word: String,
+    graphemes: Vec<String>,
+    section: u8,
+    pseudo: bool,
+    read_correctly: Option<bool>,
+    misread_graphemes: Vec<String>,
+}
+
+fn load_words(conn: &Connection, screening_id: u32) -> rusqlite::Result<Vec<StoredWord>> {
+    let mut stmt = conn.prepare(
+        "SELECT position, word, graphemes, section, pseudo, read_correctly, misread_graphemes
+         FROM phonics_screening_words WHERE screening_id = ?1 ORDER BY position"
+    )?;
+    let words = stmt.query_map(params![screening_id], |row| {
+        Ok(StoredWord {
+            position: row.get(0)?,
+            word: row.get(1)?,
+            graphemes: parse_graphemes(&row.get::<_, String>(2)?),
+            section: row.get(3)?,
+            pseudo: row.get(4)?,
+            read_correctly: row.get(5)?,
+            misread_graphemes: row.get::<_, Option<String>>(6)?
+                .map(|value| parse_graphemes(&value))
+                .unwrap_or_default(),
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+    Ok(words)
+}
+
+fn parse_graphemes(value: &str) -> Vec<String> {
+    serde_json::from_str(value).unwrap_or_default()
+}
+
+fn parse_time(value: Option<String>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+        .map(|value| value.with_timezone(&Utc))
+}
+
+/// Fisher-Yates shuffle
+fn shuffled<T>(mut items: Vec<T>) -> AppResult<Vec<T>> {
+    for i in (1..items.len()).rev() {
+        let mut bytes = [0u8; 4];
+        getrandom::getrandom(&mut bytes)
+            .map_err(|e| AppError::Internal(format!("Failed to generate random number: {}", e)))?;
+        items.swap(i, u32::from_le_bytes(bytes) as usize % (i + 1));
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::{ContentManager, SecurityService};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_screening_mixes_word_types_and_reports_grapheme_accuracy() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+        let content_manager = Arc::new(ContentManager::new(
+            db_manager.clone(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let phonics = Arc::new(PhonicsService::new(content_manager, temp_dir.path().join("phonics_audio")));
+        let service = PhonicsScreeningService::new(db_manager.clone(), phonics).unwrap();
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )
+        }).unwrap();
+
+        // Every bundled word is written exactly as its graphemes spell it
+        for entry in &service.words {
+            assert_eq!(entry.graphemes.concat().replace('_', "").len(), entry.word.len(), "{}", entry.word);
+        }
+
+        let screening = service.start_screening(ProfileId(1)).unwrap();
+        assert_eq!(screening.words.len(), WORDS_PER_SECTION * 2);
+        assert!(screening.words[..WORDS_PER_SECTION].iter().all(|word| word.section == 1));
+        assert_eq!(screening.words.iter().filter(|word| word.pseudo).count(), PSEUDO_WORDS_PER_SECTION * 2);
+        // No synthesiser is installed, so there's no audio to offer
+        assert!(screening.words.iter().all(|word| word.audio.is_none()));
+
+        // Misread the first grapheme of six words and the whole of two more
+        let last = screening.words.len();
+        for word in &screening.words {
+            let result = match word.position {
+                1..=6 => service.mark_word(screening.id, word.position, false, vec![word.graphemes[0].clone()]),
+                7 | 8 => service.mark_word(screening.id, word.position, false, Vec::new()),
+                _ => service.mark_word(screening.id, word.position, true, Vec::new()),
+            }.unwrap();
+            if word.position <= 6 {
+                assert!(service.mark_word(screening.id, word.position, false, vec!["xyz".to_string()]).is_err());
+            }
+            assert_eq!(result.is_some(), word.position == last);
+        }
+        assert!(service.mark_word(screening.id, 1, true, Vec::new()).is_err());
+
+        let result = service.get_result(screening.id).unwrap();
+        assert_eq!((result.score, result.total), (32, 40));
+        assert!(result.met_expected_standard);
+        assert_eq!(result.real_word_score + result.pseudo_word_score, 32);
+        assert_eq!(result.section_scores, [12, 20]);
+
+        let attempts: u32 = result.grapheme_accuracy.iter().map(|accuracy| accuracy.attempts).sum();
+        let graphemes: usize = screening.words.iter().map(|word| word.graphemes.len()).sum();
+        assert_eq!(attempts as usize, graphemes);
+        let missed: u32 = result.grapheme_accuracy.iter().map(|accuracy| accuracy.attempts - accuracy.correct).sum();
+        let whole_words: usize = screening.words[6..8].iter().map(|word| word.graphemes.len()).sum();
+        assert_eq!(missed as usize, 6 + whole_words);
+        assert!(result.grapheme_accuracy[0].accuracy < 1.0);
+
+        assert_eq!(service.get_grapheme_accuracy(ProfileId(1)).unwrap(), result.grapheme_accuracy);
+    }
+}
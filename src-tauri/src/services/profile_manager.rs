@@ -1,21 +1,32 @@
 use crate::errors::{AppError, AppResult};
-use crate::models::{Profile, CreateProfileRequest, Progress};
+use crate::models::{Profile, CreateProfileRequest, KeyStage, Progress, DailyStreak, LevelInfo, XpGain, HouseholdStatistics, HouseholdSubjectTotal, BusiestDay, accuracy_percentage, ProfileId, QuestionId, SessionId};
 use crate::database::DatabaseManager;
-use crate::services::SecurityService;
+use crate::services::{AchievementManager, ExperienceTracker, SecurityService, StreakTracker};
+use crate::services::quiz_engine::HeadToHeadSummary;
+use crate::services::households::{record_profile_household, HOUSEHOLD_PROFILES_SQL};
 use std::sync::Arc;
-use rusqlite::params;
-use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+
+/// Subjects listed in the household statistics
+const TOP_SUBJECT_COUNT: usize = 3;
 
 /// Profile manager for handling user profile CRUD operations
 pub struct ProfileManager {
     db_manager: Arc<DatabaseManager>,
-    security_service: SecurityService,
+    security_service: Arc<SecurityService>,
+    achievements: AchievementManager,
+    streaks: StreakTracker,
+    experience: ExperienceTracker,
 }
 
 impl ProfileManager {
     /// Create a new profile manager
-    pub fn new(db_manager: Arc<DatabaseManager>, security_service: SecurityService) -> Self {
+    pub fn new(db_manager: Arc<DatabaseManager>, security_service: Arc<SecurityService>) -> Self {
         Self {
+            achievements: AchievementManager::new(db_manager.clone()),
+            streaks: StreakTracker::new(db_manager.clone()),
+            experience: ExperienceTracker::new(db_manager.clone()),
             db_manager,
             security_service,
         }
@@ -43,7 +54,7 @@ impl ProfileManager {
         
         let theme_preference = request.theme_preference.unwrap_or_else(|| "default".to_string());
         
-        let profile_id = self.db_manager.execute(|conn| {
+        let profile_id = self.db_manager.transaction(|conn| {
             conn.execute(
                 "INSERT INTO profiles (name, avatar, theme_preference, created_at) VALUES (?1, ?2, ?3, ?4)",
                 params![
@@ -54,7 +65,8 @@ impl ProfileManager {
                 ],
             )?;
             
-            let id = conn.last_insert_rowid() as u32;
+            let id = ProfileId(conn.last_insert_rowid() as u32);
+            record_profile_household(conn, id)?;
             Ok(id)
         })?;
         
@@ -66,7 +78,7 @@ impl ProfileManager {
     }
     
     /// Get a profile by ID
-    pub fn get_profile_by_id(&self, profile_id: u32) -> AppResult<Profile> {
+    pub fn get_profile_by_id(&self, profile_id: ProfileId) -> AppResult<Profile> {
         self.db_manager.execute(|conn| {
             let mut stmt = conn.prepare(
                 "SELECT id, name, avatar, created_at, theme_preference FROM profiles WHERE id = ?1"
@@ -74,7 +86,7 @@ impl ProfileManager {
             
             let profile = stmt.query_row(params![profile_id], |row| {
                 Ok(Profile {
-                    id: Some(row.get::<_, u32>(0)?),
+                    id: Some(row.get(0)?),
                     name: row.get::<_, String>(1)?,
                     avatar: row.get::<_, String>(2)?,
                     created_at: Some(DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
@@ -91,16 +103,18 @@ impl ProfileManager {
         })
     }
     
-    /// Get all profiles
+    /// Get all profiles in the active household
     pub fn get_all_profiles(&self) -> AppResult<Vec<Profile>> {
         let profiles = self.db_manager.execute(|conn| {
-            let mut stmt = conn.prepare(
-                "SELECT id, name, avatar, created_at, theme_preference FROM profiles ORDER BY created_at DESC"
-            )?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, name, avatar, created_at, theme_preference FROM profiles
+                 WHERE id IN ({}) ORDER BY created_at DESC",
+                HOUSEHOLD_PROFILES_SQL
+            ))?;
             
             let profile_iter = stmt.query_map([], |row| {
                 Ok(Profile {
-                    id: Some(row.get::<_, u32>(0)?),
+                    id: Some(row.get(0)?),
                     name: row.get::<_, String>(1)?,
                     avatar: row.get::<_, String>(2)?,
                     created_at: Some(DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
@@ -132,7 +146,7 @@ impl ProfileManager {
     }
     
     /// Update a profile
-    pub fn update_profile(&self, profile_id: u32, updates: ProfileUpdateRequest) -> AppResult<Profile> {
+    pub fn update_profile(&self, profile_id: ProfileId, updates: ProfileUpdateRequest) -> AppResult<Profile> {
         // Validate that profile exists
         let _existing_profile = self.get_profile_by_id(profile_id)?;
         
@@ -201,11 +215,11 @@ impl ProfileManager {
     }
     
     /// Delete a profile
-    pub fn delete_profile(&self, profile_id: u32) -> AppResult<()> {
+    pub fn delete_profile(&self, profile_id: ProfileId) -> AppResult<()> {
         // Validate that profile exists
         let _existing_profile = self.get_profile_by_id(profile_id)?;
         
-        Ok(self.db_manager.transaction(|tx| {
+        self.db_manager.transaction(|tx| {
             // Delete progress data
             tx.execute("DELETE FROM progress WHERE profile_id = ?1", params![profile_id])?;
             
@@ -216,13 +230,273 @@ impl ProfileManager {
             tx.execute("DELETE FROM profiles WHERE id = ?1", params![profile_id])?;
             
             Ok(())
+        })?;
+        
+        // Everything recorded against the profile cascades with it
+        self.db_manager.schedule_compaction();
+        Ok(())
+    }
+    
+    /// Reset part or all of a profile's progress, keeping the profile and its achievements.
+    /// The cleared records are saved with an audit log entry first, so the reset can be undone.
+    pub fn reset_progress(&self, profile_id: ProfileId, scope: ResetScope) -> AppResult<ProgressReset> {
+        // Validate that profile exists
+        let _profile = self.get_profile_by_id(profile_id)?;
+        self.validate_reset_scope(&scope)?;
+
+        // Every filter mentions ?2 so the same parameters fit each scope
+        let (progress_filter, attempt_filter, value) = match &scope {
+            ResetScope::Everything => ("?2 IS NOT NULL", "?2 IS NOT NULL", String::new()),
+            ResetScope::Subject { subject } => (
+                "(LOWER(subject) = LOWER(?2)
+                  OR LOWER(subject) = (SELECT LOWER(display_name) FROM subjects WHERE name = ?2))",
+                "question_id IN (SELECT q.id FROM questions q JOIN subjects s ON s.id = q.subject_id WHERE s.name = ?2)",
+                subject.clone(),
+            ),
+            // Progress counters are kept per subject, so a tag reset only clears per-question history
+            ResetScope::Tag { tag } => (
+                "?2 IS NULL",
+                "question_id IN (SELECT id FROM questions WHERE EXISTS (SELECT 1 FROM json_each(questions.tags) WHERE value = ?2))",
+                tag.clone(),
+            ),
+        };
+        let scope_json = serde_json::to_string(&scope)?;
+        let reset_at = Utc::now();
+
+        let reset_id = self.db_manager.transaction(|tx| {
+            let snapshot = {
+                let mut stmt = tx.prepare(&format!(
+                    "SELECT subject, key_stage, questions_answered, correct_answers, total_time_spent, last_activity
+                     FROM progress WHERE profile_id = ?1 AND {} ORDER BY subject, key_stage",
+                    progress_filter
+                ))?;
+                let progress = stmt.query_map(params![profile_id, value], |row| {
+                    Ok(ProgressRecord {
+                        subject: row.get(0)?,
+                        key_stage: row.get(1)?,
+                        questions_answered: row.get(2)?,
+                        correct_answers: row.get(3)?,
+                        total_time_spent: row.get(4)?,
+                        last_activity: row.get(5)?,
+                    })
+                })?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+                let mut stmt = tx.prepare(&format!(
+                    "SELECT question_id, difficulty_level, is_correct, attempted_at
+                     FROM boss_question_attempts WHERE profile_id = ?1 AND {} ORDER BY id",
+                    attempt_filter
+                ))?;
+                let boss_attempts = stmt.query_map(params![profile_id, value], |row| {
+                    Ok(BossAttemptRecord {
+                        question_id: row.get(0)?,
+                        difficulty_level: row.get(1)?,
+                        is_correct: row.get(2)?,
+                        attempted_at: row.get(3)?,
+                    })
+                })?.collect::<rusqlite::Result<Vec<_>>>()?;
+
+                ProgressSnapshot { progress, boss_attempts }
+            };
+            let snapshot_json = serde_json::to_string(&snapshot)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+            tx.execute(
+                "INSERT INTO progress_resets (profile_id, scope, snapshot, reset_at) VALUES (?1, ?2, ?3, ?4)",
+                params![profile_id, scope_json, snapshot_json, reset_at.to_rfc3339()],
+            )?;
+            let reset_id = tx.last_insert_rowid() as u32;
+
+            tx.execute(
+                &format!(
+                    "UPDATE progress SET questions_answered = 0, correct_answers = 0, total_time_spent = 0, last_activity = ?3
+                     WHERE profile_id = ?1 AND {}",
+                    progress_filter
+                ),
+                params![profile_id, value, reset_at.to_rfc3339()],
+            )?;
+            tx.execute(
+                &format!(
+                    "DELETE FROM boss_question_attempts WHERE profile_id = ?1 AND {}",
+                    attempt_filter
+                ),
+                params![profile_id, value],
+            )?;
+
+            Ok(reset_id)
+        })?;
+
+        self.db_manager.schedule_compaction();
+        self.get_progress_reset(reset_id)
+    }
+
+    /// Audit log of a profile's progress resets, newest first
+    pub fn get_progress_resets(&self, profile_id: ProfileId) -> AppResult<Vec<ProgressReset>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, profile_id, scope, snapshot, reset_at, restored_at FROM progress_resets
+                 WHERE profile_id = ?1 ORDER BY reset_at DESC, id DESC"
+            )?;
+            let rows = stmt.query_map(params![profile_id], Self::row_to_progress_reset)?;
+            rows.collect()
         })?)
     }
+
+    /// Put back the records a reset cleared. Progress earned since the reset is replaced
+    /// by the saved figures.
+    pub fn restore_progress_reset(&self, reset_id: u32) -> AppResult<ProgressReset> {
+        let reset = self.get_progress_reset(reset_id)?;
+        if reset.restored_at.is_some() {
+            return Err(AppError::InvalidInput("This reset has already been undone".to_string()));
+        }
+
+        self.db_manager.transaction(|tx| {
+            for record in &reset.snapshot.progress {
+                tx.execute(
+                    "INSERT OR REPLACE INTO progress
+                     (profile_id, subject, key_stage, questions_answered, correct_answers, total_time_spent, last_activity)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        reset.profile_id,
+                        record.subject,
+                        record.key_stage,
+                        record.questions_answered,
+                        record.correct_answers,
+                        record.total_time_spent,
+                        record.last_activity
+                    ],
+                )?;
+            }
+            for attempt in &reset.snapshot.boss_attempts {
+                // Questions deleted since the reset have nothing to restore against
+                tx.execute(
+                    "INSERT INTO boss_question_attempts (profile_id, question_id, difficulty_level, is_correct, attempted_at)
+                     SELECT ?1, ?2, ?3, ?4, ?5 WHERE EXISTS (SELECT 1 FROM questions WHERE id = ?2)",
+                    params![
+                        reset.profile_id,
+                        attempt.question_id,
+                        attempt.difficulty_level,
+                        attempt.is_correct,
+                        attempt.attempted_at
+                    ],
+                )?;
+            }
+            tx.execute(
+                "UPDATE progress_resets SET restored_at = ?2 WHERE id = ?1",
+                params![reset_id, Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        self.get_progress_reset(reset_id)
+    }
+
+    fn get_progress_reset(&self, reset_id: u32) -> AppResult<ProgressReset> {
+        self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT id, profile_id, scope, snapshot, reset_at, restored_at FROM progress_resets WHERE id = ?1",
+                params![reset_id],
+                Self::row_to_progress_reset,
+            ).optional()
+        })?
+        .ok_or_else(|| AppError::NotFound(format!("Progress reset {} not found", reset_id)))
+    }
+
+    fn validate_reset_scope(&self, scope: &ResetScope) -> AppResult<()> {
+        match scope {
+            ResetScope::Everything => Ok(()),
+            ResetScope::Subject { subject } => {
+                let exists: bool = self.db_manager.execute(|conn| {
+                    conn.query_row("SELECT EXISTS(SELECT 1 FROM subjects WHERE name = ?1)", params![subject], |row| row.get(0))
+                })?;
+                if exists {
+                    Ok(())
+                } else {
+                    Err(AppError::NotFound(format!("Subject '{}' not found", subject)))
+                }
+            }
+            ResetScope::Tag { tag } if tag.trim().is_empty() => {
+                Err(AppError::InvalidInput("A tag is needed to reset progress by tag".to_string()))
+            }
+            ResetScope::Tag { .. } => Ok(()),
+        }
+    }
+
+    fn row_to_progress_reset(row: &rusqlite::Row) -> rusqlite::Result<ProgressReset> {
+        let scope: String = row.get(2)?;
+        let snapshot: String = row.get(3)?;
+        let restored_at: Option<String> = row.get(5)?;
+
+        Ok(ProgressReset {
+            id: row.get(0)?,
+            profile_id: row.get(1)?,
+            scope: serde_json::from_str(&scope)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(2, "scope".to_string(), rusqlite::types::Type::Text))?,
+            snapshot: serde_json::from_str(&snapshot)
+                .map_err(|_| rusqlite::Error::InvalidColumnType(3, "snapshot".to_string(), rusqlite::types::Type::Text))?,
+            reset_at: parse_timestamp(&row.get::<_, String>(4)?, 4)?,
+            restored_at: restored_at.map(|value| parse_timestamp(&value, 5)).transpose()?,
+        })
+    }
     
+    /// Totals across every profile in the active household: questions answered all-time,
+    /// top subjects and the busiest day
+    pub fn get_household_statistics(&self) -> AppResult<HouseholdStatistics> {
+        Ok(self.db_manager.execute(|conn| {
+            let profile_count: u32 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM ({})", HOUSEHOLD_PROFILES_SQL),
+                [],
+                |row| row.get(0),
+            )?;
+
+            let mut stmt = conn.prepare(&format!(
+                "SELECT subject, SUM(questions_answered), SUM(correct_answers) FROM progress
+                 WHERE profile_id IN ({})
+                 GROUP BY subject HAVING SUM(questions_answered) > 0
+                 ORDER BY SUM(questions_answered) DESC, subject",
+                HOUSEHOLD_PROFILES_SQL
+            ))?;
+            let subjects = stmt.query_map([], |row| {
+                Ok(HouseholdSubjectTotal {
+                    subject: row.get(0)?,
+                    questions_answered: row.get(1)?,
+                    correct_answers: row.get(2)?,
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+
+            let busiest_day = conn.query_row(
+                &format!(
+                    "SELECT activity_date, SUM(questions_answered) FROM daily_activity
+                     WHERE profile_id IN ({})
+                     GROUP BY activity_date HAVING SUM(questions_answered) > 0
+                     ORDER BY SUM(questions_answered) DESC, activity_date DESC LIMIT 1",
+                    HOUSEHOLD_PROFILES_SQL
+                ),
+                [],
+                |row| {
+                    let date = NaiveDate::parse_from_str(&row.get::<_, String>(0)?, "%Y-%m-%d")
+                        .map_err(|_| rusqlite::Error::InvalidColumnType(0, "activity_date".to_string(), rusqlite::types::Type::Text))?;
+                    Ok(BusiestDay { date, questions_answered: row.get(1)? })
+                },
+            ).optional()?;
+
+            let total_questions_answered = subjects.iter().map(|subject| subject.questions_answered).sum();
+            let total_correct_answers = subjects.iter().map(|subject| subject.correct_answers).sum();
+            Ok(HouseholdStatistics {
+                profile_count,
+                total_questions_answered,
+                total_correct_answers,
+                accuracy_percentage: accuracy_percentage(total_correct_answers, total_questions_answered).round() as u8,
+                top_subjects: subjects.into_iter().take(TOP_SUBJECT_COUNT).collect(),
+                busiest_day,
+            })
+        })?)
+    }
+
     /// Get progress for a profile
-    pub fn get_progress(&self, profile_id: u32) -> AppResult<Progress> {
+    pub fn get_progress(&self, profile_id: ProfileId) -> AppResult<Progress> {
         // Validate that profile exists
         let _profile = self.get_profile_by_id(profile_id)?;
+        let streak = self.streaks.get_streak(profile_id, &Local::now())?;
         
         Ok(self.db_manager.execute(|conn| {
             // Get progress data
@@ -275,15 +549,8 @@ impl ProfileManager {
             )?;
             
             let achievements_iter = achievements_stmt.query_map(params![profile_id], |row| {
-                let category_str: String = row.get(4)?;
-                let category = match category_str.as_str() {
-                    "accuracy" => crate::models::AchievementCategory::Accuracy,
-                    "streak" => crate::models::AchievementCategory::Streak,
-                    "completion" => crate::models::AchievementCategory::Completion,
-                    "time" => crate::models::AchievementCategory::Time,
-                    "subject_mastery" => crate::models::AchievementCategory::SubjectMastery,
-                    _ => crate::models::AchievementCategory::Completion,
-                };
+                let category = crate::models::AchievementCategory::parse(&row.get::<_, String>(4)?)
+                    .unwrap_or(crate::models::AchievementCategory::Completion);
 
                 Ok(crate::models::Achievement {
                     id: row.get::<_, String>(0)?,
@@ -307,13 +574,13 @@ impl ProfileManager {
                 total_questions_answered: total_questions,
                 total_correct_answers: total_correct,
                 achievements,
-                streaks: Vec::new(), // TODO: Implement streaks
+                streaks: vec![streak.to_streak()],
             })
         })?)
     }
     
-    /// Update progress for a profile after quiz completion
-    pub fn update_progress(&self, profile_id: u32, quiz_result: QuizResult) -> AppResult<()> {
+    /// Update progress for a profile after quiz completion, returning any achievements newly earned
+    pub fn update_progress(&self, profile_id: ProfileId, quiz_result: QuizResult) -> AppResult<Vec<crate::models::Achievement>> {
         // Validate that profile exists
         let _profile = self.get_profile_by_id(profile_id)?;
         
@@ -343,114 +610,111 @@ impl ProfileManager {
             Ok(())
         })?;
 
+        // The quiz counts towards today's streak before achievements look at it
+        self.streaks.record_quiz_completed(profile_id, &Local::now())?;
+
         // Check and award achievements after updating progress
-        self.check_and_award_achievements(profile_id)?;
-        
-        Ok(())
+        self.achievements.check_after_quiz(profile_id, &quiz_result)
     }
 
-    /// Check for and award new achievements based on current progress
-    fn check_and_award_achievements(&self, profile_id: u32) -> AppResult<()> {
-        let progress = self.get_progress(profile_id)?;
-        let mut new_achievements = Vec::new();
-
-        // First Steps Achievement
-        if progress.total_questions_answered >= 1 && 
-           !progress.achievements.iter().any(|a| a.id == "first_steps") {
-            new_achievements.push(crate::models::Achievement {
-                id: "first_steps".to_string(),
-                name: "First Steps".to_string(),
-                description: "Answered your first question!".to_string(),
-                icon: "👶".to_string(),
-                earned_at: Utc::now(),
-                category: crate::models::AchievementCategory::Completion,
-            });
-        }
-
-        // Perfect Score Achievement
-        let overall_accuracy = if progress.total_questions_answered > 0 {
-            (progress.total_correct_answers as f64 / progress.total_questions_answered as f64) * 100.0
-        } else {
-            0.0
-        };
+    /// Days in a row the profile has completed a quiz, in the device's time zone
+    pub fn get_streak(&self, profile_id: ProfileId) -> AppResult<DailyStreak> {
+        self.get_profile_by_id(profile_id)?;
+        self.streaks.get_streak(profile_id, &Local::now())
+    }
 
-        if overall_accuracy == 100.0 && progress.total_questions_answered >= 5 &&
-           !progress.achievements.iter().any(|a| a.id == "perfect_score") {
-            new_achievements.push(crate::models::Achievement {
-                id: "perfect_score".to_string(),
-                name: "Perfect Score".to_string(),
-                description: "Achieved 100% accuracy with at least 5 questions!".to_string(),
-                icon: "💯".to_string(),
-                earned_at: Utc::now(),
-                category: crate::models::AchievementCategory::Accuracy,
-            });
-        }
+    /// Add XP earned in a quiz to the profile's total, which sets their level
+    pub fn award_xp(&self, profile_id: ProfileId, xp: u32, subject: &str) -> AppResult<XpGain> {
+        self.get_profile_by_id(profile_id)?;
+        self.experience.award(profile_id, xp, subject)
+    }
 
-        // Quick Learner Achievement
-        if progress.total_questions_answered >= 10 &&
-           !progress.achievements.iter().any(|a| a.id == "quick_learner") {
-            new_achievements.push(crate::models::Achievement {
-                id: "quick_learner".to_string(),
-                name: "Quick Learner".to_string(),
-                description: "Answered 10 questions!".to_string(),
-                icon: "⚡".to_string(),
-                earned_at: Utc::now(),
-                category: crate::models::AchievementCategory::Completion,
-            });
-        }
+    /// The profile's level, the XP still needed for the next one, and recent gains
+    pub fn get_level_info(&self, profile_id: ProfileId) -> AppResult<LevelInfo> {
+        self.get_profile_by_id(profile_id)?;
+        self.experience.get_level_info(profile_id)
+    }
 
-        // Subject Explorer Achievement
-        let subjects_with_progress = progress.subject_progress.values()
-            .filter(|sp| sp.questions_answered > 0)
-            .map(|sp| sp.subject.clone())
-            .collect::<std::collections::HashSet<_>>();
-
-        if subjects_with_progress.len() >= 3 &&
-           !progress.achievements.iter().any(|a| a.id == "subject_explorer") {
-            new_achievements.push(crate::models::Achievement {
-                id: "subject_explorer".to_string(),
-                name: "Subject Explorer".to_string(),
-                description: "Tried questions from 3 different subjects!".to_string(),
-                icon: "🗺️".to_string(),
-                earned_at: Utc::now(),
-                category: crate::models::AchievementCategory::SubjectMastery,
-            });
-        }
+    /// Give a profile freeze tokens that keep their streak going over missed days.
+    /// Returns how many they now have.
+    pub fn grant_streak_freezes(&self, profile_id: ProfileId, count: u32) -> AppResult<u32> {
+        self.get_profile_by_id(profile_id)?;
+        self.streaks.grant_freezes(profile_id, count)
+    }
 
-        // Save new achievements to database
-        for achievement in new_achievements {
-            self.save_achievement(profile_id, &achievement)?;
+    /// Save a finished head-to-head quiz in both players' records. Returns false, saving
+    /// nothing, if it was already saved.
+    pub fn record_head_to_head(&self, summary: &HeadToHeadSummary, subject: &str) -> AppResult<bool> {
+        if summary.players.len() != 2 {
+            return Err(AppError::InvalidInput("A head-to-head quiz has two players".to_string()));
         }
+        let played_at = Utc::now().to_rfc3339();
 
-        Ok(())
+        Ok(self.db_manager.transaction(|tx| {
+            let mut recorded = false;
+            for (player, opponent) in [(&summary.players[0], &summary.players[1]), (&summary.players[1], &summary.players[0])] {
+                let outcome = match summary.winner {
+                    Some(winner) if winner == player.profile_id => MatchOutcome::Won,
+                    Some(_) => MatchOutcome::Lost,
+                    None => MatchOutcome::Drew,
+                };
+                recorded |= tx.execute(
+                    "INSERT OR IGNORE INTO head_to_head_results
+                     (session_id, profile_id, opponent_id, subject, correct_answers, opponent_correct_answers,
+                      total_questions, final_score, outcome, played_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        summary.session_id,
+                        player.profile_id,
+                        opponent.profile_id,
+                        subject,
+                        player.score.correct_answers,
+                        opponent.score.correct_answers,
+                        player.score.total_questions,
+                        player.score.final_score,
+                        outcome.as_str(),
+                        played_at,
+                    ],
+                )? > 0;
+            }
+            Ok(recorded)
+        })?)
     }
 
-    /// Save an achievement to the database
-    fn save_achievement(&self, profile_id: u32, achievement: &crate::models::Achievement) -> AppResult<()> {
-        self.db_manager.execute(|conn| {
-            conn.execute(
-                "INSERT OR IGNORE INTO achievements (profile_id, achievement_id, name, description, icon, category, earned_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                params![
-                    profile_id,
-                    achievement.id,
-                    achievement.name,
-                    achievement.description,
-                    achievement.icon,
-                    format!("{:?}", achievement.category).to_lowercase(),
-                    achievement.earned_at.to_rfc3339()
-                ],
+    /// A profile's head-to-head results, newest first
+    pub fn get_head_to_head_results(&self, profile_id: ProfileId, limit: u32) -> AppResult<Vec<HeadToHeadResult>> {
+        self.get_profile_by_id(profile_id)?;
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT session_id, opponent_id, subject, correct_answers, opponent_correct_answers,
+                        total_questions, final_score, outcome, played_at
+                 FROM head_to_head_results WHERE profile_id = ?1
+                 ORDER BY played_at DESC, session_id DESC LIMIT ?2"
             )?;
-            Ok(())
-        })?;
-        Ok(())
+            let rows = stmt.query_map(params![profile_id, limit], |row| {
+                let outcome: String = row.get(7)?;
+                Ok(HeadToHeadResult {
+                    session_id: row.get(0)?,
+                    opponent_id: row.get(1)?,
+                    subject: row.get(2)?,
+                    correct_answers: row.get(3)?,
+                    opponent_correct_answers: row.get(4)?,
+                    total_questions: row.get(5)?,
+                    final_score: row.get(6)?,
+                    outcome: MatchOutcome::parse(&outcome)
+                        .ok_or_else(|| rusqlite::Error::InvalidColumnType(7, "outcome".to_string(), rusqlite::types::Type::Text))?,
+                    played_at: parse_timestamp(&row.get::<_, String>(8)?, 8)?,
+                })
+            })?;
+            rows.collect()
+        })?)
     }
-    
+
     /// Check if a profile name already exists
     fn profile_name_exists(&self, name: &str) -> AppResult<bool> {
         Ok(self.db_manager.execute(|conn| {
             let count: i32 = conn.query_row(
-                "SELECT COUNT(*) FROM profiles WHERE LOWER(name) = LOWER(?1)",
+                &format!("SELECT COUNT(*) FROM profiles WHERE LOWER(name) = LOWER(?1) AND id IN ({})", HOUSEHOLD_PROFILES_SQL),
                 params![name],
                 |row| row.get(0)
             )?;
@@ -459,10 +723,13 @@ impl ProfileManager {
     }
     
     /// Check if a profile name exists excluding a specific profile ID
-    fn profile_name_exists_excluding(&self, name: &str, exclude_id: u32) -> AppResult<bool> {
+    fn profile_name_exists_excluding(&self, name: &str, exclude_id: ProfileId) -> AppResult<bool> {
         Ok(self.db_manager.execute(|conn| {
             let count: i32 = conn.query_row(
-                "SELECT COUNT(*) FROM profiles WHERE LOWER(name) = LOWER(?1) AND id != ?2",
+                &format!(
+                    "SELECT COUNT(*) FROM profiles WHERE LOWER(name) = LOWER(?1) AND id != ?2 AND id IN ({})",
+                    HOUSEHOLD_PROFILES_SQL
+                ),
                 params![name, exclude_id],
                 |row| row.get(0)
             )?;
@@ -471,10 +738,10 @@ impl ProfileManager {
     }
     
     /// Initialize progress tracking for a new profile
-    fn initialize_profile_progress(&self, profile_id: u32) -> AppResult<()> {
+    fn initialize_profile_progress(&self, profile_id: ProfileId) -> AppResult<()> {
         // Initialize progress entries for all subjects and key stages
         let subjects = vec!["Mathematics", "Geography", "English", "Science", "General Knowledge"];
-        let key_stages = vec!["KS1", "KS2"];
+        let key_stages = KeyStage::ALL;
         
         Ok(self.db_manager.execute(|conn| {
             for subject in &subjects {
@@ -485,7 +752,7 @@ impl ProfileManager {
                         params![
                             profile_id,
                             subject,
-                            key_stage,
+                            key_stage.as_str(),
                             Utc::now().to_rfc3339()
                         ],
                     )?;
@@ -496,6 +763,100 @@ impl ProfileManager {
     }
 }
 
+/// Which progress a reset clears
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "scope", rename_all = "snake_case")]
+pub enum ResetScope {
+    #[default]
+    Everything,
+    /// One subject's counters and question history, by subject name (e.g. `mathematics`)
+    Subject { subject: String },
+    /// History for questions with a tag. Counters are per subject, so they're left alone.
+    Tag { tag: String },
+}
+
+/// A progress row as it was before a reset
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProgressRecord {
+    pub subject: String,
+    pub key_stage: String,
+    pub questions_answered: u32,
+    pub correct_answers: u32,
+    pub total_time_spent: u32,
+    pub last_activity: String,
+}
+
+/// A boss question attempt as it was before a reset
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BossAttemptRecord {
+    pub question_id: QuestionId,
+    pub difficulty_level: u8,
+    pub is_correct: bool,
+    pub attempted_at: String,
+}
+
+/// Everything a reset cleared, kept so it can be undone
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ProgressSnapshot {
+    pub progress: Vec<ProgressRecord>,
+    pub boss_attempts: Vec<BossAttemptRecord>,
+}
+
+/// Audit log entry for a progress reset
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProgressReset {
+    pub id: u32,
+    pub profile_id: ProfileId,
+    pub scope: ResetScope,
+    pub snapshot: ProgressSnapshot,
+    pub reset_at: DateTime<Utc>,
+    /// Set once the reset has been undone
+    pub restored_at: Option<DateTime<Utc>>,
+}
+
+/// How a head-to-head quiz went for one player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchOutcome {
+    Won,
+    Lost,
+    Drew,
+}
+
+impl MatchOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchOutcome::Won => "won",
+            MatchOutcome::Lost => "lost",
+            MatchOutcome::Drew => "drew",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "won" => Some(MatchOutcome::Won),
+            "lost" => Some(MatchOutcome::Lost),
+            "drew" => Some(MatchOutcome::Drew),
+            _ => None,
+        }
+    }
+}
+
+/// One head-to-head quiz in a profile's record
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HeadToHeadResult {
+    pub session_id: SessionId,
+    /// None once the opponent's profile has been deleted
+    pub opponent_id: Option<ProfileId>,
+    pub subject: String,
+    pub correct_answers: u32,
+    pub opponent_correct_answers: u32,
+    pub total_questions: u32,
+    pub final_score: u32,
+    pub outcome: MatchOutcome,
+    pub played_at: DateTime<Utc>,
+}
+
 /// Request structure for updating profiles
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ProfileUpdateRequest {
@@ -512,6 +873,20 @@ pub struct QuizResult {
     pub questions_answered: u32,
     pub correct_answers: u32,
     pub time_spent_seconds: u32,
+    /// The quiz session the result came from, if any; sessions flagged for rushed answers
+    /// aren't counted towards progress
+    #[serde(default)]
+    pub session_id: Option<SessionId>,
+    /// A practice quiz, which only reports the questions got right first time, so it can't
+    /// be a perfect quiz
+    #[serde(default)]
+    pub practice: bool,
+}
+
+fn parse_timestamp(value: &str, index: usize) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| rusqlite::Error::InvalidColumnType(index, "timestamp".to_string(), rusqlite::types::Type::Text))
 }
 
 #[cfg(test)]
@@ -527,7 +902,7 @@ mod tests {
         let db_service = DatabaseService::new(&db_path).unwrap();
         db_service.initialize().unwrap();
         
-        let security_service = SecurityService::new().unwrap();
+        let security_service = Arc::new(SecurityService::new().unwrap());
         let profile_manager = ProfileManager::new(db_service.manager(), security_service);
         
         (profile_manager, temp_dir)
@@ -654,6 +1029,8 @@ mod tests {
             questions_answered: 10,
             correct_answers: 8,
             time_spent_seconds: 300,
+            session_id: None,
+            practice: false,
         };
         
         profile_manager.update_progress(profile_id, quiz_result).unwrap();
@@ -669,4 +1046,94 @@ mod tests {
         assert_eq!(math_progress.correct_answers, 8);
         assert_eq!(math_progress.accuracy_percentage, 80);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_scoped_reset_keeps_a_snapshot_that_can_be_restored() {
+        let (profile_manager, _temp_dir) = create_test_profile_manager();
+        let profile_id = profile_manager.create_profile(CreateProfileRequest {
+            name: "Test Child".to_string(),
+            avatar: "avatar1".to_string(),
+            theme_preference: None,
+        }).unwrap().id.unwrap();
+
+        for (subject, answered) in [("Mathematics", 10), ("English", 6)] {
+            profile_manager.update_progress(profile_id, QuizResult {
+                subject: subject.to_string(),
+                key_stage: "KS1".to_string(),
+                questions_answered: answered,
+                correct_answers: answered / 2,
+                time_spent_seconds: 120,
+                session_id: None,
+                practice: false,
+            }).unwrap();
+        }
+
+        let unknown = ResetScope::Subject { subject: "history".to_string() };
+        assert!(profile_manager.reset_progress(profile_id, unknown).is_err());
+
+        let scope = ResetScope::Subject { subject: "mathematics".to_string() };
+        let reset = profile_manager.reset_progress(profile_id, scope.clone()).unwrap();
+        assert_eq!(reset.scope, scope);
+        // One row per key stage, only KS1 has any answers
+        assert_eq!(reset.snapshot.progress.len(), KeyStage::ALL.len());
+        assert_eq!(reset.snapshot.progress.iter().map(|record| record.questions_answered).sum::<u32>(), 10);
+
+        let progress = profile_manager.get_progress(profile_id).unwrap();
+        assert_eq!(progress.total_questions_answered, 6);
+        assert_eq!(profile_manager.get_progress_resets(profile_id).unwrap().len(), 1);
+
+        let restored = profile_manager.restore_progress_reset(reset.id).unwrap();
+        assert!(restored.restored_at.is_some());
+        assert_eq!(profile_manager.get_progress(profile_id).unwrap().total_questions_answered, 16);
+        assert!(profile_manager.restore_progress_reset(reset.id).is_err());
+
+        profile_manager.reset_progress(profile_id, ResetScope::Everything).unwrap();
+        assert_eq!(profile_manager.get_progress(profile_id).unwrap().total_questions_answered, 0);
+    }
+
+    #[test]
+    fn test_household_statistics_total_every_profile() {
+        let (profile_manager, _temp_dir) = create_test_profile_manager();
+        let empty = profile_manager.get_household_statistics().unwrap();
+        assert_eq!(empty.total_questions_answered, 0);
+        assert!(empty.top_subjects.is_empty() && empty.busiest_day.is_none());
+
+        for (name, results) in [
+            ("Ada", vec![("Mathematics", 10, 8), ("English", 4, 2)]),
+            ("Sam", vec![("English", 12, 6), ("Science", 2, 2), ("Geography", 1, 0)]),
+        ] {
+            let profile_id = profile_manager.create_profile(CreateProfileRequest {
+                name: name.to_string(),
+                avatar: "avatar1".to_string(),
+                theme_preference: None,
+            }).unwrap().id.unwrap();
+            for (subject, answered, correct) in results {
+                profile_manager.update_progress(profile_id, QuizResult {
+                    subject: subject.to_string(),
+                    key_stage: "KS2".to_string(),
+                    questions_answered: answered,
+                    correct_answers: correct,
+                    time_spent_seconds: 60,
+                    session_id: None,
+                    practice: false,
+                }).unwrap();
+            }
+            profile_manager.db_manager.execute(|conn| {
+                conn.execute(
+                    "INSERT INTO daily_activity (profile_id, activity_date, questions_answered) VALUES (?1, '2024-03-09', ?2)",
+                    params![profile_id, 15],
+                )
+            }).unwrap();
+        }
+
+        let stats = profile_manager.get_household_statistics().unwrap();
+        assert_eq!(stats.profile_count, 2);
+        assert_eq!((stats.total_questions_answered, stats.total_correct_answers, stats.accuracy_percentage), (29, 18, 62));
+        let top: Vec<(&str, u32)> = stats.top_subjects.iter()
+            .map(|subject| (subject.subject.as_str(), subject.questions_answered))
+            .collect();
+        assert_eq!(top, vec![("English", 16), ("Mathematics", 10), ("Science", 2)]);
+        let busiest = stats.busiest_day.unwrap();
+        assert_eq!((busiest.date, busiest.questions_answered), (NaiveDate::from_ymd_opt(2024, 3, 9).unwrap(), 30));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::models::{Question, KeyStage};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use serde::{Deserialize, Serialize};
+
+/// Cache key: subject name and key stage (`None` caches every key stage together)
+type BucketKey = (String, Option<KeyStage>);
+
+/// Questions for one subject/key stage, bucketed by difficulty level
+type DifficultyBuckets = BTreeMap<u8, Vec<Question>>;
+
+/// Cache statistics reported in diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+    pub cached_subjects: usize,
+    pub cached_buckets: usize,
+    pub cached_questions: usize,
+}
+
+/// In-memory cache of questions grouped by subject, key stage and difficulty
+pub struct QuestionCache {
+    entries: Mutex<HashMap<BucketKey, DifficultyBuckets>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+impl QuestionCache {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    /// Get cached questions within the difficulty range, or `None` on a cache miss
+    pub fn get(
+        &self,
+        subject_name: &str,
+        key_stage: Option<KeyStage>,
+        difficulty_range: Option<(u8, u8)>,
+    ) -> Option<Vec<Question>> {
+        let entries = match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(_) => return None,
+        };
+
+        match entries.get(&(subject_name.to_string(), key_stage)) {
+            Some(buckets) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                let (min_diff, max_diff) = difficulty_range.unwrap_or((u8::MIN, u8::MAX));
+                if min_diff > max_diff {
+                    return Some(Vec::new());
+                }
+
+                Some(buckets.range(min_diff..=max_diff)
+                    .flat_map(|(_, questions)| questions.iter().cloned())
+                    .collect())
+            },
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Store every question for a subject and key stage
+    pub fn insert(&self, subject_name: &str, key_stage: Option<KeyStage>, questions: Vec<Question>) {
+        let mut buckets = DifficultyBuckets::new();
+        for question in questions {
+            buckets.entry(question.difficulty_level).or_default().push(question);
+        }
+
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert((subject_name.to_string(), key_stage), buckets);
+        }
+    }
+
+    /// Drop all cached questions. Called whenever question content changes.
+    pub fn invalidate(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+        self.invalidations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current cache statistics
+    pub fn stats(&self) -> QuestionCacheStats {
+        let (cached_subjects, cached_buckets, cached_questions) = match self.entries.lock() {
+            Ok(entries) => (
+                entries.len(),
+                entries.values().map(|buckets| buckets.len()).sum(),
+                entries.values()
+                    .flat_map(|buckets| buckets.values())
+                    .map(|questions| questions.len())
+                    .sum(),
+            ),
+            Err(_) => (0, 0, 0),
+        };
+
+        QuestionCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            cached_subjects,
+            cached_buckets,
+            cached_questions,
+        }
+    }
+}
+
+impl Default for QuestionCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,296 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{KeyStage, Question, ProfileId, QuestionId};
+use crate::services::ContentManager;
+use std::collections::HashSet;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A question isn't picked again within this many days while others are available
+const REPEAT_AFTER_DAYS: i64 = 60;
+
+/// Today's question for a key stage, with the profile's answer if they have had a go
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyQuestion {
+    pub date: NaiveDate,
+    pub key_stage: KeyStage,
+    pub question: Question,
+    pub completion: Option<DailyQuestionCompletion>,
+    /// Consecutive days (ending today or yesterday) the profile has answered the question of the day
+    pub streak_days: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyQuestionCompletion {
+    pub profile_id: ProfileId,
+    pub question_id: QuestionId,
+    pub correct: bool,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Picks one question per key stage per day, the same for every profile and every caller
+pub struct QuestionOfTheDayService {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+}
+
+impl QuestionOfTheDayService {
+    pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>) -> Self {
+        Self {
+            db_manager,
+            content_manager,
+        }
+    }
+
+    /// The question of the day, or `None` when no content is installed for the key stage
+    pub fn get_question_of_the_day(&self, key_stage: KeyStage, date: NaiveDate) -> AppResult<Option<Question>> {
+        match self.select_question_id(key_stage, date)? {
+            Some(question_id) => Ok(Some(self.content_manager.get_question_by_id(question_id)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The question of the day together with a profile's completion state
+    pub fn get_for_profile(&self, profile_id: ProfileId, key_stage: KeyStage, date: NaiveDate) -> AppResult<Option<DailyQuestion>> {
+        let question = match self.get_question_of_the_day(key_stage, date)? {
+            Some(question) => question,
+            None => return Ok(None),
+        };
+
+        Ok(Some(DailyQuestion {
+            date,
+            key_stage,
+            question,
+            completion: self.get_completion(profile_id, key_stage, date)?,
+            streak_days: self.get_streak(profile_id, key_stage, date)?,
+        }))
+    }
+
+    /// Record a profile's answer to the day's question. Only the first answer counts.
+    pub fn record_completion(
+        &self,
+        profile_id: ProfileId,
+        key_stage: KeyStage,
+        date: NaiveDate,
+        question_id: QuestionId,
+        correct: bool,
+    ) -> AppResult<DailyQuestionCompletion> {
+        if self.select_question_id(key_stage, date)? != Some(question_id) {
+            return Err(AppError::InvalidInput(format!(
+                "Question {} is not the question of the day for {}", question_id, date
+            )));
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO question_of_the_day_completions
+                 (profile_id, qotd_date, key_stage, question_id, correct, completed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    profile_id,
+                    date.format(DATE_FORMAT).to_string(),
+                    key_stage.as_str(),
+                    question_id,
+                    correct,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })?;
+
+        self.get_completion(profile_id, key_stage, date)?
+            .ok_or_else(|| AppError::Internal("Question of the day completion was not saved".to_string()))
+    }
+
+    fn get_completion(&self, profile_id: ProfileId, key_stage: KeyStage, date: NaiveDate) -> AppResult<Option<DailyQuestionCompletion>> {
+        let row = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT question_id, correct, completed_at FROM question_of_the_day_completions
+                 WHERE profile_id = ?1 AND qotd_date = ?2 AND key_stage = ?3",
+                params![profile_id, date.format(DATE_FORMAT).to_string(), key_stage.as_str()],
+                |row| Ok((row.get::<_, QuestionId>(0)?, row.get::<_, bool>(1)?, row.get::<_, String>(2)?)),
+            ).optional()
+        })?;
+
+        Ok(row.map(|(question_id, correct, completed_at)| DailyQuestionCompletion {
+            profile_id,
+            question_id,
+            correct,
+            completed_at: DateTime::parse_from_rfc3339(&completed_at)
+                .map(|completed_at| completed_at.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now()),
+        }))
+    }
+
+    /// Count back from today, or from yesterday if today's question hasn't been answered yet
+    fn get_streak(&self, profile_id: ProfileId, key_stage: KeyStage, today: NaiveDate) -> AppResult<u32> {
+        let dates: HashSet<String> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT qotd_date FROM question_of_the_day_completions WHERE profile_id = ?1 AND key_stage = ?2"
+            )?;
+            let rows = stmt.query_map(params![profile_id, key_stage.as_str()], |row| row.get::<_, String>(0))?;
+            rows.collect()
+        })?;
+
+        let completed = |date: NaiveDate| dates.contains(&date.format(DATE_FORMAT).to_string());
+        let mut day = if completed(today) { today } else { today - Duration::days(1) };
+        let mut streak = 0;
+        while completed(day) {
+            streak += 1;
+            day -= Duration::days(1);
+        }
+
+        Ok(streak)
+    }
+
+    /// Reuse the day's stored pick, otherwise choose one from a hash of the date and key stage,
+    /// skipping recently used questions, and store it so later content changes don't move it
+    fn select_question_id(&self, key_stage: KeyStage, date: NaiveDate) -> AppResult<Option<QuestionId>> {
+        let key_stage = key_stage.as_str();
+        let day = date.format(DATE_FORMAT).to_string();
+
+        Ok(self.db_manager.transaction(|tx| {
+            let existing = tx.query_row(
+                "SELECT q.question_id FROM question_of_the_day q
+                 JOIN questions ON questions.id = q.question_id
+                 WHERE q.qotd_date = ?1 AND q.key_stage = ?2",
+                params![day, key_stage],
+                |row| row.get::<_, QuestionId>(0),
+            ).optional()?;
+            if existing.is_some() {
+                return Ok(existing);
+            }
+
+            let mut stmt = tx.prepare(
+                "SELECT id FROM questions WHERE key_stage = ?1
+                 AND id NOT IN (SELECT question_id FROM retired_questions) ORDER BY id"
+            )?;
+            let question_ids = stmt.query_map(params![key_stage], |row| row.get::<_, QuestionId>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            if question_ids.is_empty() {
+                return Ok(None);
+            }
+
+            let since = (date - Duration::days(REPEAT_AFTER_DAYS)).format(DATE_FORMAT).to_string();
+            let mut stmt = tx.prepare(
+                "SELECT question_id FROM question_of_the_day WHERE key_stage = ?1 AND qotd_date >= ?2 AND qotd_date < ?3"
+            )?;
+            let recent = stmt.query_map(params![key_stage, since, day], |row| row.get::<_, QuestionId>(0))?
+                .collect::<Result<HashSet<_>, _>>()?;
+
+            let fresh: Vec<QuestionId> = question_ids.iter().copied().filter(|id| !recent.contains(id)).collect();
+            let candidates = if fresh.is_empty() { &question_ids } else { &fresh };
+            let question_id = candidates[(daily_seed(&day, key_stage) % candidates.len() as u64) as usize];
+
+            tx.execute(
+                "INSERT OR REPLACE INTO question_of_the_day (qotd_date, key_stage, question_id) VALUES (?1, ?2, ?3)",
+                params![day, key_stage, question_id],
+            )?;
+            Ok(Some(question_id))
+        })?)
+    }
+}
+
+/// Stable across platforms and releases, unlike `std`'s hasher
+fn daily_seed(day: &str, key_stage: &str) -> u64 {
+    let digest = Sha256::digest(format!("{}:{}", day, key_stage).as_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::CreateProfileRequest;
+    use crate::services::{ProfileManager, SecurityService};
+    use tempfile::tempdir;
+
+    fn create_test_service() -> (QuestionOfTheDayService, Arc<DatabaseManager>, ProfileId, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            security_service.clone(),
+            temp_dir.path().join("content"),
+        ));
+        let profile_id = ProfileManager::new(db_service.manager(), security_service)
+            .create_profile(CreateProfileRequest {
+                name: "Ada".to_string(),
+                avatar: "avatar1".to_string(),
+                theme_preference: None,
+            }).unwrap().id.unwrap();
+
+        db_service.manager().execute(|conn| {
+            for n in 1..=5 {
+                conn.execute(
+                    "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
+                     VALUES ((SELECT id FROM subjects WHERE name = 'mathematics'), 'KS2', 'fill_blank', ?1, ?2, 1, '[]', ?3)",
+                    params![
+                        format!(r#"{{"text":"What is {} + {}?"}}"#, n, n),
+                        format!("\"{}\"", n * 2),
+                        Utc::now().to_rfc3339(),
+                    ],
+                )?;
+            }
+            Ok(())
+        }).unwrap();
+
+        let service = QuestionOfTheDayService::new(db_service.manager(), content_manager);
+        (service, db_service.manager(), profile_id, temp_dir)
+    }
+
+    #[test]
+    fn test_selection_is_stable_and_avoids_repeats() {
+        let (service, _db, _profile_id, _temp_dir) = create_test_service();
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        assert!(service.get_question_of_the_day(KeyStage::KS1, day).unwrap().is_none());
+
+        let first = service.get_question_of_the_day(KeyStage::KS2, day).unwrap().unwrap();
+        let again = service.get_question_of_the_day(KeyStage::KS2, day).unwrap().unwrap();
+        assert_eq!(first.id, again.id);
+
+        // Five questions last five days without a repeat
+        let picks: HashSet<_> = (0..5)
+            .map(|offset| service.get_question_of_the_day(KeyStage::KS2, day + Duration::days(offset)).unwrap().unwrap().id)
+            .collect();
+        assert_eq!(picks.len(), 5);
+    }
+
+    #[test]
+    fn test_completion_tracking_and_streak() {
+        let (service, _db, profile_id, _temp_dir) = create_test_service();
+        let yesterday = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let today = yesterday + Duration::days(1);
+
+        let question_id = service.get_question_of_the_day(KeyStage::KS2, yesterday).unwrap().unwrap().id.unwrap();
+        service.record_completion(profile_id, KeyStage::KS2, yesterday, question_id, true).unwrap();
+
+        let daily = service.get_for_profile(profile_id, KeyStage::KS2, today).unwrap().unwrap();
+        assert!(daily.completion.is_none());
+        assert_eq!(daily.streak_days, 1);
+
+        let todays_id = daily.question.id.unwrap();
+        assert!(service.record_completion(profile_id, KeyStage::KS2, today, QuestionId(todays_id.0 + 100), true).is_err());
+
+        // Only the first answer counts
+        service.record_completion(profile_id, KeyStage::KS2, today, todays_id, false).unwrap();
+        let completion = service.record_completion(profile_id, KeyStage::KS2, today, todays_id, true).unwrap();
+        assert!(!completion.correct);
+
+        let daily = service.get_for_profile(profile_id, KeyStage::KS2, today).unwrap().unwrap();
+        assert_eq!(daily.streak_days, 2);
+    }
+}
@@ -0,0 +1,404 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::{row_exists, DatabaseManager};
+use crate::models::{EntityId, KeyStage, QuestionContent, ProfileId, QuestionId};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+
+/// Answers needed before a question's accuracy counts as too easy or too hard
+const MIN_ANSWERS_FOR_ACCURACY: u32 = 10;
+const TOO_EASY_ACCURACY: f64 = 0.95;
+const TOO_HARD_ACCURACY: f64 = 0.2;
+/// Share of words two questions must have in common to count as near-duplicates
+const NEAR_DUPLICATE_SIMILARITY: f64 = 0.9;
+
+const TOO_EASY_PENALTY: u32 = 20;
+const TOO_HARD_PENALTY: u32 = 25;
+const PENALTY_PER_FLAG: u32 = 10;
+const MAX_FLAG_PENALTY: u32 = 40;
+const NEAR_DUPLICATE_PENALTY: u32 = 20;
+const MISSING_EXPLANATION_PENALTY: u32 = 10;
+
+/// Something about a question that needs a maintainer's attention
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QualityIssue {
+    /// Almost everyone gets it right
+    TooEasy { accuracy: f64 },
+    /// Almost everyone gets it wrong
+    TooHard { accuracy: f64 },
+    /// Reported by children or parents, or broke during a quiz
+    Flagged { count: u32 },
+    /// Asks nearly the same thing as another question
+    NearDuplicate { question_id: QuestionId },
+    /// There's no explanation to show after answering
+    MissingExplanation,
+}
+
+impl QualityIssue {
+    fn penalty(&self) -> u32 {
+        match self {
+            QualityIssue::TooEasy { .. } => TOO_EASY_PENALTY,
+            QualityIssue::TooHard { .. } => TOO_HARD_PENALTY,
+            QualityIssue::Flagged { count } => (count * PENALTY_PER_FLAG).min(MAX_FLAG_PENALTY),
+            QualityIssue::NearDuplicate { .. } => NEAR_DUPLICATE_PENALTY,
+            QualityIssue::MissingExplanation => MISSING_EXPLANATION_PENALTY,
+        }
+    }
+}
+
+/// How a question is holding up, from 100 (nothing wrong) down to 0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionQuality {
+    pub question_id: QuestionId,
+    pub text: String,
+    pub subject: String,
+    pub key_stage: KeyStage,
+    pub score: u32,
+    pub issues: Vec<QualityIssue>,
+    pub times_answered: u32,
+    /// Share of answers that were right, once anyone has answered it
+    pub accuracy: Option<f64>,
+}
+
+/// How children get on with one wording of a question
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhrasingStats {
+    /// 0 is the question's own text, then its alternatives in order
+    pub phrasing: usize,
+    pub text: String,
+    pub times_shown: u32,
+    pub times_answered: u32,
+    pub times_correct: u32,
+    /// Share of answers that were right, once anyone has answered it
+    pub accuracy: Option<f64>,
+}
+
+struct QuestionSummary {
+    id: QuestionId,
+    subject: String,
+    key_stage: KeyStage,
+    content: QuestionContent,
+    has_explanation: bool,
+}
+
+#[derive(Default)]
+struct QuestionStats {
+    times_answered: u32,
+    times_correct: u32,
+    flags: u32,
+}
+
+/// Scores questions on their answer history, flags and content, for content maintenance
+pub struct QuestionQualityService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl QuestionQualityService {
+    /// Create a new question quality service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Report a question as wrong or confusing
+    pub fn flag_question(&self, question_id: QuestionId, profile_id: Option<ProfileId>, reason: Option<&str>) -> AppResult<()> {
+        let reason = reason.map(str::trim).filter(|reason| !reason.is_empty());
+        let flagged = self.db_manager.execute(|conn| {
+            let exists = row_exists(conn, question_id)?;
+            if exists {
+                conn.execute(
+                    "INSERT INTO question_flags (question_id, profile_id, reason, flagged_at) VALUES (?1, ?2, ?3, ?4)",
+                    params![question_id, profile_id, reason, Utc::now().to_rfc3339()],
+                )?;
+            }
+            Ok(exists)
+        })?;
+
+        if !flagged {
+            return Err(question_id.not_found());
+        }
+        Ok(())
+    }
+
+    /// Quality of a single question
+    pub fn get_question_quality(&self, question_id: QuestionId) -> AppResult<QuestionQuality> {
+        self.score_questions(None)?
+            .into_iter()
+            .find(|quality| quality.question_id == question_id)
+            .ok_or_else(|| AppError::NotFound(format!("Question {} not found", question_id)))
+    }
+
+    /// Questions with at least one issue, worst first, optionally for one subject
+    pub fn get_needs_attention(&self, subject: Option<&str>, limit: usize) -> AppResult<Vec<QuestionQuality>> {
+        let mut needing_attention: Vec<QuestionQuality> = self.score_questions(subject)?
+            .into_iter()
+            .filter(|quality| !quality.issues.is_empty())
+            .collect();
+        needing_attention.sort_by(|a, b| a.score.cmp(&b.score).then(a.question_id.cmp(&b.question_id)));
+        needing_attention.truncate(limit);
+        Ok(needing_attention)
+    }
+
+    /// How often each phrasing of a question has been shown and answered correctly, from
+    /// the quiz timelines still kept
+    pub fn get_phrasing_stats(&self, question_id: QuestionId) -> AppResult<Vec<PhrasingStats>> {
+        let (content, counts) = self.db_manager.execute(|conn| {
+            let content: Option<String> = conn.query_row(
+                "SELECT content FROM questions WHERE id = ?1",
+                params![question_id],
+                |row| row.get(0),
+            ).optional()?;
+            let mut stmt = conn.prepare(
+                "SELECT phrasing, COUNT(*), SUM(answered), SUM(correct) FROM (
+                    SELECT COALESCE(json_extract(s.details, '$.phrasing'), 0) AS phrasing,
+                        EXISTS (SELECT 1 FROM session_events a
+                                WHERE a.session_id = s.session_id AND a.question_index = s.question_index
+                                  AND a.kind = 'answered') AS answered,
+                        EXISTS (SELECT 1 FROM session_events a
+                                WHERE a.session_id = s.session_id AND a.question_index = s.question_index
+                                  AND a.kind = 'answered' AND json_extract(a.details, '$.is_correct') = 1) AS correct
+                    FROM session_events s
+                    WHERE s.question_id = ?1 AND s.kind = 'question_shown'
+                 ) GROUP BY phrasing",
+            )?;
+            let counts = stmt.query_map(params![question_id], |row| {
+                Ok((row.get::<_, u32>(0)? as usize, (row.get::<_, u32>(1)?, row.get::<_, u32>(2)?, row.get::<_, u32>(3)?)))
+            })?.collect::<Result<HashMap<_, _>, _>>()?;
+            Ok((content, counts))
+        })?;
+
+        let content: QuestionContent = match content {
+            Some(content) => serde_json::from_str(&content)?,
+            None => return Err(question_id.not_found()),
+        };
+        Ok(content.phrasings()?
+            .into_iter()
+            .enumerate()
+            .map(|(phrasing, text)| {
+                let (times_shown, times_answered, times_correct) = counts.get(&phrasing).copied().unwrap_or_default();
+                PhrasingStats {
+                    phrasing,
+                    text,
+                    times_shown,
+                    times_answered,
+                    times_correct,
+                    accuracy: if times_answered > 0 {
+                        Some(times_correct as f64 / times_answered as f64)
+                    } else {
+                        None
+                    },
+                }
+            })
+            .collect())
+    }
+
+    fn score_questions(&self, subject: Option<&str>) -> AppResult<Vec<QuestionQuality>> {
+        let (questions, mut stats) = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT q.id, s.name, q.key_stage, q.content,
+                    EXISTS (SELECT 1 FROM question_explanations e WHERE e.question_id = q.id)
+                 FROM questions q
+                 JOIN subjects s ON s.id = q.subject_id
+                 WHERE q.id NOT IN (SELECT question_id FROM retired_questions)
+                   AND (?1 IS NULL OR s.name = ?1)
+                 ORDER BY q.id",
+            )?;
+            let questions = stmt.query_map(params![subject], row_to_summary)?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut stats: HashMap<QuestionId, QuestionStats> = HashMap::new();
+            let mut stmt = conn.prepare(
+                "SELECT question_id, SUM(times_answered), SUM(times_correct) FROM answered_questions GROUP BY question_id",
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, QuestionId>(0)?, row.get::<_, u32>(1)?, row.get::<_, u32>(2)?)))?;
+            for row in rows {
+                let (question_id, times_answered, times_correct) = row?;
+                let entry = stats.entry(question_id).or_default();
+                entry.times_answered = times_answered;
+                entry.times_correct = times_correct;
+            }
+
+            // Questions that broke mid-quiz count the same as ones reported by hand
+            let mut stmt = conn.prepare(
+                "SELECT question_id, COUNT(*) FROM (
+                    SELECT question_id FROM question_flags
+                    UNION ALL
+                    SELECT question_id FROM quiz_incidents WHERE question_id IS NOT NULL
+                 ) GROUP BY question_id",
+            )?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, QuestionId>(0)?, row.get::<_, u32>(1)?)))?;
+            for row in rows {
+                let (question_id, flags) = row?;
+                stats.entry(question_id).or_default().flags = flags;
+            }
+            Ok((questions, stats))
+        })?;
+
+        let duplicates = find_near_duplicates(&questions);
+        Ok(questions.into_iter()
+            .map(|question| {
+                let stats = stats.remove(&question.id).unwrap_or_default();
+                let accuracy = if stats.times_answered > 0 {
+                    Some(stats.times_correct as f64 / stats.times_answered as f64)
+                } else {
+                    None
+                };
+
+                let mut issues = Vec::new();
+                if let Some(accuracy) = accuracy.filter(|_| stats.times_answered >= MIN_ANSWERS_FOR_ACCURACY) {
+                    if accuracy > TOO_EASY_ACCURACY {
+                        issues.push(QualityIssue::TooEasy { accuracy });
+                    } else if accuracy < TOO_HARD_ACCURACY {
+                        issues.push(QualityIssue::TooHard { accuracy });
+                    }
+                }
+                if stats.flags > 0 {
+                    issues.push(QualityIssue::Flagged { count: stats.flags });
+                }
+                if let Some(other) = duplicates.get(&question.id) {
+                    issues.push(QualityIssue::NearDuplicate { question_id: *other });
+                }
+                if !question.has_explanation {
+                    issues.push(QualityIssue::MissingExplanation);
+                }
+
+                let penalty: u32 = issues.iter().map(QualityIssue::penalty).sum();
+                QuestionQuality {
+                    question_id: question.id,
+                    text: question.content.text,
+                    subject: question.subject,
+                    key_stage: question.key_stage,
+                    score: 100u32.saturating_sub(penalty),
+                    issues,
+                    times_answered: stats.times_answered,
+                    accuracy,
+                }
+            })
+            .collect())
+    }
+}
+
+fn row_to_summary(row: &Row) -> Result<QuestionSummary, rusqlite::Error> {
+    let key_stage = row.get::<_, String>(2)?.parse::<KeyStage>()
+        .map_err(|_| rusqlite::Error::InvalidColumnType(2, "key_stage".to_string(), rusqlite::types::Type::Text))?;
+    let content = serde_json::from_str(&row.get::<_, String>(3)?)
+        .map_err(|_| rusqlite::Error::InvalidColumnType(3, "content".to_string(), rusqlite::types::Type::Text))?;
+    Ok(QuestionSummary {
+        id: row.get(0)?,
+        subject: row.get(1)?,
+        key_stage,
+        content,
+        has_explanation: row.get(4)?,
+    })
+}
+
+/// For each question that nearly repeats another in the same subject and key stage, the
+/// question it repeats. The earlier question of a pair is kept as the original.
+fn find_near_duplicates(questions: &[QuestionSummary]) -> HashMap<QuestionId, QuestionId> {
+    let words: Vec<BTreeSet<String>> = questions.iter().map(|question| question_words(&question.content.text)).collect();
+    let mut duplicates = HashMap::new();
+    for (later, question) in questions.iter().enumerate() {
+        let original = (0..later).find(|&earlier| {
+            let other = &questions[earlier];
+            other.subject == question.subject
+                && other.key_stage == question.key_stage
+                && similarity(&words[earlier], &words[later]) >= NEAR_DUPLICATE_SIMILARITY
+        });
+        if let Some(earlier) = original {
+            duplicates.insert(question.id, questions[earlier].id);
+        }
+    }
+    duplicates
+}
+
+fn question_words(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Words in common over words in either
+fn similarity(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_needs_attention_ranks_worst_questions_first() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+
+        let (profile_id, ids) = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            let profile_id = ProfileId(conn.last_insert_rowid() as u32);
+
+            let mut ids = Vec::new();
+            for (content, explanation) in [
+                (r#"{"text": "What is 2 + 2?"}"#, Some("Count on two from two")),
+                (r#"{"text": "What is 2 + 2 ?"}"#, Some("Two and two make four")),
+                (r#"{"text": "Which shape has three sides?"}"#, Some("A triangle has three sides")),
+                (r#"{"text": "What is 9 × 7?"}"#, None),
+            ] {
+                conn.execute(
+                    "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
+                     VALUES ((SELECT id FROM subjects WHERE name = 'mathematics'), 'KS1', 'multiple_choice', ?1, '\"4\"', 1, '[]', ?2)",
+                    params![content, Utc::now().to_rfc3339()],
+                )?;
+                let question_id = QuestionId(conn.last_insert_rowid() as u32);
+                if let Some(explanation) = explanation {
+                    conn.execute(
+                        "INSERT INTO question_explanations (question_id, explanation) VALUES (?1, ?2)",
+                        params![question_id, explanation],
+                    )?;
+                }
+                ids.push(question_id);
+            }
+            // The shapes question is always answered correctly, the 9 × 7 one almost never
+            conn.execute(
+                "INSERT INTO answered_questions (profile_id, question_id, times_answered, times_correct, last_answered_at)
+                 VALUES (?1, ?2, 20, 20, ?4), (?1, ?3, 20, 2, ?4)",
+                params![profile_id, ids[2], ids[3], Utc::now().to_rfc3339()],
+            )?;
+            Ok((profile_id, ids))
+        }).unwrap();
+
+        let service = QuestionQualityService::new(db_manager);
+        service.flag_question(ids[3], Some(profile_id), Some("The answer looks wrong")).unwrap();
+        assert!(service.flag_question(QuestionId(9999), None, None).is_err());
+
+        let original = service.get_question_quality(ids[0]).unwrap();
+        assert_eq!(original.score, 100);
+        assert!(original.issues.is_empty());
+
+        let attention = service.get_needs_attention(Some("mathematics"), 10).unwrap();
+        let ranked: Vec<(QuestionId, u32)> = attention.iter().map(|quality| (quality.question_id, quality.score)).collect();
+        assert_eq!(ranked, vec![(ids[3], 55), (ids[1], 80), (ids[2], 80)]);
+        assert_eq!(attention[0].issues, vec![
+            QualityIssue::TooHard { accuracy: 0.1 },
+            QualityIssue::Flagged { count: 1 },
+            QualityIssue::MissingExplanation,
+        ]);
+        assert_eq!(attention[1].issues, vec![QualityIssue::NearDuplicate { question_id: ids[0] }]);
+        assert_eq!(attention[2].issues, vec![QualityIssue::TooEasy { accuracy: 1.0 }]);
+
+        assert!(service.get_needs_attention(Some("english"), 10).unwrap().is_empty());
+        assert_eq!(service.get_needs_attention(None, 1).unwrap().len(), 1);
+    }
+}
@@ -1,32 +1,107 @@
 use crate::errors::{AppError, AppResult};
-use crate::models::{Question, KeyStage, Answer, QuestionType};
+use crate::models::{Question, KeyStage, Answer, QuestionType, PassageBlock, PassageGroup, BlankConfig, HotspotRegion, Hint, NormalizationRule, ProfileId, QuestionId, SessionId};
 use crate::database::DatabaseManager;
 use crate::services::ContentManager;
+use crate::services::profile_manager::QuizResult;
+use crate::services::expression;
+use crate::services::answer_normalization::normalize_answer;
+use crate::services::answer_timing::AnswerTiming;
+use crate::services::adaptive_length::{self, EarlyStop};
+use crate::services::experience;
+use crate::services::session_replay::{SessionEvent, SessionReplay, SessionReplayLog};
+use crate::services::selection_audit::{SelectionAudit, MAX_AUDIT_RUNS};
+use crate::services::spaced_repetition::{ReviewScheduler, CORRECT_ANSWER_QUALITY, WRONG_ANSWER_QUALITY};
+use crate::services::formatting::{localize_money_question, FormattingService};
+use crate::services::curriculum_profiles::CurriculumService;
+use crate::services::feature_flags::{flags, FeatureFlagService};
 use std::sync::Arc;
 use std::collections::HashMap;
+use std::path::Path;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+/// Extra points for getting a boss question right
+pub const BOSS_BONUS_POINTS: u32 = 25;
+
+/// Most warm-up questions a session can open with
+pub const MAX_WARM_UP_QUESTIONS: usize = 2;
+
+/// Share of a question's points lost for each hint taken, unless the quiz sets its own
+pub const DEFAULT_HINT_PENALTY_PERCENT: u8 = 25;
+
+/// Shown to the child when a question couldn't be marked and was swapped out or skipped.
+/// The session carries on; fetch the current question again to continue.
+pub const QUESTION_REPLACED_MESSAGE: &str = "Oops! That question got muddled up, so we've swapped it. Let's keep going!";
+
+/// Questions fetched when looking for a stand-in for a broken one
+const REPLACEMENT_CANDIDATES: usize = 20;
+
+/// Profile id on parent sandbox sessions, which belong to no profile
+pub const SANDBOX_PROFILE_ID: ProfileId = ProfileId(0);
+
+/// How many children can share a pass-and-play or co-op quiz
+pub const MIN_PASS_AND_PLAY_PLAYERS: usize = 2;
+pub const MAX_PASS_AND_PLAY_PLAYERS: usize = 4;
+
+/// Share of a co-op quiz's questions the players aim to get right between them, unless
+/// they pick their own target
+pub const DEFAULT_COOP_TARGET_PERCENT: u32 = 70;
+
 /// Quiz engine for question randomization, scoring, and quiz session management
 pub struct QuizEngine {
     db_manager: Arc<DatabaseManager>,
     content_manager: Arc<ContentManager>,
     randomizer: QuestionRandomizer,
     timer: QuizTimer,
-    sessions: std::sync::Mutex<HashMap<u32, QuizSession>>,
+    sessions: std::sync::Mutex<HashMap<SessionId, QuizSession>>,
     next_session_id: std::sync::Mutex<u32>,
+    replay_log: SessionReplayLog,
+    reviews: ReviewScheduler,
+    formatting: FormattingService,
+    curriculum: CurriculumService,
+    feature_flags: Option<Arc<FeatureFlagService>>,
 }
 
 impl QuizEngine {
     /// Create a new quiz engine
     pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>) -> Self {
+        // Carry on from the last recorded session so replays stay unambiguous across restarts
+        let replay_log = SessionReplayLog::new(db_manager.clone());
+        let last_session_id = replay_log.last_session_id().unwrap_or_else(|e| {
+            log::warn!("Failed to read the last quiz session id: {}", e);
+            0
+        });
+
         Self {
+            reviews: ReviewScheduler::new(db_manager.clone()),
+            formatting: FormattingService::new(db_manager.clone()),
+            curriculum: CurriculumService::new(db_manager.clone()),
             db_manager,
             content_manager,
             randomizer: QuestionRandomizer::new(),
             timer: QuizTimer::new(),
             sessions: std::sync::Mutex::new(HashMap::new()),
-            next_session_id: std::sync::Mutex::new(1),
+            next_session_id: std::sync::Mutex::new(last_session_id + 1),
+            replay_log,
+            feature_flags: None,
+        }
+    }
+
+    /// Switch adaptive behaviour, such as ending a quiz early once it's mastered, on and off
+    /// with the `adaptive_engine` flag. Without flags it follows each quiz's configuration.
+    pub fn with_feature_flags(mut self, feature_flags: Arc<FeatureFlagService>) -> Self {
+        self.feature_flags = Some(feature_flags);
+        self
+    }
+
+    fn adaptive_engine_enabled(&self, profile_id: ProfileId) -> bool {
+        match &self.feature_flags {
+            Some(feature_flags) => feature_flags.is_enabled(flags::ADAPTIVE_ENGINE, Some(profile_id))
+                .unwrap_or_else(|e| {
+                    log::warn!("Failed to read the adaptive engine flag: {}", e);
+                    false
+                }),
+            None => true,
         }
     }
     
@@ -37,12 +112,28 @@ impl QuizEngine {
         key_stage: KeyStage,
         count: usize,
         difficulty_range: Option<(u8, u8)>,
+    ) -> AppResult<Vec<Question>> {
+        self.get_questions_of_types(subject, key_stage, count, difficulty_range, &[])
+    }
+    
+    /// Like `get_questions`, drawing only from `question_types` unless it's empty
+    pub fn get_questions_of_types(
+        &self,
+        subject: &str,
+        key_stage: KeyStage,
+        count: usize,
+        difficulty_range: Option<(u8, u8)>,
+        question_types: &[QuestionType],
     ) -> AppResult<Vec<Question>> {
         println!("🔍 BACKEND: Getting questions - Subject: {}, KeyStage: {:?}, Requested: {}", 
                  subject, key_stage, count);
         
         // OPTIMIZATION: Use database-level randomization for better performance
-        let fetch_count = std::cmp::max(count * 2, count + 10); // Reduced multiplier for better performance
+        let mut fetch_count = std::cmp::max(count * 2, count + 10); // Reduced multiplier for better performance
+        if !question_types.is_empty() {
+            // Some of what's fetched will be filtered out
+            fetch_count *= 3;
+        }
         
         println!("🔍 BACKEND: Fetching {} questions from database", fetch_count);
         
@@ -56,6 +147,10 @@ impl QuizEngine {
         
         println!("🔍 BACKEND: Retrieved {} questions from database", questions.len());
         
+        if !question_types.is_empty() {
+            questions.retain(|question| question_types.contains(&question.question_type));
+        }
+        
         if questions.is_empty() {
             return Err(AppError::QuizEngine(
                 "No questions available for the specified criteria".to_string()
@@ -103,6 +198,12 @@ impl QuizEngine {
         difficulty_range: Option<(u8, u8)>,
         limit: usize,
     ) -> AppResult<Vec<Question>> {
+        // Without a range of its own, the quiz keeps to what the family's curriculum expects
+        let difficulty_range = match difficulty_range {
+            Some(range) => Some(range),
+            None => self.curriculum.level(key_stage)?.difficulty_range(),
+        };
+        
         // Use content manager to get questions instead of direct DB access
         self.content_manager.get_questions_by_subject(
             subject,
@@ -113,10 +214,14 @@ impl QuizEngine {
     }
 
 
-    /// Batch randomize questions for better performance
+    /// Batch randomize questions for better performance, asking money questions in the
+    /// family's currency
     fn batch_randomize_questions(&self, questions: &mut [Question]) -> AppResult<()> {
+        let locale = self.formatting.get_locale()?;
+        
         // OPTIMIZATION: Process questions in batches to reduce overhead
         for question in questions.iter_mut() {
+            localize_money_question(question, locale);
             match question.question_type {
                 QuestionType::MultipleChoice => {
                     self.randomizer.shuffle_answer_options(question)?;
@@ -124,6 +229,9 @@ impl QuizEngine {
                 QuestionType::DragDrop => {
                     self.randomizer.shuffle_drag_drop_items(question)?;
                 },
+                QuestionType::Ordering => {
+                    self.randomizer.shuffle_ordering_items(question)?;
+                },
                 QuestionType::Hotspot => {
                     self.randomizer.randomize_hotspot_distractors(question)?;
                 },
@@ -197,18 +305,59 @@ impl QuizEngine {
     }
     
     /// Validate an answer and return the result
-    pub fn validate_answer(&self, question_id: u32, submitted_answer: Answer) -> AppResult<AnswerResult> {
+    pub fn validate_answer(&self, question_id: QuestionId, submitted_answer: Answer) -> AppResult<AnswerResult> {
         // Get the question from database
         let question = self.content_manager.get_question_by_id(question_id)?;
         
+        // Cloze questions are marked blank by blank, with credit for each blank filled correctly
+        let blank_results = self.mark_blanks(&question, &submitted_answer);
+        if !blank_results.is_empty() {
+            let correct_blanks = blank_results.iter().filter(|blank| blank.is_correct).count();
+            let is_correct = correct_blanks == blank_results.len();
+            let explanation = if is_correct || correct_blanks == 0 {
+                self.generate_explanation(&question, is_correct)
+            } else {
+                Some(format!(
+                    "You got {} of {} blanks right. Check the highlighted ones and try to spot what fits.",
+                    correct_blanks,
+                    blank_results.len()
+                ))
+            };
+            
+            return Ok(AnswerResult {
+                question_id,
+                is_correct,
+                points: self.calculate_points(&question) * correct_blanks as u32 / blank_results.len() as u32,
+                correct_answer: question.correct_answer.clone(),
+                explanation,
+                time_taken: None,
+                is_boss_question: false,
+                hints_used: 0,
+                blank_results,
+                partial_credit: None,
+                late: None,
+                answered_by: None,
+                attempts: 1,
+                try_again: false,
+            });
+        }
+        
         // Validate the answer based on question type
         let is_correct = self.check_answer_correctness(&question, &submitted_answer)?;
         
+        // Ordering questions earn part of the points for the items already in the right order
+        let partial_credit = match (&question.correct_answer, &submitted_answer) {
+            (Answer::Ordering { order: correct }, Answer::Ordering { order: submitted }) if !is_correct => {
+                Some(ordering_credit(correct, submitted)).filter(|credit| *credit > 0.0)
+            },
+            _ => None,
+        };
+        
         // Calculate points based on difficulty and correctness
         let points = if is_correct {
             self.calculate_points(&question)
         } else {
-            0
+            partial_credit.map_or(0, |credit| (self.calculate_points(&question) as f32 * credit) as u32)
         };
         
         Ok(AnswerResult {
@@ -218,9 +367,74 @@ impl QuizEngine {
             correct_answer: question.correct_answer.clone(),
             explanation: self.generate_explanation(&question, is_correct),
             time_taken: None, // Will be set by caller if needed
+            is_boss_question: false,
+            hints_used: 0,
+            blank_results: Vec::new(),
+            partial_credit,
+            late: None,
+            answered_by: None,
+            attempts: 1,
+            try_again: false,
         })
     }
     
+    /// Mark each blank of a cloze question (a fill-in-blank question with several blanks).
+    /// The answer lists what was written in each blank, in blank position order; missing
+    /// entries count as wrong. Returns no results for any other question or answer shape.
+    fn mark_blanks(&self, question: &Question, submitted_answer: &Answer) -> Vec<BlankResult> {
+        let submitted = match submitted_answer {
+            Answer::Multiple(submitted) => submitted,
+            _ => return Vec::new(),
+        };
+        let mut blanks: Vec<&BlankConfig> = match &question.content.blanks {
+            Some(blanks) if question.question_type == QuestionType::FillBlank && blanks.len() > 1 => blanks.iter().collect(),
+            _ => return Vec::new(),
+        };
+        blanks.sort_by_key(|blank| blank.position);
+        
+        blanks.into_iter().enumerate().map(|(index, blank)| {
+            let answer = submitted.get(index).map(|answer| answer.trim()).unwrap_or("");
+            let edits = self.compare_blank_answer(question, blank, answer);
+            BlankResult {
+                position: blank.position,
+                submitted: answer.to_string(),
+                expected_answer: blank.expected_answer.clone(),
+                is_correct: edits.is_some(),
+                near_miss: edits.map_or(false, |edits| edits > 0),
+            }
+        }).collect()
+    }
+    
+    /// Mark one blank against its expected answer and alternatives, each compared in the
+    /// blank's own case sensitivity. Returns how many typing mistakes the closest accepted
+    /// answer needed (0 for an exact match), or None if the blank is wrong.
+    fn compare_blank_answer(&self, question: &Question, blank: &BlankConfig, submitted: &str) -> Option<usize> {
+        let mut normalization = question.content.answer_normalization().unwrap_or_default();
+        if blank.case_sensitive {
+            normalization = normalization.without(NormalizationRule::CaseFold);
+        } else if !normalization.rules.contains(&NormalizationRule::CaseFold) {
+            normalization.rules.push(NormalizationRule::CaseFold);
+        }
+        let submitted = normalize_answer(submitted, &normalization);
+        if submitted.trim().is_empty() {
+            return None;
+        }
+        
+        let alternatives = blank.accept_alternatives.iter().flatten();
+        std::iter::once(&blank.expected_answer).chain(alternatives).filter_map(|expected| {
+            let expected = normalize_answer(expected, &normalization);
+            if let Some(equal) = expression::answers_equal(expected.trim(), submitted.trim()) {
+                return if equal { Some(0) } else { None };
+            }
+            // In a case-sensitive blank, the wrong case is wrong however long the word
+            if blank.case_sensitive && expected != submitted && expected.to_lowercase() == submitted.to_lowercase() {
+                return None;
+            }
+            let edits = self.levenshtein_distance(&expected, &submitted);
+            Some(edits).filter(|&edits| edits <= blank.allowed_edits(expected.chars().count()))
+        }).min()
+    }
+    
     /// Calculate the final score for a quiz session
     pub fn calculate_score(&self, quiz_session: &QuizSession) -> AppResult<Score> {
         println!("🏁 BACKEND: Calculating score for session with {} questions and {} answers", 
@@ -249,8 +463,13 @@ impl QuizEngine {
             answered_questions  // User didn't finish, only count what they answered
         };
         
+        // Partly right answers count for the share of the question they got right
+        let credited_answers: f64 = quiz_session.answers.iter()
+            .map(|answer| if answer.is_correct { 1.0 } else { answer.partial_credit.unwrap_or(0.0) as f64 })
+            .sum();
+        
         let accuracy_percentage = if questions_for_accuracy > 0 {
-            (correct_answers as f64 / questions_for_accuracy as f64 * 100.0) as u8
+            (credited_answers / questions_for_accuracy as f64 * 100.0) as u8
         } else {
             0
         };
@@ -269,7 +488,20 @@ impl QuizEngine {
         // Calculate streak bonus
         let streak_bonus = self.calculate_streak_bonus(&quiz_session.answers);
         
-        let final_score = total_points + time_bonus + streak_bonus;
+        // Each hint costs a share of the points its question earned
+        let hint_penalty: u32 = quiz_session.answers.iter()
+            .map(|answer| {
+                let percent = (answer.hints_used * quiz_session.config.hint_penalty_percent as u32).min(100);
+                answer.points * percent / 100
+            })
+            .sum();
+        
+        // Practice isn't scored, so there are no bonuses either
+        let (time_bonus, streak_bonus) = match quiz_session.config.mode {
+            QuizMode::Assessed => (time_bonus, streak_bonus),
+            QuizMode::Practice => (0, 0),
+        };
+        let final_score = (total_points + time_bonus + streak_bonus).saturating_sub(hint_penalty);
         
         // Determine performance level
         let performance_level = match accuracy_percentage {
@@ -290,41 +522,383 @@ impl QuizEngine {
             total_points,
             time_bonus,
             streak_bonus,
+            hint_penalty,
             final_score,
             performance_level,
             achievements: self.check_achievements(&quiz_session)?,
+            xp: experience::quiz_xp(quiz_session, None),
+        })
+    }
+    
+    /// XP a profile earned in a session: on their own turns in a shared quiz, or for the
+    /// whole quiz otherwise
+    pub fn get_xp_earned(&self, session_id: SessionId, profile_id: ProfileId) -> AppResult<u32> {
+        let session = self.load_quiz_session(session_id)?;
+        let player = if session.players.is_empty() { None } else { Some(profile_id) };
+        Ok(experience::quiz_xp(&session, player))
+    }
+    
+    /// For a practice quiz, the questions a profile got right first time, which are all that
+    /// count towards their progress; None for a scored quiz
+    pub fn get_practice_credit(&self, session_id: SessionId, profile_id: ProfileId) -> AppResult<Option<u32>> {
+        let session = self.load_quiz_session(session_id)?;
+        if session.config.mode != QuizMode::Practice {
+            return Ok(None);
+        }
+        let first_time = session.answers.iter()
+            .filter(|answer| session.players.is_empty() || answer.answered_by == Some(profile_id))
+            .filter(|answer| answer.is_correct && answer.attempts <= 1)
+            .count();
+        Ok(Some(first_time as u32))
+    }
+    
+    /// Score each player in a pass-and-play quiz on their own turns, and say how they did
+    /// together
+    pub fn get_pass_and_play_summary(&self, session_id: SessionId) -> AppResult<PassAndPlaySummary> {
+        let session = self.load_quiz_session(session_id)?;
+        if session.mode != SessionMode::PassAndPlay {
+            return Err(AppError::QuizEngine("Not a pass-and-play quiz".to_string()));
+        }
+        
+        let players = self.player_scores(&session)?;
+        let best = players.iter().map(|player| player.score.final_score).max().unwrap_or(0);
+        Ok(PassAndPlaySummary {
+            session_id,
+            winners: players.iter()
+                .filter(|player| player.score.final_score == best)
+                .map(|player| player.profile_id)
+                .collect(),
+            combined_correct: session.answers.iter().filter(|answer| answer.is_correct).count() as u32,
+            total_questions: session.questions.len() as u32,
+            players,
+        })
+    }
+    
+    /// Who won a finished head-to-head quiz, each player's score on the same questions, and
+    /// who got each question right
+    pub fn get_head_to_head_summary(&self, session_id: SessionId) -> AppResult<HeadToHeadSummary> {
+        let session = self.load_quiz_session(session_id)?;
+        if session.mode != SessionMode::HeadToHead {
+            return Err(AppError::QuizEngine("Not a head-to-head quiz".to_string()));
+        }
+        if !session.is_completed() {
+            return Err(AppError::QuizEngine("The head-to-head quiz hasn't finished yet".to_string()));
+        }
+        
+        let players = self.player_scores(&session)?;
+        let rounds = session.answers.chunks(session.players.len())
+            .map(|answers| HeadToHeadRound {
+                question_id: answers[0].question_id,
+                correct: answers.iter()
+                    .filter(|answer| answer.is_correct)
+                    .filter_map(|answer| answer.answered_by)
+                    .collect(),
+            })
+            .collect();
+        
+        // Right answers decide it, then points when that's level
+        let ranking = |player: &PlayerScore| (player.score.correct_answers, player.score.final_score);
+        let best = players.iter().map(ranking).max().unwrap_or_default();
+        let leaders: Vec<ProfileId> = players.iter()
+            .filter(|player| ranking(player) == best)
+            .map(|player| player.profile_id)
+            .collect();
+        
+        Ok(HeadToHeadSummary {
+            session_id,
+            winner: if leaders.len() == 1 { Some(leaders[0]) } else { None },
+            players,
+            rounds,
+        })
+    }
+    
+    /// What a player's own turns in a shared quiz count for towards their progress
+    pub fn get_player_quiz_result(&self, session_id: SessionId, profile_id: ProfileId) -> AppResult<QuizResult> {
+        let session = self.load_quiz_session(session_id)?;
+        if !session.players.contains(&profile_id) {
+            return Err(AppError::InvalidInput("That profile isn't playing in this quiz".to_string()));
+        }
+        let answers = session.answers.iter().filter(|answer| answer.answered_by == Some(profile_id));
+        
+        Ok(QuizResult {
+            subject: session.config.subject.clone(),
+            key_stage: session.config.key_stage.as_str().to_string(),
+            questions_answered: answers.clone().count() as u32,
+            correct_answers: answers.clone().filter(|answer| answer.is_correct).count() as u32,
+            time_spent_seconds: answers.filter_map(|answer| answer.time_taken).sum(),
+            session_id: Some(session_id),
+            practice: false,
+        })
+    }
+    
+    /// Each player's score on their own turns in a shared quiz, in turn order
+    fn player_scores(&self, session: &QuizSession) -> AppResult<Vec<PlayerScore>> {
+        session.players.iter()
+            .map(|&player| {
+                let mut turns = session.clone();
+                turns.questions = session.questions.iter().enumerate()
+                    .filter(|(index, _)| session.player_for(*index) == player)
+                    .map(|(_, question)| question.clone())
+                    .collect();
+                turns.answers.retain(|answer| answer.answered_by == Some(player));
+                turns.total_time_seconds = turns.answers.iter().filter_map(|answer| answer.time_taken).sum();
+                Ok(PlayerScore { profile_id: player, score: self.calculate_score(&turns)? })
+            })
+            .collect()
+    }
+    
+    /// How a co-op team is doing against its target, and what each player has added. There's
+    /// no ranking: everyone's right answers go into the one score.
+    pub fn get_coop_summary(&self, session_id: SessionId) -> AppResult<CoopSummary> {
+        let session = self.load_quiz_session(session_id)?;
+        if session.mode != SessionMode::Coop {
+            return Err(AppError::QuizEngine("Not a co-op quiz".to_string()));
+        }
+        
+        let contributions = session.players.iter()
+            .map(|&player| {
+                let answers = session.answers.iter().filter(|answer| answer.answered_by == Some(player));
+                PlayerContribution {
+                    profile_id: player,
+                    questions_answered: answers.clone().count() as u32,
+                    correct_answers: answers.filter(|answer| answer.is_correct).count() as u32,
+                }
+            })
+            .collect();
+        let team_correct = session.team_correct();
+        let target = session.team_target.unwrap_or(0);
+        
+        Ok(CoopSummary {
+            session_id,
+            team_correct,
+            target,
+            target_reached: team_correct >= target,
+            total_questions: session.questions.len() as u32,
+            contributions,
         })
     }
     
     /// Start a new quiz session
     pub fn start_quiz_session(
         &self,
-        profile_id: u32,
+        profile_id: ProfileId,
+        config: QuizConfig,
+    ) -> AppResult<QuizSession> {
+        self.start_session(profile_id, config, false, SessionMode::Standard, Vec::new())
+    }
+
+    /// Start a timed assessment, e.g. a mock test. It can't be paused, its questions are
+    /// fixed now, and it ends when the time limit runs out.
+    pub fn start_assessment_session(
+        &self,
+        profile_id: ProfileId,
+        config: QuizConfig,
+    ) -> AppResult<QuizSession> {
+        if config.time_limit_seconds.map_or(true, |limit| limit == 0) {
+            return Err(AppError::InvalidInput("An assessment needs a time limit".to_string()));
+        }
+        if config.mode == QuizMode::Practice {
+            return Err(AppError::InvalidInput("An assessment can't be a practice quiz".to_string()));
+        }
+        self.start_session(profile_id, config, false, SessionMode::Assessment, Vec::new())
+    }
+    
+    /// Start a pass-and-play quiz: siblings on one device take turns, in `players` order, at
+    /// alternate questions. `question_count` is per player, and everyone gets the same number
+    /// of turns. Boss and review questions are left out, as only one player would get them.
+    pub fn start_pass_and_play_session(
+        &self,
+        players: Vec<ProfileId>,
+        mut config: QuizConfig,
+    ) -> AppResult<QuizSession> {
+        check_players(&players)?;
+        
+        config.question_count *= players.len();
+        config.boss_question = false;
+        config.cool_down_review = false;
+        self.start_session(players[0], config, false, SessionMode::PassAndPlay, players)
+    }
+    
+    /// Start a head-to-head quiz: two players on one device answer the same questions, each
+    /// taking their turn at every question before the quiz moves on. `question_count` is the
+    /// number of questions they both answer. The frontend keeps each question's result back
+    /// until both have answered it, so the second player doesn't see the answer first.
+    pub fn start_head_to_head_session(
+        &self,
+        players: [ProfileId; 2],
+        mut config: QuizConfig,
+    ) -> AppResult<QuizSession> {
+        check_players(&players)?;
+        
+        config.boss_question = false;
+        config.cool_down_review = false;
+        self.start_session(players[0], config, false, SessionMode::HeadToHead, players.to_vec())
+    }
+    
+    /// Start a co-op quiz: family members take turns as in pass-and-play, but each gets
+    /// questions at their own key stage and every right answer goes towards one shared
+    /// target. `question_count` is per player. Without a `target`, the team aims for
+    /// `DEFAULT_COOP_TARGET_PERCENT` of the questions.
+    pub fn start_coop_session(
+        &self,
+        players: Vec<CoopPlayer>,
+        mut config: QuizConfig,
+        target: Option<u32>,
+    ) -> AppResult<QuizSession> {
+        let profile_ids: Vec<ProfileId> = players.iter().map(|player| player.profile_id).collect();
+        check_players(&profile_ids)?;
+        
+        let mut turns = Vec::with_capacity(players.len());
+        for player in &players {
+            let mut questions = self.get_questions_of_types(
+                &config.subject,
+                player.key_stage,
+                config.question_count,
+                config.difficulty_range,
+                &config.allowed_question_types,
+            )?;
+            if let Some(max_run) = config.max_consecutive_same_tag {
+                self.randomizer.interleave_by_tag(&mut questions, max_run);
+            }
+            turns.push(questions.into_iter());
+        }
+        
+        // Everyone gets the same number of turns, so the youngest isn't left out when their
+        // key stage has fewer questions
+        let rounds = turns.iter().map(|questions| questions.len()).min().unwrap_or(0);
+        if rounds == 0 {
+            return Err(AppError::QuizEngine(
+                "No questions available for the specified criteria".to_string()
+            ));
+        }
+        let mut questions = Vec::with_capacity(rounds * players.len());
+        for _ in 0..rounds {
+            questions.extend(turns.iter_mut().filter_map(|questions| questions.next()));
+        }
+        
+        let total_questions = questions.len() as u32;
+        let target = target
+            .unwrap_or_else(|| (total_questions * DEFAULT_COOP_TARGET_PERCENT + 99) / 100)
+            .clamp(1, total_questions);
+        
+        config.question_count = questions.len();
+        config.boss_question = false;
+        config.cool_down_review = false;
+        config.warm_up_questions = 0;
+        
+        let session_id = {
+            let mut next_id = self.next_session_id.lock().unwrap();
+            let id = SessionId(*next_id);
+            *next_id += 1;
+            id
+        };
+        
+        let session = QuizSession {
+            id: Some(session_id),
+            profile_id: profile_ids[0],
+            config,
+            questions,
+            answers: Vec::new(),
+            current_question_index: 0,
+            started_at: Utc::now(),
+            completed_at: None,
+            total_time_seconds: 0,
+            is_paused: false,
+            pause_time: None,
+            passage_blocks: Vec::new(),
+            boss_question_index: None,
+            review_question_index: None,
+            current_hints: 0,
+            current_attempts: 0,
+            timing: AnswerTiming::default(),
+            sandbox: false,
+            last_shown_index: None,
+            mode: SessionMode::Coop,
+            integrity_notes: Vec::new(),
+            paused_seconds: 0,
+            players: profile_ids,
+            team_target: Some(target),
+            stopped_early: None,
+            assignment_id: None,
+        };
+        
+        self.save_quiz_session(&session)?;
+        self.record_event(&session, None, SessionEvent::Started { question_count: session.questions.len(), mode: session.mode });
+        
+        Ok(session)
+    }
+
+    fn start_session(
+        &self,
+        profile_id: ProfileId,
         config: QuizConfig,
+        sandbox: bool,
+        mode: SessionMode,
+        players: Vec<ProfileId>,
     ) -> AppResult<QuizSession> {
         println!("🚀 BACKEND: Starting quiz session - Subject: {}, KeyStage: {:?}, Count: {}", 
                  config.subject, config.key_stage, config.question_count);
         
         // Get questions for the quiz
-        let questions = self.get_questions(
+        let mut questions = self.get_questions_of_types(
             &config.subject,
             config.key_stage,
             config.question_count,
             config.difficulty_range,
+            &config.allowed_question_types,
         )?;
         
         println!("🚀 BACKEND: Retrieved {} questions for quiz", questions.len());
         
+        // Space out topics so the same tag doesn't come up too many times in a row
+        if let Some(max_run) = config.max_consecutive_same_tag {
+            self.randomizer.interleave_by_tag(&mut questions, max_run);
+        }
+        
+        // Players taking turns each get the same number of questions
+        if !players.is_empty() && mode != SessionMode::HeadToHead {
+            questions.truncate(questions.len() - questions.len() % players.len());
+        }
+        
         if questions.is_empty() {
             return Err(AppError::QuizEngine(
                 "No questions available for the specified criteria".to_string()
             ));
         }
         
+        // Open with the easiest questions, after interleaving so they stay at the front
+        let warm_up_count = self.order_warm_up(&mut questions, config.warm_up_questions);
+        
+        // Head-to-head players each get every question, one straight after the other
+        if mode == SessionMode::HeadToHead {
+            questions = questions.into_iter()
+                .flat_map(|question| std::iter::repeat(question).take(players.len()))
+                .collect();
+        }
+        
+        // Finish on a harder boss question when asked for and one is available
+        let mut boss_question_index = None;
+        if config.boss_question {
+            if let Some(boss) = self.pick_boss_question(&config, &questions)? {
+                boss_question_index = Some(questions.len());
+                questions.push(boss);
+            }
+        }
+        
+        // Then ask one of the main questions again to review it
+        let mut review_question_index = None;
+        if config.cool_down_review {
+            let main_end = boss_question_index.unwrap_or(questions.len());
+            if let Some(review) = self.pick_review_question(&questions[warm_up_count..main_end])? {
+                review_question_index = Some(questions.len());
+                questions.push(review);
+            }
+        }
+        
         // Generate a new session ID
         let session_id = {
             let mut next_id = self.next_session_id.lock().unwrap();
-            let id = *next_id;
+            let id = SessionId(*next_id);
             *next_id += 1;
             id
         };
@@ -342,71 +916,774 @@ impl QuizEngine {
             total_time_seconds: 0,
             is_paused: false,
             pause_time: None,
+            passage_blocks: Vec::new(),
+            boss_question_index,
+            review_question_index,
+            current_hints: 0,
+            current_attempts: 0,
+            timing: AnswerTiming::default(),
+            sandbox,
+            last_shown_index: None,
+            mode,
+            integrity_notes: Vec::new(),
+            paused_seconds: 0,
+            players,
+            team_target: None,
+            stopped_early: None,
+            assignment_id: None,
         };
         
         // Save session to in-memory storage
         self.save_quiz_session(&session)?;
+        self.record_event(&session, None, SessionEvent::Started { question_count: session.questions.len(), mode });
         
         Ok(session)
     }
-    
-    /// Submit an answer for the current question in a quiz session
-    pub fn submit_answer(
-        &mut self,
-        session_id: u32,
-        answer: Answer,
-        time_taken_seconds: u32,
-    ) -> AppResult<AnswerResult> {
-        // Load session from database
-        let mut session = self.load_quiz_session(session_id)?;
-        
-        if session.is_completed() {
-            return Err(AppError::QuizEngine("Quiz session is already completed".to_string()));
+
+    /// Start a session made of "read the passage, then answer its questions" blocks.
+    /// Questions keep their order within each passage.
+    pub fn start_passage_session(
+        &self,
+        profile_id: ProfileId,
+        mut config: QuizConfig,
+        groups: Vec<PassageGroup>,
+    ) -> AppResult<QuizSession> {
+        let mut questions = Vec::new();
+        let mut passage_blocks = Vec::new();
+
+        for group in groups {
+            if group.questions.is_empty() {
+                continue;
+            }
+
+            passage_blocks.push(PassageBlock {
+                passage: group.passage,
+                start_index: questions.len(),
+                question_count: group.questions.len(),
+            });
+            questions.extend(group.questions);
         }
-        
-        // Get current question
-        let current_question = session.get_current_question()
-            .ok_or_else(|| AppError::QuizEngine("No current question available".to_string()))?;
-        
-        // Validate the answer
-        let mut answer_result = self.validate_answer(current_question.id.unwrap(), answer)?;
-        answer_result.time_taken = Some(time_taken_seconds);
-        
-        // Add answer to session
-        session.answers.push(answer_result.clone());
-        session.total_time_seconds += time_taken_seconds;
-        session.current_question_index += 1;
-        
-        // Check if quiz is completed
-        if session.current_question_index >= session.questions.len() {
-            session.completed_at = Some(Utc::now());
+
+        if questions.is_empty() {
+            return Err(AppError::QuizEngine(
+                "No passage questions available for the specified criteria".to_string()
+            ));
         }
-        
-        // Update session in database
-        self.update_quiz_session(&session)?;
-        
-        Ok(answer_result)
+
+        self.batch_randomize_questions(&mut questions)?;
+        config.question_count = questions.len();
+
+        let session_id = {
+            let mut next_id = self.next_session_id.lock().unwrap();
+            let id = SessionId(*next_id);
+            *next_id += 1;
+            id
+        };
+
+        let session = QuizSession {
+            id: Some(session_id),
+            profile_id,
+            config,
+            questions,
+            answers: Vec::new(),
+            current_question_index: 0,
+            started_at: Utc::now(),
+            completed_at: None,
+            total_time_seconds: 0,
+            is_paused: false,
+            pause_time: None,
+            passage_blocks,
+            boss_question_index: None,
+            review_question_index: None,
+            current_hints: 0,
+            current_attempts: 0,
+            timing: AnswerTiming::default(),
+            sandbox: false,
+            last_shown_index: None,
+            mode: SessionMode::Standard,
+            integrity_notes: Vec::new(),
+            paused_seconds: 0,
+            players: Vec::new(),
+            team_target: None,
+            stopped_early: None,
+            assignment_id: None,
+        };
+
+        self.save_quiz_session(&session)?;
+        self.record_event(&session, None, SessionEvent::Started { question_count: session.questions.len(), mode: session.mode });
+
+        Ok(session)
     }
-    
-    /// Get the current question for a quiz session (one-at-a-time enforcement)
-    pub fn get_current_question(&self, session_id: u32) -> AppResult<Option<Question>> {
-        let session = self.load_quiz_session(session_id)?;
-        
-        // Security check: only return current question, never future questions
-        if let Some(mut question) = session.get_current_question().cloned() {
-            // Remove any metadata that could reveal future questions
-            self.sanitize_question_for_display(&mut question);
-            Ok(Some(question))
-        } else {
-            Ok(None)
-        }
+
+    /// Start a parent sandbox session, drawing questions as a quiz with this config would
+    pub fn start_sandbox_session(&self, config: QuizConfig) -> AppResult<QuizSession> {
+        self.start_session(SANDBOX_PROFILE_ID, config, true, SessionMode::Standard, Vec::new())
     }
-    
-    /// Get quiz session progress without revealing future questions
-    pub fn get_quiz_progress(&self, session_id: u32) -> AppResult<QuizProgress> {
-        let session = self.load_quiz_session(session_id)?;
-        
-        Ok(QuizProgress {
+
+    /// Start a parent sandbox session over chosen questions, e.g. one question or a custom
+    /// mix, in the order given
+    pub fn start_sandbox_preview(&self, title: &str, mut questions: Vec<Question>) -> AppResult<QuizSession> {
+        let key_stage = questions.first()
+            .map(|question| question.key_stage)
+            .ok_or_else(|| AppError::QuizEngine("No questions to preview".to_string()))?;
+        self.batch_randomize_questions(&mut questions)?;
+
+        let session_id = {
+            let mut next_id = self.next_session_id.lock().unwrap();
+            let id = SessionId(*next_id);
+            *next_id += 1;
+            id
+        };
+
+        let session = QuizSession {
+            id: Some(session_id),
+            profile_id: SANDBOX_PROFILE_ID,
+            config: QuizConfig {
+                subject: title.to_string(),
+                key_stage,
+                question_count: questions.len(),
+                difficulty_range: None,
+                time_limit_seconds: None,
+                randomize_questions: false,
+                randomize_answers: true,
+                max_consecutive_same_tag: None,
+                boss_question: false,
+                warm_up_questions: 0,
+                cool_down_review: false,
+                hint_penalty_percent: DEFAULT_HINT_PENALTY_PERCENT,
+                time_limit_per_question: None,
+                time_limit_total: None,
+                late_answers: LateAnswer::default(),
+                stop_when_mastered: false,
+                mode: QuizMode::default(),
+                allowed_question_types: Vec::new(),
+            },
+            questions,
+            answers: Vec::new(),
+            current_question_index: 0,
+            started_at: Utc::now(),
+            completed_at: None,
+            total_time_seconds: 0,
+            is_paused: false,
+            pause_time: None,
+            passage_blocks: Vec::new(),
+            boss_question_index: None,
+            review_question_index: None,
+            current_hints: 0,
+            current_attempts: 0,
+            timing: AnswerTiming::default(),
+            sandbox: true,
+            last_shown_index: None,
+            mode: SessionMode::Standard,
+            integrity_notes: Vec::new(),
+            paused_seconds: 0,
+            players: Vec::new(),
+            team_target: None,
+            stopped_early: None,
+            assignment_id: None,
+        };
+
+        self.save_quiz_session(&session)?;
+
+        Ok(session)
+    }
+
+    /// Exactly what the quiz UI would be sent for a question that hasn't been saved yet, so
+    /// authors can see it before saving. The question is validated as on save, read back as
+    /// it would be from the database, given the same shuffling as in a quiz and lists every
+    /// asset it would be stored with.
+    pub fn render_question_preview(&self, question: Question) -> AppResult<Question> {
+        self.content_manager.validate_question(&question)?;
+        if let Some(problem) = question_problem(&question) {
+            return Err(AppError::InvalidQuestion(problem.to_string()));
+        }
+
+        // Content and answer are stored as JSON, so parse them back the same way
+        let mut preview = question.clone();
+        preview.content = serde_json::from_str(&serde_json::to_string(&question.content)?)?;
+        preview.correct_answer = serde_json::from_str(&serde_json::to_string(&question.correct_answer)?)?;
+
+        let mut assets = preview.content.assets_with_option_images(question.assets.as_ref());
+        for asset in assets.iter_mut() {
+            let metadata = std::fs::metadata(&asset.file_path).map_err(|_| {
+                AppError::InvalidQuestion(format!("Asset file not found: {}", asset.file_path))
+            })?;
+            asset.file_size = asset.file_size.or(Some(metadata.len()));
+        }
+        preview.assets = if assets.is_empty() { None } else { Some(assets) };
+
+        self.batch_randomize_questions(std::slice::from_mut(&mut preview))?;
+        self.sanitize_question_for_display(&mut preview);
+        Ok(preview)
+    }
+
+    /// Start an assessment over a fixed paper of questions, kept in the order given
+    pub fn start_paper_session(
+        &self,
+        profile_id: ProfileId,
+        title: &str,
+        time_limit_seconds: u32,
+        questions: Vec<Question>,
+    ) -> AppResult<QuizSession> {
+        if questions.is_empty() {
+            return Err(AppError::QuizEngine("The paper has no questions".to_string()));
+        }
+        self.start_fixed_session(profile_id, title, Some(time_limit_seconds), questions, SessionMode::Assessment)
+    }
+
+    /// Start a review session made up entirely of the profile's questions that are due
+    /// for spaced-repetition review, most overdue first
+    pub fn start_review_session(&self, profile_id: ProfileId, question_count: usize) -> AppResult<QuizSession> {
+        let mut questions = Vec::new();
+        for review in self.reviews.get_due_reviews(profile_id, question_count)? {
+            match self.content_manager.get_question_by_id(review.question_id) {
+                Ok(question) => questions.push(question),
+                Err(e) => log::warn!("Skipping review of question {}: {}", review.question_id, e),
+            }
+        }
+        if questions.is_empty() {
+            return Err(AppError::QuizEngine("No questions are due for review".to_string()));
+        }
+        self.start_fixed_session(profile_id, "Review", None, questions, SessionMode::Review)
+    }
+
+    /// Start a session over the questions a profile set aside while exploring
+    pub fn start_practice_later_session(&self, profile_id: ProfileId, questions: Vec<Question>) -> AppResult<QuizSession> {
+        if questions.is_empty() {
+            return Err(AppError::QuizEngine("No questions have been saved to practise later".to_string()));
+        }
+        self.start_fixed_session(profile_id, "Practice later", None, questions, SessionMode::Standard)
+    }
+
+    /// Start a session retrying questions the profile got wrong before
+    pub fn start_mistakes_session(&self, profile_id: ProfileId, questions: Vec<Question>) -> AppResult<QuizSession> {
+        if questions.is_empty() {
+            return Err(AppError::QuizEngine("There are no mistakes to retry".to_string()));
+        }
+        self.start_fixed_session(profile_id, "Mistakes", None, questions, SessionMode::Standard)
+    }
+
+    /// Start a session over questions picked for a homework assignment, tagged with the
+    /// assignment so its result can be recorded against it
+    pub fn start_assignment_session(
+        &self,
+        profile_id: ProfileId,
+        assignment_id: u32,
+        title: &str,
+        time_limit_seconds: Option<u32>,
+        questions: Vec<Question>,
+    ) -> AppResult<QuizSession> {
+        if questions.is_empty() {
+            return Err(AppError::QuizEngine("No questions match this assignment".to_string()));
+        }
+        let mut session = self.start_fixed_session(profile_id, title, time_limit_seconds, questions, SessionMode::Standard)?;
+        session.assignment_id = Some(assignment_id);
+        self.update_quiz_session(&session)?;
+        Ok(session)
+    }
+
+    /// Start a session over questions chosen by the caller, kept in the order given
+    fn start_fixed_session(
+        &self,
+        profile_id: ProfileId,
+        title: &str,
+        time_limit_seconds: Option<u32>,
+        mut questions: Vec<Question>,
+        mode: SessionMode,
+    ) -> AppResult<QuizSession> {
+        let key_stage = questions.first()
+            .map(|question| question.key_stage)
+            .ok_or_else(|| AppError::QuizEngine("No questions available for the session".to_string()))?;
+        self.batch_randomize_questions(&mut questions)?;
+
+        let session_id = {
+            let mut next_id = self.next_session_id.lock().unwrap();
+            let id = SessionId(*next_id);
+            *next_id += 1;
+            id
+        };
+
+        let session = QuizSession {
+            id: Some(session_id),
+            profile_id,
+            config: QuizConfig {
+                subject: title.to_string(),
+                key_stage,
+                question_count: questions.len(),
+                difficulty_range: None,
+                time_limit_seconds,
+                randomize_questions: false,
+                randomize_answers: true,
+                max_consecutive_same_tag: None,
+                boss_question: false,
+                warm_up_questions: 0,
+                cool_down_review: false,
+                hint_penalty_percent: DEFAULT_HINT_PENALTY_PERCENT,
+                time_limit_per_question: None,
+                time_limit_total: None,
+                late_answers: LateAnswer::default(),
+                stop_when_mastered: false,
+                mode: QuizMode::default(),
+                allowed_question_types: Vec::new(),
+            },
+            questions,
+            answers: Vec::new(),
+            current_question_index: 0,
+            started_at: Utc::now(),
+            completed_at: None,
+            total_time_seconds: 0,
+            is_paused: false,
+            pause_time: None,
+            passage_blocks: Vec::new(),
+            boss_question_index: None,
+            review_question_index: None,
+            current_hints: 0,
+            current_attempts: 0,
+            timing: AnswerTiming::default(),
+            sandbox: false,
+            last_shown_index: None,
+            mode,
+            integrity_notes: Vec::new(),
+            paused_seconds: 0,
+            players: Vec::new(),
+            team_target: None,
+            stopped_early: None,
+            assignment_id: None,
+        };
+
+        self.save_quiz_session(&session)?;
+        self.record_event(&session, None, SessionEvent::Started { question_count: session.questions.len(), mode: session.mode });
+
+        Ok(session)
+    }
+
+    /// The passage to show alongside the current question, if the session has passage blocks
+    pub fn get_current_passage(&self, session_id: SessionId) -> AppResult<Option<PassageBlock>> {
+        let session = self.load_quiz_session(session_id)?;
+        Ok(session.current_passage_block().cloned())
+    }
+    
+    /// Submit an answer for the current question in a quiz session
+    pub fn submit_answer(
+        &mut self,
+        session_id: SessionId,
+        answer: Answer,
+        time_taken_seconds: u32,
+    ) -> AppResult<AnswerResult> {
+        // Load session from database
+        let mut session = self.load_quiz_session(session_id)?;
+        
+        if session.is_completed() {
+            return Err(AppError::QuizEngine("Quiz session is already completed".to_string()));
+        }
+        
+        let now = Utc::now();
+        
+        // Answers after an assessment's time is up don't count; it ends there
+        if session.mode == SessionMode::Assessment && self.timer.total_remaining(&session, now) == Some(0) {
+            session.completed_at = Some(now);
+            self.record_event(&session, None, SessionEvent::Completed {
+                correct_answers: session.answers.iter().filter(|answer| answer.is_correct).count(),
+                total_questions: session.questions.len(),
+            });
+            self.update_quiz_session(&session)?;
+            return Err(AppError::QuizEngine("Time is up for this assessment".to_string()));
+        }
+        
+        // Get current question
+        let current_question = session.get_current_question()
+            .ok_or_else(|| AppError::QuizEngine("No current question available".to_string()))?;
+        
+        // Validate the answer. A question that can't be marked is swapped out rather than
+        // ending the quiz.
+        let submitted = answer.clone();
+        let validated = current_question.id
+            .ok_or_else(|| AppError::QuizEngine("Question has no id".to_string()))
+            .and_then(|question_id| self.validate_answer(question_id, answer));
+        let mut answer_result = match validated {
+            Ok(answer_result) => answer_result,
+            Err(e) => {
+                self.recover_current_question(&mut session, "validation", &e)?;
+                return Err(AppError::QuestionReplaced(QUESTION_REPLACED_MESSAGE.to_string()));
+            }
+        };
+        answer_result.time_taken = Some(time_taken_seconds);
+        answer_result.hints_used = session.current_hints;
+        if !session.players.is_empty() {
+            answer_result.answered_by = Some(session.current_player());
+        }
+        
+        // In a timed quiz, an answer after the time ran out earns nothing, right or not.
+        // Practice has no deadline for each question, so its answers are never late.
+        if session.config.mode == QuizMode::Assessed && self.timer.is_late(&session, now) {
+            answer_result.is_correct = false;
+            answer_result.points = 0;
+            answer_result.partial_credit = None;
+            answer_result.late = Some(session.config.late_answers);
+        }
+        
+        if session.boss_question_index == Some(session.current_question_index) {
+            answer_result.is_boss_question = true;
+            if answer_result.is_correct {
+                answer_result.points += BOSS_BONUS_POINTS;
+            }
+        }
+        if session.config.mode == QuizMode::Practice {
+            answer_result.points = 0;
+        }
+        
+        // Time the answer ourselves; the client's time_taken is only used for scoring
+        session.timing.record(session.started_at, Utc::now(), answer_result.is_correct);
+        
+        self.record_event(&session, Some(session.current_question_index), SessionEvent::Answered {
+            answer: submitted,
+            is_correct: answer_result.is_correct,
+            points: answer_result.points,
+            reported_time_seconds: time_taken_seconds,
+        });
+
+        // Every answer is a review: it schedules when the question should next come up.
+        // A skipped late answer says nothing about what the child knows, and nor do the
+        // goes after the first in practice.
+        if !session.sandbox && answer_result.late != Some(LateAnswer::Skip) && session.current_attempts == 0 {
+            let quality = if answer_result.is_correct { CORRECT_ANSWER_QUALITY } else { WRONG_ANSWER_QUALITY };
+            if let Err(e) = self.reviews.record_review_result(session.current_player(), answer_result.question_id, quality) {
+                log::warn!("Failed to schedule question review: {}", e);
+            }
+        }
+        
+        // A wrong answer in practice leaves the question up for another go
+        session.total_time_seconds += time_taken_seconds;
+        if session.config.mode == QuizMode::Practice && !answer_result.is_correct {
+            session.current_attempts += 1;
+            answer_result.attempts = session.current_attempts;
+            answer_result.try_again = true;
+            // Handing back the answer would give the next go away
+            answer_result.correct_answer = Answer::Text(String::new());
+            for blank in &mut answer_result.blank_results {
+                blank.expected_answer.clear();
+            }
+            self.update_quiz_session(&session)?;
+            return Ok(answer_result);
+        }
+        answer_result.attempts = session.current_attempts + 1;
+        
+        // Add answer to session
+        session.answers.push(answer_result.clone());
+        session.current_question_index += 1;
+        session.current_hints = 0;
+        session.current_attempts = 0;
+        
+        // A "stop when mastered" quiz can end here, dropping the questions it won't need
+        if session.config.stop_when_mastered
+            && self.adaptive_engine_enabled(session.profile_id)
+            && session.mode == SessionMode::Standard
+            && session.passage_blocks.is_empty()
+            && session.current_question_index < session.questions.len()
+        {
+            if let Some(reason) = adaptive_length::check_early_stop(&session.timing.answers) {
+                while session.questions.len() > session.current_question_index {
+                    session.remove_question(session.questions.len() - 1);
+                }
+                session.stopped_early = Some(reason);
+            }
+        }
+        
+        // Check if quiz is completed, or its time has run out
+        if session.current_question_index >= session.questions.len()
+            || self.timer.total_remaining(&session, now) == Some(0)
+        {
+            session.completed_at = Some(Utc::now());
+            self.record_event(&session, None, SessionEvent::Completed {
+                correct_answers: session.answers.iter().filter(|answer| answer.is_correct).count(),
+                total_questions: session.questions.len(),
+            });
+        }
+        
+        // Update session in database
+        self.update_quiz_session(&session)?;
+        
+        Ok(answer_result)
+    }
+    
+    /// Get the current question for a quiz session (one-at-a-time enforcement)
+    pub fn get_current_question(&self, session_id: SessionId) -> AppResult<Option<Question>> {
+        let mut session = self.load_quiz_session(session_id)?;
+        
+        // Swap out a question that can't be shown instead of failing the quiz
+        while let Some(problem) = session.get_current_question().and_then(question_problem) {
+            let error = AppError::InvalidQuestion(problem.to_string());
+            self.recover_current_question(&mut session, "retrieval", &error)?;
+        }
+        
+        // The first time each question is shown starts its clock in the replay and fixes
+        // which of its phrasings the child sees
+        if session.get_current_question().is_some() && session.last_shown_index != Some(session.current_question_index) {
+            let phrasing = self.pick_phrasing(&mut session)?;
+            session.last_shown_index = Some(session.current_question_index);
+            session.timing.restart_clock(Utc::now());
+            self.update_quiz_session(&session)?;
+            self.record_event(&session, Some(session.current_question_index), SessionEvent::QuestionShown { phrasing });
+        }
+        
+        // Security check: only return current question, never future questions
+        if let Some(mut question) = session.get_current_question().cloned() {
+            // Remove any metadata that could reveal future questions
+            self.sanitize_question_for_display(&mut question);
+            Ok(Some(question))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Word the current question with one of its phrasings, avoiding the one the child saw
+    /// last time where there's a choice. Returns the phrasing's index.
+    fn pick_phrasing(&self, session: &mut QuizSession) -> AppResult<usize> {
+        let index = session.current_question_index;
+        let question = match session.questions.get(index) {
+            Some(question) => question,
+            None => return Ok(0),
+        };
+        let phrasings = question.content.phrasings().unwrap_or_default();
+        if phrasings.len() <= 1 {
+            return Ok(0);
+        }
+
+        // The second head-to-head player sees the question worded as the first did
+        let turn = session.round_turn(index);
+        if turn > 0 {
+            let text = session.questions[index - turn].content.text.clone();
+            let phrasing = phrasings.iter().position(|phrasing| *phrasing == text).unwrap_or(0);
+            session.questions[index].content.text = text;
+            return Ok(phrasing);
+        }
+
+        let last_shown = match question.id {
+            Some(question_id) if !session.sandbox => self.replay_log.last_phrasing_shown(session.profile_id, question_id)?,
+            _ => None,
+        };
+        let choices: Vec<usize> = (0..phrasings.len()).filter(|&choice| Some(choice) != last_shown).collect();
+        let phrasing = choices[(self.randomizer.next_random() % choices.len() as u64) as usize];
+        session.questions[index].content.text = phrasings[phrasing].clone();
+        Ok(phrasing)
+    }
+    
+    /// Replace the session's current question with another from the same subject, or drop it
+    /// when there's nothing suitable, and log the incident. Returns the replacement's id.
+    fn recover_current_question(&self, session: &mut QuizSession, stage: &str, error: &AppError) -> AppResult<Option<QuestionId>> {
+        let index = session.current_question_index;
+        let failed_question_id = session.get_current_question().and_then(|question| question.id);
+        // In head-to-head the other players' turns at the question go with it
+        let copies = session.questions[index..].iter()
+            .take(session.round_length() - session.round_turn(index))
+            .take_while(|question| question.id == failed_question_id)
+            .count()
+            .max(1);
+        log::warn!(
+            "Quiz session {:?}: {} failed for question {:?}: {}",
+            session.id, stage, failed_question_id, error
+        );
+        
+        // Questions in a passage block belong to their passage, an assessment's questions
+        // are fixed when it starts, and a review only has due questions, so those are only
+        // ever dropped. In co-op the stand-in is at the same key stage as the player's own.
+        let mut replacement = None;
+        if session.current_passage_block().is_none()
+            && matches!(session.mode, SessionMode::Standard | SessionMode::PassAndPlay | SessionMode::Coop | SessionMode::HeadToHead)
+        {
+            let key_stage = match session.mode {
+                SessionMode::Coop => session.get_current_question().map_or(session.config.key_stage, |question| question.key_stage),
+                _ => session.config.key_stage,
+            };
+            let mut candidates = self.get_questions_optimized(
+                &session.config.subject,
+                key_stage,
+                session.config.difficulty_range,
+                REPLACEMENT_CANDIDATES,
+            ).unwrap_or_default();
+            candidates.retain(|candidate| {
+                candidate.id.is_some()
+                    && question_problem(candidate).is_none()
+                    && !session.questions.iter().any(|question| question.id == candidate.id)
+            });
+            replacement = candidates.into_iter().next();
+        }
+        
+        let replacement_id = match replacement {
+            Some(mut question) => {
+                self.batch_randomize_questions(std::slice::from_mut(&mut question))?;
+                let replacement_id = question.id;
+                for copy in &mut session.questions[index..index + copies] {
+                    *copy = question.clone();
+                }
+                replacement_id
+            }
+            None => {
+                for _ in 0..copies {
+                    session.remove_question(index);
+                }
+                if session.current_question_index >= session.questions.len() {
+                    session.completed_at = Some(Utc::now());
+                }
+                None
+            }
+        };
+        
+        // Whatever takes the question's place gets its own "shown" event and hints
+        session.last_shown_index = None;
+        session.current_hints = 0;
+        
+        self.update_quiz_session(session)?;
+        self.record_incident(session, failed_question_id, stage, error, replacement_id);
+        self.record_question_event(session, Some((index, failed_question_id)), SessionEvent::QuestionReplaced {
+            stage: stage.to_string(),
+            replacement_question_id: replacement_id,
+        });
+        Ok(replacement_id)
+    }
+
+    /// Reveal the next hint for the current question. Each one taken costs
+    /// `hint_penalty_percent` of the question's points when the quiz is scored.
+    pub fn request_hint(&self, session_id: SessionId) -> AppResult<HintResult> {
+        let mut session = self.load_quiz_session(session_id)?;
+        if session.is_completed() {
+            return Err(AppError::QuizEngine("Quiz session is already completed".to_string()));
+        }
+        if session.mode == SessionMode::Assessment {
+            return Err(AppError::QuizEngine("Hints aren't available during an assessment".to_string()));
+        }
+        let question = session.get_current_question()
+            .ok_or_else(|| AppError::QuizEngine("No current question available".to_string()))?;
+
+        let hints = question.available_hints();
+        let index = session.current_hints as usize;
+        if index >= hints.len() {
+            return Err(AppError::QuizEngine("There are no more hints for this question".to_string()));
+        }
+        let hint = reveal_hint(question, &hints, index);
+
+        session.current_hints += 1;
+        self.update_quiz_session(&session)?;
+        self.record_event(&session, Some(session.current_question_index), SessionEvent::HintUsed);
+
+        Ok(HintResult {
+            hint,
+            hints_used: session.current_hints,
+            hints_remaining: (hints.len() - session.current_hints as usize) as u32,
+            penalty_percent: (session.current_hints * session.config.hint_penalty_percent as u32).min(100) as u8,
+        })
+    }
+
+    /// Note that the child opened a hint for the current question
+    pub fn record_hint_used(&self, session_id: SessionId) -> AppResult<()> {
+        let session = self.load_quiz_session(session_id)?;
+        if session.get_current_question().is_none() {
+            return Err(AppError::QuizEngine("No current question available".to_string()));
+        }
+        self.record_event(&session, Some(session.current_question_index), SessionEvent::HintUsed);
+        Ok(())
+    }
+
+    /// Add to a session's replay timeline, logging instead if that fails; sandbox sessions
+    /// have none
+    fn record_event(&self, session: &QuizSession, question_index: Option<usize>, event: SessionEvent) {
+        let question = question_index.map(|index| {
+            (index, session.questions.get(index).and_then(|question| question.id))
+        });
+        self.record_question_event(session, question, event);
+    }
+
+    fn record_question_event(&self, session: &QuizSession, question: Option<(usize, Option<QuestionId>)>, event: SessionEvent) {
+        let session_id = match session.id {
+            Some(session_id) if !session.sandbox => session_id,
+            _ => return,
+        };
+        if let Err(e) = self.replay_log.record(session_id, session.profile_id, question, &event) {
+            log::warn!("Failed to record quiz session event: {}", e);
+        }
+    }
+    
+    /// Store a recovered failure for parents to review, logging instead if that fails too
+    fn record_incident(
+        &self,
+        session: &QuizSession,
+        question_id: Option<QuestionId>,
+        stage: &str,
+        error: &AppError,
+        replacement_question_id: Option<QuestionId>,
+    ) {
+        // Incidents are filed under a profile, so sandbox ones are only logged
+        if session.sandbox {
+            log::warn!("Sandbox session {:?} replaced question {:?} at {}: {}", session.id, question_id, stage, error);
+            return;
+        }
+        let stored = self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO quiz_incidents (session_id, profile_id, question_id, stage, error, replacement_question_id, occurred_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    session.id,
+                    session.profile_id,
+                    question_id,
+                    stage,
+                    error.to_string(),
+                    replacement_question_id,
+                    Utc::now().to_rfc3339()
+                ],
+            )
+        });
+        if let Err(e) = stored {
+            log::warn!("Failed to record quiz incident: {}", e);
+        }
+    }
+    
+    /// Recently recovered question failures, newest first
+    pub fn get_incidents(&self, limit: u32) -> AppResult<Vec<QuizIncident>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, session_id, profile_id, question_id, stage, error, replacement_question_id, occurred_at
+                 FROM quiz_incidents ORDER BY occurred_at DESC, id DESC LIMIT ?1"
+            )?;
+            let rows = stmt.query_map(rusqlite::params![limit], |row| {
+                let occurred_at: String = row.get(7)?;
+                Ok(QuizIncident {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    profile_id: row.get(2)?,
+                    question_id: row.get(3)?,
+                    stage: row.get(4)?,
+                    error: row.get(5)?,
+                    replacement_question_id: row.get(6)?,
+                    occurred_at: DateTime::parse_from_rfc3339(&occurred_at)
+                        .map(|occurred_at| occurred_at.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                })
+            })?;
+            rows.collect()
+        })?)
+    }
+    
+    /// Whether a "slow down" nudge is due for the session; clears it once taken
+    pub fn take_slow_down(&self, session_id: SessionId) -> AppResult<bool> {
+        let mut session = self.load_quiz_session(session_id)?;
+        let due = session.timing.take_slow_down();
+        if due {
+            self.update_quiz_session(&session)?;
+        }
+        Ok(due)
+    }
+    
+    /// Whether the session's answers looked rushed, so it shouldn't count towards mastery
+    pub fn is_timing_flagged(&self, session_id: SessionId) -> AppResult<bool> {
+        Ok(self.load_quiz_session(session_id)?.timing.flag.is_some())
+    }
+    
+    /// Get quiz session progress without revealing future questions
+    pub fn get_quiz_progress(&self, session_id: SessionId) -> AppResult<QuizProgress> {
+        let session = self.load_quiz_session(session_id)?;
+        let now = Utc::now();
+        
+        Ok(QuizProgress {
             session_id,
             current_question_index: session.current_question_index,
             total_questions: session.questions.len(),
@@ -414,6 +1691,13 @@ impl QuizEngine {
             is_completed: session.is_completed(),
             time_elapsed: session.total_time_seconds,
             is_paused: session.is_paused,
+            mode: session.mode,
+            seconds_remaining: self.timer.total_remaining(&session, now),
+            question_seconds_remaining: self.timer.question_remaining(&session, now),
+            current_player: (!session.players.is_empty() && !session.is_completed()).then(|| session.current_player()),
+            team_correct: session.team_target.map(|_| session.team_correct()),
+            team_target: session.team_target,
+            stopped_early: session.stopped_early,
         })
     }
     
@@ -421,6 +1705,7 @@ impl QuizEngine {
     fn sanitize_question_for_display(&self, question: &mut Question) {
         // Remove any hints or metadata that could help with cheating
         question.tags.clear();
+        question.explanation = None;
         
         // For multiple choice, ensure options are properly randomized
         if question.question_type == QuestionType::MultipleChoice {
@@ -432,32 +1717,70 @@ impl QuizEngine {
     }
     
     /// Pause a quiz session
-    pub fn pause_quiz(&mut self, session_id: u32) -> AppResult<()> {
+    pub fn pause_quiz(&mut self, session_id: SessionId) -> AppResult<()> {
         let mut session = self.load_quiz_session(session_id)?;
         
         if session.is_completed() {
             return Err(AppError::QuizEngine("Cannot pause completed quiz".to_string()));
         }
+        if session.mode == SessionMode::Assessment {
+            return Err(AppError::QuizEngine("Assessments can't be paused".to_string()));
+        }
         
         session.is_paused = true;
         session.pause_time = Some(Utc::now());
         
         self.update_quiz_session(&session)?;
+        self.record_event(&session, None, SessionEvent::Paused);
         Ok(())
     }
     
     /// Resume a paused quiz session
-    pub fn resume_quiz(&mut self, session_id: u32) -> AppResult<()> {
+    pub fn resume_quiz(&mut self, session_id: SessionId) -> AppResult<()> {
         let mut session = self.load_quiz_session(session_id)?;
         
         if !session.is_paused {
             return Err(AppError::QuizEngine("Quiz is not paused".to_string()));
         }
         
+        let now = Utc::now();
+        if let Some(pause_time) = session.pause_time.take() {
+            session.paused_seconds += (now - pause_time).num_seconds().max(0) as u32;
+        }
         session.is_paused = false;
-        session.pause_time = None;
+        session.timing.restart_clock(now);
+        
+        self.update_quiz_session(&session)?;
+        self.record_event(&session, None, SessionEvent::Resumed);
+        Ok(())
+    }
+    
+    /// Note that the child left the quiz window (`left`) or came back to it. Only recorded
+    /// for assessments still in progress, where it goes in the session's integrity notes.
+    pub fn record_focus_change(&self, session_id: SessionId, left: bool) -> AppResult<()> {
+        let mut session = self.load_quiz_session(session_id)?;
+        if session.mode != SessionMode::Assessment || session.is_completed() {
+            return Ok(());
+        }
+        
+        let kind = if left { IntegrityNoteKind::Left } else { IntegrityNoteKind::Returned };
+        // Windows report focus changes more than once; only the change itself matters
+        let already_noted = session.integrity_notes.last()
+            .map_or(kind == IntegrityNoteKind::Returned, |note| note.kind == kind);
+        if already_noted {
+            return Ok(());
+        }
         
+        session.integrity_notes.push(IntegrityNote {
+            kind,
+            question_index: session.current_question_index,
+            occurred_at: Utc::now(),
+        });
         self.update_quiz_session(&session)?;
+        self.record_event(&session, Some(session.current_question_index), match kind {
+            IntegrityNoteKind::Left => SessionEvent::LeftSession,
+            IntegrityNoteKind::Returned => SessionEvent::ReturnedToSession,
+        });
         Ok(())
     }
     
@@ -476,6 +1799,17 @@ impl QuizEngine {
             (Answer::Mapping(correct), Answer::Mapping(submitted)) => {
                 Ok(self.compare_mapping_answers(correct, submitted))
             },
+            (Answer::Ordering { order: correct }, Answer::Ordering { order: submitted }) => {
+                Ok(ordering_credit(correct, submitted) >= 1.0)
+            },
+            (correct, Answer::Hotspot { x, y }) if question.question_type == QuestionType::Hotspot => {
+                self.check_hotspot_click(question, correct, *x, *y)
+            },
+            // Written answers are marked by what the drawing recogniser read, if it could read anything
+            (Answer::Text(correct), Answer::Drawing(drawing)) => {
+                Ok(drawing.recognized_text.as_deref()
+                    .map_or(false, |recognized| self.compare_text_answers(correct, recognized, question)))
+            },
             _ => Err(AppError::QuizEngine(
                 "Answer type mismatch with question".to_string()
             )),
@@ -484,25 +1818,44 @@ impl QuizEngine {
     
     /// Compare text answers with fuzzy matching for fill-in-blank questions
     fn compare_text_answers(&self, correct: &str, submitted: &str, question: &Question) -> bool {
-        let correct_normalized = correct.trim().to_lowercase();
-        let submitted_normalized = submitted.trim().to_lowercase();
+        if question.question_type == QuestionType::ShortAnswer {
+            return self.compare_short_answer(correct, submitted, question);
+        }
+        
+        // A lone blank is marked with its own case sensitivity and spelling tolerance
+        if question.question_type == QuestionType::FillBlank {
+            if let Some([blank]) = question.content.blanks.as_deref() {
+                return submitted.trim() == correct.trim() || self.compare_blank_answer(question, blank, submitted).is_some();
+            }
+        }
+        
+        let normalization = question.content.answer_normalization().unwrap_or_default();
+        let correct_normalized = normalize_answer(correct, &normalization);
+        let submitted_normalized = normalize_answer(submitted, &normalization);
         
         // Exact match
         if correct_normalized == submitted_normalized {
             return true;
         }
         
+        // Numeric answers are compared by value, so "14/2" or "7.0" count for 7
+        let numeric_answer = match expression::answers_equal(&correct_normalized, &submitted_normalized) {
+            Some(true) => return true,
+            Some(false) => true,
+            None => false,
+        };
+        
         // For fill-in-blank questions, check alternative answers
         if question.question_type == QuestionType::FillBlank {
             if let Some(blanks) = &question.content.blanks {
                 for blank in blanks {
-                    if blank.expected_answer.to_lowercase() == submitted_normalized {
+                    if normalize_answer(&blank.expected_answer, &normalization) == submitted_normalized {
                         return true;
                     }
                     
                     if let Some(alternatives) = &blank.accept_alternatives {
                         for alt in alternatives {
-                            if alt.to_lowercase() == submitted_normalized {
+                            if normalize_answer(alt, &normalization) == submitted_normalized {
                                 return true;
                             }
                         }
@@ -511,10 +1864,62 @@ impl QuizEngine {
             }
         }
         
+        // A number that is nearly right is still wrong
+        if numeric_answer {
+            return false;
+        }
+        
         // Simple fuzzy matching for spelling variations
         self.fuzzy_text_match(&correct_normalized, &submitted_normalized)
     }
     
+    /// Mark a short answer against the expected answer and its accepted alternatives, allowing
+    /// a few typing mistakes on longer answers. Numbers must still be exactly right.
+    fn compare_short_answer(&self, correct: &str, submitted: &str, question: &Question) -> bool {
+        let config = question.content.short_answer_config().unwrap_or_default();
+        let mut normalization = question.content.answer_normalization().unwrap_or_default();
+        if config.case_sensitive {
+            normalization = normalization.without(NormalizationRule::CaseFold);
+        }
+        let submitted = normalize_answer(submitted, &normalization);
+        let submitted_normalized = normalize_short_answer(&submitted, config.case_sensitive);
+        if submitted_normalized.is_empty() {
+            return false;
+        }
+        
+        std::iter::once(correct).chain(config.accepted_answers.iter().map(String::as_str)).any(|expected| {
+            let expected = normalize_answer(expected, &normalization);
+            if let Some(equal) = expression::answers_equal(expected.trim(), submitted.trim()) {
+                return equal;
+            }
+            
+            let expected_normalized = normalize_short_answer(&expected, config.case_sensitive);
+            let allowed_edits = config.allowed_edits(expected_normalized.chars().count());
+            expected_normalized == submitted_normalized
+                || self.levenshtein_distance(&expected_normalized, &submitted_normalized) <= allowed_edits
+        })
+    }
+    
+    /// Whether a click lands on (or within the question's tolerance of) a correct region.
+    /// Regions set on the question are used; otherwise the answer's coordinates are.
+    fn check_hotspot_click(&self, question: &Question, correct: &Answer, x: f64, y: f64) -> AppResult<bool> {
+        if !x.is_finite() || !y.is_finite() {
+            return Ok(false);
+        }
+        let config = question.content.hotspot_config()
+            .map_err(|e| AppError::InvalidQuestion(format!("Invalid hotspot settings: {}", e)))?;
+        let regions = if !config.regions.is_empty() {
+            config.regions
+        } else {
+            match correct {
+                Answer::Coordinates(coordinates) => coordinates.iter().map(HotspotRegion::from).collect(),
+                Answer::Hotspot { x, y } => vec![HotspotRegion::Circle { x: *x, y: *y, radius: 0.0 }],
+                _ => return Err(AppError::QuizEngine("Answer type mismatch with question".to_string())),
+            }
+        };
+        Ok(regions.iter().any(|region| region.distance_to(x, y) <= config.tolerance))
+    }
+    
     /// Compare multiple choice answers
     fn compare_multiple_answers(&self, correct: &[String], submitted: &[String]) -> bool {
         if correct.len() != submitted.len() {
@@ -640,9 +2045,74 @@ impl QuizEngine {
             QuestionType::DragDrop => 10,
             QuestionType::Hotspot => 10,
             QuestionType::StoryQuiz => 15,
+            QuestionType::ShortAnswer => 10,
+            QuestionType::Ordering => 10,
         };
         
-        base_points + type_bonus
+        base_points + type_bonus + question.key_stage.points_bonus()
+    }
+    
+    /// A question one difficulty level above the session, not already in it. Without a set
+    /// range the session's hardest question is the baseline; at the top level the hardest
+    /// questions available are used.
+    fn pick_boss_question(&self, config: &QuizConfig, questions: &[Question]) -> AppResult<Option<Question>> {
+        let top_level = config.difficulty_range
+            .map(|(_, max)| max)
+            .or_else(|| questions.iter().map(|question| question.difficulty_level).max())
+            .unwrap_or(1);
+        let boss_level = (top_level + 1).min(5);
+
+        let mut candidates = self.get_questions_optimized(
+            &config.subject,
+            config.key_stage,
+            Some((boss_level, boss_level)),
+            20,
+        )?;
+        candidates.retain(|candidate| {
+            (config.allowed_question_types.is_empty() || config.allowed_question_types.contains(&candidate.question_type))
+                && !questions.iter().any(|question| question.id == candidate.id)
+        });
+
+        let mut boss = match candidates.into_iter().next() {
+            Some(boss) => boss,
+            None => return Ok(None),
+        };
+        self.batch_randomize_questions(std::slice::from_mut(&mut boss))?;
+        Ok(Some(boss))
+    }
+    
+    /// Move up to `requested` of the easiest questions to the front, keeping their drawn
+    /// order. Only questions easier than the hardest in the session count as warm-ups, so
+    /// nothing moves when they're all the same level. Returns how many were moved.
+    fn order_warm_up(&self, questions: &mut Vec<Question>, requested: usize) -> usize {
+        let hardest = match questions.iter().map(|question| question.difficulty_level).max() {
+            Some(hardest) => hardest,
+            None => return 0,
+        };
+        let mut by_difficulty: Vec<usize> = (0..questions.len())
+            .filter(|&index| questions[index].difficulty_level < hardest)
+            .collect();
+        by_difficulty.sort_by_key(|&index| questions[index].difficulty_level);
+        by_difficulty.truncate(requested.min(MAX_WARM_UP_QUESTIONS));
+        by_difficulty.sort_unstable();
+
+        for (position, &index) in by_difficulty.iter().enumerate() {
+            let warm_up = questions.remove(index);
+            questions.insert(position, warm_up);
+        }
+        by_difficulty.len()
+    }
+
+    /// A fresh copy of one of `questions` to ask again at the end of the session
+    fn pick_review_question(&self, questions: &[Question]) -> AppResult<Option<Question>> {
+        if questions.is_empty() {
+            return Ok(None);
+        }
+
+        let mut review = questions[(self.randomizer.next_random() % questions.len() as u64) as usize].clone();
+        // Shuffle the copy again so it isn't answered from where the options sat last time
+        self.batch_randomize_questions(std::slice::from_mut(&mut review))?;
+        Ok(Some(review))
     }
     
     /// Calculate time bonus points
@@ -684,8 +2154,12 @@ impl QuizEngine {
         }
     }
     
-    /// Generate explanation for answer result
+    /// Generate explanation for answer result, using the question's own explanation when it has one
     fn generate_explanation(&self, question: &Question, is_correct: bool) -> Option<String> {
+        if let Some(explanation) = &question.explanation {
+            let lead = if is_correct { "Correct!" } else { "Not quite." };
+            return Some(format!("{} {}", lead, explanation));
+        }
         if is_correct {
             Some("Correct! Well done!".to_string())
         } else {
@@ -705,6 +2179,12 @@ impl QuizEngine {
                 QuestionType::StoryQuiz => {
                     Some("Read the story again and look for clues that answer the question.".to_string())
                 },
+                QuestionType::ShortAnswer => {
+                    Some("Not quite. Think about the question again and check how you spelled your answer.".to_string())
+                },
+                QuestionType::Ordering => {
+                    Some("Some items are out of place. Compare them two at a time and try again.".to_string())
+                },
             }
         }
     }
@@ -775,17 +2255,141 @@ impl QuizEngine {
     }
     
     /// Load quiz session from in-memory storage
-    fn load_quiz_session(&self, session_id: u32) -> AppResult<QuizSession> {
+    fn load_quiz_session(&self, session_id: SessionId) -> AppResult<QuizSession> {
         let sessions = self.sessions.lock().unwrap();
         sessions.get(&session_id)
             .cloned()
             .ok_or_else(|| AppError::NotFound(format!("Quiz session {} not found", session_id)))
     }
     
+    /// Pick questions for `config` `runs` times without starting a session, and report how
+    /// the picks were spread across the pool, so selection bias shows up
+    pub fn audit_selection(&self, config: &QuizConfig, runs: u32) -> AppResult<SelectionAudit> {
+        if runs == 0 || runs > MAX_AUDIT_RUNS {
+            return Err(AppError::InvalidInput(format!("An audit must simulate between 1 and {} selections", MAX_AUDIT_RUNS)));
+        }
+
+        let mut pool = self.content_manager.get_questions_by_subject(
+            &config.subject,
+            Some(config.key_stage),
+            config.difficulty_range,
+            None,
+        )?;
+        if !config.allowed_question_types.is_empty() {
+            pool.retain(|question| config.allowed_question_types.contains(&question.question_type));
+        }
+
+        let selections = (0..runs)
+            .map(|_| self.get_questions_of_types(
+                &config.subject,
+                config.key_stage,
+                config.question_count,
+                config.difficulty_range,
+                &config.allowed_question_types,
+            ))
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(SelectionAudit::from_runs(&pool, &selections))
+    }
+
+    /// Everything that happened in a session, for replaying it
+    pub fn get_session_replay(&self, session_id: SessionId) -> AppResult<SessionReplay> {
+        self.replay_log.get_replay(session_id)
+    }
+
+    /// A session that has been played to the end, e.g. to credit its result elsewhere
+    pub fn get_completed_session(&self, session_id: SessionId) -> AppResult<QuizSession> {
+        let session = self.load_quiz_session(session_id)?;
+        if !session.is_completed() {
+            return Err(AppError::QuizEngine(format!("Quiz session {} has not been finished", session_id)));
+        }
+        Ok(session)
+    }
+
+    /// The profile a quiz session belongs to
+    pub fn get_session_profile_id(&self, session_id: SessionId) -> AppResult<ProfileId> {
+        Ok(self.load_quiz_session(session_id)?.profile_id)
+    }
+
+    /// The profile whose turn it is, which is the session's own profile unless it's pass-and-play
+    pub fn get_current_player(&self, session_id: SessionId) -> AppResult<ProfileId> {
+        Ok(self.load_quiz_session(session_id)?.current_player())
+    }
+
+    /// The profile to record a session's answers and work against; `None` for sandbox sessions
+    pub fn get_recording_profile_id(&self, session_id: SessionId) -> AppResult<Option<ProfileId>> {
+        let session = self.load_quiz_session(session_id)?;
+        Ok(if session.sandbox { None } else { Some(session.profile_id) })
+    }
+    
     /// Update quiz session in in-memory storage
     fn update_quiz_session(&self, session: &QuizSession) -> AppResult<()> {
         self.save_quiz_session(session) // Same as save for in-memory storage
     }
+    
+    /// Get sessions that have not been completed, optionally for a single profile
+    pub fn get_active_sessions(&self, profile_id: Option<ProfileId>) -> Vec<QuizSession> {
+        let sessions = self.sessions.lock().unwrap();
+        let mut active: Vec<QuizSession> = sessions.values()
+            .filter(|s| !s.is_completed())
+            .filter(|s| profile_id.map_or(true, |id| s.profile_id == id))
+            .cloned()
+            .collect();
+        active.sort_by_key(|s| s.id);
+        active
+    }
+    
+    /// Write in-flight sessions to disk so they survive an app restart
+    pub fn save_sessions_snapshot(&self, path: &Path) -> AppResult<usize> {
+        let active = self.get_active_sessions(None);
+        
+        if active.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+            return Ok(0);
+        }
+        
+        let temp_path = path.with_extension("tmp");
+        std::fs::write(&temp_path, serde_json::to_vec(&active)?)?;
+        std::fs::rename(&temp_path, path)?;
+        
+        Ok(active.len())
+    }
+    
+    /// Restore sessions written by `save_sessions_snapshot`. Restored sessions are paused
+    /// and the snapshot file is removed so the sessions are only restored once.
+    pub fn restore_sessions_snapshot(&self, path: &Path) -> AppResult<usize> {
+        if !path.exists() {
+            return Ok(0);
+        }
+        
+        let data = std::fs::read(path)?;
+        let restored: Vec<QuizSession> = serde_json::from_slice(&data)?;
+        std::fs::remove_file(path)?;
+        
+        let mut sessions = self.sessions.lock().unwrap();
+        let mut next_id = self.next_session_id.lock().unwrap();
+        let mut count = 0;
+        
+        for mut session in restored {
+            let session_id = match session.id {
+                Some(id) => id,
+                None => continue,
+            };
+            
+            if !session.is_paused {
+                session.is_paused = true;
+                session.pause_time = Some(Utc::now());
+            }
+            
+            *next_id = (*next_id).max(session_id.0 + 1);
+            sessions.insert(session_id, session);
+            count += 1;
+        }
+        
+        Ok(count)
+    }
 }
 
 /// Question randomizer for shuffling questions and answers
@@ -795,22 +2399,34 @@ pub struct QuestionRandomizer {
 
 impl QuestionRandomizer {
     pub fn new() -> Self {
+        use std::sync::atomic::{AtomicU64, Ordering};
         use std::time::{SystemTime, UNIX_EPOCH};
-        let seed = SystemTime::now()
+
+        // Randomizers created within the same clock tick would otherwise share a seed
+        static INSTANCES: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_nanos() as u64;
+        let instance = INSTANCES.fetch_add(1, Ordering::Relaxed);
+
+        // SplitMix64 finaliser, so nearby seeds start far apart
+        let mut seed = nanos ^ instance.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        seed = (seed ^ (seed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        seed ^= seed >> 31;
         
         Self {
             rng_state: std::cell::RefCell::new(seed),
         }
     }
     
-    /// Generate next pseudo-random number using Linear Congruential Generator
+    /// Generate next pseudo-random number using a Linear Congruential Generator. Only the
+    /// high bits are returned, as the low bits of an LCG repeat with a short period.
     fn next_random(&self) -> u64 {
         let mut state = self.rng_state.borrow_mut();
-        *state = state.wrapping_mul(1103515245).wrapping_add(12345);
-        *state
+        *state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        *state >> 33
     }
     
     /// Shuffle questions using Fisher-Yates algorithm with proper randomization
@@ -826,6 +2442,42 @@ impl QuestionRandomizer {
         }
     }
     
+    /// Reorder questions so no more than `max_run` in a row share a tag, keeping the current
+    /// order where possible. Untagged questions break a run. When the remaining questions can't
+    /// be spaced out (e.g. all on one topic), the shortest possible run is used instead.
+    pub fn interleave_by_tag(&self, questions: &mut Vec<Question>, max_run: usize) {
+        let max_run = max_run.max(1);
+        let mut remaining = std::mem::take(questions);
+        let mut runs: HashMap<String, usize> = HashMap::new();
+
+        while !remaining.is_empty() {
+            let longest_run = |question: &Question| {
+                question.tags.iter()
+                    .map(|tag| runs.get(&tag.to_lowercase()).copied().unwrap_or(0) + 1)
+                    .max()
+                    .unwrap_or(0)
+            };
+
+            let index = remaining.iter()
+                .position(|question| longest_run(question) <= max_run)
+                .unwrap_or_else(|| {
+                    (0..remaining.len())
+                        .min_by_key(|&index| longest_run(&remaining[index]))
+                        .unwrap_or(0)
+                });
+
+            let question = remaining.remove(index);
+            runs = question.tags.iter()
+                .map(|tag| {
+                    let tag = tag.to_lowercase();
+                    let run = runs.get(&tag).copied().unwrap_or(0) + 1;
+                    (tag, run)
+                })
+                .collect();
+            questions.push(question);
+        }
+    }
+    
     /// Shuffle answer options for multiple choice questions
     pub fn shuffle_answer_options(&self, question: &mut Question) -> AppResult<()> {
         if let Some(ref mut options) = question.content.options {
@@ -845,7 +2497,8 @@ impl QuestionRandomizer {
                 options.swap(i, j);
             }
             
-            // Correct answer remains the same text, position doesn't matter
+            // Correct answer remains the same text, position doesn't matter. Option images
+            // are keyed by option text too, so they move with their options.
             question.correct_answer = Answer::Text(correct_answer_text);
         }
         
@@ -882,6 +2535,30 @@ impl QuestionRandomizer {
         Ok(())
     }
     
+    /// Mix up the items of an ordering question, making sure they don't start out already
+    /// in the right order
+    pub fn shuffle_ordering_items(&self, question: &mut Question) -> AppResult<()> {
+        let order = match &question.correct_answer {
+            Answer::Ordering { order } => order,
+            _ => return Ok(()),
+        };
+        if let Some(ref mut items) = question.content.options {
+            if items.len() <= 1 {
+                return Ok(());
+            }
+
+            for i in (1..items.len()).rev() {
+                let j = (self.next_random() % (i + 1) as u64) as usize;
+                items.swap(i, j);
+            }
+            if items == order {
+                items.rotate_left(1);
+            }
+        }
+
+        Ok(())
+    }
+    
     /// Randomize hotspot positions (if applicable)
     pub fn randomize_hotspot_distractors(&self, question: &mut Question) -> AppResult<()> {
         if question.question_type != QuestionType::Hotspot {
@@ -895,15 +2572,115 @@ impl QuestionRandomizer {
     }
 }
 
-/// Quiz timer for managing time limits
-pub struct QuizTimer {
-    // Timer implementation would go here
-}
+/// Quiz timer for managing time limits. The clocks stand still while a session is paused.
+pub struct QuizTimer {}
 
 impl QuizTimer {
     pub fn new() -> Self {
         Self {}
     }
+    
+    /// Seconds left on the whole quiz, or `None` when it has no overall limit
+    pub fn total_remaining(&self, session: &QuizSession, now: DateTime<Utc>) -> Option<u32> {
+        let assessment_limit = match session.mode {
+            SessionMode::Assessment => session.config.time_limit_seconds,
+            _ => None,
+        };
+        let limit = assessment_limit.or(session.config.time_limit_total)? as i64;
+        let elapsed = (Self::clock(session, now) - session.started_at).num_seconds() - session.paused_seconds as i64;
+        Some((limit - elapsed).max(0) as u32)
+    }
+    
+    /// Seconds left to answer the current question, or `None` when questions aren't timed
+    pub fn question_remaining(&self, session: &QuizSession, now: DateTime<Utc>) -> Option<u32> {
+        let limit = session.config.time_limit_per_question? as i64;
+        let started_at = session.timing.question_started_at.unwrap_or(session.started_at);
+        let elapsed = (Self::clock(session, now) - started_at).num_seconds();
+        Some((limit - elapsed).max(0) as u32)
+    }
+    
+    /// Whether an answer given at `now` is too late for the current question
+    pub fn is_late(&self, session: &QuizSession, now: DateTime<Utc>) -> bool {
+        self.question_remaining(session, now) == Some(0) || self.total_remaining(session, now) == Some(0)
+    }
+    
+    /// A paused session's clocks stopped when it was paused
+    fn clock(session: &QuizSession, now: DateTime<Utc>) -> DateTime<Utc> {
+        match session.pause_time {
+            Some(pause_time) if session.is_paused => pause_time,
+            _ => now,
+        }
+    }
+}
+
+/// Players for a shared quiz: between the minimum and maximum, each joining once
+fn check_players(players: &[ProfileId]) -> AppResult<()> {
+    if !(MIN_PASS_AND_PLAY_PLAYERS..=MAX_PASS_AND_PLAY_PLAYERS).contains(&players.len()) {
+        return Err(AppError::InvalidInput(format!(
+            "A shared quiz needs {} to {} players", MIN_PASS_AND_PLAY_PLAYERS, MAX_PASS_AND_PLAY_PLAYERS
+        )));
+    }
+    if players.iter().enumerate().any(|(index, player)| players[..index].contains(player)) {
+        return Err(AppError::InvalidInput("Each player can only join once".to_string()));
+    }
+    Ok(())
+}
+
+/// Why a question can't be shown or marked, if it can't
+fn question_problem(question: &Question) -> Option<&'static str> {
+    if question.content.text.trim().is_empty() {
+        return Some("Question has no text");
+    }
+    if question.question_type == QuestionType::MultipleChoice
+        && question.content.options.as_ref().map_or(true, |options| options.is_empty())
+    {
+        return Some("Multiple choice question has no options");
+    }
+    None
+}
+
+/// Share of an ordering answer in the right order: the longest run of items, not
+/// necessarily next to each other, that keep their correct relative order, out of all the
+/// items. Unknown and repeated items count for nothing.
+fn ordering_credit(correct: &[String], submitted: &[String]) -> f32 {
+    if correct.is_empty() {
+        return 0.0;
+    }
+    let mut seen = Vec::new();
+    let positions: Vec<usize> = submitted.iter()
+        .filter_map(|item| correct.iter().position(|expected| expected.trim() == item.trim()))
+        .filter(|position| {
+            let first = !seen.contains(position);
+            seen.push(*position);
+            first
+        })
+        .collect();
+
+    // Longest increasing subsequence, by patience sorting
+    let mut tails: Vec<usize> = Vec::new();
+    for position in positions {
+        match tails.binary_search(&position) {
+            Ok(_) => {}
+            Err(index) if index == tails.len() => tails.push(position),
+            Err(index) => tails[index] = position,
+        }
+    }
+    tails.len() as f32 / correct.len() as f32
+}
+
+/// Lowercase (unless case matters), drop punctuation and collapse whitespace, so
+/// "  St. Paul's " and "st pauls" compare equal
+fn normalize_short_answer(text: &str, case_sensitive: bool) -> String {
+    let cleaned: String = text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+    let words = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    
+    if case_sensitive {
+        words
+    } else {
+        words.to_lowercase()
+    }
 }
 
 /// Quiz configuration
@@ -916,13 +2693,136 @@ pub struct QuizConfig {
     pub time_limit_seconds: Option<u32>,
     pub randomize_questions: bool,
     pub randomize_answers: bool,
+    /// Most questions in a row that may share a tag, so topics are interleaved; `None` keeps the drawn order
+    #[serde(default)]
+    pub max_consecutive_same_tag: Option<usize>,
+    /// End the session on a harder "boss" question worth bonus points
+    #[serde(default)]
+    pub boss_question: bool,
+    /// Question types to draw from; empty allows every type
+    #[serde(default)]
+    pub allowed_question_types: Vec<QuestionType>,
+    /// Easy questions to open the session with, to build confidence; capped at `MAX_WARM_UP_QUESTIONS`
+    #[serde(default)]
+    pub warm_up_questions: usize,
+    /// Close the session by asking one of its earlier questions again, as a review
+    #[serde(default)]
+    pub cool_down_review: bool,
+    /// Percentage of a question's points lost for each hint taken on it
+    #[serde(default = "default_hint_penalty_percent")]
+    pub hint_penalty_percent: u8,
+    /// Seconds allowed for each question, counted from when it's first shown
+    #[serde(default)]
+    pub time_limit_per_question: Option<u32>,
+    /// Seconds allowed for the whole quiz, not counting time paused. An assessment's
+    /// `time_limit_seconds` takes precedence.
+    #[serde(default)]
+    pub time_limit_total: Option<u32>,
+    /// What becomes of an answer submitted after the time runs out
+    #[serde(default)]
+    pub late_answers: LateAnswer,
+    /// End the quiz early once the child has clearly mastered the questions, or is
+    /// clearly getting tired, rather than always asking `question_count`
+    #[serde(default)]
+    pub stop_when_mastered: bool,
+    /// Whether the quiz is scored, or is practice the child can't get wrong
+    #[serde(default)]
+    pub mode: QuizMode,
+}
+
+fn default_hint_penalty_percent() -> u8 {
+    DEFAULT_HINT_PENALTY_PERCENT
+}
+
+/// How an answer that arrives after its question's or the quiz's time ran out is recorded.
+/// Either way it earns nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LateAnswer {
+    /// Marked wrong, and the question comes up for review sooner
+    #[default]
+    MarkWrong,
+    /// Passed over without marking, leaving the question's review schedule alone
+    Skip,
+}
+
+/// Whether a quiz counts, or is just for practice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuizMode {
+    /// Answers are scored and count towards progress
+    #[default]
+    Assessed,
+    /// No points: a wrong answer leaves the question up to try again, with its explanation,
+    /// and only questions got right first time count towards progress
+    Practice,
+}
+
+/// The kind of quiz session, which changes what the child may do during it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionMode {
+    #[default]
+    Standard,
+    /// A timed mock test under invigilation: no pausing, questions fixed at the start, and
+    /// leaving the quiz is noted. Listed separately in history.
+    Assessment,
+    /// Spaced-repetition review of questions that are due to come up again
+    Review,
+    /// Siblings on one device taking turns at alternate questions, each scored separately
+    PassAndPlay,
+    /// Family members taking turns, each at their own key stage, towards one shared target
+    Coop,
+    /// Two players on one device answering each question in turn, scored against each other
+    HeadToHead,
+}
+
+impl SessionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionMode::Standard => "standard",
+            SessionMode::Assessment => "assessment",
+            SessionMode::Review => "review",
+            SessionMode::PassAndPlay => "pass_and_play",
+            SessionMode::Coop => "coop",
+            SessionMode::HeadToHead => "head_to_head",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "standard" => Some(SessionMode::Standard),
+            "assessment" => Some(SessionMode::Assessment),
+            "review" => Some(SessionMode::Review),
+            "pass_and_play" => Some(SessionMode::PassAndPlay),
+            "coop" => Some(SessionMode::Coop),
+            "head_to_head" => Some(SessionMode::HeadToHead),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityNoteKind {
+    Left,
+    Returned,
+}
+
+/// The child leaving or coming back to an assessment part way through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityNote {
+    pub kind: IntegrityNoteKind,
+    /// Question on screen at the time
+    pub question_index: usize,
+    pub occurred_at: DateTime<Utc>,
 }
 
 /// Quiz session state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuizSession {
-    pub id: Option<u32>,
-    pub profile_id: u32,
+    pub id: Option<SessionId>,
+    pub profile_id: ProfileId,
     pub config: QuizConfig,
     pub questions: Vec<Question>,
     pub answers: Vec<AnswerResult>,
@@ -932,6 +2832,53 @@ pub struct QuizSession {
     pub total_time_seconds: u32,
     pub is_paused: bool,
     pub pause_time: Option<DateTime<Utc>>,
+    /// Reading passages covering ranges of `questions`; empty for ordinary quizzes
+    #[serde(default)]
+    pub passage_blocks: Vec<PassageBlock>,
+    /// Position of the boss question in `questions`, if the session has one
+    #[serde(default)]
+    pub boss_question_index: Option<usize>,
+    /// Position of the closing review question in `questions`, if the session has one
+    #[serde(default)]
+    pub review_question_index: Option<usize>,
+    /// Hints taken so far on the current question
+    #[serde(default)]
+    pub current_hints: u32,
+    /// Wrong answers so far to the current question, in practice
+    #[serde(default)]
+    pub current_attempts: u32,
+    /// How quickly questions are being answered, to spot rushing
+    #[serde(default)]
+    pub timing: AnswerTiming,
+    /// A parent trying questions out: answers are marked, but nothing is recorded against
+    /// any profile
+    #[serde(default)]
+    pub sandbox: bool,
+    /// Last question recorded as shown in the replay timeline
+    #[serde(default)]
+    pub last_shown_index: Option<usize>,
+    #[serde(default)]
+    pub mode: SessionMode,
+    /// Times the child left an assessment and came back, for whoever reviews the result
+    #[serde(default)]
+    pub integrity_notes: Vec<IntegrityNote>,
+    /// Time spent paused, which doesn't count towards the whole-quiz time limit
+    #[serde(default)]
+    pub paused_seconds: u32,
+    /// Turn order in a pass-and-play, co-op or head-to-head quiz, where each player answers
+    /// every `players.len()`th question starting from their place (in head-to-head each
+    /// question is in the list once per player); empty for one child
+    #[serde(default)]
+    pub players: Vec<ProfileId>,
+    /// Right answers a co-op team is aiming for between them
+    #[serde(default)]
+    pub team_target: Option<u32>,
+    /// Why a "stop when mastered" quiz ended before its configured length, if it did
+    #[serde(default)]
+    pub stopped_early: Option<EarlyStop>,
+    /// Homework assignment the session was started for, so only it can count as an attempt
+    #[serde(default)]
+    pub assignment_id: Option<u32>,
 }
 
 impl QuizSession {
@@ -943,6 +2890,40 @@ impl QuizSession {
         self.questions.get(self.current_question_index)
     }
     
+    /// Whose turn it is: in a shared quiz the players take turns, otherwise it's always the
+    /// session's own profile
+    pub fn current_player(&self) -> ProfileId {
+        self.player_for(self.answers.len())
+    }
+    
+    /// Right answers so far from everyone in the session
+    pub fn team_correct(&self) -> u32 {
+        self.answers.iter().filter(|answer| answer.is_correct).count() as u32
+    }
+    
+    /// Turns at each question: one per player in head-to-head, where they all answer it
+    fn round_length(&self) -> usize {
+        match self.mode {
+            SessionMode::HeadToHead => self.players.len(),
+            _ => 1,
+        }
+    }
+    
+    /// How many players have had their turn at question `index` before this one, in
+    /// head-to-head; always 0 otherwise
+    fn round_turn(&self, index: usize) -> usize {
+        index % self.round_length()
+    }
+    
+    /// The player who answers question `index`
+    fn player_for(&self, index: usize) -> ProfileId {
+        if self.players.is_empty() {
+            self.profile_id
+        } else {
+            self.players[index % self.players.len()]
+        }
+    }
+    
     pub fn get_progress_percentage(&self) -> u8 {
         if self.questions.is_empty() {
             return 100;
@@ -951,17 +2932,169 @@ impl QuizSession {
         let progress = (self.current_question_index as f64 / self.questions.len() as f64 * 100.0) as u8;
         progress.min(100)
     }
+    
+    pub fn current_passage_block(&self) -> Option<&PassageBlock> {
+        self.passage_blocks.iter().find(|block| block.contains(self.current_question_index))
+    }
+    
+    /// Drop a question, keeping passage blocks and the boss question pointing at the right ones
+    fn remove_question(&mut self, index: usize) {
+        if index >= self.questions.len() {
+            return;
+        }
+        self.questions.remove(index);
+        
+        for block in &mut self.passage_blocks {
+            if block.contains(index) {
+                block.question_count -= 1;
+            } else if block.start_index > index {
+                block.start_index -= 1;
+            }
+        }
+        self.passage_blocks.retain(|block| block.question_count > 0);
+        
+        self.boss_question_index = match self.boss_question_index {
+            Some(boss) if boss == index => None,
+            Some(boss) if boss > index => Some(boss - 1),
+            boss => boss,
+        };
+        self.review_question_index = match self.review_question_index {
+            Some(review) if review == index => None,
+            Some(review) if review > index => Some(review - 1),
+            review => review,
+        };
+    }
+}
+
+/// What a hint shows the child
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HintReveal {
+    /// Wrong options to take away
+    EliminatedOptions { options: Vec<String> },
+    /// The first letter of the answer, or of each blank in position order
+    FirstLetters { letters: Vec<String> },
+    Text { text: String },
+}
+
+/// A hint revealed for the current question
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HintResult {
+    pub hint: HintReveal,
+    /// Hints taken on this question, including this one
+    pub hints_used: u32,
+    pub hints_remaining: u32,
+    /// Share of the question's points that will be lost, as a percentage
+    pub penalty_percent: u8,
+}
+
+/// Work out what the hint at `index` of `hints` shows for `question`
+fn reveal_hint(question: &Question, hints: &[Hint], index: usize) -> HintReveal {
+    match &hints[index] {
+        Hint::EliminateOptions { count } => {
+            let is_correct = |option: &String| match &question.correct_answer {
+                Answer::Text(correct) => option.trim().eq_ignore_ascii_case(correct.trim()),
+                Answer::Multiple(correct) => correct.iter().any(|correct| option.trim().eq_ignore_ascii_case(correct.trim())),
+                _ => false,
+            };
+            let wrong: Vec<String> = question.content.options.iter()
+                .flatten()
+                .filter(|option| !is_correct(option))
+                .cloned()
+                .collect();
+            // Carry on from options earlier hints took away, always leaving one wrong option
+            let already_eliminated: usize = hints[..index].iter()
+                .map(|hint| match hint {
+                    Hint::EliminateOptions { count } => *count,
+                    _ => 0,
+                })
+                .sum();
+            let removable = wrong.len().saturating_sub(1);
+            let start = already_eliminated.min(removable);
+            let end = (already_eliminated + count).min(removable);
+            HintReveal::EliminatedOptions { options: wrong[start..end].to_vec() }
+        }
+        Hint::FirstLetter => {
+            let first_letter = |text: &str| text.trim().chars().next().map(String::from).unwrap_or_default();
+            let letters = match (&question.content.blanks, &question.correct_answer) {
+                (Some(blanks), _) if !blanks.is_empty() => {
+                    let mut blanks = blanks.clone();
+                    blanks.sort_by_key(|blank| blank.position);
+                    blanks.iter().map(|blank| first_letter(&blank.expected_answer)).collect()
+                }
+                (_, Answer::Text(answer)) => vec![first_letter(answer)],
+                (_, Answer::Multiple(answers)) => answers.iter().map(|answer| first_letter(answer)).collect(),
+                _ => Vec::new(),
+            };
+            HintReveal::FirstLetters { letters }
+        }
+        Hint::Text { text } => HintReveal::Text { text: text.trim().to_string() },
+    }
+}
+
+/// A question failure the engine recovered from by swapping or dropping the question
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuizIncident {
+    pub id: u32,
+    pub session_id: SessionId,
+    pub profile_id: ProfileId,
+    pub question_id: Option<QuestionId>,
+    /// Where it failed: "retrieval" (showing the question) or "validation" (marking it)
+    pub stage: String,
+    pub error: String,
+    /// The question shown instead, or `None` if it was dropped
+    pub replacement_question_id: Option<QuestionId>,
+    pub occurred_at: DateTime<Utc>,
 }
 
 /// Answer result with validation and scoring
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnswerResult {
-    pub question_id: u32,
+    pub question_id: QuestionId,
     pub is_correct: bool,
     pub points: u32,
     pub correct_answer: Answer,
     pub explanation: Option<String>,
     pub time_taken: Option<u32>,
+    /// The session's boss question; `points` include the boss bonus when correct
+    #[serde(default)]
+    pub is_boss_question: bool,
+    /// Hints taken before answering; the penalty for them comes off in `calculate_score`
+    #[serde(default)]
+    pub hints_used: u32,
+    /// Per-blank marks for a cloze question, in blank position order; empty for other questions.
+    /// `is_correct` means every blank was right, and `points` are shared out per correct blank.
+    #[serde(default)]
+    pub blank_results: Vec<BlankResult>,
+    /// Share of an ordering question earned when it was only partly right, between 0 and
+    /// 1. `points` are already scaled by it, and it counts towards accuracy.
+    #[serde(default)]
+    pub partial_credit: Option<f32>,
+    /// Set when the answer came after the time ran out, saying how it was recorded
+    #[serde(default)]
+    pub late: Option<LateAnswer>,
+    /// The player who answered, in a pass-and-play quiz
+    #[serde(default)]
+    pub answered_by: Option<ProfileId>,
+    /// Goes at the question, this one included; more than 1 only in practice
+    #[serde(default)]
+    pub attempts: u32,
+    /// A wrong answer in practice: the question stays for another go. `correct_answer` and
+    /// each blank's `expected_answer` are left empty so the answer isn't given away.
+    #[serde(default)]
+    pub try_again: bool,
+}
+
+/// How one blank of a cloze question was answered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlankResult {
+    pub position: usize,
+    pub submitted: String,
+    pub expected_answer: String,
+    pub is_correct: bool,
+    /// Accepted despite a small spelling slip, so the child can be shown the right spelling
+    #[serde(default)]
+    pub near_miss: bool,
 }
 
 /// Final quiz score
@@ -973,9 +3106,15 @@ pub struct Score {
     pub total_points: u32,
     pub time_bonus: u32,
     pub streak_bonus: u32,
+    /// Points taken off for hints, already subtracted from `final_score`
+    #[serde(default)]
+    pub hint_penalty: u32,
     pub final_score: u32,
     pub performance_level: PerformanceLevel,
     pub achievements: Vec<String>,
+    /// Experience points earned, for the profile's level
+    #[serde(default)]
+    pub xp: u32,
 }
 
 /// Performance level based on accuracy
@@ -988,16 +3127,101 @@ pub enum PerformanceLevel {
     Poor,
 }
 
+/// One player's result in a pass-and-play quiz
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerScore {
+    pub profile_id: ProfileId,
+    pub score: Score,
+}
+
+/// Everyone's results from a pass-and-play quiz
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassAndPlaySummary {
+    pub session_id: SessionId,
+    /// In turn order
+    pub players: Vec<PlayerScore>,
+    /// Players with the best final score; more than one on a draw
+    pub winners: Vec<ProfileId>,
+    /// Questions the players got right between them, out of `total_questions`
+    pub combined_correct: u32,
+    pub total_questions: u32,
+}
+
+/// Who got one head-to-head question right
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadToHeadRound {
+    pub question_id: QuestionId,
+    /// Players who answered it correctly, in turn order
+    pub correct: Vec<ProfileId>,
+}
+
+/// The result of a head-to-head quiz
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeadToHeadSummary {
+    pub session_id: SessionId,
+    /// In turn order, each scored on the same questions
+    pub players: Vec<PlayerScore>,
+    /// The player with more right answers, or more points when those are level; none on a draw
+    pub winner: Option<ProfileId>,
+    /// In the order the questions were asked
+    pub rounds: Vec<HeadToHeadRound>,
+}
+
+/// A family member joining a co-op quiz, with the key stage their questions come from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoopPlayer {
+    pub profile_id: ProfileId,
+    pub key_stage: KeyStage,
+}
+
+/// What one player has added to a co-op team's score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerContribution {
+    pub profile_id: ProfileId,
+    pub questions_answered: u32,
+    pub correct_answers: u32,
+}
+
+/// A co-op team's shared score against its target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoopSummary {
+    pub session_id: SessionId,
+    /// Right answers from everyone together
+    pub team_correct: u32,
+    pub target: u32,
+    pub target_reached: bool,
+    pub total_questions: u32,
+    /// In turn order
+    pub contributions: Vec<PlayerContribution>,
+}
+
 /// Quiz progress information (sanitized for security)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuizProgress {
-    pub session_id: u32,
+    pub session_id: SessionId,
     pub current_question_index: usize,
     pub total_questions: usize,
     pub answered_questions: usize,
     pub is_completed: bool,
     pub time_elapsed: u32,
     pub is_paused: bool,
+    pub mode: SessionMode,
+    /// Seconds left on the whole quiz, for an assessment or a quiz with `time_limit_total`
+    pub seconds_remaining: Option<u32>,
+    /// Seconds left to answer the current question, for a quiz with `time_limit_per_question`
+    #[serde(default)]
+    pub question_seconds_remaining: Option<u32>,
+    /// Whose turn it is in a pass-and-play, co-op or head-to-head quiz
+    #[serde(default)]
+    pub current_player: Option<ProfileId>,
+    /// Right answers so far and the target, in a co-op quiz
+    #[serde(default)]
+    pub team_correct: Option<u32>,
+    #[serde(default)]
+    pub team_target: Option<u32>,
+    /// Why the quiz ended before its configured length, if it did
+    #[serde(default)]
+    pub stopped_early: Option<EarlyStop>,
 }
 
 #[cfg(test)]
@@ -1014,7 +3238,7 @@ mod tests {
         let db_service = DatabaseService::new(&db_path).unwrap();
         db_service.initialize().unwrap();
         
-        let security_service = SecurityService::new().unwrap();
+        let security_service = Arc::new(SecurityService::new().unwrap());
         let content_dir = temp_dir.path().join("content");
         std::fs::create_dir_all(&content_dir).unwrap();
         
@@ -1029,6 +3253,56 @@ mod tests {
         (quiz_engine, temp_dir)
     }
 
+    fn create_test_session(session_id: SessionId, completed: bool) -> QuizSession {
+        QuizSession {
+            id: Some(session_id),
+            profile_id: ProfileId(1),
+            config: QuizConfig {
+                subject: "mathematics".to_string(),
+                key_stage: KeyStage::KS1,
+                question_count: 5,
+                difficulty_range: None,
+                time_limit_seconds: None,
+                randomize_questions: true,
+                randomize_answers: true,
+                max_consecutive_same_tag: None,
+                boss_question: false,
+                warm_up_questions: 0,
+                cool_down_review: false,
+                hint_penalty_percent: DEFAULT_HINT_PENALTY_PERCENT,
+                time_limit_per_question: None,
+                time_limit_total: None,
+                late_answers: LateAnswer::default(),
+                stop_when_mastered: false,
+                mode: QuizMode::default(),
+                allowed_question_types: Vec::new(),
+            },
+            questions: Vec::new(),
+            answers: Vec::new(),
+            current_question_index: 0,
+            started_at: Utc::now(),
+            completed_at: if completed { Some(Utc::now()) } else { None },
+            total_time_seconds: 0,
+            is_paused: false,
+            pause_time: None,
+            passage_blocks: Vec::new(),
+            boss_question_index: None,
+            review_question_index: None,
+            current_hints: 0,
+            current_attempts: 0,
+            timing: AnswerTiming::default(),
+            sandbox: false,
+            last_shown_index: None,
+            mode: SessionMode::Standard,
+            integrity_notes: Vec::new(),
+            paused_seconds: 0,
+            players: Vec::new(),
+            team_target: None,
+            stopped_early: None,
+            assignment_id: None,
+        }
+    }
+
     #[test]
     fn test_quiz_engine_creation() {
         let (_quiz_engine, _temp_dir) = create_test_quiz_engine();
@@ -1054,21 +3328,145 @@ mod tests {
     }
 
     #[test]
-    fn test_levenshtein_distance() {
-        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
-        
-        assert_eq!(quiz_engine.levenshtein_distance("", ""), 0);
-        assert_eq!(quiz_engine.levenshtein_distance("hello", "hello"), 0);
-        assert_eq!(quiz_engine.levenshtein_distance("hello", "helo"), 1);
-        assert_eq!(quiz_engine.levenshtein_distance("cat", "dog"), 3);
-    }
+    fn test_short_answer_marking() {
+        use crate::models::{QuestionContent, ShortAnswerConfig, SHORT_ANSWER_KEY};
 
-    #[test]
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let short_answer = |answer: &str, config: Option<ShortAnswerConfig>| Question::new(
+            1,
+            KeyStage::KS2,
+            QuestionType::ShortAnswer,
+            QuestionContent {
+                text: "Answer the question".to_string(),
+                options: None,
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: config.map(|config| {
+                    HashMap::from([(SHORT_ANSWER_KEY.to_string(), serde_json::to_value(config).unwrap())])
+                }),
+            },
+            Answer::Text(answer.to_string()),
+        );
+        let marks = |question: &Question, submitted: &str| {
+            quiz_engine.check_answer_correctness(question, &Answer::Text(submitted.to_string())).unwrap()
+        };
+
+        let capital = short_answer("Paris", None);
+        assert!(marks(&capital, "  paris. "));
+        assert!(marks(&capital, "Pariss"));
+        assert!(!marks(&capital, "Rome"));
+        assert!(!marks(&capital, ""));
+
+        let cathedral = short_answer("St. Paul's", None);
+        assert!(marks(&cathedral, "st pauls"));
+
+        // Numbers in words and sums of money match however they're written
+        assert!(marks(&short_answer("7", None), "Seven"));
+        assert!(!marks(&short_answer("17", None), "seventy"));
+        assert!(marks(&short_answer("£1.50", None), "150p"));
+
+        let big = short_answer("enormous", Some(ShortAnswerConfig {
+            accepted_answers: vec!["huge".to_string(), "gigantic".to_string()],
+            max_edit_distance: Some(0),
+            case_sensitive: false,
+        }));
+        assert!(marks(&big, "Huge"));
+        assert!(marks(&big, "gigantic!"));
+        assert!(!marks(&big, "enormus"));
+
+        let cells = short_answer("DNA", Some(ShortAnswerConfig { case_sensitive: true, ..ShortAnswerConfig::default() }));
+        assert!(marks(&cells, "DNA"));
+        assert!(!marks(&cells, "dna"));
+
+        let number = short_answer("12", None);
+        assert!(marks(&number, "12.0"));
+        assert!(!marks(&number, "13"));
+    }
+
+    #[test]
+    fn test_cloze_blanks_get_partial_credit() {
+        use crate::models::QuestionContent;
+
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let blank = |position: usize, expected: &str, alternatives: Option<Vec<&str>>| BlankConfig {
+            position,
+            expected_answer: expected.to_string(),
+            case_sensitive: false,
+            accept_alternatives: alternatives.map(|alternatives| alternatives.into_iter().map(String::from).collect()),
+            max_edit_distance: None,
+        };
+        let marks_blanks = |question: &Question, submitted: &[&str]| {
+            quiz_engine.mark_blanks(question, &Answer::Multiple(submitted.iter().map(|s| s.to_string()).collect()))
+        };
+        let cloze = Question::new(
+            1,
+            KeyStage::KS2,
+            QuestionType::FillBlank,
+            QuestionContent {
+                text: "The ___ sat on the ___ and ate ___ biscuits.".to_string(),
+                options: None,
+                story: None,
+                image_url: None,
+                hotspots: None,
+                // Listed out of order; answers follow blank position
+                blanks: Some(vec![
+                    blank(2, "3", None),
+                    blank(0, "cat", Some(vec!["kitten"])),
+                    blank(1, "mat", None),
+                ]),
+                additional_data: None,
+            },
+            Answer::Multiple(vec!["cat".to_string(), "mat".to_string(), "3".to_string()]),
+        );
+        let marks = |submitted: &[&str]| {
+            quiz_engine.mark_blanks(&cloze, &Answer::Multiple(submitted.iter().map(|s| s.to_string()).collect()))
+        };
+
+        let all_right = marks(&["Kitten", " mat ", "3.0"]);
+        assert_eq!(all_right.len(), 3);
+        assert!(all_right.iter().all(|blank| blank.is_correct));
+
+        let partly_right = marks(&["dog", "mat"]);
+        assert_eq!(partly_right.iter().map(|blank| blank.is_correct).collect::<Vec<_>>(), vec![false, true, false]);
+        assert_eq!(partly_right[2].position, 2);
+        assert_eq!(partly_right[2].expected_answer, "3");
+
+        // Single answers and single-blank questions keep the old marking
+        assert!(quiz_engine.mark_blanks(&cloze, &Answer::Text("cat".to_string())).is_empty());
+
+        // Each blank has its own case sensitivity and spelling tolerance
+        let mut names = cloze.clone();
+        names.content.blanks = Some(vec![
+            BlankConfig { case_sensitive: true, ..blank(0, "Paris", None) },
+            BlankConfig { max_edit_distance: Some(2), ..blank(1, "necessary", None) },
+            blank(2, "elephants", None),
+        ]);
+        let results = marks_blanks(&names, &["paris", "neccesary", "elefants"]);
+        assert_eq!(results.iter().map(|blank| blank.is_correct).collect::<Vec<_>>(), vec![false, true, false]);
+        assert!(results[1].near_miss);
+        let results = marks_blanks(&names, &["Paris", "necessary", "Elephant"]);
+        assert!(results.iter().all(|blank| blank.is_correct));
+        assert_eq!(results.iter().map(|blank| blank.near_miss).collect::<Vec<_>>(), vec![false, false, true]);
+    }
+
+    #[test]
+    fn test_levenshtein_distance() {
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        
+        assert_eq!(quiz_engine.levenshtein_distance("", ""), 0);
+        assert_eq!(quiz_engine.levenshtein_distance("hello", "hello"), 0);
+        assert_eq!(quiz_engine.levenshtein_distance("hello", "helo"), 1);
+        assert_eq!(quiz_engine.levenshtein_distance("cat", "dog"), 3);
+    }
+
+    #[test]
     fn test_points_calculation() {
         let (quiz_engine, _temp_dir) = create_test_quiz_engine();
         
         let question = Question {
-            id: Some(1),
+            id: Some(QuestionId(1)),
             subject_id: 1,
             key_stage: KeyStage::KS1,
             question_type: QuestionType::MultipleChoice,
@@ -1086,10 +3484,15 @@ mod tests {
             tags: Vec::new(),
             assets: None,
             created_at: None,
+            source: None,
+            explanation: None,
         };
         
         let points = quiz_engine.calculate_points(&question);
         assert_eq!(points, 20); // Base 20 for difficulty 3, no type bonus for multiple choice
+
+        let ks3_question = Question { key_stage: KeyStage::KS3, ..question };
+        assert_eq!(quiz_engine.calculate_points(&ks3_question), 30); // KS3 bonus on top
     }
 
     #[test]
@@ -1098,36 +3501,68 @@ mod tests {
         
         let answers = vec![
             AnswerResult {
-                question_id: 1,
+                question_id: QuestionId(1),
                 is_correct: true,
                 points: 10,
                 correct_answer: Answer::Text("A".to_string()),
                 explanation: None,
                 time_taken: None,
+                is_boss_question: false,
+                hints_used: 0,
+                blank_results: Vec::new(),
+                partial_credit: None,
+                late: None,
+                answered_by: None,
+                attempts: 1,
+                try_again: false,
             },
             AnswerResult {
-                question_id: 2,
+                question_id: QuestionId(2),
                 is_correct: true,
                 points: 10,
                 correct_answer: Answer::Text("B".to_string()),
                 explanation: None,
                 time_taken: None,
+                is_boss_question: false,
+                hints_used: 0,
+                blank_results: Vec::new(),
+                partial_credit: None,
+                late: None,
+                answered_by: None,
+                attempts: 1,
+                try_again: false,
             },
             AnswerResult {
-                question_id: 3,
+                question_id: QuestionId(3),
                 is_correct: false,
                 points: 0,
                 correct_answer: Answer::Text("C".to_string()),
                 explanation: None,
                 time_taken: None,
+                is_boss_question: false,
+                hints_used: 0,
+                blank_results: Vec::new(),
+                partial_credit: None,
+                late: None,
+                answered_by: None,
+                attempts: 1,
+                try_again: false,
             },
             AnswerResult {
-                question_id: 4,
+                question_id: QuestionId(4),
                 is_correct: true,
                 points: 10,
                 correct_answer: Answer::Text("D".to_string()),
                 explanation: None,
                 time_taken: None,
+                is_boss_question: false,
+                hints_used: 0,
+                blank_results: Vec::new(),
+                partial_credit: None,
+                late: None,
+                answered_by: None,
+                attempts: 1,
+                try_again: false,
             },
         ];
         
@@ -1137,4 +3572,1119 @@ mod tests {
         let streak_bonus = quiz_engine.calculate_streak_bonus(&answers);
         assert_eq!(streak_bonus, 0); // No bonus for streak < 3
     }
+
+    #[test]
+    fn test_interleave_by_tag() {
+        let randomizer = QuestionRandomizer::new();
+        let tagged = |id: u32, tag: &str| {
+            let mut question = Question::new(
+                1,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                crate::models::QuestionContent {
+                    text: format!("Question {}", id),
+                    options: None,
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("a".to_string()),
+            ).with_tags(vec![tag.to_string()]);
+            question.id = Some(QuestionId(id));
+            question
+        };
+        let tags = |questions: &[Question]| questions.iter().map(|q| q.tags[0].clone()).collect::<Vec<_>>();
+
+        let mut questions = vec![
+            tagged(1, "fractions"), tagged(2, "fractions"), tagged(3, "Fractions"),
+            tagged(4, "fractions"), tagged(5, "shapes"), tagged(6, "time"),
+        ];
+        randomizer.interleave_by_tag(&mut questions, 2);
+        assert_eq!(questions.iter().map(|q| q.id.unwrap().0).collect::<Vec<_>>(), vec![1, 2, 5, 3, 4, 6]);
+
+        // One at a time alternates topics for as long as there is something else to pick
+        let mut questions = vec![tagged(1, "a"), tagged(2, "a"), tagged(3, "a"), tagged(4, "b")];
+        randomizer.interleave_by_tag(&mut questions, 1);
+        assert_eq!(tags(&questions), vec!["a", "b", "a", "a"]);
+    }
+
+    #[test]
+    fn test_session_snapshot_round_trip() {
+        let (quiz_engine, temp_dir) = create_test_quiz_engine();
+        let snapshot_path = temp_dir.path().join("sessions.json");
+        
+        quiz_engine.save_quiz_session(&create_test_session(SessionId(3), false)).unwrap();
+        quiz_engine.save_quiz_session(&create_test_session(SessionId(4), true)).unwrap();
+        
+        // Only in-flight sessions are written
+        assert_eq!(quiz_engine.save_sessions_snapshot(&snapshot_path).unwrap(), 1);
+        
+        let (restored_engine, _restored_dir) = create_test_quiz_engine();
+        assert_eq!(restored_engine.restore_sessions_snapshot(&snapshot_path).unwrap(), 1);
+        assert!(!snapshot_path.exists());
+        
+        let session = restored_engine.load_quiz_session(SessionId(3)).unwrap();
+        assert!(session.is_paused);
+        assert_eq!(*restored_engine.next_session_id.lock().unwrap(), 4);
+    }
+    #[test]
+    fn test_boss_question_ends_session() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for (text, difficulty) in [("1 + 1", 1), ("2 + 2", 1), ("3 + 3", 1), ("12 x 12", 2)] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            ).with_difficulty(difficulty)).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 3;
+        config.difficulty_range = Some((1, 1));
+        config.boss_question = true;
+        let session = quiz_engine.start_quiz_session(ProfileId(1), config).unwrap();
+        let session_id = session.id.unwrap();
+
+        assert_eq!(session.questions.len(), 4);
+        assert_eq!(session.boss_question_index, Some(3));
+        assert_eq!(session.questions[3].difficulty_level, 2);
+
+        for _ in 0..3 {
+            let result = quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 5).unwrap();
+            assert!(!result.is_boss_question);
+        }
+        let boss = quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 5).unwrap();
+        assert!(boss.is_boss_question);
+        assert_eq!(boss.points, 15 + BOSS_BONUS_POINTS);
+    }
+
+    #[test]
+    fn test_answer_result_carries_question_explanation() {
+        use crate::models::{QuestionContent, EXPLANATION_KEY};
+
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let question = |additional_data: Option<HashMap<String, serde_json::Value>>| Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: "What is 2 + 3?".to_string(),
+                options: Some(vec!["5".to_string(), "6".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data,
+            },
+            Answer::Text("5".to_string()),
+        );
+
+        let explained = quiz_engine.content_manager
+            .add_question(question(None).with_explanation("  Count on three from two. "))
+            .unwrap();
+        // Older content kept the explanation in additional_data
+        let legacy = quiz_engine.content_manager
+            .add_question(question(Some(HashMap::from([(EXPLANATION_KEY.to_string(), serde_json::json!("Two and three make five."))]))))
+            .unwrap();
+        let plain = quiz_engine.content_manager.add_question(question(None)).unwrap();
+
+        assert_eq!(
+            quiz_engine.content_manager.get_question_by_id(explained).unwrap().explanation.as_deref(),
+            Some("Count on three from two.")
+        );
+        let right = quiz_engine.validate_answer(explained, Answer::Text("5".to_string())).unwrap();
+        assert_eq!(right.explanation.as_deref(), Some("Correct! Count on three from two."));
+        let wrong = quiz_engine.validate_answer(legacy, Answer::Text("6".to_string())).unwrap();
+        assert_eq!(wrong.explanation.as_deref(), Some("Not quite. Two and three make five."));
+        let generic = quiz_engine.validate_answer(plain, Answer::Text("5".to_string())).unwrap();
+        assert_eq!(generic.explanation.as_deref(), Some("Correct! Well done!"));
+
+        // Editing a question can take its explanation away
+        let mut edited = quiz_engine.content_manager.get_question_by_id(explained).unwrap();
+        edited.explanation = Some(" ".to_string());
+        quiz_engine.content_manager.update_question(explained, edited).unwrap();
+        assert!(quiz_engine.content_manager.get_question_by_id(explained).unwrap().explanation.is_none());
+    }
+
+    #[test]
+    fn test_hints_are_revealed_in_turn_and_cost_points() {
+        use crate::models::{BlankConfig, QuestionContent, HINTS_KEY};
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let multiple_choice = quiz_engine.content_manager.add_question(Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: "What is 2 + 3?".to_string(),
+                options: Some(vec!["4".to_string(), "5".to_string(), "6".to_string(), "7".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("5".to_string()),
+        )).unwrap();
+        let hints = serde_json::json!([{ "kind": "text", "text": "It lives in water." }, { "kind": "first_letter" }]);
+        let fill_blank = quiz_engine.content_manager.add_question(Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::FillBlank,
+            QuestionContent {
+                text: "A ___ swims in the sea.".to_string(),
+                options: None,
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: Some(vec![BlankConfig {
+                    position: 0,
+                    expected_answer: "fish".to_string(),
+                    case_sensitive: false,
+                    accept_alternatives: None,
+                    max_edit_distance: None,
+                }]),
+                additional_data: Some(HashMap::from([(HINTS_KEY.to_string(), hints)])),
+            },
+            Answer::Text("fish".to_string()),
+        )).unwrap();
+
+        let questions = vec![
+            quiz_engine.content_manager.get_question_by_id(multiple_choice).unwrap(),
+            quiz_engine.content_manager.get_question_by_id(fill_blank).unwrap(),
+        ];
+        let session = quiz_engine.start_fixed_session(ProfileId(1), "Hints", None, questions, SessionMode::Standard).unwrap();
+        let session_id = session.id.unwrap();
+
+        // Without hints of its own, a multiple choice question loses two wrong options
+        let hint = quiz_engine.request_hint(session_id).unwrap();
+        match hint.hint {
+            HintReveal::EliminatedOptions { options } => {
+                assert_eq!(options.len(), 2);
+                assert!(!options.contains(&"5".to_string()));
+            }
+            other => panic!("Unexpected hint {:?}", other),
+        }
+        assert_eq!((hint.hints_used, hint.hints_remaining, hint.penalty_percent), (1, 0, 25));
+        assert!(quiz_engine.request_hint(session_id).is_err());
+        let answer = quiz_engine.submit_answer(session_id, Answer::Text("5".to_string()), 5).unwrap();
+        assert_eq!(answer.hints_used, 1);
+
+        // Written hints come in order, and the count starts again on each question
+        assert!(matches!(quiz_engine.request_hint(session_id).unwrap().hint, HintReveal::Text { .. }));
+        match quiz_engine.request_hint(session_id).unwrap().hint {
+            HintReveal::FirstLetters { letters } => assert_eq!(letters, vec!["f".to_string()]),
+            other => panic!("Unexpected hint {:?}", other),
+        }
+        quiz_engine.submit_answer(session_id, Answer::Text("fish".to_string()), 5).unwrap();
+
+        let session = quiz_engine.load_quiz_session(session_id).unwrap();
+        let score = quiz_engine.calculate_score(&session).unwrap();
+        let expected_penalty = session.answers[0].points / 4 + session.answers[1].points / 2;
+        assert_eq!(score.hint_penalty, expected_penalty);
+        assert_eq!(score.final_score, score.total_points + score.time_bonus + score.streak_bonus - expected_penalty);
+    }
+
+    #[test]
+    fn test_warm_up_and_cool_down_review() {
+        use crate::models::QuestionContent;
+
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for (text, difficulty) in [("10 x 10", 3), ("1 + 1", 1), ("11 x 11", 3), ("2 + 1", 2), ("12 x 12", 3)] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            ).with_difficulty(difficulty)).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 5;
+        config.difficulty_range = None;
+        config.warm_up_questions = 5;
+        config.cool_down_review = true;
+        let session = quiz_engine.start_quiz_session(ProfileId(1), config).unwrap();
+
+        // Capped at two warm-ups, easiest first
+        let difficulties: Vec<u8> = session.questions.iter().map(|question| question.difficulty_level).collect();
+        assert_eq!(difficulties[..5].iter().filter(|&&level| level < 3).count(), 2);
+        assert!(difficulties[..2].iter().all(|&level| level < 3));
+
+        // The review repeats one of the main questions, not a warm-up
+        assert_eq!(session.questions.len(), 6);
+        assert_eq!(session.review_question_index, Some(5));
+        let review = &session.questions[5];
+        assert_eq!(review.difficulty_level, 3);
+        assert!(session.questions[2..5].iter().any(|question| question.id == review.id));
+    }
+
+    #[test]
+    fn test_sandbox_preview_is_not_recorded_against_a_profile() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let question_id = quiz_engine.content_manager.add_question(Question::new(
+            1,
+            KeyStage::KS2,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: "7 x 8".to_string(),
+                options: Some(vec!["56".to_string(), "54".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("56".to_string()),
+        )).unwrap();
+        let question = quiz_engine.content_manager.get_question_by_id(question_id).unwrap();
+
+        assert!(quiz_engine.start_sandbox_preview("Question preview", Vec::new()).is_err());
+        let session = quiz_engine.start_sandbox_preview("Question preview", vec![question]).unwrap();
+        let session_id = session.id.unwrap();
+        assert!(session.sandbox);
+        assert_eq!(session.profile_id, SANDBOX_PROFILE_ID);
+        assert_eq!(session.config.key_stage, KeyStage::KS2);
+        assert_eq!(quiz_engine.get_recording_profile_id(session_id).unwrap(), None);
+
+        let result = quiz_engine.submit_answer(session_id, Answer::Text("56".to_string()), 3).unwrap();
+        assert!(result.is_correct);
+        assert!(quiz_engine.get_quiz_progress(session_id).unwrap().is_completed);
+    }
+
+    #[test]
+    fn test_hotspot_clicks_are_hit_tested_against_regions() {
+        use crate::models::{Coordinate, HotspotConfig, Point, QuestionContent, HOTSPOT_KEY};
+
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let hotspot = |config: Option<HotspotConfig>, answer: Answer| Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::Hotspot,
+            QuestionContent {
+                text: "Click on the triangle".to_string(),
+                options: None,
+                story: None,
+                image_url: Some("shapes.png".to_string()),
+                hotspots: Some(vec![Coordinate { x: 100.0, y: 100.0, width: Some(40.0), height: Some(40.0), label: None }]),
+                blanks: None,
+                additional_data: config.map(|config| {
+                    HashMap::from([(HOTSPOT_KEY.to_string(), serde_json::to_value(config).unwrap())])
+                }),
+            },
+            answer,
+        );
+        let clicks = |question: &Question, x: f64, y: f64| {
+            quiz_engine.check_answer_correctness(question, &Answer::Hotspot { x, y }).unwrap()
+        };
+
+        // Without regions, the answer's boxes (centred on the point) are the target
+        let legacy = hotspot(None, Answer::Coordinates(vec![
+            Coordinate { x: 100.0, y: 100.0, width: Some(40.0), height: Some(40.0), label: None },
+        ]));
+        assert!(clicks(&legacy, 118.0, 82.0));
+        assert!(clicks(&legacy, 135.0, 100.0));
+        assert!(!clicks(&legacy, 145.0, 100.0));
+
+        let regions = hotspot(Some(HotspotConfig {
+            regions: vec![
+                HotspotRegion::Circle { x: 300.0, y: 300.0, radius: 30.0 },
+                HotspotRegion::Rect { x: 0.0, y: 400.0, width: 100.0, height: 50.0 },
+                HotspotRegion::Polygon { points: vec![
+                    Point { x: 500.0, y: 0.0 },
+                    Point { x: 600.0, y: 100.0 },
+                    Point { x: 400.0, y: 100.0 },
+                ] },
+            ],
+            tolerance: 5.0,
+        }), Answer::Hotspot { x: 300.0, y: 300.0 });
+        assert!(clicks(&regions, 320.0, 310.0));
+        assert!(clicks(&regions, 334.0, 300.0));
+        assert!(!clicks(&regions, 340.0, 300.0));
+        assert!(clicks(&regions, 50.0, 425.0));
+        assert!(clicks(&regions, 104.0, 450.0));
+        assert!(clicks(&regions, 500.0, 60.0));
+        assert!(!clicks(&regions, 420.0, 20.0));
+        assert!(!clicks(&regions, f64::NAN, 300.0));
+        assert!(quiz_engine.check_answer_correctness(&regions, &Answer::Text("triangle".to_string())).is_err());
+
+        let bad_polygon = hotspot(Some(HotspotConfig {
+            regions: vec![HotspotRegion::Polygon { points: vec![Point { x: 0.0, y: 0.0 }] }],
+            tolerance: 5.0,
+        }), Answer::Hotspot { x: 0.0, y: 0.0 });
+        assert!(quiz_engine.content_manager.validate_question(&bad_polygon).is_err());
+        assert!(quiz_engine.content_manager.validate_question(&regions).is_ok());
+    }
+
+    #[test]
+    fn test_phrasings_vary_between_sessions_and_are_recorded() {
+        use crate::models::{QuestionContent, PHRASINGS_KEY};
+        use crate::services::QuestionQualityService;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let profile_id = quiz_engine.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                [Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+        let phrasings = vec!["What is seven times eight?".to_string(), "  What is 7 × 8?".to_string()];
+        let question_id = quiz_engine.content_manager.add_question(Question::new(
+            1,
+            KeyStage::KS2,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: "What is 7 × 8?".to_string(),
+                options: Some(vec!["56".to_string(), "54".to_string()]),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: Some(HashMap::from([(PHRASINGS_KEY.to_string(), serde_json::json!(phrasings))])),
+            },
+            Answer::Text("56".to_string()),
+        )).unwrap();
+        let question = quiz_engine.content_manager.get_question_by_id(question_id).unwrap();
+        // The repeated wording is dropped
+        assert_eq!(question.content.phrasings().unwrap().len(), 2);
+
+        let mut shown = Vec::new();
+        for answer in ["56", "54", "56"] {
+            let session = quiz_engine.start_fixed_session(profile_id, "Times tables", None, vec![question.clone()], SessionMode::Standard).unwrap();
+            let session_id = session.id.unwrap();
+            let text = quiz_engine.get_current_question(session_id).unwrap().unwrap().content.text;
+            // The same wording stays on screen until the question is answered
+            assert_eq!(quiz_engine.get_current_question(session_id).unwrap().unwrap().content.text, text);
+            quiz_engine.submit_answer(session_id, Answer::Text(answer.to_string()), 3).unwrap();
+            shown.push(text);
+        }
+        assert_ne!(shown[0], shown[1]);
+        assert_ne!(shown[1], shown[2]);
+
+        let stats = QuestionQualityService::new(quiz_engine.db_manager.clone()).get_phrasing_stats(question_id).unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats.iter().map(|stats| stats.times_shown).sum::<u32>(), 3);
+        assert_eq!(stats.iter().map(|stats| stats.times_correct).sum::<u32>(), 2);
+        let second = stats.iter().find(|stats| stats.text == shown[1]).unwrap();
+        assert_eq!((second.times_answered, second.times_correct), (1, 0));
+    }
+
+    #[test]
+    fn test_question_preview_matches_what_a_quiz_shows() {
+        use crate::models::{Asset, AssetType, QuestionContent};
+
+        let (quiz_engine, temp_dir) = create_test_quiz_engine();
+        let image_path = temp_dir.path().join("clock.png");
+        std::fs::write(&image_path, b"png").unwrap();
+        let image_path = image_path.to_string_lossy().into_owned();
+
+        let items: Vec<String> = ["one", "two", "three", "four"].iter().map(|item| item.to_string()).collect();
+        let mut question = Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::Ordering,
+            QuestionContent {
+                text: "Put the numbers in order".to_string(),
+                options: Some(items.clone()),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Ordering { order: items.clone() },
+        ).with_tags(vec!["counting".to_string()]);
+        question.assets = Some(vec![Asset {
+            id: None,
+            question_id: QuestionId(0),
+            asset_type: AssetType::Image,
+            file_path: image_path.clone(),
+            alt_text: Some("A clock".to_string()),
+            file_size: None,
+            created_at: None,
+        }]);
+
+        let preview = quiz_engine.render_question_preview(question.clone()).unwrap();
+        assert!(preview.id.is_none());
+        assert!(preview.tags.is_empty());
+        assert_ne!(preview.content.options.as_ref(), Some(&items));
+        assert!(matches!(&preview.correct_answer, Answer::Ordering { order } if order == &items));
+        let assets = preview.assets.unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].file_size, Some(3));
+
+        // Problems that would stop the question being saved stop the preview too
+        let mut no_answer = question.clone();
+        no_answer.correct_answer = Answer::Text("one".to_string());
+        assert!(quiz_engine.render_question_preview(no_answer).is_err());
+        let mut missing_asset = question;
+        missing_asset.assets.as_mut().unwrap()[0].file_path = format!("{}.missing", image_path);
+        assert!(quiz_engine.render_question_preview(missing_asset).is_err());
+    }
+
+    #[test]
+    fn test_selection_audit_finds_no_bias_in_question_picks() {
+        use crate::models::QuestionContent;
+
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        for index in 0..30u8 {
+            let mut question = Question::new(
+                1,
+                KeyStage::KS2,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: format!("{} + 1", index),
+                    options: Some(vec![(index + 1).to_string(), index.to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text((index + 1).to_string()),
+            );
+            question.difficulty_level = index % 3 + 1;
+            question.tags = vec![format!("group_{}", index % 5)];
+            quiz_engine.content_manager.add_question(question).unwrap();
+        }
+
+        let config = QuizConfig {
+            subject: "mathematics".to_string(),
+            key_stage: KeyStage::KS2,
+            question_count: 5,
+            difficulty_range: None,
+            time_limit_seconds: None,
+            randomize_questions: true,
+            randomize_answers: false,
+            max_consecutive_same_tag: None,
+            boss_question: false,
+            warm_up_questions: 0,
+            cool_down_review: false,
+            hint_penalty_percent: DEFAULT_HINT_PENALTY_PERCENT,
+            time_limit_per_question: None,
+            time_limit_total: None,
+            late_answers: LateAnswer::default(),
+            stop_when_mastered: false,
+            mode: QuizMode::default(),
+            allowed_question_types: Vec::new(),
+        };
+        assert!(quiz_engine.audit_selection(&config, 0).is_err());
+
+        let audit = quiz_engine.audit_selection(&config, 400).unwrap();
+        assert_eq!(audit.pool_size, 30);
+        assert_eq!(audit.questions_selected, 2000);
+        assert_eq!(audit.by_difficulty.len(), 3);
+        assert_eq!(audit.by_tag.len(), 5);
+        assert!(audit.never_selected.is_empty());
+        assert!((audit.mean_id_percentile - 0.5).abs() < 0.05, "{:?}", audit);
+        assert!(!audit.biased, "{:?}", audit);
+    }
+
+    #[test]
+    fn test_unmarkable_question_is_replaced() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let profile_id = quiz_engine.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                rusqlite::params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for text in ["1 + 1", "2 + 2"] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 1;
+        let session = quiz_engine.start_quiz_session(profile_id, config).unwrap();
+        let session_id = session.id.unwrap();
+        let broken_id = session.questions[0].id.unwrap();
+
+        // The question is purged mid-session, so marking it fails
+        quiz_engine.content_manager.delete_question(broken_id).unwrap();
+        quiz_engine.content_manager.purge_retired_questions(chrono::Duration::zero()).unwrap();
+        let error = quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 5).unwrap_err();
+        assert!(matches!(&error, AppError::QuestionReplaced(message) if message == QUESTION_REPLACED_MESSAGE));
+
+        let replacement = quiz_engine.get_current_question(session_id).unwrap().unwrap();
+        assert_ne!(replacement.id, Some(broken_id));
+        assert!(quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 5).unwrap().is_correct);
+
+        let incidents = quiz_engine.get_incidents(10).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].question_id, Some(broken_id));
+        assert_eq!(incidents[0].stage, "validation");
+        assert_eq!(incidents[0].replacement_question_id, replacement.id);
+    }
+
+    #[test]
+    fn test_assessment_is_locked_down() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let profile_id = quiz_engine.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                rusqlite::params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for text in ["1 + 1", "2 + 2", "3 + 3"] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 2;
+        assert!(quiz_engine.start_assessment_session(profile_id, config.clone()).is_err());
+        config.time_limit_seconds = Some(600);
+        let session = quiz_engine.start_assessment_session(profile_id, config).unwrap();
+        let session_id = session.id.unwrap();
+        assert_eq!(session.mode, SessionMode::Assessment);
+        assert!(quiz_engine.pause_quiz(session_id).is_err());
+
+        quiz_engine.record_focus_change(session_id, false).unwrap();
+        quiz_engine.record_focus_change(session_id, true).unwrap();
+        quiz_engine.record_focus_change(session_id, true).unwrap();
+        quiz_engine.record_focus_change(session_id, false).unwrap();
+
+        // A broken question is dropped rather than swapped for a new one
+        let broken_id = session.questions[0].id.unwrap();
+        quiz_engine.content_manager.delete_question(broken_id).unwrap();
+        quiz_engine.content_manager.purge_retired_questions(chrono::Duration::zero()).unwrap();
+        assert!(quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 5).is_err());
+        let progress = quiz_engine.get_quiz_progress(session_id).unwrap();
+        assert_eq!(progress.total_questions, 1);
+        assert!(progress.seconds_remaining.unwrap() > 590);
+
+        let replay = quiz_engine.get_session_replay(session_id).unwrap();
+        assert_eq!(replay.mode, SessionMode::Assessment);
+        assert_eq!(replay.times_left, 1);
+        let notes = quiz_engine.load_quiz_session(session_id).unwrap().integrity_notes;
+        assert_eq!(notes.iter().map(|note| note.kind).collect::<Vec<_>>(), vec![IntegrityNoteKind::Left, IntegrityNoteKind::Returned]);
+
+        // Once time is up, answering ends the assessment
+        let mut session = quiz_engine.load_quiz_session(session_id).unwrap();
+        session.started_at = Utc::now() - chrono::Duration::seconds(601);
+        quiz_engine.update_quiz_session(&session).unwrap();
+        assert!(quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 5).is_err());
+        assert!(quiz_engine.get_quiz_progress(session_id).unwrap().is_completed);
+    }
+
+    #[test]
+    fn test_timed_quiz_marks_late_answers() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for text in ["1 + 1", "2 + 2", "3 + 3"] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 3;
+        config.time_limit_per_question = Some(10);
+        config.time_limit_total = Some(300);
+        let session_id = quiz_engine.start_quiz_session(ProfileId(1), config).unwrap().id.unwrap();
+
+        quiz_engine.get_current_question(session_id).unwrap();
+        let progress = quiz_engine.get_quiz_progress(session_id).unwrap();
+        assert!(progress.question_seconds_remaining.unwrap() >= 9);
+        assert!(progress.seconds_remaining.unwrap() > 290);
+
+        // Too late for the question: the right answer is marked wrong
+        let mut session = quiz_engine.load_quiz_session(session_id).unwrap();
+        session.timing.question_started_at = Some(Utc::now() - chrono::Duration::seconds(11));
+        quiz_engine.update_quiz_session(&session).unwrap();
+        assert_eq!(quiz_engine.get_quiz_progress(session_id).unwrap().question_seconds_remaining, Some(0));
+        let late = quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 11).unwrap();
+        assert!(!late.is_correct);
+        assert_eq!(late.points, 0);
+        assert_eq!(late.late, Some(LateAnswer::MarkWrong));
+
+        quiz_engine.get_current_question(session_id).unwrap();
+        let on_time = quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 2).unwrap();
+        assert!(on_time.is_correct);
+        assert_eq!(on_time.late, None);
+
+        // Time paused doesn't count against the whole quiz
+        quiz_engine.get_current_question(session_id).unwrap();
+        quiz_engine.pause_quiz(session_id).unwrap();
+        let mut session = quiz_engine.load_quiz_session(session_id).unwrap();
+        session.pause_time = Some(Utc::now() - chrono::Duration::seconds(200));
+        session.started_at = Utc::now() - chrono::Duration::seconds(250);
+        quiz_engine.update_quiz_session(&session).unwrap();
+        quiz_engine.resume_quiz(session_id).unwrap();
+        let progress = quiz_engine.get_quiz_progress(session_id).unwrap();
+        assert!((240..=250).contains(&progress.seconds_remaining.unwrap()));
+
+        // Out of time for the whole quiz: the answer is skipped and the quiz ends
+        let mut session = quiz_engine.load_quiz_session(session_id).unwrap();
+        session.started_at = Utc::now() - chrono::Duration::seconds(500);
+        session.config.late_answers = LateAnswer::Skip;
+        quiz_engine.update_quiz_session(&session).unwrap();
+        let skipped = quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 2).unwrap();
+        assert_eq!(skipped.late, Some(LateAnswer::Skip));
+        assert!(quiz_engine.get_quiz_progress(session_id).unwrap().is_completed);
+    }
+
+    #[test]
+    fn test_pass_and_play_takes_turns_and_scores_each_player() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for text in ["1 + 1", "2 + 2", "3 + 3", "4 + 4", "5 + 5"] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 2;
+        assert!(quiz_engine.start_pass_and_play_session(vec![ProfileId(1)], config.clone()).is_err());
+        assert!(quiz_engine.start_pass_and_play_session(vec![ProfileId(1), ProfileId(1)], config.clone()).is_err());
+
+        let (ada, ben) = (ProfileId(1), ProfileId(2));
+        let session = quiz_engine.start_pass_and_play_session(vec![ada, ben], config).unwrap();
+        let session_id = session.id.unwrap();
+        assert_eq!(session.mode, SessionMode::PassAndPlay);
+        assert_eq!(session.questions.len(), 4);
+
+        for (player, answer) in [(ada, "right"), (ben, "wrong"), (ada, "right"), (ben, "right")] {
+            assert_eq!(quiz_engine.get_quiz_progress(session_id).unwrap().current_player, Some(player));
+            let result = quiz_engine.submit_answer(session_id, Answer::Text(answer.to_string()), 5).unwrap();
+            assert_eq!(result.answered_by, Some(player));
+        }
+        assert_eq!(quiz_engine.get_quiz_progress(session_id).unwrap().current_player, None);
+
+        let summary = quiz_engine.get_pass_and_play_summary(session_id).unwrap();
+        let correct: Vec<(ProfileId, u32, u32)> = summary.players.iter()
+            .map(|player| (player.profile_id, player.score.correct_answers, player.score.total_questions))
+            .collect();
+        assert_eq!(correct, vec![(ada, 2, 2), (ben, 1, 2)]);
+        assert_eq!(summary.winners, vec![ada]);
+        assert_eq!((summary.combined_correct, summary.total_questions), (3, 4));
+    }
+
+    #[test]
+    fn test_practice_keeps_the_question_until_it_is_right() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for text in ["1 + 1", "2 + 2"] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 2;
+        config.mode = QuizMode::Practice;
+        config.time_limit_seconds = Some(600);
+        assert!(quiz_engine.start_assessment_session(ProfileId(1), config.clone()).is_err());
+        config.time_limit_seconds = None;
+
+        let session_id = quiz_engine.start_quiz_session(ProfileId(1), config).unwrap().id.unwrap();
+        let first = quiz_engine.get_current_question(session_id).unwrap().unwrap().id;
+
+        for attempt in 1..=2 {
+            let result = quiz_engine.submit_answer(session_id, Answer::Text("wrong".to_string()), 5).unwrap();
+            assert!(result.try_again && result.explanation.is_some());
+            assert!(matches!(&result.correct_answer, Answer::Text(text) if text.is_empty()));
+            assert_eq!(result.attempts, attempt);
+            assert_eq!(quiz_engine.get_current_question(session_id).unwrap().unwrap().id, first);
+        }
+        let result = quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 5).unwrap();
+        assert_eq!((result.is_correct, result.try_again, result.attempts, result.points), (true, false, 3, 0));
+
+        let result = quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 5).unwrap();
+        assert_eq!(result.attempts, 1);
+        assert!(quiz_engine.get_quiz_progress(session_id).unwrap().is_completed);
+
+        assert_eq!(quiz_engine.get_practice_credit(session_id, ProfileId(1)).unwrap(), Some(1));
+        let session = quiz_engine.load_quiz_session(session_id).unwrap();
+        assert_eq!(session.answers.len(), 2);
+        let score = quiz_engine.calculate_score(&session).unwrap();
+        assert_eq!((score.final_score, score.xp), (0, 0));
+    }
+
+    #[test]
+    fn test_head_to_head_asks_both_players_every_question() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for text in ["1 + 1", "2 + 2", "3 + 3"] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 2;
+        let (ada, ben) = (ProfileId(1), ProfileId(2));
+        assert!(quiz_engine.start_head_to_head_session([ada, ada], config.clone()).is_err());
+
+        let session = quiz_engine.start_head_to_head_session([ada, ben], config).unwrap();
+        let session_id = session.id.unwrap();
+        assert_eq!(session.mode, SessionMode::HeadToHead);
+        assert_eq!(session.questions.len(), 4);
+        assert_eq!(session.questions[0].id, session.questions[1].id);
+        assert_eq!(session.questions[2].id, session.questions[3].id);
+        assert!(quiz_engine.get_head_to_head_summary(session_id).is_err());
+
+        for (player, answer) in [(ada, "right"), (ben, "right"), (ada, "right"), (ben, "wrong")] {
+            assert_eq!(quiz_engine.get_quiz_progress(session_id).unwrap().current_player, Some(player));
+            let result = quiz_engine.submit_answer(session_id, Answer::Text(answer.to_string()), 5).unwrap();
+            assert_eq!(result.answered_by, Some(player));
+        }
+
+        let summary = quiz_engine.get_head_to_head_summary(session_id).unwrap();
+        let correct: Vec<(ProfileId, u32, u32)> = summary.players.iter()
+            .map(|player| (player.profile_id, player.score.correct_answers, player.score.total_questions))
+            .collect();
+        assert_eq!(correct, vec![(ada, 2, 2), (ben, 1, 2)]);
+        assert_eq!(summary.winner, Some(ada));
+        let rounds: Vec<Vec<ProfileId>> = summary.rounds.iter().map(|round| round.correct.clone()).collect();
+        assert_eq!(rounds, vec![vec![ada, ben], vec![ada]]);
+
+        let ben_result = quiz_engine.get_player_quiz_result(session_id, ben).unwrap();
+        assert_eq!((ben_result.questions_answered, ben_result.correct_answers, ben_result.time_spent_seconds), (2, 1, 10));
+        assert!(quiz_engine.get_player_quiz_result(session_id, ProfileId(3)).is_err());
+    }
+
+    #[test]
+    fn test_coop_gives_each_player_their_key_stage_and_shares_the_score() {
+        use crate::models::QuestionContent;
+
+        let (mut quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for (key_stage, text) in [(KeyStage::KS1, "1 + 1"), (KeyStage::KS1, "2 + 2"), (KeyStage::KS2, "12 x 3"), (KeyStage::KS2, "45 / 5"), (KeyStage::KS2, "7 x 8")] {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                key_stage,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 2;
+        let (ada, ben) = (ProfileId(1), ProfileId(2));
+        let players = vec![
+            CoopPlayer { profile_id: ada, key_stage: KeyStage::KS1 },
+            CoopPlayer { profile_id: ben, key_stage: KeyStage::KS2 },
+        ];
+        let session = quiz_engine.start_coop_session(players, config, None).unwrap();
+        let session_id = session.id.unwrap();
+        assert_eq!(session.mode, SessionMode::Coop);
+        let key_stages: Vec<KeyStage> = session.questions.iter().map(|question| question.key_stage).collect();
+        assert_eq!(key_stages, vec![KeyStage::KS1, KeyStage::KS2, KeyStage::KS1, KeyStage::KS2]);
+        assert_eq!(session.team_target, Some(3));
+
+        for (player, answer) in [(ada, "right"), (ben, "wrong"), (ada, "right"), (ben, "right")] {
+            assert_eq!(quiz_engine.get_quiz_progress(session_id).unwrap().current_player, Some(player));
+            quiz_engine.submit_answer(session_id, Answer::Text(answer.to_string()), 5).unwrap();
+        }
+        let progress = quiz_engine.get_quiz_progress(session_id).unwrap();
+        assert_eq!((progress.team_correct, progress.team_target), (Some(3), Some(3)));
+
+        let summary = quiz_engine.get_coop_summary(session_id).unwrap();
+        assert!(summary.target_reached);
+        let contributions: Vec<(ProfileId, u32, u32)> = summary.contributions.iter()
+            .map(|player| (player.profile_id, player.correct_answers, player.questions_answered))
+            .collect();
+        assert_eq!(contributions, vec![(ada, 2, 2), (ben, 1, 2)]);
+        assert!(quiz_engine.get_pass_and_play_summary(session_id).is_err());
+    }
+
+    #[test]
+    fn test_stop_when_mastered_ends_the_quiz_early() {
+        use crate::models::QuestionContent;
+
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let subject_id = quiz_engine.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for n in 1..=15 {
+            quiz_engine.content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: format!("{} + {}", n, n),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.question_count = 15;
+        config.stop_when_mastered = true;
+
+        // Only once the adaptive engine is switched on
+        let feature_flags = Arc::new(FeatureFlagService::new(quiz_engine.db_manager.clone()));
+        let mut quiz_engine = quiz_engine.with_feature_flags(feature_flags.clone());
+        let session_id = quiz_engine.start_quiz_session(ProfileId(1), config.clone()).unwrap().id.unwrap();
+        for _ in 1..=11 {
+            quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 3).unwrap();
+        }
+        assert!(!quiz_engine.get_quiz_progress(session_id).unwrap().is_completed);
+
+        feature_flags.set_flag(flags::ADAPTIVE_ENGINE, true).unwrap();
+        let session_id = quiz_engine.start_quiz_session(ProfileId(1), config).unwrap().id.unwrap();
+
+        for answered in 1..=11 {
+            assert!(!quiz_engine.get_quiz_progress(session_id).unwrap().is_completed, "ended after {}", answered - 1);
+            quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 3).unwrap();
+        }
+        let progress = quiz_engine.get_quiz_progress(session_id).unwrap();
+        assert!(progress.is_completed);
+        assert_eq!(progress.stopped_early, Some(EarlyStop::Mastered));
+
+        // Scored on the questions actually asked
+        let score = quiz_engine.calculate_score(&quiz_engine.load_quiz_session(session_id).unwrap()).unwrap();
+        assert_eq!((score.correct_answers, score.total_questions), (11, 11));
+    }
+
+    #[test]
+    fn test_passage_session_blocks() {
+        use crate::models::{Passage, QuestionContent};
+
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let story_question = |id: QuestionId| Question {
+            id: Some(id),
+            subject_id: 1,
+            key_stage: KeyStage::KS2,
+            question_type: QuestionType::StoryQuiz,
+            content: QuestionContent {
+                text: format!("Question {}", id),
+                options: None,
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            correct_answer: Answer::Text("yes".to_string()),
+            difficulty_level: 1,
+            tags: Vec::new(),
+            assets: None,
+            created_at: None,
+            source: None,
+            explanation: None,
+        };
+        let group = |passage_id: u32, question_ids: Vec<QuestionId>| PassageGroup {
+            passage: Passage {
+                id: Some(passage_id),
+                subject_id: 1,
+                key_stage: KeyStage::KS2,
+                title: format!("Passage {}", passage_id),
+                text: "Once upon a time...".to_string(),
+                question_ids: question_ids.clone(),
+                created_at: None,
+            },
+            questions: question_ids.into_iter().map(story_question).collect(),
+        };
+
+        let mut config = create_test_session(SessionId(0), false).config;
+        config.key_stage = KeyStage::KS2;
+        let session = quiz_engine
+            .start_passage_session(ProfileId(1), config, vec![group(1, vec![QuestionId(10), QuestionId(11)]), group(2, vec![QuestionId(20), QuestionId(21), QuestionId(22)])])
+            .unwrap();
+        let session_id = session.id.unwrap();
+
+        assert_eq!(session.config.question_count, 5);
+        assert_eq!(quiz_engine.get_current_passage(session_id).unwrap().unwrap().passage.id, Some(1));
+
+        let mut session = quiz_engine.load_quiz_session(session_id).unwrap();
+        session.current_question_index = 2;
+        assert_eq!(session.current_passage_block().unwrap().passage.id, Some(2));
+        assert_eq!(session.get_current_question().unwrap().id, Some(QuestionId(20)));
+    }
+    #[test]
+    fn test_ordering_questions_earn_partial_credit() {
+        use crate::models::QuestionContent;
+        let (quiz_engine, _temp_dir) = create_test_quiz_engine();
+        let order: Vec<String> = ["3", "7", "12", "25"].iter().map(|item| item.to_string()).collect();
+        let ordering_question = |items: Vec<String>, order: Vec<String>| Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::Ordering,
+            QuestionContent {
+                text: "Put these numbers in order, smallest first".to_string(),
+                options: Some(items),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Ordering { order },
+        );
+
+        // The answer must use every item exactly once
+        let mut missing_item = order.clone();
+        missing_item.pop();
+        assert!(quiz_engine.content_manager.add_question(ordering_question(order.clone(), missing_item)).is_err());
+        let question_id = quiz_engine.content_manager.add_question(ordering_question(order.clone(), order.clone())).unwrap();
+
+        let mut question = quiz_engine.content_manager.get_question_by_id(question_id).unwrap();
+        quiz_engine.batch_randomize_questions(std::slice::from_mut(&mut question)).unwrap();
+        assert_ne!(question.content.options.as_ref().unwrap(), &order);
+
+        let submit = |items: &[&str]| quiz_engine.validate_answer(question_id, Answer::Ordering {
+            order: items.iter().map(|item| item.to_string()).collect(),
+        }).unwrap();
+        let right = submit(&["3", "7", "12", "25"]);
+        assert!(right.is_correct);
+        assert_eq!(right.partial_credit, None);
+
+        // One pair swapped still has three of the four in order
+        let swapped = submit(&["3", "12", "7", "25"]);
+        assert!(!swapped.is_correct);
+        assert_eq!(swapped.partial_credit, Some(0.75));
+        assert_eq!(swapped.points, right.points * 3 / 4);
+        assert_eq!(submit(&["25", "12", "7", "3"]).partial_credit, Some(0.25));
+
+        let mut session = create_test_session(SessionId(1), true);
+        session.answers = vec![right, swapped];
+        let score = quiz_engine.calculate_score(&session).unwrap();
+        assert_eq!(score.correct_answers, 1);
+        assert_eq!(score.accuracy_percentage, 87);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,256 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{KeyStage, QuestionType};
+use crate::database::DatabaseManager;
+use crate::services::{LateAnswer, QuizMode, QuizConfig, DEFAULT_HINT_PENALTY_PERCENT};
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+
+/// Questions in a quick play quiz, unless the subject's preset says otherwise
+const DEFAULT_QUESTION_COUNT: usize = 10;
+
+/// Most questions a preset can ask for
+const MAX_QUESTION_COUNT: usize = 50;
+
+/// Range allowed for a preset's time limit
+const MIN_TIME_LIMIT_SECONDS: u32 = 30;
+const MAX_TIME_LIMIT_SECONDS: u32 = 3600;
+
+/// How "quick play" on a subject tile sets up a quiz
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuizPreset {
+    pub subject: String,
+    pub question_count: usize,
+    /// Time limit for the whole quiz; `None` is untimed
+    pub time_limit_seconds: Option<u32>,
+    /// Question types to draw from; empty allows every type
+    pub allowed_question_types: Vec<QuestionType>,
+    /// False while the subject uses the built-in defaults
+    #[serde(default)]
+    pub customized: bool,
+}
+
+impl QuizPreset {
+    /// Built-in defaults: timed drills for times tables, relaxed quizzes for everything else
+    pub fn defaults(subject: &str) -> Self {
+        let (question_count, time_limit_seconds) = match subject {
+            "times_tables" => (20, Some(300)),
+            _ => (DEFAULT_QUESTION_COUNT, None),
+        };
+
+        Self {
+            subject: subject.to_string(),
+            question_count,
+            time_limit_seconds,
+            allowed_question_types: Vec::new(),
+            customized: false,
+        }
+    }
+
+    /// The quiz a child gets from quick play on this subject
+    pub fn to_config(&self, key_stage: KeyStage) -> QuizConfig {
+        QuizConfig {
+            subject: self.subject.clone(),
+            key_stage,
+            question_count: self.question_count,
+            difficulty_range: None,
+            time_limit_seconds: self.time_limit_seconds,
+            randomize_questions: true,
+            randomize_answers: true,
+            max_consecutive_same_tag: None,
+            boss_question: false,
+            warm_up_questions: 0,
+            cool_down_review: false,
+            hint_penalty_percent: DEFAULT_HINT_PENALTY_PERCENT,
+            time_limit_per_question: None,
+            time_limit_total: None,
+            late_answers: LateAnswer::default(),
+            stop_when_mastered: false,
+            mode: QuizMode::default(),
+            allowed_question_types: self.allowed_question_types.clone(),
+        }
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        if self.question_count == 0 || self.question_count > MAX_QUESTION_COUNT {
+            return Err(AppError::InvalidInput(format!(
+                "A quiz must have between 1 and {} questions", MAX_QUESTION_COUNT
+            )));
+        }
+        if let Some(limit) = self.time_limit_seconds {
+            if !(MIN_TIME_LIMIT_SECONDS..=MAX_TIME_LIMIT_SECONDS).contains(&limit) {
+                return Err(AppError::InvalidInput(format!(
+                    "Time limits must be between {} seconds and {} minutes",
+                    MIN_TIME_LIMIT_SECONDS, MAX_TIME_LIMIT_SECONDS / 60
+                )));
+            }
+        }
+        let types = &self.allowed_question_types;
+        if types.iter().enumerate().any(|(i, question_type)| types[..i].contains(question_type)) {
+            return Err(AppError::InvalidInput("Each question type can only be allowed once".to_string()));
+        }
+        Ok(())
+    }
+
+    fn matches_defaults(&self) -> bool {
+        let defaults = Self::defaults(&self.subject);
+        self.question_count == defaults.question_count
+            && self.time_limit_seconds == defaults.time_limit_seconds
+            && self.allowed_question_types == defaults.allowed_question_types
+    }
+}
+
+/// Per-subject quick play presets, editable by parents
+pub struct QuizPresetService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl QuizPresetService {
+    /// Create a new quiz preset service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// A subject's preset, or its defaults if a parent hasn't changed them
+    pub fn get_preset(&self, subject: &str) -> AppResult<QuizPreset> {
+        let subject_id = self.subject_id(subject)?;
+        let stored = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT question_count, time_limit_seconds, allowed_question_types
+                 FROM quiz_presets WHERE subject_id = ?1",
+                params![subject_id],
+                |row| Ok((row.get::<_, u32>(0)?, row.get::<_, Option<u32>>(1)?, row.get::<_, String>(2)?)),
+            ).optional()
+        })?;
+
+        match stored {
+            Some((question_count, time_limit_seconds, allowed_question_types)) => Ok(QuizPreset {
+                subject: subject.to_string(),
+                question_count: question_count as usize,
+                time_limit_seconds,
+                allowed_question_types: serde_json::from_str(&allowed_question_types)?,
+                customized: true,
+            }),
+            None => Ok(QuizPreset::defaults(subject)),
+        }
+    }
+
+    /// Presets for every subject, in subject order
+    pub fn list_presets(&self) -> AppResult<Vec<QuizPreset>> {
+        let subjects: Vec<String> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT name FROM subjects ORDER BY name")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect()
+        })?;
+
+        subjects.iter().map(|subject| self.get_preset(subject)).collect()
+    }
+
+    /// Save a subject's preset. Saving the defaults clears the customisation.
+    pub fn set_preset(&self, preset: &QuizPreset) -> AppResult<QuizPreset> {
+        preset.validate()?;
+        let subject_id = self.subject_id(&preset.subject)?;
+        let allowed_question_types = serde_json::to_string(&preset.allowed_question_types)?;
+
+        self.db_manager.execute(|conn| {
+            if preset.matches_defaults() {
+                conn.execute("DELETE FROM quiz_presets WHERE subject_id = ?1", params![subject_id])?;
+                return Ok(());
+            }
+
+            conn.execute(
+                "INSERT OR REPLACE INTO quiz_presets
+                 (subject_id, question_count, time_limit_seconds, allowed_question_types, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    subject_id,
+                    preset.question_count as u32,
+                    preset.time_limit_seconds,
+                    allowed_question_types,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+
+        self.get_preset(&preset.subject)
+    }
+
+    /// Go back to the subject's built-in defaults
+    pub fn reset_preset(&self, subject: &str) -> AppResult<QuizPreset> {
+        let subject_id = self.subject_id(subject)?;
+        self.db_manager.execute(|conn| {
+            conn.execute("DELETE FROM quiz_presets WHERE subject_id = ?1", params![subject_id])
+        })?;
+        Ok(QuizPreset::defaults(subject))
+    }
+
+    /// The quiz config quick play starts for a subject
+    pub fn quick_play_config(&self, subject: &str, key_stage: KeyStage) -> AppResult<QuizConfig> {
+        Ok(self.get_preset(subject)?.to_config(key_stage))
+    }
+
+    fn subject_id(&self, subject: &str) -> AppResult<u32> {
+        self.db_manager.execute(|conn| {
+            conn.query_row("SELECT id FROM subjects WHERE name = ?1", params![subject], |row| row.get(0)).optional()
+        })?
+        .ok_or_else(|| AppError::NotFound(format!("Subject '{}' not found", subject)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    fn create_test_preset_service() -> (QuizPresetService, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        (QuizPresetService::new(db_service.manager()), temp_dir)
+    }
+
+    #[test]
+    fn test_presets_default_until_customized() {
+        let (service, _temp_dir) = create_test_preset_service();
+
+        let presets = service.list_presets().unwrap();
+        assert!(presets.iter().all(|preset| !preset.customized));
+        let times_tables = presets.iter().find(|preset| preset.subject == "times_tables").unwrap();
+        assert_eq!(times_tables.time_limit_seconds, Some(300));
+        assert!(service.get_preset("history").is_err());
+
+        let mut preset = service.get_preset("mathematics").unwrap();
+        preset.question_count = 15;
+        preset.time_limit_seconds = Some(600);
+        preset.allowed_question_types = vec![QuestionType::MultipleChoice, QuestionType::FillBlank];
+        let saved = service.set_preset(&preset).unwrap();
+        assert!(saved.customized);
+        assert_eq!(saved.allowed_question_types, preset.allowed_question_types);
+
+        let config = service.quick_play_config("mathematics", KeyStage::KS2).unwrap();
+        assert_eq!(config.question_count, 15);
+        assert_eq!(config.time_limit_seconds, Some(600));
+        assert_eq!(config.key_stage, KeyStage::KS2);
+        assert_eq!(config.allowed_question_types.len(), 2);
+
+        assert!(!service.reset_preset("mathematics").unwrap().customized);
+        assert_eq!(service.get_preset("mathematics").unwrap(), QuizPreset::defaults("mathematics"));
+    }
+
+    #[test]
+    fn test_invalid_presets_are_rejected() {
+        let (service, _temp_dir) = create_test_preset_service();
+        let preset = QuizPreset::defaults("english");
+
+        assert!(service.set_preset(&QuizPreset { question_count: 0, ..preset.clone() }).is_err());
+        assert!(service.set_preset(&QuizPreset { time_limit_seconds: Some(5), ..preset.clone() }).is_err());
+        let repeated = vec![QuestionType::Hotspot, QuestionType::Hotspot];
+        assert!(service.set_preset(&QuizPreset { allowed_question_types: repeated, ..preset }).is_err());
+    }
+}
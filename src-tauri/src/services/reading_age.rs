@@ -0,0 +1,316 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{KeyStage, ProfileId};
+use std::str::FromStr;
+use std::sync::Arc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Question tags that count as reading: comprehension and vocabulary
+pub const READING_TAGS: &[&str] = &[
+    "reading_comprehension", "main_idea", "inference", "vocabulary", "synonyms", "antonyms",
+];
+
+/// Too few answers give an interval so wide it tells a parent nothing
+const MIN_ANSWERS: u32 = 10;
+const MIN_READING_AGE: f64 = 4.0;
+const MAX_READING_AGE: f64 = 16.0;
+/// Years of reading age over which the chance of a right answer rises from 1 in 4 to 3 in 4
+const ABILITY_SCALE: f64 = 1.1;
+/// A child reading at a question's level gets it right this often
+const ACCURACY_AT_LEVEL: f64 = 0.75;
+/// Spread of the prior, centred on the level of the questions tried. It keeps a perfect
+/// score from running off to the cap while barely moving a well-evidenced estimate.
+const PRIOR_SD_YEARS: f64 = 2.0;
+/// z for a 95% interval
+const CONFIDENCE_Z: f64 = 1.96;
+const NEWTON_ITERATIONS: usize = 30;
+
+/// Estimated reading age, in years, with a 95% confidence interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingAgeEstimate {
+    pub profile_id: ProfileId,
+    pub reading_age: f64,
+    pub lower_bound: f64,
+    pub upper_bound: f64,
+    /// Answers to reading questions the estimate is based on
+    pub answers_considered: u32,
+    pub estimated_at: DateTime<Utc>,
+}
+
+impl ReadingAgeEstimate {
+    /// e.g. "8y 4m (likely between 7y 9m and 8y 11m, from 42 answers)"
+    pub fn describe(&self) -> String {
+        format!(
+            "{} (likely between {} and {}, from {} answers)",
+            format_years(self.reading_age),
+            format_years(self.lower_bound),
+            format_years(self.upper_bound),
+            self.answers_considered,
+        )
+    }
+}
+
+fn format_years(age: f64) -> String {
+    let months = (age * 12.0).round() as u32;
+    format!("{}y {}m", months / 12, months % 12)
+}
+
+/// The reading age a question is pitched at. Key stages span the ages they are taught
+/// at, and difficulty 1-5 places the question within that span.
+fn question_level(key_stage: KeyStage, difficulty: u8) -> f64 {
+    let (start, end) = match key_stage {
+        KeyStage::EYFS => (4.0, 5.0),
+        KeyStage::KS1 => (5.0, 7.0),
+        KeyStage::KS2 => (7.0, 11.0),
+        KeyStage::KS3 => (11.0, 14.0),
+    };
+    let position = (difficulty.clamp(1, 5) as f64 - 0.5) / 5.0;
+    start + (end - start) * position
+}
+
+/// Answers to one question, at the level it is pitched at
+struct ReadingEvidence {
+    level: f64,
+    times_answered: u32,
+    times_correct: u32,
+}
+
+/// Estimates reading age from how a profile does on comprehension and vocabulary
+/// questions, and keeps each estimate so parents can see it change over time.
+///
+/// Each question is treated as pitched at a reading age (see [`question_level`]) and
+/// the estimate is the reading age that best explains which ones were answered right,
+/// under a logistic model with a weak prior.
+pub struct ReadingAgeService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ReadingAgeService {
+    /// Create a new reading age service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Current estimate, without storing it. None until enough reading questions have
+    /// been answered.
+    pub fn estimate(&self, profile_id: ProfileId) -> AppResult<Option<ReadingAgeEstimate>> {
+        let evidence = self.load_evidence(profile_id)?;
+        Ok(estimate_from_evidence(&evidence).map(|(reading_age, lower_bound, upper_bound, answers_considered)| {
+            ReadingAgeEstimate {
+                profile_id,
+                reading_age,
+                lower_bound,
+                upper_bound,
+                answers_considered,
+                estimated_at: Utc::now(),
+            }
+        }))
+    }
+
+    /// Work out the current estimate and add it to the profile's history. Nothing new is
+    /// stored if no reading questions have been answered since the last estimate.
+    pub fn record_estimate(&self, profile_id: ProfileId) -> AppResult<Option<ReadingAgeEstimate>> {
+        let estimate = match self.estimate(profile_id)? {
+            Some(estimate) => estimate,
+            None => return Ok(None),
+        };
+
+        if let Some(latest) = self.get_history(profile_id)?.pop() {
+            if latest.answers_considered == estimate.answers_considered {
+                return Ok(Some(latest));
+            }
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO reading_age_estimates (profile_id, reading_age, lower_bound, upper_bound, answers_considered, estimated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    profile_id,
+                    estimate.reading_age,
+                    estimate.lower_bound,
+                    estimate.upper_bound,
+                    estimate.answers_considered,
+                    estimate.estimated_at.to_rfc3339(),
+                ],
+            )
+        })?;
+        Ok(Some(estimate))
+    }
+
+    /// Every stored estimate for a profile, oldest first
+    pub fn get_history(&self, profile_id: ProfileId) -> AppResult<Vec<ReadingAgeEstimate>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT reading_age, lower_bound, upper_bound, answers_considered, estimated_at
+                 FROM reading_age_estimates WHERE profile_id = ?1 ORDER BY estimated_at, id"
+            )?;
+            let estimates = stmt.query_map(params![profile_id], |row| {
+                Ok(ReadingAgeEstimate {
+                    profile_id,
+                    reading_age: row.get(0)?,
+                    lower_bound: row.get(1)?,
+                    upper_bound: row.get(2)?,
+                    answers_considered: row.get(3)?,
+                    estimated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                        .map_err(|_| rusqlite::Error::InvalidColumnType(4, "estimated_at".to_string(), rusqlite::types::Type::Text))?
+                        .with_timezone(&Utc),
+                })
+            })?.collect::<Result<Vec<_>, _>>()?;
+            Ok(estimates)
+        })?)
+    }
+
+    /// Answered questions that are reading questions: tagged as such, or set on a passage
+    fn load_evidence(&self, profile_id: ProfileId) -> AppResult<Vec<ReadingEvidence>> {
+        let rows = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT q.key_stage, q.difficulty_level, q.tags, pq.passage_id IS NOT NULL, a.times_answered, a.times_correct
+                 FROM answered_questions a
+                 JOIN questions q ON q.id = a.question_id
+                 LEFT JOIN passage_questions pq ON pq.question_id = q.id
+                 WHERE a.profile_id = ?1"
+            )?;
+            let rows = stmt.query_map(params![profile_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, u8>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, bool>(3)?,
+                    row.get::<_, u32>(4)?,
+                    row.get::<_, u32>(5)?,
+                ))
+            })?.collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })?;
+
+        let mut evidence = Vec::new();
+        for (key_stage, difficulty, tags, on_passage, times_answered, times_correct) in rows {
+            let tags: Vec<String> = tags
+                .and_then(|tags| serde_json::from_str(&tags).ok())
+                .unwrap_or_default();
+            if !on_passage && !tags.iter().any(|tag| READING_TAGS.contains(&tag.as_str())) {
+                continue;
+            }
+            let key_stage = KeyStage::from_str(&key_stage)
+                .map_err(|_| AppError::Internal(format!("Unknown key stage {}", key_stage)))?;
+            evidence.push(ReadingEvidence {
+                level: question_level(key_stage, difficulty),
+                times_answered,
+                times_correct: times_correct.min(times_answered),
+            });
+        }
+        Ok(evidence)
+    }
+}
+
+/// Maximum a posteriori reading age and its 95% interval, with the number of answers
+/// used. Fitted by Newton's method; the interval comes from the curvature at the peak.
+fn estimate_from_evidence(evidence: &[ReadingEvidence]) -> Option<(f64, f64, f64, u32)> {
+    let answers: u32 = evidence.iter().map(|item| item.times_answered).sum();
+    if answers < MIN_ANSWERS {
+        return None;
+    }
+
+    let prior_mean = evidence.iter().map(|item| item.level * item.times_answered as f64).sum::<f64>() / answers as f64;
+    let prior_precision = 1.0 / (PRIOR_SD_YEARS * PRIOR_SD_YEARS);
+    let offset = (ACCURACY_AT_LEVEL / (1.0 - ACCURACY_AT_LEVEL)).ln();
+    let slope = (3.0f64).ln() * 2.0 / ABILITY_SCALE;
+
+    let derivatives = |ability: f64| {
+        let mut gradient = -(ability - prior_mean) * prior_precision;
+        let mut information = prior_precision;
+        for item in evidence {
+            let p = 1.0 / (1.0 + (-(slope * (ability - item.level) + offset)).exp());
+            gradient += slope * (item.times_correct as f64 - item.times_answered as f64 * p);
+            information += slope * slope * item.times_answered as f64 * p * (1.0 - p);
+        }
+        (gradient, information)
+    };
+
+    let mut ability = prior_mean;
+    for _ in 0..NEWTON_ITERATIONS {
+        let (gradient, information) = derivatives(ability);
+        let step = gradient / information;
+        ability = (ability + step).clamp(MIN_READING_AGE, MAX_READING_AGE);
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+
+    let (_, information) = derivatives(ability);
+    let margin = CONFIDENCE_Z / information.sqrt();
+    Some((
+        ability,
+        (ability - margin).max(MIN_READING_AGE),
+        (ability + margin).min(MAX_READING_AGE),
+        answers,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_estimate_tracks_reading_accuracy_and_is_stored() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+        let service = ReadingAgeService::new(db_manager.clone());
+
+        let answer = |tags: &str, key_stage: &str, difficulty: u8, answered: u32, correct: u32| {
+            db_manager.execute(|conn| {
+                conn.execute(
+                    "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, difficulty_level, tags, created_at)
+                     VALUES ((SELECT id FROM subjects WHERE name = 'english'), ?1, 'multiple_choice', '{}', '\"a\"', ?2, ?3, ?4)",
+                    params![key_stage, difficulty, tags, Utc::now().to_rfc3339()],
+                )?;
+                conn.execute(
+                    "INSERT INTO answered_questions (profile_id, question_id, times_answered, times_correct, last_answered_at)
+                     VALUES (1, ?1, ?2, ?3, ?4)",
+                    params![conn.last_insert_rowid(), answered, correct, Utc::now().to_rfc3339()],
+                )?;
+                Ok(())
+            }).unwrap();
+        };
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )
+        }).unwrap();
+        let profile_id = ProfileId(1);
+
+        // Maths answers don't count, and a handful of reading answers isn't enough
+        answer(r#"["addition"]"#, "KS2", 3, 40, 40);
+        answer(r#"["vocabulary"]"#, "KS1", 3, 4, 4);
+        assert!(service.estimate(profile_id).unwrap().is_none());
+        assert!(service.record_estimate(profile_id).unwrap().is_none());
+
+        // Strong at KS1, shaky at the start of KS2
+        answer(r#"["reading_comprehension"]"#, "KS1", 5, 12, 11);
+        answer(r#"["synonyms"]"#, "KS2", 1, 10, 5);
+        let first = service.record_estimate(profile_id).unwrap().unwrap();
+        assert_eq!(first.answers_considered, 26);
+        assert!(first.reading_age > 6.5 && first.reading_age < 8.5, "{}", first.reading_age);
+        assert!(first.lower_bound < first.reading_age && first.reading_age < first.upper_bound);
+
+        // Nothing new answered, so nothing new stored
+        service.record_estimate(profile_id).unwrap();
+        assert_eq!(service.get_history(profile_id).unwrap().len(), 1);
+
+        // Later, harder passages go well and the estimate rises and narrows
+        answer(r#"["main_idea"]"#, "KS2", 4, 30, 26);
+        let second = service.record_estimate(profile_id).unwrap().unwrap();
+        assert!(second.reading_age > first.reading_age);
+        assert!(second.upper_bound - second.lower_bound < first.upper_bound - first.lower_bound);
+        assert_eq!(service.get_history(profile_id).unwrap().len(), 2);
+        assert!(second.describe().contains("from 56 answers"));
+    }
+}
@@ -0,0 +1,537 @@
+use crate::database::{DatabaseManager, MigrationManager};
+use crate::errors::{AppError, AppResult};
+use crate::models::ProfileId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use rusqlite::{params, Connection};
+use chrono::{DateTime, Utc};
+
+/// Folder under the app data directory where a restore is prepared before it's applied on
+/// the next start
+pub const RESTORE_DIRECTORY: &str = "restore";
+
+/// Files kept in the restore directory between preparing a restore and applying it
+const PREPARED_RESTORE_FILE: &str = "prepared.db";
+const CONFIRMED_RESTORE_FILE: &str = "confirmed.db";
+const SELECTION_FILE: &str = "selection.json";
+
+/// Profiles and everything recorded about them: progress, quiz history, streaks, XP,
+/// classes, households and their own settings
+const PROFILE_TABLES: &[&str] = &[
+    "profiles", "progress", "progress_resets", "quiz_sessions", "question_attempts", "quiz_completions",
+    "quiz_incidents", "answered_questions", "answer_drawings", "scratchpad_entries", "session_events",
+    "condensed_sessions", "achievements", "profile_xp", "xp_gains", "streak_freezes", "streak_freeze_days",
+    "daily_activity", "mistakes", "question_reviews", "practice_later", "explore_exposures",
+    "boss_question_attempts", "head_to_head_results", "question_of_the_day_completions",
+    "multiplication_checks", "multiplication_check_questions", "phonics_screenings", "phonics_screening_words",
+    "reading_age_estimates", "spelling_lists", "spelling_words", "spelling_attempts", "custom_mixes",
+    "assignments", "classes", "class_members", "households", "profile_households", "active_household",
+    "profile_school_details", "profile_feature_flags", "accessibility_settings", "wellbeing_settings",
+    "wellbeing_activity", "notification_preferences", "notification_log", "difficulty_curves",
+    "approval_requests", "onboarding_state",
+];
+
+/// Subjects, questions and what goes with them
+const CONTENT_TABLES: &[&str] = &[
+    "subjects", "questions", "assets", "asset_hashes", "passages", "passage_questions", "question_sources",
+    "question_explanations", "question_versions", "content_change_alerts", "retired_questions",
+    "question_flags", "question_households", "question_of_the_day", "mock_papers", "mock_paper_questions",
+    "curriculum_levels",
+];
+
+/// App-wide settings a parent has chosen
+const SETTINGS_TABLES: &[&str] = &[
+    "feature_flags", "telemetry_settings", "notification_templates", "email_settings", "storage_settings",
+    "retention_settings", "locale_settings", "curriculum_settings", "quiz_presets", "achievement_rules",
+];
+
+/// Tables a restore of chosen parts never touches: secrets, logs of what this machine has
+/// done, and SQLite's own bookkeeping
+#[cfg(test)]
+const KEPT_TABLES: &[&str] = &[
+    "api_tokens", "cloud_backup_settings", "cloud_backup_log", "telemetry_counters", "email_report_log",
+    "retention_runs", "asset_integrity_runs", "content_write_origin", "schema_migrations", "sqlite_sequence",
+];
+
+/// A part of the database that can be restored on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreComponent {
+    Profiles,
+    Content,
+    Settings,
+}
+
+impl RestoreComponent {
+    fn tables(self) -> &'static [&'static str] {
+        match self {
+            RestoreComponent::Profiles => PROFILE_TABLES,
+            RestoreComponent::Content => CONTENT_TABLES,
+            RestoreComponent::Settings => SETTINGS_TABLES,
+        }
+    }
+}
+
+/// How much of a backup to restore
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "scope", rename_all = "snake_case")]
+pub enum RestoreSelection {
+    /// Replace the whole database with the backup
+    Everything,
+    /// Replace only these parts and keep the rest as it is now. Rows left pointing at
+    /// something the restore removed, like progress on a question that's gone, are dropped.
+    Components { components: Vec<RestoreComponent> },
+}
+
+/// Where a backup being restored came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupSource {
+    /// Taken on this machine before a destructive change
+    Local,
+    /// Downloaded from the parent's backup folder or bucket
+    Cloud,
+}
+
+/// How restoring would change something
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Only in the backup, so it comes back
+    Restored,
+    /// Only in the current data, so it goes
+    Removed,
+    /// In both, but different
+    Changed,
+}
+
+/// A profile restoring would bring back, remove or change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileChange {
+    pub profile_id: ProfileId,
+    pub name: String,
+    pub change: ChangeKind,
+    pub quiz_sessions_now: u32,
+    pub quiz_sessions_in_backup: u32,
+}
+
+/// Questions restoring would bring back, remove or change
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuestionChanges {
+    pub restored: u32,
+    pub removed: u32,
+    pub changed: u32,
+}
+
+/// A settings table restoring would change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettingsChange {
+    pub table: String,
+    /// Rows in the backup that differ from the current ones
+    pub restored_rows: u32,
+    /// Current rows the backup would replace or remove
+    pub replaced_rows: u32,
+}
+
+/// What restoring a backup would change, shown before the parent confirms it and chooses
+/// which parts to restore. Only profiles, questions and settings that differ are listed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorePreview {
+    pub source: BackupSource,
+    pub backup_name: String,
+    pub taken_at: DateTime<Utc>,
+    /// Database version the backup was made at; older ones are upgraded before restoring
+    pub schema_version: u32,
+    pub profiles: Vec<ProfileChange>,
+    pub questions: QuestionChanges,
+    pub settings: Vec<SettingsChange>,
+}
+
+/// name, avatar, theme, quiz sessions
+type ProfileRow = (String, String, Option<String>, u32);
+
+/// Restores local or cloud backups, in full or just chosen parts. A backup is staged and
+/// compared with the current data first; once confirmed it's applied when the app next
+/// starts, before anything opens the database.
+pub struct RestoreService {
+    db_manager: Arc<DatabaseManager>,
+    staging_directory: PathBuf,
+}
+
+impl RestoreService {
+    pub fn new(db_manager: Arc<DatabaseManager>, staging_directory: PathBuf) -> Self {
+        Self { db_manager, staging_directory }
+    }
+
+    /// Stage a backup's database, check this version of the app can open it, upgrade it to
+    /// the current schema and compare it with the current data. Replaces any restore
+    /// prepared earlier; nothing else changes until the restore is confirmed.
+    pub fn prepare(
+        &self,
+        database: &[u8],
+        source: BackupSource,
+        backup_name: &str,
+        taken_at: DateTime<Utc>,
+    ) -> AppResult<RestorePreview> {
+        self.cancel()?;
+        fs::create_dir_all(&self.staging_directory)?;
+        let prepared = self.staging_directory.join(PREPARED_RESTORE_FILE);
+        fs::write(&prepared, database)?;
+
+        let result = upgrade_backup(&prepared).and_then(|schema_version| {
+            let backup_path = prepared.to_string_lossy().into_owned();
+            let (profiles, questions, settings) = self.db_manager.execute(|conn| {
+                conn.execute("ATTACH DATABASE ?1 AS backup", params![backup_path])?;
+                let changes = compare_with_backup(conn);
+                conn.execute_batch("DETACH DATABASE backup")?;
+                changes
+            })?;
+
+            Ok(RestorePreview {
+                source,
+                backup_name: backup_name.to_string(),
+                taken_at,
+                schema_version,
+                profiles,
+                questions,
+                settings,
+            })
+        });
+
+        if result.is_err() {
+            let _ = fs::remove_file(&prepared);
+        }
+        result
+    }
+
+    /// Restore the prepared backup, or the chosen parts of it, the next time the app starts
+    pub fn confirm(&self, selection: &RestoreSelection) -> AppResult<()> {
+        let prepared = self.staging_directory.join(PREPARED_RESTORE_FILE);
+        if !prepared.exists() {
+            return Err(AppError::NotFound("No backup has been prepared for restoring".to_string()));
+        }
+        if let RestoreSelection::Components { components } = selection {
+            if components.is_empty() {
+                return Err(AppError::InvalidInput("Choose at least one part of the backup to restore".to_string()));
+            }
+        }
+
+        let selection = serde_json::to_vec(selection)
+            .map_err(|e| AppError::Internal(format!("Failed to save the restore selection: {}", e)))?;
+        fs::write(self.staging_directory.join(SELECTION_FILE), selection)?;
+        fs::rename(prepared, self.staging_directory.join(CONFIRMED_RESTORE_FILE))?;
+        Ok(())
+    }
+
+    /// Throw away a prepared or confirmed restore that hasn't been applied yet
+    pub fn cancel(&self) -> AppResult<()> {
+        remove_staged_files(&self.staging_directory, &[PREPARED_RESTORE_FILE, CONFIRMED_RESTORE_FILE, SELECTION_FILE])
+    }
+}
+
+/// Apply a confirmed restore. Called at startup, before the database is opened.
+/// Returns whether a backup was restored.
+pub fn apply_pending_restore(database_path: &Path, staging_directory: &Path) -> AppResult<bool> {
+    let confirmed = staging_directory.join(CONFIRMED_RESTORE_FILE);
+    if !confirmed.exists() {
+        return Ok(false);
+    }
+
+    let selection = match fs::read(staging_directory.join(SELECTION_FILE)) {
+        Ok(selection) => serde_json::from_slice(&selection)
+            .map_err(|e| AppError::Internal(format!("Failed to read the restore selection: {}", e)))?,
+        Err(e) if e.kind() == ErrorKind::NotFound => RestoreSelection::Everything,
+        Err(e) => return Err(e.into()),
+    };
+
+    let result = match selection {
+        RestoreSelection::Everything => replace_database(database_path, &confirmed),
+        RestoreSelection::Components { components } => restore_components(database_path, &confirmed, &components),
+    };
+    // A restore that failed part way was rolled back, and would only fail again next time
+    remove_staged_files(staging_directory, &[CONFIRMED_RESTORE_FILE, SELECTION_FILE])?;
+    result.map(|_| true)
+}
+
+/// Check a staged backup isn't newer than this app and bring it up to the current schema.
+/// Returns the version it was made at.
+fn upgrade_backup(path: &Path) -> AppResult<u32> {
+    let conn = Connection::open(path)?;
+    let migrations = MigrationManager::new();
+    let schema_version = migrations.get_current_version(&conn)?;
+    if schema_version > migrations.get_latest_version() {
+        return Err(AppError::Backup("This backup was made by a newer version of QuiZDD; update the app first".to_string()));
+    }
+    migrations.migrate_to_latest(&conn)?;
+    Ok(schema_version)
+}
+
+fn compare_with_backup(conn: &Connection) -> rusqlite::Result<(Vec<ProfileChange>, QuestionChanges, Vec<SettingsChange>)> {
+    let now = profile_rows(conn, "main")?;
+    let in_backup = profile_rows(conn, "backup")?;
+    let mut profiles = Vec::new();
+    for (id, current) in &now {
+        match in_backup.get(id) {
+            None => profiles.push(ProfileChange {
+                profile_id: ProfileId(*id),
+                name: current.0.clone(),
+                change: ChangeKind::Removed,
+                quiz_sessions_now: current.3,
+                quiz_sessions_in_backup: 0,
+            }),
+            Some(backup) if backup != current => profiles.push(ProfileChange {
+                profile_id: ProfileId(*id),
+                name: backup.0.clone(),
+                change: ChangeKind::Changed,
+                quiz_sessions_now: current.3,
+                quiz_sessions_in_backup: backup.3,
+            }),
+            Some(_) => {}
+        }
+    }
+    for (id, backup) in in_backup.iter().filter(|(id, _)| !now.contains_key(id)) {
+        profiles.push(ProfileChange {
+            profile_id: ProfileId(*id),
+            name: backup.0.clone(),
+            change: ChangeKind::Restored,
+            quiz_sessions_now: 0,
+            quiz_sessions_in_backup: backup.3,
+        });
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name).then(a.profile_id.cmp(&b.profile_id)));
+
+    let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, u32>(0));
+    let restored = count("SELECT COUNT(*) FROM backup.questions WHERE id NOT IN (SELECT id FROM main.questions)")?;
+    let removed = count("SELECT COUNT(*) FROM main.questions WHERE id NOT IN (SELECT id FROM backup.questions)")?;
+    let questions = QuestionChanges {
+        restored,
+        removed,
+        changed: differing_rows(conn, "questions", "backup", "main")?.saturating_sub(restored),
+    };
+
+    let mut settings = Vec::new();
+    for table in SETTINGS_TABLES {
+        let restored_rows = differing_rows(conn, table, "backup", "main")?;
+        let replaced_rows = differing_rows(conn, table, "main", "backup")?;
+        if restored_rows + replaced_rows > 0 {
+            settings.push(SettingsChange { table: table.to_string(), restored_rows, replaced_rows });
+        }
+    }
+
+    Ok((profiles, questions, settings))
+}
+
+fn profile_rows(conn: &Connection, schema: &str) -> rusqlite::Result<BTreeMap<u32, ProfileRow>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT p.id, p.name, p.avatar, p.theme_preference,
+                (SELECT COUNT(*) FROM {schema}.quiz_sessions s WHERE s.profile_id = p.id)
+         FROM {schema}.profiles p",
+        schema = schema
+    ))?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))))?;
+    rows.collect()
+}
+
+/// Rows of `table` in `from` with no identical row in `to`, comparing the columns both have
+fn differing_rows(conn: &Connection, table: &str, from: &str, to: &str) -> rusqlite::Result<u32> {
+    let columns = shared_columns(conn, table)?;
+    if columns.is_empty() {
+        return Ok(0);
+    }
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM (SELECT {columns} FROM {from}.\"{table}\" EXCEPT SELECT {columns} FROM {to}.\"{table}\")",
+            columns = columns, from = from, to = to, table = table
+        ),
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Quoted names of the columns `table` has in both the current database and the backup,
+/// or none if either lacks the table
+fn shared_columns(conn: &Connection, table: &str) -> rusqlite::Result<String> {
+    let mut stmt = conn.prepare(
+        "SELECT current.name FROM pragma_table_info(?1, 'main') current
+         JOIN pragma_table_info(?1, 'backup') backup ON backup.name = current.name
+         ORDER BY current.cid"
+    )?;
+    let columns = stmt.query_map(params![table], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(columns.iter().map(|column| format!("\"{}\"", column.replace('"', "\"\""))).collect::<Vec<_>>().join(", "))
+}
+
+fn replace_database(database_path: &Path, confirmed: &Path) -> AppResult<()> {
+    // The write-ahead log belongs to the database being replaced
+    for suffix in ["-wal", "-shm"] {
+        let mut journal = database_path.as_os_str().to_os_string();
+        journal.push(suffix);
+        match fs::remove_file(PathBuf::from(journal)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    fs::rename(confirmed, database_path)?;
+    Ok(())
+}
+
+/// Copy the chosen parts of the backup over the current data in one transaction, then
+/// clear up rows the other parts had pointing at something that's gone
+fn restore_components(database_path: &Path, confirmed: &Path, components: &[RestoreComponent]) -> AppResult<()> {
+    let mut conn = Connection::open(database_path)?;
+    // Tables are replaced one at a time, so references only line up again at the end
+    conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+    conn.execute("ATTACH DATABASE ?1 AS backup", params![confirmed.to_string_lossy().into_owned()])?;
+
+    let tx = conn.transaction()?;
+    for table in components.iter().flat_map(|component| component.tables()) {
+        let columns = shared_columns(&tx, table)?;
+        if columns.is_empty() {
+            continue;
+        }
+        tx.execute(&format!("DELETE FROM main.\"{}\"", table), [])?;
+        tx.execute(
+            &format!(
+                "INSERT INTO main.\"{table}\" ({columns}) SELECT {columns} FROM backup.\"{table}\"",
+                table = table, columns = columns
+            ),
+            [],
+        )?;
+    }
+    remove_dangling_rows(&tx)?;
+    tx.commit()?;
+
+    conn.execute_batch("DETACH DATABASE backup")?;
+    Ok(())
+}
+
+/// Do what each broken foreign key would have done had its parent row been deleted:
+/// clear the reference where the key says so, and delete the row otherwise. Repeats
+/// until nothing is left dangling, as deleting a row can leave others pointing at it.
+fn remove_dangling_rows(conn: &Connection) -> rusqlite::Result<()> {
+    loop {
+        let mut stmt = conn.prepare("PRAGMA main.foreign_key_check")?;
+        let dangling = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?, row.get::<_, i64>(3)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut fixed = 0;
+        for (table, rowid, key) in dangling {
+            let rowid = match rowid {
+                Some(rowid) => rowid,
+                None => continue,
+            };
+            let mut stmt = conn.prepare(&format!(
+                "SELECT \"from\", on_delete FROM pragma_foreign_key_list('{}') WHERE id = ?1",
+                table.replace('\'', "''")
+            ))?;
+            let columns = stmt.query_map(params![key], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let sql = if columns.iter().any(|(_, on_delete)| on_delete == "SET NULL") {
+                let assignments: Vec<String> = columns.iter().map(|(column, _)| format!("\"{}\" = NULL", column)).collect();
+                format!("UPDATE main.\"{}\" SET {} WHERE rowid = ?1", table, assignments.join(", "))
+            } else {
+                format!("DELETE FROM main.\"{}\" WHERE rowid = ?1", table)
+            };
+            fixed += conn.execute(&sql, params![rowid])?;
+        }
+
+        if fixed == 0 {
+            return Ok(());
+        }
+    }
+}
+
+fn remove_staged_files(staging_directory: &Path, files: &[&str]) -> AppResult<()> {
+    for file in files {
+        match fs::remove_file(staging_directory.join(file)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_every_table_belongs_to_one_part_of_a_restore() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let tables: Vec<String> = db_service.manager().execute(|conn| {
+            let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table'")?;
+            let tables = stmt.query_map([], |row| row.get(0))?;
+            tables.collect()
+        }).unwrap();
+
+        for table in &tables {
+            let parts = [PROFILE_TABLES, CONTENT_TABLES, SETTINGS_TABLES, KEPT_TABLES].iter()
+                .filter(|part| part.contains(&table.as_str()))
+                .count();
+            assert_eq!(parts, 1, "{} should be in exactly one part of a restore", table);
+        }
+    }
+
+    #[test]
+    fn test_preview_and_restore_only_profiles() {
+        let temp_dir = tempdir().unwrap();
+        let database_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&database_path).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+        let now = Utc::now().to_rfc3339();
+        db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1), ('Alex', 'avatar2', ?1)",
+                params![now],
+            )
+        }).unwrap();
+
+        let backup_path = temp_dir.path().join("backup.db");
+        let target = backup_path.to_string_lossy().into_owned();
+        db_manager.execute(|conn| conn.execute("VACUUM INTO ?1", params![target])).unwrap();
+
+        // Since the backup: Alex was deleted, Jo was added, a subject and a setting changed
+        db_manager.execute(|conn| {
+            conn.execute("DELETE FROM profiles WHERE name = 'Alex'", [])?;
+            conn.execute("INSERT INTO profiles (name, avatar, created_at) VALUES ('Jo', 'avatar3', ?1)", params![now])?;
+            conn.execute("UPDATE subjects SET display_name = 'Maths and Numbers' WHERE id = 1", [])?;
+            conn.execute("UPDATE storage_settings SET backups_to_keep = backups_to_keep + 1", [])
+        }).unwrap();
+
+        let staging_directory = temp_dir.path().join(RESTORE_DIRECTORY);
+        let service = RestoreService::new(db_manager.clone(), staging_directory.clone());
+        let preview = service.prepare(&fs::read(&backup_path).unwrap(), BackupSource::Local, "backup_1", Utc::now()).unwrap();
+        let changes: Vec<_> = preview.profiles.iter().map(|profile| (profile.name.as_str(), profile.change)).collect();
+        assert_eq!(changes, vec![("Alex", ChangeKind::Restored), ("Jo", ChangeKind::Removed)]);
+        assert_eq!(preview.questions, QuestionChanges::default());
+        assert_eq!(preview.settings.iter().map(|change| change.table.as_str()).collect::<Vec<_>>(), vec!["storage_settings"]);
+
+        assert!(service.confirm(&RestoreSelection::Components { components: Vec::new() }).is_err());
+        service.confirm(&RestoreSelection::Components { components: vec![RestoreComponent::Profiles] }).unwrap();
+        drop(service);
+        drop(db_manager);
+        db_service.close().unwrap();
+        assert!(apply_pending_restore(&database_path, &staging_directory).unwrap());
+        assert!(!apply_pending_restore(&database_path, &staging_directory).unwrap());
+
+        // The profiles are back as they were, and the newer content and settings are kept
+        let restored = Connection::open(&database_path).unwrap();
+        let mut stmt = restored.prepare("SELECT name FROM profiles ORDER BY name").unwrap();
+        let names: Vec<String> = stmt.query_map([], |row| row.get(0)).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(names, vec!["Alex".to_string(), "Sam".to_string()]);
+        let subject: String = restored.query_row(
+            "SELECT display_name FROM subjects WHERE id = 1", [], |row| row.get(0),
+        ).unwrap();
+        assert_eq!(subject, "Maths and Numbers");
+    }
+}
@@ -0,0 +1,213 @@
+use crate::errors::AppResult;
+use crate::models::KeyStage;
+use crate::services::{ContentManager, PerformanceLevel, Score};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use chrono::Utc;
+
+/// Card size in pixels, the usual aspect ratio for shared link previews
+const CARD_WIDTH: u32 = 1200;
+const CARD_HEIGHT: u32 = 630;
+
+/// Used when a subject has no usable colour
+const DEFAULT_ACCENT: &str = "#3F51B5";
+
+/// What goes on a results card. Deliberately holds nothing that identifies the child.
+#[derive(Debug, Clone)]
+pub struct ResultCard {
+    pub subject: String,
+    pub key_stage: KeyStage,
+    pub correct_answers: u32,
+    pub total_questions: u32,
+    pub accuracy_percentage: u8,
+    pub performance_level: PerformanceLevel,
+    /// Subject colour as `#RRGGBB`
+    pub accent: String,
+}
+
+/// Renders shareable results cards as SVG images saved to disk, for families to print or
+/// send on themselves. Nothing is uploaded.
+pub struct ResultCardGenerator {
+    content_manager: Arc<ContentManager>,
+    output_dir: PathBuf,
+}
+
+impl ResultCardGenerator {
+    /// Create a new generator saving cards under `output_dir`
+    pub fn new(content_manager: Arc<ContentManager>, output_dir: PathBuf) -> Self {
+        Self {
+            content_manager,
+            output_dir,
+        }
+    }
+
+    /// Render the card for a finished quiz and return where it was saved
+    pub fn generate(&self, subject: &str, key_stage: KeyStage, score: &Score) -> AppResult<PathBuf> {
+        let subject_info = self.content_manager.get_subjects()?
+            .into_iter()
+            .find(|candidate| candidate.name == subject);
+
+        let card = ResultCard {
+            subject: subject_info.as_ref()
+                .map(|info| info.display_name.clone())
+                .unwrap_or_else(|| subject.to_string()),
+            key_stage,
+            correct_answers: score.correct_answers,
+            total_questions: score.total_questions,
+            accuracy_percentage: score.accuracy_percentage,
+            performance_level: score.performance_level.clone(),
+            accent: subject_info
+                .and_then(|info| info.color_scheme)
+                .filter(|colour| is_hex_colour(colour))
+                .unwrap_or_else(|| DEFAULT_ACCENT.to_string()),
+        };
+
+        fs::create_dir_all(&self.output_dir)?;
+        // Named by time only, so the file name gives nothing away either
+        let path = self.output_dir.join(format!("result-{}.svg", Utc::now().format("%Y%m%d-%H%M%S%3f")));
+        fs::write(&path, render_result_card_svg(&card))?;
+        Ok(path)
+    }
+}
+
+/// Badge title and colours (fill, trim) for a performance level
+fn badge(level: &PerformanceLevel) -> (&'static str, &'static str, &'static str) {
+    match level {
+        PerformanceLevel::Excellent => ("Superstar!", "#F4C430", "#B8860B"),
+        PerformanceLevel::Good => ("Brilliant!", "#C0C0C0", "#808080"),
+        PerformanceLevel::Fair => ("Well done!", "#CD7F32", "#8B4513"),
+        PerformanceLevel::NeedsImprovement | PerformanceLevel::Poor => ("Great effort!", "#81C784", "#388E3C"),
+    }
+}
+
+/// Render a results card as a standalone SVG image
+pub fn render_result_card_svg(card: &ResultCard) -> String {
+    let (title, fill, trim) = badge(&card.performance_level);
+    let centre_x = CARD_WIDTH / 2;
+    let mut svg = String::new();
+
+    svg.push_str(&format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = CARD_WIDTH, h = CARD_HEIGHT
+    ));
+    svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"#FFFBEB\"/>\n", CARD_WIDTH, CARD_HEIGHT));
+    svg.push_str(&format!(
+        "<rect x=\"16\" y=\"16\" width=\"{}\" height=\"{}\" rx=\"32\" fill=\"none\" stroke=\"{}\" stroke-width=\"12\"/>\n",
+        CARD_WIDTH - 32, CARD_HEIGHT - 32, card.accent
+    ));
+
+    // Badge: a rosette with a star, ribbons underneath
+    let (badge_x, badge_y) = (230.0, 290.0);
+    svg.push_str(&format!(
+        "<path d=\"M{} {} l-50 170 l50 -35 l50 35 z\" fill=\"{}\"/>\n",
+        badge_x - 40.0, badge_y + 40.0, card.accent
+    ));
+    svg.push_str(&format!(
+        "<path d=\"M{} {} l-50 170 l50 -35 l50 35 z\" fill=\"{}\"/>\n",
+        badge_x + 40.0, badge_y + 40.0, card.accent
+    ));
+    svg.push_str(&format!(
+        "<circle cx=\"{}\" cy=\"{}\" r=\"120\" fill=\"{}\" stroke=\"{}\" stroke-width=\"10\"/>\n",
+        badge_x, badge_y, fill, trim
+    ));
+    svg.push_str(&format!("<path d=\"{}\" fill=\"#FFFFFF\"/>\n", star_path(badge_x, badge_y, 80.0)));
+
+    let text_x = centre_x + 130;
+    svg.push_str(&text(text_x, 150, 64, "#333333", "middle", title));
+    svg.push_str(&text(text_x, 270, 120, &card.accent, "middle", &format!(
+        "{} / {}", card.correct_answers, card.total_questions
+    )));
+    svg.push_str(&text(text_x, 340, 40, "#555555", "middle", &format!("{}% correct", card.accuracy_percentage)));
+    svg.push_str(&text(text_x, 440, 48, "#333333", "middle", &card.subject));
+    svg.push_str(&text(text_x, 500, 32, "#777777", "middle", key_stage_label(card.key_stage)));
+    svg.push_str(&text(CARD_WIDTH - 60, CARD_HEIGHT - 50, 28, "#999999", "end", "QuizDD"));
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn key_stage_label(key_stage: KeyStage) -> &'static str {
+    match key_stage {
+        KeyStage::EYFS => "Reception",
+        KeyStage::KS1 => "Key Stage 1",
+        KeyStage::KS2 => "Key Stage 2",
+        KeyStage::KS3 => "Key Stage 3",
+    }
+}
+
+fn text(x: u32, y: u32, size: u32, colour: &str, anchor: &str, content: &str) -> String {
+    format!(
+        "<text x=\"{}\" y=\"{}\" font-family=\"Helvetica, Arial, sans-serif\" font-weight=\"bold\" font-size=\"{}\" fill=\"{}\" text-anchor=\"{}\">{}</text>\n",
+        x, y, size, colour, anchor, escape_xml(content)
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn is_hex_colour(colour: &str) -> bool {
+    colour.len() == 7 && colour.starts_with('#') && colour[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn star_path(cx: f32, cy: f32, radius: f32) -> String {
+    let mut path = String::new();
+    for point in 0..10 {
+        let r = if point % 2 == 0 { radius } else { radius * 0.4 };
+        let angle = -std::f32::consts::FRAC_PI_2 + point as f32 * std::f32::consts::PI / 5.0;
+        let (x, y) = (cx + r * angle.cos(), cy + r * angle.sin());
+        path.push_str(&format!("{}{:.1} {:.1} ", if point == 0 { "M" } else { "L" }, x, y));
+    }
+    path.push('Z');
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_result_card_is_saved_without_personal_data() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let generator = ResultCardGenerator::new(content_manager, temp_dir.path().join("result_cards"));
+
+        let score = Score {
+            total_questions: 10,
+            correct_answers: 9,
+            accuracy_percentage: 90,
+            total_points: 90,
+            time_bonus: 0,
+            streak_bonus: 0,
+            hint_penalty: 0,
+            final_score: 90,
+            performance_level: PerformanceLevel::Excellent,
+            achievements: Vec::new(),
+            xp: 0,
+        };
+        let path = generator.generate("flags_capitals", KeyStage::KS2, &score).unwrap();
+        assert!(path.file_name().unwrap().to_string_lossy().starts_with("result-"));
+
+        let svg = fs::read_to_string(path).unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(">Flags &amp; Capitals</text>"));
+        assert!(svg.contains(">9 / 10</text>"));
+        assert!(svg.contains(">Superstar!</text>"));
+        assert!(svg.contains("#00BCD4"));
+    }
+}
@@ -0,0 +1,278 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Answer, Drawing, ProfileId, QuestionId, SessionId};
+use crate::database::DatabaseManager;
+use crate::services::drawing_answers::validate_drawing;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Longest typed working-out accepted for one question
+const MAX_SCRATCHPAD_TEXT_LENGTH: usize = 4000;
+
+/// Working-out jotted down for one question, typed, drawn or both
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScratchpadWork {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub drawing: Option<Drawing>,
+}
+
+impl ScratchpadWork {
+    pub fn is_empty(&self) -> bool {
+        self.text.is_none() && self.drawing.is_none()
+    }
+}
+
+/// Working-out for one question, with the answer it led to once submitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScratchpadEntry {
+    pub id: u32,
+    pub profile_id: ProfileId,
+    pub session_id: SessionId,
+    pub question_id: QuestionId,
+    pub work: ScratchpadWork,
+    pub answer: Option<Answer>,
+    pub is_correct: Option<bool>,
+    pub updated_at: DateTime<Utc>,
+    pub answered_at: Option<DateTime<Utc>>,
+}
+
+/// Keeps a child's working-out per question so parents can see how a problem was attempted
+pub struct ScratchpadService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ScratchpadService {
+    /// Create a new scratchpad service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Save the working-out for a question that hasn't been answered yet, replacing what was
+    /// there. Saving empty work clears the scratchpad.
+    pub fn save_work(&self, profile_id: ProfileId, session_id: SessionId, question_id: QuestionId, work: ScratchpadWork) -> AppResult<()> {
+        let work = normalize_work(work)?;
+        let drawing_json = work.drawing.as_ref().map(serde_json::to_string).transpose()?;
+        let now = Utc::now().to_rfc3339();
+
+        self.db_manager.transaction(|tx| {
+            if work.is_empty() {
+                tx.execute(
+                    "DELETE FROM scratchpad_entries
+                     WHERE profile_id = ?1 AND session_id = ?2 AND question_id = ?3 AND answered_at IS NULL",
+                    params![profile_id, session_id, question_id],
+                )?;
+                return Ok(());
+            }
+
+            let updated = tx.execute(
+                "UPDATE scratchpad_entries SET text = ?4, drawing = ?5, updated_at = ?6
+                 WHERE profile_id = ?1 AND session_id = ?2 AND question_id = ?3 AND answered_at IS NULL",
+                params![profile_id, session_id, question_id, work.text, drawing_json, now],
+            )?;
+            if updated == 0 {
+                tx.execute(
+                    "INSERT INTO scratchpad_entries (profile_id, session_id, question_id, text, drawing, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![profile_id, session_id, question_id, work.text, drawing_json, now],
+                )?;
+            }
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Working-out saved for a question still being answered, so it survives navigating away
+    pub fn get_work(&self, profile_id: ProfileId, session_id: SessionId, question_id: QuestionId) -> AppResult<Option<ScratchpadWork>> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT id, profile_id, session_id, question_id, text, drawing, answer, is_correct, updated_at, answered_at
+                 FROM scratchpad_entries
+                 WHERE profile_id = ?1 AND session_id = ?2 AND question_id = ?3 AND answered_at IS NULL",
+                params![profile_id, session_id, question_id],
+                Self::row_to_entry,
+            ).optional()
+        })?.map(|entry| entry.work))
+    }
+
+    /// Store the submitted answer with the question's working-out. Returns false when there was
+    /// no working-out for the question.
+    pub fn attach_answer(
+        &self,
+        profile_id: ProfileId,
+        session_id: SessionId,
+        question_id: QuestionId,
+        answer: &Answer,
+        is_correct: bool,
+    ) -> AppResult<bool> {
+        let answer_json = serde_json::to_string(answer)?;
+
+        let updated = self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE scratchpad_entries SET answer = ?4, is_correct = ?5, answered_at = ?6
+                 WHERE profile_id = ?1 AND session_id = ?2 AND question_id = ?3 AND answered_at IS NULL",
+                params![profile_id, session_id, question_id, answer_json, is_correct, Utc::now().to_rfc3339()],
+            )
+        })?;
+
+        Ok(updated > 0)
+    }
+
+    /// Working-out from one quiz session, in the order it was started
+    pub fn get_session_entries(&self, profile_id: ProfileId, session_id: SessionId) -> AppResult<Vec<ScratchpadEntry>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, profile_id, session_id, question_id, text, drawing, answer, is_correct, updated_at, answered_at
+                 FROM scratchpad_entries WHERE profile_id = ?1 AND session_id = ?2 ORDER BY id"
+            )?;
+            let rows = stmt.query_map(params![profile_id, session_id], Self::row_to_entry)?;
+            rows.collect()
+        })?)
+    }
+
+    /// A profile's most recent answered questions that had working-out, newest first
+    pub fn get_profile_entries(&self, profile_id: ProfileId, limit: u32) -> AppResult<Vec<ScratchpadEntry>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, profile_id, session_id, question_id, text, drawing, answer, is_correct, updated_at, answered_at
+                 FROM scratchpad_entries WHERE profile_id = ?1 AND answered_at IS NOT NULL
+                 ORDER BY answered_at DESC, id DESC LIMIT ?2"
+            )?;
+            let rows = stmt.query_map(params![profile_id, limit], Self::row_to_entry)?;
+            rows.collect()
+        })?)
+    }
+
+    fn row_to_entry(row: &Row) -> rusqlite::Result<ScratchpadEntry> {
+        let drawing_json: Option<String> = row.get(5)?;
+        let drawing = drawing_json
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|_| rusqlite::Error::InvalidColumnType(5, "drawing".to_string(), rusqlite::types::Type::Text))?;
+        let answer_json: Option<String> = row.get(6)?;
+        let answer = answer_json
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|_| rusqlite::Error::InvalidColumnType(6, "answer".to_string(), rusqlite::types::Type::Text))?;
+        let updated_at: String = row.get(8)?;
+        let answered_at: Option<String> = row.get(9)?;
+
+        Ok(ScratchpadEntry {
+            id: row.get(0)?,
+            profile_id: row.get(1)?,
+            session_id: row.get(2)?,
+            question_id: row.get(3)?,
+            work: ScratchpadWork {
+                text: row.get(4)?,
+                drawing,
+            },
+            answer,
+            is_correct: row.get(7)?,
+            updated_at: parse_timestamp(&updated_at).unwrap_or_else(Utc::now),
+            answered_at: answered_at.as_deref().and_then(parse_timestamp),
+        })
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+/// Drop blank text and empty canvases, then check what's left
+fn normalize_work(work: ScratchpadWork) -> AppResult<ScratchpadWork> {
+    let text = work.text.filter(|text| !text.trim().is_empty());
+    if text.as_ref().map_or(false, |text| text.chars().count() > MAX_SCRATCHPAD_TEXT_LENGTH) {
+        return Err(AppError::InvalidInput("Working-out is too long".to_string()));
+    }
+
+    let drawing = work.drawing.filter(|drawing| drawing.point_count() > 0);
+    if let Some(drawing) = &drawing {
+        validate_drawing(drawing)?;
+    }
+
+    Ok(ScratchpadWork { text, drawing })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    fn create_test_scratchpad_service() -> (ScratchpadService, ProfileId, QuestionId, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let (profile_id, question_id) = db_service.manager().execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            let profile_id = ProfileId(conn.last_insert_rowid() as u32);
+            conn.execute(
+                "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer)
+                 VALUES ((SELECT id FROM subjects WHERE name = 'mathematics'), 'KS1', 'fill_blank', '{}', '\"12\"')",
+                [],
+            )?;
+            Ok((profile_id, QuestionId(conn.last_insert_rowid() as u32)))
+        }).unwrap();
+
+        (ScratchpadService::new(db_service.manager()), profile_id, question_id, temp_dir)
+    }
+
+    fn typed(text: &str) -> ScratchpadWork {
+        ScratchpadWork { text: Some(text.to_string()), drawing: None }
+    }
+
+    #[test]
+    fn test_save_work_replaces_until_answered() {
+        let (service, profile_id, question_id, _temp_dir) = create_test_scratchpad_service();
+
+        service.save_work(profile_id, SessionId(1), question_id, typed("5 + 5")).unwrap();
+        service.save_work(profile_id, SessionId(1), question_id, typed("5 + 5 = 10, 10 + 2")).unwrap();
+        let work = service.get_work(profile_id, SessionId(1), question_id).unwrap().unwrap();
+        assert_eq!(work.text.as_deref(), Some("5 + 5 = 10, 10 + 2"));
+
+        // Blank text and an empty canvas clear the scratchpad
+        let cleared = ScratchpadWork {
+            text: Some("  ".to_string()),
+            drawing: Some(Drawing { width: 100.0, height: 100.0, strokes: Vec::new(), image_png_base64: None, recognized_text: None }),
+        };
+        service.save_work(profile_id, SessionId(1), question_id, cleared).unwrap();
+        assert!(service.get_work(profile_id, SessionId(1), question_id).unwrap().is_none());
+
+        assert!(service.save_work(profile_id, SessionId(1), question_id, typed(&"x".repeat(5000))).is_err());
+    }
+
+    #[test]
+    fn test_attach_answer_keeps_work_for_history() {
+        let (service, profile_id, question_id, _temp_dir) = create_test_scratchpad_service();
+
+        assert!(!service.attach_answer(profile_id, SessionId(1), question_id, &Answer::Text("12".to_string()), true).unwrap());
+
+        service.save_work(profile_id, SessionId(1), question_id, typed("10 + 2")).unwrap();
+        assert!(service.attach_answer(profile_id, SessionId(1), question_id, &Answer::Text("12".to_string()), true).unwrap());
+
+        // Answered work is no longer editable; a later session with the same id starts afresh
+        assert!(service.get_work(profile_id, SessionId(1), question_id).unwrap().is_none());
+        service.save_work(profile_id, SessionId(1), question_id, typed("6 + 6")).unwrap();
+
+        let session = service.get_session_entries(profile_id, SessionId(1)).unwrap();
+        assert_eq!(session.len(), 2);
+        assert!(matches!(&session[0].answer, Some(Answer::Text(text)) if text == "12"));
+        assert_eq!(session[0].is_correct, Some(true));
+        assert!(session[1].answer.is_none());
+
+        let history = service.get_profile_entries(profile_id, 10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].work.text.as_deref(), Some("10 + 2"));
+    }
+}
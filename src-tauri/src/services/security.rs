@@ -1,33 +1,101 @@
 use crate::errors::{AppError, AppResult};
 use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 
 /// Parental access challenge
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParentalChallenge {
     pub id: String,
     pub question: String,
+    /// Kept on the backend: the answer is checked by `redeem_parental_challenge`
+    #[serde(skip_serializing, default)]
     pub expected_answer: u32,
     pub expires_at: u64,
 }
 
+/// Permission level required to invoke a command. Levels are ordered so that a
+/// higher level also grants everything below it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Permission {
+    #[serde(rename = "child")]
+    Child,
+    #[serde(rename = "parent")]
+    Parent,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+const PARENTAL_TOKEN_PREFIX: &str = "parental_access_";
+const ADMIN_TOKEN_PREFIX: &str = "admin_access_";
+const PARENTAL_TOKEN_LIFETIME_SECS: u64 = 3600;
+const ADMIN_TOKEN_LIFETIME_SECS: u64 = 900;
+
+/// Length of the key session tokens are signed with
+pub const SESSION_KEY_BYTES: usize = 32;
+
+type HmacSha256 = Hmac<Sha256>;
+
 /// Security service for cryptographic operations and content verification
 pub struct SecurityService {
     key_store: KeyStore,
     signature_verifier: SignatureVerifier,
+    /// Household session tokens are issued for; they grant nothing in any other
+    household_id: AtomicU32,
+    /// Key session tokens are signed with, so they can't be forged
+    session_key: [u8; SESSION_KEY_BYTES],
+    /// Challenges handed out and not yet answered, with when they expire
+    issued_challenges: Mutex<HashMap<String, u64>>,
 }
 
 impl SecurityService {
-    /// Create a new security service with default configuration
+    /// Create a new security service whose session tokens are signed with a fresh random
+    /// key, so they only last as long as the service does
     pub fn new() -> AppResult<Self> {
+        let mut session_key = [0u8; SESSION_KEY_BYTES];
+        getrandom::getrandom(&mut session_key)
+            .map_err(|e| AppError::Security(format!("Failed to generate session key: {}", e)))?;
+        Self::with_session_key(session_key)
+    }
+
+    /// Create a new security service signing session tokens with this install's key
+    pub fn with_session_key(session_key: [u8; SESSION_KEY_BYTES]) -> AppResult<Self> {
         let key_store = KeyStore::new()?;
         let signature_verifier = SignatureVerifier::new()?;
         
         Ok(Self {
             key_store,
             signature_verifier,
+            household_id: AtomicU32::new(crate::services::DEFAULT_HOUSEHOLD_ID),
+            session_key,
+            issued_challenges: Mutex::new(HashMap::new()),
         })
     }
+
+    /// This install's session key, read from `path` or generated and saved there on first run
+    pub fn load_or_create_session_key(path: &Path) -> AppResult<[u8; SESSION_KEY_BYTES]> {
+        match fs::read(path) {
+            Ok(key) => key.try_into()
+                .map_err(|_| AppError::Security(format!("Session key at {} is damaged", path.display()))),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                let mut key = [0u8; SESSION_KEY_BYTES];
+                getrandom::getrandom(&mut key)
+                    .map_err(|e| AppError::Security(format!("Failed to generate session key: {}", e)))?;
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, key)?;
+                Ok(key)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
     
     /// Verify the signature of an update package
     pub fn verify_update_signature(&self, update_data: &[u8], signature: &[u8]) -> AppResult<bool> {
@@ -95,13 +163,38 @@ impl SecurityService {
             }
         };
         
+        let expires_at = timestamp + 300; // 5 minutes expiry
+        let mut issued = self.issued_challenges.lock()
+            .map_err(|_| AppError::Security("Challenge lock poisoned".to_string()))?;
+        issued.retain(|_, expiry| *expiry > timestamp);
+        issued.insert(question.clone(), expires_at);
+
         Ok(ParentalChallenge {
             id: format!("{}", timestamp),
             question,
             expected_answer: answer,
-            expires_at: timestamp + 300, // 5 minutes expiry
+            expires_at,
         })
     }
+
+    /// Check the answer to a challenge this service handed out and, if it's right, issue a
+    /// parental session token. Each challenge can be answered once, so a wrong answer means
+    /// asking for a new one.
+    pub fn redeem_parental_challenge(&self, challenge: &str, input: &str) -> AppResult<Option<String>> {
+        let expires_at = self.issued_challenges.lock()
+            .map_err(|_| AppError::Security("Challenge lock poisoned".to_string()))?
+            .remove(challenge);
+        match expires_at {
+            Some(expires_at) if expires_at > Self::current_timestamp()? => {}
+            _ => return Err(AppError::PermissionDenied("This challenge has expired; ask for a new one".to_string())),
+        }
+
+        if self.validate_parental_access(challenge, input).unwrap_or(false) {
+            self.generate_parental_session_token().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
     
     /// Validate parental access for sensitive features
     pub fn validate_parental_feature_access(&self, feature: &str, session_token: &str) -> AppResult<bool> {
@@ -114,53 +207,121 @@ impl SecurityService {
         }
     }
     
-    /// Generate a session token for parental access
+    /// Switch to another household. Parent and admin tokens issued for the previous one
+    /// stop granting access.
+    pub fn set_household(&self, household_id: u32) {
+        self.household_id.store(household_id, Ordering::SeqCst);
+    }
+
+    /// Generate a session token for parental access. Only call this once the parent has
+    /// proved who they are.
     pub fn generate_parental_session_token(&self) -> AppResult<String> {
-        use std::time::{SystemTime, UNIX_EPOCH};
+        self.generate_session_token(PARENTAL_TOKEN_PREFIX)
+    }
+    
+    /// Exchange a valid parental session token for a short-lived admin token
+    pub fn generate_admin_session_token(&self, parental_token: &str) -> AppResult<String> {
+        if self.session_permission(parental_token)? < Permission::Parent {
+            return Err(AppError::PermissionDenied("Parental access required".to_string()));
+        }
         
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| AppError::Security(format!("Time error: {}", e)))?
-            .as_secs();
+        self.generate_session_token(ADMIN_TOKEN_PREFIX)
+    }
+    
+    /// Get the permission level granted by a session token. Missing, invalid or
+    /// expired tokens only grant child access.
+    pub fn session_permission(&self, token: &str) -> AppResult<Permission> {
+        if token.is_empty() {
+            return Ok(Permission::Child);
+        }
+        
+        let (token_string, signature) = match token.rsplit_once('.') {
+            Some(parts) => parts,
+            None => return Ok(Permission::Child),
+        };
+        let signature = match self.decode_hex(signature) {
+            Ok(signature) => signature,
+            Err(_) => return Ok(Permission::Child),
+        };
+        if self.token_mac(token_string)?.verify_slice(&signature).is_err() {
+            return Ok(Permission::Child);
+        }
         
-        // Simple token generation (in production, use proper JWT or similar)
-        let token_data = format!("parental_access_{}", timestamp);
-        let encrypted_token = self.encrypt_sensitive_data(token_data.as_bytes())?;
+        let (permission, timestamp_str, lifetime) = if let Some(rest) = token_string.strip_prefix(ADMIN_TOKEN_PREFIX) {
+            (Permission::Admin, rest, ADMIN_TOKEN_LIFETIME_SECS)
+        } else if let Some(rest) = token_string.strip_prefix(PARENTAL_TOKEN_PREFIX) {
+            (Permission::Parent, rest, PARENTAL_TOKEN_LIFETIME_SECS)
+        } else {
+            return Ok(Permission::Child);
+        };
+        
+        let (household_str, timestamp_str) = match timestamp_str.split_once(':') {
+            Some(parts) => parts,
+            None => return Ok(Permission::Child),
+        };
+        if household_str.parse::<u32>().ok() != Some(self.household_id.load(Ordering::SeqCst)) {
+            return Ok(Permission::Child);
+        }
+        let token_timestamp = timestamp_str.parse::<u64>()
+            .map_err(|_| AppError::Security("Invalid token timestamp".to_string()))?;
         
-        // Encode as hex string
-        Ok(self.encode_hex(&encrypted_token))
+        if Self::current_timestamp()?.saturating_sub(token_timestamp) < lifetime {
+            Ok(permission)
+        } else {
+            Ok(Permission::Child)
+        }
     }
     
-    /// Validate a parental session token
-    fn validate_session_token(&self, token: &str) -> AppResult<bool> {
-        use std::time::{SystemTime, UNIX_EPOCH};
+    /// Check that a session token grants at least the required permission
+    pub fn authorize(&self, required: Permission, session_token: &str) -> AppResult<()> {
+        if required == Permission::Child {
+            return Ok(());
+        }
         
-        // Decode hex token
-        let encrypted_data = self.decode_hex(token)
-            .map_err(|_| AppError::Security("Invalid token format".to_string()))?;
-        
-        // Decrypt token
-        let decrypted_data = self.decrypt_sensitive_data(&encrypted_data)?;
-        let token_string = String::from_utf8(decrypted_data)
-            .map_err(|_| AppError::Security("Invalid token data".to_string()))?;
-        
-        // Parse timestamp from token
-        if let Some(timestamp_str) = token_string.strip_prefix("parental_access_") {
-            let token_timestamp = timestamp_str.parse::<u64>()
-                .map_err(|_| AppError::Security("Invalid token timestamp".to_string()))?;
-            
-            let current_timestamp = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .map_err(|e| AppError::Security(format!("Time error: {}", e)))?
-                .as_secs();
-            
-            // Token valid for 1 hour
-            Ok(current_timestamp - token_timestamp < 3600)
+        let granted = self.session_permission(session_token)?;
+        if granted >= required {
+            Ok(())
         } else {
-            Ok(false)
+            Err(AppError::PermissionDenied(format!(
+                "This action requires {:?} access",
+                required
+            )))
         }
     }
     
+    /// `prefix household:timestamp`, followed by an HMAC of it under the session key
+    fn generate_session_token(&self, prefix: &str) -> AppResult<String> {
+        let token_data = format!(
+            "{}{}:{}",
+            prefix,
+            self.household_id.load(Ordering::SeqCst),
+            Self::current_timestamp()?
+        );
+        let signature = self.token_mac(&token_data)?.finalize().into_bytes();
+        Ok(format!("{}.{}", token_data, self.encode_hex(&signature)))
+    }
+
+    fn token_mac(&self, token_data: &str) -> AppResult<HmacSha256> {
+        let mut mac = HmacSha256::new_from_slice(&self.session_key)
+            .map_err(|e| AppError::Security(format!("Invalid session key: {}", e)))?;
+        mac.update(token_data.as_bytes());
+        Ok(mac)
+    }
+    
+    fn current_timestamp() -> AppResult<u64> {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        
+        Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| AppError::Security(format!("Time error: {}", e)))?
+            .as_secs())
+    }
+    
+    /// Validate a parental session token
+    fn validate_session_token(&self, token: &str) -> AppResult<bool> {
+        Ok(self.session_permission(token)? >= Permission::Parent)
+    }
+    
     /// Solve math challenge to get expected answer
     fn solve_math_challenge(&self, challenge: &str) -> AppResult<u32> {
         // Parse the challenge string to extract the math problem
@@ -438,4 +599,55 @@ mod tests {
         let invalid_signature = vec![0x00; 16];
         assert!(!service.verify_update_signature(test_data, &invalid_signature).unwrap());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_permission_levels() {
+        let service = SecurityService::new().unwrap();
+        
+        assert!(service.authorize(Permission::Child, "").is_ok());
+        assert!(matches!(service.authorize(Permission::Parent, ""), Err(AppError::PermissionDenied(_))));
+        assert!(service.authorize(Permission::Parent, "not-a-token").is_err());
+        
+        let parental_token = service.generate_parental_session_token().unwrap();
+        assert!(service.authorize(Permission::Parent, &parental_token).is_ok());
+        assert!(service.authorize(Permission::Admin, &parental_token).is_err());
+        
+        let admin_token = service.generate_admin_session_token(&parental_token).unwrap();
+        assert!(service.authorize(Permission::Admin, &admin_token).is_ok());
+        assert!(service.authorize(Permission::Parent, &admin_token).is_ok());
+        assert!(service.generate_admin_session_token("").is_err());
+
+        // Tokens only work in the household they were issued for
+        service.set_household(2);
+        assert!(service.authorize(Permission::Parent, &parental_token).is_err());
+        assert!(service.authorize(Permission::Parent, &admin_token).is_err());
+        service.set_household(crate::services::DEFAULT_HOUSEHOLD_ID);
+        assert!(service.authorize(Permission::Parent, &parental_token).is_ok());
+
+        // Tokens can't be forged or reused under another install's key
+        let forged = parental_token.replacen(PARENTAL_TOKEN_PREFIX, ADMIN_TOKEN_PREFIX, 1);
+        assert!(service.authorize(Permission::Admin, &forged).is_err());
+        let other_install = SecurityService::new().unwrap();
+        assert!(other_install.authorize(Permission::Parent, &parental_token).is_err());
+    }
+
+    #[test]
+    fn test_only_issued_challenges_grant_tokens() {
+        let service = SecurityService::new().unwrap();
+        assert!(service.redeem_parental_challenge("What is 1 + 1?", "2").is_err());
+
+        let challenge = service.generate_parental_challenge().unwrap();
+        let wrong = (challenge.expected_answer + 1).to_string();
+        assert_eq!(service.redeem_parental_challenge(&challenge.question, &wrong).unwrap(), None);
+        // Each challenge is good for one answer
+        let right = challenge.expected_answer.to_string();
+        assert!(service.redeem_parental_challenge(&challenge.question, &right).is_err());
+
+        let challenge = service.generate_parental_challenge().unwrap();
+        let token = service.redeem_parental_challenge(&challenge.question, &challenge.expected_answer.to_string())
+            .unwrap()
+            .unwrap();
+        assert!(service.authorize(Permission::Parent, &token).is_ok());
+        assert!(serde_json::to_value(&challenge).unwrap().get("expected_answer").is_none());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::models::{Question, QuestionId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Most selections a single audit will simulate
+pub const MAX_AUDIT_RUNS: u32 = 5_000;
+
+/// How often one tag or difficulty came up, against how often it would if every
+/// question in the pool were equally likely to be picked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionBucket {
+    pub value: String,
+    /// Questions in the pool with this tag or difficulty
+    pub available: u32,
+    /// Times a question with this tag or difficulty was selected
+    pub selected: u32,
+    /// Share of the pool with this tag or difficulty
+    pub expected_share: f64,
+    /// Share of selected questions with this tag or difficulty
+    pub observed_share: f64,
+}
+
+/// Distribution of simulated question selections, for spotting selection bias
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionAudit {
+    pub runs: u32,
+    pub pool_size: u32,
+    pub questions_selected: u32,
+    pub by_difficulty: Vec<SelectionBucket>,
+    pub by_tag: Vec<SelectionBucket>,
+    /// Pool questions that were never selected
+    pub never_selected: Vec<QuestionId>,
+    /// Average position of selected questions when the pool is sorted by id, from 0 (lowest
+    /// id) to 1 (highest). An unbiased selection averages about 0.5.
+    pub mean_id_percentile: f64,
+    /// Chi-squared statistic of per-question selection counts against a uniform pick,
+    /// allowing for each run picking a question at most once
+    pub chi_squared: f64,
+    pub degrees_of_freedom: u32,
+    /// Whether `chi_squared` is more than three standard deviations above what a uniform
+    /// pick would give
+    pub biased: bool,
+}
+
+impl SelectionAudit {
+    /// Summarise `runs`, each the questions one selection picked from `pool`
+    pub fn from_runs(pool: &[Question], runs: &[Vec<Question>]) -> Self {
+        let mut pool_ids: Vec<QuestionId> = pool.iter().filter_map(|question| question.id).collect();
+        pool_ids.sort_unstable();
+        pool_ids.dedup();
+        let rank: HashMap<QuestionId, usize> = pool_ids.iter().enumerate().map(|(rank, id)| (*id, rank)).collect();
+
+        let mut counts: HashMap<QuestionId, u32> = pool_ids.iter().map(|id| (*id, 0)).collect();
+        let mut percentile_total = 0.0;
+        let selected: Vec<&Question> = runs.iter().flatten().collect();
+        for question in &selected {
+            if let Some(id) = question.id {
+                *counts.entry(id).or_default() += 1;
+                if pool_ids.len() > 1 {
+                    percentile_total += rank.get(&id).copied().unwrap_or_default() as f64 / (pool_ids.len() - 1) as f64;
+                }
+            }
+        }
+
+        let questions_selected = selected.len() as u32;
+        let expected_per_question = questions_selected as f64 / pool_ids.len().max(1) as f64;
+        // Each run picks a question at most once, so its count is binomial over the runs
+        // rather than Poisson, and the variance shrinks as a run covers more of the pool
+        let pick_chance = expected_per_question / runs.len().max(1) as f64;
+        let variance = expected_per_question * (1.0 - pick_chance);
+        let chi_squared = if variance > 0.0 {
+            pool_ids.iter()
+                .map(|id| {
+                    let difference = counts[id] as f64 - expected_per_question;
+                    difference * difference / variance
+                })
+                .sum()
+        } else {
+            0.0
+        };
+        let degrees_of_freedom = pool_ids.len().saturating_sub(1) as u32;
+        let tolerance = 3.0 * (2.0 * degrees_of_freedom as f64).sqrt();
+
+        Self {
+            runs: runs.len() as u32,
+            pool_size: pool_ids.len() as u32,
+            questions_selected,
+            by_difficulty: buckets(pool, &selected, |question| vec![question.difficulty_level.to_string()]),
+            by_tag: buckets(pool, &selected, |question| question.tags.clone()),
+            never_selected: pool_ids.iter().copied().filter(|id| counts[id] == 0).collect(),
+            mean_id_percentile: if questions_selected > 0 {
+                percentile_total / questions_selected as f64
+            } else {
+                0.0
+            },
+            chi_squared,
+            degrees_of_freedom,
+            biased: degrees_of_freedom > 0 && chi_squared > degrees_of_freedom as f64 + tolerance,
+        }
+    }
+}
+
+fn buckets<F>(pool: &[Question], selected: &[&Question], values: F) -> Vec<SelectionBucket>
+where
+    F: Fn(&Question) -> Vec<String>,
+{
+    let mut totals: BTreeMap<String, (u32, u32)> = BTreeMap::new();
+    for question in pool {
+        for value in values(question) {
+            totals.entry(value).or_default().0 += 1;
+        }
+    }
+    for question in selected {
+        for value in values(question) {
+            totals.entry(value).or_default().1 += 1;
+        }
+    }
+
+    let share = |count: u32, total: usize| if total == 0 { 0.0 } else { count as f64 / total as f64 };
+    totals.into_iter()
+        .map(|(value, (available, chosen))| SelectionBucket {
+            value,
+            available,
+            selected: chosen,
+            expected_share: share(available, pool.len()),
+            observed_share: share(chosen, selected.len()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Answer, KeyStage, QuestionContent, QuestionType};
+
+    fn question(id: u32, difficulty: u8) -> Question {
+        let mut question = Question::new(
+            1,
+            KeyStage::KS1,
+            QuestionType::MultipleChoice,
+            QuestionContent {
+                text: format!("Question {}", id),
+                options: None,
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: None,
+                additional_data: None,
+            },
+            Answer::Text("a".to_string()),
+        );
+        question.id = Some(QuestionId(id));
+        question.difficulty_level = difficulty;
+        question.tags = vec![if id % 2 == 0 { "even" } else { "odd" }.to_string()];
+        question
+    }
+
+    #[test]
+    fn test_always_picking_low_ids_is_flagged() {
+        let pool: Vec<Question> = (1..=20).map(|id| question(id, if id <= 10 { 1 } else { 2 })).collect();
+
+        let rotating: Vec<Vec<Question>> = (0..100)
+            .map(|run| (0..5).map(|offset| pool[(run * 5 + offset) % pool.len()].clone()).collect())
+            .collect();
+        let audit = SelectionAudit::from_runs(&pool, &rotating);
+        assert!(!audit.biased);
+        assert!(audit.never_selected.is_empty());
+        assert!((audit.mean_id_percentile - 0.5).abs() < 0.01);
+
+        let lowest: Vec<Vec<Question>> = (0..100).map(|_| pool[..5].to_vec()).collect();
+        let audit = SelectionAudit::from_runs(&pool, &lowest);
+        assert!(audit.biased);
+        assert_eq!(audit.never_selected.len(), 15);
+        assert!(audit.mean_id_percentile < 0.2);
+
+        let easy = &audit.by_difficulty[0];
+        assert_eq!((easy.value.as_str(), easy.available, easy.selected), ("1", 10, 500));
+        assert_eq!(easy.expected_share, 0.5);
+        assert_eq!(easy.observed_share, 1.0);
+        let odd = audit.by_tag.iter().find(|bucket| bucket.value == "odd").unwrap();
+        assert_eq!(odd.selected, 300);
+    }
+}
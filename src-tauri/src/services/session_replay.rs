@@ -0,0 +1,339 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Answer, ProfileId, QuestionId, SessionId};
+use crate::database::DatabaseManager;
+use crate::services::quiz_engine::SessionMode;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Something that happened during a quiz session, in the order it happened
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionEvent {
+    Started {
+        question_count: usize,
+        #[serde(default)]
+        mode: SessionMode,
+    },
+    QuestionShown {
+        /// Which of the question's phrasings was shown; 0 is its own text
+        #[serde(default)]
+        phrasing: usize,
+    },
+    Answered {
+        answer: Answer,
+        is_correct: bool,
+        points: u32,
+        /// Time the frontend reported, which is what scoring used
+        reported_time_seconds: u32,
+    },
+    HintUsed,
+    /// A question that couldn't be shown or marked was swapped out, or dropped if there was
+    /// no replacement
+    QuestionReplaced {
+        stage: String,
+        replacement_question_id: Option<QuestionId>,
+    },
+    Paused,
+    Resumed,
+    /// The child left an assessment's window
+    LeftSession,
+    ReturnedToSession,
+    Completed { correct_answers: usize, total_questions: usize },
+}
+
+impl SessionEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SessionEvent::Started { .. } => "started",
+            SessionEvent::QuestionShown { .. } => "question_shown",
+            SessionEvent::Answered { .. } => "answered",
+            SessionEvent::HintUsed => "hint_used",
+            SessionEvent::QuestionReplaced { .. } => "question_replaced",
+            SessionEvent::Paused => "paused",
+            SessionEvent::Resumed => "resumed",
+            SessionEvent::LeftSession => "left_session",
+            SessionEvent::ReturnedToSession => "returned_to_session",
+            SessionEvent::Completed { .. } => "completed",
+        }
+    }
+}
+
+/// One step of a replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    #[serde(flatten)]
+    pub event: SessionEvent,
+    /// Position of the question in the session, for question events
+    pub question_index: Option<usize>,
+    pub question_id: Option<QuestionId>,
+    pub question_text: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    /// Seconds since the session started
+    pub elapsed_seconds: i64,
+    /// For answers, seconds since the question was shown, including any time paused
+    pub time_spent_seconds: Option<i64>,
+}
+
+/// Everything that happened in a quiz session, for the frontend to play back
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionReplay {
+    pub session_id: SessionId,
+    pub profile_id: ProfileId,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Assessments are shown apart from ordinary quizzes
+    pub mode: SessionMode,
+    pub hints_used: u32,
+    /// Times the child left an assessment before finishing it
+    pub times_left: u32,
+    pub events: Vec<ReplayEvent>,
+}
+
+/// Keeps a timeline of each quiz session so it can be replayed after it's over
+pub struct SessionReplayLog {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl SessionReplayLog {
+    /// Create a new session replay log
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Add an event to a session's timeline
+    pub fn record(
+        &self,
+        session_id: SessionId,
+        profile_id: ProfileId,
+        question: Option<(usize, Option<QuestionId>)>,
+        event: &SessionEvent,
+    ) -> AppResult<()> {
+        let details = serde_json::to_string(event)?;
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO session_events (session_id, profile_id, kind, question_index, question_id, details, occurred_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    session_id,
+                    profile_id,
+                    event.kind(),
+                    question.map(|(index, _)| index as u32),
+                    question.and_then(|(_, question_id)| question_id),
+                    details,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// The phrasing of a question the profile was shown last time they saw it
+    pub fn last_phrasing_shown(&self, profile_id: ProfileId, question_id: QuestionId) -> AppResult<Option<usize>> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT COALESCE(json_extract(details, '$.phrasing'), 0) FROM session_events
+                 WHERE profile_id = ?1 AND question_id = ?2 AND kind = 'question_shown'
+                 ORDER BY id DESC LIMIT 1",
+                params![profile_id, question_id],
+                |row| row.get::<_, u32>(0).map(|phrasing| phrasing as usize),
+            ).optional()
+        })?)
+    }
+
+    /// Highest session id with a timeline, so new sessions don't reuse one after a restart
+    pub fn last_session_id(&self) -> AppResult<u32> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT MAX(
+                    (SELECT COALESCE(MAX(session_id), 0) FROM session_events),
+                    (SELECT COALESCE(MAX(session_id), 0) FROM condensed_sessions)
+                 )",
+                [],
+                |row| row.get(0),
+            )
+        })?)
+    }
+
+    /// The timeline of a session, in order
+    pub fn get_replay(&self, session_id: SessionId) -> AppResult<SessionReplay> {
+        let rows = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT e.profile_id, e.question_index, e.question_id, json_extract(q.content, '$.text'), e.details, e.occurred_at
+                 FROM session_events e
+                 LEFT JOIN questions q ON q.id = e.question_id
+                 WHERE e.session_id = ?1
+                 ORDER BY e.id"
+            )?;
+            let rows = stmt.query_map(params![session_id], |row| {
+                let event: SessionEvent = serde_json::from_str(&row.get::<_, String>(4)?)
+                    .map_err(|_| rusqlite::Error::InvalidColumnType(4, "details".to_string(), rusqlite::types::Type::Text))?;
+                let occurred_at = DateTime::parse_from_rfc3339(&row.get::<_, String>(5)?)
+                    .map_err(|_| rusqlite::Error::InvalidColumnType(5, "occurred_at".to_string(), rusqlite::types::Type::Text))?
+                    .with_timezone(&Utc);
+                Ok((
+                    row.get::<_, ProfileId>(0)?,
+                    ReplayEvent {
+                        event,
+                        question_index: row.get::<_, Option<u32>>(1)?.map(|index| index as usize),
+                        question_id: row.get(2)?,
+                        question_text: row.get(3)?,
+                        occurred_at,
+                        elapsed_seconds: 0,
+                        time_spent_seconds: None,
+                    },
+                ))
+            })?;
+            rows.collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let (profile_id, started_at) = match rows.first() {
+            Some((profile_id, first)) => (*profile_id, first.occurred_at),
+            None => return Err(AppError::NotFound(format!("No replay for quiz session {}", session_id))),
+        };
+
+        let mut events = Vec::with_capacity(rows.len());
+        let mut shown_at: Option<(Option<usize>, DateTime<Utc>)> = None;
+        for (_, mut event) in rows {
+            event.elapsed_seconds = (event.occurred_at - started_at).num_seconds();
+            match event.event {
+                SessionEvent::QuestionShown { .. } => shown_at = Some((event.question_index, event.occurred_at)),
+                SessionEvent::Answered { .. } => {
+                    event.time_spent_seconds = shown_at
+                        .filter(|(index, _)| *index == event.question_index)
+                        .map(|(_, shown)| (event.occurred_at - shown).num_seconds());
+                }
+                _ => {}
+            }
+            events.push(event);
+        }
+
+        Ok(SessionReplay {
+            session_id,
+            profile_id,
+            started_at,
+            completed_at: events.iter()
+                .find(|event| matches!(event.event, SessionEvent::Completed { .. }))
+                .map(|event| event.occurred_at),
+            mode: events.iter()
+                .find_map(|event| match event.event {
+                    SessionEvent::Started { mode, .. } => Some(mode),
+                    _ => None,
+                })
+                .unwrap_or_default(),
+            hints_used: events.iter().filter(|event| matches!(event.event, SessionEvent::HintUsed)).count() as u32,
+            times_left: events.iter().filter(|event| matches!(event.event, SessionEvent::LeftSession)).count() as u32,
+            events,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{KeyStage, Question, QuestionContent, QuestionType};
+    use crate::services::{ContentManager, LateAnswer, QuizMode, QuizConfig, QuizEngine, SecurityService, DEFAULT_HINT_PENALTY_PERCENT};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_replay_follows_the_session_in_order() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let profile_id = db_service.manager().execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let subject_id = content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        for text in ["1 + 1", "2 + 2"] {
+            content_manager.add_question(Question::new(
+                subject_id,
+                KeyStage::KS1,
+                QuestionType::MultipleChoice,
+                QuestionContent {
+                    text: text.to_string(),
+                    options: Some(vec!["right".to_string(), "wrong".to_string()]),
+                    story: None,
+                    image_url: None,
+                    hotspots: None,
+                    blanks: None,
+                    additional_data: None,
+                },
+                Answer::Text("right".to_string()),
+            )).unwrap();
+        }
+
+        let config = QuizConfig {
+            subject: "mathematics".to_string(),
+            key_stage: KeyStage::KS1,
+            question_count: 2,
+            difficulty_range: None,
+            time_limit_seconds: None,
+            randomize_questions: false,
+            randomize_answers: false,
+            max_consecutive_same_tag: None,
+            boss_question: false,
+            warm_up_questions: 0,
+            cool_down_review: false,
+            hint_penalty_percent: DEFAULT_HINT_PENALTY_PERCENT,
+            time_limit_per_question: None,
+            time_limit_total: None,
+            late_answers: LateAnswer::default(),
+            stop_when_mastered: false,
+            mode: QuizMode::default(),
+            allowed_question_types: Vec::new(),
+        };
+        let mut quiz_engine = QuizEngine::new(db_service.manager(), content_manager.clone());
+        let session_id = quiz_engine.start_quiz_session(profile_id, config.clone()).unwrap().id.unwrap();
+
+        let first = quiz_engine.get_current_question(session_id).unwrap().unwrap();
+        quiz_engine.get_current_question(session_id).unwrap();
+        quiz_engine.record_hint_used(session_id).unwrap();
+        quiz_engine.submit_answer(session_id, Answer::Text("wrong".to_string()), 7).unwrap();
+        quiz_engine.pause_quiz(session_id).unwrap();
+        quiz_engine.resume_quiz(session_id).unwrap();
+        quiz_engine.get_current_question(session_id).unwrap();
+        quiz_engine.submit_answer(session_id, Answer::Text("right".to_string()), 3).unwrap();
+
+        let replay = quiz_engine.get_session_replay(session_id).unwrap();
+        let kinds: Vec<&str> = replay.events.iter().map(|event| event.event.kind()).collect();
+        assert_eq!(kinds, vec![
+            "started", "question_shown", "hint_used", "answered", "paused", "resumed",
+            "question_shown", "answered", "completed",
+        ]);
+        assert_eq!(replay.profile_id, profile_id);
+        assert_eq!(replay.hints_used, 1);
+        assert!(replay.completed_at.is_some());
+
+        let answered = &replay.events[3];
+        assert_eq!(answered.question_id, first.id);
+        assert_eq!(answered.question_text.as_deref(), Some(first.content.text.as_str()));
+        assert_eq!(answered.time_spent_seconds, Some(0));
+        match &answered.event {
+            SessionEvent::Answered { is_correct, reported_time_seconds, .. } => {
+                assert!(!is_correct);
+                assert_eq!(*reported_time_seconds, 7);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // A restarted engine doesn't hand out an id that already has a replay
+        let restarted = QuizEngine::new(db_service.manager(), content_manager);
+        assert!(restarted.start_quiz_session(profile_id, config).unwrap().id.unwrap() > session_id);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::errors::AppResult;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+type ShutdownAction = Box<dyn Fn() -> AppResult<()> + Send + Sync>;
+
+/// A named step run during shutdown
+struct ShutdownHook {
+    name: String,
+    action: ShutdownAction,
+}
+
+/// Outcome of a shutdown run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    pub completed_hooks: Vec<String>,
+    pub failed_hooks: Vec<(String, String)>,
+}
+
+impl ShutdownReport {
+    pub fn is_clean(&self) -> bool {
+        self.failed_hooks.is_empty()
+    }
+}
+
+/// Coordinates an orderly shutdown: signals background jobs to stop, then runs
+/// registered hooks (session flush, pending writes, pool close) in registration order.
+pub struct ShutdownCoordinator {
+    signal: watch::Sender<bool>,
+    hooks: Mutex<Vec<ShutdownHook>>,
+    shutting_down: AtomicBool,
+}
+
+impl ShutdownCoordinator {
+    /// Create a new shutdown coordinator
+    pub fn new() -> Self {
+        let (signal, _) = watch::channel(false);
+
+        Self {
+            signal,
+            hooks: Mutex::new(Vec::new()),
+            shutting_down: AtomicBool::new(false),
+        }
+    }
+
+    /// Register a hook. Hooks run in the order they were registered.
+    pub fn register_hook<F>(&self, name: &str, action: F)
+    where
+        F: Fn() -> AppResult<()> + Send + Sync + 'static,
+    {
+        if let Ok(mut hooks) = self.hooks.lock() {
+            hooks.push(ShutdownHook {
+                name: name.to_string(),
+                action: Box::new(action),
+            });
+        }
+    }
+
+    /// Subscribe to the shutdown signal. Background jobs should stop once the value becomes `true`.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.signal.subscribe()
+    }
+
+    /// Whether shutdown has started
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// Run the shutdown sequence. Only the first call does any work; later calls return `None`.
+    pub fn shutdown(&self) -> Option<ShutdownReport> {
+        if self.shutting_down.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+
+        // Stop background jobs before flushing state they might still be touching
+        self.signal.send_replace(true);
+
+        let mut report = ShutdownReport {
+            completed_hooks: Vec::new(),
+            failed_hooks: Vec::new(),
+        };
+
+        let hooks = match self.hooks.lock() {
+            Ok(mut hooks) => std::mem::take(&mut *hooks),
+            Err(_) => Vec::new(),
+        };
+
+        for hook in hooks {
+            match (hook.action)() {
+                Ok(()) => report.completed_hooks.push(hook.name),
+                Err(e) => {
+                    log::error!("Shutdown hook '{}' failed: {}", hook.name, e);
+                    report.failed_hooks.push((hook.name, e.to_string()));
+                }
+            }
+        }
+
+        Some(report)
+    }
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::AppError;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_hooks_run_once_in_order() {
+        let coordinator = ShutdownCoordinator::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+
+        for name in ["flush_sessions", "close_database"] {
+            let calls = calls.clone();
+            coordinator.register_hook(name, move || {
+                calls.lock().unwrap().push(name);
+                Ok(())
+            });
+        }
+
+        let report = coordinator.shutdown().unwrap();
+        assert!(report.is_clean());
+        assert_eq!(*calls.lock().unwrap(), vec!["flush_sessions", "close_database"]);
+
+        assert!(coordinator.shutdown().is_none());
+        assert_eq!(calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_failing_hook_does_not_stop_later_hooks() {
+        let coordinator = ShutdownCoordinator::new();
+        let receiver = coordinator.subscribe();
+
+        coordinator.register_hook("broken", || Err(AppError::Internal("disk full".to_string())));
+        coordinator.register_hook("close_database", || Ok(()));
+
+        let report = coordinator.shutdown().unwrap();
+        assert!(!report.is_clean());
+        assert_eq!(report.failed_hooks[0].0, "broken");
+        assert_eq!(report.completed_hooks, vec!["close_database".to_string()]);
+        assert!(*receiver.borrow());
+        assert!(coordinator.is_shutting_down());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{ProfileId, QuestionId};
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+
+/// Highest SM-2 recall quality: a perfect, instant answer
+pub const MAX_QUALITY: u8 = 5;
+/// Quality recorded for a right answer given in a quiz
+pub const CORRECT_ANSWER_QUALITY: u8 = 4;
+/// Quality recorded for a wrong answer given in a quiz
+pub const WRONG_ANSWER_QUALITY: u8 = 1;
+/// Answers below this quality count as forgotten and start the question over
+const PASSING_QUALITY: u8 = 3;
+const INITIAL_EASE_FACTOR: f64 = 2.5;
+const MIN_EASE_FACTOR: f64 = 1.3;
+/// Days until the first and second reviews of a remembered question
+const FIRST_INTERVAL_DAYS: u32 = 1;
+const SECOND_INTERVAL_DAYS: u32 = 6;
+/// Longest gap between reviews, so nothing disappears for years
+const MAX_INTERVAL_DAYS: u32 = 365;
+
+/// Where one question stands in a profile's review schedule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuestionReview {
+    pub profile_id: ProfileId,
+    pub question_id: QuestionId,
+    /// SM-2 easiness: how quickly the interval grows after each remembered review
+    pub ease_factor: f64,
+    pub interval_days: u32,
+    /// Remembered reviews in a row
+    pub repetitions: u32,
+    pub due_at: DateTime<Utc>,
+    pub last_reviewed_at: DateTime<Utc>,
+}
+
+impl QuestionReview {
+    fn new(profile_id: ProfileId, question_id: QuestionId, now: DateTime<Utc>) -> Self {
+        Self {
+            profile_id,
+            question_id,
+            ease_factor: INITIAL_EASE_FACTOR,
+            interval_days: 0,
+            repetitions: 0,
+            due_at: now,
+            last_reviewed_at: now,
+        }
+    }
+
+    /// Apply the SM-2 update for an answer of `quality` (0-5) given at `now`
+    fn apply(&mut self, quality: u8, now: DateTime<Utc>) {
+        if quality >= PASSING_QUALITY {
+            self.interval_days = match self.repetitions {
+                0 => FIRST_INTERVAL_DAYS,
+                1 => SECOND_INTERVAL_DAYS,
+                _ => ((self.interval_days as f64 * self.ease_factor).round() as u32).min(MAX_INTERVAL_DAYS),
+            };
+            self.repetitions += 1;
+        } else {
+            self.repetitions = 0;
+            self.interval_days = FIRST_INTERVAL_DAYS;
+        }
+
+        let shortfall = (MAX_QUALITY - quality) as f64;
+        self.ease_factor = (self.ease_factor + 0.1 - shortfall * (0.08 + shortfall * 0.02)).max(MIN_EASE_FACTOR);
+        self.due_at = now + Duration::days(self.interval_days as i64);
+        self.last_reviewed_at = now;
+    }
+}
+
+/// SM-2 style spaced repetition: every question a profile answers is scheduled for
+/// review, sooner if it was got wrong and further apart each time it is remembered.
+pub struct ReviewScheduler {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl ReviewScheduler {
+    /// Create a new review scheduler
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Record how well a question was recalled, on the SM-2 scale of 0 (blank) to 5
+    /// (perfect), and reschedule it
+    pub fn record_review_result(&self, profile_id: ProfileId, question_id: QuestionId, quality: u8) -> AppResult<QuestionReview> {
+        self.record_review_result_at(profile_id, question_id, quality, Utc::now())
+    }
+
+    fn record_review_result_at(
+        &self,
+        profile_id: ProfileId,
+        question_id: QuestionId,
+        quality: u8,
+        now: DateTime<Utc>,
+    ) -> AppResult<QuestionReview> {
+        if quality > MAX_QUALITY {
+            return Err(AppError::InvalidInput(format!(
+                "Review quality must be between 0 and {}", MAX_QUALITY
+            )));
+        }
+
+        Ok(self.db_manager.transaction(|tx| {
+            let mut review = tx.query_row(
+                "SELECT ease_factor, interval_days, repetitions, due_at, last_reviewed_at
+                 FROM question_reviews WHERE profile_id = ?1 AND question_id = ?2",
+                params![profile_id, question_id],
+                |row| row_to_review(row, profile_id, question_id),
+            ).optional()?.unwrap_or_else(|| QuestionReview::new(profile_id, question_id, now));
+            review.apply(quality, now);
+
+            tx.execute(
+                "INSERT OR REPLACE INTO question_reviews
+                    (profile_id, question_id, ease_factor, interval_days, repetitions, due_at, last_reviewed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    profile_id,
+                    question_id,
+                    review.ease_factor,
+                    review.interval_days,
+                    review.repetitions,
+                    review.due_at.to_rfc3339(),
+                    review.last_reviewed_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(review)
+        })?)
+    }
+
+    /// Questions due for review, most overdue first. Retired questions are left out.
+    pub fn get_due_reviews(&self, profile_id: ProfileId, limit: usize) -> AppResult<Vec<QuestionReview>> {
+        self.get_due_reviews_at(profile_id, limit, Utc::now())
+    }
+
+    fn get_due_reviews_at(&self, profile_id: ProfileId, limit: usize, now: DateTime<Utc>) -> AppResult<Vec<QuestionReview>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT ease_factor, interval_days, repetitions, due_at, last_reviewed_at, question_id
+                 FROM question_reviews
+                 WHERE profile_id = ?1 AND due_at <= ?2
+                   AND question_id NOT IN (SELECT question_id FROM retired_questions)
+                 ORDER BY due_at, question_id
+                 LIMIT ?3"
+            )?;
+            let reviews = stmt.query_map(params![profile_id, now.to_rfc3339(), limit as i64], |row| {
+                row_to_review(row, profile_id, QuestionId(row.get(5)?))
+            })?.collect::<Result<Vec<_>, _>>()?;
+            Ok(reviews)
+        })?)
+    }
+}
+
+fn row_to_review(row: &rusqlite::Row, profile_id: ProfileId, question_id: QuestionId) -> rusqlite::Result<QuestionReview> {
+    let parse = |index: usize| -> rusqlite::Result<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&row.get::<_, String>(index)?)
+            .map(|value| value.with_timezone(&Utc))
+            .map_err(|_| rusqlite::Error::InvalidColumnType(index, "question_reviews".to_string(), rusqlite::types::Type::Text))
+    };
+    Ok(QuestionReview {
+        profile_id,
+        question_id,
+        ease_factor: row.get(0)?,
+        interval_days: row.get(1)?,
+        repetitions: row.get(2)?,
+        due_at: parse(3)?,
+        last_reviewed_at: parse(4)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_intervals_grow_when_remembered_and_reset_when_forgotten() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+        let scheduler = ReviewScheduler::new(db_manager.clone());
+
+        let (profile_id, question_ids) = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            let profile_id = ProfileId(conn.last_insert_rowid() as u32);
+            let mut question_ids = Vec::new();
+            for _ in 0..2 {
+                conn.execute(
+                    "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer, created_at)
+                     VALUES (1, 'KS1', 'multiple_choice', '{}', '\"4\"', ?1)",
+                    params![Utc::now().to_rfc3339()],
+                )?;
+                question_ids.push(QuestionId(conn.last_insert_rowid() as u32));
+            }
+            Ok((profile_id, question_ids))
+        }).unwrap();
+        let (remembered, forgotten) = (question_ids[0], question_ids[1]);
+
+        let start = Utc::now();
+        let day = |days: i64| start + Duration::days(days);
+        assert!(scheduler.record_review_result_at(profile_id, remembered, 6, start).is_err());
+
+        let intervals: Vec<u32> = [(0, 5), (1, 4), (7, 4)].iter()
+            .map(|&(on, quality)| scheduler.record_review_result_at(profile_id, remembered, quality, day(on)).unwrap().interval_days)
+            .collect();
+        assert_eq!(intervals, vec![1, 6, 16]);
+
+        let review = scheduler.record_review_result_at(profile_id, forgotten, WRONG_ANSWER_QUALITY, start).unwrap();
+        assert_eq!((review.interval_days, review.repetitions), (1, 0));
+        assert!(review.ease_factor < INITIAL_EASE_FACTOR);
+
+        // Only the forgotten question is due tomorrow; both are due once the long interval passes
+        let due: Vec<QuestionId> = scheduler.get_due_reviews_at(profile_id, 10, day(1)).unwrap()
+            .iter().map(|review| review.question_id).collect();
+        assert_eq!(due, vec![forgotten]);
+        let due = scheduler.get_due_reviews_at(profile_id, 10, day(30)).unwrap();
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].question_id, forgotten);
+        assert_eq!(scheduler.get_due_reviews_at(profile_id, 1, day(30)).unwrap().len(), 1);
+    }
+}
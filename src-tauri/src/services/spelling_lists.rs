@@ -0,0 +1,642 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Answer, AnswerNormalization, BlankConfig, KeyStage, NormalizationRule, Question, QuestionContent, QuestionSource, QuestionType, ShortAnswerConfig, NORMALIZATION_KEY, SHORT_ANSWER_KEY, ProfileId, QuestionId};
+use crate::database::{row_exists, DatabaseManager};
+use crate::services::{ContentManager, FormattingService};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Correct attempts in a row a word needs before it counts as ready for the test
+const READY_STREAK: usize = 2;
+
+/// Most words a weekly list can hold
+const MAX_WORDS: usize = 30;
+
+/// A word on a spelling list, with an optional sentence to use it in
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpellingWord {
+    pub word: String,
+    /// e.g. "It is necessary to wear a coat.", used as the clue in the generated questions
+    #[serde(default)]
+    pub sentence: Option<String>,
+}
+
+/// A school's weekly word list, entered by a parent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSpellingListRequest {
+    pub profile_id: ProfileId,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default = "default_key_stage")]
+    pub key_stage: KeyStage,
+    /// Day of the test at school, usually a Friday
+    pub test_date: NaiveDate,
+    pub words: Vec<SpellingWord>,
+}
+
+fn default_key_stage() -> KeyStage {
+    KeyStage::KS2
+}
+
+/// A word on a stored list, with the questions made from it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpellingListWord {
+    pub word: String,
+    pub sentence: Option<String>,
+    /// Short-answer "spell the word" question
+    pub spelling_question_id: Option<QuestionId>,
+    /// Fill-blank "fill in the missing letters" question
+    pub fill_blank_question_id: Option<QuestionId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpellingList {
+    pub id: u32,
+    pub profile_id: ProfileId,
+    pub title: String,
+    pub key_stage: KeyStage,
+    /// First day of practice, the day the list was entered
+    pub starts_on: NaiveDate,
+    pub test_date: NaiveDate,
+    pub words: Vec<SpellingListWord>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The words to practise on one day of the week
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PracticeDay {
+    pub date: NaiveDate,
+    pub words: Vec<String>,
+    /// The last day before the test goes over every word
+    pub review: bool,
+}
+
+/// Result of one attempt at spelling a word
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpellingAttemptResult {
+    pub word: String,
+    pub correct: bool,
+    /// Whether the word now counts as ready for the test
+    pub ready: bool,
+}
+
+/// How well one word is known
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordReadiness {
+    pub word: String,
+    pub attempts: u32,
+    pub correct: u32,
+    pub ready: bool,
+}
+
+/// How ready a child is for the test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpellingReadiness {
+    pub list_id: u32,
+    pub test_date: NaiveDate,
+    /// Negative once the test has passed
+    pub days_until_test: i64,
+    pub words: Vec<WordReadiness>,
+    pub ready_words: usize,
+    pub readiness_percentage: u8,
+}
+
+/// Weekly spelling lists: turns the school's word list into practice questions, spreads
+/// practice across the days before the test and tracks how ready each word is
+pub struct SpellingListService {
+    db_manager: Arc<DatabaseManager>,
+    content_manager: Arc<ContentManager>,
+    formatting: FormattingService,
+}
+
+impl SpellingListService {
+    /// Create a new spelling list service
+    pub fn new(db_manager: Arc<DatabaseManager>, content_manager: Arc<ContentManager>) -> Self {
+        Self {
+            formatting: FormattingService::new(db_manager.clone()),
+            db_manager,
+            content_manager,
+        }
+    }
+
+    /// Store a weekly list, starting practice `today`, and generate its questions
+    pub fn create_list(&self, request: CreateSpellingListRequest, today: NaiveDate) -> AppResult<SpellingList> {
+        let words = validate_words(&request.words)?;
+        if request.test_date < today {
+            return Err(AppError::InvalidInput("The test date has already passed".to_string()));
+        }
+
+        let english_id = self.content_manager.get_subjects()?
+            .into_iter()
+            .find(|subject| subject.name == "english")
+            .and_then(|subject| subject.id)
+            .ok_or_else(|| AppError::NotFound("The English subject is missing".to_string()))?;
+
+        let title = match request.title.clone().filter(|title| !title.trim().is_empty()) {
+            Some(title) => title,
+            None => format!(
+                "Spellings for {}",
+                self.formatting.get_locale()?.format_weekday_date(request.test_date, false)
+            ),
+        };
+
+        let list_id = self.db_manager.transaction(|tx| {
+            if !row_exists(tx, request.profile_id)? {
+                return Ok(None);
+            }
+
+            tx.execute(
+                "INSERT INTO spelling_lists (profile_id, title, key_stage, starts_on, test_date, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    request.profile_id,
+                    title,
+                    request.key_stage.as_str(),
+                    today.format(DATE_FORMAT).to_string(),
+                    request.test_date.format(DATE_FORMAT).to_string(),
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(Some(tx.last_insert_rowid() as u32))
+        })?.ok_or(AppError::ProfileNotFound { id: request.profile_id })?;
+
+        for (position, word) in words.iter().enumerate() {
+            let tags = vec!["spelling".to_string(), format!("spelling_list_{}", list_id)];
+            let spelling_id = self.content_manager.add_question(
+                spelling_question(english_id, request.key_stage, word).with_tags(tags.clone())
+            )?;
+            let fill_blank_id = self.content_manager.add_question(
+                fill_blank_question(english_id, request.key_stage, &word.word).with_tags(tags)
+            )?;
+
+            self.db_manager.execute(|conn| {
+                conn.execute(
+                    "INSERT INTO spelling_words (list_id, position, word, sentence, spelling_question_id, fill_blank_question_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![list_id, position as u32, word.word, word.sentence, spelling_id, fill_blank_id],
+                )
+            })?;
+        }
+
+        self.get_list(list_id)
+    }
+
+    /// A profile's lists, soonest test first
+    pub fn get_lists(&self, profile_id: ProfileId) -> AppResult<Vec<SpellingList>> {
+        let list_ids: Vec<u32> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id FROM spelling_lists WHERE profile_id = ?1 ORDER BY test_date DESC, id DESC"
+            )?;
+            let rows = stmt.query_map(params![profile_id], |row| row.get(0))?;
+            rows.collect()
+        })?;
+
+        list_ids.into_iter().map(|list_id| self.get_list(list_id)).collect()
+    }
+
+    pub fn get_list(&self, list_id: u32) -> AppResult<SpellingList> {
+        self.db_manager.execute(|conn| {
+            let list = conn.query_row(
+                "SELECT id, profile_id, title, key_stage, starts_on, test_date, created_at
+                 FROM spelling_lists WHERE id = ?1",
+                params![list_id],
+                |row| {
+                    Ok(SpellingList {
+                        id: row.get(0)?,
+                        profile_id: row.get(1)?,
+                        title: row.get(2)?,
+                        key_stage: KeyStage::from_str(&row.get::<_, String>(3)?)
+                            .map_err(|_| rusqlite::Error::InvalidColumnType(3, "key_stage".to_string(), rusqlite::types::Type::Text))?,
+                        starts_on: parse_date(&row.get::<_, String>(4)?, 4)?,
+                        test_date: parse_date(&row.get::<_, String>(5)?, 5)?,
+                        words: Vec::new(),
+                        created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(6)?)
+                            .map_err(|_| rusqlite::Error::InvalidColumnType(6, "created_at".to_string(), rusqlite::types::Type::Text))?
+                            .with_timezone(&Utc),
+                    })
+                },
+            ).optional()?;
+
+            let mut list = match list {
+                Some(list) => list,
+                None => return Ok(None),
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT word, sentence, spelling_question_id, fill_blank_question_id
+                 FROM spelling_words WHERE list_id = ?1 ORDER BY position"
+            )?;
+            let words = stmt.query_map(params![list_id], |row| {
+                Ok(SpellingListWord {
+                    word: row.get(0)?,
+                    sentence: row.get(1)?,
+                    spelling_question_id: row.get(2)?,
+                    fill_blank_question_id: row.get(3)?,
+                })
+            })?;
+            list.words = words.collect::<Result<_, _>>()?;
+            Ok(Some(list))
+        })?.ok_or_else(|| AppError::NotFound(format!("Spelling list {} not found", list_id)))
+    }
+
+    /// Delete a list. Its questions are retired, so any quiz history stays readable.
+    pub fn delete_list(&self, list_id: u32) -> AppResult<()> {
+        let list = self.get_list(list_id)?;
+        for word in &list.words {
+            for question_id in word.spelling_question_id.iter().chain(word.fill_blank_question_id.iter()) {
+                self.content_manager.delete_question(*question_id)?;
+            }
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute("DELETE FROM spelling_lists WHERE id = ?1", params![list_id])
+        })?;
+        Ok(())
+    }
+
+    /// Which words to practise on which day, from the day the list was entered to the day
+    /// before the test
+    pub fn practice_schedule(&self, list_id: u32) -> AppResult<Vec<PracticeDay>> {
+        Ok(build_schedule(&self.get_list(list_id)?))
+    }
+
+    /// Questions to practise on `date`: that day's words plus any earlier words that aren't
+    /// ready yet. On the review day, every word.
+    pub fn get_practice_questions(&self, list_id: u32, date: NaiveDate) -> AppResult<Vec<Question>> {
+        let list = self.get_list(list_id)?;
+        let readiness = self.get_readiness(list_id, date)?;
+        let ready: HashMap<&str, bool> = readiness.words.iter()
+            .map(|word| (word.word.as_str(), word.ready))
+            .collect();
+
+        let mut words: Vec<String> = Vec::new();
+        for day in build_schedule(&list).iter().filter(|day| day.date <= date) {
+            for word in &day.words {
+                let due = day.date == date || !ready.get(word.as_str()).copied().unwrap_or(false);
+                if due && !words.contains(word) {
+                    words.push(word.clone());
+                }
+            }
+        }
+
+        let mut questions = Vec::new();
+        for word in list.words.iter().filter(|word| words.contains(&word.word)) {
+            for question_id in word.spelling_question_id.iter().chain(word.fill_blank_question_id.iter()) {
+                questions.push(self.content_manager.get_question_by_id(*question_id)?);
+            }
+        }
+        Ok(questions)
+    }
+
+    /// Mark an attempt at spelling a word from the list. Spelling must be exact; only
+    /// capital letters and surrounding spaces are forgiven.
+    pub fn submit_attempt(&self, list_id: u32, word: &str, answer: &str) -> AppResult<SpellingAttemptResult> {
+        let list = self.get_list(list_id)?;
+        let expected = list.words.iter()
+            .find(|candidate| candidate.word.eq_ignore_ascii_case(word.trim()))
+            .ok_or_else(|| AppError::NotFound(format!("'{}' is not on this spelling list", word)))?;
+        let correct = answer.trim().to_lowercase() == expected.word.to_lowercase();
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO spelling_attempts (list_id, word, answer, correct, attempted_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![list_id, expected.word, answer.trim(), correct, Utc::now().to_rfc3339()],
+            )
+        })?;
+
+        let ready = self.recent_results(list_id, &expected.word)?.iter().take(READY_STREAK).filter(|&&correct| correct).count() == READY_STREAK;
+        Ok(SpellingAttemptResult {
+            word: expected.word.clone(),
+            correct,
+            ready,
+        })
+    }
+
+    /// How ready each word is, for the parent to check before the test
+    pub fn get_readiness(&self, list_id: u32, today: NaiveDate) -> AppResult<SpellingReadiness> {
+        let list = self.get_list(list_id)?;
+
+        let mut words = Vec::new();
+        for word in &list.words {
+            let results = self.recent_results(list_id, &word.word)?;
+            words.push(WordReadiness {
+                word: word.word.clone(),
+                attempts: results.len() as u32,
+                correct: results.iter().filter(|&&correct| correct).count() as u32,
+                ready: results.len() >= READY_STREAK && results.iter().take(READY_STREAK).all(|&correct| correct),
+            });
+        }
+
+        let ready_words = words.iter().filter(|word| word.ready).count();
+        Ok(SpellingReadiness {
+            list_id,
+            test_date: list.test_date,
+            days_until_test: (list.test_date - today).num_days(),
+            readiness_percentage: (ready_words * 100 / words.len().max(1)) as u8,
+            ready_words,
+            words,
+        })
+    }
+
+    /// Results of every attempt at a word, newest first
+    fn recent_results(&self, list_id: u32, word: &str) -> AppResult<Vec<bool>> {
+        Ok(self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT correct FROM spelling_attempts WHERE list_id = ?1 AND word = ?2 ORDER BY id DESC"
+            )?;
+            let rows = stmt.query_map(params![list_id, word], |row| row.get(0))?;
+            rows.collect()
+        })?)
+    }
+}
+
+fn validate_words(words: &[SpellingWord]) -> AppResult<Vec<SpellingWord>> {
+    if words.is_empty() {
+        return Err(AppError::InvalidInput("A spelling list needs at least one word".to_string()));
+    }
+    if words.len() > MAX_WORDS {
+        return Err(AppError::InvalidInput(format!("A spelling list can have at most {} words", MAX_WORDS)));
+    }
+
+    let mut cleaned: Vec<SpellingWord> = Vec::new();
+    for word in words {
+        let text = word.word.trim();
+        if text.is_empty() || !text.chars().all(|c| c.is_alphabetic() || c == '\'' || c == '-') {
+            return Err(AppError::InvalidInput(format!("'{}' is not a single word", word.word)));
+        }
+        if cleaned.iter().any(|other| other.word.eq_ignore_ascii_case(text)) {
+            return Err(AppError::InvalidInput(format!("'{}' is on the list twice", text)));
+        }
+        cleaned.push(SpellingWord {
+            word: text.to_string(),
+            sentence: word.sentence.as_ref().map(|sentence| sentence.trim().to_string()).filter(|sentence| !sentence.is_empty()),
+        });
+    }
+    Ok(cleaned)
+}
+
+/// Spread the words over the days before the test, keeping the last day for review
+fn build_schedule(list: &SpellingList) -> Vec<PracticeDay> {
+    let words: Vec<String> = list.words.iter().map(|word| word.word.clone()).collect();
+    let days = (list.test_date - list.starts_on).num_days().max(1) as usize;
+    if days == 1 {
+        return vec![PracticeDay { date: list.starts_on, words, review: true }];
+    }
+
+    let learning_days = days - 1;
+    let mut schedule: Vec<PracticeDay> = (0..learning_days)
+        .map(|day| PracticeDay {
+            date: list.starts_on + Duration::days(day as i64),
+            words: words.iter()
+                .enumerate()
+                .filter(|(index, _)| index * learning_days / words.len() == day)
+                .map(|(_, word)| word.clone())
+                .collect(),
+            review: false,
+        })
+        .filter(|day| !day.words.is_empty())
+        .collect();
+    schedule.push(PracticeDay {
+        date: list.test_date - Duration::days(1),
+        words,
+        review: true,
+    });
+    schedule
+}
+
+/// "Spell the word" question, with the word blanked out of its sentence as the clue
+fn spelling_question(subject_id: u32, key_stage: KeyStage, word: &SpellingWord) -> Question {
+    let text = match &word.sentence {
+        Some(sentence) => format!("Spell the missing word: {}", blank_out(sentence, &word.word)),
+        None => "Listen and spell the word.".to_string(),
+    };
+    let config = ShortAnswerConfig {
+        accepted_answers: Vec::new(),
+        max_edit_distance: Some(0),
+        case_sensitive: false,
+    };
+
+    Question::new(
+        subject_id,
+        key_stage,
+        QuestionType::ShortAnswer,
+        QuestionContent {
+            text,
+            options: None,
+            story: None,
+            image_url: None,
+            hotspots: None,
+            blanks: None,
+            additional_data: Some(HashMap::from([
+                (SHORT_ANSWER_KEY.to_string(), serde_json::to_value(&config).unwrap_or_default()),
+                (NORMALIZATION_KEY.to_string(), spelling_normalization()),
+            ])),
+        },
+        Answer::Text(word.word.clone()),
+    )
+    .with_difficulty(2)
+    .with_source(QuestionSource::Generator { name: "spelling_list".to_string() })
+}
+
+/// "Fill in the missing letters" question, with the vowels after the first letter hidden
+fn fill_blank_question(subject_id: u32, key_stage: KeyStage, word: &str) -> Question {
+    let mut masked = String::new();
+    for (index, c) in word.chars().enumerate() {
+        masked.push(if index > 0 && "aeiouAEIOU".contains(c) { '_' } else { c });
+    }
+    if !masked.contains('_') {
+        // No vowels to hide, e.g. "rhythm": hide the middle letter instead
+        let middle = word.chars().count() / 2;
+        masked = word.chars().enumerate().map(|(index, c)| if index == middle { '_' } else { c }).collect();
+    }
+
+    Question::new(
+        subject_id,
+        key_stage,
+        QuestionType::FillBlank,
+        QuestionContent {
+            text: format!("Fill in the missing letters to spell the whole word: {}", masked),
+            options: None,
+            story: None,
+            image_url: None,
+            hotspots: None,
+            blanks: Some(vec![BlankConfig {
+                position: 0,
+                expected_answer: word.to_string(),
+                case_sensitive: false,
+                accept_alternatives: None,
+                max_edit_distance: Some(0),
+            }]),
+            additional_data: Some(HashMap::from([
+                (NORMALIZATION_KEY.to_string(), spelling_normalization()),
+            ])),
+        },
+        Answer::Text(word.to_string()),
+    )
+    .with_difficulty(1)
+    .with_source(QuestionSource::Generator { name: "spelling_list".to_string() })
+}
+
+/// The default answer normalisation without number words or money, so "8" doesn't count
+/// as a spelling of "eight"
+fn spelling_normalization() -> serde_json::Value {
+    let normalization = AnswerNormalization::default()
+        .without(NormalizationRule::NumberWords)
+        .without(NormalizationRule::Currency);
+    serde_json::to_value(&normalization).unwrap_or_default()
+}
+
+/// Replace the word in a sentence with a blank, ignoring case
+fn blank_out(sentence: &str, word: &str) -> String {
+    let lower = sentence.to_lowercase();
+    match lower.find(&word.to_lowercase()) {
+        Some(start) if lower.len() == sentence.len() => {
+            format!("{}___{}", &sentence[..start], &sentence[start + word.len()..])
+        }
+        _ => sentence.to_string(),
+    }
+}
+
+fn parse_date(value: &str, index: usize) -> rusqlite::Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, DATE_FORMAT)
+        .map_err(|_| rusqlite::Error::InvalidColumnType(index, "date".to_string(), rusqlite::types::Type::Text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::services::{QuizEngine, SecurityService};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_weekly_list_schedules_practice_and_tracks_readiness() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let profile_id = db_service.manager().execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let service = SpellingListService::new(db_service.manager(), content_manager.clone());
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2024, 3, 8).unwrap();
+        let word = |word: &str, sentence: Option<&str>| SpellingWord {
+            word: word.to_string(),
+            sentence: sentence.map(str::to_string),
+        };
+        let request = CreateSpellingListRequest {
+            profile_id,
+            title: None,
+            key_stage: KeyStage::KS2,
+            test_date: friday,
+            words: vec![
+                word("necessary", Some("It is necessary to wear a coat.")),
+                word("separate", None),
+                word("rhythm", None),
+                word("believe", None),
+                word("rhythm", None),
+            ],
+        };
+        assert!(service.create_list(request.clone(), monday).is_err());
+
+        let list = service.create_list(CreateSpellingListRequest {
+            words: request.words[..4].to_vec(),
+            ..request
+        }, monday).unwrap();
+        assert_eq!(list.title, "Spellings for Friday 8 March");
+
+        let spelling = content_manager.get_question_by_id(list.words[0].spelling_question_id.unwrap()).unwrap();
+        assert_eq!(spelling.content.text, "Spell the missing word: It is ___ to wear a coat.");
+        let fill_blank = content_manager.get_question_by_id(list.words[2].fill_blank_question_id.unwrap()).unwrap();
+        assert_eq!(fill_blank.content.text, "Fill in the missing letters to spell the whole word: rhy_hm");
+
+        // Monday to Wednesday learn new words, Thursday reviews them all
+        let schedule = service.practice_schedule(list.id).unwrap();
+        assert_eq!(schedule.len(), 4);
+        assert_eq!(schedule[0].words, vec!["necessary", "separate"]);
+        assert_eq!(schedule[3].date, friday - Duration::days(1));
+        assert!(schedule[3].review && schedule[3].words.len() == 4);
+
+        service.submit_attempt(list.id, "necessary", "neccesary").unwrap();
+        service.submit_attempt(list.id, "necessary", "Necessary").unwrap();
+        assert!(service.submit_attempt(list.id, "necessary", " necessary ").unwrap().ready);
+        assert!(service.submit_attempt(list.id, "separate", "separate").unwrap().correct);
+        assert!(service.submit_attempt(list.id, "yacht", "yacht").is_err());
+
+        // Tuesday brings "separate" back because it isn't ready yet
+        let tuesday_words: Vec<String> = service.get_practice_questions(list.id, monday + Duration::days(1)).unwrap()
+            .into_iter()
+            .map(|question| match question.correct_answer {
+                Answer::Text(word) => word,
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(tuesday_words, vec!["separate", "separate", "rhythm", "rhythm"]);
+
+        let readiness = service.get_readiness(list.id, monday).unwrap();
+        assert_eq!(readiness.days_until_test, 4);
+        assert_eq!(readiness.ready_words, 1);
+        assert_eq!(readiness.readiness_percentage, 25);
+        assert_eq!(readiness.words[0].attempts, 3);
+
+        service.delete_list(list.id).unwrap();
+        assert!(service.get_lists(profile_id).unwrap().is_empty());
+        assert!(content_manager.get_questions_by_subject("english", None, None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_digits_are_not_a_spelling_of_number_words() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+        let profile_id = db_service.manager().execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            Arc::new(SecurityService::new().unwrap()),
+            temp_dir.path().join("content"),
+        ));
+        let service = SpellingListService::new(db_service.manager(), content_manager.clone());
+        let quiz_engine = QuizEngine::new(db_service.manager(), content_manager);
+
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let list = service.create_list(CreateSpellingListRequest {
+            profile_id,
+            title: None,
+            key_stage: KeyStage::KS1,
+            test_date: monday + Duration::days(4),
+            words: vec![SpellingWord { word: "eight".to_string(), sentence: None }],
+        }, monday).unwrap();
+
+        let spelling = list.words[0].spelling_question_id.unwrap();
+        assert!(!quiz_engine.validate_answer(spelling, Answer::Text("8".to_string())).unwrap().is_correct);
+        assert!(quiz_engine.validate_answer(spelling, Answer::Text("Eight".to_string())).unwrap().is_correct);
+
+        let fill_blank = list.words[0].fill_blank_question_id.unwrap();
+        assert!(!quiz_engine.validate_answer(fill_blank, Answer::Text("8".to_string())).unwrap().is_correct);
+    }
+}
@@ -0,0 +1,427 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+const BACKUP_PREFIX: &str = "backup_";
+/// Backup folder names are the prefix followed by when the backup was taken
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+/// Name of the database copy inside a backup folder
+const DATABASE_BACKUP_FILE: &str = "quizdd.db";
+const CRASH_FILE_PREFIX: &str = "crash-";
+const COMPRESSED_LOG_EXTENSION: &str = "gz";
+
+/// Folders whose contents count towards the app's disk usage
+#[derive(Debug, Clone)]
+pub struct StorageLocations {
+    /// Images and audio imported through the asset pipeline
+    pub asset_directory: PathBuf,
+    /// Content backups taken before updates are installed, and database backups taken
+    /// before destructive changes
+    pub backup_directory: PathBuf,
+    /// Crash reports
+    pub log_directory: PathBuf,
+}
+
+/// How much disk space the app is using, in bytes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageUsage {
+    pub database_bytes: u64,
+    pub assets_bytes: u64,
+    pub backups_bytes: u64,
+    pub logs_bytes: u64,
+    pub total_bytes: u64,
+    pub cap_bytes: Option<u64>,
+    pub over_cap: bool,
+}
+
+/// Parent-configured limits on disk usage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageSettings {
+    /// Total usage above which old files are pruned automatically, or `None` for no limit
+    pub cap_bytes: Option<u64>,
+    /// Number of most recent backups kept when cleaning up
+    pub backups_to_keep: u32,
+}
+
+/// A copy of the database kept on this machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseBackup {
+    pub name: String,
+    pub taken_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+/// What a cleanup removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupReport {
+    pub backups_removed: u32,
+    pub orphaned_assets_removed: u32,
+    pub logs_compressed: u32,
+    pub bytes_freed: u64,
+    pub usage: StorageUsage,
+}
+
+/// Reports how much disk the app uses and frees space on low-storage machines
+pub struct StorageManager {
+    db_manager: Arc<DatabaseManager>,
+    locations: StorageLocations,
+}
+
+impl StorageManager {
+    /// Create a new storage manager
+    pub fn new(db_manager: Arc<DatabaseManager>, locations: StorageLocations) -> Self {
+        Self { db_manager, locations }
+    }
+
+    /// Get the storage cap and cleanup settings
+    pub fn get_settings(&self) -> AppResult<StorageSettings> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT cap_bytes, backups_to_keep FROM storage_settings WHERE id = 1",
+                [],
+                |row| Ok(StorageSettings {
+                    cap_bytes: row.get::<_, Option<i64>>(0)?.map(|cap| cap as u64),
+                    backups_to_keep: row.get(1)?,
+                }),
+            )
+        })?)
+    }
+
+    /// Save the storage cap and cleanup settings
+    pub fn update_settings(&self, settings: &StorageSettings) -> AppResult<StorageSettings> {
+        if settings.cap_bytes == Some(0) {
+            return Err(AppError::InvalidInput("The storage cap must be more than 0 bytes".to_string()));
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "UPDATE storage_settings SET cap_bytes = ?1, backups_to_keep = ?2, updated_at = ?3 WHERE id = 1",
+                params![
+                    settings.cap_bytes.map(|cap| cap.min(i64::MAX as u64) as i64),
+                    settings.backups_to_keep,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+
+        self.get_settings()
+    }
+
+    /// Break down the disk space used by the database, assets, backups and logs
+    pub fn get_storage_usage(&self) -> AppResult<StorageUsage> {
+        let settings = self.get_settings()?;
+        let database_bytes: i64 = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                [],
+                |row| row.get(0),
+            )
+        })?;
+
+        let database_bytes = database_bytes as u64;
+        let assets_bytes = directory_size(&self.locations.asset_directory)?;
+        let backups_bytes = directory_size(&self.locations.backup_directory)?;
+        let logs_bytes = directory_size(&self.locations.log_directory)?;
+        let total_bytes = database_bytes + assets_bytes + backups_bytes + logs_bytes;
+
+        Ok(StorageUsage {
+            database_bytes,
+            assets_bytes,
+            backups_bytes,
+            logs_bytes,
+            total_bytes,
+            cap_bytes: settings.cap_bytes,
+            over_cap: settings.cap_bytes.map(|cap| total_bytes > cap).unwrap_or(false),
+        })
+    }
+
+    /// Prune backups beyond the number to keep, delete assets no question uses and
+    /// compress crash reports the parent has already reviewed
+    pub fn cleanup(&self) -> AppResult<CleanupReport> {
+        let before = self.get_storage_usage()?;
+        let settings = self.get_settings()?;
+
+        let backups = self.list_backups()?;
+        let excess = backups.len().saturating_sub(settings.backups_to_keep as usize);
+        let backups_removed = self.remove_backups(&backups[..excess])?;
+        let orphaned_assets_removed = self.purge_orphaned_assets()?;
+        let logs_compressed = self.compress_reviewed_logs()?;
+
+        let usage = self.get_storage_usage()?;
+        Ok(CleanupReport {
+            backups_removed,
+            orphaned_assets_removed,
+            logs_compressed,
+            bytes_freed: before.total_bytes.saturating_sub(usage.total_bytes),
+            usage,
+        })
+    }
+
+    /// Clean up if usage is over the cap, then remove the oldest backups until it fits.
+    /// Returns `None` when usage was already within the cap.
+    pub fn enforce_cap(&self) -> AppResult<Option<CleanupReport>> {
+        let before = self.get_storage_usage()?;
+        if !before.over_cap {
+            return Ok(None);
+        }
+
+        let mut report = self.cleanup()?;
+        for backup in self.list_backups()? {
+            if !report.usage.over_cap {
+                break;
+            }
+            report.backups_removed += self.remove_backups(&[backup])?;
+            report.usage = self.get_storage_usage()?;
+        }
+        report.bytes_freed = before.total_bytes.saturating_sub(report.usage.total_bytes);
+
+        if report.usage.over_cap {
+            log::warn!(
+                "Storage is still over the {} byte cap after pruning ({} bytes used)",
+                report.usage.cap_bytes.unwrap_or_default(),
+                report.usage.total_bytes
+            );
+        }
+        Ok(Some(report))
+    }
+
+    /// Copy the whole database into a new backup folder, returning the copy's path
+    pub fn backup_database(&self) -> AppResult<PathBuf> {
+        let folder = self.locations.backup_directory
+            .join(format!("{}{}", BACKUP_PREFIX, Utc::now().format(BACKUP_TIMESTAMP_FORMAT)));
+        fs::create_dir_all(&folder)?;
+        let path = folder.join(DATABASE_BACKUP_FILE);
+        // A backup taken earlier in the same second would make VACUUM INTO fail
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+
+        let target = path.to_string_lossy().into_owned();
+        self.db_manager.execute(|conn| conn.execute("VACUUM INTO ?1", params![target]))?;
+        log::info!("Backed up the database to {}", path.display());
+        Ok(path)
+    }
+
+    /// When the most recent backup was taken, if there is one
+    pub fn last_backup_at(&self) -> AppResult<Option<DateTime<Utc>>> {
+        Ok(self.list_backups()?.iter().rev().find_map(|backup| backup_taken_at(backup)))
+    }
+
+    /// Backups holding a copy of the database, newest first
+    pub fn get_database_backups(&self) -> AppResult<Vec<DatabaseBackup>> {
+        let mut backups = Vec::new();
+        for folder in self.list_backups()?.iter().rev() {
+            let database = folder.join(DATABASE_BACKUP_FILE);
+            let taken_at = match backup_taken_at(folder) {
+                Some(taken_at) if database.is_file() => taken_at,
+                _ => continue,
+            };
+            backups.push(DatabaseBackup { name: file_name(folder), taken_at, size_bytes: fs::metadata(&database)?.len() });
+        }
+        Ok(backups)
+    }
+
+    /// The database copy in the named backup, for restoring
+    pub fn read_database_backup(&self, name: &str) -> AppResult<(DatabaseBackup, Vec<u8>)> {
+        let backup = self.get_database_backups()?
+            .into_iter()
+            .find(|backup| backup.name == name)
+            .ok_or_else(|| AppError::NotFound(format!("No backup named '{}'", name)))?;
+        let database = fs::read(self.locations.backup_directory.join(&backup.name).join(DATABASE_BACKUP_FILE))?;
+        Ok((backup, database))
+    }
+
+    /// Backup folders, oldest first. Backup names are timestamps, so they sort by age.
+    fn list_backups(&self) -> AppResult<Vec<PathBuf>> {
+        let mut backups: Vec<PathBuf> = read_dir_if_exists(&self.locations.backup_directory)?
+            .into_iter()
+            .filter(|path| path.is_dir() && file_name(path).starts_with(BACKUP_PREFIX))
+            .collect();
+        backups.sort();
+        Ok(backups)
+    }
+
+    fn remove_backups(&self, backups: &[PathBuf]) -> AppResult<u32> {
+        for backup in backups {
+            fs::remove_dir_all(backup)?;
+            log::info!("Removed old backup: {}", file_name(backup));
+        }
+        Ok(backups.len() as u32)
+    }
+
+    fn purge_orphaned_assets(&self) -> AppResult<u32> {
+        let referenced: HashSet<String> = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT file_path FROM assets
+                 UNION
+                 SELECT json_extract(content, '$.image_url') FROM questions
+                 WHERE json_extract(content, '$.image_url') IS NOT NULL"
+            )?;
+            let paths = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            paths.collect::<Result<Vec<_>, _>>()
+        })?
+            .iter()
+            .map(|path| file_name(Path::new(path)))
+            .collect();
+
+        let mut removed = 0;
+        for path in files_under(&self.locations.asset_directory)? {
+            if !referenced.contains(&file_name(&path)) {
+                fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn compress_reviewed_logs(&self) -> AppResult<u32> {
+        let mut compressed = 0;
+        for path in read_dir_if_exists(&self.locations.log_directory)? {
+            let is_report = file_name(&path).starts_with(CRASH_FILE_PREFIX)
+                && path.extension().and_then(|extension| extension.to_str()) == Some("json");
+            if !is_report {
+                continue;
+            }
+
+            let data = fs::read(&path)?;
+            let acknowledged = serde_json::from_slice::<serde_json::Value>(&data)
+                .map(|report| report["acknowledged"].as_bool().unwrap_or(false))
+                .unwrap_or(false);
+            if !acknowledged {
+                continue;
+            }
+
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+            encoder.write_all(&data)?;
+            let mut archive = path.clone().into_os_string();
+            archive.push(format!(".{}", COMPRESSED_LOG_EXTENSION));
+            fs::write(&archive, encoder.finish()?)?;
+            fs::remove_file(&path)?;
+            compressed += 1;
+        }
+        Ok(compressed)
+    }
+}
+
+/// When the backup in `folder` was taken, from its name
+fn backup_taken_at(folder: &Path) -> Option<DateTime<Utc>> {
+    let name = file_name(folder);
+    NaiveDateTime::parse_from_str(name.strip_prefix(BACKUP_PREFIX)?, BACKUP_TIMESTAMP_FORMAT).ok()
+        .map(|taken_at| DateTime::from_naive_utc_and_offset(taken_at, Utc))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// Entries of a directory, or none if it hasn't been created yet
+fn read_dir_if_exists(directory: &Path) -> AppResult<Vec<PathBuf>> {
+    match fs::read_dir(directory) {
+        Ok(entries) => Ok(entries.map(|entry| entry.map(|entry| entry.path())).collect::<Result<_, _>>()?),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Every file in a directory and its subdirectories
+fn files_under(directory: &Path) -> AppResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in read_dir_if_exists(directory)? {
+        if path.is_dir() {
+            files.extend(files_under(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+fn directory_size(directory: &Path) -> AppResult<u64> {
+    let mut size = 0;
+    for path in files_under(directory)? {
+        size += fs::metadata(&path)?.len();
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cleanup_prunes_backups_orphans_and_reviewed_logs() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+
+        let locations = StorageLocations {
+            asset_directory: temp_dir.path().join("assets"),
+            backup_directory: temp_dir.path().join("backups"),
+            log_directory: temp_dir.path().join("crashes"),
+        };
+        let storage = StorageManager::new(db_service.manager(), locations.clone());
+
+        for name in ["backup_20240101_000000", "backup_20240201_000000", "backup_20240301_000000"] {
+            fs::create_dir_all(locations.backup_directory.join(name)).unwrap();
+            fs::write(locations.backup_directory.join(name).join("content.json"), vec![b'x'; 1000]).unwrap();
+        }
+        let images = locations.asset_directory.join("images");
+        fs::create_dir_all(&images).unwrap();
+        fs::write(images.join("used.webp"), vec![0; 500]).unwrap();
+        fs::write(images.join("unused.webp"), vec![0; 500]).unwrap();
+        fs::create_dir_all(&locations.log_directory).unwrap();
+        fs::write(locations.log_directory.join("crash-old.json"), r#"{"acknowledged": true}"#).unwrap();
+        fs::write(locations.log_directory.join("crash-new.json"), r#"{"acknowledged": false}"#).unwrap();
+
+        db_service.manager().execute(|conn| {
+            conn.execute(
+                "INSERT INTO questions (subject_id, key_stage, question_type, content, correct_answer)
+                 VALUES (1, 'KS1', 'multiple_choice', '{\"text\": \"Which picture?\"}', '\"a\"')",
+                [],
+            )?;
+            conn.execute(
+                "INSERT INTO assets (question_id, asset_type, file_path) VALUES (?1, 'image', ?2)",
+                params![conn.last_insert_rowid(), images.join("used.webp").to_string_lossy()],
+            )
+        }).unwrap();
+
+        let usage = storage.get_storage_usage().unwrap();
+        assert_eq!(usage.assets_bytes, 1000);
+        assert_eq!(usage.backups_bytes, 3000);
+        assert!(usage.database_bytes > 0);
+        assert!(!usage.over_cap);
+        assert!(storage.enforce_cap().unwrap().is_none());
+        assert_eq!(storage.last_backup_at().unwrap().unwrap().to_rfc3339(), "2024-03-01T00:00:00+00:00");
+
+        storage.update_settings(&StorageSettings { cap_bytes: None, backups_to_keep: 2 }).unwrap();
+        let report = storage.cleanup().unwrap();
+        assert_eq!(report.backups_removed, 1);
+        assert_eq!(report.orphaned_assets_removed, 1);
+        assert_eq!(report.logs_compressed, 1);
+        assert!(!locations.backup_directory.join("backup_20240101_000000").exists());
+        assert!(images.join("used.webp").exists());
+        assert!(!images.join("unused.webp").exists());
+        assert!(locations.log_directory.join("crash-old.json.gz").exists());
+        assert!(locations.log_directory.join("crash-new.json").exists());
+
+        // A cap the backups don't fit under removes them oldest first
+        let cap = report.usage.total_bytes - 500;
+        storage.update_settings(&StorageSettings { cap_bytes: Some(cap), backups_to_keep: 2 }).unwrap();
+        let report = storage.enforce_cap().unwrap().unwrap();
+        assert_eq!(report.backups_removed, 1);
+        assert!(!report.usage.over_cap);
+        assert!(locations.backup_directory.join("backup_20240301_000000").exists());
+    }
+}
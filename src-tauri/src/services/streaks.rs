@@ -0,0 +1,267 @@
+use crate::errors::{AppError, AppResult};
+use crate::database::DatabaseManager;
+use crate::models::{DailyStreak, ProfileId};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Most freeze tokens a profile can have saved up
+pub const MAX_STREAK_FREEZES: u32 = 3;
+
+/// Tracks days in a row with a completed quiz. Completions are kept as instants and only
+/// turned into days in the time zone asked about, so a quiz at 11pm counts for that evening
+/// wherever the family is.
+pub struct StreakTracker {
+    db_manager: Arc<DatabaseManager>,
+}
+
+/// Where the current run of days stands
+struct CurrentRun {
+    days: u32,
+    /// First day with a quiz in the run
+    started_on: Option<NaiveDate>,
+    /// Missed days the run needs a new freeze for
+    needs_freezes: Vec<NaiveDate>,
+}
+
+impl StreakTracker {
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// Note a completed quiz. A run it carries on over missed days uses up freezes for them.
+    pub fn record_quiz_completed<Tz: TimeZone>(&self, profile_id: ProfileId, now: &DateTime<Tz>) -> AppResult<DailyStreak> {
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO quiz_completions (profile_id, completed_at) VALUES (?1, ?2)",
+                params![profile_id, now.with_timezone(&Utc).to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        let today = now.date_naive();
+        let (completions, frozen_days, freezes_available) = self.load(profile_id, &now.timezone())?;
+        let run = current_run(&completions, &frozen_days, freezes_available, today);
+        if !run.needs_freezes.is_empty() {
+            self.db_manager.transaction(|tx| {
+                for day in &run.needs_freezes {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO streak_freeze_days (profile_id, frozen_day) VALUES (?1, ?2)",
+                        params![profile_id, day.format(DATE_FORMAT).to_string()],
+                    )?;
+                }
+                tx.execute(
+                    "UPDATE streak_freezes SET available = available - ?2 WHERE profile_id = ?1",
+                    params![profile_id, run.needs_freezes.len() as u32],
+                )?;
+                Ok(())
+            })?;
+        }
+
+        self.get_streak(profile_id, now)
+    }
+
+    /// The profile's streak as of `now`, with days ending at midnight in `now`'s time zone
+    pub fn get_streak<Tz: TimeZone>(&self, profile_id: ProfileId, now: &DateTime<Tz>) -> AppResult<DailyStreak> {
+        let today = now.date_naive();
+        let (completions, frozen_days, freezes_available) = self.load(profile_id, &now.timezone())?;
+        let run = current_run(&completions, &frozen_days, freezes_available, today);
+
+        let run_frozen_days: Vec<NaiveDate> = match run.started_on {
+            Some(started_on) => frozen_days.range(started_on..=today)
+                .copied()
+                .chain(run.needs_freezes.iter().copied())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Ok(DailyStreak {
+            profile_id,
+            current_days: run.days,
+            best_days: best_run(&completions, &frozen_days).max(run.days),
+            completed_today: completions.contains_key(&today),
+            freezes_available,
+            frozen_days: run_frozen_days,
+            started_at: run.started_on.and_then(|day| completions.get(&day)).map(|(first, _)| *first),
+            last_completed_at: completions.values().next_back().map(|(_, last)| *last),
+        })
+    }
+
+    /// Give a profile freeze tokens, up to `MAX_STREAK_FREEZES` saved at once
+    pub fn grant_freezes(&self, profile_id: ProfileId, count: u32) -> AppResult<u32> {
+        if count == 0 {
+            return Err(AppError::InvalidInput("Grant at least one streak freeze".to_string()));
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO streak_freezes (profile_id, available) VALUES (?1, MIN(?2, ?3))
+                 ON CONFLICT(profile_id) DO UPDATE SET available = MIN(available + ?2, ?3)",
+                params![profile_id, count, MAX_STREAK_FREEZES],
+            )?;
+            Ok(())
+        })?;
+
+        self.freezes_available(profile_id)
+    }
+
+    fn freezes_available(&self, profile_id: ProfileId) -> AppResult<u32> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT available FROM streak_freezes WHERE profile_id = ?1",
+                params![profile_id],
+                |row| row.get(0),
+            ).optional()
+        })?.unwrap_or(0))
+    }
+
+    /// First and last completion on each day in `tz`, frozen days, and freezes left
+    #[allow(clippy::type_complexity)]
+    fn load<Tz: TimeZone>(
+        &self,
+        profile_id: ProfileId,
+        tz: &Tz,
+    ) -> AppResult<(BTreeMap<NaiveDate, (DateTime<Utc>, DateTime<Utc>)>, BTreeSet<NaiveDate>, u32)> {
+        let (completed_at, frozen_days): (Vec<String>, Vec<String>) = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT completed_at FROM quiz_completions WHERE profile_id = ?1 ORDER BY completed_at"
+            )?;
+            let completed_at = stmt.query_map(params![profile_id], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut stmt = conn.prepare("SELECT frozen_day FROM streak_freeze_days WHERE profile_id = ?1")?;
+            let frozen_days = stmt.query_map(params![profile_id], |row| row.get(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((completed_at, frozen_days))
+        })?;
+
+        let mut completions = BTreeMap::new();
+        for completed_at in completed_at {
+            let instant = DateTime::parse_from_rfc3339(&completed_at)
+                .map_err(|e| AppError::Internal(format!("Bad quiz completion timestamp: {}", e)))?
+                .with_timezone(&Utc);
+            let day = instant.with_timezone(tz).date_naive();
+            let (first, last) = completions.entry(day).or_insert((instant, instant));
+            *first = (*first).min(instant);
+            *last = (*last).max(instant);
+        }
+
+        let frozen_days = frozen_days.iter()
+            .filter_map(|day| NaiveDate::parse_from_str(day, DATE_FORMAT).ok())
+            .collect();
+
+        Ok((completions, frozen_days, self.freezes_available(profile_id)?))
+    }
+}
+
+/// Walk back from today over days with a quiz. Today doesn't break the run until it's over,
+/// and a gap is bridged by freezes already used on it or, if there are enough, new ones.
+fn current_run<V>(
+    completions: &BTreeMap<NaiveDate, V>,
+    frozen_days: &BTreeSet<NaiveDate>,
+    freezes_available: u32,
+    today: NaiveDate,
+) -> CurrentRun {
+    let mut run = CurrentRun { days: 0, started_on: None, needs_freezes: Vec::new() };
+    let earliest = match completions.keys().next() {
+        Some(&earliest) => earliest,
+        None => return run,
+    };
+
+    let mut gap = Vec::new();
+    let mut day = if completions.contains_key(&today) { today } else { today - Duration::days(1) };
+    while day >= earliest {
+        if completions.contains_key(&day) {
+            run.days += 1;
+            run.started_on = Some(day);
+            run.needs_freezes.append(&mut gap);
+        } else if !frozen_days.contains(&day) {
+            if run.needs_freezes.len() + gap.len() >= freezes_available as usize {
+                break;
+            }
+            gap.push(day);
+        }
+        day -= Duration::days(1);
+    }
+
+    run.needs_freezes.sort();
+    run
+}
+
+/// Most days with a quiz in any run, counting only freezes already used
+fn best_run<V>(completions: &BTreeMap<NaiveDate, V>, frozen_days: &BTreeSet<NaiveDate>) -> u32 {
+    let covered: BTreeSet<NaiveDate> = completions.keys().chain(frozen_days.iter()).copied().collect();
+    let mut best = 0;
+    let mut days = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for day in covered {
+        if previous.map_or(true, |previous| day - previous != Duration::days(1)) {
+            days = 0;
+        }
+        if completions.contains_key(&day) {
+            days += 1;
+            best = best.max(days);
+        }
+        previous = Some(day);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use chrono::FixedOffset;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_streak_counts_local_days_and_uses_freezes_for_gaps() {
+        let temp_dir = tempdir().unwrap();
+        let db_service = DatabaseService::new(temp_dir.path().join("test.db")).unwrap();
+        db_service.initialize().unwrap();
+        let db_manager = db_service.manager();
+        let profile_id = db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+        let tracker = StreakTracker::new(db_manager);
+
+        // Late evenings in New York, which are already the next day in UTC
+        let new_york = FixedOffset::west_opt(5 * 3600).unwrap();
+        let evening = |day: u32| new_york.with_ymd_and_hms(2026, 3, day, 22, 30, 0).unwrap();
+        tracker.record_quiz_completed(profile_id, &evening(1)).unwrap();
+        tracker.record_quiz_completed(profile_id, &evening(2)).unwrap();
+        let streak = tracker.record_quiz_completed(profile_id, &evening(3)).unwrap();
+        assert_eq!((streak.current_days, streak.best_days), (3, 3));
+        assert!(streak.completed_today);
+
+        // Still alive the next morning, gone once a whole day is missed
+        let morning = new_york.with_ymd_and_hms(2026, 3, 4, 8, 0, 0).unwrap();
+        let streak = tracker.get_streak(profile_id, &morning).unwrap();
+        assert_eq!(streak.current_days, 3);
+        assert!(!streak.completed_today);
+        assert_eq!(tracker.get_streak(profile_id, &evening(5)).unwrap().current_days, 0);
+
+        // A freeze covers the missed day, and is only used when the streak carries on
+        assert_eq!(tracker.grant_freezes(profile_id, 5).unwrap(), MAX_STREAK_FREEZES);
+        let streak = tracker.get_streak(profile_id, &evening(5)).unwrap();
+        assert_eq!((streak.current_days, streak.freezes_available), (3, 3));
+        let streak = tracker.record_quiz_completed(profile_id, &evening(5)).unwrap();
+        assert_eq!((streak.current_days, streak.freezes_available), (4, 2));
+        let missed = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        assert_eq!(streak.frozen_days, vec![missed]);
+
+        // Three missed days is more than the two freezes left
+        let streak = tracker.record_quiz_completed(profile_id, &evening(9)).unwrap();
+        assert_eq!((streak.current_days, streak.best_days, streak.freezes_available), (1, 4, 2));
+    }
+}
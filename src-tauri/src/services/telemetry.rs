@@ -0,0 +1,369 @@
+use crate::errors::{AppError, AppResult};
+use crate::config::apply_proxy;
+use crate::database::DatabaseManager;
+use std::sync::Arc;
+use std::collections::BTreeMap;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use url::Url;
+
+/// Counter categories stored in `telemetry_counters`
+const CATEGORY_FEATURE: &str = "feature";
+const CATEGORY_CONTENT_PACK: &str = "content_pack";
+const CATEGORY_SESSION: &str = "session";
+
+const SESSIONS_STARTED: &str = "started";
+const SESSIONS_CRASHED: &str = "crashed";
+
+/// Parent-visible telemetry settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetrySettings {
+    pub opted_in: bool,
+    pub endpoint: Option<String>,
+    pub last_submitted_at: Option<DateTime<Utc>>,
+}
+
+/// Session stability metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMetrics {
+    pub total_sessions: u64,
+    pub crashed_sessions: u64,
+    pub crash_free_sessions: u64,
+}
+
+/// Exact payload sent to the telemetry endpoint. Contains no profile data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryPayload {
+    pub installation_id: String,
+    pub app_version: String,
+    pub period_start: Option<DateTime<Utc>>,
+    pub period_end: DateTime<Utc>,
+    pub feature_usage: BTreeMap<String, u64>,
+    pub content_pack_usage: BTreeMap<String, u64>,
+    pub sessions: SessionMetrics,
+}
+
+/// Opt-in telemetry service that batches anonymized usage counters
+pub struct TelemetryService {
+    db_manager: Arc<DatabaseManager>,
+    client: reqwest::Client,
+}
+
+impl TelemetryService {
+    /// Create a new telemetry service, sending submissions through `proxy` if set
+    pub fn new(db_manager: Arc<DatabaseManager>, proxy: Option<&str>) -> AppResult<Self> {
+        let builder = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .user_agent("EducationalQuizApp/1.0");
+        let client = apply_proxy(builder, proxy)?
+            .build()
+            .map_err(|e| AppError::Telemetry(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self { db_manager, client })
+    }
+
+    /// Get the current telemetry settings
+    pub fn get_settings(&self) -> AppResult<TelemetrySettings> {
+        Ok(self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT opted_in, endpoint, last_submitted_at FROM telemetry_settings WHERE id = 1",
+                [],
+                |row| {
+                    let last_submitted_at = match row.get::<_, Option<String>>(2)? {
+                        Some(value) => Some(DateTime::parse_from_rfc3339(&value)
+                            .map_err(|_| rusqlite::Error::InvalidColumnType(2, "last_submitted_at".to_string(), rusqlite::types::Type::Text))?
+                            .with_timezone(&Utc)),
+                        None => None,
+                    };
+
+                    Ok(TelemetrySettings {
+                        opted_in: row.get(0)?,
+                        endpoint: row.get(1)?,
+                        last_submitted_at,
+                    })
+                },
+            )
+        })?)
+    }
+
+    /// Opt in to or out of telemetry. Opting out discards all pending metrics and
+    /// rotates the installation ID so a later opt-in cannot be linked to old data.
+    pub fn set_opt_in(&self, opted_in: bool) -> AppResult<TelemetrySettings> {
+        self.db_manager.transaction(|tx| {
+            if opted_in {
+                tx.execute(
+                    "UPDATE telemetry_settings SET opted_in = 1,
+                     period_started_at = COALESCE(period_started_at, ?1) WHERE id = 1",
+                    params![Utc::now().to_rfc3339()],
+                )?;
+            } else {
+                tx.execute("DELETE FROM telemetry_counters", [])?;
+                tx.execute(
+                    "UPDATE telemetry_settings SET opted_in = 0, session_in_progress = 0,
+                     period_started_at = NULL, installation_id = lower(hex(randomblob(16))) WHERE id = 1",
+                    [],
+                )?;
+            }
+            Ok(())
+        })?;
+
+        self.get_settings()
+    }
+
+    /// Configure the endpoint metrics are submitted to
+    pub fn set_endpoint(&self, endpoint: Option<String>) -> AppResult<TelemetrySettings> {
+        if let Some(ref value) = endpoint {
+            let parsed = Url::parse(value)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid telemetry endpoint: {}", e)))?;
+            if parsed.scheme() != "https" {
+                return Err(AppError::InvalidInput("Telemetry endpoint must use HTTPS".to_string()));
+            }
+        }
+
+        self.db_manager.execute(|conn| {
+            conn.execute("UPDATE telemetry_settings SET endpoint = ?1 WHERE id = 1", params![endpoint])?;
+            Ok(())
+        })?;
+
+        self.get_settings()
+    }
+
+    /// Count a use of an app feature (ignored unless opted in)
+    pub fn record_feature_usage(&self, feature: &str) {
+        self.increment(CATEGORY_FEATURE, feature);
+    }
+
+    /// Count a quiz played from a content pack or built-in subject (ignored unless opted in)
+    pub fn record_content_pack_usage(&self, pack: &str) {
+        self.increment(CATEGORY_CONTENT_PACK, pack);
+    }
+
+    /// Record an application launch. If the previous session never reported a clean
+    /// exit it is counted as crashed.
+    pub fn record_session_start(&self) {
+        if !self.is_opted_in() {
+            return;
+        }
+
+        let previous_in_progress = self.db_manager.execute(|conn| {
+            let in_progress: bool = conn.query_row(
+                "SELECT session_in_progress FROM telemetry_settings WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )?;
+            conn.execute("UPDATE telemetry_settings SET session_in_progress = 1 WHERE id = 1", [])?;
+            Ok(in_progress)
+        });
+
+        match previous_in_progress {
+            Ok(true) => self.increment(CATEGORY_SESSION, SESSIONS_CRASHED),
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to record telemetry session start: {}", e),
+        }
+        self.increment(CATEGORY_SESSION, SESSIONS_STARTED);
+    }
+
+    /// Record a clean application exit
+    pub fn record_session_end(&self) {
+        if let Err(e) = self.db_manager.execute(|conn| {
+            conn.execute("UPDATE telemetry_settings SET session_in_progress = 0 WHERE id = 1", [])?;
+            Ok(())
+        }) {
+            log::warn!("Failed to record telemetry session end: {}", e);
+        }
+    }
+
+    /// Build the payload that would be submitted next, for local inspection
+    pub fn build_payload(&self) -> AppResult<TelemetryPayload> {
+        let (installation_id, period_start) = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT installation_id, period_started_at FROM telemetry_settings WHERE id = 1",
+                [],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)),
+            )
+        })?;
+
+        let counters = self.db_manager.execute(|conn| {
+            let mut stmt = conn.prepare("SELECT category, name, count FROM telemetry_counters")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+            })?;
+
+            let mut counters = Vec::new();
+            for row in rows {
+                counters.push(row?);
+            }
+            Ok(counters)
+        })?;
+
+        let mut feature_usage = BTreeMap::new();
+        let mut content_pack_usage = BTreeMap::new();
+        let mut total_sessions = 0;
+        let mut crashed_sessions = 0;
+
+        for (category, name, count) in counters {
+            let count = count.max(0) as u64;
+            match (category.as_str(), name.as_str()) {
+                (CATEGORY_FEATURE, _) => { feature_usage.insert(name, count); },
+                (CATEGORY_CONTENT_PACK, _) => { content_pack_usage.insert(name, count); },
+                (CATEGORY_SESSION, SESSIONS_STARTED) => total_sessions = count,
+                (CATEGORY_SESSION, SESSIONS_CRASHED) => crashed_sessions = count,
+                _ => {}
+            }
+        }
+
+        let period_start = period_start
+            .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+            .map(|value| value.with_timezone(&Utc));
+
+        Ok(TelemetryPayload {
+            installation_id,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            period_start,
+            period_end: Utc::now(),
+            feature_usage,
+            content_pack_usage,
+            sessions: SessionMetrics {
+                total_sessions,
+                crashed_sessions,
+                crash_free_sessions: total_sessions.saturating_sub(crashed_sessions),
+            },
+        })
+    }
+
+    /// Submit the pending batch to the configured endpoint and start a new period
+    pub async fn submit(&self) -> AppResult<TelemetryPayload> {
+        let settings = self.get_settings()?;
+        if !settings.opted_in {
+            return Err(AppError::PermissionDenied("Telemetry is not enabled".to_string()));
+        }
+
+        let endpoint = settings.endpoint
+            .ok_or_else(|| AppError::Telemetry("No telemetry endpoint configured".to_string()))?;
+
+        let payload = self.build_payload()?;
+
+        let response = self.client
+            .post(&endpoint)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| AppError::Telemetry(format!("Failed to send telemetry: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Telemetry(format!(
+                "Telemetry endpoint responded with status: {}",
+                response.status()
+            )));
+        }
+
+        self.db_manager.transaction(|tx| {
+            tx.execute("DELETE FROM telemetry_counters", [])?;
+            tx.execute(
+                "UPDATE telemetry_settings SET period_started_at = ?1, last_submitted_at = ?1 WHERE id = 1",
+                params![payload.period_end.to_rfc3339()],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(payload)
+    }
+
+    fn is_opted_in(&self) -> bool {
+        self.get_settings().map(|s| s.opted_in).unwrap_or(false)
+    }
+
+    fn increment(&self, category: &str, name: &str) {
+        if !self.is_opted_in() {
+            return;
+        }
+
+        if let Err(e) = self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT INTO telemetry_counters (category, name, count) VALUES (?1, ?2, 1)
+                 ON CONFLICT(category, name) DO UPDATE SET count = count + 1",
+                params![category, name],
+            )?;
+            Ok(())
+        }) {
+            log::warn!("Failed to record telemetry counter {}/{}: {}", category, name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    fn create_test_telemetry_service() -> (TelemetryService, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let service = TelemetryService::new(db_service.manager(), None).unwrap();
+
+        (service, temp_dir)
+    }
+
+    #[test]
+    fn test_nothing_recorded_without_opt_in() {
+        let (service, _temp_dir) = create_test_telemetry_service();
+
+        assert!(!service.get_settings().unwrap().opted_in);
+        service.record_feature_usage("quiz_session");
+        service.record_session_start();
+
+        let payload = service.build_payload().unwrap();
+        assert!(payload.feature_usage.is_empty());
+        assert_eq!(payload.sessions.total_sessions, 0);
+    }
+
+    #[test]
+    fn test_payload_counts_and_crash_detection() {
+        let (service, _temp_dir) = create_test_telemetry_service();
+        service.set_opt_in(true).unwrap();
+
+        service.record_session_start();
+        service.record_feature_usage("quiz_session");
+        service.record_feature_usage("quiz_session");
+        service.record_content_pack_usage("mathematics");
+        // No clean exit recorded before the next launch
+        service.record_session_start();
+        service.record_session_end();
+
+        let payload = service.build_payload().unwrap();
+        assert_eq!(payload.feature_usage.get("quiz_session"), Some(&2));
+        assert_eq!(payload.content_pack_usage.get("mathematics"), Some(&1));
+        assert_eq!(payload.sessions.total_sessions, 2);
+        assert_eq!(payload.sessions.crashed_sessions, 1);
+        assert_eq!(payload.sessions.crash_free_sessions, 1);
+    }
+
+    #[test]
+    fn test_opt_out_discards_data_and_rotates_id() {
+        let (service, _temp_dir) = create_test_telemetry_service();
+        service.set_opt_in(true).unwrap();
+        service.record_feature_usage("custom_mix");
+        let original_id = service.build_payload().unwrap().installation_id;
+
+        service.set_opt_in(false).unwrap();
+
+        let payload = service.build_payload().unwrap();
+        assert!(payload.feature_usage.is_empty());
+        assert_ne!(payload.installation_id, original_id);
+    }
+
+    #[test]
+    fn test_endpoint_must_use_https() {
+        let (service, _temp_dir) = create_test_telemetry_service();
+
+        assert!(service.set_endpoint(Some("http://metrics.example.com".to_string())).is_err());
+        let settings = service.set_endpoint(Some("https://metrics.example.com/v1".to_string())).unwrap();
+        assert_eq!(settings.endpoint.as_deref(), Some("https://metrics.example.com/v1"));
+    }
+}
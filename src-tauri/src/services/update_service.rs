@@ -1,8 +1,10 @@
 use crate::errors::AppError;
+use crate::config::apply_proxy;
 use crate::services::security::SecurityService;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs as async_fs;
 use url::Url;
 
@@ -40,10 +42,12 @@ pub struct UpdateConfig {
     pub auto_check: bool,
     pub check_interval_hours: u32,
     pub backup_retention_days: u32,
+    #[serde(default)]
+    pub proxy: Option<String>,
 }
 
 pub struct UpdateService {
-    security_service: SecurityService,
+    security_service: Arc<SecurityService>,
     config: UpdateConfig,
     client: reqwest::Client,
     backup_dir: PathBuf,
@@ -56,13 +60,14 @@ unsafe impl Sync for UpdateService {}
 
 impl UpdateService {
     pub fn new(
-        security_service: SecurityService,
+        security_service: Arc<SecurityService>,
         config: UpdateConfig,
         app_data_dir: PathBuf,
     ) -> Result<Self, AppError> {
-        let client = reqwest::Client::builder()
+        let builder = reqwest::Client::builder()
             .timeout(std::time::Duration::from_secs(30))
-            .user_agent("EducationalQuizApp/1.0")
+            .user_agent("EducationalQuizApp/1.0");
+        let client = apply_proxy(builder, config.proxy.as_deref())?
             .build()
             .map_err(|e| AppError::UpdateFailed(format!("Failed to create HTTP client: {}", e)))?;
 
@@ -84,6 +89,11 @@ impl UpdateService {
         })
     }
 
+    /// The repositories and schedule updates are checked against
+    pub fn config(&self) -> &UpdateConfig {
+        &self.config
+    }
+
     /// Check for available updates from authorized repositories
     pub async fn check_for_updates(&self) -> Result<Vec<UpdateInfo>, AppError> {
         let mut all_updates = Vec::new();
@@ -144,14 +154,14 @@ impl UpdateService {
             ));
         }
 
-        // Check against authorized domains (this would be configurable in production)
-        let authorized_domains = vec![
-            "updates.educationalquizapp.com",
-            "content.educationalquizapp.com",
-        ];
+        // Only hosts of the configured repositories are authorized
+        let authorized_domains: Vec<String> = self.config.repository_urls.iter()
+            .filter_map(|repo_url| Url::parse(repo_url).ok())
+            .filter_map(|repo_url| repo_url.host_str().map(str::to_string))
+            .collect();
 
         if let Some(host) = parsed_url.host_str() {
-            if !authorized_domains.contains(&host) {
+            if !authorized_domains.iter().any(|domain| domain == host) {
                 return Err(AppError::UpdateFailed(format!(
                     "Unauthorized repository domain: {}",
                     host
@@ -473,13 +483,14 @@ mod tests {
             auto_check: false,
             check_interval_hours: 24,
             backup_retention_days: 7,
+            proxy: None,
         }
     }
 
     #[test]
     fn test_repository_url_validation() {
         let temp_dir = TempDir::new().unwrap();
-        let security_service = SecurityService::new().unwrap();
+        let security_service = Arc::new(SecurityService::new().unwrap());
         let config = create_test_config();
         
         let update_service = UpdateService::new(
@@ -504,7 +515,7 @@ mod tests {
     #[tokio::test]
     async fn test_backup_creation_and_rollback() {
         let temp_dir = TempDir::new().unwrap();
-        let security_service = SecurityService::new().unwrap();
+        let security_service = Arc::new(SecurityService::new().unwrap());
         let config = create_test_config();
         
         let update_service = UpdateService::new(
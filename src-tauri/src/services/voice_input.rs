@@ -0,0 +1,419 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::{Answer, Question, QuestionType, QuestionId};
+use crate::services::ContentManager;
+use std::sync::{Arc, RwLock};
+use serde::{Deserialize, Serialize};
+
+/// Filler words children say before an answer, dropped before matching
+const FILLER_WORDS: &[&str] = &["um", "umm", "uh", "er", "erm", "hmm"];
+
+/// One candidate transcription of a spoken answer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Transcription {
+    pub text: String,
+    /// Provider confidence from 0.0 to 1.0
+    pub confidence: f32,
+}
+
+/// Recorded audio handed to a speech-to-text provider
+#[derive(Debug, Clone)]
+pub struct SpokenAudio {
+    /// MIME type of `data`, e.g. `audio/wav`
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Pluggable speech-to-text engine. Implementations return their candidate
+/// transcriptions, best first; an empty list means nothing was heard.
+pub trait SpeechToTextProvider: Send + Sync {
+    fn name(&self) -> &str;
+    fn transcribe(&self, audio: &SpokenAudio) -> AppResult<Vec<Transcription>>;
+}
+
+/// Confidence levels deciding what happens to a spoken answer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct VoiceThresholds {
+    /// At or above this, the answer is submitted straight away
+    pub accept: f32,
+    /// At or above this (but below `accept`), the child is asked to confirm
+    pub confirm: f32,
+}
+
+impl Default for VoiceThresholds {
+    fn default() -> Self {
+        Self {
+            accept: 0.85,
+            confirm: 0.5,
+        }
+    }
+}
+
+impl VoiceThresholds {
+    fn validate(&self) -> AppResult<()> {
+        let in_range = |value: f32| (0.0..=1.0).contains(&value);
+        if !in_range(self.accept) || !in_range(self.confirm) {
+            return Err(AppError::InvalidInput("Voice thresholds must be between 0 and 1".to_string()));
+        }
+        if self.confirm > self.accept {
+            return Err(AppError::InvalidInput(
+                "The confirm threshold cannot be above the accept threshold".to_string()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What to do with a spoken answer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VoiceDecision {
+    /// Confident match: submit it
+    Accept,
+    /// Probable match: ask "Did you say ...?" before submitting
+    Confirm,
+    /// Not understood or no matching answer: ask the child to say it again
+    Retry,
+}
+
+/// A spoken answer matched against a question
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceInterpretation {
+    pub question_id: QuestionId,
+    /// What the provider heard
+    pub transcript: String,
+    pub confidence: f32,
+    /// The answer to submit, if the transcript matched one
+    pub answer: Option<Answer>,
+    pub decision: VoiceDecision,
+}
+
+impl VoiceInterpretation {
+    /// The answer to pass to `validate_answer`, once accepted or confirmed by the child
+    pub fn answer_to_submit(&self, confirmed: bool) -> Option<&Answer> {
+        match self.decision {
+            VoiceDecision::Accept => self.answer.as_ref(),
+            VoiceDecision::Confirm if confirmed => self.answer.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// Turns spoken answers into `Answer`s for hands-free mode
+pub struct VoiceInputService {
+    content_manager: Arc<ContentManager>,
+    provider: RwLock<Option<Arc<dyn SpeechToTextProvider>>>,
+    thresholds: RwLock<VoiceThresholds>,
+}
+
+impl VoiceInputService {
+    /// Create a voice input service with no speech-to-text provider installed
+    pub fn new(content_manager: Arc<ContentManager>) -> Self {
+        Self {
+            content_manager,
+            provider: RwLock::new(None),
+            thresholds: RwLock::new(VoiceThresholds::default()),
+        }
+    }
+
+    /// Install (or with `None`, remove) the speech-to-text provider
+    pub fn set_provider(&self, provider: Option<Arc<dyn SpeechToTextProvider>>) -> AppResult<()> {
+        *self.provider.write()
+            .map_err(|_| AppError::Internal("Voice provider lock poisoned".to_string()))? = provider;
+        Ok(())
+    }
+
+    /// Name of the installed provider, if any
+    pub fn provider_name(&self) -> Option<String> {
+        self.provider.read().ok()?.as_ref().map(|provider| provider.name().to_string())
+    }
+
+    pub fn get_thresholds(&self) -> VoiceThresholds {
+        self.thresholds.read().map(|thresholds| *thresholds).unwrap_or_default()
+    }
+
+    pub fn set_thresholds(&self, thresholds: VoiceThresholds) -> AppResult<()> {
+        thresholds.validate()?;
+        *self.thresholds.write()
+            .map_err(|_| AppError::Internal("Voice thresholds lock poisoned".to_string()))? = thresholds;
+        Ok(())
+    }
+
+    /// Transcribe recorded audio with the installed provider and match it against a question
+    pub fn transcribe_answer(&self, question_id: QuestionId, audio: &SpokenAudio) -> AppResult<VoiceInterpretation> {
+        let provider = self.provider.read()
+            .map_err(|_| AppError::Internal("Voice provider lock poisoned".to_string()))?
+            .clone()
+            .ok_or_else(|| AppError::InvalidInput("No speech-to-text provider is installed".to_string()))?;
+
+        let transcriptions = provider.transcribe(audio)?;
+        self.interpret(question_id, &transcriptions)
+    }
+
+    /// Match transcriptions (from the installed provider or the frontend's own recogniser)
+    /// against a question. The first candidate that matches an answer wins.
+    pub fn interpret(&self, question_id: QuestionId, transcriptions: &[Transcription]) -> AppResult<VoiceInterpretation> {
+        let question = self.content_manager.get_question_by_id(question_id)?;
+        let thresholds = self.get_thresholds();
+
+        let matched = transcriptions.iter()
+            .find_map(|transcription| {
+                spoken_answer(&question, &transcription.text).transpose()
+                    .map(|answer| answer.map(|answer| (transcription, answer)))
+            })
+            .transpose()?;
+
+        let interpretation = match matched {
+            Some((transcription, answer)) => {
+                let confidence = transcription.confidence.clamp(0.0, 1.0);
+                let decision = if confidence >= thresholds.accept {
+                    VoiceDecision::Accept
+                } else if confidence >= thresholds.confirm {
+                    VoiceDecision::Confirm
+                } else {
+                    VoiceDecision::Retry
+                };
+                VoiceInterpretation {
+                    question_id,
+                    transcript: transcription.text.clone(),
+                    confidence,
+                    answer: Some(answer),
+                    decision,
+                }
+            }
+            None => {
+                let best = transcriptions.first();
+                VoiceInterpretation {
+                    question_id,
+                    transcript: best.map(|t| t.text.clone()).unwrap_or_default(),
+                    confidence: best.map_or(0.0, |t| t.confidence.clamp(0.0, 1.0)),
+                    answer: None,
+                    decision: VoiceDecision::Retry,
+                }
+            }
+        };
+
+        Ok(interpretation)
+    }
+}
+
+/// The answer a transcript gives for `question`, or `None` when it doesn't match one.
+/// Errors for question types that can't be answered by voice.
+fn spoken_answer(question: &Question, transcript: &str) -> AppResult<Option<Answer>> {
+    let spoken = normalize_spoken(transcript);
+    if spoken.is_empty() {
+        return Ok(None);
+    }
+
+    match (&question.question_type, &question.correct_answer) {
+        (QuestionType::MultipleChoice, _) => {
+            let options = question.content.options.as_deref().unwrap_or_default();
+            let exact = options.iter().find(|option| normalize_spoken(option) == spoken);
+            // "I think it's Paris" still picks "Paris", as long as only one option was said
+            let mentioned: Vec<&String> = options.iter()
+                .filter(|option| contains_phrase(&spoken, &normalize_spoken(option)))
+                .collect();
+            let option = exact.or(if mentioned.len() == 1 { Some(mentioned[0]) } else { None });
+            Ok(option.map(|option| Answer::Text(option.clone())))
+        }
+        (QuestionType::FillBlank | QuestionType::StoryQuiz | QuestionType::ShortAnswer, Answer::Text(_)) => Ok(Some(Answer::Text(spoken))),
+        _ => Err(AppError::InvalidInput("This question can't be answered by voice".to_string())),
+    }
+}
+
+/// Whether `phrase` appears in `text` as whole words
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    !phrase.is_empty() && format!(" {} ", text).contains(&format!(" {} ", phrase))
+}
+
+/// Lower-case a transcript, drop punctuation and fillers, and write spoken numbers as digits:
+/// "Um, twenty-one!" -> "21"
+pub fn normalize_spoken(text: &str) -> String {
+    let cleaned: String = text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '.' || c == '\'' { c } else { ' ' })
+        .collect();
+
+    let mut words = Vec::new();
+    let mut number: Option<u64> = None;
+    let mut current = 0u64;
+
+    for word in cleaned.split_whitespace().map(|word| word.trim_matches('.')) {
+        if FILLER_WORDS.contains(&word) || word.is_empty() {
+            continue;
+        }
+
+        match number_word_value(word) {
+            Some(NumberWord::Unit(value)) => {
+                current += value;
+                number.get_or_insert(0);
+            }
+            Some(NumberWord::Hundred) if number.is_some() || current > 0 => {
+                current = current.max(1) * 100;
+            }
+            Some(NumberWord::Thousand) if number.is_some() || current > 0 => {
+                number = Some(number.unwrap_or(0) + current.max(1) * 1000);
+                current = 0;
+            }
+            _ if word == "and" && number.is_some() => {}
+            _ => {
+                if let Some(total) = number.take() {
+                    words.push((total + current).to_string());
+                    current = 0;
+                }
+                words.push(word.to_string());
+            }
+        }
+    }
+    if let Some(total) = number {
+        words.push((total + current).to_string());
+    }
+
+    words.join(" ")
+}
+
+enum NumberWord {
+    Unit(u64),
+    Hundred,
+    Thousand,
+}
+
+fn number_word_value(word: &str) -> Option<NumberWord> {
+    const UNITS: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+    ];
+    const TENS: [&str; 8] = ["twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+
+    if let Some(value) = UNITS.iter().position(|unit| *unit == word) {
+        return Some(NumberWord::Unit(value as u64));
+    }
+    if let Some(index) = TENS.iter().position(|tens| *tens == word) {
+        return Some(NumberWord::Unit((index as u64 + 2) * 10));
+    }
+    match word {
+        "hundred" => Some(NumberWord::Hundred),
+        "thousand" => Some(NumberWord::Thousand),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use crate::models::{BlankConfig, KeyStage, QuestionContent};
+    use crate::services::SecurityService;
+    use tempfile::tempdir;
+
+    struct FixedProvider(Vec<Transcription>);
+
+    impl SpeechToTextProvider for FixedProvider {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn transcribe(&self, _audio: &SpokenAudio) -> AppResult<Vec<Transcription>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn create_test_voice_service() -> (VoiceInputService, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let content_manager = Arc::new(ContentManager::new(
+            db_service.manager(),
+            security_service,
+            temp_dir.path().join("content"),
+        ));
+
+        (VoiceInputService::new(content_manager), temp_dir)
+    }
+
+    fn add_question(service: &VoiceInputService, question_type: QuestionType, options: Option<Vec<&str>>, answer: &str) -> QuestionId {
+        let subject_id = service.content_manager.get_subjects().unwrap()
+            .into_iter().find(|subject| subject.name == "mathematics").unwrap().id.unwrap();
+        service.content_manager.add_question(Question::new(
+            subject_id,
+            KeyStage::KS1,
+            question_type,
+            QuestionContent {
+                text: "Say the answer".to_string(),
+                options: options.map(|options| options.into_iter().map(str::to_string).collect()),
+                story: None,
+                image_url: None,
+                hotspots: None,
+                blanks: Some(vec![BlankConfig {
+                    position: 0,
+                    expected_answer: answer.to_string(),
+                    case_sensitive: false,
+                    accept_alternatives: None,
+                    max_edit_distance: None,
+                }]),
+                additional_data: None,
+            },
+            Answer::Text(answer.to_string()),
+        )).unwrap()
+    }
+
+    fn heard(text: &str, confidence: f32) -> Transcription {
+        Transcription { text: text.to_string(), confidence }
+    }
+
+    #[test]
+    fn test_normalize_spoken() {
+        assert_eq!(normalize_spoken("Um, twenty-one!"), "21");
+        assert_eq!(normalize_spoken("one hundred and five"), "105");
+        assert_eq!(normalize_spoken("two thousand three hundred"), "2300");
+        assert_eq!(normalize_spoken("It's seven"), "it's 7");
+        assert_eq!(normalize_spoken("The Cat sat"), "the cat sat");
+    }
+
+    #[test]
+    fn test_interpret_applies_thresholds() {
+        let (service, _temp_dir) = create_test_voice_service();
+        let question_id = add_question(&service, QuestionType::FillBlank, None, "12");
+
+        let accepted = service.interpret(question_id, &[heard("twelve", 0.95)]).unwrap();
+        assert_eq!(accepted.decision, VoiceDecision::Accept);
+        assert!(matches!(accepted.answer_to_submit(false), Some(Answer::Text(answer)) if answer == "12"));
+
+        let unsure = service.interpret(question_id, &[heard("twelve", 0.6)]).unwrap();
+        assert_eq!(unsure.decision, VoiceDecision::Confirm);
+        assert!(unsure.answer_to_submit(false).is_none());
+        assert!(unsure.answer_to_submit(true).is_some());
+
+        let mumbled = service.interpret(question_id, &[heard("um", 0.9)]).unwrap();
+        assert_eq!(mumbled.decision, VoiceDecision::Retry);
+        assert!(mumbled.answer.is_none());
+
+        assert!(service.set_thresholds(VoiceThresholds { accept: 0.5, confirm: 0.8 }).is_err());
+    }
+
+    #[test]
+    fn test_multiple_choice_matches_spoken_option() {
+        let (service, _temp_dir) = create_test_voice_service();
+        let question_id = add_question(&service, QuestionType::MultipleChoice, Some(vec!["Paris", "Rome", "New York"]), "Paris");
+
+        let interpretation = service.interpret(question_id, &[heard("I think it's Paris", 0.9)]).unwrap();
+        assert!(matches!(interpretation.answer, Some(Answer::Text(ref answer)) if answer == "Paris"));
+
+        // The second candidate is used when the first matches no option
+        let interpretation = service.interpret(question_id, &[heard("new work", 0.9), heard("new york", 0.7)]).unwrap();
+        assert!(matches!(interpretation.answer, Some(Answer::Text(ref answer)) if answer == "New York"));
+        assert_eq!(interpretation.decision, VoiceDecision::Confirm);
+
+        let ambiguous = service.interpret(question_id, &[heard("paris or rome", 0.9)]).unwrap();
+        assert_eq!(ambiguous.decision, VoiceDecision::Retry);
+
+        assert!(service.transcribe_answer(question_id, &SpokenAudio { mime_type: "audio/wav".to_string(), data: Vec::new() }).is_err());
+        service.set_provider(Some(Arc::new(FixedProvider(vec![heard("rome", 0.99)])))).unwrap();
+        assert_eq!(service.provider_name().as_deref(), Some("fixed"));
+        let interpretation = service.transcribe_answer(question_id, &SpokenAudio { mime_type: "audio/wav".to_string(), data: Vec::new() }).unwrap();
+        assert_eq!(interpretation.decision, VoiceDecision::Accept);
+    }
+}
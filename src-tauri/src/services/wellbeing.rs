@@ -0,0 +1,315 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::ProfileId;
+use crate::database::DatabaseManager;
+use std::sync::Arc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+
+/// Minutes of continuous quizzing allowed before a break, unless a parent changes it
+const DEFAULT_MAX_CONTINUOUS_MINUTES: u32 = 40;
+
+/// Minutes a break lasts, unless a parent changes it
+const DEFAULT_BREAK_MINUTES: u32 = 10;
+
+/// Longest continuous quizzing a parent can allow
+const MAX_CONTINUOUS_MINUTES_LIMIT: u32 = 240;
+
+/// Longest break a parent can set
+const MAX_BREAK_MINUTES_LIMIT: u32 = 120;
+
+/// Per-profile break settings, set by a parent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BreakSettings {
+    pub profile_id: ProfileId,
+    pub enabled: bool,
+    /// Quizzing for this long without a break starts a break
+    pub max_continuous_minutes: u32,
+    /// How long the break lasts; time away from quizzes this long also counts as a break
+    pub break_minutes: u32,
+}
+
+impl BreakSettings {
+    fn defaults(profile_id: ProfileId) -> Self {
+        Self {
+            profile_id,
+            enabled: true,
+            max_continuous_minutes: DEFAULT_MAX_CONTINUOUS_MINUTES,
+            break_minutes: DEFAULT_BREAK_MINUTES,
+        }
+    }
+
+    fn is_default(&self) -> bool {
+        *self == Self::defaults(self.profile_id)
+    }
+}
+
+/// Where a profile is in its quizzing/break cycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakStatus {
+    pub profile_id: ProfileId,
+    /// True while a break is due; new quiz sessions can't start until it ends
+    pub on_break: bool,
+    /// Minutes of quizzing since the last break
+    pub continuous_minutes: u32,
+    pub break_ends_at: Option<DateTime<Utc>>,
+    pub seconds_remaining: u32,
+}
+
+/// Makes children take a break after a long stretch of quizzing
+pub struct WellbeingService {
+    db_manager: Arc<DatabaseManager>,
+}
+
+impl WellbeingService {
+    /// Create a new wellbeing service
+    pub fn new(db_manager: Arc<DatabaseManager>) -> Self {
+        Self { db_manager }
+    }
+
+    /// A profile's break settings, or the defaults if a parent hasn't changed them
+    pub fn get_settings(&self, profile_id: ProfileId) -> AppResult<BreakSettings> {
+        let stored = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT enabled, max_continuous_minutes, break_minutes
+                 FROM wellbeing_settings WHERE profile_id = ?1",
+                params![profile_id],
+                |row| Ok(BreakSettings {
+                    profile_id,
+                    enabled: row.get(0)?,
+                    max_continuous_minutes: row.get(1)?,
+                    break_minutes: row.get(2)?,
+                }),
+            ).optional()
+        })?;
+
+        Ok(stored.unwrap_or_else(|| BreakSettings::defaults(profile_id)))
+    }
+
+    /// Save a profile's break settings
+    pub fn set_settings(&self, settings: &BreakSettings) -> AppResult<()> {
+        if settings.max_continuous_minutes == 0 || settings.max_continuous_minutes > MAX_CONTINUOUS_MINUTES_LIMIT {
+            return Err(AppError::InvalidInput(format!(
+                "Quizzing time before a break must be between 1 and {} minutes", MAX_CONTINUOUS_MINUTES_LIMIT
+            )));
+        }
+        if settings.break_minutes == 0 || settings.break_minutes > MAX_BREAK_MINUTES_LIMIT {
+            return Err(AppError::InvalidInput(format!(
+                "Breaks must be between 1 and {} minutes", MAX_BREAK_MINUTES_LIMIT
+            )));
+        }
+
+        self.db_manager.execute(|conn| {
+            if settings.is_default() {
+                conn.execute(
+                    "DELETE FROM wellbeing_settings WHERE profile_id = ?1",
+                    params![settings.profile_id],
+                )?;
+                return Ok(());
+            }
+
+            conn.execute(
+                "INSERT OR REPLACE INTO wellbeing_settings
+                 (profile_id, enabled, max_continuous_minutes, break_minutes, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    settings.profile_id,
+                    settings.enabled,
+                    settings.max_continuous_minutes,
+                    settings.break_minutes,
+                    Utc::now().to_rfc3339()
+                ],
+            )?;
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Note quiz activity (starting a session, answering a question) and return the new status.
+    /// A gap of at least the break length since the last activity starts a fresh stretch.
+    pub fn record_activity(&self, profile_id: ProfileId, now: DateTime<Utc>) -> AppResult<BreakStatus> {
+        let settings = self.get_settings(profile_id)?;
+        let started_at = match self.get_stretch(profile_id)? {
+            Some((started_at, last_activity_at)) if now - last_activity_at < Duration::minutes(settings.break_minutes as i64) => started_at,
+            _ => now,
+        };
+
+        self.db_manager.execute(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO wellbeing_activity (profile_id, stretch_started_at, last_activity_at)
+                 VALUES (?1, ?2, ?3)",
+                params![profile_id, started_at.to_rfc3339(), now.to_rfc3339()],
+            )
+        })?;
+
+        Ok(Self::status(&settings, Some((started_at, now)), now))
+    }
+
+    /// Current status, without recording any activity
+    pub fn get_status(&self, profile_id: ProfileId, now: DateTime<Utc>) -> AppResult<BreakStatus> {
+        let settings = self.get_settings(profile_id)?;
+        Ok(Self::status(&settings, self.get_stretch(profile_id)?, now))
+    }
+
+    /// Refuse to start a quiz session while a break is due
+    pub fn check_can_start(&self, profile_id: ProfileId, now: DateTime<Utc>) -> AppResult<BreakStatus> {
+        let status = self.get_status(profile_id, now)?;
+        if status.on_break {
+            let minutes = (status.seconds_remaining + 59) / 60;
+            return Err(AppError::PermissionDenied(format!(
+                "Time for a break! Quizzes can start again in {} minute{}",
+                minutes,
+                if minutes == 1 { "" } else { "s" }
+            )));
+        }
+        Ok(status)
+    }
+
+    /// Let a parent end a break early; the next activity starts a fresh stretch
+    pub fn end_break(&self, profile_id: ProfileId) -> AppResult<()> {
+        self.db_manager.execute(|conn| {
+            conn.execute("DELETE FROM wellbeing_activity WHERE profile_id = ?1", params![profile_id])
+        })?;
+        Ok(())
+    }
+
+    fn get_stretch(&self, profile_id: ProfileId) -> AppResult<Option<(DateTime<Utc>, DateTime<Utc>)>> {
+        let stored: Option<(String, String)> = self.db_manager.execute(|conn| {
+            conn.query_row(
+                "SELECT stretch_started_at, last_activity_at FROM wellbeing_activity WHERE profile_id = ?1",
+                params![profile_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            ).optional()
+        })?;
+
+        Ok(stored.and_then(|(started_at, last_activity_at)| {
+            Some((parse_timestamp(&started_at)?, parse_timestamp(&last_activity_at)?))
+        }))
+    }
+
+    fn status(settings: &BreakSettings, stretch: Option<(DateTime<Utc>, DateTime<Utc>)>, now: DateTime<Utc>) -> BreakStatus {
+        let break_length = Duration::minutes(settings.break_minutes as i64);
+        let mut status = BreakStatus {
+            profile_id: settings.profile_id,
+            on_break: false,
+            continuous_minutes: 0,
+            break_ends_at: None,
+            seconds_remaining: 0,
+        };
+
+        let (started_at, last_activity_at) = match stretch {
+            // A long enough gap already was a break
+            Some(stretch) if now - stretch.1 < break_length => stretch,
+            _ => return status,
+        };
+
+        let continuous = last_activity_at - started_at;
+        status.continuous_minutes = continuous.num_minutes().max(0) as u32;
+
+        if settings.enabled && continuous >= Duration::minutes(settings.max_continuous_minutes as i64) {
+            let break_ends_at = last_activity_at + break_length;
+            status.on_break = true;
+            status.break_ends_at = Some(break_ends_at);
+            status.seconds_remaining = (break_ends_at - now).num_seconds().max(0) as u32;
+        }
+
+        status
+    }
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseService;
+    use tempfile::tempdir;
+
+    fn create_test_wellbeing_service() -> (WellbeingService, ProfileId, tempfile::TempDir) {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let db_service = DatabaseService::new(&db_path).unwrap();
+        db_service.initialize().unwrap();
+
+        let profile_id = db_service.manager().execute(|conn| {
+            conn.execute(
+                "INSERT INTO profiles (name, avatar, created_at) VALUES ('Sam', 'avatar1', ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+            Ok(ProfileId(conn.last_insert_rowid() as u32))
+        }).unwrap();
+
+        (WellbeingService::new(db_service.manager()), profile_id, temp_dir)
+    }
+
+    #[test]
+    fn test_settings_defaults_and_validation() {
+        let (service, profile_id, _temp_dir) = create_test_wellbeing_service();
+
+        let mut settings = service.get_settings(profile_id).unwrap();
+        assert_eq!(settings, BreakSettings::defaults(profile_id));
+
+        settings.max_continuous_minutes = 20;
+        settings.break_minutes = 5;
+        service.set_settings(&settings).unwrap();
+        assert_eq!(service.get_settings(profile_id).unwrap(), settings);
+
+        settings.break_minutes = 0;
+        assert!(service.set_settings(&settings).is_err());
+        settings.break_minutes = 5;
+        settings.max_continuous_minutes = 1000;
+        assert!(service.set_settings(&settings).is_err());
+    }
+
+    #[test]
+    fn test_long_stretch_requires_break() {
+        let (service, profile_id, _temp_dir) = create_test_wellbeing_service();
+        let start = Utc::now();
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+
+        // Answering every five minutes keeps the stretch going
+        for minute in (0..40).step_by(5) {
+            assert!(!service.record_activity(profile_id, at(minute)).unwrap().on_break);
+        }
+        let status = service.record_activity(profile_id, at(40)).unwrap();
+        assert!(status.on_break);
+        assert_eq!(status.continuous_minutes, 40);
+        assert_eq!(status.break_ends_at, Some(at(50)));
+
+        assert!(service.check_can_start(profile_id, at(45)).is_err());
+        assert!(service.check_can_start(profile_id, at(50)).is_ok());
+
+        // After the break the next activity starts a fresh stretch
+        assert_eq!(service.record_activity(profile_id, at(51)).unwrap().continuous_minutes, 0);
+    }
+
+    #[test]
+    fn test_disabled_or_ended_breaks_never_block() {
+        let (service, profile_id, _temp_dir) = create_test_wellbeing_service();
+        let start = Utc::now();
+
+        service.record_activity(profile_id, start).unwrap();
+        service.record_activity(profile_id, start + Duration::minutes(9)).unwrap();
+        service.record_activity(profile_id, start + Duration::minutes(18)).unwrap();
+        service.record_activity(profile_id, start + Duration::minutes(27)).unwrap();
+        service.record_activity(profile_id, start + Duration::minutes(36)).unwrap();
+        assert!(service.record_activity(profile_id, start + Duration::minutes(45)).unwrap().on_break);
+
+        service.end_break(profile_id).unwrap();
+        assert!(service.check_can_start(profile_id, start + Duration::minutes(46)).is_ok());
+
+        let mut settings = service.get_settings(profile_id).unwrap();
+        settings.enabled = false;
+        service.set_settings(&settings).unwrap();
+        for minute in (46..200).step_by(5) {
+            service.record_activity(profile_id, start + Duration::minutes(minute)).unwrap();
+        }
+        assert!(service.check_can_start(profile_id, start + Duration::minutes(200)).is_ok());
+    }
+}
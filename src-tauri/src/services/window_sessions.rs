@@ -0,0 +1,147 @@
+use crate::errors::{AppError, AppResult};
+use crate::services::security::{SecurityService, Permission};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+/// Label of the child-facing main window
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+/// Label of the parent dashboard window
+pub const PARENT_DASHBOARD_LABEL: &str = "parent-dashboard";
+
+/// Public view of a window-scoped session. The token itself never leaves the backend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowSession {
+    pub window_label: String,
+    pub permission: Permission,
+    pub opened_at: DateTime<Utc>,
+}
+
+struct BoundSession {
+    session_token: String,
+    permission: Permission,
+    opened_at: DateTime<Utc>,
+}
+
+/// Binds authenticated sessions to individual windows, so commands issued from the
+/// parent dashboard are authorized by the window they come from rather than a token
+/// the child-facing window could also send.
+pub struct WindowSessionRegistry {
+    security_service: Arc<SecurityService>,
+    sessions: Mutex<HashMap<String, BoundSession>>,
+}
+
+impl WindowSessionRegistry {
+    /// Create a new window session registry
+    pub fn new(security_service: Arc<SecurityService>) -> Self {
+        Self {
+            security_service,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Bind a parental (or admin) session token to a window
+    pub fn open(&self, window_label: &str, session_token: &str) -> AppResult<WindowSession> {
+        let permission = self.security_service.session_permission(session_token)?;
+        if permission < Permission::Parent {
+            return Err(AppError::PermissionDenied("Parental access required to open this window".to_string()));
+        }
+
+        let opened_at = Utc::now();
+        self.lock_sessions()?.insert(window_label.to_string(), BoundSession {
+            session_token: session_token.to_string(),
+            permission,
+            opened_at,
+        });
+
+        Ok(WindowSession {
+            window_label: window_label.to_string(),
+            permission,
+            opened_at,
+        })
+    }
+
+    /// Check that the window holds a session granting at least `required`.
+    /// Expired sessions are dropped so the window has to re-authenticate.
+    pub fn authorize(&self, window_label: &str, required: Permission) -> AppResult<()> {
+        if required == Permission::Child {
+            return Ok(());
+        }
+
+        let mut sessions = self.lock_sessions()?;
+        let session_token = match sessions.get(window_label) {
+            Some(session) => session.session_token.clone(),
+            None => return Err(AppError::PermissionDenied(format!(
+                "Window '{}' has no authenticated session", window_label
+            ))),
+        };
+
+        if self.security_service.session_permission(&session_token)? == Permission::Child {
+            sessions.remove(window_label);
+            return Err(AppError::PermissionDenied("Window session has expired".to_string()));
+        }
+
+        self.security_service.authorize(required, &session_token)
+    }
+
+    /// Get the session bound to a window, if any
+    pub fn get_session(&self, window_label: &str) -> AppResult<Option<WindowSession>> {
+        Ok(self.lock_sessions()?.get(window_label).map(|session| WindowSession {
+            window_label: window_label.to_string(),
+            permission: session.permission,
+            opened_at: session.opened_at,
+        }))
+    }
+
+    /// Drop the session bound to a window. Returns whether a session existed.
+    pub fn close(&self, window_label: &str) -> AppResult<bool> {
+        Ok(self.lock_sessions()?.remove(window_label).is_some())
+    }
+
+    fn lock_sessions(&self) -> AppResult<std::sync::MutexGuard<'_, HashMap<String, BoundSession>>> {
+        self.sessions.lock()
+            .map_err(|_| AppError::Internal("Window session lock poisoned".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_requires_parental_session() {
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let registry = WindowSessionRegistry::new(security_service.clone());
+
+        assert!(matches!(
+            registry.open(PARENT_DASHBOARD_LABEL, "not-a-token"),
+            Err(AppError::PermissionDenied(_))
+        ));
+        assert!(registry.authorize(PARENT_DASHBOARD_LABEL, Permission::Parent).is_err());
+        assert!(registry.authorize(MAIN_WINDOW_LABEL, Permission::Child).is_ok());
+
+        let token = security_service.generate_parental_session_token().unwrap();
+        let session = registry.open(PARENT_DASHBOARD_LABEL, &token).unwrap();
+        assert_eq!(session.permission, Permission::Parent);
+
+        assert!(registry.authorize(PARENT_DASHBOARD_LABEL, Permission::Parent).is_ok());
+        assert!(registry.authorize(PARENT_DASHBOARD_LABEL, Permission::Admin).is_err());
+        assert!(registry.authorize(MAIN_WINDOW_LABEL, Permission::Parent).is_err());
+    }
+
+    #[test]
+    fn test_closing_window_drops_session() {
+        let security_service = Arc::new(SecurityService::new().unwrap());
+        let registry = WindowSessionRegistry::new(security_service.clone());
+        let token = security_service.generate_parental_session_token().unwrap();
+
+        registry.open(PARENT_DASHBOARD_LABEL, &token).unwrap();
+        assert!(registry.get_session(PARENT_DASHBOARD_LABEL).unwrap().is_some());
+
+        assert!(registry.close(PARENT_DASHBOARD_LABEL).unwrap());
+        assert!(!registry.close(PARENT_DASHBOARD_LABEL).unwrap());
+        assert!(registry.authorize(PARENT_DASHBOARD_LABEL, Permission::Parent).is_err());
+    }
+}